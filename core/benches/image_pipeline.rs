@@ -0,0 +1,76 @@
+//! Timings of the image pipeline, each step alone and the thumbnail preset as a
+//! whole. Run with `cargo bench -p organizer-core`.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use image::metadata::Orientation;
+use image::{DynamicImage, Rgb, RgbImage};
+use organizer_core::services::image_processor::{
+    ColorTarget, ConvertColor, Frame, Orient, Pipeline, Resize, ResizeFilter, Step,
+};
+use std::hint::black_box;
+
+/// A photo-sized gradient, so the encoder has something to compress
+fn photo() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(3000, 2000, |x, y| {
+        Rgb([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+    }))
+}
+
+fn steps(c: &mut Criterion) {
+    let image = photo();
+    let mut group = c.benchmark_group("steps");
+    group.sample_size(10);
+
+    group.bench_function("orient", |b| {
+        b.iter(|| {
+            let frame = Frame {
+                orientation: Orientation::Rotate90,
+                ..Frame::borrowed(&image)
+            };
+            black_box(Orient.apply(frame).unwrap());
+        })
+    });
+
+    for filter in ResizeFilter::ALL {
+        let resize = Resize {
+            filter,
+            ..Resize::fit(500, 500)
+        };
+        group.bench_with_input(
+            BenchmarkId::new("resize", format!("{:?}", filter)),
+            &resize,
+            |b, resize| b.iter(|| black_box(resize.apply(Frame::borrowed(&image)).unwrap())),
+        );
+    }
+
+    let convert = ConvertColor {
+        target: ColorTarget::Rgba8,
+    };
+    group.bench_function("convert color", |b| {
+        b.iter(|| black_box(convert.apply(Frame::borrowed(&image)).unwrap()))
+    });
+
+    group.finish();
+}
+
+fn thumbnail(c: &mut Criterion) {
+    let image = photo();
+    let output = std::env::temp_dir().join("organizer-bench-thumbnail.png");
+    let mut group = c.benchmark_group("thumbnail");
+    group.sample_size(10);
+
+    for compression in [0u8, 5, 9] {
+        let pipeline = Pipeline::thumbnail(500, 500, compression);
+        group.bench_with_input(
+            BenchmarkId::new("compression", compression),
+            &pipeline,
+            |b, pipeline| b.iter(|| pipeline.run(Frame::borrowed(&image), &output).unwrap()),
+        );
+    }
+
+    group.finish();
+    let _ = std::fs::remove_file(output);
+}
+
+criterion_group!(benches, steps, thumbnail);
+criterion_main!(benches);
@@ -0,0 +1,523 @@
+use std::collections::HashSet;
+use crate::services::atomic_file;
+use crate::services::change_service::{self, DataChange};
+use crate::services::update_service::DEFAULT_UPDATE_URL;
+use crate::utils::get_assets_path;
+use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::Mutex;
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::delete_behavior::DeleteBehavior;
+use crate::models::export_preset::ExportPreset;
+use crate::models::filter::TagMatchMode;
+use crate::models::grid_layout::GridLayout;
+use crate::models::import_filter::ImportFilter;
+use crate::models::import_rule::ImportRule;
+use crate::models::maintenance::{MaintenanceTask, TaskSchedule};
+use crate::models::saved_search::SavedSearch;
+use crate::models::watermark::Watermark;
+use std::collections::BTreeMap;
+
+/// Version of the config layout this build writes. Files without one are
+/// version 0, written before the layout was versioned.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Queries kept in the search history
+pub const SEARCH_HISTORY_LEN: usize = 20;
+
+/// What reading a config file took besides deserializing it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Version the file was migrated from, when it was older than [`CONFIG_VERSION`]
+    pub migrated_from: Option<u32>,
+    /// Version of a file written by a newer build, read as far as this one understands it
+    pub newer: Option<u32>,
+    /// Settings whose value could not be read, they use their default
+    pub reset_fields: Vec<String>,
+    /// The file could not be read at all, every setting uses its default
+    pub reset_all: bool,
+    /// Copy of the file as it was before it was rewritten
+    pub backup: Option<PathBuf>,
+}
+
+impl LoadReport {
+    /// Whether some setting the user had lost its value
+    pub fn defaults_applied(&self) -> bool {
+        self.reset_all || !self.reset_fields.is_empty()
+    }
+
+    /// Whether the file has to be written again in the current layout
+    fn needs_rewrite(&self) -> bool {
+        self.newer.is_none() && (self.migrated_from.is_some() || self.defaults_applied())
+    }
+}
+
+/// Report of the config loaded at startup, kept until the app shows it
+static LOAD_REPORT: Mutex<Option<LoadReport>> = Mutex::new(None);
+
+/// Takes the report of the startup load when defaults had to be applied
+pub fn take_load_report() -> Option<LoadReport> {
+    LOAD_REPORT.lock().ok()?.take()
+}
+
+/// Main structure holding runtime settings
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub config: Config,
+}
+
+impl Settings {
+    /// Loads settings from disk or uses defaults if the file is missing or invalid
+    pub fn load() -> Self {
+        let config = Self::load_config();
+        Settings { config }
+    }
+
+    /// Reads config.json, migrating it to the current layout. The file is
+    /// backed up before it is rewritten.
+    fn load_config() -> Config {
+        let config_path = get_assets_path().join("config.json");
+
+        let content = match fs::read_to_string(&config_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                info!("No config.json yet, using the default config");
+                return Config::default();
+            }
+            Err(err) => {
+                error!("Failed to read config.json: {}. Using default config.", err);
+                return Config::default();
+            }
+        };
+
+        let (config, mut report) = parse_config(&content);
+        if report.needs_rewrite() || report.newer.is_some() {
+            match backup_config(&config_path) {
+                Ok(backup) => {
+                    info!("Previous config backed up to {}", backup.display());
+                    report.backup = Some(backup);
+                }
+                Err(err) => error!("Failed to back up config.json: {}", err),
+            }
+        }
+        // Without a backup the file is left as it is, the next save rewrites it
+        if report.needs_rewrite()
+            && report.backup.is_some()
+            && let Err(err) = write_config(&config_path, &config)
+        {
+            error!("Failed to rewrite config.json: {}", err);
+        }
+
+        if report.defaults_applied() {
+            *LOAD_REPORT.lock().unwrap() = Some(report);
+        }
+        config
+    }
+
+    /// Saves the current settings to config.json
+    pub fn save(&self) -> Result<(), Box<dyn error::Error>> {
+        let config_path = get_assets_path().join("config.json");
+        debug!("Saving config to {}", config_path.display());
+        write_config(&config_path, &self.config)?;
+        info!("Config saved");
+        change_service::notify(DataChange::Settings);
+        Ok(())
+    }
+
+    /// Restores the default config and saves it to config.json
+    pub fn reset(&mut self) -> Result<(), Box<dyn error::Error>> {
+        self.config = Config::default();
+        self.save()
+    }
+
+    /// Writes the current config to an arbitrary file (e.g. a backup chosen by the user)
+    pub fn export_to(&self, path: &Path) -> Result<(), Box<dyn error::Error>> {
+        let config_json = serde_json::to_string_pretty(&self.config)?;
+        atomic_file::write(path, config_json)?;
+        info!("Config exported to {}", path.display());
+        Ok(())
+    }
+
+    /// Replaces the current config with the one stored in `path` and saves it to config.json.
+    /// Files of older versions are migrated, settings that can't be read keep their default.
+    pub fn import_from(&mut self, path: &Path) -> Result<LoadReport, Box<dyn error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let (config, report) = parse_config(&content);
+        if report.reset_all {
+            return Err(format!("{} is not a config file", path.display()).into());
+        }
+        self.config = config;
+        info!("Config imported from {}", path.display());
+        self.save()?;
+        Ok(report)
+    }
+}
+
+/// Reads a config file of any version. Older layouts are migrated, and each setting
+/// that can't be read falls back to its default instead of the whole config.
+pub fn parse_config(content: &str) -> (Config, LoadReport) {
+    let mut report = LoadReport::default();
+    let mut file = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(serde_json::Value::Object(file)) => file,
+        Ok(_) | Err(_) => {
+            warn!("config.json can't be read, using the default config");
+            report.reset_all = true;
+            return (Config::default(), report);
+        }
+    };
+
+    let version = file
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0) as u32;
+    if version > CONFIG_VERSION {
+        warn!(
+            "config.json has version {}, this build reads up to {}",
+            version, CONFIG_VERSION
+        );
+        report.newer = Some(version);
+    } else if version < CONFIG_VERSION {
+        for from in version..CONFIG_VERSION {
+            migrate_config(from, &mut file);
+        }
+        info!("Migrated config.json from version {} to {}", version, CONFIG_VERSION);
+        report.migrated_from = Some(version);
+    }
+
+    // Start from the defaults and take each setting of the file that deserializes
+    let serde_json::Value::Object(mut merged) =
+        serde_json::to_value(Config::default()).expect("Config serializes to an object")
+    else {
+        unreachable!("Config serializes to an object");
+    };
+    for (key, value) in file {
+        if key == "version" || !merged.contains_key(&key) {
+            continue;
+        }
+        let previous = merged.insert(key.clone(), value);
+        if serde_json::from_value::<Config>(serde_json::Value::Object(merged.clone())).is_err() {
+            warn!("Setting {} in config.json can't be read, using its default", key);
+            if let Some(previous) = previous {
+                merged.insert(key.clone(), previous);
+            }
+            report.reset_fields.push(key);
+        }
+    }
+
+    let mut config: Config = serde_json::from_value(serde_json::Value::Object(merged))
+        .expect("Defaults with readable settings deserialize");
+    config.version = CONFIG_VERSION;
+    (config, report)
+}
+
+/// Moves a config of version `from` to the next version
+fn migrate_config(from: u32, _file: &mut serde_json::Map<String, serde_json::Value>) {
+    match from {
+        // Version 1 added the version itself, the settings are unchanged
+        0 => {}
+        _ => unreachable!("No migration from config version {}", from),
+    }
+}
+
+/// Copies the config file next to itself, under a name with the current time
+fn backup_config(config_path: &Path) -> std::io::Result<PathBuf> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let backup = config_path.with_file_name(format!("config.{}.json.bak", stamp));
+    fs::copy(config_path, &backup)?;
+    Ok(backup)
+}
+
+fn write_config(config_path: &Path, config: &Config) -> Result<(), Box<dyn error::Error>> {
+    let config_json = serde_json::to_string_pretty(config)?;
+    debug!("Config JSON: {}", config_json);
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_file::write(config_path, config_json)?;
+    Ok(())
+}
+
+/// Serializable structure for app config
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Config {
+    /// Layout of the file, see [`CONFIG_VERSION`]
+    #[serde(default)]
+    pub version: u32,
+    pub theme: String,
+    pub language: String,
+    pub items_per_page: u64,
+    pub thumb_compression: Option<u8>,
+    pub image_compression: Option<u8>,
+    /// Tag applied to images registered from a clipboard paste
+    pub clipboard_tag: Option<String>,
+    /// Description template for clipboard pastes, `{date}` and `{time}` are replaced
+    pub clipboard_description: Option<String>,
+    /// Pre-fill the description from the file or folder name on import
+    pub description_from_filename: Option<bool>,
+    /// Pattern for stored file names, supports `{id}`, `{date}` and `{description_slug}`
+    pub file_name_pattern: Option<String>,
+    /// Command used to edit images, `{path}` is replaced by the file (appended when missing)
+    pub external_editor: Option<String>,
+    /// Tag the wallpaper rotation picks images from
+    pub wallpaper_tag: Option<String>,
+    /// Minutes between wallpaper changes, rotation is off when 0
+    pub wallpaper_interval: Option<u64>,
+    /// Tries of a database operation while the database is busy, 1 disables retrying
+    pub db_retry_attempts: Option<u32>,
+    /// Wait before the first retry in milliseconds, doubled on each following one
+    pub db_retry_backoff_ms: Option<u64>,
+    /// Write-ahead logging, lets searches read while an import writes. Applied on restart
+    pub db_wal: Option<bool>,
+    /// How long a connection waits for another writer before the database counts as busy
+    pub db_busy_timeout_ms: Option<u64>,
+    /// Navbar shrunk to icons, toggled from the navbar itself
+    pub navbar_collapsed: Option<bool>,
+    /// Navbar width set by dragging its edge
+    pub navbar_width: Option<f32>,
+    /// Pure black or white background with brighter accents and outlines
+    pub high_contrast: Option<bool>,
+    /// Cards and previews change at once instead of animating
+    pub reduce_motion: Option<bool>,
+    /// Cards per row of the search grid, scaled to fit. 0 wraps them at their own size
+    pub grid_columns: Option<u32>,
+    /// Fixed size cards or a masonry of cards keeping the image's aspect ratio
+    pub grid_layout: Option<GridLayout>,
+    /// Memory in MB for decoded thumbnails and previews, least recently used ones go first
+    pub image_cache_mb: Option<u64>,
+    /// Files left out of folder imports, unless overridden for one import
+    pub import_filter: Option<ImportFilter>,
+    /// Tags and settings given to imported images matching their conditions
+    pub import_rules: Option<Vec<ImportRule>>,
+    /// Whether and how often each maintenance task runs, tasks missing here use their default
+    pub maintenance: Option<BTreeMap<MaintenanceTask, TaskSchedule>>,
+    /// Overlay exported copies can get, see `export_service::export_copies`
+    pub watermark: Option<Watermark>,
+    /// Sizes and formats offered when exporting copies
+    pub export_presets: Option<Vec<ExportPreset>>,
+    /// Milliseconds a card is hovered before its quick look opens, 0 leaves it to
+    /// the space bar
+    pub quick_look_delay_ms: Option<u64>,
+    /// Searches pinned below the navbar buttons, in the order they are shown
+    pub pinned_searches: Option<Vec<SavedSearch>>,
+    /// Descriptions only need to come close to the search query, tolerating typos
+    pub fuzzy_search: Option<bool>,
+    /// Whether files deleted for good are removed or go to the recycle bin
+    pub delete_behavior: Option<DeleteBehavior>,
+    /// Queries searched for lately, latest first
+    pub search_history: Option<Vec<String>>,
+    /// Look for a newer release at startup
+    pub update_check: Option<bool>,
+    /// Where releases are looked up, a GitHub "latest release" endpoint or one
+    /// answering in the same shape
+    pub update_url: Option<String>,
+    /// Version of the app that last ran, tells an upgrade apart from a normal start
+    pub last_version: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            theme: "Dark".to_string(),
+            language: "en".to_string(),
+            items_per_page: 35,
+            thumb_compression: Some(9),
+            image_compression: Some(5),
+            clipboard_tag: None,
+            clipboard_description: None,
+            description_from_filename: Some(false),
+            file_name_pattern: None,
+            external_editor: None,
+            wallpaper_tag: None,
+            wallpaper_interval: Some(0),
+            db_retry_attempts: Some(3),
+            db_retry_backoff_ms: Some(100),
+            db_wal: Some(true),
+            db_busy_timeout_ms: Some(5000),
+            navbar_collapsed: Some(false),
+            navbar_width: Some(280.0),
+            high_contrast: Some(false),
+            reduce_motion: Some(false),
+            grid_columns: Some(0),
+            grid_layout: Some(GridLayout::Grid),
+            image_cache_mb: Some(256),
+            import_filter: Some(ImportFilter::default()),
+            import_rules: Some(Vec::new()),
+            maintenance: Some(BTreeMap::new()),
+            watermark: Some(Watermark::default()),
+            export_presets: Some(ExportPreset::defaults()),
+            quick_look_delay_ms: Some(0),
+            pinned_searches: Some(Vec::new()),
+            fuzzy_search: Some(false),
+            delete_behavior: Some(DeleteBehavior::Permanent),
+            search_history: Some(Vec::new()),
+            update_check: Some(false),
+            update_url: Some(DEFAULT_UPDATE_URL.to_string()),
+            last_version: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn maintenance_schedule(&self, task: MaintenanceTask) -> TaskSchedule {
+        self.maintenance
+            .as_ref()
+            .and_then(|schedules| schedules.get(&task).copied())
+            .unwrap_or_else(|| task.default_schedule())
+    }
+
+    /// Puts the query at the top of the search history. An earlier copy differing
+    /// only in case goes, as do the queries beyond [`SEARCH_HISTORY_LEN`]
+    pub fn remember_search(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+        let history = self.search_history.get_or_insert_with(Vec::new);
+        let lowercase = query.to_lowercase();
+        history.retain(|earlier| earlier.to_lowercase() != lowercase);
+        history.insert(0, query.to_string());
+        history.truncate(SEARCH_HISTORY_LEN);
+    }
+}
+
+/// In-memory UI state (search filters, pagination, scroll, etc.)
+/// This is NOT persisted to disk - it's session-only
+#[derive(Debug, Clone, Default)]
+pub struct UIState {
+    pub search_query: String,
+    pub refinements: Vec<String>,
+    pub selected_tags: HashSet<TagDTO>,
+    pub excluded_tags: HashSet<TagDTO>,
+    pub tag_match: TagMatchMode,
+    pub current_page: u64,
+    pub scroll_offset: f32,
+}
+
+// ===================================
+//         GLOBAL SINGLETONS
+// ===================================
+
+static SETTINGS: Lazy<RwLock<Settings>> = Lazy::new(|| {
+    let settings = Settings::load();
+    RwLock::new(settings)
+});
+
+
+static UI_STATE: Lazy<Mutex<UIState>> = Lazy::new(|| {
+    Mutex::new(UIState::default())
+});
+
+/// Gets a read-only lock on the global Settings
+pub fn get_settings() -> RwLockReadGuard<'static, Settings> {
+    SETTINGS
+        .read()
+        .expect("Failed to acquire read lock on SETTINGS")
+}
+
+/// Changes a setting outside of Preferences and saves it right away
+pub fn update_config(change: impl FnOnce(&mut Config)) {
+    let mut settings = get_settings_mut();
+    change(&mut settings.config);
+    if let Err(err) = settings.save() {
+        error!("Failed to save settings: {}", err);
+    }
+}
+
+/// Gets a writable lock on the global Settings
+pub fn get_settings_mut() -> RwLockWriteGuard<'static, Settings> {
+    SETTINGS
+        .write()
+        .expect("Failed to acquire write lock on SETTINGS")
+}
+
+// ===================================
+//  UI STATE FUNCTIONS (IN-MEMORY ONLY)
+// ===================================
+
+/// Updates the search query
+pub fn set_search_query(query: String) {
+    UI_STATE.lock().unwrap().search_query = query;
+}
+
+/// Gets the current search query
+pub fn get_search_query() -> String {
+    UI_STATE.lock().unwrap().search_query.clone()
+}
+
+/// Updates the earlier queries the search is refined by
+pub fn set_refinements(steps: Vec<String>) {
+    UI_STATE.lock().unwrap().refinements = steps;
+}
+
+/// Gets the earlier queries the search is refined by
+pub fn get_refinements() -> Vec<String> {
+    UI_STATE.lock().unwrap().refinements.clone()
+}
+
+/// Updates the selected tags
+pub fn set_selected_tags(tags: HashSet<TagDTO>) {
+    UI_STATE.lock().unwrap().selected_tags = tags;
+}
+
+/// Gets the current selected tags
+pub fn get_selected_tags() -> HashSet<TagDTO> {
+    UI_STATE.lock().unwrap().selected_tags.clone()
+}
+
+/// Updates the tags whose images are left out
+pub fn set_excluded_tags(tags: HashSet<TagDTO>) {
+    UI_STATE.lock().unwrap().excluded_tags = tags;
+}
+
+/// Gets the tags whose images are left out
+pub fn get_excluded_tags() -> HashSet<TagDTO> {
+    UI_STATE.lock().unwrap().excluded_tags.clone()
+}
+
+/// Updates whether the images need every selected tag or any of them
+pub fn set_tag_match(mode: TagMatchMode) {
+    UI_STATE.lock().unwrap().tag_match = mode;
+}
+
+/// Gets whether the images need every selected tag or any of them
+pub fn get_tag_match() -> TagMatchMode {
+    UI_STATE.lock().unwrap().tag_match
+}
+
+/// Updates the current page
+pub fn set_current_page(page: u64) {
+    UI_STATE.lock().unwrap().current_page = page;
+}
+
+/// Gets the current page
+pub fn get_current_page() -> u64 {
+    UI_STATE.lock().unwrap().current_page
+}
+
+/// Updates the scroll offset
+pub fn set_scroll_offset(offset: f32) {
+    UI_STATE.lock().unwrap().scroll_offset = offset;
+}
+
+/// Gets the current scroll offset
+pub fn get_scroll_offset() -> f32 {
+    UI_STATE.lock().unwrap().scroll_offset
+}
+
+/// Resets the UI state to default (useful for "clear filters" functionality)
+#[allow(dead_code)]
+pub fn reset_ui_state() {
+    *UI_STATE.lock().unwrap() = UIState::default();
+}
+
+/// Gets a complete clone of the UI state (useful for debugging)
+#[allow(dead_code)]
+pub fn get_ui_state() -> UIState {
+    UI_STATE.lock().unwrap().clone()
+}
\ No newline at end of file
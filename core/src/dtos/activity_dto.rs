@@ -0,0 +1,24 @@
+use crate::models::activity_action::ActivityAction;
+
+#[derive(Debug, Clone)]
+pub struct ActivityDTO {
+    pub id: i64,
+    pub action: ActivityAction,
+    pub image_id: Option<i64>,
+    pub details: String,
+    pub created_at: String,
+}
+
+/// Usage figures of the activity screen, worked out on this machine from the
+/// activity history and never sent anywhere
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageInsightsDTO {
+    /// Tag names with how many times they were added to an image, most added first
+    pub top_tags: Vec<(String, u64)>,
+    /// Days (`YYYY-MM-DD`) with how many entries were created on them, busiest first
+    pub busiest_days: Vec<(String, u64)>,
+    /// Tags per image in the library, trash left out
+    pub average_tags: f64,
+    /// Date of the oldest activity entry, where the figures start
+    pub since: Option<String>,
+}
@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use crate::dtos::tag_dto::TagDTO;
+
+#[derive(Debug, Clone)]
+pub struct ImageDTO {
+    pub id: i64,
+    pub path: String,
+    pub thumbnail_path: String,
+    pub description: String,
+    pub tags: HashSet<TagDTO>,
+    pub created_at: String,
+    pub is_folder: bool,
+    pub is_prepared: bool,
+    pub source_url: Option<String>,
+    pub is_pinned: bool,
+    /// Stack the image belongs to
+    pub stack_id: Option<i64>,
+    /// Number of images in the stack, filled in for search results
+    pub stack_size: usize,
+    pub language: Option<String>,
+    /// Byte ranges of the description the search query matched, filled in for
+    /// search results
+    pub matches: Vec<Range<usize>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImageUpdateDTO {
+    pub path: Option<String>,
+    pub thumbnail_path: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<HashSet<TagDTO>>,
+    pub is_folder: bool,
+    pub is_prepared: bool,
+    pub content_hash: Option<String>,
+    pub source_url: Option<String>,
+    /// New language, an empty string clears it
+    pub language: Option<String>,
+}
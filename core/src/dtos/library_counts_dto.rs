@@ -0,0 +1,14 @@
+/// Totals shown next to the navbar entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LibraryCountsDTO {
+    pub images: u64,
+    /// Registered images without any tag, still waiting to be sorted
+    pub untagged: u64,
+    /// Imported images not triaged in the inbox yet
+    pub unreviewed: u64,
+    pub tags: u64,
+    /// Files that failed to import, waiting to be retried or discarded
+    pub quarantined: u64,
+    /// Entries in the trash, waiting to be restored or purged
+    pub trashed: u64,
+}
@@ -0,0 +1,12 @@
+pub mod activity_dto;
+pub mod image_dto;
+pub mod library_counts_dto;
+pub mod quarantine_dto;
+pub mod release_dto;
+pub mod stack_dto;
+pub mod statistics_dto;
+pub mod tag_dto;
+pub mod tag_settings_dto;
+pub mod timeline_dto;
+pub mod trashed_image_dto;
+pub mod version_dto;
\ No newline at end of file
@@ -0,0 +1,10 @@
+/// A file left out of an import because it could not be decoded
+#[derive(Debug, Clone)]
+pub struct QuarantinedFileDTO {
+    pub id: i64,
+    pub path: String,
+    /// Folder entry the file belongs to
+    pub image_id: Option<i64>,
+    pub reason: String,
+    pub created_at: String,
+}
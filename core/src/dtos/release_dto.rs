@@ -0,0 +1,17 @@
+/// A published release of Organizer, as the update endpoint describes it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseDTO {
+    /// Version without the leading `v` of the tag, e.g. `1.4.0`
+    pub version: String,
+    /// Release notes as written, usually markdown
+    pub notes: String,
+    /// Page of the release, shown when there is nothing to download for this platform
+    pub page_url: Option<String>,
+    /// Installer or archive for this platform
+    pub download_url: Option<String>,
+    pub file_name: Option<String>,
+    /// SHA-256 of the download in hex, when the endpoint states it
+    pub sha256: Option<String>,
+    /// `.sha256` file published next to the download, read when `sha256` is unknown
+    pub checksum_url: Option<String>,
+}
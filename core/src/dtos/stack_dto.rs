@@ -0,0 +1,9 @@
+use crate::dtos::image_dto::ImageDTO;
+
+#[derive(Debug, Clone)]
+pub struct StackDTO {
+    pub id: i64,
+    pub cover_image_id: i64,
+    /// Images of the stack in their stacking order
+    pub images: Vec<ImageDTO>,
+}
@@ -0,0 +1,25 @@
+use crate::dtos::library_counts_dto::LibraryCountsDTO;
+use crate::dtos::timeline_dto::MonthGroupDTO;
+
+/// Figures of the library report
+#[derive(Debug, Clone, Default)]
+pub struct LibraryStatsDTO {
+    pub counts: LibraryCountsDTO,
+    pub folders: u64,
+    /// Images registered per month, oldest first
+    pub growth: Vec<MonthGroupDTO>,
+    /// Tag names with their image count, most used first
+    pub top_tags: Vec<(String, u64)>,
+    pub storage: StorageDTO,
+}
+
+/// Bytes used on disk
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StorageDTO {
+    /// Stored images with their thumbnails and previews
+    pub images: u64,
+    /// Earlier versions kept by edits
+    pub versions: u64,
+    /// The database and its backups
+    pub database: u64,
+}
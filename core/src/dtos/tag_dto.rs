@@ -0,0 +1,22 @@
+use crate::dtos::tag_settings_dto::TagSettingsDTO;
+use crate::models::tag_color::TagColor;
+
+#[derive(Debug, Clone)]
+#[derive(Eq, Hash, PartialEq)]
+pub struct TagDTO {
+    pub id: i64,
+    pub name: String,
+    pub color: TagColor,
+    /// Tag one level up, see [`crate::services::tag_service::parent_name`]
+    pub parent_id: Option<i64>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TagUpdateDTO {
+    pub name: String,
+    pub color: TagColor,
+    /// Empty leaves the tag uncategorized
+    pub category: String,
+    pub view: TagSettingsDTO,
+}
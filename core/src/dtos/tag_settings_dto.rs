@@ -0,0 +1,15 @@
+use crate::models::filter::SortOrder;
+use crate::models::view_density::ViewDensity;
+
+/// Default view of a tag, `None` keeps the regular search setting
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TagSettingsDTO {
+    pub sort_order: Option<SortOrder>,
+    pub view_density: Option<ViewDensity>,
+}
+
+impl TagSettingsDTO {
+    pub fn is_empty(&self) -> bool {
+        self.sort_order.is_none() && self.view_density.is_none()
+    }
+}
@@ -0,0 +1,14 @@
+/// Number of images registered in a given month
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthGroupDTO {
+    pub year: i32,
+    pub month: u32,
+    pub count: u64,
+}
+
+impl MonthGroupDTO {
+    /// `YYYY-MM`, as used to query the month
+    pub fn key(&self) -> String {
+        format!("{:04}-{:02}", self.year, self.month)
+    }
+}
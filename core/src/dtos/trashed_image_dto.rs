@@ -0,0 +1,11 @@
+use crate::dtos::image_dto::ImageDTO;
+
+/// An entry in the trash, waiting to be restored or purged
+#[derive(Debug, Clone)]
+pub struct TrashedImageDTO {
+    /// The entry as it was, its paths are where the files go back on restore
+    pub image: ImageDTO,
+    /// Thumbnail as it is while the entry is in the trash
+    pub thumbnail_path: String,
+    pub deleted_at: String,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Clone)]
+pub struct VersionDTO {
+    pub id: i64,
+    pub image_id: i64,
+    pub original_path: String,
+    pub path: String,
+    pub created_at: String,
+}
@@ -0,0 +1,18 @@
+//! Library logic of Organizer without the desktop front end: the database,
+//! stored files, tags, imports and the settings they read.
+//!
+//! The modules are the public API. `services` holds the operations, taking and
+//! returning the types of `dtos`; `models` are the database entities and the
+//! value types the services share. The database has to be prepared with
+//! `services::database_service::prepare_database` before any other service runs.
+
+#[macro_use]
+extern crate rust_i18n;
+
+pub mod config;
+pub mod dtos;
+pub mod models;
+pub mod services;
+pub mod utils;
+
+i18n!("../locales", fallback = "en");
@@ -0,0 +1,51 @@
+use sea_orm::Iterable;
+use sea_orm::entity::prelude::*;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, DeriveActiveEnum, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+pub enum ActivityAction {
+    #[sea_orm(string_value = "create")]
+    Create,
+    #[sea_orm(string_value = "update")]
+    Update,
+    #[sea_orm(string_value = "delete")]
+    Delete,
+    #[sea_orm(string_value = "tags")]
+    TagChange,
+    #[sea_orm(string_value = "trash")]
+    Trash,
+    #[sea_orm(string_value = "restore")]
+    Restore,
+}
+
+impl ActivityAction {
+    pub fn all() -> Vec<ActivityAction> {
+        Self::iter().collect()
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ActivityAction::Create => "plus",
+            ActivityAction::Update => "pen",
+            ActivityAction::Delete => "trash",
+            ActivityAction::TagChange => "tags",
+            ActivityAction::Trash => "trash-can",
+            ActivityAction::Restore => "trash-arrow-up",
+        }
+    }
+}
+
+impl fmt::Display for ActivityAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ActivityAction::Create => t!("activity.action.create"),
+            ActivityAction::Update => t!("activity.action.update"),
+            ActivityAction::Delete => t!("activity.action.delete"),
+            ActivityAction::TagChange => t!("activity.action.tags"),
+            ActivityAction::Trash => t!("activity.action.trash"),
+            ActivityAction::Restore => t!("activity.action.restore"),
+        };
+        write!(f, "{label}")
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What happens to the files of an entry when it is deleted for good
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteBehavior {
+    /// Files are removed from the disk
+    #[default]
+    Permanent,
+    /// Originals go to the recycle bin of the system, thumbnails and previews are
+    /// removed
+    RecycleBin,
+}
+
+impl DeleteBehavior {
+    pub const ALL: [DeleteBehavior; 2] = [DeleteBehavior::Permanent, DeleteBehavior::RecycleBin];
+}
+
+impl fmt::Display for DeleteBehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            DeleteBehavior::Permanent => t!("delete_behavior.permanent"),
+            DeleteBehavior::RecycleBin => t!("delete_behavior.recycle_bin"),
+        };
+        write!(f, "{}", s)
+    }
+}
@@ -9,7 +9,7 @@ pub enum ImageType {
 }
 
 impl ImageType {
-    pub fn from_str(s: &str) -> ImageType {
+    pub fn parse(s: &str) -> ImageType {
         match s {
             "folder" => ImageType::Folder,
             "image" => ImageType::Image,
@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// File format exported copies are written in
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Same format as the stored original
+    #[default]
+    Original,
+    Jpeg,
+    Png,
+    /// Lossless, the quality is not used
+    Webp,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Original,
+        ExportFormat::Jpeg,
+        ExportFormat::Png,
+        ExportFormat::Webp,
+    ];
+
+    /// Extension of the written files, `None` keeps the original's
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            ExportFormat::Original => None,
+            ExportFormat::Jpeg => Some("jpg"),
+            ExportFormat::Png => Some("png"),
+            ExportFormat::Webp => Some("webp"),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Original => write!(f, "{}", t!("export_preset.format.original")),
+            ExportFormat::Jpeg => write!(f, "JPEG"),
+            ExportFormat::Png => write!(f, "PNG"),
+            ExportFormat::Webp => write!(f, "WebP"),
+        }
+    }
+}
+
+/// Named size and format exported copies can be written with
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    /// Longest side in pixels, 0 keeps the size. Smaller images are never enlarged.
+    #[serde(default)]
+    pub max_side: u32,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// JPEG quality, 1 to 100
+    #[serde(default = "default_quality")]
+    pub quality: u8,
+}
+
+fn default_quality() -> u8 {
+    85
+}
+
+impl Default for ExportPreset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            max_side: 0,
+            format: ExportFormat::default(),
+            quality: default_quality(),
+        }
+    }
+}
+
+impl ExportPreset {
+    /// Presets a new config starts with
+    pub fn defaults() -> Vec<ExportPreset> {
+        vec![
+            ExportPreset {
+                name: "Web 1920px JPEG 85".to_string(),
+                max_side: 1920,
+                format: ExportFormat::Jpeg,
+                quality: 85,
+            },
+            ExportPreset {
+                name: "Thumbnail 512 WebP".to_string(),
+                max_side: 512,
+                format: ExportFormat::Webp,
+                quality: 85,
+            },
+        ]
+    }
+}
+
+impl fmt::Display for ExportPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, DeriveActiveEnum, EnumIter)] // <-- adicione Copy e Eq
+#[sea_orm(rs_type = "String", db_type = "Text")]
+pub enum SortOrder {
+    #[sea_orm(string_value = "created_asc")]
+    CreatedAsc,
+    #[sea_orm(string_value = "created_desc")]
+    CreatedDesc,
+    #[sea_orm(string_value = "description_asc")]
+    DescriptionAsc,
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::CreatedAsc => write!(f, "{}", t!("search.order.oldest")),
+            SortOrder::CreatedDesc => write!(f, "{}", t!("search.order.newest")),
+            SortOrder::DescriptionAsc => write!(f, "{}", t!("search.order.description")),
+        }
+    }
+}
+
+/// How the selected tags of a search combine
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatchMode {
+    /// Images carrying every selected tag
+    #[default]
+    All,
+    /// Images carrying at least one of them
+    Any,
+}
+
+impl TagMatchMode {
+    pub const ALL: [TagMatchMode; 2] = [TagMatchMode::All, TagMatchMode::Any];
+}
+
+impl fmt::Display for TagMatchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TagMatchMode::All => write!(f, "{}", t!("search.tag_match.all")),
+            TagMatchMode::Any => write!(f, "{}", t!("search.tag_match.any")),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Filter {
+    pub query: String,
+    /// Earlier queries the images must match as well, each narrowing the one before
+    pub refinements: Vec<String>,
+    pub tags: HashSet<String>,
+    /// Images must carry each of these, whatever `tag_match` says. The `tag:`
+    /// operators of the query end up here.
+    pub required_tags: HashSet<String>,
+    /// Images carrying any of these tags are left out
+    pub excluded_tags: HashSet<String>,
+    pub tag_match: TagMatchMode,
+    pub sort_order: SortOrder,
+    /// Only images in this language
+    pub language: Option<String>,
+    /// Descriptions only need to come close to the query, closest first
+    pub fuzzy: bool,
+    /// Only images added before this day
+    pub before: Option<NaiveDate>,
+    /// Only images added on this day or later
+    pub after: Option<NaiveDate>,
+    /// Only folder entries, or only single images
+    pub is_folder: Option<bool>,
+    /// Only images carrying a tag of this category
+    pub category: Option<String>,
+}
+
+/// Language choice of the search filter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanguageFilter {
+    Any,
+    Only(String),
+}
+
+impl fmt::Display for LanguageFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LanguageFilter::Any => write!(f, "{}", t!("search.language.any")),
+            LanguageFilter::Only(language) => write!(f, "{}", language),
+        }
+    }
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            refinements: Vec::new(),
+            tags: HashSet::new(),
+            required_tags: HashSet::new(),
+            excluded_tags: HashSet::new(),
+            tag_match: TagMatchMode::All,
+            sort_order: SortOrder::CreatedDesc,
+            language: None,
+            fuzzy: false,
+            before: None,
+            after: None,
+            is_folder: None,
+            category: None,
+        }
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
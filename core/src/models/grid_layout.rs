@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How the search grid places its cards
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridLayout {
+    /// Rows of cards of the same size, images fitted into a fixed box
+    #[default]
+    Grid,
+    /// Columns of cards as tall as their image needs to keep its aspect ratio
+    Masonry,
+}
+
+impl GridLayout {
+    pub const ALL: [GridLayout; 2] = [GridLayout::Grid, GridLayout::Masonry];
+}
+
+impl fmt::Display for GridLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            GridLayout::Grid => t!("grid_layout.grid"),
+            GridLayout::Masonry => t!("grid_layout.masonry"),
+        };
+        write!(f, "{}", s)
+    }
+}
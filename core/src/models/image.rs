@@ -1,3 +1,4 @@
+use crate::models::review_status::ReviewStatus;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -10,7 +11,18 @@ pub struct Model {
     pub description: String,
     pub created_at: DateTime,
     pub is_folder: bool,
-    pub is_prepared: bool
+    pub is_prepared: bool,
+    pub content_hash: Option<String>,
+    pub source_url: Option<String>,
+    pub is_pinned: bool,
+    /// Difference hash of the thumbnail, the 64 bits stored as a signed integer
+    pub perceptual_hash: Option<i64>,
+    /// Language or script of the text in the image, as typed by the user
+    pub language: Option<String>,
+    /// Set to unreviewed on import, until the image is triaged in the inbox
+    pub review_status: ReviewStatus,
+    /// When the entry was moved to the trash, its files are in `trash/<id>` meanwhile
+    pub deleted_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
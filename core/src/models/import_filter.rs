@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Formats a folder import can hold, by file extension
+pub const IMPORT_FORMATS: [&str; 6] = ["png", "jpg", "gif", "bmp", "tiff", "webp"];
+
+/// Rules deciding which files of a folder get imported
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportFilter {
+    /// Shortest side in pixels an image needs, 0 accepts any size
+    #[serde(default)]
+    pub min_side: u32,
+    /// Largest file size in megabytes, 0 accepts any size
+    #[serde(default)]
+    pub max_size_mb: u64,
+    /// Formats left out of imports, see `IMPORT_FORMATS`
+    #[serde(default)]
+    pub excluded_formats: Vec<String>,
+}
+
+/// Why a file was left out of an import
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    TooSmall,
+    TooLarge,
+    ExcludedFormat,
+}
+
+impl SkipReason {
+    pub const ALL: [SkipReason; 3] = [
+        SkipReason::TooSmall,
+        SkipReason::TooLarge,
+        SkipReason::ExcludedFormat,
+    ];
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SkipReason::TooSmall => t!("import_filter.reason.too_small"),
+            SkipReason::TooLarge => t!("import_filter.reason.too_large"),
+            SkipReason::ExcludedFormat => t!("import_filter.reason.excluded_format"),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl ImportFilter {
+    /// Normalized format of a file extension, `jpeg` counts as `jpg` and `tif` as `tiff`
+    pub fn format_of(extension: &str) -> String {
+        match extension.to_lowercase().as_str() {
+            "jpeg" => "jpg".to_string(),
+            "tif" => "tiff".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn allows_format(&self, format: &str) -> bool {
+        !self
+            .excluded_formats
+            .iter()
+            .any(|excluded| excluded == format)
+    }
+
+    /// Checks the file size and extension, before the file is read
+    pub fn check_file(&self, extension: &str, size: u64) -> Result<(), SkipReason> {
+        if !self.allows_format(&Self::format_of(extension)) {
+            return Err(SkipReason::ExcludedFormat);
+        }
+        if self.max_size_mb > 0 && size > self.max_size_mb * 1024 * 1024 {
+            return Err(SkipReason::TooLarge);
+        }
+        Ok(())
+    }
+
+    /// Checks the decoded dimensions
+    pub fn check_dimensions(&self, width: u32, height: u32) -> Result<(), SkipReason> {
+        if width.min(height) < self.min_side {
+            return Err(SkipReason::TooSmall);
+        }
+        Ok(())
+    }
+
+    /// Same filter with the format turned on or off
+    pub fn with_format(mut self, format: &str, allowed: bool) -> Self {
+        self.excluded_formats.retain(|excluded| excluded != format);
+        if !allowed {
+            self.excluded_formats.push(format.to_string());
+        }
+        self
+    }
+}
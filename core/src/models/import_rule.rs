@@ -0,0 +1,133 @@
+use crate::models::import_filter::ImportFilter;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// What a rule condition looks at
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionKind {
+    /// File name contains the text, ignoring case
+    #[default]
+    FileName,
+    /// Path of the folder the file came from contains the text, ignoring case
+    Folder,
+    /// Width in pixels is at least the value
+    MinWidth,
+    /// Height in pixels is at least the value
+    MinHeight,
+    /// File format, by extension
+    Format,
+}
+
+impl ConditionKind {
+    pub const ALL: [ConditionKind; 5] = [
+        ConditionKind::FileName,
+        ConditionKind::Folder,
+        ConditionKind::MinWidth,
+        ConditionKind::MinHeight,
+        ConditionKind::Format,
+    ];
+}
+
+impl fmt::Display for ConditionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ConditionKind::FileName => t!("rules.condition.file_name"),
+            ConditionKind::Folder => t!("rules.condition.folder"),
+            ConditionKind::MinWidth => t!("rules.condition.min_width"),
+            ConditionKind::MinHeight => t!("rules.condition.min_height"),
+            ConditionKind::Format => t!("rules.condition.format"),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleCondition {
+    pub kind: ConditionKind,
+    /// Text or number compared against the file, as typed
+    pub value: String,
+}
+
+impl RuleCondition {
+    fn matches(&self, file: &ImportedFile) -> bool {
+        let value = self.value.trim();
+        let contains = |text: &str| text.to_lowercase().contains(&value.to_lowercase());
+        let at_least = |size: u32| value.parse::<u32>().is_ok_and(|min| size >= min);
+
+        match self.kind {
+            ConditionKind::FileName => contains(&file.file_name),
+            ConditionKind::Folder => file.folder.as_deref().is_some_and(contains),
+            ConditionKind::MinWidth => at_least(file.width),
+            ConditionKind::MinHeight => at_least(file.height),
+            ConditionKind::Format => file.format == ImportFilter::format_of(value),
+        }
+    }
+}
+
+/// Tags and settings given to every imported image matching all the conditions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportRule {
+    pub name: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub conditions: Vec<RuleCondition>,
+    /// Tags added to the image, by id
+    #[serde(default)]
+    pub tag_ids: Vec<i64>,
+    /// Language set on the image
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Pins the image
+    #[serde(default)]
+    pub pin: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl ImportRule {
+    /// A rule without conditions matches nothing, so a new rule does no harm
+    pub fn matches(&self, file: &ImportedFile) -> bool {
+        self.enabled
+            && !self.conditions.is_empty()
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(file))
+    }
+}
+
+/// What the rules know about an imported file
+#[derive(Clone, Debug)]
+pub struct ImportedFile {
+    pub file_name: String,
+    pub folder: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    /// Normalized extension, see `ImportFilter::format_of`
+    pub format: String,
+}
+
+impl ImportedFile {
+    /// Facts of a file at `origin`, a path or address, with its decoded size
+    pub fn new(origin: Option<&str>, format: &str, width: u32, height: u32) -> Self {
+        let path = origin.map(Path::new);
+        Self {
+            file_name: path
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            folder: path
+                .and_then(Path::parent)
+                .map(|folder| folder.to_string_lossy().to_string())
+                .filter(|folder| !folder.is_empty()),
+            width,
+            height,
+            format: ImportFilter::format_of(format),
+        }
+    }
+}
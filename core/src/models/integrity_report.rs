@@ -0,0 +1,25 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityProblem {
+    /// Content no longer matches the stored hash
+    Modified,
+    /// File or folder is gone from disk
+    Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct IntegrityIssue {
+    pub id: i64,
+    pub path: String,
+    pub is_folder: bool,
+    pub content_hash: Option<String>,
+    pub problem: IntegrityProblem,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    /// Entries compared against their stored hash
+    pub checked: usize,
+    /// Entries without a hash yet, hashed during this verification
+    pub baselined: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
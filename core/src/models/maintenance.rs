@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Housekeeping the app runs on its own at the configured interval
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceTask {
+    /// Removes thumbnails and previews whose image is gone, and outdated previews
+    PruneThumbnails,
+    /// Copies the database next to the working directory
+    Backup,
+    /// Compares the stored entries with their content hashes
+    VerifyIntegrity,
+    /// Removes stored folders no entry points to anymore
+    CleanOrphans,
+    /// Renumbers folder entries to follow their source folder and restores missing thumbnails
+    RepairFolders,
+}
+
+impl MaintenanceTask {
+    pub const ALL: [MaintenanceTask; 5] = [
+        MaintenanceTask::PruneThumbnails,
+        MaintenanceTask::Backup,
+        MaintenanceTask::VerifyIntegrity,
+        MaintenanceTask::CleanOrphans,
+        MaintenanceTask::RepairFolders,
+    ];
+
+    /// Schedule used until the task is configured
+    pub fn default_schedule(self) -> TaskSchedule {
+        match self {
+            MaintenanceTask::PruneThumbnails => TaskSchedule::new(true, 24 * 7),
+            MaintenanceTask::Backup => TaskSchedule::new(true, 24),
+            MaintenanceTask::VerifyIntegrity => TaskSchedule::new(false, 24 * 30),
+            MaintenanceTask::CleanOrphans => TaskSchedule::new(false, 24 * 30),
+            MaintenanceTask::RepairFolders => TaskSchedule::new(false, 24 * 30),
+        }
+    }
+}
+
+impl fmt::Display for MaintenanceTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MaintenanceTask::PruneThumbnails => t!("maintenance.task.prune_thumbnails"),
+            MaintenanceTask::Backup => t!("maintenance.task.backup"),
+            MaintenanceTask::VerifyIntegrity => t!("maintenance.task.verify_integrity"),
+            MaintenanceTask::CleanOrphans => t!("maintenance.task.clean_orphans"),
+            MaintenanceTask::RepairFolders => t!("maintenance.task.repair_folders"),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskSchedule {
+    pub enabled: bool,
+    /// Hours between two runs
+    pub interval_hours: u32,
+}
+
+impl TaskSchedule {
+    pub fn new(enabled: bool, interval_hours: u32) -> Self {
+        Self {
+            enabled,
+            interval_hours,
+        }
+    }
+}
+
+/// Outcome of the last run of a task
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TaskRun {
+    /// Unix time the run finished at
+    pub finished_at: i64,
+    /// Files removed or problems found, depending on the task
+    #[serde(default)]
+    pub count: usize,
+    #[serde(default)]
+    pub error: Option<String>,
+}
@@ -0,0 +1,27 @@
+pub mod activity;
+pub mod activity_action;
+pub mod delete_behavior;
+pub mod export_preset;
+pub mod filter;
+pub mod grid_layout;
+pub mod image;
+pub mod image_tag;
+pub mod image_version;
+pub mod import_filter;
+pub mod import_rule;
+pub mod integrity_report;
+pub mod maintenance;
+pub mod page;
+pub mod quarantined_file;
+pub mod review_status;
+pub mod saved_search;
+pub mod stack;
+pub mod stack_image;
+pub mod tag;
+pub mod tag_alias;
+pub mod tag_settings;
+pub mod toast;
+pub mod tag_color;
+pub mod view_density;
+pub mod watermark;
+pub mod enums;
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+use std::fmt;
+
+/// Where an image stands in the inbox triage
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, DeriveActiveEnum, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+pub enum ReviewStatus {
+    /// Imported and not looked at yet, listed in the inbox
+    #[sea_orm(string_value = "unreviewed")]
+    Unreviewed,
+    #[default]
+    #[sea_orm(string_value = "kept")]
+    Kept,
+    /// Kept, but its description or tags still need work
+    #[sea_orm(string_value = "needs_work")]
+    NeedsWork,
+}
+
+impl fmt::Display for ReviewStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ReviewStatus::Unreviewed => t!("review_status.unreviewed"),
+            ReviewStatus::Kept => t!("review_status.kept"),
+            ReviewStatus::NeedsWork => t!("review_status.needs_work"),
+        };
+        write!(f, "{}", label)
+    }
+}
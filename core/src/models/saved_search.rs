@@ -0,0 +1,34 @@
+use crate::models::filter::TagMatchMode;
+use serde::{Deserialize, Serialize};
+
+/// Query and tags of a search, pinned to the navbar to run again in one click
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    /// Shown in the navbar
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    /// Earlier queries the search was refined by
+    #[serde(default)]
+    pub refinements: Vec<String>,
+    /// Tag names in lowercase, tags deleted since are skipped when it runs
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tag names in lowercase whose images are left out
+    #[serde(default)]
+    pub excluded_tags: Vec<String>,
+    /// Whether the images need every tag or any of them
+    #[serde(default)]
+    pub tag_match: TagMatchMode,
+}
+
+impl SavedSearch {
+    /// Whether both run the same search, whatever their names
+    pub fn same_filters(&self, other: &SavedSearch) -> bool {
+        self.query == other.query
+            && self.refinements == other.refinements
+            && self.tags == other.tags
+            && self.excluded_tags == other.excluded_tags
+            && self.tag_match == other.tag_match
+    }
+}
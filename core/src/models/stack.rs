@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+
+/// Variants of one artwork shown as a single card
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "stacks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub cover_image_id: i64,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::stack_image::Entity")]
+    StackImage,
+}
+
+impl Related<super::stack_image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StackImage.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
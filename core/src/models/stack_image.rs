@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "stack_images")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub image_id: i64,
+    pub stack_id: i64,
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::stack::Entity",
+        from = "Column::StackId",
+        to = "super::stack::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Stack,
+    #[sea_orm(
+        belongs_to = "super::image::Entity",
+        from = "Column::ImageId",
+        to = "super::image::Column::Id",
+        on_update = "Cascade",
+        on_delete = "Cascade"
+    )]
+    Image,
+}
+
+impl Related<super::stack::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Stack.def()
+    }
+}
+
+impl Related<super::image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Image.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -9,6 +9,10 @@ pub struct Model {
     #[sea_orm(unique)]
     pub name: String,
     pub color: TagColor,
+    /// Tag one level up the name's `/` path, `animals` for `animals/cats`
+    pub parent_id: Option<i64>,
+    /// Kind of tag, like `character` or `source`, lowercase. None for uncategorized.
+    pub category: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -3,7 +3,7 @@ use sea_orm::EnumIter;
 use sea_orm::Iterable;
 use std::fmt;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, DeriveActiveEnum, EnumIter)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, DeriveActiveEnum, EnumIter)]
 #[sea_orm(rs_type = "String", db_type = "Text")]
 pub enum TagColor {
     #[sea_orm(string_value = "red")]
@@ -11,6 +11,7 @@ pub enum TagColor {
     #[sea_orm(string_value = "green")]
     Green,
     #[sea_orm(string_value = "blue")]
+    #[default]
     Blue,
     #[sea_orm(string_value = "orange")]
     Orange,
@@ -26,12 +27,6 @@ pub enum TagColor {
     Gray,
 }
 
-impl Default for TagColor {
-    fn default() -> Self {
-        TagColor::Blue
-    }
-}
-
 impl TagColor {
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -47,7 +42,7 @@ impl TagColor {
         }
     }
 
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "red" => Some(TagColor::Red),
             "green" => Some(TagColor::Green),
@@ -0,0 +1,93 @@
+use sea_orm::entity::prelude::*;
+use std::fmt;
+
+/// Size of the cards in the search grid
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, DeriveActiveEnum, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+pub enum ViewDensity {
+    #[sea_orm(string_value = "compact")]
+    Compact,
+    #[sea_orm(string_value = "comfortable")]
+    #[default]
+    Comfortable,
+    #[sea_orm(string_value = "large")]
+    Large,
+}
+
+impl ViewDensity {
+    pub fn card_width(&self) -> f32 {
+        match self {
+            ViewDensity::Compact => 190.0,
+            ViewDensity::Comfortable => 220.0,
+            ViewDensity::Large => 320.0,
+        }
+    }
+
+    pub fn image_height(&self) -> f32 {
+        match self {
+            ViewDensity::Compact => 130.0,
+            ViewDensity::Comfortable => 180.0,
+            ViewDensity::Large => 280.0,
+        }
+    }
+}
+
+/// Width and image height of the cards in the grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CardSize {
+    pub width: f32,
+    pub image_height: f32,
+}
+
+impl CardSize {
+    /// Narrowest a card gets when fitted to many columns
+    const MIN_WIDTH: f32 = 120.0;
+
+    /// Cards of the density scaled so `columns` of them, `spacing` apart, fill `width`
+    pub fn fitted(density: ViewDensity, columns: u32, width: f32, spacing: f32) -> Self {
+        let columns = columns.max(1) as f32;
+        let card_width = ((width - spacing * (columns - 1.0)) / columns).max(Self::MIN_WIDTH);
+        CardSize {
+            width: card_width,
+            image_height: density.image_height() * card_width / density.card_width(),
+        }
+    }
+
+    /// Same width, with the image box as tall as a picture of `aspect_ratio` (width over
+    /// height) needs to fill it. Very tall pictures stop at three times the width.
+    pub fn with_aspect_ratio(self, aspect_ratio: f32) -> Self {
+        // Padding of the card and of the box around the picture
+        const INSET: f32 = 2.0 * 5.0 + 2.0 * 8.0;
+        let picture_width = (self.width - INSET).max(1.0);
+        let picture_height = (picture_width / aspect_ratio.max(0.01)).min(3.0 * self.width);
+        CardSize {
+            width: self.width,
+            image_height: picture_height + 2.0 * 8.0,
+        }
+    }
+
+    /// Image plus description, tags, date and buttons
+    pub fn height(&self) -> f32 {
+        self.image_height + 208.0
+    }
+}
+
+impl From<ViewDensity> for CardSize {
+    fn from(density: ViewDensity) -> Self {
+        CardSize {
+            width: density.card_width(),
+            image_height: density.image_height(),
+        }
+    }
+}
+
+impl fmt::Display for ViewDensity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ViewDensity::Compact => t!("view_density.compact"),
+            ViewDensity::Comfortable => t!("view_density.comfortable"),
+            ViewDensity::Large => t!("view_density.large"),
+        };
+        write!(f, "{}", s)
+    }
+}
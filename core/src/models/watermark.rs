@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What the watermark shows
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkKind {
+    #[default]
+    Text,
+    /// A PNG, usually a logo with a transparent background
+    Image,
+}
+
+impl WatermarkKind {
+    pub const ALL: [WatermarkKind; 2] = [WatermarkKind::Text, WatermarkKind::Image];
+}
+
+impl fmt::Display for WatermarkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            WatermarkKind::Text => t!("watermark.kind.text"),
+            WatermarkKind::Image => t!("watermark.kind.image"),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Where the watermark sits on the picture
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkPosition {
+    TopLeft,
+    TopRight,
+    Center,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl WatermarkPosition {
+    pub const ALL: [WatermarkPosition; 5] = [
+        WatermarkPosition::TopLeft,
+        WatermarkPosition::TopRight,
+        WatermarkPosition::Center,
+        WatermarkPosition::BottomLeft,
+        WatermarkPosition::BottomRight,
+    ];
+
+    /// Top left corner of a `mark` sized box on a `canvas` sized picture, `margin`
+    /// pixels away from the edges it sits at
+    pub fn place(self, canvas: (u32, u32), mark: (u32, u32), margin: u32) -> (i64, i64) {
+        let (width, height) = (canvas.0 as i64, canvas.1 as i64);
+        let (mark_width, mark_height) = (mark.0 as i64, mark.1 as i64);
+        let margin = margin as i64;
+
+        let left = margin;
+        let right = width - mark_width - margin;
+        let top = margin;
+        let bottom = height - mark_height - margin;
+
+        match self {
+            WatermarkPosition::TopLeft => (left, top),
+            WatermarkPosition::TopRight => (right, top),
+            WatermarkPosition::Center => ((width - mark_width) / 2, (height - mark_height) / 2),
+            WatermarkPosition::BottomLeft => (left, bottom),
+            WatermarkPosition::BottomRight => (right, bottom),
+        }
+    }
+}
+
+impl fmt::Display for WatermarkPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            WatermarkPosition::TopLeft => t!("watermark.position.top_left"),
+            WatermarkPosition::TopRight => t!("watermark.position.top_right"),
+            WatermarkPosition::Center => t!("watermark.position.center"),
+            WatermarkPosition::BottomLeft => t!("watermark.position.bottom_left"),
+            WatermarkPosition::BottomRight => t!("watermark.position.bottom_right"),
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Overlay put on exported copies. Originals in the library are never changed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Watermark {
+    #[serde(default)]
+    pub kind: WatermarkKind,
+    /// Shown when the kind is text
+    #[serde(default)]
+    pub text: String,
+    /// PNG shown when the kind is image
+    #[serde(default)]
+    pub image_path: String,
+    #[serde(default)]
+    pub position: WatermarkPosition,
+    /// 0 (invisible) to 100 (opaque)
+    #[serde(default = "default_opacity")]
+    pub opacity: u8,
+}
+
+fn default_opacity() -> u8 {
+    50
+}
+
+impl Default for Watermark {
+    fn default() -> Self {
+        Self {
+            kind: WatermarkKind::default(),
+            text: String::new(),
+            image_path: String::new(),
+            position: WatermarkPosition::default(),
+            opacity: default_opacity(),
+        }
+    }
+}
+
+impl Watermark {
+    /// Whether there is something to draw
+    pub fn is_set(&self) -> bool {
+        match self.kind {
+            WatermarkKind::Text => !self.text.trim().is_empty(),
+            WatermarkKind::Image => !self.image_path.trim().is_empty(),
+        }
+    }
+}
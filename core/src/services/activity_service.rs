@@ -0,0 +1,141 @@
+use crate::dtos::activity_dto::{ActivityDTO, UsageInsightsDTO};
+use crate::models::activity::{ActiveModel, Column, Entity, Model};
+use crate::models::activity_action::ActivityAction;
+use crate::models::{image, image_tag};
+use crate::services::connection_db::db_ref;
+use crate::services::image_service;
+use log::error;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, Set,
+};
+use std::collections::HashMap;
+
+/// Most entries loaded at once on the activity screen
+const MAX_ENTRIES: u64 = 500;
+/// Tags listed in the usage insights
+const INSIGHT_TAGS: usize = 10;
+/// Days listed in the usage insights
+const INSIGHT_DAYS: u64 = 5;
+
+/// Records an event in the activity history. Failures are only logged so they
+/// never break the operation being recorded.
+pub async fn record(action: ActivityAction, image_id: Option<i64>, details: impl Into<String>) {
+    let entry = ActiveModel {
+        action: Set(action),
+        image_id: Set(image_id),
+        details: Set(details.into()),
+        ..Default::default()
+    };
+
+    if let Err(e) = entry.insert(db_ref()).await {
+        error!("Failed to record activity {:?}: {}", action, e);
+    }
+}
+
+/// Latest activity entries, optionally filtered by action and details text
+pub async fn find(action: Option<ActivityAction>, query: &str) -> Result<Vec<ActivityDTO>, DbErr> {
+    let mut select = Entity::find();
+
+    if let Some(action) = action {
+        select = select.filter(Column::Action.eq(action));
+    }
+
+    let query = query.trim();
+    if !query.is_empty() {
+        select = select.filter(Column::Details.contains(query));
+    }
+
+    let entries = select
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .limit(MAX_ENTRIES)
+        .all(db_ref())
+        .await?;
+
+    Ok(entries.iter().map(to_dto).collect())
+}
+
+/// Most added tags and busiest days read from the activity history, with the tags
+/// per image of the library. Worked out on demand, nothing is stored or sent.
+pub async fn insights() -> Result<UsageInsightsDTO, DbErr> {
+    let db = db_ref();
+
+    // Tag changes of an image are recorded as `<description>: +added, -removed`
+    let changes: Vec<String> = Entity::find()
+        .select_only()
+        .column(Column::Details)
+        .filter(Column::Action.eq(ActivityAction::TagChange))
+        .filter(Column::ImageId.is_not_null())
+        .into_tuple()
+        .all(db)
+        .await?;
+    let mut added: HashMap<&str, u64> = HashMap::new();
+    for name in changes
+        .iter()
+        .filter_map(|details| details.rsplit_once(": "))
+        .flat_map(|(_, changes)| changes.split(", "))
+        .filter_map(|change| change.strip_prefix('+'))
+    {
+        *added.entry(name).or_default() += 1;
+    }
+    let mut top_tags: Vec<(String, u64)> = added
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+    top_tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_tags.truncate(INSIGHT_TAGS);
+
+    let day = Expr::cust("date(activities.created_at)");
+    let busiest_days: Vec<(String, i64)> = Entity::find()
+        .select_only()
+        .column_as(day.clone(), "day")
+        .column_as(Expr::col(Column::Id).count(), "entries")
+        .filter(Column::Action.eq(ActivityAction::Create))
+        .group_by(day)
+        .order_by_desc(Expr::cust("entries"))
+        .order_by_desc(Expr::cust("day"))
+        .limit(INSIGHT_DAYS)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let images = image_service::count_library().await?.images;
+    let tags = image_tag::Entity::find()
+        .join(JoinType::InnerJoin, image_tag::Relation::Image.def())
+        .filter(image::Column::DeletedAt.is_null())
+        .filter(image::Column::IsPrepared.eq(true))
+        .count(db)
+        .await?;
+
+    let since = Entity::find()
+        .order_by_asc(Column::CreatedAt)
+        .one(db)
+        .await?
+        .map(|entry| entry.created_at.format("%Y-%m-%d").to_string());
+
+    Ok(UsageInsightsDTO {
+        top_tags,
+        busiest_days: busiest_days
+            .into_iter()
+            .map(|(day, count)| (day, count as u64))
+            .collect(),
+        average_tags: if images == 0 {
+            0.0
+        } else {
+            tags as f64 / images as f64
+        },
+        since,
+    })
+}
+
+fn to_dto(model: &Model) -> ActivityDTO {
+    ActivityDTO {
+        id: model.id,
+        action: model.action,
+        image_id: model.image_id,
+        details: model.details.clone(),
+        created_at: model.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
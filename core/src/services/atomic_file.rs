@@ -0,0 +1,55 @@
+//! Crash safe writes. The content goes to a temporary file next to the target,
+//! is synced to disk and then renamed over the target, so a crash in between
+//! leaves either the previous file or the new one, never a partial file.
+
+use image::{DynamicImage, ImageFormat, ImageResult};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Temporary file the content of `path` is written to before it takes its place.
+/// Its extension is not one of an image, so listings of stored images skip it.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Writes `contents` to `path` atomically
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_with(path, |temp| fs::write(temp, contents.as_ref()))
+}
+
+/// Saves `image` to `path` atomically, in the format its extension names
+pub fn save_image(image: &DynamicImage, path: &Path) -> ImageResult<()> {
+    let format = ImageFormat::from_path(path)?;
+    write_with(path, |temp| image.save_with_format(temp, format))
+}
+
+/// Replaces `path` atomically with the file `write` creates at the path it is given
+pub fn write_with<E: From<io::Error>>(
+    path: &Path,
+    write: impl FnOnce(&Path) -> Result<(), E>,
+) -> Result<(), E> {
+    let temp = temp_path_for(path);
+    let result = write(&temp).and_then(|()| commit(&temp, path).map_err(E::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn commit(temp: &Path, path: &Path) -> io::Result<()> {
+    OpenOptions::new().write(true).open(temp)?.sync_all()?;
+    fs::rename(temp, path)?;
+    // The rename itself is only durable once the folder is synced, which
+    // Windows does not allow and does not need
+    #[cfg(unix)]
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
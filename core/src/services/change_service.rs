@@ -0,0 +1,25 @@
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
+
+/// What kind of data a service changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataChange {
+    /// Images were added, edited, deleted, pinned or stacked
+    Images,
+    /// Tags were created, renamed or deleted
+    Tags,
+    /// The config was changed and saved, `get_settings` holds the new one
+    Settings,
+}
+
+static CHANGES: Lazy<broadcast::Sender<DataChange>> = Lazy::new(|| broadcast::channel(64).0);
+
+/// Tells the open screens that data changed, wherever the change came from
+pub fn notify(change: DataChange) {
+    // No receiver just means nothing is listening yet
+    let _ = CHANGES.send(change);
+}
+
+pub fn subscribe() -> broadcast::Receiver<DataChange> {
+    CHANGES.subscribe()
+}
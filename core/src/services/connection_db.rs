@@ -0,0 +1,128 @@
+use crate::config::get_settings;
+use crate::utils::{DATABASE_FILE, get_data_dir};
+use log::warn;
+use migration::Migrator;
+use once_cell::sync::OnceCell;
+use sea_orm::sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sea_orm::sqlx::ConnectOptions;
+use sea_orm::sqlx::SqlitePool;
+use sea_orm::{DatabaseConnection, DbErr, SqlxSqliteConnector};
+use sea_orm_migration::MigratorTrait;
+use std::str::FromStr;
+use std::future::Future;
+use std::{sync::Arc, time::Duration};
+
+static DB: OnceCell<Arc<DatabaseConnection>> = OnceCell::new();
+
+pub async fn init_db() -> Result<(), DbErr> {
+    let db_path = get_data_dir().join(DATABASE_FILE);
+    let db_url = format!("sqlite://{}?mode=rwc", db_path.to_string_lossy());
+
+    let (wal, busy_timeout) = {
+        let config = &get_settings().config;
+        (
+            config.db_wal.unwrap_or(true),
+            config.db_busy_timeout_ms.unwrap_or(5000),
+        )
+    };
+
+    // The journal mode is stored in the database file, so an existing database is
+    // converted to WAL (or back) the first time it is opened with the new setting.
+    // busy_timeout is per connection and makes SQLite wait for a writer before failing.
+    let options = SqliteConnectOptions::from_str(&db_url)
+        .map_err(|e| DbErr::Custom(e.to_string()))?
+        .journal_mode(if wal {
+            SqliteJournalMode::Wal
+        } else {
+            SqliteJournalMode::Delete
+        })
+        .busy_timeout(Duration::from_millis(busy_timeout))
+        .disable_statement_logging();
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(5)
+        .acquire_timeout(Duration::from_secs(3))
+        .connect_with(options)
+        .await
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    set_pool(pool)
+}
+
+/// Opens an empty database in memory with every migration applied, for tests and
+/// tools that must not touch organizer.db. It lasts as long as the process.
+pub async fn init_memory_db() -> Result<(), DbErr> {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")
+        .map_err(|e| DbErr::Custom(e.to_string()))?
+        .disable_statement_logging();
+
+    // The connections share one in-memory database, which SQLite drops when the
+    // last of them closes, so the pool keeps one open for good
+    let pool = SqlitePoolOptions::new()
+        .max_connections(5)
+        .min_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .acquire_timeout(Duration::from_secs(3))
+        .connect_with(options)
+        .await
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    set_pool(pool)?;
+    Migrator::up(db_ref(), None).await
+}
+
+fn set_pool(pool: SqlitePool) -> Result<(), DbErr> {
+    let db = SqlxSqliteConnector::from_sqlx_sqlite_pool(pool);
+
+    DB.set(Arc::new(db))
+        .map_err(|_| DbErr::Custom("DB already initialized".into()))
+}
+
+pub fn db_ref() -> &'static DatabaseConnection {
+    DB.get()
+        .expect("DB not initialized. Call init_db() first")
+        .as_ref()
+}
+/// Runs the operation again, doubling the wait each time, while SQLite reports the
+/// database as busy or locked (a backup or another tool writing to it).
+/// Attempts and the first wait come from the config.
+pub async fn with_retry<T, F, Fut>(mut operation: F) -> Result<T, DbErr>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, DbErr>>,
+{
+    let (attempts, backoff) = {
+        let config = &get_settings().config;
+        (
+            config.db_retry_attempts.unwrap_or(3).max(1),
+            config.db_retry_backoff_ms.unwrap_or(100),
+        )
+    };
+
+    let mut delay = Duration::from_millis(backoff);
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(err) if attempt < attempts && is_transient(&err) => {
+                warn!(
+                    "Database busy (attempt {}/{}), retrying in {:?}: {}",
+                    attempt, attempts, delay, err
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// SQLITE_BUSY (5) and SQLITE_LOCKED (6), which go away once the other writer is done
+fn is_transient(err: &DbErr) -> bool {
+    let message = err.to_string();
+    message.contains("database is locked")
+        || message.contains("database table is locked")
+        || message.contains("(code: 5)")
+        || message.contains("(code: 6)")
+}
@@ -1,10 +1,17 @@
 use log::{error, info};
 use migration::Migrator;
+use sea_orm::ConnectionTrait;
 use sea_orm_migration::MigratorTrait;
 use std::{error::Error, fs, path::Path, time::Instant};
 use std::path::PathBuf;
 use crate::services::connection_db::{db_ref, init_db};
-use crate::utils::get_exe_dir;
+use crate::services::job_service;
+use crate::utils::{DATABASE_FILE, get_data_dir};
+use chrono::{DateTime, Local, NaiveDateTime};
+
+/// Backups are written next to the working directory as `database_backup_<UTC time>.db`
+pub(crate) const BACKUP_PREFIX: &str = "database_backup_";
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d_%H%M%S";
 
 pub async fn run_migrations_safe(db: &sea_orm::DatabaseConnection) -> Result<(), Box<dyn Error>> {
     info!("Iniciando verificação de migrações...");
@@ -83,19 +90,64 @@ pub async fn prepare_database() -> Result<(), Box<dyn Error>> {
 }
 
 pub async fn backup_database() -> Result<(), Box<dyn Error>> {
-    let exe_dir = get_exe_dir();
-    let db_path: PathBuf = exe_dir.join("organizer.db");
+    let _job = job_service::start();
+    let data_dir = get_data_dir();
+    let db_path: PathBuf = data_dir.join(DATABASE_FILE);
 
     if db_path.exists() {
-        let backup_path = format!(
-            "database_backup_{}.db",
-            chrono::Utc::now().format("%Y%m%d_%H%M%S")
-        );
-        fs::copy(&db_path, &backup_path)?;
-        info!("Backup created: {}", backup_path);
+        // In WAL mode recent writes live in organizer.db-wal until checkpointed,
+        // move them into the main file so the copy is complete
+        if let Err(e) = db_ref()
+            .execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE)")
+            .await
+        {
+            error!("Failed to checkpoint the database before the backup: {}", e);
+        }
+
+        copy_database(&db_path)?;
     } else {
         info!("Database file not found at {:?}", db_path);
     }
 
     Ok(())
 }
+
+/// Backs up the database before it's opened, e.g. ahead of the migrations of a new
+/// version. Returns the copy, none when there is no database yet.
+pub fn backup_closed_database() -> std::io::Result<Option<PathBuf>> {
+    let db_path = get_data_dir().join(DATABASE_FILE);
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let backup_path = copy_database(&db_path)?;
+    // Writes of a run that didn't close cleanly are still in the log
+    let wal = db_path.with_extension("db-wal");
+    if wal.exists() {
+        fs::copy(&wal, backup_path.with_extension("db-wal"))?;
+    }
+    Ok(Some(backup_path))
+}
+
+fn copy_database(db_path: &Path) -> std::io::Result<PathBuf> {
+    let backup_path = db_path.with_file_name(format!(
+        "{}{}.db",
+        BACKUP_PREFIX,
+        chrono::Utc::now().format(BACKUP_TIME_FORMAT)
+    ));
+    fs::copy(db_path, &backup_path)?;
+    info!("Backup created: {}", backup_path.display());
+    Ok(backup_path)
+}
+
+/// Time of the newest database backup, read from the backup file names
+pub fn last_backup() -> Option<DateTime<Local>> {
+    fs::read_dir(get_data_dir())
+        .ok()?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let stamp = name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".db")?;
+            NaiveDateTime::parse_from_str(stamp, BACKUP_TIME_FORMAT).ok()
+        })
+        .max()
+        .map(|time| time.and_utc().with_timezone(&Local))
+}
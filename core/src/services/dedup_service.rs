@@ -0,0 +1,83 @@
+//! Warns about images registered twice. Every image gets the perceptual hash of its
+//! thumbnail when it is registered, and a new image is compared against the stored
+//! hashes before it is saved.
+
+use crate::dtos::image_dto::ImageDTO;
+use crate::models::image;
+use crate::services::connection_db::db_ref;
+use crate::services::image_service::{not_trashed, to_dto};
+use crate::services::similarity_service::{fill_missing_hashes, thumbnail_hash};
+use crate::services::tag_service::get_tags_for_images;
+use ::image::DynamicImage;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, Condition, DbErr, EntityTrait, QueryFilter};
+
+/// Hash bits a copy may differ by, enough for a re-encode or a resize but not for
+/// a different picture
+pub const DUPLICATE_DISTANCE: u32 = 4;
+
+/// Stores the hash of a registered image, so later checks don't have to compute it
+pub async fn store_hash(image_id: i64, hash: u64) -> Result<(), DbErr> {
+    image::Entity::update_many()
+        .col_expr(image::Column::PerceptualHash, (hash as i64).into())
+        .filter(image::Column::Id.eq(image_id))
+        .exec(db_ref())
+        .await?;
+    Ok(())
+}
+
+/// Images already stored that look the same as `image`, closest first
+pub async fn find_duplicates(image: DynamicImage) -> Result<Vec<ImageDTO>, DbErr> {
+    let hash = tokio::task::spawn_blocking(move || thumbnail_hash(&image))
+        .await
+        .map_err(|e| DbErr::Custom(e.to_string()))?;
+    find_by_hash(hash).await
+}
+
+/// Images whose hash is within [`DUPLICATE_DISTANCE`] of `hash`, closest first
+pub async fn find_by_hash(hash: u64) -> Result<Vec<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let candidates = || {
+        not_trashed()
+            .filter(image::Column::IsPrepared.eq(true))
+            .filter(image::Column::IsFolder.eq(false))
+    };
+    let mut unhashed = candidates()
+        .filter(image::Column::PerceptualHash.is_null())
+        .all(db)
+        .await?;
+    fill_missing_hashes(&mut unhashed).await?;
+
+    let models = candidates().filter(shares_a_band(hash)).all(db).await?;
+    let mut matches: Vec<(u32, image::Model)> = models
+        .into_iter()
+        .filter_map(|m| {
+            let distance = (m.perceptual_hash? as u64 ^ hash).count_ones();
+            (distance <= DUPLICATE_DISTANCE).then_some((distance, m))
+        })
+        .collect();
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.id.cmp(&a.1.id)));
+
+    let models: Vec<image::Model> = matches.into_iter().map(|(_, m)| m).collect();
+    let image_ids: Vec<i64> = models.iter().map(|m| m.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+    Ok(to_dto(models, tags_map))
+}
+
+/// Hashes equal to `hash` in at least one of [`DUPLICATE_DISTANCE`] + 1 bands of
+/// bits. A hash that differs in at most that many bits leaves one band untouched,
+/// so the filter keeps every duplicate and lets SQLite drop most of the rest.
+fn shares_a_band(hash: u64) -> Condition {
+    let bands = u64::from(DUPLICATE_DISTANCE) + 1;
+    let width = 64u64.div_ceil(bands);
+    (0..bands)
+        .map(|band| band * width)
+        .filter(|shift| *shift < 64)
+        .fold(Condition::any(), |condition, shift| {
+            let mask = (1u64 << width.min(64 - shift)) - 1;
+            condition.add(Expr::cust_with_values(
+                "((perceptual_hash >> ?) & ?) = ?",
+                [shift as i64, mask as i64, ((hash >> shift) & mask) as i64],
+            ))
+        })
+}
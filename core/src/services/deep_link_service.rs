@@ -0,0 +1,423 @@
+use crate::utils::{get_exe_dir, user_data_dir};
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use url::Url;
+
+pub const SCHEME: &str = "organizer";
+
+/// Locked by the running instance for as long as it runs. Holding it is what makes
+/// an instance the running one.
+const INSTANCE_LOCK: &str = "instance.lock";
+/// Port and token of the running instance, for later launches of the same user
+const INSTANCE_FILE: &str = "instance.json";
+/// How long a launch gets to send each line before its connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Listener claimed at startup with the token a launch has to send first, handed
+/// to the subscription once the window is up
+static INSTANCE_LISTENER: Lazy<Mutex<Option<(std::net::TcpListener, String)>>> =
+    Lazy::new(|| Mutex::new(None));
+/// Kept open so the lock lasts until the app exits
+static INSTANCE_LOCK_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InstanceInfo {
+    port: u16,
+    token: String,
+}
+
+/// What a launch argument or `organizer://` link asks the app to open
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    /// `organizer://search?q=text&tags=a,b`
+    Search { query: String, tags: Vec<String> },
+    /// `organizer://image/42`
+    Image(i64),
+    /// A file or folder passed on the command line, opened in Register
+    Import(String),
+    /// Brings the window to the front, sent by a launch without arguments
+    Focus,
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Option<Self> {
+        let url = Url::parse(link).ok()?;
+        if url.scheme() != SCHEME {
+            return None;
+        }
+
+        let mut segments = url.path().trim_matches('/').split('/');
+        match url.host_str().unwrap_or_default() {
+            "search" => {
+                let mut query = String::new();
+                let mut tags = Vec::new();
+                for (key, value) in url.query_pairs() {
+                    match key.as_ref() {
+                        "q" => query = value.into_owned(),
+                        "tags" => tags.extend(
+                            value
+                                .split(',')
+                                .map(|tag| tag.trim().to_lowercase())
+                                .filter(|tag| !tag.is_empty()),
+                        ),
+                        _ => {}
+                    }
+                }
+                Some(DeepLink::Search { query, tags })
+            }
+            "image" => segments
+                .next()
+                .and_then(|id| id.parse().ok())
+                .map(DeepLink::Image),
+            "" => Some(DeepLink::Focus),
+            _ => None,
+        }
+    }
+
+    /// Reads a launch argument: an `organizer://` link or the path of a file to import
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        if arg.starts_with(&format!("{}:", SCHEME)) {
+            return Self::parse(arg);
+        }
+        Path::new(arg)
+            .exists()
+            .then(|| DeepLink::Import(arg.to_string()))
+    }
+}
+
+/// Links and files passed on the command line, paths made absolute so another
+/// instance can open them from its own working directory
+pub fn launch_args() -> Vec<String> {
+    std::env::args()
+        .skip(1)
+        .map(|arg| {
+            if arg.starts_with(&format!("{}:", SCHEME)) {
+                return arg;
+            }
+            std::fs::canonicalize(&arg)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or(arg)
+        })
+        .collect()
+}
+
+/// Folder of the instance files. Per user, so each user of the machine runs their
+/// own instance and no one else can read the token.
+fn instance_dir() -> PathBuf {
+    user_data_dir().unwrap_or_else(get_exe_dir)
+}
+
+/// Claims the single-instance slot. Returns false when another instance holds it.
+pub fn claim_instance() -> bool {
+    let dir = instance_dir();
+    let lock = fs::create_dir_all(&dir).and_then(|()| {
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(dir.join(INSTANCE_LOCK))
+    });
+    let lock = match lock {
+        Ok(lock) => lock,
+        Err(e) => {
+            // Without the lock this instance still runs, just without forwarding
+            error!("Could not claim the instance lock: {}", e);
+            return true;
+        }
+    };
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(fs::TryLockError::WouldBlock) => return false,
+        Err(fs::TryLockError::Error(e)) => {
+            error!("Could not claim the instance lock: {}", e);
+            return true;
+        }
+    }
+    if let Ok(mut slot) = INSTANCE_LOCK_FILE.lock() {
+        *slot = Some(lock);
+    }
+
+    if let Err(e) = open_listener(&dir) {
+        error!("Could not listen for links from other launches: {}", e);
+    }
+    true
+}
+
+/// Listens on a free local port and leaves it with a new token for later launches
+fn open_listener(dir: &Path) -> io::Result<()> {
+    let listener = std::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))?;
+    let info = InstanceInfo {
+        port: listener.local_addr()?.port(),
+        token: new_token()?,
+    };
+
+    let mut options = OpenOptions::new();
+    options.create(true).truncate(true).write(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let content = serde_json::to_string(&info).map_err(io::Error::other)?;
+    options
+        .open(dir.join(INSTANCE_FILE))?
+        .write_all(content.as_bytes())?;
+
+    if let Ok(mut slot) = INSTANCE_LISTENER.lock() {
+        *slot = Some((listener, info.token));
+    }
+    Ok(())
+}
+
+/// 128 random bits from the OS, in hex
+fn new_token() -> io::Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Hands the launch arguments to the instance that is already running, which
+/// also brings its window to the front. Returns false when no instance answered.
+pub fn forward_to_running_instance(args: &[String]) -> bool {
+    let Some(info) = fs::read_to_string(instance_dir().join(INSTANCE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<InstanceInfo>(&content).ok())
+    else {
+        return false;
+    };
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, info.port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, Duration::from_millis(300)) else {
+        return false;
+    };
+
+    let focus = [format!("{}://", SCHEME)];
+    let lines = if args.is_empty() { &focus[..] } else { args };
+    match std::iter::once(&info.token)
+        .chain(lines)
+        .try_for_each(|line| writeln!(stream, "{}", line))
+    {
+        Ok(()) => {
+            info!("Launch forwarded to the running instance: {:?}", lines);
+            true
+        }
+        Err(e) => {
+            warn!("Could not forward launch to the running instance: {}", e);
+            false
+        }
+    }
+}
+
+/// Listens for links sent by later launches of the app. A connection that doesn't
+/// start with the token of this instance is dropped unread.
+pub fn listen() -> mpsc::UnboundedReceiver<DeepLink> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    let Some((listener, token)) = INSTANCE_LISTENER
+        .lock()
+        .ok()
+        .and_then(|mut slot| slot.take())
+    else {
+        return receiver;
+    };
+
+    tokio::spawn(async move {
+        let listener = match listener
+            .set_nonblocking(true)
+            .and_then(|_| TcpListener::from_std(listener))
+        {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Could not listen for links from other launches: {}", e);
+                return;
+            }
+        };
+
+        let token = Arc::new(token);
+        while let Ok((stream, _)) = listener.accept().await {
+            // Each launch gets its own task, one that never writes holds up no other
+            tokio::spawn(read_launch(stream, token.clone(), sender.clone()));
+        }
+    });
+
+    receiver
+}
+
+/// Reads the links of one launch, dropping the connection when it doesn't start
+/// with the token or goes quiet
+async fn read_launch(
+    stream: tokio::net::TcpStream,
+    token: Arc<String>,
+    sender: mpsc::UnboundedSender<DeepLink>,
+) {
+    let mut lines = BufReader::new(stream).lines();
+    match timeout(READ_TIMEOUT, lines.next_line()).await {
+        Ok(Ok(Some(line))) if line.trim() == token.as_str() => {}
+        _ => {
+            warn!("Ignoring a connection without the instance token");
+            return;
+        }
+    }
+    while let Ok(Ok(Some(line))) = timeout(READ_TIMEOUT, lines.next_line()).await {
+        match DeepLink::from_arg(line.trim()) {
+            Some(link) => {
+                if sender.send(link).is_err() {
+                    return;
+                }
+            }
+            None => warn!("Ignoring unknown launch argument: {}", line),
+        }
+    }
+}
+
+/// Registers the app as the handler of `organizer://` links for the current user
+pub fn register_protocol() -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let exe = exe.to_string_lossy();
+
+    if cfg!(target_os = "windows") {
+        let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+        let command = format!("\"{}\" \"%1\"", exe);
+        reg_add(&key, None, "URL:Organizer")?;
+        reg_add(&key, Some("URL Protocol"), "")?;
+        reg_add(&format!(r"{}\shell\open\command", key), None, &command)
+    } else if cfg!(target_os = "linux") {
+        register_linux_handler(&exe)
+    } else {
+        // macOS reads URL schemes from the Info.plist of the app bundle
+        Ok(())
+    }
+}
+
+fn reg_add(key: &str, value: Option<&str>, data: &str) -> io::Result<()> {
+    let mut command = Command::new("reg");
+    command.args(["add", key]);
+    match value {
+        Some(value) => command.args(["/v", value]),
+        None => command.arg("/ve"),
+    };
+    let status = command.args(["/d", data, "/f"]).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "reg add {} exited with {}",
+            key, status
+        )))
+    }
+}
+
+fn register_linux_handler(exe: &str) -> io::Result<()> {
+    let file_name = format!("{}-url-handler.desktop", SCHEME);
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName=Organizer\nExec=\"{}\" %u\nPath={}\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe,
+        get_exe_dir().display(),
+        SCHEME
+    );
+
+    if !write_desktop_entry(&file_name, &content)? {
+        return Ok(());
+    }
+
+    let status = Command::new("xdg-mime")
+        .args([
+            "default",
+            &file_name,
+            &format!("x-scheme-handler/{}", SCHEME),
+        ])
+        .status()?;
+    if !status.success() {
+        warn!("xdg-mime exited with {}", status);
+    }
+    Ok(())
+}
+
+/// Adds "Open with Organizer" for images and folders (and Send To on Windows)
+/// without taking over as the default app
+pub fn register_open_with() -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let exe = exe.to_string_lossy();
+    let label = t!("shell.open_with").to_string();
+
+    if cfg!(target_os = "windows") {
+        let command = format!("\"{}\" \"%1\"", exe);
+        for class in [r"SystemFileAssociations\image", "Directory"] {
+            let key = format!(r"HKCU\Software\Classes\{}\shell\{}", class, SCHEME);
+            reg_add(&key, None, &label)?;
+            reg_add(&key, Some("Icon"), &exe)?;
+            reg_add(&format!(r"{}\command", key), None, &command)?;
+        }
+        create_send_to_shortcut(&exe)
+    } else if cfg!(target_os = "linux") {
+        let content = format!(
+            "[Desktop Entry]\nType=Application\nName=Organizer\nGenericName={}\nExec=\"{}\" %f\nPath={}\nNoDisplay=true\nMimeType={};\n",
+            label,
+            exe,
+            get_exe_dir().display(),
+            OPEN_WITH_MIME_TYPES.join(";")
+        );
+        write_desktop_entry(&format!("{}-open-with.desktop", SCHEME), &content).map(|_| ())
+    } else {
+        // macOS takes document types from the Info.plist of the app bundle
+        Ok(())
+    }
+}
+
+/// Types the Linux "Open With" entry is offered for
+const OPEN_WITH_MIME_TYPES: [&str; 7] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/bmp",
+    "image/tiff",
+    "inode/directory",
+];
+
+fn create_send_to_shortcut(exe: &str) -> io::Result<()> {
+    let script = "$shortcut = (New-Object -ComObject WScript.Shell).CreateShortcut((Join-Path $env:APPDATA 'Microsoft\\Windows\\SendTo\\Organizer.lnk')); $shortcut.TargetPath = $env:ORGANIZER_EXE; $shortcut.Save()";
+    let status = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .env("ORGANIZER_EXE", exe)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "Send To shortcut creation exited with {}",
+            status
+        )))
+    }
+}
+
+/// Writes a desktop entry to the user's applications folder.
+/// Returns false when it was already there with the same content.
+fn write_desktop_entry(file_name: &str, content: &str) -> io::Result<bool> {
+    let Some(data_dir) = std::env::var_os("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".local/share"))
+        })
+    else {
+        return Ok(false);
+    };
+
+    let applications = data_dir.join("applications");
+    let desktop_file = applications.join(file_name);
+    if std::fs::read_to_string(&desktop_file).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+
+    std::fs::create_dir_all(&applications)?;
+    std::fs::write(&desktop_file, content)?;
+    Ok(true)
+}
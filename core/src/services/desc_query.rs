@@ -0,0 +1,156 @@
+//! Description search queries. `+` separates alternatives, within one every word
+//! has to start a word of the description, in any order, and quoted words have to
+//! follow each other as written. The query goes to the `images_fts` full-text
+//! index, which compares words without case and accents.
+
+use std::ops::Range;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Part of one alternative of the query
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    /// A word of the description starts with it
+    Prefix(String),
+    /// Words following each other
+    Phrase(Vec<String>),
+}
+
+/// Alternatives of the query, each with its terms. Alternatives without words are
+/// left out.
+fn alternatives(query: &str) -> Vec<Vec<Term>> {
+    query
+        .split('+')
+        .map(terms)
+        .filter(|terms| !terms.is_empty())
+        .collect()
+}
+
+fn terms(alternative: &str) -> Vec<Term> {
+    let mut terms = Vec::new();
+    // Odd parts are inside quotes, an unclosed quote runs to the end
+    for (index, part) in alternative.split('"').enumerate() {
+        let words: Vec<String> = word_spans(part)
+            .into_iter()
+            .map(|span| fold(&part[span]))
+            .collect();
+        if index % 2 == 0 {
+            terms.extend(words.into_iter().map(Term::Prefix));
+        } else if !words.is_empty() {
+            terms.push(Term::Phrase(words));
+        }
+    }
+    terms
+}
+
+/// FTS5 query matching the same descriptions as `query`, none when it has no words
+pub fn fts_expression(query: &str) -> Option<String> {
+    let alternatives = alternatives(query);
+    if alternatives.is_empty() {
+        return None;
+    }
+    // Words hold letters and digits only, quoting them is enough to escape them
+    let alternatives: Vec<String> = alternatives
+        .iter()
+        .map(|terms| {
+            let terms: Vec<String> = terms
+                .iter()
+                .map(|term| match term {
+                    Term::Prefix(word) => format!("\"{}\"*", word),
+                    Term::Phrase(words) => format!("\"{}\"", words.join(" ")),
+                })
+                .collect();
+            format!("({})", terms.join(" "))
+        })
+        .collect();
+    Some(alternatives.join(" OR "))
+}
+
+/// Byte ranges of the description matched by the alternatives of the query it
+/// satisfies: the start of each prefixed word and the words of each phrase
+pub fn match_ranges(query: &str, description: &str) -> Vec<Range<usize>> {
+    let spans = word_spans(description);
+    let words: Vec<String> = spans
+        .iter()
+        .map(|span| fold(&description[span.clone()]))
+        .collect();
+
+    let mut ranges = Vec::new();
+    for terms in alternatives(query) {
+        let mut found = Vec::new();
+        for term in &terms {
+            let before = found.len();
+            match term {
+                Term::Prefix(prefix) => {
+                    for (span, word) in spans.iter().zip(&words) {
+                        if word.starts_with(prefix.as_str()) {
+                            found.push(prefix_range(description, span, prefix));
+                        }
+                    }
+                }
+                Term::Phrase(phrase) => {
+                    for (start, window) in words.windows(phrase.len()).enumerate() {
+                        if window == phrase.as_slice() {
+                            let end = start + phrase.len() - 1;
+                            found.push(spans[start].start..spans[end].end);
+                        }
+                    }
+                }
+            }
+            if found.len() == before {
+                found.clear();
+                break;
+            }
+        }
+        ranges.extend(found);
+    }
+    ranges
+}
+
+/// The part of the word under `span` the prefix covers. Folding can change the
+/// length, so the prefix is measured in folded characters, and the accents of
+/// the last character covered are covered with it.
+fn prefix_range(description: &str, span: &Range<usize>, prefix: &str) -> Range<usize> {
+    let wanted = prefix.chars().count();
+    let mut folded = 0;
+    for (index, c) in description[span.clone()].char_indices() {
+        let count = fold_char(c).count();
+        if folded >= wanted && count > 0 {
+            return span.start..span.start + index;
+        }
+        folded += count;
+    }
+    span.clone()
+}
+
+/// Lowercase without accents, words compared the way the full-text index's
+/// `remove_diacritics` compares them
+fn fold(word: &str) -> String {
+    word.chars().flat_map(fold_char).collect()
+}
+
+fn fold_char(c: char) -> impl Iterator<Item = char> {
+    c.to_lowercase()
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+}
+
+/// Byte ranges of the words of the text, runs of letters and digits
+pub fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(from)) => {
+                spans.push(from..index);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        spans.push(from..text.len());
+    }
+    spans
+}
@@ -0,0 +1,114 @@
+use crate::dtos::tag_dto::TagDTO;
+use crate::services::file_service::detect_image_format;
+use crate::services::{image_service, job_service};
+use image::{DynamicImage, ImageFormat};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Downloads running at the same time in a batch import
+const MAX_CONCURRENT_DOWNLOADS: usize = 4;
+/// Minimum delay between starting two downloads, to avoid hammering a single host
+const DOWNLOAD_INTERVAL: Duration = Duration::from_millis(300);
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .user_agent(concat!("Organizer/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("Failed to create HTTP client")
+});
+
+/// Whether the text looks like an address that can be downloaded
+pub fn is_http_url(text: &str) -> bool {
+    let text = text.trim();
+    (text.starts_with("http://") || text.starts_with("https://"))
+        && !text.contains(char::is_whitespace)
+}
+
+/// Downloads and decodes an image from an http(s) address
+pub async fn download_image(url: &str) -> Result<(DynamicImage, ImageFormat), String> {
+    let url = url.trim();
+    if !is_http_url(url) {
+        return Err(format!("Invalid URL: {}", url));
+    }
+
+    let response = CLIENT
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+
+    let format = detect_image_format(&bytes);
+    let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    info!("Downloaded {} ({} bytes, {:?})", url, bytes.len(), format);
+
+    Ok((image, format))
+}
+
+/// Downloads and registers each (url, description) pair with the same tags.
+/// Results keep the input order; failures carry the reason.
+pub async fn import_urls(
+    entries: Vec<(String, String)>,
+    tags: HashSet<TagDTO>,
+) -> Vec<(String, Result<i64, String>)> {
+    let _job = job_service::start();
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_DOWNLOADS));
+    let mut interval = tokio::time::interval(DOWNLOAD_INTERVAL);
+    let mut jobs = JoinSet::new();
+
+    for (index, (url, description)) in entries.iter().cloned().enumerate() {
+        let permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("Download semaphore closed");
+        interval.tick().await;
+
+        let tags = tags.clone();
+        jobs.spawn(async move {
+            let result = match download_image(&url).await {
+                Ok((image, format)) => {
+                    image_service::register_image(
+                        image,
+                        format,
+                        description,
+                        tags,
+                        Some(url.trim().to_string()),
+                        Some(url.trim().to_string()),
+                    )
+                    .await
+                }
+                Err(err) => Err(err),
+            };
+            drop(permit);
+            (index, result)
+        });
+    }
+
+    let mut results: Vec<Option<Result<i64, String>>> = vec![None; entries.len()];
+    while let Some(joined) = jobs.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(err) => warn!("Download task failed: {}", err),
+        }
+    }
+
+    entries
+        .into_iter()
+        .zip(results)
+        .map(|((url, _), result)| {
+            let result = result.unwrap_or_else(|| Err("Download task failed".to_string()));
+            if let Err(err) = &result {
+                warn!("Failed to import {}: {}", url, err);
+            }
+            (url, result)
+        })
+        .collect()
+}
@@ -0,0 +1,607 @@
+use crate::config::get_settings;
+use crate::dtos::image_dto::ImageDTO;
+use crate::models::export_preset::{ExportFormat, ExportPreset};
+use crate::models::watermark::{Watermark, WatermarkKind};
+use crate::services::image_processor::{Frame, Orient, Pipeline, PngEncoder, Resize, Step};
+use crate::services::{file_service, image_processor, job_service, thumbnail_renderer};
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbImage, Rgba, RgbaImage};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Side of the square each image is fitted into
+const CELL_SIZE: u32 = 240;
+const CELL_PADDING: u32 = 16;
+const LABEL_HEIGHT: u32 = 28;
+const LABEL_FONT_SIZE: f32 = 16.0;
+/// Resolution the PDF pages are laid out at
+const PDF_DPI: f32 = 96.0;
+
+const BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const CELL_BACKGROUND: Rgba<u8> = Rgba([240, 240, 240, 255]);
+const LABEL_COLOR: [f32; 3] = [40.0, 40.0, 40.0];
+
+/// Watermark text height and distance from the edges, relative to the shorter side
+const WATERMARK_TEXT_SCALE: f32 = 0.05;
+const WATERMARK_MARGIN_SCALE: f32 = 0.02;
+/// Share of the picture's width and height a watermark image may cover
+const WATERMARK_IMAGE_SCALE: f32 = 0.25;
+
+/// Sans-serif system font for labels, loaded once. Labels are skipped without one.
+static LABEL_FONT: Lazy<Option<FontVec>> = Lazy::new(|| {
+    let mut db = fontdb::Database::new();
+    db.load_system_fonts();
+    // The generic sans-serif family maps to Arial, which most Linux systems lack
+    let families = [
+        fontdb::Family::SansSerif,
+        fontdb::Family::Name("Segoe UI"),
+        fontdb::Family::Name("Helvetica"),
+        fontdb::Family::Name("DejaVu Sans"),
+        fontdb::Family::Name("Noto Sans"),
+        fontdb::Family::Name("Liberation Sans"),
+    ];
+    let id = db
+        .query(&fontdb::Query {
+            families: &families,
+            ..Default::default()
+        })
+        .or_else(|| db.faces().next().map(|face| face.id))?;
+    db.with_face_data(id, |data, index| {
+        FontVec::try_from_vec_and_index(data.to_vec(), index).ok()
+    })
+    .flatten()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetFormat {
+    Png,
+    Pdf,
+}
+
+impl SheetFormat {
+    pub const ALL: [SheetFormat; 2] = [SheetFormat::Png, SheetFormat::Pdf];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SheetFormat::Png => "png",
+            SheetFormat::Pdf => "pdf",
+        }
+    }
+}
+
+impl fmt::Display for SheetFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SheetFormat::Png => write!(f, "PNG"),
+            SheetFormat::Pdf => write!(f, "PDF"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContactSheetOptions {
+    pub columns: u32,
+    pub rows: u32,
+    pub labels: bool,
+    pub format: SheetFormat,
+}
+
+/// Renders the images into contact sheets of `rows × columns` cells.
+/// A PDF gets one page per sheet; PNG sheets after the first are numbered.
+/// Returns the number of sheets written.
+pub async fn export_contact_sheet(
+    images: Vec<ImageDTO>,
+    options: ContactSheetOptions,
+    destination: PathBuf,
+) -> Result<usize, String> {
+    let _job = job_service::start();
+    tokio::task::spawn_blocking(move || {
+        if images.is_empty() {
+            return Err("No images to export".to_string());
+        }
+
+        let per_sheet = (options.columns * options.rows).max(1) as usize;
+        let sheets: Vec<RgbImage> = images
+            .chunks(per_sheet)
+            .map(|chunk| render_sheet(chunk, &options))
+            .collect();
+
+        match options.format {
+            SheetFormat::Png => write_png_sheets(&sheets, &destination)?,
+            SheetFormat::Pdf => write_pdf(&sheets, &destination)?,
+        }
+
+        info!(
+            "Exported {} images to {} sheet(s) at {}",
+            images.len(),
+            sheets.len(),
+            destination.display()
+        );
+        Ok(sheets.len())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn render_sheet(images: &[ImageDTO], options: &ContactSheetOptions) -> RgbImage {
+    let label_height = if options.labels { LABEL_HEIGHT } else { 0 };
+    let row_height = CELL_SIZE + label_height;
+    let width = options.columns * CELL_SIZE + (options.columns + 1) * CELL_PADDING;
+    let height = options.rows * row_height + (options.rows + 1) * CELL_PADDING;
+
+    let mut sheet = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+    for (index, image_dto) in images.iter().enumerate() {
+        let column = index as u32 % options.columns;
+        let row = index as u32 / options.columns;
+        let x = CELL_PADDING + column * (CELL_SIZE + CELL_PADDING);
+        let y = CELL_PADDING + row * (row_height + CELL_PADDING);
+
+        fill_rect(&mut sheet, x, y, CELL_SIZE, CELL_SIZE, CELL_BACKGROUND);
+
+        match load_cell_image(image_dto) {
+            Some(cell) => {
+                let offset_x = x + (CELL_SIZE - cell.width()) / 2;
+                let offset_y = y + (CELL_SIZE - cell.height()) / 2;
+                image::imageops::overlay(&mut sheet, &cell, offset_x as i64, offset_y as i64);
+            }
+            None => warn!("Could not load {} for the contact sheet", image_dto.path),
+        }
+
+        if options.labels {
+            draw_label(&mut sheet, &image_dto.description, x, y + CELL_SIZE);
+        }
+    }
+
+    DynamicImage::ImageRgba8(sheet).to_rgb8()
+}
+
+/// Thumbnail (or the original when missing) fitted into a cell
+fn load_cell_image(image_dto: &ImageDTO) -> Option<RgbaImage> {
+    let image = image::open(&image_dto.thumbnail_path)
+        .or_else(|_| image::open(&image_dto.path))
+        .ok()?;
+    Some(
+        image
+            .resize(CELL_SIZE, CELL_SIZE, FilterType::Triangle)
+            .to_rgba8(),
+    )
+}
+
+fn fill_rect(sheet: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(sheet.height()) {
+        for px in x..(x + width).min(sheet.width()) {
+            sheet.put_pixel(px, py, color);
+        }
+    }
+}
+
+/// Draws the text centered under a cell, cut with an ellipsis when wider than the cell
+fn draw_label(sheet: &mut RgbaImage, text: &str, x: u32, y: u32) {
+    let Some(font) = LABEL_FONT.as_ref() else {
+        return;
+    };
+    let scaled = font.as_scaled(PxScale::from(LABEL_FONT_SIZE));
+    let text_width = |text: &str| -> f32 {
+        text.chars()
+            .map(|c| scaled.h_advance(font.glyph_id(c)))
+            .sum()
+    };
+
+    let max_width = CELL_SIZE as f32;
+    let mut label = text.trim().to_string();
+    if text_width(&label) > max_width {
+        while !label.is_empty() && text_width(&format!("{}…", label)) > max_width {
+            label.pop();
+        }
+        label.push('…');
+    }
+
+    let baseline = y as f32 + (LABEL_HEIGHT as f32 + scaled.ascent() + scaled.descent()) / 2.0;
+    let mut caret = x as f32 + (max_width - text_width(&label)).max(0.0) / 2.0;
+
+    for c in label.chars() {
+        let glyph_id = font.glyph_id(c);
+        let glyph = glyph_id.with_scale_and_position(scaled.scale(), point(caret, baseline));
+        caret += scaled.h_advance(glyph_id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|gx, gy, coverage| {
+            let px = bounds.min.x as i32 + gx as i32;
+            let py = bounds.min.y as i32 + gy as i32;
+            if px < 0 || py < 0 || px >= sheet.width() as i32 || py >= sheet.height() as i32 {
+                return;
+            }
+            let pixel = sheet.get_pixel_mut(px as u32, py as u32);
+            for (channel, target) in pixel.0.iter_mut().zip(LABEL_COLOR) {
+                *channel = (*channel as f32 * (1.0 - coverage) + target * coverage) as u8;
+            }
+        });
+    }
+}
+
+fn write_png_sheets(sheets: &[RgbImage], destination: &Path) -> Result<(), String> {
+    for (index, sheet) in sheets.iter().enumerate() {
+        let path = if index == 0 {
+            destination.to_path_buf()
+        } else {
+            numbered_path(destination, index + 1)
+        };
+        sheet
+            .save_with_format(&path, image::ImageFormat::Png)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// `sheet.png` -> `sheet_2.png`
+fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, number, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, number),
+    };
+    path.with_file_name(name)
+}
+
+// ===================================
+//         COPIES
+// ===================================
+
+/// Copies the originals of the images into `destination`, with every image of a
+/// folder entry. A preset resizes and converts the copies, a watermark is drawn over
+/// them; raster images are written again for either, other media are always copied
+/// as they are. Returns the number of files written.
+pub async fn export_copies(
+    images: Vec<ImageDTO>,
+    destination: PathBuf,
+    preset: Option<ExportPreset>,
+    watermark: Option<Watermark>,
+) -> Result<usize, String> {
+    let _job = job_service::start();
+    let compression = get_settings().config.image_compression.unwrap_or(5);
+    tokio::task::spawn_blocking(move || {
+        if images.is_empty() {
+            return Err("No images to export".to_string());
+        }
+
+        let preset = preset.unwrap_or_default();
+        let watermark = watermark.filter(Watermark::is_set);
+        let unchanged = preset.max_side == 0 && preset.format == ExportFormat::Original;
+        let pipeline = if unchanged && watermark.is_none() {
+            None
+        } else {
+            let mut pipeline = Pipeline::new(PngEncoder::new(compression)).with_step(Orient);
+            if preset.max_side > 0 {
+                pipeline = pipeline.with_step(Resize::shrink_to(preset.max_side, preset.max_side));
+            }
+            if let Some(watermark) = watermark {
+                pipeline = pipeline.with_step(WatermarkStep::new(watermark)?);
+            }
+            Some(pipeline)
+        };
+
+        let sources: Vec<PathBuf> = images
+            .iter()
+            .flat_map(|image_dto| {
+                if image_dto.is_folder {
+                    file_service::expand_folder_dto(image_dto)
+                        .into_iter()
+                        .map(|image| PathBuf::from(image.path))
+                        .collect()
+                } else {
+                    vec![PathBuf::from(&image_dto.path)]
+                }
+            })
+            .collect();
+
+        let mut written = 0;
+        for source in &sources {
+            let Some(name) = source.file_name() else {
+                continue;
+            };
+            let mut target = destination.join(name);
+            let pipeline = pipeline
+                .as_ref()
+                .filter(|_| thumbnail_renderer::is_displayable(source));
+            if let (Some(_), Some(extension)) = (pipeline, preset.format.extension()) {
+                target.set_extension(extension);
+            }
+            let target = free_path(&target);
+
+            let result = match pipeline {
+                Some(pipeline) => write_copy(source, &target, pipeline, preset.quality),
+                None => fs::copy(source, &target).map(|_| ()).map_err(Into::into),
+            };
+            match result {
+                Ok(()) => written += 1,
+                Err(err) => warn!("Could not export {}: {}", source.display(), err),
+            }
+        }
+
+        if written == 0 {
+            return Err(format!(
+                "None of the {} files could be exported",
+                sources.len()
+            ));
+        }
+        info!(
+            "Exported {} of {} files to {}",
+            written,
+            sources.len(),
+            destination.display()
+        );
+        Ok(written)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// `path`, or the first `path_{n}` not taken yet
+fn free_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    (2..)
+        .map(|number| numbered_path(path, number))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Runs the original through the pipeline and writes it in the format of `target`
+fn write_copy(
+    source: &Path,
+    target: &Path,
+    pipeline: &Pipeline,
+    jpeg_quality: u8,
+) -> Result<(), Box<dyn Error>> {
+    let frame = image_processor::decode(&fs::read(source)?)?;
+    let format = ImageFormat::from_path(target)?;
+    if format == ImageFormat::Png {
+        return pipeline.run(frame, target);
+    }
+
+    let image = pipeline.process(frame)?.into_image();
+    match format {
+        ImageFormat::Jpeg => {
+            let file = fs::File::create(target)?;
+            JpegEncoder::new_with_quality(std::io::BufWriter::new(file), jpeg_quality.clamp(1, 100))
+                .encode_image(&DynamicImage::ImageRgb8(image.to_rgb8()))?;
+        }
+        format => image.save_with_format(target, format)?,
+    }
+    Ok(())
+}
+
+/// Draws a [`Watermark`] over the picture, sized to it
+pub struct WatermarkStep {
+    watermark: Watermark,
+    /// Picture of an image watermark, read once for every export
+    image: Option<RgbaImage>,
+}
+
+impl WatermarkStep {
+    pub fn new(watermark: Watermark) -> Result<Self, String> {
+        let image = match watermark.kind {
+            WatermarkKind::Text => None,
+            WatermarkKind::Image => Some(
+                image::open(&watermark.image_path)
+                    .map_err(|e| format!("{}: {}", watermark.image_path, e))?
+                    .to_rgba8(),
+            ),
+        };
+        Ok(Self { watermark, image })
+    }
+
+    /// The mark, sized for a `width` × `height` picture
+    fn render(&self, width: u32, height: u32) -> Option<RgbaImage> {
+        let side = width.min(height) as f32;
+        match &self.image {
+            Some(image) => {
+                let max_width = (width as f32 * WATERMARK_IMAGE_SCALE).max(1.0);
+                let max_height = (height as f32 * WATERMARK_IMAGE_SCALE).max(1.0);
+                let scale = (max_width / image.width() as f32)
+                    .min(max_height / image.height() as f32)
+                    .min(1.0);
+                let mark_width = ((image.width() as f32 * scale).round() as u32).max(1);
+                let mark_height = ((image.height() as f32 * scale).round() as u32).max(1);
+                Some(image::imageops::resize(
+                    image,
+                    mark_width,
+                    mark_height,
+                    FilterType::Triangle,
+                ))
+            }
+            None => render_text(
+                self.watermark.text.trim(),
+                (side * WATERMARK_TEXT_SCALE).max(12.0),
+            ),
+        }
+    }
+}
+
+impl Step for WatermarkStep {
+    fn name(&self) -> &'static str {
+        "watermark"
+    }
+
+    fn apply<'a>(&self, frame: Frame<'a>) -> Result<Frame<'a>, Box<dyn Error>> {
+        let (width, height) = (frame.image.width(), frame.image.height());
+        let Some(mut mark) = self.render(width, height) else {
+            return Ok(frame);
+        };
+
+        let opacity = self.watermark.opacity.min(100) as f32 / 100.0;
+        for pixel in mark.pixels_mut() {
+            pixel.0[3] = (pixel.0[3] as f32 * opacity).round() as u8;
+        }
+
+        let margin = ((width.min(height) as f32 * WATERMARK_MARGIN_SCALE) as u32).max(4);
+        let (x, y) = self
+            .watermark
+            .position
+            .place((width, height), mark.dimensions(), margin);
+
+        let mut picture = frame.image.to_rgba8();
+        image::imageops::overlay(&mut picture, &mark, x, y);
+        Ok(Frame {
+            image: Cow::Owned(DynamicImage::ImageRgba8(picture)),
+            orientation: frame.orientation,
+        })
+    }
+}
+
+/// White text with a dark shadow, `None` without a font or text
+fn render_text(text: &str, size: f32) -> Option<RgbaImage> {
+    let font = LABEL_FONT.as_ref()?;
+    if text.is_empty() {
+        return None;
+    }
+    let scaled = font.as_scaled(PxScale::from(size));
+    let shadow = (size / 16.0).ceil().max(1.0);
+
+    let text_width: f32 = text
+        .chars()
+        .map(|c| scaled.h_advance(font.glyph_id(c)))
+        .sum();
+    let width = (text_width + shadow).ceil() as u32 + 1;
+    let height = (scaled.ascent() - scaled.descent() + shadow).ceil() as u32 + 1;
+    let mut mark = RgbaImage::new(width.max(1), height.max(1));
+
+    for (offset, shade) in [(shadow, 0u8), (0.0, 255u8)] {
+        let mut caret = offset;
+        for c in text.chars() {
+            let glyph_id = font.glyph_id(c);
+            let glyph = glyph_id
+                .with_scale_and_position(scaled.scale(), point(caret, scaled.ascent() + offset));
+            caret += scaled.h_advance(glyph_id);
+
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                if px < 0 || py < 0 || px >= mark.width() as i32 || py >= mark.height() as i32 {
+                    return;
+                }
+                let pixel = mark.get_pixel_mut(px as u32, py as u32);
+                let alpha = pixel.0[3] as f32 / 255.0;
+                let blended = |channel: u8| {
+                    (channel as f32 * alpha * (1.0 - coverage) + shade as f32 * coverage)
+                        / (alpha * (1.0 - coverage) + coverage).max(f32::EPSILON)
+                };
+                let color = blended(pixel.0[0]) as u8;
+                let out_alpha = alpha + coverage * (1.0 - alpha);
+                *pixel = Rgba([color, color, color, (out_alpha * 255.0).round() as u8]);
+            });
+        }
+    }
+
+    Some(mark)
+}
+
+/// Writes a minimal PDF with one page per sheet, each holding the sheet as a JPEG
+fn write_pdf(sheets: &[RgbImage], destination: &Path) -> Result<(), String> {
+    let mut pdf: Vec<u8> = Vec::new();
+    let mut offsets: Vec<usize> = Vec::new();
+    let page_ids: Vec<usize> = (0..sheets.len()).map(|i| 3 + i * 3).collect();
+
+    pdf.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+    let mut object = |pdf: &mut Vec<u8>, body: &[u8]| {
+        offsets.push(pdf.len());
+        let id = offsets.len();
+        let _ = writeln!(pdf, "{} 0 obj", id);
+        pdf.extend_from_slice(body);
+        pdf.extend_from_slice(b"\nendobj\n");
+    };
+
+    object(&mut pdf, b"<< /Type /Catalog /Pages 2 0 R >>");
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+    object(
+        &mut pdf,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            sheets.len()
+        )
+        .as_bytes(),
+    );
+
+    for (sheet, page_id) in sheets.iter().zip(&page_ids) {
+        let scale = 72.0 / PDF_DPI;
+        let page_width = sheet.width() as f32 * scale;
+        let page_height = sheet.height() as f32 * scale;
+
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, 90)
+            .encode_image(&DynamicImage::ImageRgb8(sheet.clone()))
+            .map_err(|e| e.to_string())?;
+
+        object(
+            &mut pdf,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] \
+                 /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                page_width,
+                page_height,
+                page_id + 1,
+                page_id + 2
+            )
+            .as_bytes(),
+        );
+
+        let mut image_object = format!(
+            "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+             /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+            sheet.width(),
+            sheet.height(),
+            jpeg.len()
+        )
+        .into_bytes();
+        image_object.extend_from_slice(&jpeg);
+        image_object.extend_from_slice(b"\nendstream");
+        object(&mut pdf, &image_object);
+
+        let content = format!(
+            "q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q",
+            page_width, page_height
+        );
+        object(
+            &mut pdf,
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            )
+            .as_bytes(),
+        );
+    }
+
+    let xref_offset = pdf.len();
+    let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+    for offset in &offsets {
+        let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        offsets.len() + 1,
+        xref_offset
+    );
+
+    fs::write(destination, pdf).map_err(|e| format!("{}: {}", destination.display(), e))
+}
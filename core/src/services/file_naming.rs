@@ -0,0 +1,193 @@
+//! Names of the files stored for an entry and of the files made from them.
+//!
+//! A single image is stored as `{stem}.{ext}` and the images of a folder entry as
+//! `{stem}_{index}.{ext}`. Every image gets a thumbnail `thumb_{image stem}.png`
+//! and, when large, a preview `thumb_{image stem}.preview.png`. Folder entries also
+//! hold `thumb_folder.png` for their cover.
+//!
+//! The image stem is the file name up to its last dot, so a name with dots in it
+//! still finds its thumbnail. Two names can't be told apart: a thumbnail of an image
+//! whose stem ends in `.preview` and the folder cover of an image named `folder`.
+//! Stored stems have neither, see `file_service::stored_file_stem`.
+//!
+//! Images of a folder are taken in [`natural_order`], when imported and when shown,
+//! so the index an image is stored under matches its place in the source folder.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Start of every thumbnail and preview name, left out of image listings
+const DERIVED_PREFIX: &str = "thumb_";
+const THUMBNAIL_SUFFIX: &str = ".png";
+const PREVIEW_SUFFIX: &str = ".preview.png";
+
+/// Thumbnail of a folder entry, made from its first image
+pub const FOLDER_THUMBNAIL: &str = "thumb_folder.png";
+/// Bookkeeping of a folder entry, see `folder_meta`
+pub const META_FILE: &str = "meta.json";
+
+/// A thumbnail or preview file, with the stem of the image it was made from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivedFile<'a> {
+    Thumbnail(&'a str),
+    Preview(&'a str),
+    FolderThumbnail,
+}
+
+/// Characters Windows refuses in file names, the strictest of the platforms
+const FORBIDDEN_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+/// Device names Windows reserves, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+/// Bytes a typed name may take, leaving room for the thumbnail's prefix and suffix
+const MAX_STEM_LEN: usize = 200;
+
+/// Why a typed file name can't be used, see [`typed_stem`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidName {
+    Empty,
+    Forbidden(char),
+    /// A device name, or one the thumbnails couldn't be told apart by
+    Reserved,
+    TooLong,
+}
+
+impl fmt::Display for InvalidName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidName::Empty => write!(f, "{}", t!("message.rename.empty")),
+            InvalidName::Forbidden(c) => write!(
+                f,
+                "{}",
+                t!("message.rename.forbidden", character = c.to_string())
+            ),
+            InvalidName::Reserved => write!(f, "{}", t!("message.rename.reserved")),
+            InvalidName::TooLong => write!(f, "{}", t!("message.rename.too_long")),
+        }
+    }
+}
+
+/// Stem for a stored image out of a name typed for it. Surrounding spaces and
+/// dots go, as does the image's extension when typed along.
+pub fn typed_stem(name: &str, extension: &str) -> Result<String, InvalidName> {
+    let name = name.trim();
+    let suffix_len = extension.len() + 1;
+    let name = match name.len().checked_sub(suffix_len) {
+        Some(split)
+            if name.is_char_boundary(split)
+                && name[split..].eq_ignore_ascii_case(&format!(".{}", extension)) =>
+        {
+            &name[..split]
+        }
+        _ => name,
+    };
+    // Windows drops trailing dots and spaces, leading ones would hide the file
+    let stem = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+
+    if stem.is_empty() {
+        return Err(InvalidName::Empty);
+    }
+    if let Some(c) = stem
+        .chars()
+        .find(|c| c.is_control() || FORBIDDEN_CHARS.contains(c))
+    {
+        return Err(InvalidName::Forbidden(c));
+    }
+    let device = stem.split('.').next().unwrap_or(stem);
+    if stem.starts_with(DERIVED_PREFIX)
+        || stem.ends_with(".preview")
+        || RESERVED_NAMES.iter().any(|reserved| device.eq_ignore_ascii_case(reserved))
+    {
+        return Err(InvalidName::Reserved);
+    }
+    if stem.len() > MAX_STEM_LEN {
+        return Err(InvalidName::TooLong);
+    }
+    Ok(stem.to_string())
+}
+
+/// Name of a stored image
+pub fn image_file_name(stem: &str, extension: &str) -> String {
+    format!("{}.{}", stem, extension)
+}
+
+/// Stem of the image at `index` in a folder entry
+pub fn folder_image_stem(stem: &str, index: usize) -> String {
+    format!("{}_{}", stem, index)
+}
+
+/// Position of a folder entry image, read back from its stem
+pub fn folder_image_index(image_stem: &str) -> Option<usize> {
+    image_stem.rsplit_once('_')?.1.parse().ok()
+}
+
+/// File name without its extension. Like [`Path::file_stem`], a leading dot is
+/// part of the name and only the last dot starts the extension.
+pub fn image_stem(file_name: &str) -> &str {
+    match file_name.rfind('.') {
+        Some(0) | None => file_name,
+        Some(dot) => &file_name[..dot],
+    }
+}
+
+pub fn thumbnail_name(image_stem: &str) -> String {
+    format!("{}{}{}", DERIVED_PREFIX, image_stem, THUMBNAIL_SUFFIX)
+}
+
+pub fn preview_name(image_stem: &str) -> String {
+    format!("{}{}{}", DERIVED_PREFIX, image_stem, PREVIEW_SUFFIX)
+}
+
+/// Whether the file was made from a stored image rather than being one
+pub fn is_derived(file_name: &str) -> bool {
+    file_name.starts_with(DERIVED_PREFIX)
+}
+
+/// Reads a thumbnail or preview name back, `None` for any other file
+pub fn parse_derived(file_name: &str) -> Option<DerivedFile<'_>> {
+    if file_name == FOLDER_THUMBNAIL {
+        return Some(DerivedFile::FolderThumbnail);
+    }
+    let rest = file_name.strip_prefix(DERIVED_PREFIX)?;
+    if let Some(stem) = rest.strip_suffix(PREVIEW_SUFFIX) {
+        return Some(DerivedFile::Preview(stem));
+    }
+    rest.strip_suffix(THUMBNAIL_SUFFIX)
+        .map(DerivedFile::Thumbnail)
+}
+
+/// Path of the thumbnail generated for a stored image
+pub fn thumbnail_path_for(image_path: &Path) -> Option<PathBuf> {
+    let name = image_path.file_name()?.to_str()?;
+    Some(image_path.with_file_name(thumbnail_name(image_stem(name))))
+}
+
+/// Path of the mid-size rendition of a stored image. Named like the thumbnails so
+/// folder listings skip it.
+pub fn preview_path_for(image_path: &Path) -> Option<PathBuf> {
+    let name = image_path.file_name()?.to_str()?;
+    Some(image_path.with_file_name(preview_name(image_stem(name))))
+}
+
+/// Order of the images of a folder: numbers by value (`2` before `10`) and letters
+/// ignoring case. Names equal that way are ordered by their exact bytes, so the
+/// result never depends on the order the file system lists them in.
+pub fn natural_order(a: &str, b: &str) -> Ordering {
+    natord::compare_ignore_case(a, b).then_with(|| a.cmp(b))
+}
+
+/// Sorts paths by file name in [`natural_order`]
+pub fn sort_by_name(paths: &mut [PathBuf]) {
+    paths.sort_by(|a, b| {
+        let name = |path: &PathBuf| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+        natural_order(&name(a), &name(b))
+    });
+}
@@ -0,0 +1,1488 @@
+use crate::config::get_settings;
+use crate::dtos::image_dto::ImageDTO;
+use crate::models::delete_behavior::DeleteBehavior;
+use crate::models::import_filter::{ImportFilter, SkipReason};
+use crate::models::import_rule::ImportedFile;
+use crate::services::file_naming::{
+    DerivedFile, META_FILE, folder_image_index, folder_image_stem, image_file_name,
+    image_stem, is_derived, natural_order, parse_derived, preview_path_for, sort_by_name,
+    thumbnail_name, thumbnail_path_for,
+};
+use crate::services::atomic_file;
+use crate::services::folder_meta::FolderMeta;
+use crate::services::image_processor::generate_thumbnail_from_image;
+use crate::services::job_service;
+use crate::services::thumbnail_renderer::{self, is_displayable, renderer_for};
+use crate::utils::get_data_dir;
+use chrono::NaiveDate;
+use image::DynamicImage;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
+use crate::models::enums::image_type::ImageType;
+
+// ===================================
+//         UTILITY FUNCTIONS
+// ===================================
+
+pub fn detect_image_format(bytes: &[u8]) -> image::ImageFormat {
+    if let Some(kind) = infer::get(bytes) {
+        match kind.mime_type() {
+            "image/jpeg" => image::ImageFormat::Jpeg,
+            "image/png" => image::ImageFormat::Png,
+            "image/gif" => image::ImageFormat::Gif,
+            "image/webp" => image::ImageFormat::WebP,
+            "image/bmp" => image::ImageFormat::Bmp,
+            "image/tiff" => image::ImageFormat::Tiff,
+            _ => image::ImageFormat::Png,
+        }
+    } else {
+        image::ImageFormat::Png
+    }
+}
+
+pub fn format_to_extension(format: image::ImageFormat) -> &'static str {
+    match format {
+        image::ImageFormat::Jpeg => "jpg",
+        image::ImageFormat::Png => "png",
+        image::ImageFormat::Gif => "gif",
+        image::ImageFormat::WebP => "webp",
+        image::ImageFormat::Bmp => "bmp",
+        image::ImageFormat::Tiff => "tiff",
+        _ => "png",
+    }
+}
+
+/// Naming pattern used when none is configured, the historical `image_{id}` layout
+pub const DEFAULT_FILE_NAME_PATTERN: &str = "image_{id}";
+
+/// Returns the configured naming pattern, falling back to the default when it
+/// doesn't contain `{id}` (names must stay unique)
+fn file_name_pattern() -> String {
+    get_settings()
+        .config
+        .file_name_pattern
+        .clone()
+        .filter(|p| p.contains("{id}"))
+        .unwrap_or_else(|| DEFAULT_FILE_NAME_PATTERN.to_string())
+}
+
+fn slugify(text: &str) -> String {
+    let slug: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+
+    slug.split('-')
+        .filter(|part| !part.is_empty())
+        .take(8)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Builds the stem (name without extension) of a stored file from the naming pattern
+pub fn stored_file_stem(id: i64, description: &str, date: NaiveDate) -> String {
+    let name = file_name_pattern()
+        .replace("{id}", &id.to_string())
+        .replace("{date}", &date.format("%Y-%m-%d").to_string())
+        .replace("{description_slug}", &slugify(description));
+
+    // Only keep filesystem safe characters and collapse empty placeholders
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    sanitized
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Box the thumbnails are fitted in
+pub const THUMBNAIL_SIZE: u32 = 500;
+
+/// Longest side of the renditions the preview shows in place of larger originals
+pub const PREVIEW_SIZE: u32 = 2048;
+
+/// Saves the preview rendition of an image larger than [`PREVIEW_SIZE`], and of
+/// every original that can't be shown as it is
+fn save_preview_rendition(
+    image: &DynamicImage,
+    image_path: &Path,
+    compression: u8,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let longest = image.width().max(image.height());
+    if longest <= PREVIEW_SIZE && is_displayable(image_path) {
+        return Ok(());
+    }
+    let preview_path = preview_path_for(image_path).ok_or("Invalid image path")?;
+    let size = longest.min(PREVIEW_SIZE);
+    generate_thumbnail_from_image(image, &preview_path, size, size, compression)
+}
+
+/// File the preview opens for an image: its rendition when one is up to date,
+/// otherwise the original
+pub fn preview_source(image_path: &str) -> String {
+    let path = Path::new(image_path);
+    match preview_path_for(path) {
+        Some(preview_path) if is_derived_current(path, &preview_path) => {
+            preview_path.to_string_lossy().to_string()
+        }
+        _ => image_path.to_string(),
+    }
+}
+
+/// Generates the missing or outdated rendition of an image in the background. Returns
+/// the rendition, or `None` when the image can be previewed as is.
+pub async fn prepare_preview(image_path: String) -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&image_path);
+        let preview_path = preview_path_for(path).ok_or("Invalid image path")?;
+        if is_derived_current(path, &preview_path) {
+            return Ok(Some(preview_path.to_string_lossy().to_string()));
+        }
+
+        // Only the header is read to tell whether a rendition is needed
+        if is_displayable(path) {
+            let (width, height) = image::image_dimensions(path).map_err(|e| e.to_string())?;
+            if width <= PREVIEW_SIZE && height <= PREVIEW_SIZE {
+                return Ok(None);
+            }
+        }
+
+        let _job = job_service::start();
+        let image = thumbnail_renderer::render(path, PREVIEW_SIZE)?;
+        let compression = get_settings().config.thumb_compression.unwrap_or(9);
+        save_preview_rendition(&image, path, compression).map_err(|e| e.to_string())?;
+        info!("Generated preview rendition: {}", preview_path.display());
+        Ok(Some(preview_path.to_string_lossy().to_string()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+pub fn save_image_file_with_thumbnail(
+    id: i64,
+    image: DynamicImage,
+    original_format: image::ImageFormat,
+    description: &str,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let image_dir = get_data_dir().join("images").join(id.to_string());
+    if !image_dir.exists() {
+        fs::create_dir_all(&image_dir)?;
+    }
+
+    let extension = format_to_extension(original_format);
+    let stem = stored_file_stem(id, description, chrono::Local::now().date_naive());
+    let image_path = image_dir.join(image_file_name(&stem, extension));
+    let thumb_path = image_dir.join(thumbnail_name(&stem));
+
+    // Salvar no formato original
+    atomic_file::save_image(&image, &image_path)?;
+
+    // Thumbnail continua em PNG
+    let thumb_compression = get_settings().config.thumb_compression.unwrap_or(9);
+    generate_thumbnail_from_image(&image, &thumb_path, THUMBNAIL_SIZE, THUMBNAIL_SIZE, thumb_compression)?;
+    save_preview_rendition(&image, &image_path, thumb_compression)?;
+
+    Ok((
+        image_path.to_string_lossy().to_string(),
+        thumb_path.to_string_lossy().to_string(),
+    ))
+}
+
+/// Totals of the images stored in a folder entry, see [`folder_stats`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FolderStats {
+    pub image_count: usize,
+    /// Bytes taken by the images, without their thumbnails and previews
+    pub total_size: u64,
+    /// Day the oldest image was stored or last modified, as `YYYY-MM-DD`
+    pub first_date: Option<String>,
+    /// Day the newest image was stored or last modified, as `YYYY-MM-DD`
+    pub last_date: Option<String>,
+}
+
+/// Files under a folder and the bytes they take, see [`dir_contents`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirContents {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// Images stored by a folder import
+#[derive(Debug, Clone, Default)]
+pub struct FolderImport {
+    pub image_dir: String,
+    /// Thumbnail of the first image of the entry, empty while it holds none
+    pub cover_thumb: String,
+    /// Thumbnails of the images added, in import order
+    pub thumbnails: Vec<String>,
+    /// Files left out because the entry already holds them
+    pub skipped: usize,
+    /// Files left out by the import filter, by name
+    pub filtered: Vec<(String, SkipReason)>,
+    /// Files that could not be decoded, by path with the decoder error
+    pub unreadable: Vec<(String, String)>,
+    /// Files stored, for the import rules
+    pub files: Vec<ImportedFile>,
+}
+
+/// Image files directly inside a folder picked for import, in the order they are stored
+fn source_images(folder_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(folder_path)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_image_file(path))
+        .collect();
+
+    sort_by_name(&mut files);
+    Ok(files)
+}
+
+fn canonical_string(path: &Path) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Why a source file was not stored
+enum Rejection {
+    Filtered(SkipReason),
+    /// Could not be decoded, with the decoder error
+    Unreadable(String),
+}
+
+/// Checks a source file against the import filter, rendering it when it passes
+/// the checks that need no rendering
+fn filtered_image(
+    path: &Path,
+    bytes: &[u8],
+    filter: &ImportFilter,
+) -> Result<DynamicImage, Rejection> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    filter
+        .check_file(extension, bytes.len() as u64)
+        .map_err(Rejection::Filtered)?;
+
+    let image = thumbnail_renderer::render_bytes(path, bytes, PREVIEW_SIZE).map_err(|err| {
+        warn!("Quarantining unreadable image {}: {}", path.display(), err);
+        Rejection::Unreadable(err)
+    })?;
+    filter
+        .check_dimensions(image.width(), image.height())
+        .map_err(Rejection::Filtered)?;
+    Ok(image)
+}
+
+/// Copies the images of `files` into the entry folder after the ones it holds,
+/// skipping files whose source hash the entry already knows and those the
+/// filter leaves out. The meta is saved after every image so an interrupted
+/// import can continue where it stopped.
+fn store_folder_images(
+    image_dir: &Path,
+    files: &[PathBuf],
+    stem: &str,
+    meta: &mut FolderMeta,
+    filter: &ImportFilter,
+) -> Result<FolderImport, Box<dyn std::error::Error>> {
+    let thumb_compression = get_settings().config.thumb_compression.unwrap_or(9);
+    let mut known: HashSet<String> = meta.source_hashes.iter().cloned().collect();
+    let mut import = FolderImport {
+        image_dir: image_dir.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+
+    for path in files {
+        let bytes = fs::read(path)?;
+        let hash = blake3::hash(&bytes).to_hex().to_string();
+        if !known.insert(hash.clone()) {
+            debug!("Skipping already imported file: {}", path.display());
+            import.skipped += 1;
+            continue;
+        }
+
+        let image = match filtered_image(path, &bytes, filter) {
+            Ok(image) => image,
+            Err(rejection) => {
+                known.remove(&hash);
+                match rejection {
+                    Rejection::Filtered(reason) => {
+                        debug!("Filtered out {} from the import: {:?}", path.display(), reason);
+                        let name = path.file_name().unwrap_or_default().to_string_lossy();
+                        import.filtered.push((name.to_string(), reason));
+                    }
+                    Rejection::Unreadable(err) => import
+                        .unreadable
+                        .push((path.to_string_lossy().to_string(), err)),
+                }
+                continue;
+            }
+        };
+
+        // Images are stored in the format their content has, other originals are
+        // copied as they are
+        let displayable = is_displayable(path);
+        let extension = if displayable {
+            format_to_extension(detect_image_format(&bytes)).to_string()
+        } else {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or_default()
+                .to_lowercase()
+        };
+        let index = meta.next_index;
+        import.files.push(ImportedFile::new(
+            path.to_str(),
+            &extension,
+            image.width(),
+            image.height(),
+        ));
+
+        let image_stem = folder_image_stem(stem, index);
+        let image_path = image_dir.join(image_file_name(&image_stem, &extension));
+        let thumb_path = image_dir.join(thumbnail_name(&image_stem));
+
+        if displayable {
+            atomic_file::save_image(&image, &image_path)?;
+        } else {
+            atomic_file::write(&image_path, &bytes)?;
+        }
+
+        generate_thumbnail_from_image(&image, &thumb_path, THUMBNAIL_SIZE, THUMBNAIL_SIZE, thumb_compression)?;
+        save_preview_rendition(&image, &image_path, thumb_compression)?;
+
+        let thumb_path = thumb_path.to_string_lossy().to_string();
+        if meta.cover_thumb.is_empty() {
+            meta.cover_thumb = thumb_path.clone();
+            let folder_thumb_path = Path::new(&meta.folder_thumb);
+            if !meta.folder_thumb.is_empty() && !folder_thumb_path.exists() {
+                generate_thumbnail_from_image(
+                    &image,
+                    folder_thumb_path,
+                    500,
+                    500,
+                    thumb_compression,
+                )?;
+                info!("Created folder thumbnail: {}", folder_thumb_path.display());
+            }
+        }
+        import.thumbnails.push(thumb_path);
+        meta.source_hashes.push(hash);
+        meta.next_index += 1;
+        meta.image_count += 1;
+        meta.stats = None;
+        meta.write(image_dir)?;
+    }
+
+    import.cover_thumb = meta.cover_thumb.clone();
+    Ok(import)
+}
+
+/// Stores the images of a folder in the entry folder. When an earlier run of the
+/// same import was interrupted, only the files it did not store yet are added.
+pub fn save_images_from_folder_with_thumbnails(
+    id: i64,
+    folder_path: &Path,
+    description: &str,
+    filter: &ImportFilter,
+) -> Result<FolderImport, Box<dyn std::error::Error>> {
+    let base_dir = get_data_dir();
+    let image_dir = base_dir.join("images").join(id.to_string());
+
+    if !image_dir.exists() {
+        fs::create_dir_all(&image_dir)?;
+    }
+
+    let files = source_images(folder_path)?;
+    let stem = stored_file_stem(id, description, chrono::Local::now().date_naive());
+
+    if image_dir.join(META_FILE).exists() {
+        let mut meta = FolderMeta::load(&image_dir)?;
+        info!("Resuming folder import for ID {} at image {}", id, meta.next_index);
+        return store_folder_images(&image_dir, &files, &stem, &mut meta, filter);
+    }
+
+    // The folder thumbnail is made from the first image stored
+    let mut meta = FolderMeta::new(&image_dir, Some(canonical_string(folder_path)));
+    meta.write(&image_dir)?;
+    store_folder_images(&image_dir, &files, &stem, &mut meta, filter)
+}
+
+/// Folder a folder entry was imported from, unknown for entries imported before it was recorded
+pub fn folder_entry_source(image_dir: &Path) -> Option<String> {
+    FolderMeta::read(image_dir).ok()?.source_folder
+}
+
+/// Whether the folder entry was imported from `folder_path`
+pub fn is_imported_from(image_dir: &Path, folder_path: &Path) -> bool {
+    folder_entry_source(image_dir).is_some_and(|source| source == canonical_string(folder_path))
+}
+
+/// Adds the images of `folder_path` that the folder entry does not hold yet
+pub fn add_new_folder_images(
+    id: i64,
+    image_dir: &Path,
+    folder_path: &Path,
+    description: &str,
+    filter: &ImportFilter,
+) -> Result<FolderImport, Box<dyn std::error::Error>> {
+    let files = source_images(folder_path)?;
+    add_files_to_folder_entry(id, image_dir, &files, description, filter)
+}
+
+/// Adds the given files to a folder entry, after the images it holds
+pub fn add_files_to_folder_entry(
+    id: i64,
+    image_dir: &Path,
+    files: &[PathBuf],
+    description: &str,
+    filter: &ImportFilter,
+) -> Result<FolderImport, Box<dyn std::error::Error>> {
+    let mut meta = FolderMeta::load(image_dir)?;
+    let stem = stored_file_stem(id, description, chrono::Local::now().date_naive());
+
+    let import = store_folder_images(image_dir, files, &stem, &mut meta, filter)?;
+    info!(
+        "Added {} images to folder entry {}, skipped {} already imported",
+        import.thumbnails.len(),
+        id,
+        import.skipped
+    );
+
+    Ok(import)
+}
+
+/// Outcome of [`repair_folder_entry`]
+#[derive(Debug, Clone, Default)]
+pub struct FolderRepair {
+    /// Whether the images were renumbered to follow their source folder
+    pub reordered: bool,
+    /// Thumbnails made again because they were missing
+    pub thumbnails: usize,
+    /// Thumbnail of the first image, empty while the entry holds none
+    pub cover_thumb: String,
+}
+
+/// Totals of the images of a folder entry. They are counted on the first call and
+/// kept in its meta, so later calls don't go over the files again until the
+/// images change. Entries whose meta can't be written are counted every time.
+pub fn folder_stats(image_dir: &Path) -> io::Result<FolderStats> {
+    let meta = FolderMeta::load(image_dir).ok();
+    if let Some(stats) = meta.as_ref().and_then(|meta| meta.stats.clone()) {
+        return Ok(stats);
+    }
+
+    let mut stats = FolderStats::default();
+    let mut first: Option<SystemTime> = None;
+    let mut last: Option<SystemTime> = None;
+    for (_, path) in stored_folder_images(image_dir)? {
+        let metadata = fs::metadata(&path)?;
+        stats.image_count += 1;
+        stats.total_size += metadata.len();
+        if let Ok(modified) = metadata.modified() {
+            first = Some(first.map_or(modified, |first| first.min(modified)));
+            last = Some(last.map_or(modified, |last| last.max(modified)));
+        }
+    }
+    let day = |time: SystemTime| {
+        chrono::DateTime::<chrono::Local>::from(time)
+            .format("%Y-%m-%d")
+            .to_string()
+    };
+    stats.first_date = first.map(day);
+    stats.last_date = last.map(day);
+
+    if let Some(mut meta) = meta {
+        meta.stats = Some(stats.clone());
+        if let Err(err) = meta.write(image_dir) {
+            warn!("Failed to keep the totals of {}: {}", image_dir.display(), err);
+        }
+    }
+    Ok(stats)
+}
+
+/// Drops the totals kept for a folder entry, once its images changed
+fn forget_folder_stats(image_dir: &Path) {
+    if let Ok(mut meta) = FolderMeta::read(image_dir)
+        && meta.stats.take().is_some()
+        && let Err(err) = meta.write(image_dir)
+    {
+        warn!("Failed to update the meta of {}: {}", image_dir.display(), err);
+    }
+}
+
+/// Images stored in a folder entry with their index, by index
+pub(crate) fn stored_folder_images(image_dir: &Path) -> io::Result<Vec<(usize, PathBuf)>> {
+    let mut images: Vec<(usize, PathBuf)> = fs::read_dir(image_dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_image_file(path))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            if is_derived(name) {
+                return None;
+            }
+            Some((folder_image_index(image_stem(name))?, path))
+        })
+        .collect();
+    images.sort_by_key(|(index, _)| *index);
+    Ok(images)
+}
+
+/// Place of every stored image in the natural order of its source folder, by the
+/// source hash recorded for its index. `None` when the source folder is gone.
+fn source_ranks(meta: &FolderMeta, images: &[(usize, PathBuf)]) -> Option<Vec<usize>> {
+    let files = source_images(Path::new(meta.source_folder.as_ref()?)).ok()?;
+    let mut ranks = HashMap::new();
+    for (rank, path) in files.iter().enumerate() {
+        if let Ok(bytes) = fs::read(path) {
+            ranks
+                .entry(blake3::hash(&bytes).to_hex().to_string())
+                .or_insert(rank);
+        }
+    }
+
+    Some(
+        images
+            .iter()
+            .map(|(index, _)| {
+                meta.source_hashes
+                    .get(*index)
+                    .and_then(|hash| ranks.get(hash))
+                    .copied()
+                    .unwrap_or(usize::MAX)
+            })
+            .collect(),
+    )
+}
+
+/// Brings a folder entry imported before imports used [`natural_order`] in line
+/// with its source folder: the images are renumbered, with their thumbnails and
+/// previews, to follow the natural order of the files they came from. Images whose
+/// source file is gone keep their place after the others. Missing thumbnails are
+/// made again. Entries without a source folder only get their thumbnails checked.
+pub fn repair_folder_entry(image_dir: &Path) -> Result<FolderRepair, Box<dyn std::error::Error>> {
+    let mut images = stored_folder_images(image_dir)?;
+    let mut meta = FolderMeta::load(image_dir).ok();
+    let mut repair = FolderRepair::default();
+
+    if let Some(meta) = meta.as_mut()
+        && let Some(ranks) = source_ranks(meta, &images)
+    {
+        let mut order: Vec<usize> = (0..images.len()).collect();
+        order.sort_by_key(|&position| (ranks[position], images[position].0));
+
+        if order.iter().enumerate().any(|(new, &old)| new != old) {
+            let reordered: Vec<(usize, PathBuf)> =
+                order.iter().map(|&old| images[old].clone()).collect();
+            images = renumber_folder_images(&reordered)?;
+
+            // The hash of each source file follows its image to the new index,
+            // those of removed images stay after them
+            let mut hashes: Vec<String> = reordered
+                .iter()
+                .filter_map(|(index, _)| meta.source_hashes.get(*index).cloned())
+                .collect();
+            let moved: HashSet<usize> = reordered.iter().map(|(index, _)| *index).collect();
+            hashes.extend(
+                meta.source_hashes
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !moved.contains(index))
+                    .map(|(_, hash)| hash.clone()),
+            );
+            meta.source_hashes = hashes;
+            repair.reordered = true;
+            info!("Renumbered the images of {} in natural order", image_dir.display());
+        }
+    }
+
+    for (_, path) in &images {
+        if thumbnail_path_for(path).is_some_and(|thumb| !thumb.exists()) {
+            regenerate_thumbnail(path)?;
+            repair.thumbnails += 1;
+        }
+    }
+
+    repair.cover_thumb = images
+        .first()
+        .and_then(|(_, path)| thumbnail_path_for(path))
+        .map(|thumb| thumb.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if let Some(mut meta) = meta
+        && (repair.reordered || meta.cover_thumb != repair.cover_thumb)
+    {
+        meta.cover_thumb = repair.cover_thumb.clone();
+        meta.write(image_dir)?;
+    }
+
+    Ok(repair)
+}
+
+/// Gives the images indexes `0..` in the given order. Files that change index are
+/// first moved aside so none is renamed onto another still waiting for its turn.
+fn renumber_folder_images(images: &[(usize, PathBuf)]) -> io::Result<Vec<(usize, PathBuf)>> {
+    let mut moved = Vec::new();
+    let mut renumbered = Vec::new();
+
+    for (new_index, (index, path)) in images.iter().enumerate() {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let base = image_stem(name).rsplit_once('_').map(|(base, _)| base).unwrap_or_default();
+        let stem = folder_image_stem(base, new_index);
+        if new_index == *index {
+            renumbered.push((new_index, path.clone()));
+            continue;
+        }
+
+        let (aside, _) = rename_stored_image(path, &format!("{}.renumber", stem))?;
+        moved.push((new_index, PathBuf::from(aside), stem));
+    }
+
+    for (new_index, aside, stem) in moved {
+        let (path, _) = rename_stored_image(&aside, &stem)?;
+        renumbered.push((new_index, PathBuf::from(path)));
+    }
+
+    renumbered.sort_by_key(|(index, _)| *index);
+    Ok(renumbered)
+}
+
+/// Regenerates the thumbnail of a stored image from its current content
+pub fn regenerate_thumbnail(image_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let thumb_path = thumbnail_path_for(image_path).ok_or("Invalid image path")?;
+    let image = thumbnail_renderer::render(image_path, PREVIEW_SIZE)?;
+
+    let thumb_compression = get_settings().config.thumb_compression.unwrap_or(9);
+    generate_thumbnail_from_image(&image, &thumb_path, THUMBNAIL_SIZE, THUMBNAIL_SIZE, thumb_compression)?;
+    save_preview_rendition(&image, image_path, thumb_compression)?;
+    info!("Regenerated thumbnail: {}", thumb_path.display());
+    // The file changed, so do the totals of the folder entry holding it
+    if let Some(parent) = image_path.parent() {
+        forget_folder_stats(parent);
+    }
+
+    Ok(thumb_path)
+}
+
+/// Whether the original was modified after its thumbnail was generated
+pub fn is_thumbnail_stale(image_path: &Path) -> bool {
+    let Some(thumb_path) = thumbnail_path_for(image_path) else {
+        return false;
+    };
+
+    match (modified_at(image_path), modified_at(&thumb_path)) {
+        (Some(original), Some(thumbnail)) => original > thumbnail,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Regenerates the outdated thumbnails among the given images in the background.
+/// Returns the paths of the images whose thumbnail was refreshed.
+pub async fn refresh_stale_thumbnails(paths: Vec<String>) -> Vec<String> {
+    let _job = job_service::start();
+    tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .filter(|path| {
+                let image_path = Path::new(path);
+                if !is_thumbnail_stale(image_path) {
+                    return false;
+                }
+                match regenerate_thumbnail(image_path) {
+                    Ok(_) => true,
+                    Err(e) => {
+                        warn!("Failed to refresh thumbnail of {}: {}", path, e);
+                        false
+                    }
+                }
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Removes the thumbnails and previews of an entry folder whose image is gone,
+/// and the previews older than their image, made again when next opened.
+/// Files in `keep` are left alone. Returns how many files were removed.
+pub fn prune_derived_files(image_dir: &Path, keep: &HashSet<String>) -> io::Result<usize> {
+    let paths: Vec<PathBuf> = fs::read_dir(image_dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    let originals: HashSet<String> = paths
+        .iter()
+        .filter(|path| is_image_file(path))
+        .filter_map(|path| path.file_name()?.to_str())
+        .filter(|name| !is_derived(name))
+        .map(|name| image_stem(name).to_string())
+        .collect();
+
+    let mut removed = 0;
+    for path in &paths {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if keep.contains(&path.to_string_lossy().to_string()) {
+            continue;
+        }
+        let (stem, is_preview) = match parse_derived(name) {
+            Some(DerivedFile::Thumbnail(stem)) => (stem, false),
+            Some(DerivedFile::Preview(stem)) => (stem, true),
+            Some(DerivedFile::FolderThumbnail) | None => continue,
+        };
+
+        let outdated = !originals.contains(stem)
+            || (is_preview
+                && paths
+                    .iter()
+                    .find(|original| preview_path_for(original).as_deref() == Some(path.as_path()))
+                    .is_some_and(|original| !is_derived_current(original, path)));
+        if outdated {
+            fs::remove_file(path)?;
+            debug!("Pruned {}", path.display());
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+// ===================================
+//         DELETION FUNCTIONS
+// ===================================
+
+/// Deletes the files of an image, the originals as `behavior` says
+pub async fn delete_image(
+    path: &str,
+    image_type: ImageType,
+    behavior: DeleteBehavior,
+) -> Result<(), io::Error> {
+    let image_path = Path::new(path);
+    info!("Deleting {:?} at {}", image_type, image_path.display());
+
+    if !image_path.exists() {
+        warn!("Path does not exist: {}", image_path.display());
+        return Err(io::Error::new(io::ErrorKind::NotFound, "Path does not exist"));
+    }
+
+    match image_type {
+        ImageType::FromFolder => {
+            delete_single_file_with_thumbnail(path, behavior).await?;
+            if let Some(parent) = image_path.parent() {
+                forget_folder_stats(parent);
+            }
+
+            // Only thumbnails are left in the folder
+            if let Some(parent) = image_path.parent()
+                && count_image_files_in_folder(parent)? == 0
+            {
+                delete_entire_folder(parent, DeleteBehavior::Permanent).await?;
+            }
+            Ok(())
+        }
+        ImageType::Image => {
+            delete_single_file_with_thumbnail(path, behavior).await?;
+
+            if let Some(parent) = image_path.parent() {
+                delete_entire_folder(parent, DeleteBehavior::Permanent).await?;
+            }
+            Ok(())
+        }
+        ImageType::Folder => delete_entire_folder(image_path, behavior).await,
+    }
+}
+
+async fn delete_single_file_with_thumbnail(
+    path: &str,
+    behavior: DeleteBehavior,
+) -> Result<(), io::Error> {
+    let image_path = Path::new(path);
+    if image_path.exists() {
+        discard(image_path, behavior)?;
+        info!("Deleted file: {}", image_path.display());
+
+        if let Some(thumb_path) = thumbnail_path_for(image_path)
+            && thumb_path.exists()
+        {
+            fs::remove_file(&thumb_path)?;
+            info!("Deleted thumbnail: {}", thumb_path.display());
+        }
+
+        if let Some(preview_path) = preview_path_for(image_path)
+            && preview_path.exists()
+        {
+            fs::remove_file(&preview_path)?;
+        }
+    } else {
+        debug!("File does not exist: {}", image_path.display());
+    }
+    Ok(())
+}
+
+async fn delete_entire_folder(folder_path: &Path, behavior: DeleteBehavior) -> Result<(), io::Error> {
+    if !folder_path.exists() {
+        warn!("Folder does not exist: {}", folder_path.display());
+        return Ok(());
+    }
+    if folder_path.file_name().and_then(|n| n.to_str()) == Some("images") {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Cannot delete root images folder"));
+    }
+    discard_entry_dir(folder_path, behavior)?;
+    info!("Deleted folder: {}", folder_path.display());
+    Ok(())
+}
+
+/// Removes a file or folder, or sends it to the recycle bin
+pub fn discard(path: &Path, behavior: DeleteBehavior) -> io::Result<()> {
+    match behavior {
+        DeleteBehavior::RecycleBin => trash::delete(path).map_err(io::Error::other),
+        DeleteBehavior::Permanent if path.is_dir() => fs::remove_dir_all(path),
+        DeleteBehavior::Permanent => fs::remove_file(path),
+    }
+}
+
+/// Removes the folder of an entry. Thumbnails and previews are always removed,
+/// the rest goes as `behavior` says.
+fn discard_entry_dir(dir: &Path, behavior: DeleteBehavior) -> io::Result<()> {
+    if behavior == DeleteBehavior::RecycleBin {
+        let originals: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_dir()
+                    || !path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|name| parse_derived(name).is_some() || name == META_FILE)
+            })
+            .collect();
+        if !originals.is_empty() {
+            trash::delete_all(&originals).map_err(io::Error::other)?;
+        }
+    }
+    fs::remove_dir_all(dir)
+}
+
+/// Counts every file under the folder, thumbnails and previews included, with
+/// the bytes they take
+pub fn dir_contents(dir: &Path) -> io::Result<DirContents> {
+    let mut contents = DirContents::default();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                contents.files += 1;
+                contents.bytes += metadata.len();
+            }
+        }
+    }
+    Ok(contents)
+}
+
+// ===================================
+//         TRASH FUNCTIONS
+// ===================================
+
+/// Folder the files of an entry are kept in while it is in the trash
+pub fn trash_dir(image_id: i64) -> PathBuf {
+    get_data_dir().join("trash").join(image_id.to_string())
+}
+
+/// Where a stored file of the entry is while the entry is in the trash.
+/// Files outside of the entry's folder are left where they are.
+pub fn trashed_path(image_id: i64, path: &str) -> PathBuf {
+    let image_dir = get_data_dir().join("images").join(image_id.to_string());
+    match Path::new(path).strip_prefix(&image_dir) {
+        Ok(relative) => trash_dir(image_id).join(relative),
+        Err(_) => PathBuf::from(path),
+    }
+}
+
+/// Moves the folder of an entry, with its thumbnails and previews, to the trash
+pub fn move_to_trash(image_id: i64) -> io::Result<()> {
+    let image_dir = get_data_dir().join("images").join(image_id.to_string());
+    let target = trash_dir(image_id);
+    move_entry_dir(&image_dir, &target)?;
+    info!("Moved {} to the trash", image_dir.display());
+    Ok(())
+}
+
+/// Moves the folder of an entry back from the trash
+pub fn restore_from_trash(image_id: i64) -> io::Result<()> {
+    let image_dir = get_data_dir().join("images").join(image_id.to_string());
+    move_entry_dir(&trash_dir(image_id), &image_dir)?;
+    info!("Restored {} from the trash", image_dir.display());
+    Ok(())
+}
+
+/// Deletes the files an entry left in the trash, the originals as `behavior` says
+pub fn purge_from_trash(image_id: i64, behavior: DeleteBehavior) -> io::Result<()> {
+    let dir = trash_dir(image_id);
+    if dir.exists() {
+        discard_entry_dir(&dir, behavior)?;
+        info!("Purged {}", dir.display());
+    }
+    Ok(())
+}
+
+/// Moves an entry folder without replacing one already at the target.
+/// An entry without a folder has nothing to move.
+fn move_entry_dir(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.exists() {
+        warn!("Folder does not exist: {}", from.display());
+        return Ok(());
+    }
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(from, to)
+}
+
+
+// ===================================
+//         RENAMING FUNCTIONS
+// ===================================
+
+/// Renames a single stored image and its thumbnail to the given stem.
+/// Returns the new image and thumbnail paths.
+pub fn rename_stored_image(image_path: &Path, stem: &str) -> io::Result<(String, String)> {
+    let extension = image_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png");
+    let new_path = image_path.with_file_name(image_file_name(stem, extension));
+    let new_thumb = image_path.with_file_name(thumbnail_name(stem));
+
+    // Checked up front, so a taken name leaves every file where it was
+    if let Some(taken) = [&new_path, &new_thumb]
+        .into_iter()
+        .chain(preview_path_for(&new_path).as_ref())
+        .find(|target| target.exists() && !same_file_name(target, image_path))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("Target already exists: {}", taken.display()),
+        ));
+    }
+
+    rename_if_free(image_path, &new_path)?;
+    if let Some(old_thumb) = thumbnail_path_for(image_path)
+        && old_thumb.exists()
+    {
+        rename_if_free(&old_thumb, &new_thumb)?;
+    }
+    if let Some(old_preview) = preview_path_for(image_path)
+        && let Some(new_preview) = preview_path_for(&new_path)
+        && old_preview.exists()
+    {
+        rename_if_free(&old_preview, &new_preview)?;
+    }
+
+    Ok((
+        new_path.to_string_lossy().to_string(),
+        new_thumb.to_string_lossy().to_string(),
+    ))
+}
+
+/// Renames every image of a stored folder to `{stem}_{index}`, keeping the index
+/// suffix of each file. Returns the old and new thumbnail paths of renamed files.
+pub fn rename_folder_images(folder_path: &Path, stem: &str) -> io::Result<Vec<(String, String)>> {
+    let mut renamed_thumbs = Vec::new();
+
+    for entry in fs::read_dir(folder_path)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !path.is_file() || !is_image_file(&path) || is_derived(file_name) {
+            continue;
+        }
+
+        let file_stem = image_stem(file_name);
+        let Some(index) = folder_image_index(file_stem) else {
+            warn!("Skipping file without index suffix: {}", path.display());
+            continue;
+        };
+
+        let new_stem = folder_image_stem(stem, index);
+        if new_stem == file_stem {
+            continue;
+        }
+
+        let old_thumb = thumbnail_path_for(&path);
+        let (_, new_thumb) = rename_stored_image(&path, &new_stem)?;
+        if let Some(old_thumb) = old_thumb {
+            renamed_thumbs.push((old_thumb.to_string_lossy().to_string(), new_thumb));
+        }
+    }
+
+    Ok(renamed_thumbs)
+}
+
+/// Whether the target is one of the files of the image being renamed, which frees
+/// it on the way
+fn same_file_name(target: &Path, image_path: &Path) -> bool {
+    target == image_path
+        || thumbnail_path_for(image_path).as_deref() == Some(target)
+        || preview_path_for(image_path).as_deref() == Some(target)
+}
+
+fn rename_if_free(from: &Path, to: &Path) -> io::Result<()> {
+    if from == to {
+        return Ok(());
+    }
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("Target already exists: {}", to.display()),
+        ));
+    }
+    fs::rename(from, to)?;
+    debug!("Renamed {} to {}", from.display(), to.display());
+    Ok(())
+}
+
+// ===================================
+//         GROUPING FUNCTIONS
+// ===================================
+
+/// Standalone images moved into a folder entry by [`move_into_folder_entry`]
+#[derive(Debug)]
+pub struct MovedImages {
+    /// The entry folder, with the thumbnail of its first image
+    pub import: FolderImport,
+    /// Every file moved, from where to where
+    moves: Vec<(PathBuf, PathBuf)>,
+}
+
+impl MovedImages {
+    /// Puts the files back where they were and removes the entry folder
+    pub fn undo(&self) {
+        undo_moves(&self.moves);
+        let _ = fs::remove_dir_all(&self.import.image_dir);
+    }
+
+    /// Removes the folders the images were moved out of, once nothing refers to them
+    pub fn remove_sources(&self) {
+        let sources: HashSet<&Path> = self
+            .moves
+            .iter()
+            .filter_map(|(from, _)| from.parent())
+            .collect();
+        for source in sources {
+            // Like `delete_entire_folder`, the root of the library is never removed
+            if source.file_name().and_then(|n| n.to_str()) == Some("images") {
+                continue;
+            }
+            if let Err(err) = fs::remove_dir_all(source) {
+                warn!("Failed to remove {}: {}", source.display(), err);
+            }
+        }
+    }
+}
+
+fn undo_moves(moves: &[(PathBuf, PathBuf)]) {
+    for (from, to) in moves.iter().rev() {
+        if let Err(err) = fs::rename(to, from) {
+            warn!("Failed to move {} back: {}", to.display(), err);
+        }
+    }
+}
+
+/// Moves stored standalone images, with their thumbnails and previews, into the
+/// folder entry at `image_dir` as its images `{stem}_0..` in the given order, and
+/// writes its meta. When a file can't be moved the ones already moved go back.
+pub fn move_into_folder_entry(
+    image_dir: &Path,
+    images: &[PathBuf],
+    stem: &str,
+) -> Result<MovedImages, Box<dyn std::error::Error>> {
+    fs::create_dir_all(image_dir)?;
+    let mut moves = Vec::new();
+    match move_images(image_dir, images, stem, &mut moves) {
+        Ok(import) => Ok(MovedImages { import, moves }),
+        Err(err) => {
+            undo_moves(&moves);
+            let _ = fs::remove_dir_all(image_dir);
+            Err(err)
+        }
+    }
+}
+
+fn move_images(
+    image_dir: &Path,
+    images: &[PathBuf],
+    stem: &str,
+    moves: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<FolderImport, Box<dyn std::error::Error>> {
+    let mut meta = FolderMeta::new(image_dir, None);
+    let mut import = FolderImport {
+        image_dir: image_dir.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+
+    for (index, path) in images.iter().enumerate() {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("png");
+        let image_stem = folder_image_stem(stem, index);
+        let image_path = image_dir.join(image_file_name(&image_stem, extension));
+        let hash = blake3::hash(&fs::read(path)?).to_hex().to_string();
+
+        rename_if_free(path, &image_path)?;
+        moves.push((path.clone(), image_path.clone()));
+        let derived = [
+            (thumbnail_path_for(path), thumbnail_path_for(&image_path)),
+            (preview_path_for(path), preview_path_for(&image_path)),
+        ];
+        for (from, to) in derived {
+            if let (Some(from), Some(to)) = (from, to)
+                && from.exists()
+            {
+                rename_if_free(&from, &to)?;
+                moves.push((from, to));
+            }
+        }
+
+        let thumb_path = image_dir.join(thumbnail_name(&image_stem));
+        if !thumb_path.exists() {
+            regenerate_thumbnail(&image_path)?;
+        }
+        import.thumbnails.push(thumb_path.to_string_lossy().to_string());
+        meta.source_hashes.push(hash);
+    }
+
+    if let Some(cover) = import.thumbnails.first() {
+        fs::copy(cover, &meta.folder_thumb)?;
+        meta.cover_thumb = cover.clone();
+    }
+    meta.image_count = images.len();
+    meta.next_index = images.len();
+    meta.write(image_dir)?;
+
+    import.cover_thumb = meta.cover_thumb;
+    Ok(import)
+}
+
+/// Images of a folder entry moved out into standalone entries by [`move_out_of_folder_entry`]
+#[derive(Debug)]
+pub struct SplitImages {
+    /// Path and thumbnail of every image moved out, in the given order
+    pub images: Vec<(String, String)>,
+    /// Every file moved, from where to where
+    moves: Vec<(PathBuf, PathBuf)>,
+    folder_dir: PathBuf,
+}
+
+impl SplitImages {
+    /// Puts the images back into the folder entry and removes their new folders
+    pub fn undo(&self) {
+        undo_moves(&self.moves);
+        for (_, to) in &self.moves {
+            if let Some(dir) = to.parent() {
+                let _ = fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    /// Removes the folder entry the images were moved out of, with what is left in it
+    pub fn remove_source(&self) {
+        if let Err(err) = fs::remove_dir_all(&self.folder_dir) {
+            warn!("Failed to remove {}: {}", self.folder_dir.display(), err);
+        }
+    }
+}
+
+/// Images stored in a folder entry, by index
+pub fn folder_entry_images(image_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    Ok(stored_folder_images(image_dir)?
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect())
+}
+
+/// Moves the images of the folder entry at `folder_dir` out to standalone folders,
+/// each given as the image, the folder it goes to and its new stem. Their thumbnails
+/// and previews are made again next to them. When an image can't be moved the ones
+/// already moved go back.
+pub fn move_out_of_folder_entry(
+    folder_dir: &Path,
+    images: &[(PathBuf, PathBuf, String)],
+) -> Result<SplitImages, Box<dyn std::error::Error>> {
+    let mut split = SplitImages {
+        images: Vec::new(),
+        moves: Vec::new(),
+        folder_dir: folder_dir.to_path_buf(),
+    };
+    for (path, image_dir, stem) in images {
+        let moved = move_out_image(path, image_dir, stem, &mut split.moves);
+        match moved {
+            Ok(image) => split.images.push(image),
+            Err(err) => {
+                split.undo();
+                let _ = fs::remove_dir_all(image_dir);
+                return Err(err);
+            }
+        }
+    }
+    Ok(split)
+}
+
+fn move_out_image(
+    path: &Path,
+    image_dir: &Path,
+    stem: &str,
+    moves: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(String, String), Box<dyn std::error::Error>> {
+    fs::create_dir_all(image_dir)?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("png");
+    let image_path = image_dir.join(image_file_name(stem, extension));
+    rename_if_free(path, &image_path)?;
+    moves.push((path.to_path_buf(), image_path.clone()));
+
+    let thumb_path = regenerate_thumbnail(&image_path)?;
+    Ok((
+        image_path.to_string_lossy().to_string(),
+        thumb_path.to_string_lossy().to_string(),
+    ))
+}
+
+// ===================================
+//         OTHER UTILITY FUNCTIONS
+// ===================================
+
+pub fn open_in_file_explorer(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Path does not exist",
+        ));
+    }
+
+    if cfg!(target_os = "windows") {
+        Command::new("explorer").arg(path).spawn()?;
+    } else if cfg!(target_os = "linux") {
+        Command::new("xdg-open").arg(path).spawn()?;
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(path).spawn()?;
+    } else {
+        return Err(io::Error::other("Unsupported OS"));
+    }
+
+    Ok(())
+}
+
+pub fn open_url(url: &str) -> io::Result<()> {
+    if cfg!(target_os = "windows") {
+        Command::new("rundll32")
+            .args(["url.dll,FileProtocolHandler", url])
+            .spawn()?;
+    } else if cfg!(target_os = "linux") {
+        Command::new("xdg-open").arg(url).spawn()?;
+    } else if cfg!(target_os = "macos") {
+        Command::new("open").arg(url).spawn()?;
+    } else {
+        return Err(io::Error::other("Unsupported OS"));
+    }
+
+    Ok(())
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Whether a file generated from `original` exists and is not older than it
+fn is_derived_current(original: &Path, derived: &Path) -> bool {
+    matches!(
+        (modified_at(original), modified_at(derived)),
+        (Some(original), Some(derived)) if derived >= original
+    )
+}
+
+fn editor_command(path: &Path) -> io::Result<Command> {
+    let path_str = path.to_string_lossy().to_string();
+    let template = get_settings()
+        .config
+        .external_editor
+        .clone()
+        .filter(|c| !c.trim().is_empty());
+
+    let Some(template) = template else {
+        // No editor configured, fall back to the system default application
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", "/WAIT", ""]);
+            command
+        } else if cfg!(target_os = "macos") {
+            let mut command = Command::new("open");
+            command.arg("-W");
+            command
+        } else {
+            Command::new("xdg-open")
+        };
+        command.arg(&path_str);
+        return Ok(command);
+    };
+
+    let mut parts = template.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Empty editor command"))?;
+    let mut args: Vec<String> = parts.map(|a| a.replace("{path}", &path_str)).collect();
+    if !template.contains("{path}") {
+        args.push(path_str);
+    }
+
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+/// Opens an image in the configured external editor and waits for it to be saved.
+/// Returns whether the file changed, in which case its thumbnail was regenerated.
+pub async fn edit_in_external_program(path: String) -> Result<bool, String> {
+    /// Editors exiting faster than this most likely handed the file to another process
+    const DETACHED_THRESHOLD: Duration = Duration::from_secs(3);
+    /// How long to keep watching a file opened by a detached editor
+    const DETACHED_WATCH: Duration = Duration::from_secs(30 * 60);
+
+    let image_path = PathBuf::from(&path);
+    let before = modified_at(&image_path);
+    let mut child = editor_command(&image_path)
+        .and_then(|mut c| c.spawn())
+        .map_err(|e| e.to_string())?;
+
+    let started = Instant::now();
+    tokio::task::spawn_blocking(move || child.wait())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let mut changed = modified_at(&image_path) != before;
+    if !changed && started.elapsed() < DETACHED_THRESHOLD {
+        let deadline = Instant::now() + DETACHED_WATCH;
+        while !changed && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            changed = modified_at(&image_path) != before;
+        }
+    }
+
+    if changed {
+        // Give the editor a moment to finish writing before reading the file back
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        regenerate_thumbnail(&image_path).map_err(|e| e.to_string())?;
+    }
+
+    Ok(changed)
+}
+
+/// Whether a thumbnail renderer reads the file, so it can be stored in the library
+pub fn is_image_file(path: &Path) -> bool {
+    renderer_for(path).is_some()
+}
+
+pub fn expand_folder_dto(image_dto: &ImageDTO) -> Vec<ImageDTO> {
+    let folder_path = Path::new(&image_dto.path);
+    if !folder_path.is_dir() {
+        return vec![];
+    }
+
+    let entries = match fs::read_dir(folder_path) {
+        Ok(e) => e,
+        Err(_) => return vec![],
+    };
+
+    let mut files: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.is_file()
+                && let Some(filename) = path.file_name().and_then(|f| f.to_str())
+                && is_image_file(&path)
+                && !is_derived(filename)
+            {
+                return Some((filename.to_string(), path));
+            }
+            None
+        })
+        .collect();
+
+    files.sort_by(|a, b| natural_order(&a.0, &b.0));
+
+    let mut dtos = Vec::new();
+    for (index, (_, path)) in files.into_iter().enumerate() {
+        let thumb_path = thumbnail_path_for(&path).unwrap_or_default();
+
+        let dto = ImageDTO {
+            id: index as i64,
+            path: path.to_string_lossy().to_string(),
+            thumbnail_path: thumb_path.to_string_lossy().to_string(),
+            description: image_dto.description.clone(),
+            tags: image_dto.tags.clone(),
+            created_at: image_dto.created_at.clone(),
+            is_folder: false,
+            is_prepared: true,
+            source_url: image_dto.source_url.clone(),
+            is_pinned: false,
+            stack_id: None,
+            stack_size: 0,
+            language: image_dto.language.clone(),
+            matches: image_dto.matches.clone(),
+        };
+
+        dtos.push(dto);
+    }
+    dtos
+}
+
+/// Images stored in a folder entry, its derived files left out
+pub fn count_image_files_in_folder(folder_path: &Path) -> Result<usize, io::Error> {
+    if !folder_path.exists() || !folder_path.is_dir() {
+        return Ok(0);
+    }
+
+    let mut count = 0;
+
+    for entry in fs::read_dir(folder_path)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file()
+            && let Some(file_name) = path.file_name().and_then(|n| n.to_str())
+            && is_image_file(&path)
+            && !is_derived(file_name)
+            && file_name != META_FILE
+        {
+            count += 1;
+        }
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,200 @@
+//! `meta.json`, the bookkeeping a folder entry keeps next to its images.
+//!
+//! The file carries the version of its layout. Files of an older version are
+//! upgraded when read, and a file that is missing or can't be used is made
+//! again from the images the entry holds, through [`FolderMeta::load`].
+
+use crate::services::atomic_file;
+use crate::services::file_naming::{FOLDER_THUMBNAIL, META_FILE, thumbnail_path_for};
+use crate::services::file_service::{FolderStats, stored_folder_images};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Version of the layout this build writes. Files without one are version 0,
+/// written before the layout was versioned.
+pub const META_VERSION: u32 = 1;
+
+/// Why the `meta.json` of a folder entry can't be used as it is
+#[derive(Debug)]
+pub enum MetaError {
+    Missing,
+    /// Not valid JSON, or values that don't add up
+    Corrupt(String),
+    /// Written by a newer version of the app, left untouched
+    Newer(u32),
+    Io(io::Error),
+}
+
+impl fmt::Display for MetaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetaError::Missing => write!(f, "{} is missing", META_FILE),
+            MetaError::Corrupt(reason) => write!(f, "{} is corrupt: {}", META_FILE, reason),
+            MetaError::Newer(version) => write!(
+                f,
+                "{} has version {}, this build reads up to {}",
+                META_FILE, version, META_VERSION
+            ),
+            MetaError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for MetaError {}
+
+/// Bookkeeping of a folder entry, stored as `meta.json` next to its images
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct FolderMeta {
+    /// Layout of the file, see [`META_VERSION`]
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub image_count: usize,
+    /// Index the next image added gets
+    #[serde(default)]
+    pub next_index: usize,
+    #[serde(default)]
+    pub folder_thumb: String,
+    /// Thumbnail of the first stored image, shown for the entry
+    #[serde(default)]
+    pub cover_thumb: String,
+    /// Folder the images were imported from
+    #[serde(default)]
+    pub source_folder: Option<String>,
+    /// Hashes of the imported files as read from the source, to skip them on re-import
+    #[serde(default)]
+    pub source_hashes: Vec<String>,
+    /// Totals of the stored images, dropped whenever they change
+    #[serde(default)]
+    pub stats: Option<FolderStats>,
+}
+
+impl FolderMeta {
+    /// Meta of an entry holding no images yet
+    pub fn new(image_dir: &Path, source_folder: Option<String>) -> Self {
+        Self {
+            version: META_VERSION,
+            image_count: 0,
+            next_index: 0,
+            folder_thumb: image_dir
+                .join(FOLDER_THUMBNAIL)
+                .to_string_lossy()
+                .to_string(),
+            cover_thumb: String::new(),
+            source_folder,
+            source_hashes: Vec::new(),
+            stats: None,
+        }
+    }
+
+    /// Reads the meta of an entry, upgrading it to the current version first
+    pub fn read(image_dir: &Path) -> Result<Self, MetaError> {
+        let content =
+            fs::read_to_string(image_dir.join(META_FILE)).map_err(|err| match err.kind() {
+                io::ErrorKind::NotFound => MetaError::Missing,
+                _ => MetaError::Io(err),
+            })?;
+        let value: serde_json::Value =
+            serde_json::from_str(&content).map_err(|err| MetaError::Corrupt(err.to_string()))?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0);
+        if version > META_VERSION as u64 {
+            return Err(MetaError::Newer(version as u32));
+        }
+
+        let mut meta: FolderMeta =
+            serde_json::from_value(value).map_err(|err| MetaError::Corrupt(err.to_string()))?;
+        meta.validate().map_err(MetaError::Corrupt)?;
+        if meta.version < META_VERSION {
+            meta.upgrade(image_dir);
+            meta.write(image_dir).map_err(MetaError::Io)?;
+        }
+        Ok(meta)
+    }
+
+    /// Reads the meta of an entry, making it again from the stored images when it
+    /// is missing or corrupt. The source folder and hashes of a corrupt file are
+    /// kept when they can still be read.
+    pub fn load(image_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match Self::read(image_dir) {
+            Ok(meta) => Ok(meta),
+            Err(err @ (MetaError::Newer(_) | MetaError::Io(_))) => Err(err.into()),
+            Err(err) => {
+                warn!("Rebuilding the meta of {}: {}", image_dir.display(), err);
+                let previous = fs::read_to_string(image_dir.join(META_FILE))
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<FolderMeta>(&content).ok());
+                let meta = Self::regenerate(image_dir, previous)?;
+                meta.write(image_dir)?;
+                Ok(meta)
+            }
+        }
+    }
+
+    pub fn write(&self, image_dir: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        atomic_file::write(&image_dir.join(META_FILE), content)
+    }
+
+    /// Checks the values depend on each other the way the imports write them
+    fn validate(&self) -> Result<(), String> {
+        if self.next_index < self.image_count {
+            return Err(format!(
+                "next index {} is below the image count {}",
+                self.next_index, self.image_count
+            ));
+        }
+        if self.source_hashes.len() > self.next_index {
+            return Err(format!(
+                "{} source hashes for {} images",
+                self.source_hashes.len(),
+                self.next_index
+            ));
+        }
+        Ok(())
+    }
+
+    /// Brings a meta of an older version to the current one
+    fn upgrade(&mut self, image_dir: &Path) {
+        // Version 0 could be written without the folder thumbnail
+        if self.version < 1 && self.folder_thumb.is_empty() {
+            self.folder_thumb = image_dir
+                .join(FOLDER_THUMBNAIL)
+                .to_string_lossy()
+                .to_string();
+        }
+        info!(
+            "Upgraded the meta of {} from version {} to {}",
+            image_dir.display(),
+            self.version,
+            META_VERSION
+        );
+        self.version = META_VERSION;
+    }
+
+    /// Meta made from the images the entry holds
+    fn regenerate(image_dir: &Path, previous: Option<FolderMeta>) -> io::Result<Self> {
+        let images = stored_folder_images(image_dir)?;
+        let mut meta = Self::new(image_dir, None);
+        meta.image_count = images.len();
+        meta.next_index = images.last().map_or(0, |(index, _)| index + 1);
+        meta.cover_thumb = images
+            .first()
+            .and_then(|(_, path)| thumbnail_path_for(path))
+            .filter(|thumb| thumb.exists())
+            .map(|thumb| thumb.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if let Some(mut previous) = previous {
+            previous.source_hashes.truncate(meta.next_index);
+            meta.source_folder = previous.source_folder;
+            meta.source_hashes = previous.source_hashes;
+        }
+        Ok(meta)
+    }
+}
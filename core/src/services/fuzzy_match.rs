@@ -0,0 +1,110 @@
+//! Typo tolerant matching of a search query against descriptions. Each word of the
+//! query is compared with the words of the description, allowing a few edits
+//! (insertions, deletions, substitutions and swapped letters) depending on its
+//! length. As in the exact search, `+` separates alternatives.
+
+use crate::services::desc_query::word_spans;
+use std::ops::Range;
+use strsim::damerau_levenshtein;
+
+/// Edits a query word of this many characters may need to match
+fn allowed_edits(len: usize) -> usize {
+    match len {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// How close the text comes to the query, from 0 to 1 with 1 for an exact match.
+/// None when some word of the query has nothing close enough in the text.
+pub fn score(query: &str, text: &str) -> Option<f64> {
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    query
+        .split('+')
+        .filter_map(|alternative| score_words(&alternative.to_lowercase(), &text, &words))
+        .max_by(f64::total_cmp)
+}
+
+/// Byte ranges of the words of the text coming close to the query, for
+/// highlighting. Only alternatives of the query the text matches count.
+pub fn matched_ranges(query: &str, text: &str) -> Vec<Range<usize>> {
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let spans = word_spans(text);
+
+    let mut ranges = Vec::new();
+    for alternative in query.split('+') {
+        let alternative = alternative.to_lowercase();
+        if score_words(&alternative, &lowercase, &words).is_none() {
+            continue;
+        }
+        let terms = split_terms(&alternative);
+        ranges.extend(
+            spans
+                .iter()
+                .filter(|span| {
+                    let word = text[(*span).clone()].to_lowercase();
+                    terms
+                        .iter()
+                        .any(|term| word_edits(term, &word) <= allowed_edits(term.chars().count()))
+                })
+                .cloned(),
+        );
+    }
+    ranges
+}
+
+fn split_terms(query: &str) -> Vec<&str> {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// Mean closeness of the query words, each to its closest word of the text
+fn score_words(query: &str, text: &str, words: &[&str]) -> Option<f64> {
+    let terms = split_terms(query);
+    if terms.is_empty() {
+        return None;
+    }
+
+    let mut total = 0.0;
+    for term in &terms {
+        total += score_term(term, text, words)?;
+    }
+    Some(total / terms.len() as f64)
+}
+
+fn score_term(term: &str, text: &str, words: &[&str]) -> Option<f64> {
+    if text.contains(term) {
+        return Some(1.0);
+    }
+    let len = term.chars().count();
+    let allowed = allowed_edits(len);
+    words
+        .iter()
+        .map(|word| word_edits(term, word))
+        .filter(|edits| *edits <= allowed)
+        .min()
+        .map(|edits| 1.0 - edits as f64 / (len + 1) as f64)
+}
+
+/// Edits turning the word into the term, none when the word contains it
+fn word_edits(term: &str, word: &str) -> usize {
+    if word.contains(term) {
+        return 0;
+    }
+    // A word longer than the term is also compared by its start, so a typo in a
+    // partly typed word still finds it
+    let start: String = word.chars().take(term.chars().count()).collect();
+    damerau_levenshtein(term, word).min(damerau_levenshtein(term, &start))
+}
@@ -0,0 +1,421 @@
+//! Image processing as a pipeline: a picture is decoded into a [`Frame`], passed
+//! through the [`Step`]s of a [`Pipeline`] in order (orient, resize, convert the
+//! colors...) and written by its encoder. Each step carries its own settings, so
+//! new processing such as sharpening is one more `Step` given to the pipeline.
+
+use crate::services::atomic_file;
+use fast_image_resize as fr;
+use fast_image_resize::images::Image;
+use image::metadata::Orientation;
+use image::{ColorType, DynamicImage, ImageDecoder, ImageReader};
+use log::{debug, info};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+use std::time::Instant;
+
+// ===================================
+//         FRAMES
+// ===================================
+
+/// Picture going through a pipeline. Borrowed until a step changes it.
+pub struct Frame<'a> {
+    pub image: Cow<'a, DynamicImage>,
+    /// Rotation and flip the source asks for, not applied yet
+    pub orientation: Orientation,
+}
+
+impl<'a> Frame<'a> {
+    pub fn borrowed(image: &'a DynamicImage) -> Self {
+        Self {
+            image: Cow::Borrowed(image),
+            orientation: Orientation::NoTransforms,
+        }
+    }
+
+    pub fn into_image(self) -> DynamicImage {
+        self.image.into_owned()
+    }
+}
+
+impl From<DynamicImage> for Frame<'static> {
+    fn from(image: DynamicImage) -> Self {
+        Self {
+            image: Cow::Owned(image),
+            orientation: Orientation::NoTransforms,
+        }
+    }
+}
+
+/// Decodes an encoded image, keeping the orientation its metadata asks for
+pub fn decode(bytes: &[u8]) -> Result<Frame<'static>, Box<dyn Error>> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let image = DynamicImage::from_decoder(decoder)?;
+
+    Ok(Frame {
+        image: Cow::Owned(image),
+        orientation,
+    })
+}
+
+// ===================================
+//         STEPS
+// ===================================
+
+/// One stage of a pipeline
+pub trait Step: Send + Sync {
+    /// Name shown in the timings of the log
+    fn name(&self) -> &'static str;
+
+    fn apply<'a>(&self, frame: Frame<'a>) -> Result<Frame<'a>, Box<dyn Error>>;
+}
+
+/// Turns the picture upright as the orientation of its source asks
+pub struct Orient;
+
+impl Step for Orient {
+    fn name(&self) -> &'static str {
+        "orient"
+    }
+
+    fn apply<'a>(&self, frame: Frame<'a>) -> Result<Frame<'a>, Box<dyn Error>> {
+        if frame.orientation == Orientation::NoTransforms {
+            return Ok(frame);
+        }
+        let mut image = frame.image.into_owned();
+        image.apply_orientation(frame.orientation);
+        Ok(Frame::from(image))
+    }
+}
+
+/// Filter a [`Resize`] samples with, from the fastest to the sharpest
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ResizeFilter {
+    Nearest,
+    Bilinear,
+    CatmullRom,
+    #[default]
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    pub const ALL: [ResizeFilter; 4] = [
+        ResizeFilter::Nearest,
+        ResizeFilter::Bilinear,
+        ResizeFilter::CatmullRom,
+        ResizeFilter::Lanczos3,
+    ];
+
+    fn algorithm(self) -> fr::ResizeAlg {
+        match self {
+            ResizeFilter::Nearest => fr::ResizeAlg::Nearest,
+            ResizeFilter::Bilinear => fr::ResizeAlg::Convolution(fr::FilterType::Bilinear),
+            ResizeFilter::CatmullRom => fr::ResizeAlg::Convolution(fr::FilterType::CatmullRom),
+            ResizeFilter::Lanczos3 => fr::ResizeAlg::Convolution(fr::FilterType::Lanczos3),
+        }
+    }
+}
+
+/// Scales the picture to fit in a box, keeping its aspect ratio. The result is
+/// 8-bit RGBA.
+pub struct Resize {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub filter: ResizeFilter,
+    /// Whether pictures smaller than the box are scaled up to it
+    pub enlarge: bool,
+}
+
+impl Resize {
+    /// Fills the box, however small the picture
+    pub fn fit(max_width: u32, max_height: u32) -> Self {
+        Self {
+            max_width,
+            max_height,
+            filter: ResizeFilter::default(),
+            enlarge: true,
+        }
+    }
+
+    /// Only scales pictures larger than the box
+    pub fn shrink_to(max_width: u32, max_height: u32) -> Self {
+        Self {
+            enlarge: false,
+            ..Self::fit(max_width, max_height)
+        }
+    }
+}
+
+impl Step for Resize {
+    fn name(&self) -> &'static str {
+        "resize"
+    }
+
+    fn apply<'a>(&self, frame: Frame<'a>) -> Result<Frame<'a>, Box<dyn Error>> {
+        let image = &frame.image;
+        if !self.enlarge && image.width() <= self.max_width && image.height() <= self.max_height {
+            return Ok(frame);
+        }
+        let (new_width, new_height) = calculate_dimensions(
+            image.width(),
+            image.height(),
+            self.max_width,
+            self.max_height,
+        );
+
+        let mut rgba_image = image.to_rgba8();
+        let (orig_width, orig_height) = rgba_image.dimensions();
+
+        let src_image = Image::from_slice_u8(
+            orig_width,
+            orig_height,
+            &mut rgba_image,
+            fr::PixelType::U8x4,
+        )?;
+
+        let mut dst_image = Image::new(new_width, new_height, fr::PixelType::U8x4);
+
+        let mut resizer = fr::Resizer::new();
+        let options = fr::ResizeOptions::new().resize_alg(self.filter.algorithm());
+        resizer.resize(&src_image, &mut dst_image, Some(&options))?;
+
+        let buffer = dst_image.into_vec();
+        let rgba_result = image::RgbaImage::from_raw(new_width, new_height, buffer)
+            .ok_or("Failed to create RgbaImage")?;
+
+        Ok(Frame {
+            image: Cow::Owned(DynamicImage::ImageRgba8(rgba_result)),
+            orientation: frame.orientation,
+        })
+    }
+}
+
+/// Calculates new dimensions while preserving aspect ratio
+#[inline]
+fn calculate_dimensions(width: u32, height: u32, max_width: u32, max_height: u32) -> (u32, u32) {
+    let width_ratio = max_width as f32 / width as f32;
+    let height_ratio = max_height as f32 / height as f32;
+    let scale_ratio = width_ratio.min(height_ratio);
+
+    (
+        ((width as f32 * scale_ratio).round() as u32).max(1),
+        ((height as f32 * scale_ratio).round() as u32).max(1),
+    )
+}
+
+/// 8-bit pixel layouts the encoder writes
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorTarget {
+    Rgba8,
+    Rgb8,
+    Luma8,
+    LumaA8,
+}
+
+/// Converts the picture to another pixel layout, dropping what it can't hold
+pub struct ConvertColor {
+    pub target: ColorTarget,
+}
+
+impl Step for ConvertColor {
+    fn name(&self) -> &'static str {
+        "convert color"
+    }
+
+    fn apply<'a>(&self, frame: Frame<'a>) -> Result<Frame<'a>, Box<dyn Error>> {
+        let color = match self.target {
+            ColorTarget::Rgba8 => ColorType::Rgba8,
+            ColorTarget::Rgb8 => ColorType::Rgb8,
+            ColorTarget::Luma8 => ColorType::L8,
+            ColorTarget::LumaA8 => ColorType::La8,
+        };
+        if frame.image.color() == color {
+            return Ok(frame);
+        }
+
+        let image = match self.target {
+            ColorTarget::Rgba8 => DynamicImage::ImageRgba8(frame.image.to_rgba8()),
+            ColorTarget::Rgb8 => DynamicImage::ImageRgb8(frame.image.to_rgb8()),
+            ColorTarget::Luma8 => DynamicImage::ImageLuma8(frame.image.to_luma8()),
+            ColorTarget::LumaA8 => DynamicImage::ImageLumaA8(frame.image.to_luma_alpha8()),
+        };
+        Ok(Frame {
+            image: Cow::Owned(image),
+            orientation: frame.orientation,
+        })
+    }
+}
+
+// ===================================
+//         PIPELINE
+// ===================================
+
+/// Steps run in order, then the PNG encoder
+pub struct Pipeline {
+    steps: Vec<Box<dyn Step>>,
+    encoder: PngEncoder,
+}
+
+impl Pipeline {
+    pub fn new(encoder: PngEncoder) -> Self {
+        Self {
+            steps: Vec::new(),
+            encoder,
+        }
+    }
+
+    /// Adds a step after the ones already there
+    pub fn with_step(mut self, step: impl Step + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Upright, fit in the box, 8-bit RGBA
+    pub fn thumbnail(max_width: u32, max_height: u32, compression_level: u8) -> Self {
+        Self::new(PngEncoder::new(compression_level))
+            .with_step(Orient)
+            .with_step(Resize::fit(max_width, max_height))
+            .with_step(ConvertColor {
+                target: ColorTarget::Rgba8,
+            })
+    }
+
+    /// Names of the steps, in the order they run
+    pub fn step_names(&self) -> Vec<&'static str> {
+        self.steps.iter().map(|step| step.name()).collect()
+    }
+
+    /// Runs the steps, without encoding
+    pub fn process<'a>(&self, mut frame: Frame<'a>) -> Result<Frame<'a>, Box<dyn Error>> {
+        for step in &self.steps {
+            let start = Instant::now();
+            frame = step.apply(frame)?;
+            debug!("Step {} took {:?}", step.name(), start.elapsed());
+        }
+        Ok(frame)
+    }
+
+    /// Runs the steps and writes the result to `output_path`
+    pub fn run<P: AsRef<Path>>(&self, frame: Frame, output_path: P) -> Result<(), Box<dyn Error>> {
+        let frame = self.process(frame)?;
+        let start = Instant::now();
+        self.encoder.encode(&frame.image, output_path)?;
+        debug!("Encoding took {:?}", start.elapsed());
+        Ok(())
+    }
+}
+
+// ===================================
+//         THUMBNAIL GENERATION
+// ===================================
+
+/// Generates a thumbnail from a specific image
+pub fn generate_thumbnail_from_image<P: AsRef<Path>>(
+    image: &DynamicImage,
+    output_path: P,
+    max_width: u32,
+    max_height: u32,
+    compression_level: u8,
+) -> Result<(), Box<dyn Error>> {
+    let start_time = Instant::now();
+
+    Pipeline::thumbnail(max_width, max_height, compression_level)
+        .run(Frame::borrowed(image), output_path)?;
+
+    let elapsed = start_time.elapsed();
+    info!(
+        "Thumbnail generated in {:.3} seconds",
+        elapsed.as_secs_f64()
+    );
+
+    Ok(())
+}
+
+// ===================================
+//         IMAGE SAVING
+// ===================================
+
+/// Writes PNG files with configurable compression
+pub struct PngEncoder {
+    /// 0 to 9, from the fastest to the smallest file
+    pub compression_level: u8,
+}
+
+impl PngEncoder {
+    pub fn new(compression_level: u8) -> Self {
+        Self { compression_level }
+    }
+
+    pub fn encode<P: AsRef<Path>>(
+        &self,
+        img: &DynamicImage,
+        output_path: P,
+    ) -> Result<(), Box<dyn Error>> {
+        save_image_as_png(img, output_path, self.compression_level)
+    }
+}
+
+/// Saves an image as PNG with configurable compression
+pub fn save_image_as_png<P: AsRef<Path>>(
+    img: &DynamicImage,
+    output_path: P,
+    compression_level: u8,
+) -> Result<(), Box<dyn Error>> {
+    // Written with 8 bits per channel, deeper or float pixels are converted first
+    let converted;
+    let img = match img.color() {
+        ColorType::L8 | ColorType::La8 | ColorType::Rgb8 | ColorType::Rgba8 => img,
+        _ => {
+            converted = DynamicImage::ImageRgba8(img.to_rgba8());
+            &converted
+        }
+    };
+
+    atomic_file::write_with(output_path.as_ref(), |temp| {
+        let file = File::create(temp)?;
+        let w = &mut BufWriter::new(file);
+        write_png(img, w, compression_level)?;
+        w.flush()?;
+        Ok(())
+    })
+}
+
+/// Encodes an image with 8 bits per channel as PNG
+fn write_png(
+    img: &DynamicImage,
+    w: &mut BufWriter<File>,
+    compression_level: u8,
+) -> Result<(), Box<dyn Error>> {
+    let mut encoder = png::Encoder::new(w, img.width(), img.height());
+
+    // Set color type based on image
+    match img.color() {
+        ColorType::L8 => encoder.set_color(png::ColorType::Grayscale),
+        ColorType::La8 => encoder.set_color(png::ColorType::GrayscaleAlpha),
+        ColorType::Rgb8 => encoder.set_color(png::ColorType::Rgb),
+        _ => encoder.set_color(png::ColorType::Rgba),
+    }
+
+    encoder.set_depth(png::BitDepth::Eight);
+
+    // Set compression level (0-9 → zlib levels)
+    let level = match compression_level {
+        0..=3 => png::Compression::Fast,
+        4..=6 => png::Compression::Balanced,
+        7..=9 => png::Compression::High,
+        _ => png::Compression::Balanced,
+    };
+    encoder.set_compression(level);
+    encoder.set_filter(png::Filter::Sub);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(img.as_bytes())?;
+    writer.finish()?;
+
+    Ok(())
+}
@@ -0,0 +1,1384 @@
+use crate::dtos::image_dto::{ImageDTO, ImageUpdateDTO};
+use crate::dtos::library_counts_dto::LibraryCountsDTO;
+use crate::dtos::tag_dto::TagDTO;
+use crate::dtos::timeline_dto::MonthGroupDTO;
+use crate::models::filter::{Filter, SortOrder, TagMatchMode};
+use crate::models::import_rule::ImportedFile;
+use crate::models::image::{ActiveModel, Entity, Model};
+use crate::models::page::Page;
+use crate::models::review_status::ReviewStatus;
+use crate::models::{image, image_tag, image_version, tag};
+use crate::models::activity_action::ActivityAction;
+use crate::services::change_service::{self, DataChange};
+use crate::services::{
+    activity_service, dedup_service, desc_query, fuzzy_match, quarantine_service, rule_service, stack_service,
+    trash_service,
+};
+use crate::services::connection_db::{db_ref, with_retry};
+use crate::services::file_service::{
+    folder_entry_images, format_to_extension, is_imported_from, move_into_folder_entry,
+    move_out_of_folder_entry, rename_folder_images, rename_stored_image,
+    save_image_file_with_thumbnail, stored_file_stem,
+};
+use crate::services::file_naming::{image_file_name, typed_stem};
+use crate::services::similarity_service::thumbnail_hash;
+use crate::services::version_service;
+use crate::utils::{get_data_dir, random_index};
+use crate::services::integrity_service::hash_entry;
+use crate::services::tag_service::{self, TagCountRow, get_tags_for_images, update_tags_for_image};
+use ::image::{DynamicImage, ImageFormat};
+use chrono::NaiveTime;
+use log::{error, info, warn};
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, InsertResult, JoinType, Order,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait, prelude::*,
+};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// Leaves out stacked images other than the cover, the stack is shown through its cover
+const NOT_STACK_MEMBER: &str = "images.id NOT IN (SELECT stack_images.image_id FROM stack_images \
+     JOIN stacks ON stacks.id = stack_images.stack_id \
+     WHERE stack_images.image_id <> stacks.cover_image_id)";
+
+/// Overlap with the tags of the image a related search starts from. Each shared tag
+/// weighs by how rare it is, so a niche tag counts more than one used everywhere.
+const RELATED_WEIGHT: &str = "SUM(1.0 / (SELECT COUNT(*) FROM image_tags AS usage \
+     WHERE usage.tag_id = image_tags.tag_id))";
+
+/// Image ids per query in the fuzzy search, below SQLite's limit on bound values
+const ID_CHUNK: usize = 5000;
+
+/// Year and month of an image's creation date, as `YYYY-MM`
+const MONTH_EXPR: &str = "strftime('%Y-%m', created_at)";
+
+/// Entries that are not in the trash, every listing starts from these
+pub fn not_trashed() -> Select<Entity> {
+    Entity::find().filter(image::Column::DeletedAt.is_null())
+}
+
+pub async fn insert_image(desc: &str) -> Result<i64, DbErr> {
+    let db = db_ref();
+    let new_image = ActiveModel {
+        description: Set(desc.to_string()),
+        path: Set(String::new()),
+        thumbnail_path: Set(String::new()),
+        is_prepared: Set(false),
+        review_status: Set(ReviewStatus::Unreviewed),
+        ..Default::default()
+    };
+
+    let result: InsertResult<ActiveModel> = Entity::insert(new_image).exec(db).await?;
+    Ok(result.last_insert_id)
+}
+
+pub async fn find_all(filter: Filter, page: u64, size: u64) -> Result<Page<ImageDTO>, DbErr> {
+    with_retry(|| find_page(filter.clone(), page, size)).await
+}
+
+async fn find_page(filter: Filter, page: u64, size: u64) -> Result<Page<ImageDTO>, DbErr> {
+    let db = db_ref();
+    // Verify if we have a query
+    let has_query = !filter.query.trim().is_empty()
+        || filter.refinements.iter().any(|step| !step.trim().is_empty());
+    let has_tags = !filter.tags.is_empty()
+        || !filter.required_tags.is_empty()
+        || !filter.excluded_tags.is_empty();
+    let has_language = filter.language.is_some();
+    let has_operators = filter.before.is_some()
+        || filter.after.is_some()
+        || filter.is_folder.is_some()
+        || filter.category.is_some();
+
+    // If we don't have a query, tags, language or operators, just return all
+    if !has_query && !has_tags && !has_language && !has_operators {
+        return find_all_images_without_filter(page, size, filter, db).await;
+    }
+    if let Some(matches) = fuzzy_matches(&filter, filtered_query(&filter)).await? {
+        return find_fuzzy_page(&filter, matches, page, size, db).await;
+    }
+
+    let mut query = filtered_query(&filter);
+
+    // Count total
+    let total_count = query
+        .clone()
+        .select_only()
+        .column(image::Column::Id)
+        .distinct()
+        .count(db)
+        .await?;
+
+    let total_pages = if total_count == 0 {
+        0
+    } else {
+        total_count.div_ceil(size)
+    };
+
+    query = sorted(query, filter.sort_order);
+
+    // Search for images
+    let images = query
+        .distinct()
+        .limit(size)
+        .offset(page * size)
+        .into_model::<Model>()
+        .all(db)
+        .await?;
+
+    // Search for tags for each image
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+
+    let mut dtos = to_dto(images, tags_map);
+    mark_matches(&mut dtos, &filter);
+    stack_service::annotate(&mut dtos, db).await?;
+
+    Ok(Page {
+        content: dtos,
+        total_pages,
+        page_number: page,
+    })
+}
+
+/// Page of the images the fuzzy search found, closest first
+async fn find_fuzzy_page(
+    filter: &Filter,
+    matches: Vec<i64>,
+    page: u64,
+    size: u64,
+    db: &DatabaseConnection,
+) -> Result<Page<ImageDTO>, DbErr> {
+    let total_pages = (matches.len() as u64).div_ceil(size);
+    let page_ids: Vec<i64> = matches
+        .into_iter()
+        .skip((page * size) as usize)
+        .take(size as usize)
+        .collect();
+
+    let mut images = Entity::find()
+        .filter(image::Column::Id.is_in(page_ids.clone()))
+        .all(db)
+        .await?;
+    images.sort_by_key(|img| page_ids.iter().position(|id| *id == img.id));
+
+    let tags_map = get_tags_for_images(&page_ids, db).await?;
+    let mut dtos = to_dto(images, tags_map);
+    mark_matches(&mut dtos, filter);
+    stack_service::annotate(&mut dtos, db).await?;
+
+    Ok(Page {
+        content: dtos,
+        total_pages,
+        page_number: page,
+    })
+}
+
+/// Ids of the images of `query` whose description comes close to every step of the
+/// fuzzy filter, closest first. Images as close as each other keep the sort order.
+/// None when the filter doesn't search fuzzily.
+async fn fuzzy_matches(filter: &Filter, query: Select<Entity>) -> Result<Option<Vec<i64>>, DbErr> {
+    let steps = query_steps(filter);
+    if !filter.fuzzy || steps.is_empty() {
+        return Ok(None);
+    }
+
+    let candidates: Vec<(i64, String)> = sorted(query, filter.sort_order)
+        .select_only()
+        .column(image::Column::Id)
+        .column(image::Column::Description)
+        .into_tuple()
+        .all(db_ref())
+        .await?;
+
+    let mut scored: Vec<(i64, f64)> = candidates
+        .into_iter()
+        .filter_map(|(id, description)| {
+            let mut total = 0.0;
+            for step in &steps {
+                total += fuzzy_match::score(step, &description)?;
+            }
+            Some((id, total / steps.len() as f64))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    Ok(Some(scored.into_iter().map(|(id, _)| id).collect()))
+}
+
+/// Refinements and query of the filter that have something to match
+fn query_steps(filter: &Filter) -> Vec<&str> {
+    filter
+        .refinements
+        .iter()
+        .chain([&filter.query])
+        .map(|step| step.trim())
+        .filter(|step| !step.is_empty())
+        .collect()
+}
+
+/// Notes where the steps of the query matched each description, for highlighting
+fn mark_matches(dtos: &mut [ImageDTO], filter: &Filter) {
+    let steps = query_steps(filter);
+    if steps.is_empty() {
+        return;
+    }
+    for dto in dtos {
+        let mut ranges: Vec<Range<usize>> = steps
+            .iter()
+            .flat_map(|step| {
+                if filter.fuzzy {
+                    fuzzy_match::matched_ranges(step, &dto.description)
+                } else {
+                    desc_match_ranges(step, &dto.description)
+                }
+            })
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        dto.matches = ranges.into_iter().fold(Vec::new(), |mut merged, range| {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+            merged
+        });
+    }
+}
+
+/// Every entry matching the filter, fetched page by page
+pub async fn find_all_matching(filter: Filter) -> Result<Vec<ImageDTO>, DbErr> {
+    const PAGE_SIZE: u64 = 500;
+    let mut images = Vec::new();
+    let mut page = 0;
+
+    loop {
+        let result = find_all(filter.clone(), page, PAGE_SIZE).await?;
+        images.extend(result.content);
+        page += 1;
+        if page >= result.total_pages {
+            return Ok(images);
+        }
+    }
+}
+
+/// Images sharing tags with the given one, most related first
+pub async fn find_related(image_id: i64, page: u64, size: u64) -> Result<Page<ImageDTO>, DbErr> {
+    with_retry(|| find_related_page(image_id, page, size)).await
+}
+
+async fn find_related_page(image_id: i64, page: u64, size: u64) -> Result<Page<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let query = not_trashed()
+        .join(JoinType::InnerJoin, image::Relation::ImageTag.def())
+        .filter(Expr::cust_with_values(
+            "image_tags.tag_id IN (SELECT tag_id FROM image_tags WHERE image_id = ?)",
+            [image_id],
+        ))
+        .filter(image::Column::Id.ne(image_id))
+        .filter(Expr::cust(NOT_STACK_MEMBER))
+        .group_by(image::Column::Id);
+
+    let total_count = query
+        .clone()
+        .select_only()
+        .column(image::Column::Id)
+        .distinct()
+        .count(db)
+        .await?;
+    let total_pages = total_count.div_ceil(size);
+
+    let images = query
+        .order_by(Expr::cust(RELATED_WEIGHT), Order::Desc)
+        .order_by(image::Column::CreatedAt, Order::Desc)
+        .limit(size)
+        .offset(page * size)
+        .all(db)
+        .await?;
+
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+    let mut dtos = to_dto(images, tags_map);
+    stack_service::annotate(&mut dtos, db).await?;
+
+    Ok(Page {
+        content: dtos,
+        total_pages,
+        page_number: page,
+    })
+}
+
+/// Registered, untagged, unreviewed and tag totals for the navbar
+pub async fn count_library() -> Result<LibraryCountsDTO, DbErr> {
+    let db = db_ref();
+    let prepared = || not_trashed().filter(image::Column::IsPrepared.eq(true));
+
+    let images = prepared().count(db).await?;
+    let untagged = prepared()
+        .filter(Expr::cust(
+            "images.id NOT IN (SELECT image_tags.image_id FROM image_tags)",
+        ))
+        .count(db)
+        .await?;
+    let unreviewed = prepared()
+        .filter(image::Column::ReviewStatus.eq(ReviewStatus::Unreviewed))
+        .count(db)
+        .await?;
+    let tags = tag::Entity::find().count(db).await?;
+    let quarantined = quarantine_service::count().await?;
+    let trashed = trash_service::count().await?;
+
+    Ok(LibraryCountsDTO {
+        images,
+        untagged,
+        unreviewed,
+        tags,
+        quarantined,
+        trashed,
+    })
+}
+
+/// Image count per month, newest first
+pub async fn count_by_month() -> Result<Vec<MonthGroupDTO>, DbErr> {
+    let rows: Vec<(String, i64)> = with_retry(|| {
+        not_trashed()
+            .select_only()
+            .column_as(Expr::cust(MONTH_EXPR), "month")
+            .column_as(image::Column::Id.count(), "count")
+            .group_by(Expr::cust(MONTH_EXPR))
+            .order_by(Expr::cust(MONTH_EXPR), Order::Desc)
+            .into_tuple()
+            .all(db_ref())
+    })
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(month, count)| {
+            let (year, month) = month.split_once('-')?;
+            Some(MonthGroupDTO {
+                year: year.parse().ok()?,
+                month: month.parse().ok()?,
+                count: count as u64,
+            })
+        })
+        .collect())
+}
+
+/// One page of the images registered in a month (`YYYY-MM`), newest first
+pub async fn find_by_month(month: &str, page: u64, size: u64) -> Result<Vec<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let images = not_trashed()
+        .filter(Expr::cust_with_values(
+            format!("{} = ?", MONTH_EXPR),
+            [month.to_string()],
+        ))
+        .order_by(image::Column::CreatedAt, Order::Desc)
+        .limit(size)
+        .offset(page * size)
+        .all(db)
+        .await?;
+
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+
+    Ok(to_dto(images, tags_map))
+}
+
+/// Images matching the description query and each refinement, carrying every tag of
+/// the filter (or any of them, depending on its match mode) and none of its excluded
+/// tags
+fn filtered_query(filter: &Filter) -> Select<Entity> {
+    // Base query for images
+    let mut query = not_trashed().filter(Expr::cust(NOT_STACK_MEMBER));
+
+    // A tag also selects the images of the tags under it, `animals` those tagged
+    // `animals/cats`
+    if !filter.tags.is_empty() {
+        let tags: Vec<String> = filter.tags.iter().cloned().collect();
+        if filter.tag_match == TagMatchMode::All {
+            for tag in tags {
+                query = query.filter(tag_service::tagged_under(&[tag]));
+            }
+        } else {
+            query = query.filter(tag_service::tagged_under(&tags));
+        }
+    }
+    for tag in &filter.required_tags {
+        query = query.filter(tag_service::tagged_under(std::slice::from_ref(tag)));
+    }
+
+    if !filter.excluded_tags.is_empty() {
+        let excluded: Vec<String> = filter.excluded_tags.iter().cloned().collect();
+        query = query.filter(tag_service::tagged_under(&excluded).not());
+    }
+
+    // Apply conditions to query, the fuzzy search scores the descriptions itself
+    if !filter.fuzzy {
+        for step in filter.refinements.iter().chain([&filter.query]) {
+            if let Some(desc_cond) = build_desc_condition(step) {
+                query = query.filter(desc_cond);
+            }
+        }
+    }
+
+    if let Some(language) = &filter.language {
+        query = query.filter(image::Column::Language.eq(language.as_str()));
+    }
+
+    if let Some(before) = filter.before {
+        query = query.filter(image::Column::CreatedAt.lt(before.and_time(NaiveTime::MIN)));
+    }
+    if let Some(after) = filter.after {
+        query = query.filter(image::Column::CreatedAt.gte(after.and_time(NaiveTime::MIN)));
+    }
+    if let Some(is_folder) = filter.is_folder {
+        query = query.filter(image::Column::IsFolder.eq(is_folder));
+    }
+    if let Some(category) = &filter.category {
+        query = query.filter(tag_service::tagged_in_category(category));
+    }
+
+    query
+}
+
+/// Tags of the images matching the filter, each with how many of those images carry
+/// it, most common first. Tags the filter already selects are left out.
+pub async fn count_tags_in_results(
+    filter: Filter,
+    limit: u64,
+) -> Result<Vec<(TagDTO, u64)>, DbErr> {
+    with_retry(|| tag_facets(filter.clone(), limit)).await
+}
+
+async fn tag_facets(filter: Filter, limit: u64) -> Result<Vec<(TagDTO, u64)>, DbErr> {
+    let Some(matches) = fuzzy_matches(&filter, filtered_query(&filter)).await? else {
+        let matching = filtered_query(&filter)
+            .select_only()
+            .column(image::Column::Id)
+            .into_query();
+        let images = image_tag::Column::ImageId.in_subquery(matching);
+        return count_tags(&filter, images, Some(limit)).await;
+    };
+
+    let mut totals: HashMap<TagDTO, u64> = HashMap::new();
+    for chunk in matches.chunks(ID_CHUNK) {
+        let images = image_tag::Column::ImageId.is_in(chunk.to_vec());
+        for (tag, count) in count_tags(&filter, images, None).await? {
+            *totals.entry(tag).or_default() += count;
+        }
+    }
+    let mut facets: Vec<(TagDTO, u64)> = totals.into_iter().collect();
+    facets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+    facets.truncate(limit as usize);
+    Ok(facets)
+}
+
+/// Tags carried by the images of the `images` condition with their counts, leaving
+/// out the tags the filter selects
+async fn count_tags(
+    filter: &Filter,
+    images: SimpleExpr,
+    limit: Option<u64>,
+) -> Result<Vec<(TagDTO, u64)>, DbErr> {
+    let rows: Vec<TagCountRow> = image_tag::Entity::find()
+        .select_only()
+        .column(tag::Column::Id)
+        .column(tag::Column::Name)
+        .column(tag::Column::Color)
+        .column(tag::Column::ParentId)
+        .column(tag::Column::Category)
+        .column_as(image_tag::Column::ImageId.count(), "count")
+        .join(JoinType::InnerJoin, image_tag::Relation::Tag.def())
+        .filter(images)
+        .filter(
+            tag::Column::Name.is_not_in(filter.tags.iter().chain(&filter.required_tags).cloned()),
+        )
+        .group_by(tag::Column::Id)
+        .order_by_desc(Expr::cust("count"))
+        .order_by_asc(tag::Column::Name)
+        .limit(limit)
+        .into_tuple()
+        .all(db_ref())
+        .await?;
+
+    Ok(rows.into_iter().map(tag_service::counted_tag).collect())
+}
+
+/// Distinct languages set on images, for the search filter
+pub async fn find_languages() -> Result<Vec<String>, DbErr> {
+    not_trashed()
+        .select_only()
+        .column(image::Column::Language)
+        .filter(image::Column::Language.is_not_null())
+        .distinct()
+        .order_by(Expr::cust("images.language COLLATE NOCASE"), Order::Asc)
+        .into_tuple::<String>()
+        .all(db_ref())
+        .await
+}
+
+fn sorted(query: Select<Entity>, sort_order: SortOrder) -> Select<Entity> {
+    match sort_order {
+        SortOrder::CreatedAsc => query.order_by(image::Column::CreatedAt, Order::Asc),
+        SortOrder::CreatedDesc => query.order_by(image::Column::CreatedAt, Order::Desc),
+        SortOrder::DescriptionAsc => query
+            .order_by(Expr::cust("images.description COLLATE NOCASE"), Order::Asc)
+            .order_by(image::Column::CreatedAt, Order::Desc),
+    }
+}
+
+/// Pinned entries, oldest first so the strip order stays stable
+pub async fn find_pinned() -> Result<Vec<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let images = not_trashed()
+        .filter(image::Column::IsPinned.eq(true))
+        .order_by(image::Column::CreatedAt, Order::Asc)
+        .all(db)
+        .await?;
+
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+
+    Ok(to_dto(images, tags_map))
+}
+
+pub async fn set_pinned(id: i64, pinned: bool) -> Result<(), DbErr> {
+    with_retry(|| {
+        Entity::update_many()
+            .col_expr(image::Column::IsPinned, Expr::value(pinned))
+            .filter(image::Column::Id.eq(id))
+            .exec(db_ref())
+    })
+    .await?;
+    change_service::notify(DataChange::Images);
+    Ok(())
+}
+
+/// Imported entries waiting in the inbox, oldest first so imports are triaged in order
+pub async fn find_unreviewed(limit: u64) -> Result<Vec<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let images = not_trashed()
+        .filter(image::Column::ReviewStatus.eq(ReviewStatus::Unreviewed))
+        .filter(image::Column::IsPrepared.eq(true))
+        .order_by(image::Column::CreatedAt, Order::Asc)
+        .order_by(image::Column::Id, Order::Asc)
+        .limit(limit)
+        .all(db)
+        .await?;
+
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+
+    Ok(to_dto(images, tags_map))
+}
+
+pub async fn set_review_status(id: i64, status: ReviewStatus) -> Result<(), DbErr> {
+    with_retry(|| {
+        Entity::update_many()
+            .col_expr(image::Column::ReviewStatus, Expr::value(status))
+            .filter(image::Column::Id.eq(id))
+            .exec(db_ref())
+    })
+    .await?;
+    change_service::notify(DataChange::Images);
+    Ok(())
+}
+
+/// Picks a random prepared entry matching the filter
+pub async fn random_matching(filter: Filter) -> Result<Option<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let prepared = filtered_query(&filter).filter(image::Column::IsPrepared.eq(true));
+    let model = match fuzzy_matches(&filter, prepared.clone()).await? {
+        Some(matches) if matches.is_empty() => None,
+        Some(matches) => {
+            let id = matches[random_index(matches.len())];
+            Entity::find_by_id(id).one(db).await?
+        }
+        None => {
+            prepared
+                .order_by(Expr::cust("RANDOM()"), Order::Asc)
+                .one(db)
+                .await?
+        }
+    };
+    let Some(model) = model else {
+        return Ok(None);
+    };
+
+    let tags_map = get_tags_for_images(&[model.id], db).await?;
+    Ok(Some(to_image_dto(&model, &tags_map)))
+}
+
+async fn find_all_images_without_filter(
+    page: u64,
+    size: u64,
+    filter: Filter,
+    db: &DatabaseConnection,
+) -> Result<Page<ImageDTO>, DbErr> {
+    // Count total
+    let total_count = not_trashed()
+        .filter(Expr::cust(NOT_STACK_MEMBER))
+        .count(db)
+        .await?;
+    let total_pages = if total_count == 0 {
+        0
+    } else {
+        total_count.div_ceil(size)
+    };
+
+    let mut query = not_trashed()
+        .filter(Expr::cust(NOT_STACK_MEMBER))
+        .limit(size)
+        .offset(page * size);
+
+    query = sorted(query, filter.sort_order);
+
+    let images: Vec<Model> = query.all(db).await?;
+
+    // Search for tags for each image
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+
+    let mut dtos = to_dto(images, tags_map);
+    stack_service::annotate(&mut dtos, db).await?;
+
+    Ok(Page {
+        content: dtos,
+        total_pages,
+        page_number: page,
+    })
+}
+
+pub async fn delete_image(id_val: i64) -> Result<(), DbErr> {
+    let db = db_ref();
+    stack_service::remove_image(id_val).await?;
+    let txn = db.begin().await?;
+
+    let existing = Entity::find_by_id(id_val).one(&txn).await?;
+    Entity::delete_by_id(id_val).exec(&txn).await?;
+
+    txn.commit().await?;
+    change_service::notify(DataChange::Images);
+
+    if let Some(model) = existing {
+        activity_service::record(
+            ActivityAction::Delete,
+            Some(id_val),
+            activity_details(&model),
+        )
+        .await;
+    }
+
+    // Return Ok regardless if deletion happened or not
+    Ok(())
+}
+
+pub async fn update_from_dto(id: i64, dto: ImageUpdateDTO) -> Result<Model, DbErr> {
+    let db = db_ref();
+    let existing_model = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("Image not found".to_string()))?;
+
+    let was_prepared = existing_model.is_prepared;
+    let mut active_model: ActiveModel = existing_model.into();
+
+    if let Some(path) = dto.path
+        && !path.is_empty()
+    {
+        active_model.path = Set(path);
+    }
+
+    if let Some(thumbnail_path) = dto.thumbnail_path
+        && !thumbnail_path.is_empty()
+    {
+        active_model.thumbnail_path = Set(thumbnail_path);
+    }
+
+    if let Some(description) = dto.description
+        && !description.is_empty()
+    {
+        active_model.description = Set(description);
+    }
+
+    if let Some(source_url) = dto.source_url
+        && !source_url.is_empty()
+    {
+        active_model.source_url = Set(Some(source_url));
+    }
+
+    if let Some(language) = dto.language {
+        let language = language.trim();
+        active_model.language = Set((!language.is_empty()).then(|| language.to_string()));
+    }
+
+    if let Some(content_hash) = dto.content_hash {
+        active_model.content_hash = Set(Some(content_hash));
+        // New content, the similarity hash is computed again when needed
+        active_model.perceptual_hash = Set(None);
+    }
+
+    active_model.is_prepared = Set(dto.is_prepared);
+
+    active_model.is_folder = Set(dto.is_folder);
+
+    let updated_model = active_model.update(db).await?;
+
+    let action = if was_prepared {
+        ActivityAction::Update
+    } else {
+        ActivityAction::Create
+    };
+    activity_service::record(action, Some(id), activity_details(&updated_model)).await;
+
+    if let Some(tags) = dto.tags
+        && !tags.is_empty()
+    {
+        let old_tags = get_tags_for_images(&[id], db)
+            .await?
+            .remove(&id)
+            .unwrap_or_default();
+        let old_names: HashSet<&str> = old_tags.iter().map(|t| t.name.as_str()).collect();
+        let new_names: HashSet<&str> = tags.iter().map(|t| t.name.as_str()).collect();
+
+        let mut changes: Vec<String> = new_names
+            .difference(&old_names)
+            .map(|name| format!("+{}", name))
+            .chain(old_names.difference(&new_names).map(|name| format!("-{}", name)))
+            .collect();
+        changes.sort();
+
+        update_tags_for_image(db, id, tags.clone()).await?;
+
+        if !changes.is_empty() {
+            activity_service::record(
+                ActivityAction::TagChange,
+                Some(id),
+                format!("{}: {}", activity_details(&updated_model), changes.join(", ")),
+            )
+            .await;
+        }
+    }
+
+    change_service::notify(DataChange::Images);
+    Ok(updated_model)
+}
+
+/// Adds a tag to each of the entries, creating the tag when it doesn't exist yet.
+/// Returns how many entries did not have it, under its name or an alias.
+pub async fn add_tag_to_images(image_ids: &[i64], tag: TagDTO) -> Result<usize, DbErr> {
+    let change = format!("+{}", tag.name);
+    let tag_id = tag_service::canonical_id(&tag.name).await?;
+    retag_images(image_ids, &change, |tags| {
+        !tags.iter().any(|t| Some(t.id) == tag_id) && tags.insert(tag.clone())
+    })
+    .await
+}
+
+/// Removes a tag from each of the entries, `tag_name` can be one of its aliases.
+/// Returns how many entries had it.
+pub async fn remove_tag_from_images(image_ids: &[i64], tag_name: &str) -> Result<usize, DbErr> {
+    let change = format!("-{}", tag_name);
+    let Some(tag_id) = tag_service::canonical_id(tag_name).await? else {
+        return Ok(0);
+    };
+    retag_images(image_ids, &change, |tags| {
+        let before = tags.len();
+        tags.retain(|t| t.id != tag_id);
+        tags.len() != before
+    })
+    .await
+}
+
+/// Applies `retag` to the tags of each entry and saves the ones it changed
+async fn retag_images(
+    image_ids: &[i64],
+    change: &str,
+    retag: impl Fn(&mut HashSet<TagDTO>) -> bool,
+) -> Result<usize, DbErr> {
+    let db = db_ref();
+    let models = Entity::find()
+        .filter(image::Column::Id.is_in(image_ids.to_vec()))
+        .all(db)
+        .await?;
+    let mut tags_map = get_tags_for_images(image_ids, db).await?;
+
+    let mut changed = 0;
+    for model in models {
+        let mut tags = tags_map.remove(&model.id).unwrap_or_default();
+        if !retag(&mut tags) {
+            continue;
+        }
+        update_tags_for_image(db, model.id, tags).await?;
+        activity_service::record(
+            ActivityAction::TagChange,
+            Some(model.id),
+            format!("{}: {}", activity_details(&model), change),
+        )
+        .await;
+        changed += 1;
+    }
+
+    if changed > 0 {
+        change_service::notify(DataChange::Images);
+    }
+    Ok(changed)
+}
+
+/// Renames every stored file to the configured naming pattern and updates the
+/// database paths. Returns how many entries were renamed.
+pub async fn rename_stored_files() -> Result<usize, String> {
+    let db = db_ref();
+    let models = not_trashed()
+        .filter(image::Column::IsPrepared.eq(true))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut renamed = 0;
+    for model in models {
+        let stem = stored_file_stem(model.id, &model.description, model.created_at.date());
+        let path = std::path::Path::new(&model.path);
+        let mut active_model: ActiveModel = model.clone().into();
+
+        if model.is_folder {
+            let thumbs = rename_folder_images(path, &stem).map_err(|e| e.to_string())?;
+            if thumbs.is_empty() {
+                continue;
+            }
+            if let Some((_, new_thumb)) = thumbs.iter().find(|(old, _)| *old == model.thumbnail_path)
+            {
+                active_model.thumbnail_path = Set(new_thumb.clone());
+            }
+            // Folder hashes include file names, so they change with the rename
+            if model.content_hash.is_some() {
+                let hash = hash_entry(path, true).map_err(|e| e.to_string())?;
+                active_model.content_hash = Set(Some(hash));
+            }
+        } else {
+            if path.file_stem().and_then(|s| s.to_str()) == Some(stem.as_str()) {
+                continue;
+            }
+            let (new_path, new_thumb) =
+                rename_stored_image(path, &stem).map_err(|e| e.to_string())?;
+            active_model.path = Set(new_path);
+            active_model.thumbnail_path = Set(new_thumb);
+        }
+
+        active_model.update(db).await.map_err(|e| e.to_string())?;
+        renamed += 1;
+    }
+
+    if renamed > 0 {
+        change_service::notify(DataChange::Images);
+    }
+    Ok(renamed)
+}
+
+/// Renames the stored file of an image entry to a typed name, keeping its
+/// extension. The thumbnail and preview follow. Folder entries and names taken by
+/// another file or entry are refused.
+pub async fn rename_file(id: i64, name: &str) -> Result<ImageDTO, String> {
+    let db = db_ref();
+    let model = not_trashed()
+        .filter(image::Column::Id.eq(id))
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| t!("message.rename.missing").to_string())?;
+    if model.is_folder {
+        return Err(t!("message.rename.folder").to_string());
+    }
+
+    let path = std::path::Path::new(&model.path);
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("png");
+    let stem = typed_stem(name, extension).map_err(|e| e.to_string())?;
+    if path.file_stem().and_then(|s| s.to_str()) == Some(stem.as_str()) {
+        let tags_map = get_tags_for_images(&[id], db)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(to_image_dto(&model, &tags_map));
+    }
+
+    let target = path.with_file_name(image_file_name(&stem, extension));
+    let taken = t!("message.rename.taken", name = image_file_name(&stem, extension)).to_string();
+    let stored = Entity::find()
+        .filter(image::Column::Path.eq(target.to_string_lossy().as_ref()))
+        .filter(image::Column::Id.ne(id))
+        .count(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    if stored > 0 {
+        return Err(taken);
+    }
+    let (new_path, new_thumb) = rename_stored_image(path, &stem).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AlreadyExists {
+            taken.clone()
+        } else {
+            e.to_string()
+        }
+    })?;
+
+    // Versions find their image by its path
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
+    image_version::Entity::update_many()
+        .col_expr(image_version::Column::OriginalPath, Expr::value(new_path.clone()))
+        .filter(image_version::Column::OriginalPath.eq(model.path.as_str()))
+        .exec(&txn)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut active_model: ActiveModel = model.into();
+    active_model.path = Set(new_path);
+    active_model.thumbnail_path = Set(new_thumb);
+    let updated = active_model.update(&txn).await.map_err(|e| e.to_string())?;
+    txn.commit().await.map_err(|e| e.to_string())?;
+    info!("Renamed the file of {} to {}", id, updated.path);
+
+    activity_service::record(ActivityAction::Update, Some(id), activity_details(&updated)).await;
+    change_service::notify(DataChange::Images);
+    let tags_map = get_tags_for_images(&[id], db)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(to_image_dto(&updated, &tags_map))
+}
+
+/// Inserts a single image: creates the entry, stores the file and thumbnail, then marks it prepared.
+/// `origin` is the file or address the image came from, matched by the import rules.
+pub async fn register_image(
+    dynamic_image: DynamicImage,
+    format: ImageFormat,
+    description: String,
+    tags: HashSet<TagDTO>,
+    source_url: Option<String>,
+    origin: Option<String>,
+) -> Result<i64, String> {
+    let rules = rule_service::evaluate(&[ImportedFile::new(
+        origin.as_deref(),
+        format_to_extension(format),
+        dynamic_image.width(),
+        dynamic_image.height(),
+    )]);
+
+    let hash = thumbnail_hash(&dynamic_image);
+    let image_id = insert_image(&description).await.map_err(|err| {
+        error!("Erro ao inserir imagem no banco: {}", err);
+        format!("Falha ao inserir imagem: {}", err)
+    })?;
+
+    let (new_path, thumb_path) =
+        save_image_file_with_thumbnail(image_id, dynamic_image, format, &description).map_err(
+            |err| {
+                error!("Erro ao salvar arquivo de imagem {}: {}", image_id, err);
+                format!("Falha ao salvar arquivo: {}", err)
+            },
+        )?;
+
+    let mut dto = ImageUpdateDTO {
+        content_hash: hash_entry(std::path::Path::new(&new_path), false).ok(),
+        source_url,
+        path: Some(new_path),
+        thumbnail_path: Some(thumb_path),
+        tags: Some(tags.clone()),
+        is_prepared: true,
+        ..Default::default()
+    };
+    rules
+        .merge_into(&mut dto, tags)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    update_from_dto(image_id, dto).await.map_err(|err| {
+        error!("Erro ao atualizar imagem {}: {}", image_id, err);
+        format!("Falha ao atualizar imagem: {}", err)
+    })?;
+    if let Err(err) = dedup_service::store_hash(image_id, hash).await {
+        warn!("Failed to store the hash of image {}: {}", image_id, err);
+    }
+    rules.finish(image_id).await;
+
+    info!("Image {} successfully registered", image_id);
+    Ok(image_id)
+}
+
+/// Turns standalone images into the images of a new folder entry, in the given
+/// order. The entry takes the description of the first image and the tags of all
+/// of them. The files are moved first and the rows replaced in one transaction,
+/// so a failure leaves the images as they were. Returns the new entry.
+pub async fn group_into_folder(image_ids: Vec<i64>) -> Result<i64, String> {
+    let db = db_ref();
+    let found = not_trashed()
+        .filter(image::Column::Id.is_in(image_ids.clone()))
+        .all(db)
+        .await
+        .map_err(|err| err.to_string())?;
+    let images: Vec<Model> = image_ids
+        .iter()
+        .filter_map(|id| found.iter().find(|model| model.id == *id).cloned())
+        .collect();
+    if images.len() != image_ids.len()
+        || images.iter().any(|model| model.is_folder || !model.is_prepared)
+    {
+        return Err(t!("message.group.not_standalone").to_string());
+    }
+    let Some(first) = images.first() else {
+        return Ok(0);
+    };
+
+    let tags: HashSet<TagDTO> = get_tags_for_images(&image_ids, db)
+        .await
+        .map_err(|err| err.to_string())?
+        .into_values()
+        .flatten()
+        .collect();
+    let description = first.description.clone();
+    let folder_id = insert_image(&description)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let image_dir = get_data_dir().join("images").join(folder_id.to_string());
+    let paths: Vec<std::path::PathBuf> = images.iter().map(|model| model.path.clone().into()).collect();
+    let stem = stored_file_stem(folder_id, &description, chrono::Local::now().date_naive());
+    let moved = tokio::task::spawn_blocking(move || {
+        move_into_folder_entry(&image_dir, &paths, &stem).map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())
+    .and_then(|moved| moved);
+    let moved = match moved {
+        Ok(moved) => moved,
+        Err(err) => {
+            error!("Failed to move images into folder entry {}: {}", folder_id, err);
+            let _ = Entity::delete_by_id(folder_id).exec(db).await;
+            return Err(err);
+        }
+    };
+
+    for id in &image_ids {
+        stack_service::remove_image(*id)
+            .await
+            .map_err(|err| err.to_string())?;
+    }
+
+    let replace = async {
+        let txn = db.begin().await?;
+        Entity::update_many()
+            .col_expr(image::Column::Path, Expr::value(moved.import.image_dir.clone()))
+            .col_expr(image::Column::ThumbnailPath, Expr::value(moved.import.cover_thumb.clone()))
+            .col_expr(
+                image::Column::ContentHash,
+                Expr::value(hash_entry(std::path::Path::new(&moved.import.image_dir), true).ok()),
+            )
+            .col_expr(image::Column::IsFolder, Expr::value(true))
+            .col_expr(image::Column::IsPrepared, Expr::value(true))
+            .col_expr(image::Column::ReviewStatus, Expr::value(ReviewStatus::Kept))
+            .filter(image::Column::Id.eq(folder_id))
+            .exec(&txn)
+            .await?;
+        if !tags.is_empty() {
+            image_tag::Entity::insert_many(tags.iter().map(|tag| image_tag::ActiveModel {
+                image_id: Set(folder_id),
+                tag_id: Set(tag.id),
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        Entity::delete_many()
+            .filter(image::Column::Id.is_in(image_ids.clone()))
+            .exec(&txn)
+            .await?;
+        txn.commit().await
+    };
+    if let Err(err) = replace.await {
+        error!("Failed to replace the grouped images with entry {}: {}", folder_id, err);
+        moved.undo();
+        let _ = Entity::delete_by_id(folder_id).exec(db).await;
+        return Err(err.to_string());
+    }
+
+    moved.remove_sources();
+    for id in &image_ids {
+        version_service::delete_versions(*id).await;
+    }
+    activity_service::record(ActivityAction::Create, Some(folder_id), description).await;
+    change_service::notify(DataChange::Images);
+    info!("Grouped {} images into folder entry {}", image_ids.len(), folder_id);
+    Ok(folder_id)
+}
+
+/// Turns a folder entry back into standalone images, one per image of the folder
+/// in its order. Each keeps the description, language, source and tags of the
+/// entry. Like [`group_into_folder`], the files are moved first and the rows
+/// replaced in one transaction. Returns the new images.
+pub async fn split_folder_entry(folder_id: i64) -> Result<Vec<i64>, String> {
+    let db = db_ref();
+    let folder = not_trashed()
+        .filter(image::Column::Id.eq(folder_id))
+        .one(db)
+        .await
+        .map_err(|err| err.to_string())?
+        .filter(|model| model.is_folder && model.is_prepared)
+        .ok_or_else(|| t!("message.split.not_folder").to_string())?;
+    let folder_dir = std::path::PathBuf::from(&folder.path);
+    let paths = folder_entry_images(&folder_dir).map_err(|err| err.to_string())?;
+    if paths.is_empty() {
+        return Err(t!("message.split.empty").to_string());
+    }
+
+    let tags: HashSet<TagDTO> = get_tags_for_images(&[folder_id], db)
+        .await
+        .map_err(|err| err.to_string())?
+        .into_values()
+        .flatten()
+        .collect();
+    let today = chrono::Local::now().date_naive();
+    let mut image_ids = Vec::new();
+    let mut images = Vec::new();
+    for path in paths {
+        let id = match insert_image(&folder.description).await {
+            Ok(id) => id,
+            Err(err) => {
+                delete_rows(&image_ids).await;
+                return Err(err.to_string());
+            }
+        };
+        let image_dir = get_data_dir().join("images").join(id.to_string());
+        images.push((path, image_dir, stored_file_stem(id, &folder.description, today)));
+        image_ids.push(id);
+    }
+
+    let moved = tokio::task::spawn_blocking(move || {
+        move_out_of_folder_entry(&folder_dir, &images).map_err(|err| err.to_string())
+    })
+    .await
+    .map_err(|err| err.to_string())
+    .and_then(|moved| moved);
+    let moved = match moved {
+        Ok(moved) => moved,
+        Err(err) => {
+            error!("Failed to move the images out of folder entry {}: {}", folder_id, err);
+            delete_rows(&image_ids).await;
+            return Err(err);
+        }
+    };
+
+    stack_service::remove_image(folder_id)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let replace = async {
+        let txn = db.begin().await?;
+        for (id, (path, thumbnail)) in image_ids.iter().zip(&moved.images) {
+            Entity::update_many()
+                .col_expr(image::Column::Path, Expr::value(path.clone()))
+                .col_expr(image::Column::ThumbnailPath, Expr::value(thumbnail.clone()))
+                .col_expr(
+                    image::Column::ContentHash,
+                    Expr::value(hash_entry(std::path::Path::new(path), false).ok()),
+                )
+                .col_expr(image::Column::SourceUrl, Expr::value(folder.source_url.clone()))
+                .col_expr(image::Column::Language, Expr::value(folder.language.clone()))
+                .col_expr(image::Column::IsPrepared, Expr::value(true))
+                .col_expr(image::Column::ReviewStatus, Expr::value(folder.review_status))
+                .filter(image::Column::Id.eq(*id))
+                .exec(&txn)
+                .await?;
+            if !tags.is_empty() {
+                image_tag::Entity::insert_many(tags.iter().map(|tag| image_tag::ActiveModel {
+                    image_id: Set(*id),
+                    tag_id: Set(tag.id),
+                }))
+                .exec(&txn)
+                .await?;
+            }
+        }
+        Entity::delete_by_id(folder_id).exec(&txn).await?;
+        txn.commit().await
+    };
+    if let Err(err) = replace.await {
+        error!("Failed to replace folder entry {} with its images: {}", folder_id, err);
+        moved.undo();
+        delete_rows(&image_ids).await;
+        return Err(err.to_string());
+    }
+
+    moved.remove_source();
+    version_service::delete_versions(folder_id).await;
+    for id in &image_ids {
+        activity_service::record(ActivityAction::Create, Some(*id), folder.description.clone())
+            .await;
+    }
+    change_service::notify(DataChange::Images);
+    info!("Split folder entry {} into {} images", folder_id, image_ids.len());
+    Ok(image_ids)
+}
+
+/// Removes rows inserted for an operation that could not finish
+async fn delete_rows(ids: &[i64]) {
+    if let Err(err) = Entity::delete_many()
+        .filter(image::Column::Id.is_in(ids.to_vec()))
+        .exec(db_ref())
+        .await
+    {
+        error!("Failed to remove the rows {:?}: {}", ids, err);
+    }
+}
+
+/// Picks a random prepared entry carrying the tag or a tag under it. The name can
+/// be an alias.
+pub async fn random_with_tag(tag_name: &str) -> Result<Option<Model>, DbErr> {
+    let tag_name = tag_service::normalize_name(tag_name);
+    not_trashed()
+        .filter(tag_service::tagged_under(&[tag_name]))
+        .filter(image::Column::IsPrepared.eq(true))
+        .order_by(Expr::cust("RANDOM()"), Order::Asc)
+        .one(db_ref())
+        .await
+}
+
+/// Finds the entry a stored file belongs to: the image itself or the folder containing it
+pub async fn find_by_file_path(path: &str) -> Result<Option<Model>, DbErr> {
+    let parent = std::path::Path::new(path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    not_trashed()
+        .filter(
+            image::Column::Path
+                .eq(path)
+                .or(image::Column::Path.eq(parent).and(image::Column::IsFolder.eq(true))),
+        )
+        .one(db_ref())
+        .await
+}
+
+/// Finds the folder entry that was imported from `folder`, to add new files to it
+pub async fn find_folder_imported_from(folder: &str) -> Result<Option<Model>, DbErr> {
+    let folders = not_trashed()
+        .filter(image::Column::IsFolder.eq(true))
+        .all(db_ref())
+        .await?;
+
+    let folder = std::path::Path::new(folder);
+    Ok(folders
+        .into_iter()
+        .find(|model| is_imported_from(std::path::Path::new(&model.path), folder)))
+}
+
+/// Entry with this id, `None` when it doesn't exist or is in the trash. The trash
+/// screen and restoring go through [`trash_service`](super::trash_service).
+pub async fn find_by_id(id_val: i64) -> Result<Option<ImageDTO>, DbErr> {
+    with_retry(|| load_by_id(id_val)).await
+}
+
+async fn load_by_id(id_val: i64) -> Result<Option<ImageDTO>, DbErr> {
+    let db = db_ref();
+    // Consulta o Model da imagem diretamente, sem recursão
+    if let Some(model) = not_trashed()
+        .filter(image::Column::Id.eq(id_val))
+        .one(db)
+        .await?
+    {
+        // Busca as tags dessa imagem
+        let tags_map: HashMap<i64, HashSet<TagDTO>> = get_tags_for_images(&[id_val], db).await?;
+
+        // Constrói o DTO diretamente aqui
+        let dto = ImageDTO {
+            id: model.id,
+            path: model.path,
+            thumbnail_path: model.thumbnail_path,
+            description: model.description,
+            tags: tags_map.get(&id_val).cloned().unwrap_or_default(),
+            created_at: model.created_at.format("%Y-%m-%d").to_string(),
+            is_folder: model.is_folder,
+            is_prepared: model.is_prepared,
+            source_url: model.source_url,
+            is_pinned: model.is_pinned,
+            stack_id: None,
+            stack_size: 0,
+            language: model.language,
+            matches: Vec::new(),
+        };
+
+        Ok(Some(dto))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Short description of an image used in the activity history
+pub(crate) fn activity_details(model: &Model) -> String {
+    if model.description.trim().is_empty() {
+        model.path.clone()
+    } else {
+        model.description.clone()
+    }
+}
+
+/// Condition of one step of the query, see [`desc_query`]. A query without words
+/// looks for itself anywhere in the description instead.
+fn build_desc_condition(query: &str) -> Option<Condition> {
+    let q = query.trim();
+    if q.is_empty() {
+        return None;
+    }
+
+    let condition = match desc_query::fts_expression(q) {
+        Some(expression) => Expr::cust_with_values(
+            "images.id IN (SELECT rowid FROM images_fts WHERE images_fts MATCH ?)",
+            [expression],
+        ),
+        None => image::Column::Description.contains(q),
+    };
+    Some(Condition::all().add(condition))
+}
+
+/// Where [`build_desc_condition`] matches the description. Without words, like
+/// SQLite's LIKE, only ASCII letters match regardless of case.
+fn desc_match_ranges(query: &str, description: &str) -> Vec<Range<usize>> {
+    let q = query.trim();
+    if desc_query::fts_expression(q).is_some() {
+        return desc_query::match_ranges(q, description);
+    }
+
+    let (haystack, needle) = (description.as_bytes(), q.as_bytes());
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while !needle.is_empty() && start + needle.len() <= haystack.len() {
+        if haystack[start..start + needle.len()].eq_ignore_ascii_case(needle) {
+            ranges.push(start..start + needle.len());
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+pub fn to_dto(images: Vec<Model>, tags_map: HashMap<i64, HashSet<TagDTO>>) -> Vec<ImageDTO> {
+    images
+        .iter()
+        .map(|img| to_image_dto(img, &tags_map))
+        .collect()
+}
+
+pub fn to_image_dto(model: &Model, tags_map: &HashMap<i64, HashSet<TagDTO>>) -> ImageDTO {
+    ImageDTO {
+        id: model.id,
+        path: model.path.clone(),
+        thumbnail_path: model.thumbnail_path.clone(),
+        description: model.description.clone(),
+        tags: tags_map.get(&model.id).cloned().unwrap_or_default(),
+        created_at: model.created_at.format("%Y-%m-%d").to_string(),
+        is_folder: model.is_folder,
+        is_prepared: model.is_prepared,
+        source_url: model.source_url.clone(),
+        is_pinned: model.is_pinned,
+        stack_id: None,
+        stack_size: 0,
+        language: model.language.clone(),
+        matches: Vec::new(),
+    }
+}
@@ -0,0 +1,345 @@
+use crate::dtos::image_dto::ImageUpdateDTO;
+use crate::dtos::quarantine_dto::QuarantinedFileDTO;
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::import_filter::{ImportFilter, SkipReason};
+use crate::models::import_rule::ImportedFile;
+use crate::services::file_service::{
+    FolderImport, add_files_to_folder_entry, add_new_folder_images,
+    save_images_from_folder_with_thumbnails,
+};
+use crate::services::integrity_service::hash_entry;
+use crate::services::{atomic_file, image_service, job_service, quarantine_service, rule_service, tag_service};
+use crate::utils::get_data_dir;
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Serializes the reads and writes of the job file
+static JOBS: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// A folder import still running, kept on disk until it finishes so it can be
+/// resumed on the next launch. The files already stored are tracked in the
+/// entry's `meta.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ImportJob {
+    image_id: i64,
+    folder: String,
+    description: String,
+    #[serde(default)]
+    tag_ids: Vec<i64>,
+    #[serde(default)]
+    source_url: Option<String>,
+    #[serde(default)]
+    filter: ImportFilter,
+}
+
+/// Outcome of a folder import
+#[derive(Debug, Clone)]
+pub struct FolderImportResult {
+    pub added: usize,
+    /// Files the entry already held
+    pub skipped: usize,
+    /// Files the import filter left out, by name
+    pub filtered: Vec<(String, SkipReason)>,
+    /// Files that could not be decoded and were quarantined
+    pub quarantined: usize,
+}
+
+impl From<FolderImport> for FolderImportResult {
+    fn from(import: FolderImport) -> Self {
+        Self {
+            added: import.thumbnails.len(),
+            skipped: import.skipped,
+            filtered: import.filtered,
+            quarantined: import.unreadable.len(),
+        }
+    }
+}
+
+fn jobs_path() -> PathBuf {
+    get_data_dir().join("import_jobs.json")
+}
+
+fn read_jobs() -> Vec<ImportJob> {
+    let Ok(content) = fs::read_to_string(jobs_path()) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        warn!("Ignoring unreadable import jobs: {}", err);
+        Vec::new()
+    })
+}
+
+fn write_jobs(jobs: &[ImportJob]) {
+    let result = if jobs.is_empty() {
+        fs::remove_file(jobs_path()).or_else(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(err),
+        })
+    } else {
+        serde_json::to_string_pretty(jobs)
+            .map_err(std::io::Error::other)
+            .and_then(|json| atomic_file::write(&jobs_path(), json))
+    };
+    if let Err(err) = result {
+        error!("Failed to save import jobs: {}", err);
+    }
+}
+
+fn record(job: &ImportJob) {
+    let _lock = JOBS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut jobs = read_jobs();
+    jobs.retain(|pending| pending.image_id != job.image_id);
+    jobs.push(job.clone());
+    write_jobs(&jobs);
+}
+
+fn finish(image_id: i64) {
+    let _lock = JOBS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut jobs = read_jobs();
+    jobs.retain(|pending| pending.image_id != image_id);
+    write_jobs(&jobs);
+}
+
+/// Imports a folder as a new entry. A folder imported before only gets the
+/// files its entry does not hold yet.
+pub async fn import_folder(
+    folder: String,
+    description: String,
+    tags: HashSet<TagDTO>,
+    source_url: Option<String>,
+    filter: ImportFilter,
+) -> Result<FolderImportResult, String> {
+    let _job = job_service::start();
+
+    let existing = image_service::find_folder_imported_from(&folder)
+        .await
+        .map_err(|err| format!("Falha ao buscar pasta importada: {}", err))?;
+    if let Some(entry) = existing {
+        return add_to_entry(entry.id, &entry.path, &folder, &entry.description, &filter).await;
+    }
+
+    let image_id = image_service::insert_image(&description)
+        .await
+        .map_err(|err| {
+            error!("Erro ao inserir imagem no banco: {}", err);
+            format!("Falha ao inserir imagem: {}", err)
+        })?;
+
+    let job = ImportJob {
+        image_id,
+        folder,
+        description,
+        tag_ids: tags.iter().map(|tag| tag.id).collect(),
+        source_url,
+        filter,
+    };
+    record(&job);
+    let result = run(&job, tags).await;
+    finish(image_id);
+    result
+}
+
+/// Adds the new files of the folder to the entry imported from it
+async fn add_to_entry(
+    image_id: i64,
+    image_dir: &str,
+    folder: &str,
+    description: &str,
+    filter: &ImportFilter,
+) -> Result<FolderImportResult, String> {
+    let import = add_new_folder_images(
+        image_id,
+        Path::new(image_dir),
+        Path::new(folder),
+        description,
+        filter,
+    )
+    .map_err(|err| {
+        error!("Erro ao processar imagens da pasta {}: {}", folder, err);
+        format!("Falha ao processar imagens da pasta: {}", err)
+    })?;
+
+    quarantine_service::add(Some(image_id), &import.unreadable).await;
+    if !import.thumbnails.is_empty() {
+        update_entry_content(image_id, image_dir, None, &import.files).await?;
+    }
+
+    Ok(import.into())
+}
+
+/// Stores the new content hash of a folder entry that got images added,
+/// and its thumbnail when given. The import rules run over the added files.
+async fn update_entry_content(
+    image_id: i64,
+    image_dir: &str,
+    thumbnail_path: Option<String>,
+    files: &[ImportedFile],
+) -> Result<(), String> {
+    let mut dto = ImageUpdateDTO {
+        thumbnail_path,
+        content_hash: hash_entry(Path::new(image_dir), true).ok(),
+        is_folder: true,
+        is_prepared: true,
+        ..Default::default()
+    };
+
+    let rules = rule_service::evaluate(files);
+    if !rules.rules.is_empty() {
+        let tags = image_service::find_by_id(image_id)
+            .await
+            .map_err(|err| err.to_string())?
+            .map(|entry| entry.tags)
+            .unwrap_or_default();
+        rules
+            .merge_into(&mut dto, tags)
+            .await
+            .map_err(|err| err.to_string())?;
+    }
+
+    image_service::update_from_dto(image_id, dto)
+        .await
+        .map_err(|err| {
+            error!("Erro ao atualizar imagem {}: {}", image_id, err);
+            format!("Falha ao atualizar imagem: {}", err)
+        })?;
+    rules.finish(image_id).await;
+    Ok(())
+}
+
+/// Imports a quarantined file again into its folder entry, and takes it off
+/// the list once it is stored
+pub async fn retry_quarantined(file: QuarantinedFileDTO) -> Result<(), String> {
+    let _job = job_service::start();
+
+    let entry = match file.image_id {
+        Some(id) => image_service::find_by_id(id)
+            .await
+            .map_err(|err| err.to_string())?,
+        None => None,
+    }
+    .filter(|entry| entry.is_folder)
+    .ok_or_else(|| t!("quarantine.error.entry_missing").to_string())?;
+
+    let import = add_files_to_folder_entry(
+        entry.id,
+        Path::new(&entry.path),
+        &[PathBuf::from(&file.path)],
+        &entry.description,
+        &ImportFilter::default(),
+    )
+    .map_err(|err| err.to_string())?;
+
+    if let Some((_, err)) = import.unreadable.first() {
+        quarantine_service::add(Some(entry.id), &import.unreadable).await;
+        return Err(err.clone());
+    }
+    if !import.thumbnails.is_empty() {
+        // An entry whose every image failed was never prepared and has no thumbnail yet
+        let thumbnail = (!entry.is_prepared).then_some(import.cover_thumb);
+        update_entry_content(entry.id, &entry.path, thumbnail, &import.files).await?;
+    }
+
+    quarantine_service::discard(file.id)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Stores the folder images of the job and prepares its entry
+async fn run(job: &ImportJob, tags: HashSet<TagDTO>) -> Result<FolderImportResult, String> {
+    let folder_path = Path::new(&job.folder);
+    let import = save_images_from_folder_with_thumbnails(
+        job.image_id,
+        folder_path,
+        &job.description,
+        &job.filter,
+    )
+    .map_err(|err| {
+        error!(
+            "Erro ao processar imagens da pasta {}: {}",
+            folder_path.display(),
+            err
+        );
+        format!("Falha ao processar imagens da pasta: {}", err)
+    })?;
+
+    quarantine_service::add(Some(job.image_id), &import.unreadable).await;
+    if import.cover_thumb.is_empty() {
+        return Err("Nenhuma imagem válida encontrada na pasta".to_string());
+    }
+
+    // Usar o caminho da pasta como path principal e o primeiro thumbnail
+    let mut dto = ImageUpdateDTO {
+        path: Some(import.image_dir.clone()),
+        thumbnail_path: Some(import.cover_thumb.clone()),
+        content_hash: hash_entry(Path::new(&import.image_dir), true).ok(),
+        source_url: job.source_url.clone(),
+        tags: Some(tags.clone()),
+        is_folder: true,
+        is_prepared: true,
+        ..Default::default()
+    };
+    let rules = rule_service::evaluate(&import.files);
+    rules
+        .merge_into(&mut dto, tags)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    image_service::update_from_dto(job.image_id, dto)
+        .await
+        .map_err(|err| {
+            error!("Erro ao atualizar imagem {}: {}", job.image_id, err);
+            format!("Falha ao atualizar imagem: {}", err)
+        })?;
+    rules.finish(job.image_id).await;
+
+    info!(
+        "Processadas {} imagens da pasta para ID {}",
+        import.thumbnails.len(),
+        job.image_id
+    );
+    Ok(import.into())
+}
+
+/// Continues the folder imports interrupted when the app last closed
+pub async fn resume_pending() -> Vec<Result<FolderImportResult, String>> {
+    let jobs = {
+        let _lock = JOBS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        read_jobs()
+    };
+    if jobs.is_empty() {
+        return Vec::new();
+    }
+
+    let _job = job_service::start();
+    let all_tags = tag_service::find_all().await.unwrap_or_else(|err| {
+        warn!("Could not load tags for resumed imports: {}", err);
+        HashSet::new()
+    });
+
+    let mut results = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        info!("Resuming import of {} into ID {}", job.folder, job.image_id);
+        let tags: HashSet<TagDTO> = all_tags
+            .iter()
+            .filter(|tag| job.tag_ids.contains(&tag.id))
+            .cloned()
+            .collect();
+
+        let result = match image_service::find_by_id(job.image_id).await {
+            Ok(Some(_)) => run(&job, tags).await,
+            Ok(None) => Err(format!("Entry {} no longer exists", job.image_id)),
+            Err(err) => Err(err.to_string()),
+        };
+        if let Err(err) = &result {
+            warn!("Could not resume import of {}: {}", job.folder, err);
+        }
+        finish(job.image_id);
+        results.push(result);
+    }
+    results
+}
@@ -0,0 +1,240 @@
+use crate::models::image::{ActiveModel, Column};
+use crate::models::integrity_report::{IntegrityIssue, IntegrityProblem, IntegrityReport};
+use crate::services::connection_db::db_ref;
+use crate::services::file_naming::{is_derived, sort_by_name};
+use crate::services::file_service::{is_image_file, regenerate_thumbnail};
+use crate::services::{image_service, job_service};
+use crate::utils::get_data_dir;
+use log::{info, warn};
+use sea_orm::{ActiveModelTrait, ColumnTrait, QueryFilter, Set};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+// ===================================
+//         HASHING
+// ===================================
+
+/// Images stored inside a folder entry, in display order
+fn folder_images(folder_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(folder_path)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && is_image_file(p)
+                && !p
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(is_derived)
+        })
+        .collect();
+
+    sort_by_name(&mut files);
+    Ok(files)
+}
+
+/// Content hash of a stored entry. Folders hash the name and content of every image.
+pub fn hash_entry(path: &Path, is_folder: bool) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+
+    if is_folder {
+        for file in folder_images(path)? {
+            if let Some(name) = file.file_name() {
+                hasher.update(name.to_string_lossy().as_bytes());
+            }
+            hasher.update_reader(File::open(&file)?)?;
+        }
+    } else {
+        hasher.update_reader(File::open(path)?)?;
+    }
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+async fn hash_entry_blocking(path: PathBuf, is_folder: bool) -> io::Result<String> {
+    tokio::task::spawn_blocking(move || hash_entry(&path, is_folder))
+        .await
+        .map_err(io::Error::other)?
+}
+
+// ===================================
+//         VERIFICATION
+// ===================================
+
+/// Compares every stored entry with its content hash. Entries registered before
+/// hashes existed are hashed and stored instead.
+pub async fn verify_library() -> Result<IntegrityReport, String> {
+    let _job = job_service::start();
+    let db = db_ref();
+    let models = image_service::not_trashed()
+        .filter(Column::IsPrepared.eq(true))
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut report = IntegrityReport::default();
+    for model in models {
+        let path = PathBuf::from(&model.path);
+        let issue = |problem| IntegrityIssue {
+            id: model.id,
+            path: model.path.clone(),
+            is_folder: model.is_folder,
+            content_hash: model.content_hash.clone(),
+            problem,
+        };
+
+        if !path.exists() {
+            report.issues.push(issue(IntegrityProblem::Missing));
+            continue;
+        }
+
+        let hash = match hash_entry_blocking(path, model.is_folder).await {
+            Ok(hash) => hash,
+            Err(e) => {
+                warn!("Failed to hash {}: {}", model.path, e);
+                report.issues.push(issue(IntegrityProblem::Missing));
+                continue;
+            }
+        };
+
+        match &model.content_hash {
+            Some(stored) => {
+                report.checked += 1;
+                if *stored != hash {
+                    report.issues.push(issue(IntegrityProblem::Modified));
+                }
+            }
+            None => {
+                let mut active_model: ActiveModel = model.into();
+                active_model.content_hash = Set(Some(hash));
+                active_model.update(db).await.map_err(|e| e.to_string())?;
+                report.baselined += 1;
+            }
+        }
+    }
+
+    info!(
+        "Library verified: {} checked, {} hashed, {} issues",
+        report.checked,
+        report.baselined,
+        report.issues.len()
+    );
+    Ok(report)
+}
+
+/// Stores the current hash of the entry containing the given file, used after
+/// intentional changes such as editing in an external program
+pub async fn rehash_file(path: String) -> Result<(), String> {
+    let db = db_ref();
+    let model = image_service::find_by_file_path(&path)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No entry stored for {}", path))?;
+
+    let hash = hash_entry_blocking(PathBuf::from(&model.path), model.is_folder)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut active_model: ActiveModel = model.into();
+    active_model.content_hash = Set(Some(hash));
+    active_model.perceptual_hash = Set(None);
+    active_model.update(db).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ===================================
+//         RESTORE
+// ===================================
+
+/// Location of a stored entry inside a backup of the images directory
+fn backup_candidate(backup_root: &Path, stored_path: &Path) -> Option<PathBuf> {
+    let images_root = get_data_dir().join("images");
+    let relative = match stored_path.strip_prefix(&images_root) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => {
+            // Fall back to the `{id}/{file}` layout used inside the images directory
+            let components: Vec<_> = stored_path.components().collect();
+            let start = components
+                .iter()
+                .rposition(|c| c.as_os_str() == "images")
+                .map(|i| i + 1)?;
+            components[start..].iter().collect()
+        }
+    };
+
+    [
+        backup_root.join(&relative),
+        backup_root.join("images").join(&relative),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.exists())
+}
+
+fn restore_entry(backup_root: &Path, issue: &IntegrityIssue) -> io::Result<bool> {
+    let Some(expected) = &issue.content_hash else {
+        return Ok(false);
+    };
+    let stored_path = Path::new(&issue.path);
+    let Some(candidate) = backup_candidate(backup_root, stored_path) else {
+        return Ok(false);
+    };
+
+    // Only restore copies that match what was originally registered
+    if hash_entry(&candidate, issue.is_folder)? != *expected {
+        warn!(
+            "Backup copy of {} doesn't match the stored hash",
+            issue.path
+        );
+        return Ok(false);
+    }
+
+    if issue.is_folder {
+        fs::create_dir_all(stored_path)?;
+        for file in folder_images(&candidate)? {
+            if let Some(name) = file.file_name() {
+                let target = stored_path.join(name);
+                fs::copy(&file, &target)?;
+                if let Err(e) = regenerate_thumbnail(&target) {
+                    warn!(
+                        "Failed to regenerate thumbnail of {}: {}",
+                        target.display(),
+                        e
+                    );
+                }
+            }
+        }
+    } else {
+        if let Some(parent) = stored_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&candidate, stored_path)?;
+        if let Err(e) = regenerate_thumbnail(stored_path) {
+            warn!("Failed to regenerate thumbnail of {}: {}", issue.path, e);
+        }
+    }
+
+    info!("Restored {} from {}", issue.path, candidate.display());
+    Ok(true)
+}
+
+/// Restores the given entries from a copy of the images directory.
+/// Returns how many entries were restored.
+pub async fn restore_from_backup(
+    backup_root: PathBuf,
+    issues: Vec<IntegrityIssue>,
+) -> Result<usize, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut restored = 0;
+        for issue in &issues {
+            match restore_entry(&backup_root, issue) {
+                Ok(true) => restored += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to restore {}: {}", issue.path, e),
+            }
+        }
+        restored
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,24 @@
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+
+static RUNNING: Lazy<watch::Sender<usize>> = Lazy::new(|| watch::channel(0).0);
+
+/// A background job in progress, counted until dropped
+pub struct Job(());
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        RUNNING.send_modify(|running| *running = running.saturating_sub(1));
+    }
+}
+
+/// Counts a job as running for as long as the returned guard lives
+pub fn start() -> Job {
+    RUNNING.send_modify(|running| *running += 1);
+    Job(())
+}
+
+/// Receives the number of running jobs whenever it changes
+pub fn subscribe() -> watch::Receiver<usize> {
+    RUNNING.subscribe()
+}
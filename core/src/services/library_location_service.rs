@@ -0,0 +1,414 @@
+//! Moves the library between the folder of the executable (portable mode) and the
+//! per-user data directory. The database can't move while it's open, so a move is
+//! asked for with a request file and carried out at the next start, before the
+//! config and the database are read. The stored paths point into the old folder
+//! until [`finish_move`] rewrites them once the database is open.
+//!
+//! A journal left in the old folder before anything moves lists the entries moved
+//! so far. A failed move is rolled back, and one cut short by a crash is finished
+//! from the journal at the next start.
+
+use crate::models::{image, image_version};
+use crate::services::connection_db::db_ref;
+use crate::services::atomic_file;
+use crate::services::database_service::BACKUP_PREFIX;
+use crate::utils::{
+    DATABASE_FILE, PORTABLE_FLAG, get_data_dir, get_exe_dir, resolve_data_location, user_data_dir,
+};
+use log::{error, info, warn};
+use sea_orm::sea_query::Expr;
+use sea_orm::{DbErr, EntityTrait, IdenStatic, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
+
+/// Left in the library folder by the preferences, read at the next start
+const MOVE_REQUEST: &str = "library_move.json";
+/// Left in the new library folder until the stored paths are rewritten
+const MOVED_FROM: &str = "library_moved.json";
+/// Left in the old library folder while a move is under way
+const MOVE_JOURNAL: &str = "library_move_journal.json";
+/// Suffix of an entry still being copied into the new folder
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// What makes up the library besides the database backups
+const LIBRARY_ENTRIES: [&str; 10] = [
+    DATABASE_FILE,
+    "organizer.db-wal",
+    "organizer.db-shm",
+    "images",
+    "trash",
+    "versions",
+    "config",
+    "import_jobs.json",
+    "maintenance.json",
+    "updates",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MoveRequest {
+    /// Next to the executable, or to the per-user data directory
+    portable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MoveJournal {
+    source: PathBuf,
+    target: PathBuf,
+    portable: bool,
+    /// Entries already in the target folder, by name
+    moved: Vec<String>,
+}
+
+impl MoveJournal {
+    fn path(&self) -> PathBuf {
+        self.source.join(MOVE_JOURNAL)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        atomic_file::write(&self.path(), content).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MovedFrom {
+    from: PathBuf,
+}
+
+/// Whether the library can change folders. Development builds always keep it
+/// next to the executable.
+pub fn can_move() -> bool {
+    !cfg!(debug_assertions) && user_data_dir().is_some()
+}
+
+/// Folder the library goes to, if a move is waiting for the next start
+pub fn pending_move() -> Option<PathBuf> {
+    let request = read_json::<MoveRequest>(&get_data_dir().join(MOVE_REQUEST))?;
+    target_dir(request.portable)
+}
+
+/// Asks for the library to move at the next start, next to the executable when
+/// `portable` or to the per-user data directory otherwise
+pub fn request_move(portable: bool) -> Result<PathBuf, String> {
+    let target = target_dir(portable).ok_or("The platform has no per-user data directory")?;
+    if target == get_data_dir() {
+        return Err(format!("The library is already in {}", target.display()));
+    }
+    let content = serde_json::to_string(&MoveRequest { portable }).map_err(|e| e.to_string())?;
+    fs::write(get_data_dir().join(MOVE_REQUEST), content).map_err(|e| e.to_string())?;
+    info!("Library move to {} requested", target.display());
+    Ok(target)
+}
+
+/// Drops a move asked for with [`request_move`]
+pub fn cancel_move() -> io::Result<()> {
+    match fs::remove_file(get_data_dir().join(MOVE_REQUEST)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Keeps a library stored next to the executable where it is, in portable mode
+pub fn keep_portable() -> io::Result<()> {
+    fs::write(get_exe_dir().join(PORTABLE_FLAG), "")
+}
+
+/// Carries out the move asked for at the last run, or finishes one a crash cut
+/// short. Has to run before anything reads the config or opens the database, and
+/// only once this run holds the single instance. Returns the new folder, if the
+/// library moved.
+pub fn apply_pending_move() -> Option<Result<PathBuf, String>> {
+    // The library may already be split between the two folders, so the journal is
+    // looked for in both
+    if let Some(journal) = [Some(get_exe_dir()), user_data_dir()]
+        .into_iter()
+        .flatten()
+        .find_map(|dir| read_json::<MoveJournal>(&dir.join(MOVE_JOURNAL)))
+    {
+        info!(
+            "Resuming the library move from {} to {}",
+            journal.source.display(),
+            journal.target.display()
+        );
+        let target = journal.target.clone();
+        return Some(resume_move(journal).map(|()| target));
+    }
+
+    let (location, source) = resolve_data_location();
+    let request_path = source.join(MOVE_REQUEST);
+    let request = read_json::<MoveRequest>(&request_path)?;
+
+    let target = target_dir(request.portable)?;
+    if target == source {
+        if let Err(e) = fs::remove_file(&request_path) {
+            warn!("Failed to remove {}: {}", request_path.display(), e);
+        }
+        return None;
+    }
+    info!(
+        "Moving the library from {} ({:?}) to {}",
+        source.display(),
+        location,
+        target.display()
+    );
+    Some(move_library(&source, &target, request.portable).map(|()| target))
+}
+
+/// Moves the library, putting back what already moved when an entry fails. The
+/// request stays until the move is done, so a move rolled back is tried again at
+/// the next start.
+fn move_library(source: &Path, target: &Path, portable: bool) -> Result<(), String> {
+    fs::create_dir_all(target).map_err(|e| e.to_string())?;
+    let entries = library_entries(source);
+    if let Some(taken) = entries
+        .iter()
+        .filter_map(|entry| entry.file_name())
+        .find(|name| target.join(name).exists())
+    {
+        return Err(format!(
+            "{} already has {}",
+            target.display(),
+            taken.to_string_lossy()
+        ));
+    }
+
+    let mut journal = MoveJournal {
+        source: source.to_path_buf(),
+        target: target.to_path_buf(),
+        portable,
+        moved: Vec::new(),
+    };
+    journal.save()?;
+
+    if let Err(e) = move_entries(&mut journal) {
+        return match roll_back(&journal) {
+            Ok(()) => Err(e),
+            Err(rollback) => {
+                error!("Failed to roll back the library move: {}", rollback);
+                Err(format!("{}, the move is finished at the next start", e))
+            }
+        };
+    }
+    complete_move(&journal)
+}
+
+/// Finishes a move from its journal, moving the entries still in the old folder
+fn resume_move(mut journal: MoveJournal) -> Result<(), String> {
+    move_entries(&mut journal)?;
+    complete_move(&journal)
+}
+
+/// Moves the entries of the old folder the journal doesn't list yet, noting each
+/// one as soon as it's in the new folder
+fn move_entries(journal: &mut MoveJournal) -> Result<(), String> {
+    for entry in library_entries(&journal.source) {
+        let Some(name) = entry.file_name().map(|name| name.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        // A new move checked the target had none of them, so one there already was
+        // copied over before a crash cut the removal of the original short
+        if journal.target.join(&name).exists() {
+            remove_entry(&entry)
+                .map_err(|e| format!("Failed to remove {}: {}", entry.display(), e))?;
+        } else {
+            move_entry(&entry, &journal.target.join(&name))
+                .map_err(|e| format!("Failed to move {}: {}", entry.display(), e))?;
+        }
+        if !journal.moved.contains(&name) {
+            journal.moved.push(name);
+        }
+        journal.save()?;
+    }
+    Ok(())
+}
+
+/// Moves the entries the journal lists back to the old folder, last moved first
+fn roll_back(journal: &MoveJournal) -> Result<(), String> {
+    for name in journal.moved.iter().rev() {
+        let entry = journal.target.join(name);
+        if entry.exists() {
+            move_entry(&entry, &journal.source.join(name))
+                .map_err(|e| format!("Failed to move {} back: {}", entry.display(), e))?;
+        }
+    }
+    fs::remove_file(journal.path()).map_err(|e| e.to_string())
+}
+
+/// Leaves the marker [`finish_move`] reads, sets the portable flag and drops the
+/// journal and the request, in that order so a crash in between repeats it
+fn complete_move(journal: &MoveJournal) -> Result<(), String> {
+    let moved = MovedFrom {
+        from: journal.source.clone(),
+    };
+    let content = serde_json::to_string(&moved).map_err(|e| e.to_string())?;
+    atomic_file::write(&journal.target.join(MOVED_FROM), content).map_err(|e| e.to_string())?;
+
+    let flag = get_exe_dir().join(PORTABLE_FLAG);
+    if journal.portable {
+        fs::write(&flag, "").map_err(|e| e.to_string())?;
+    } else if flag.exists()
+        && let Err(e) = fs::remove_file(&flag)
+    {
+        warn!("Failed to remove {}: {}", flag.display(), e);
+    }
+
+    fs::remove_file(journal.path()).map_err(|e| e.to_string())?;
+    let request = journal.source.join(MOVE_REQUEST);
+    if request.exists()
+        && let Err(e) = fs::remove_file(&request)
+    {
+        warn!("Failed to remove {}: {}", request.display(), e);
+    }
+    Ok(())
+}
+
+/// Points the stored paths at the folder the library moved to. Returns how many
+/// rows changed, nothing when the library didn't move.
+pub async fn finish_move() -> Result<u64, DbErr> {
+    let marker = get_data_dir().join(MOVED_FROM);
+    let Some(moved) = read_json::<MovedFrom>(&marker) else {
+        return Ok(0);
+    };
+    let changed = rewrite_paths(&moved.from, &get_data_dir()).await?;
+    if let Err(e) = fs::remove_file(&marker) {
+        warn!("Failed to remove {}: {}", marker.display(), e);
+    }
+    info!("Library move finished, {} stored paths rewritten", changed);
+    Ok(changed)
+}
+
+/// Replaces the `from` prefix of the stored image and version paths with `to`
+pub async fn rewrite_paths(from: &Path, to: &Path) -> Result<u64, DbErr> {
+    let db = db_ref();
+    // With the separator, so a sibling folder sharing the name's start is left alone
+    let from = format!("{}{}", from.to_string_lossy(), MAIN_SEPARATOR);
+    let to = format!("{}{}", to.to_string_lossy(), MAIN_SEPARATOR);
+    let prefix_len = from.chars().count() as i64;
+    // SQLite counts characters in substr, as chars() does
+    let rewrite = |column: &str| {
+        Expr::cust_with_values(
+            format!("? || substr({}, ?)", column),
+            [to.clone().into(), sea_orm::Value::from(prefix_len + 1)],
+        )
+    };
+    let starts_with = |column: &str| {
+        Expr::cust_with_values(
+            format!("substr({}, 1, ?) = ?", column),
+            [sea_orm::Value::from(prefix_len), from.clone().into()],
+        )
+    };
+
+    let mut changed = 0;
+    for column in [image::Column::Path, image::Column::ThumbnailPath] {
+        let name = column.as_str();
+        changed += image::Entity::update_many()
+            .col_expr(column, rewrite(name))
+            .filter(starts_with(name))
+            .exec(db)
+            .await?
+            .rows_affected;
+    }
+    for column in [
+        image_version::Column::Path,
+        image_version::Column::OriginalPath,
+    ] {
+        let name = column.as_str();
+        changed += image_version::Entity::update_many()
+            .col_expr(column, rewrite(name))
+            .filter(starts_with(name))
+            .exec(db)
+            .await?
+            .rows_affected;
+    }
+    Ok(changed)
+}
+
+fn target_dir(portable: bool) -> Option<PathBuf> {
+    if portable {
+        Some(get_exe_dir())
+    } else {
+        user_data_dir()
+    }
+}
+
+/// Parts of the library found in `dir`, the database backups included
+fn library_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = LIBRARY_ENTRIES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.exists())
+        .collect();
+    entries.extend(
+        fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(BACKUP_PREFIX)
+            })
+            .map(|entry| entry.path()),
+    );
+    entries
+}
+
+/// Renames the file or folder, copying it across drives when a rename can't. A
+/// copy is made under a `.partial` name and renamed once complete, so the target
+/// name only ever holds a whole entry.
+fn move_entry(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    let mut partial = to.as_os_str().to_owned();
+    partial.push(PARTIAL_SUFFIX);
+    let partial = PathBuf::from(partial);
+    // Left by a copy a crash cut short
+    if partial.exists() {
+        remove_entry(&partial)?;
+    }
+
+    let copied = if from.is_dir() {
+        copy_dir(from, &partial)
+    } else {
+        fs::copy(from, &partial).map(|_| ())
+    };
+    if let Err(e) = copied.and_then(|()| fs::rename(&partial, to)) {
+        let _ = remove_entry(&partial);
+        return Err(e);
+    }
+    remove_entry(from)
+}
+
+fn remove_entry(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .inspect_err(|e| warn!("Ignoring unreadable {}: {}", path.display(), e))
+        .ok()
+}
@@ -0,0 +1,263 @@
+use crate::config::get_settings;
+use crate::models::image::{ActiveModel, Column, Entity};
+use crate::models::maintenance::{MaintenanceTask, TaskRun};
+use crate::services::connection_db::db_ref;
+use crate::services::{
+    atomic_file, database_service, file_service, image_service, integrity_service, job_service,
+};
+use crate::utils::get_data_dir;
+use log::{error, info, warn};
+use once_cell::sync::Lazy;
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QuerySelect, Set};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Serializes the reads and writes of the state file
+static STATE: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Set while tasks run, so a slow run is not started again by the next check
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn state_path() -> PathBuf {
+    get_data_dir().join("maintenance.json")
+}
+
+/// Last run of every task that ran at least once
+pub fn last_runs() -> BTreeMap<MaintenanceTask, TaskRun> {
+    let _guard = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    read_state()
+}
+
+fn read_state() -> BTreeMap<MaintenanceTask, TaskRun> {
+    let Ok(content) = fs::read_to_string(state_path()) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        warn!("Ignoring unreadable maintenance state: {}", err);
+        BTreeMap::new()
+    })
+}
+
+fn record(task: MaintenanceTask, run: &TaskRun) {
+    let _guard = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = read_state();
+    state.insert(task, run.clone());
+    let result = serde_json::to_string_pretty(&state)
+        .map_err(std::io::Error::other)
+        .and_then(|json| atomic_file::write(&state_path(), json));
+    if let Err(err) = result {
+        error!("Failed to save maintenance state: {}", err);
+    }
+}
+
+/// Runs the enabled tasks whose interval passed since their last run
+pub async fn run_due() -> Vec<(MaintenanceTask, TaskRun)> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Vec::new();
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let last_runs = last_runs();
+    let due: Vec<MaintenanceTask> = {
+        let config = &get_settings().config;
+        MaintenanceTask::ALL
+            .into_iter()
+            .filter(|task| {
+                let schedule = config.maintenance_schedule(*task);
+                let interval = i64::from(schedule.interval_hours.max(1)) * 3600;
+                schedule.enabled
+                    && last_runs
+                        .get(task)
+                        .is_none_or(|run| now - run.finished_at >= interval)
+            })
+            .collect()
+    };
+
+    let mut runs = Vec::new();
+    for task in due {
+        runs.push((task, execute(task).await));
+    }
+    RUNNING.store(false, Ordering::SeqCst);
+    runs
+}
+
+/// Runs a task now, whatever its schedule
+pub async fn run(task: MaintenanceTask) -> TaskRun {
+    execute(task).await
+}
+
+async fn execute(task: MaintenanceTask) -> TaskRun {
+    info!("Running maintenance task {:?}", task);
+    let result = match task {
+        MaintenanceTask::PruneThumbnails => prune_thumbnails().await,
+        MaintenanceTask::Backup => database_service::backup_database()
+            .await
+            .map(|_| 0)
+            .map_err(|e| e.to_string()),
+        MaintenanceTask::VerifyIntegrity => integrity_service::verify_library()
+            .await
+            .map(|report| report.issues.len()),
+        MaintenanceTask::CleanOrphans => clean_orphans().await,
+        MaintenanceTask::RepairFolders => repair_folders().await,
+    };
+
+    if let Err(err) = &result {
+        error!("Maintenance task {:?} failed: {}", task, err);
+    }
+    let run = TaskRun {
+        finished_at: chrono::Utc::now().timestamp(),
+        count: result.as_ref().copied().unwrap_or_default(),
+        error: result.err(),
+    };
+    record(task, &run);
+    run
+}
+
+/// Ids of the stored entries
+async fn entry_ids() -> Result<HashSet<i64>, String> {
+    let ids: Vec<i64> = Entity::find()
+        .select_only()
+        .column(Column::Id)
+        .into_tuple()
+        .all(db_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(ids.into_iter().collect())
+}
+
+/// Entry folders directly under `root`, with the id they are named after
+fn entry_folders(root: &Path) -> Vec<(i64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let id = path.file_name()?.to_str()?.parse().ok()?;
+            Some((id, path))
+        })
+        .collect()
+}
+
+async fn prune_thumbnails() -> Result<usize, String> {
+    let _job = job_service::start();
+    let thumbnails: Vec<String> = Entity::find()
+        .select_only()
+        .column(Column::ThumbnailPath)
+        .into_tuple()
+        .all(db_ref())
+        .await
+        .map_err(|e| e.to_string())?;
+    let keep: HashSet<String> = thumbnails.into_iter().collect();
+
+    let removed = tokio::task::spawn_blocking(move || {
+        entry_folders(&get_data_dir().join("images"))
+            .into_iter()
+            .map(
+                |(_, folder)| match file_service::prune_derived_files(&folder, &keep) {
+                    Ok(removed) => removed,
+                    Err(e) => {
+                        warn!("Failed to prune {}: {}", folder.display(), e);
+                        0
+                    }
+                },
+            )
+            .sum()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    info!("Pruned {} thumbnails and previews", removed);
+    Ok(removed)
+}
+
+/// Removes the image and version folders of entries that no longer exist
+async fn clean_orphans() -> Result<usize, String> {
+    let _job = job_service::start();
+    let ids = entry_ids().await?;
+
+    let removed = tokio::task::spawn_blocking(move || {
+        let root = get_data_dir();
+        let mut removed = 0;
+        for folder in ["images", "versions"] {
+            for (id, path) in entry_folders(&root.join(folder)) {
+                if ids.contains(&id) {
+                    continue;
+                }
+                match fs::remove_dir_all(&path) {
+                    Ok(()) => {
+                        info!("Removed orphan folder {}", path.display());
+                        removed += 1;
+                    }
+                    Err(e) => warn!("Failed to remove orphan folder {}: {}", path.display(), e),
+                }
+            }
+        }
+        removed
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(removed)
+}
+
+/// Repairs the order and thumbnails of every folder entry, see
+/// [`file_service::repair_folder_entry`]. Returns how many entries changed.
+async fn repair_folders() -> Result<usize, String> {
+    let _job = job_service::start();
+    let db = db_ref();
+    let folders: Vec<(i64, String, String)> = image_service::not_trashed()
+        .select_only()
+        .column(Column::Id)
+        .column(Column::Path)
+        .column(Column::ThumbnailPath)
+        .filter(Column::IsFolder.eq(true))
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut repaired = 0;
+    for (id, path, thumbnail_path) in folders {
+        let image_dir = PathBuf::from(&path);
+        let result = tokio::task::spawn_blocking(move || {
+            file_service::repair_folder_entry(&image_dir).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        let repair = match result {
+            Ok(repair) => repair,
+            Err(e) => {
+                warn!("Failed to repair folder entry {}: {}", id, e);
+                continue;
+            }
+        };
+        if !repair.reordered && repair.thumbnails == 0 {
+            continue;
+        }
+        repaired += 1;
+
+        if !repair.cover_thumb.is_empty() && repair.cover_thumb != thumbnail_path {
+            let entry = ActiveModel {
+                id: Set(id),
+                thumbnail_path: Set(repair.cover_thumb),
+                ..Default::default()
+            };
+            entry.update(db).await.map_err(|e| e.to_string())?;
+        }
+        // The renamed files are the entry's content now, not a change to report
+        if repair.reordered
+            && let Err(e) = integrity_service::rehash_file(path).await
+        {
+            warn!("Failed to update the hash of folder entry {}: {}", id, e);
+        }
+    }
+
+    info!("Repaired {} folder entries", repaired);
+    Ok(repaired)
+}
@@ -0,0 +1,35 @@
+pub mod activity_service;
+pub mod atomic_file;
+pub mod image_service;
+pub mod file_service;
+pub mod file_naming;
+pub mod folder_meta;
+pub mod desc_query;
+pub mod fuzzy_match;
+pub mod query_parser;
+pub mod change_service;
+pub mod connection_db;
+pub mod similarity_service;
+pub mod statistics_service;
+pub mod stack_service;
+pub mod tag_service;
+pub mod tag_settings_service;
+pub mod thumbnail_renderer;
+pub mod database_service;
+pub mod dedup_service;
+pub mod deep_link_service;
+pub mod download_service;
+pub mod export_service;
+pub mod toast_service;
+pub mod trash_service;
+pub mod update_service;
+pub mod image_processor;
+pub mod import_service;
+pub mod integrity_service;
+pub mod job_service;
+pub mod library_location_service;
+pub mod maintenance_service;
+pub mod quarantine_service;
+pub mod rule_service;
+pub mod version_service;
+pub mod wallpaper_service;
@@ -0,0 +1,87 @@
+use crate::dtos::quarantine_dto::QuarantinedFileDTO;
+use crate::models::quarantined_file::{ActiveModel, Column, Entity, Model};
+use crate::services::change_service::{self, DataChange};
+use crate::services::connection_db::db_ref;
+use log::{error, info};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+
+/// Lists files that failed to decode during an import of the folder entry.
+/// A file already listed only gets its reason updated.
+pub async fn add(image_id: Option<i64>, files: &[(String, String)]) {
+    if files.is_empty() {
+        return;
+    }
+
+    for (path, reason) in files {
+        if let Err(e) = upsert(image_id, path, reason).await {
+            error!("Failed to quarantine {}: {}", path, e);
+        }
+    }
+    info!("Quarantined {} unreadable files", files.len());
+    change_service::notify(DataChange::Images);
+}
+
+async fn upsert(image_id: Option<i64>, path: &str, reason: &str) -> Result<(), DbErr> {
+    let db = db_ref();
+    let existing = Entity::find()
+        .filter(Column::Path.eq(path))
+        .filter(match image_id {
+            Some(id) => Column::ImageId.eq(id),
+            None => Column::ImageId.is_null(),
+        })
+        .one(db)
+        .await?;
+
+    match existing {
+        Some(model) => {
+            let mut active: ActiveModel = model.into();
+            active.reason = Set(reason.to_string());
+            active.update(db).await?;
+        }
+        None => {
+            ActiveModel {
+                path: Set(path.to_string()),
+                image_id: Set(image_id),
+                reason: Set(reason.to_string()),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Quarantined files, newest first
+pub async fn find_all() -> Result<Vec<QuarantinedFileDTO>, DbErr> {
+    let files = Entity::find()
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .all(db_ref())
+        .await?;
+
+    Ok(files.iter().map(to_dto).collect())
+}
+
+pub async fn count() -> Result<u64, DbErr> {
+    Entity::find().count(db_ref()).await
+}
+
+/// Removes a file from the list, the file itself is left where it is
+pub async fn discard(id: i64) -> Result<(), DbErr> {
+    Entity::delete_by_id(id).exec(db_ref()).await?;
+    change_service::notify(DataChange::Images);
+    Ok(())
+}
+
+fn to_dto(model: &Model) -> QuarantinedFileDTO {
+    QuarantinedFileDTO {
+        id: model.id,
+        path: model.path.clone(),
+        image_id: model.image_id,
+        reason: model.reason.clone(),
+        created_at: model.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
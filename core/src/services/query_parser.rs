@@ -0,0 +1,141 @@
+//! Operators typed into the search bar along with the description query:
+//!
+//! - `tag:foo` only images tagged `foo`, `-tag:bar` leaves out those tagged `bar`
+//! - `before:2024-01-01` images added before that day, `after:2024-01-01` on or after it
+//! - `is:folder` only folder entries, `-is:folder` only single images
+//! - `category:character` only images with a tag of that category
+//!
+//! Values with spaces go in quotes, `tag:"old photos"`. Everything else, including
+//! an operator that can't be read, is left to the description search.
+
+use crate::models::filter::Filter;
+use chrono::NaiveDate;
+
+/// A search bar query taken apart, see the module docs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedQuery {
+    /// What's left for the description search
+    pub text: String,
+    pub tags: Vec<String>,
+    pub excluded_tags: Vec<String>,
+    pub before: Option<NaiveDate>,
+    pub after: Option<NaiveDate>,
+    pub is_folder: Option<bool>,
+    pub category: Option<String>,
+}
+
+impl ParsedQuery {
+    /// Adds the operators to the filter, narrowing what it already holds
+    pub fn apply(self, filter: &mut Filter) {
+        filter.required_tags.extend(self.tags);
+        filter.excluded_tags.extend(self.excluded_tags);
+        filter.before = earliest(filter.before, self.before);
+        filter.after = latest(filter.after, self.after);
+        if self.is_folder.is_some() {
+            filter.is_folder = self.is_folder;
+        }
+        if self.category.is_some() {
+            filter.category = self.category;
+        }
+    }
+}
+
+/// Takes the operators out of the query and refinements of the filter, leaving
+/// them the text to search descriptions for
+pub fn apply_operators(filter: &mut Filter) {
+    let query = parse(&filter.query);
+    filter.query = query.text.clone();
+    query.apply(filter);
+
+    let refinements = std::mem::take(&mut filter.refinements);
+    for step in refinements {
+        let step = parse(&step);
+        filter.refinements.push(step.text.clone());
+        step.apply(filter);
+    }
+}
+
+/// Takes the operators out of a search bar query
+pub fn parse(input: &str) -> ParsedQuery {
+    let mut parsed = ParsedQuery::default();
+    let mut text = Vec::new();
+
+    for token in tokens(input) {
+        let (negated, operator) = match token.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, token),
+        };
+        let Some((name, value)) = operator.split_once(':') else {
+            text.push(token);
+            continue;
+        };
+        let value = value.trim_matches('"');
+        let understood = match (name.to_lowercase().as_str(), negated) {
+            ("tag", _) if !value.is_empty() => {
+                let tag = value.to_lowercase();
+                if negated {
+                    parsed.excluded_tags.push(tag);
+                } else {
+                    parsed.tags.push(tag);
+                }
+                true
+            }
+            ("before", false) => date(value)
+                .map(|day| parsed.before = earliest(parsed.before, Some(day)))
+                .is_some(),
+            ("after", false) => date(value)
+                .map(|day| parsed.after = latest(parsed.after, Some(day)))
+                .is_some(),
+            ("category", false) if !value.is_empty() => {
+                parsed.category = Some(value.to_lowercase());
+                true
+            }
+            ("is", _) if value.eq_ignore_ascii_case("folder") => {
+                parsed.is_folder = Some(!negated);
+                true
+            }
+            _ => false,
+        };
+        if !understood {
+            text.push(token);
+        }
+    }
+
+    parsed.text = text.join(" ");
+    parsed
+}
+
+/// Words of the query, a quoted part staying in one piece with its quotes
+fn tokens(input: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (index, c) in input.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if let Some(from) = start.take() {
+                tokens.push(&input[from..index]);
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(from) = start {
+        tokens.push(&input[from..]);
+    }
+    tokens
+}
+
+fn date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+fn earliest(a: Option<NaiveDate>, b: Option<NaiveDate>) -> Option<NaiveDate> {
+    a.into_iter().chain(b).min()
+}
+
+fn latest(a: Option<NaiveDate>, b: Option<NaiveDate>) -> Option<NaiveDate> {
+    a.into_iter().chain(b).max()
+}
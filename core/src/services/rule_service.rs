@@ -0,0 +1,77 @@
+use crate::config::get_settings;
+use crate::dtos::image_dto::ImageUpdateDTO;
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::import_rule::ImportedFile;
+use crate::services::{image_service, tag_service};
+use log::{error, info};
+use sea_orm::DbErr;
+use std::collections::HashSet;
+
+/// Combined effect of the import rules matching any of the imported files
+#[derive(Debug, Default)]
+pub struct RuleOutcome {
+    pub tag_ids: HashSet<i64>,
+    /// Language of the last matching rule that sets one
+    pub language: Option<String>,
+    pub pin: bool,
+    /// Names of the rules that matched
+    pub rules: Vec<String>,
+}
+
+/// Runs the configured import rules over the files of one entry
+pub fn evaluate(files: &[ImportedFile]) -> RuleOutcome {
+    let settings = get_settings();
+    let mut outcome = RuleOutcome::default();
+
+    for rule in settings.config.import_rules.iter().flatten() {
+        if !files.iter().any(|file| rule.matches(file)) {
+            continue;
+        }
+        outcome.tag_ids.extend(rule.tag_ids.iter().copied());
+        if let Some(language) = rule.language.as_ref().filter(|l| !l.trim().is_empty()) {
+            outcome.language = Some(language.clone());
+        }
+        outcome.pin |= rule.pin;
+        outcome.rules.push(rule.name.clone());
+    }
+    outcome
+}
+
+impl RuleOutcome {
+    /// Adds the rule tags and language to the update storing the imported entry.
+    /// `tags` are the ones the entry gets without the rules.
+    pub async fn merge_into(
+        &self,
+        dto: &mut ImageUpdateDTO,
+        tags: HashSet<TagDTO>,
+    ) -> Result<(), DbErr> {
+        if self.rules.is_empty() {
+            return Ok(());
+        }
+        info!("Import rules matched: {}", self.rules.join(", "));
+
+        let mut tags = tags;
+        if !self.tag_ids.is_empty() {
+            tags.extend(
+                tag_service::find_all()
+                    .await?
+                    .into_iter()
+                    .filter(|tag| self.tag_ids.contains(&tag.id)),
+            );
+        }
+        dto.tags = Some(tags);
+        if self.language.is_some() {
+            dto.language = self.language.clone();
+        }
+        Ok(())
+    }
+
+    /// Applies what cannot go through the update, once the entry is stored
+    pub async fn finish(&self, image_id: i64) {
+        if self.pin
+            && let Err(err) = image_service::set_pinned(image_id, true).await
+        {
+            error!("Failed to pin {} by import rule: {}", image_id, err);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::dtos::image_dto::ImageDTO;
+use crate::models::{image, image_tag};
+use crate::services::connection_db::db_ref;
+use crate::services::file_service::THUMBNAIL_SIZE;
+use crate::services::image_processor::{Frame, Pipeline};
+use crate::services::image_service::{not_trashed, to_dto};
+use crate::services::tag_service::get_tags_for_images;
+use ::image::DynamicImage;
+use ::image::imageops::FilterType;
+use log::warn;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter};
+use std::collections::{HashMap, HashSet};
+
+/// Hash bits two images may differ by and still count as looking alike
+const MAX_DISTANCE: u32 = 20;
+
+/// Difference hash, each bit tells whether a pixel is darker than its right neighbour
+pub fn perceptual_hash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    hash
+}
+
+/// Hash of the thumbnail the image gets, the same picture [`fill_missing_hashes`]
+/// reads back, so hashes taken at registration and later compare alike
+pub fn thumbnail_hash(image: &DynamicImage) -> u64 {
+    let pipeline = Pipeline::thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE, 0);
+    match pipeline.process(Frame::borrowed(image)) {
+        Ok(frame) => perceptual_hash(&frame.image),
+        Err(e) => {
+            warn!("Could not render the thumbnail to hash: {}", e);
+            perceptual_hash(image)
+        }
+    }
+}
+
+/// Images most alike the given one, by look or by shared tags, best match first
+pub async fn find_similar(image_id: i64, limit: usize) -> Result<Vec<ImageDTO>, DbErr> {
+    let db = db_ref();
+    let mut models = not_trashed()
+        .filter(image::Column::IsPrepared.eq(true))
+        .all(db)
+        .await?;
+    fill_missing_hashes(&mut models).await?;
+
+    let mut tags: HashMap<i64, HashSet<i64>> = HashMap::new();
+    for link in image_tag::Entity::find().all(db).await? {
+        tags.entry(link.image_id).or_default().insert(link.tag_id);
+    }
+
+    let Some(target) = models.iter().find(|m| m.id == image_id) else {
+        return Ok(Vec::new());
+    };
+    let no_tags = HashSet::new();
+    let target_tags = tags.get(&image_id).unwrap_or(&no_tags);
+    let target_hash = target.perceptual_hash;
+
+    let mut scored: Vec<(f32, image::Model)> = models
+        .iter()
+        .filter(|m| m.id != image_id)
+        .filter_map(|m| {
+            let visual = match (target_hash, m.perceptual_hash) {
+                (Some(a), Some(b)) => {
+                    let distance = (a ^ b).count_ones();
+                    if distance <= MAX_DISTANCE {
+                        1.0 - distance as f32 / 64.0
+                    } else {
+                        0.0
+                    }
+                }
+                _ => 0.0,
+            };
+
+            let image_tags = tags.get(&m.id).unwrap_or(&no_tags);
+            let shared = target_tags.intersection(image_tags).count();
+            let all = target_tags.union(image_tags).count();
+            let tag_wise = if all == 0 {
+                0.0
+            } else {
+                shared as f32 / all as f32
+            };
+
+            let score = visual.max(tag_wise);
+            (score > 0.0).then(|| (score, m.clone()))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.id.cmp(&a.1.id)));
+    scored.truncate(limit);
+
+    let similar: Vec<image::Model> = scored.into_iter().map(|(_, m)| m).collect();
+    let image_ids: Vec<i64> = similar.iter().map(|m| m.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+    Ok(to_dto(similar, tags_map))
+}
+
+/// Hashes the thumbnails of the images that have no hash yet and stores the results
+pub(crate) async fn fill_missing_hashes(models: &mut [image::Model]) -> Result<(), DbErr> {
+    let missing: Vec<(i64, String)> = models
+        .iter()
+        .filter(|m| m.perceptual_hash.is_none() && !m.thumbnail_path.is_empty())
+        .map(|m| (m.id, m.thumbnail_path.clone()))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let hashes = tokio::task::spawn_blocking(move || {
+        missing
+            .into_iter()
+            .filter_map(|(id, path)| match ::image::open(&path) {
+                Ok(img) => Some((id, perceptual_hash(&img) as i64)),
+                Err(e) => {
+                    warn!("Could not hash thumbnail {}: {}", path, e);
+                    None
+                }
+            })
+            .collect::<HashMap<i64, i64>>()
+    })
+    .await
+    .map_err(|e| DbErr::Custom(e.to_string()))?;
+
+    let db = db_ref();
+    for (id, hash) in &hashes {
+        image::Entity::update_many()
+            .col_expr(image::Column::PerceptualHash, (*hash).into())
+            .filter(image::Column::Id.eq(*id))
+            .exec(db)
+            .await?;
+    }
+
+    for model in models.iter_mut() {
+        if let Some(hash) = hashes.get(&model.id) {
+            model.perceptual_hash = Some(*hash);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use crate::dtos::image_dto::ImageDTO;
+use crate::dtos::stack_dto::StackDTO;
+use crate::models::{image, stack, stack_image};
+use crate::services::change_service::{self, DataChange};
+use crate::services::connection_db::{db_ref, with_retry};
+use crate::services::image_service::to_dto;
+use crate::services::tag_service::get_tags_for_images;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, JoinType, Order, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, Set, TransactionTrait,
+};
+use std::collections::HashMap;
+
+/// Fills in the stack of each image and how many images that stack holds
+pub async fn annotate<C: ConnectionTrait>(images: &mut [ImageDTO], db: &C) -> Result<(), DbErr> {
+    if images.is_empty() {
+        return Ok(());
+    }
+
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+    let memberships: HashMap<i64, i64> = stack_image::Entity::find()
+        .filter(stack_image::Column::ImageId.is_in(image_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|member| (member.image_id, member.stack_id))
+        .collect();
+
+    if memberships.is_empty() {
+        return Ok(());
+    }
+
+    let sizes: HashMap<i64, i64> = stack_image::Entity::find()
+        .filter(stack_image::Column::StackId.is_in(memberships.values().copied()))
+        .select_only()
+        .column(stack_image::Column::StackId)
+        .column_as(stack_image::Column::ImageId.count(), "size")
+        .group_by(stack_image::Column::StackId)
+        .into_tuple::<(i64, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    for image in images {
+        if let Some(&stack_id) = memberships.get(&image.id) {
+            image.stack_id = Some(stack_id);
+            image.stack_size = sizes.get(&stack_id).copied().unwrap_or_default() as usize;
+        }
+    }
+    Ok(())
+}
+
+pub async fn find_stack(stack_id: i64) -> Result<Option<StackDTO>, DbErr> {
+    let db = db_ref();
+    let Some(stack) = stack::Entity::find_by_id(stack_id).one(db).await? else {
+        return Ok(None);
+    };
+
+    let images = image::Entity::find()
+        .join(
+            JoinType::InnerJoin,
+            stack_image::Relation::Image.def().rev(),
+        )
+        .filter(stack_image::Column::StackId.eq(stack_id))
+        .order_by(stack_image::Column::Position, Order::Asc)
+        .all(db)
+        .await?;
+
+    let image_ids: Vec<i64> = images.iter().map(|img| img.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+
+    let mut images = to_dto(images, tags_map);
+    let stack_size = images.len();
+    for image in &mut images {
+        image.stack_id = Some(stack_id);
+        image.stack_size = stack_size;
+    }
+
+    Ok(Some(StackDTO {
+        id: stack.id,
+        cover_image_id: stack.cover_image_id,
+        images,
+    }))
+}
+
+/// Stacks the images in the given order, returning the stack id.
+/// Images already in a stack bring the rest of their stack along; the first
+/// existing stack is kept, otherwise a new one is created with the first image as cover.
+pub async fn create(image_ids: Vec<i64>) -> Result<i64, DbErr> {
+    if image_ids.len() < 2 {
+        return Err(DbErr::Custom(
+            "A stack needs at least two images".to_string(),
+        ));
+    }
+
+    let db = db_ref();
+    let txn = db.begin().await?;
+
+    let memberships: HashMap<i64, i64> = stack_image::Entity::find()
+        .filter(stack_image::Column::ImageId.is_in(image_ids.clone()))
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|member| (member.image_id, member.stack_id))
+        .collect();
+
+    let stack_id = match image_ids.iter().find_map(|id| memberships.get(id)) {
+        Some(&stack_id) => stack_id,
+        None => {
+            let stack = stack::ActiveModel {
+                cover_image_id: Set(image_ids[0]),
+                ..Default::default()
+            };
+            stack::Entity::insert(stack)
+                .exec(&txn)
+                .await?
+                .last_insert_id
+        }
+    };
+
+    // Current members of every stack involved, in stacking order
+    let mut stack_members: HashMap<i64, Vec<i64>> = HashMap::new();
+    for member in stack_image::Entity::find()
+        .filter(stack_image::Column::StackId.is_in(memberships.values().copied()))
+        .order_by(stack_image::Column::Position, Order::Asc)
+        .all(&txn)
+        .await?
+    {
+        stack_members
+            .entry(member.stack_id)
+            .or_default()
+            .push(member.image_id);
+    }
+
+    let mut ordered = stack_members.remove(&stack_id).unwrap_or_default();
+    for id in &image_ids {
+        let members = match memberships.get(id) {
+            Some(other_stack) => stack_members.remove(other_stack).unwrap_or_default(),
+            None => vec![*id],
+        };
+        for member in members {
+            if !ordered.contains(&member) {
+                ordered.push(member);
+            }
+        }
+    }
+
+    // The merged stacks are emptied and removed, their images move to the kept one
+    stack_image::Entity::delete_many()
+        .filter(stack_image::Column::ImageId.is_in(ordered.clone()))
+        .exec(&txn)
+        .await?;
+    stack::Entity::delete_many()
+        .filter(stack::Column::Id.is_in(memberships.values().copied()))
+        .filter(stack::Column::Id.ne(stack_id))
+        .exec(&txn)
+        .await?;
+
+    let members = ordered
+        .iter()
+        .enumerate()
+        .map(|(position, image_id)| stack_image::ActiveModel {
+            image_id: Set(*image_id),
+            stack_id: Set(stack_id),
+            position: Set(position as i32),
+        });
+    stack_image::Entity::insert_many(members).exec(&txn).await?;
+
+    txn.commit().await?;
+    change_service::notify(DataChange::Images);
+    Ok(stack_id)
+}
+
+pub async fn set_cover(stack_id: i64, image_id: i64) -> Result<(), DbErr> {
+    with_retry(|| {
+        stack::Entity::update_many()
+            .col_expr(stack::Column::CoverImageId, image_id.into())
+            .filter(stack::Column::Id.eq(stack_id))
+            .exec(db_ref())
+    })
+    .await?;
+    change_service::notify(DataChange::Images);
+    Ok(())
+}
+
+/// Takes the image out of its stack. The stack moves its cover to the next image,
+/// or is removed once a single image is left.
+pub async fn remove_image(image_id: i64) -> Result<(), DbErr> {
+    let db = db_ref();
+    let txn = db.begin().await?;
+
+    let Some(member) = stack_image::Entity::find_by_id(image_id).one(&txn).await? else {
+        return Ok(());
+    };
+    stack_image::Entity::delete_by_id(image_id)
+        .exec(&txn)
+        .await?;
+
+    let remaining = stack_image::Entity::find()
+        .filter(stack_image::Column::StackId.eq(member.stack_id))
+        .order_by(stack_image::Column::Position, Order::Asc)
+        .all(&txn)
+        .await?;
+
+    match remaining.first() {
+        Some(next) if remaining.len() > 1 => {
+            stack::Entity::update_many()
+                .col_expr(stack::Column::CoverImageId, next.image_id.into())
+                .filter(stack::Column::Id.eq(member.stack_id))
+                .filter(stack::Column::CoverImageId.eq(image_id))
+                .exec(&txn)
+                .await?;
+        }
+        _ => {
+            stack::Entity::delete_by_id(member.stack_id)
+                .exec(&txn)
+                .await?;
+        }
+    }
+
+    txn.commit().await?;
+    change_service::notify(DataChange::Images);
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use crate::dtos::statistics_dto::{LibraryStatsDTO, StorageDTO};
+use crate::models::image::Column;
+use crate::services::connection_db::db_ref;
+use crate::services::{image_service, job_service, tag_service};
+use crate::utils::get_data_dir;
+use log::info;
+use sea_orm::{ColumnTrait, DbErr, PaginatorTrait, QueryFilter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Layout of the report, placeholders are written `{{name}}`
+const REPORT_TEMPLATE: &str = include_str!("../templates/library_report.html");
+
+/// Tags listed in the report
+const TOP_TAGS: u64 = 15;
+/// Months shown in the growth chart, the most recent ones
+const GROWTH_MONTHS: usize = 24;
+
+const CHART_WIDTH: usize = 820;
+const CHART_HEIGHT: usize = 200;
+const CHART_LABEL_HEIGHT: usize = 20;
+
+/// Gathers the figures of the report from the aggregate queries and the disk
+pub async fn collect() -> Result<LibraryStatsDTO, DbErr> {
+    let counts = image_service::count_library().await?;
+    let folders = image_service::not_trashed()
+        .filter(Column::IsPrepared.eq(true))
+        .filter(Column::IsFolder.eq(true))
+        .count(db_ref())
+        .await?;
+
+    let mut growth = image_service::count_by_month().await?;
+    growth.truncate(GROWTH_MONTHS);
+    growth.reverse();
+
+    let top_tags = tag_service::find_most_used(TOP_TAGS).await?;
+    let storage = tokio::task::spawn_blocking(measure_storage)
+        .await
+        .unwrap_or_default();
+
+    Ok(LibraryStatsDTO {
+        counts,
+        folders,
+        growth,
+        top_tags,
+        storage,
+    })
+}
+
+fn measure_storage() -> StorageDTO {
+    let root = get_data_dir();
+    let database = fs::read_dir(&root)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("organizer.db") || name.starts_with("database_backup_")
+        })
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum();
+
+    StorageDTO {
+        images: dir_size(&root.join("images")),
+        versions: dir_size(&root.join("versions")),
+        database,
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(_) => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Writes the library report as a standalone HTML page, printable to PDF from a browser
+pub async fn export_report(destination: PathBuf) -> Result<(), String> {
+    let _job = job_service::start();
+    let stats = collect().await.map_err(|e| e.to_string())?;
+    let html = render_report(&stats);
+    tokio::fs::write(&destination, html)
+        .await
+        .map_err(|e| e.to_string())?;
+    info!("Library report exported to {}", destination.display());
+    Ok(())
+}
+
+fn render_report(stats: &LibraryStatsDTO) -> String {
+    let generated = t!(
+        "report.generated",
+        date = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string()
+    );
+
+    let counts = [
+        (stats.counts.images, t!("report.count.images")),
+        (stats.folders, t!("report.count.folders")),
+        (stats.counts.untagged, t!("report.count.untagged")),
+        (stats.counts.tags, t!("report.count.tags")),
+    ]
+    .iter()
+    .map(|(value, label)| {
+        format!(
+            "<div class=\"card\"><div class=\"value\">{}</div><div class=\"label\">{}</div></div>",
+            value,
+            escape(label)
+        )
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    let top_tags = if stats.top_tags.is_empty() {
+        format!("<p>{}</p>", escape(&t!("report.no_tags")))
+    } else {
+        let rows: String = stats
+            .top_tags
+            .iter()
+            .map(|(name, count)| {
+                format!(
+                    "<tr><td>{}</td><td class=\"count\">{}</td></tr>\n",
+                    escape(name),
+                    count
+                )
+            })
+            .collect();
+        format!("<table>\n{}</table>", rows)
+    };
+
+    let storage = [
+        (t!("report.storage.images"), stats.storage.images),
+        (t!("report.storage.versions"), stats.storage.versions),
+        (t!("report.storage.database"), stats.storage.database),
+    ]
+    .iter()
+    .map(|(label, bytes)| {
+        format!(
+            "<tr><td>{}</td><td class=\"count\">{}</td></tr>\n",
+            escape(label),
+            format_size(*bytes)
+        )
+    })
+    .collect::<String>();
+
+    [
+        ("lang", rust_i18n::locale().to_string()),
+        ("title", escape(&t!("report.title"))),
+        ("generated", escape(&generated)),
+        ("counts", counts),
+        ("growth_title", escape(&t!("report.section.growth"))),
+        ("growth_chart", growth_chart(stats)),
+        ("tags_title", escape(&t!("report.section.top_tags"))),
+        ("top_tags", top_tags),
+        ("storage_title", escape(&t!("report.section.storage"))),
+        ("storage", format!("<table>\n{}</table>", storage)),
+    ]
+    .iter()
+    .fold(REPORT_TEMPLATE.to_string(), |html, (name, value)| {
+        html.replace(&format!("{{{{{}}}}}", name), value)
+    })
+}
+
+/// Bars of the images registered per month, as inline SVG
+fn growth_chart(stats: &LibraryStatsDTO) -> String {
+    if stats.growth.is_empty() {
+        return format!("<p>{}</p>", escape(&t!("report.no_images")));
+    }
+
+    let max = stats
+        .growth
+        .iter()
+        .map(|m| m.count)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    let slot = CHART_WIDTH / stats.growth.len();
+    let bar_width = (slot * 3 / 4).max(1);
+
+    let bars: String = stats
+        .growth
+        .iter()
+        .enumerate()
+        .map(|(index, month)| {
+            let height = (month.count as usize * CHART_HEIGHT) / max as usize;
+            let x = index * slot + (slot - bar_width) / 2;
+            format!(
+                "<rect x=\"{x}\" y=\"{y}\" width=\"{bar_width}\" height=\"{height}\" fill=\"#4a7bd0\"><title>{key}: {count}</title></rect>\
+                 <text x=\"{label_x}\" y=\"{label_y}\" text-anchor=\"middle\">{label_month:02}/{label_year:02}</text>\n",
+                y = CHART_HEIGHT - height,
+                key = month.key(),
+                count = month.count,
+                label_x = x + bar_width / 2,
+                label_y = CHART_HEIGHT + CHART_LABEL_HEIGHT - 6,
+                label_month = month.month,
+                label_year = month.year % 100,
+            )
+        })
+        .collect();
+
+    format!(
+        "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\n{}</svg>",
+        CHART_WIDTH,
+        CHART_HEIGHT + CHART_LABEL_HEIGHT,
+        bars
+    )
+}
+
+/// Size in bytes in the largest unit that keeps it above 1, like `3.4 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
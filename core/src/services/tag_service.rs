@@ -0,0 +1,541 @@
+use crate::dtos::tag_dto::{TagDTO, TagUpdateDTO};
+use crate::models::activity_action::ActivityAction;
+use crate::models::tag::{ActiveModel, Model};
+use crate::models::tag_color::TagColor;
+use crate::models::{image, image_tag, tag, tag_alias};
+use crate::services::activity_service;
+use crate::services::change_service::{self, DataChange};
+use crate::services::connection_db::{db_ref, with_retry};
+use crate::services::tag_service::image_tag::Entity;
+use crate::services::tag_service::tag::Entity as TagEntity;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{
+    prelude::*, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use once_cell::sync::Lazy;
+use std::cmp::Ordering as CmpOrdering;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Every tag, loaded on first use and dropped whenever a tag is created, renamed or deleted
+static TAG_CACHE: Lazy<RwLock<Option<HashSet<TagDTO>>>> = Lazy::new(|| RwLock::new(None));
+/// Bumped on invalidation so a query that started before a change doesn't refill the cache
+static TAG_CACHE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Drops the cache before telling the subscribers, so the tags they load are fresh
+fn invalidate_cache() {
+    TAG_CACHE_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut cache) = TAG_CACHE.write() {
+        *cache = None;
+    }
+    change_service::notify(DataChange::Tags);
+}
+
+pub async fn get_tags_for_images(
+    image_ids: &[i64],
+    db: &DatabaseConnection,
+) -> Result<HashMap<i64, HashSet<TagDTO>>, DbErr> {
+    if image_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let rows = image_tag::Entity::find()
+        .join(JoinType::InnerJoin, image_tag::Relation::Tag.def())
+        .filter(image_tag::Column::ImageId.is_in(image_ids.to_vec()))
+        .select_only()
+        .column(image_tag::Column::ImageId)
+        .column(tag::Column::Id)
+        .column(tag::Column::Name)
+        .column(tag::Column::Color)
+        .column(tag::Column::ParentId)
+        .column(tag::Column::Category)
+        .into_tuple::<(i64, i64, String, TagColor, Option<i64>, Option<String>)>()
+        .all(db)
+        .await?;
+
+    let mut tags_map: HashMap<i64, HashSet<TagDTO>> = HashMap::new();
+
+    for (image_id, tag_id, name, color, parent_id, category) in rows {
+        let tag_dto = TagDTO {
+            id: tag_id,
+            name,
+            color,
+            parent_id,
+            category,
+        };
+
+        tags_map
+            .entry(image_id)
+            .or_default()
+            .insert(tag_dto);
+    }
+
+    Ok(tags_map)
+}
+
+/// Saves the name and color of a tag. A new name moves the tags under it along,
+/// `animals/cats` becomes `pets/cats` when `animals` is renamed to `pets`.
+pub async fn update_from_dto(id: i64, dto: TagUpdateDTO) -> Result<Model, DbErr> {
+    let txn = db_ref().begin().await?;
+
+    let existing_model = TagEntity::find_by_id(id)
+        .one(&txn)
+        .await?
+        .ok_or_else(|| DbErr::RecordNotFound("Tag not found".to_string()))?;
+    let old_name = existing_model.name.clone();
+
+    let mut active_model: ActiveModel = existing_model.into();
+
+    let name = normalize_name(&dto.name);
+    if !name.is_empty() && name != old_name {
+        if name.starts_with(&format!("{}/", old_name)) {
+            return Err(DbErr::Custom(format!(
+                "{} can't be moved under itself",
+                old_name
+            )));
+        }
+        if canonical_name(&txn, &name).await? != name {
+            return Err(DbErr::Custom(format!(
+                "{} is an alias of another tag",
+                name
+            )));
+        }
+        active_model.parent_id = Set(parent_id_for(&txn, &name, &dto.color).await?);
+        active_model.name = Set(name.clone());
+        rename_descendants(&txn, &old_name, &name).await?;
+    }
+
+    active_model.color = Set(dto.color);
+    active_model.category = Set(normalize_category(&dto.category));
+
+    let updated_model = active_model.update(&txn).await?;
+    txn.commit().await?;
+    invalidate_cache();
+
+    activity_service::record(
+        ActivityAction::TagChange,
+        None,
+        t!("activity.details.tag_updated", name = updated_model.name).to_string(),
+    )
+    .await;
+
+    Ok(updated_model)
+}
+
+/// Replaces the tags of the image, creating the ones that don't exist yet. Aliases
+/// resolve to their tag, so a tag given under several names is added once.
+pub async fn update_tags_for_image(
+    db: &DatabaseConnection,
+    image_id: i64,
+    tags: HashSet<TagDTO>,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+
+    let mut tag_ids = HashSet::new();
+    let mut created = false;
+    for tag_dto in tags {
+        let name = canonical_name(&txn, &normalize_name(&tag_dto.name)).await?;
+        if name.is_empty() {
+            continue;
+        }
+        let tag = match tag::Entity::find()
+            .filter(tag::Column::Name.eq(&name))
+            .one(&txn)
+            .await?
+        {
+            Some(existing_tag) => existing_tag,
+            None => {
+                created = true;
+                create(&txn, &name, tag_dto.color).await?
+            }
+        };
+        tag_ids.insert(tag.id);
+    }
+
+    Entity::delete_many()
+        .filter(image_tag::Column::ImageId.eq(image_id))
+        .exec(&txn)
+        .await?;
+    for tag_id in tag_ids {
+        image_tag::ActiveModel {
+            image_id: Set(image_id),
+            tag_id: Set(tag_id),
+        }
+        .insert(&txn)
+        .await?;
+    }
+
+    txn.commit().await?;
+    if created {
+        invalidate_cache();
+    }
+    Ok(())
+}
+
+/// Id of the tag `name` or its alias names, `None` when there is no such tag
+pub async fn canonical_id(name: &str) -> Result<Option<i64>, DbErr> {
+    let db = db_ref();
+    let name = canonical_name(db, &normalize_name(name)).await?;
+    let tag = TagEntity::find()
+        .filter(tag::Column::Name.eq(name))
+        .one(db)
+        .await?;
+    Ok(tag.map(|tag| tag.id))
+}
+
+pub async fn find_all() -> Result<HashSet<TagDTO>, DbErr> {
+    if let Some(tags) = TAG_CACHE.read().ok().and_then(|cache| cache.clone()) {
+        return Ok(tags);
+    }
+
+    let generation = TAG_CACHE_GENERATION.load(Ordering::SeqCst);
+    let db = db_ref();
+    let tags = to_dto(with_retry(|| tag::Entity::find().all(db)).await?);
+
+    if let Ok(mut cache) = TAG_CACHE.write()
+        && generation == TAG_CACHE_GENERATION.load(Ordering::SeqCst)
+    {
+        *cache = Some(tags.clone());
+    }
+    Ok(tags)
+}
+
+/// Every tag with the number of images carrying it, unused tags counting 0.
+/// Images in the trash are left out.
+pub async fn find_all_with_counts() -> Result<Vec<(TagDTO, u64)>, DbErr> {
+    let rows: Vec<TagCountRow> = with_retry(|| {
+        TagEntity::find()
+            .select_only()
+            .column(tag::Column::Id)
+            .column(tag::Column::Name)
+            .column(tag::Column::Color)
+            .column(tag::Column::ParentId)
+            .column(tag::Column::Category)
+            .column_as(Expr::cust("COUNT(images.id)"), "count")
+            .join(JoinType::LeftJoin, tag::Relation::ImageTag.def())
+            .join(
+                JoinType::LeftJoin,
+                image_tag::Relation::Image.def().on_condition(|_, images| {
+                    Condition::all().add(Expr::col((images, image::Column::DeletedAt)).is_null())
+                }),
+            )
+            .group_by(tag::Column::Id)
+            .order_by_asc(tag::Column::Name)
+            .into_tuple()
+            .all(db_ref())
+    })
+    .await?;
+
+    Ok(rows.into_iter().map(counted_tag).collect())
+}
+
+/// Columns of a tag followed by a count, as the queries counting tag uses select them
+pub(crate) type TagCountRow = (i64, String, TagColor, Option<i64>, Option<String>, i64);
+
+pub(crate) fn counted_tag(row: TagCountRow) -> (TagDTO, u64) {
+    let (id, name, color, parent_id, category, count) = row;
+    let tag = TagDTO {
+        id,
+        name,
+        color,
+        parent_id,
+        category,
+    };
+    (tag, count as u64)
+}
+
+/// Tags with the number of images using them, most used first
+pub async fn find_most_used(limit: u64) -> Result<Vec<(String, u64)>, DbErr> {
+    let rows: Vec<(String, i64)> = with_retry(|| {
+        Entity::find()
+            .select_only()
+            .column(tag::Column::Name)
+            .column_as(image_tag::Column::ImageId.count(), "count")
+            .join(JoinType::InnerJoin, image_tag::Relation::Tag.def())
+            .filter(Expr::cust(
+                "image_tags.image_id NOT IN (SELECT id FROM images WHERE deleted_at IS NOT NULL)",
+            ))
+            .group_by(tag::Column::Id)
+            .order_by_desc(Expr::cust("count"))
+            .limit(limit)
+            .into_tuple()
+            .all(db_ref())
+    })
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(name, count)| (name, count as u64))
+        .collect())
+}
+
+/// Creates a tag, and the tags up its `/` path that don't exist yet. An alias
+/// already names a tag, nothing is created for it.
+pub async fn save(name: &str, color: TagColor) -> Result<(), DbErr> {
+    let db = db_ref();
+    let name = normalize_name(name);
+    if canonical_name(db, &name).await? != name {
+        return Ok(());
+    }
+    let tag = create(db, &name, color).await?;
+    invalidate_cache();
+
+    activity_service::record(
+        ActivityAction::TagChange,
+        None,
+        t!("activity.details.tag_created", name = tag.name).to_string(),
+    )
+    .await;
+    Ok(())
+}
+
+pub async fn delete(id: i64) -> Result<(), DbErr> {
+    let db = db_ref();
+    let existing = TagEntity::find_by_id(id).one(db).await?;
+    // The tags under it stay, at the top until a tag with its name is created again
+    TagEntity::update_many()
+        .col_expr(tag::Column::ParentId, Expr::value(Option::<i64>::None))
+        .filter(tag::Column::ParentId.eq(id))
+        .exec(db)
+        .await?;
+    TagEntity::delete_by_id(id).exec(db).await?;
+    invalidate_cache();
+
+    if let Some(tag) = existing {
+        activity_service::record(
+            ActivityAction::TagChange,
+            None,
+            t!("activity.details.tag_deleted", name = tag.name).to_string(),
+        )
+        .await;
+    }
+    Ok(())
+}
+
+/// Aliases of each tag that has some, sorted
+pub async fn find_aliases() -> Result<HashMap<i64, Vec<String>>, DbErr> {
+    let aliases = tag_alias::Entity::find()
+        .order_by_asc(tag_alias::Column::Alias)
+        .all(db_ref())
+        .await?;
+
+    let mut by_tag: HashMap<i64, Vec<String>> = HashMap::new();
+    for alias in aliases {
+        by_tag.entry(alias.tag_id).or_default().push(alias.alias);
+    }
+    Ok(by_tag)
+}
+
+/// Makes `alias` another name of the tag. It can't be the name of a tag or an
+/// alias already.
+pub async fn add_alias(tag_id: i64, alias: &str) -> Result<(), DbErr> {
+    let db = db_ref();
+    let alias = normalize_name(alias);
+    if alias.is_empty() {
+        return Err(DbErr::Custom("An alias needs a name".to_string()));
+    }
+    let taken = TagEntity::find()
+        .filter(tag::Column::Name.eq(&alias))
+        .one(db)
+        .await?
+        .is_some();
+    if taken {
+        return Err(DbErr::Custom(format!("{} is already a tag", alias)));
+    }
+
+    tag_alias::ActiveModel {
+        alias: Set(alias),
+        tag_id: Set(tag_id),
+    }
+    .insert(db)
+    .await?;
+    invalidate_cache();
+    Ok(())
+}
+
+pub async fn remove_alias(alias: &str) -> Result<(), DbErr> {
+    tag_alias::Entity::delete_by_id(alias.to_string())
+        .exec(db_ref())
+        .await?;
+    invalidate_cache();
+    Ok(())
+}
+
+/// Name of the tag `name` is an alias of, or `name` itself
+pub async fn resolve_alias(name: &str) -> Result<String, DbErr> {
+    canonical_name(db_ref(), &normalize_name(name)).await
+}
+
+async fn canonical_name<C: ConnectionTrait>(db: &C, name: &str) -> Result<String, DbErr> {
+    let tag = tag_alias::Entity::find_by_id(name.to_string())
+        .find_also_related(TagEntity)
+        .one(db)
+        .await?
+        .and_then(|(_, tag)| tag);
+    Ok(tag.map_or_else(|| name.to_string(), |tag| tag.name))
+}
+
+/// Condition on `images.id` keeping the images that carry one of the tags or a tag
+/// anywhere under them. The names can be aliases.
+pub(crate) fn tagged_under(names: &[String]) -> SimpleExpr {
+    let placeholders = vec!["?"; names.len()].join(", ");
+    Expr::cust_with_values(
+        format!(
+            "images.id IN (SELECT image_tags.image_id FROM image_tags \
+             WHERE image_tags.tag_id IN (WITH RECURSIVE subtree(id) AS \
+             (SELECT id FROM tags WHERE name IN ({placeholders}) \
+             OR id IN (SELECT tag_id FROM tag_aliases WHERE alias IN ({placeholders})) \
+             UNION SELECT tags.id FROM tags JOIN subtree ON tags.parent_id = subtree.id) \
+             SELECT id FROM subtree))"
+        ),
+        names.iter().chain(names).cloned(),
+    )
+}
+
+/// Condition on `images.id` keeping the images that carry a tag of the category
+pub(crate) fn tagged_in_category(category: &str) -> SimpleExpr {
+    Expr::cust_with_values(
+        "images.id IN (SELECT image_tags.image_id FROM image_tags \
+         JOIN tags ON tags.id = image_tags.tag_id WHERE tags.category = ?)",
+        [category.to_string()],
+    )
+}
+
+/// Category as stored: trimmed and lowercase, none when empty
+pub fn normalize_category(category: &str) -> Option<String> {
+    let category = category.trim().to_lowercase();
+    (!category.is_empty()).then_some(category)
+}
+
+/// Name as tags are stored: lowercase, with each `/` segment trimmed and empty
+/// segments dropped
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Name of the tag one level up, `animals` for `animals/cats`
+pub fn parent_name(name: &str) -> Option<&str> {
+    name.rsplit_once('/').map(|(parent, _)| parent)
+}
+
+/// Last segment of the name, how a tag is shown under its parent
+pub fn leaf_name(name: &str) -> &str {
+    name.rsplit_once('/').map_or(name, |(_, leaf)| leaf)
+}
+
+/// Order the tag lists group categories in, by name with the uncategorized tags last
+pub fn category_order(a: Option<&str>, b: Option<&str>) -> CmpOrdering {
+    (a.is_none(), a).cmp(&(b.is_none(), b))
+}
+
+/// Inserts a tag under its parent, creating the tags up its path that don't exist
+/// yet with the same color
+async fn create<C: ConnectionTrait>(db: &C, name: &str, color: TagColor) -> Result<Model, DbErr> {
+    let parent_id = parent_id_for(db, name, &color).await?;
+    insert(db, name, color, parent_id).await
+}
+
+/// Id of the parent of a tag with this name, created when missing
+async fn parent_id_for<C: ConnectionTrait>(
+    db: &C,
+    name: &str,
+    color: &TagColor,
+) -> Result<Option<i64>, DbErr> {
+    let Some(parent) = parent_name(name) else {
+        return Ok(None);
+    };
+
+    let mut parent_id = None;
+    let mut path = String::new();
+    for segment in parent.split('/') {
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(segment);
+        let existing = TagEntity::find()
+            .filter(tag::Column::Name.eq(&path))
+            .one(db)
+            .await?;
+        let id = match existing {
+            Some(tag) => tag.id,
+            None => insert(db, &path, color.clone(), parent_id).await?.id,
+        };
+        parent_id = Some(id);
+    }
+    Ok(parent_id)
+}
+
+async fn insert<C: ConnectionTrait>(
+    db: &C,
+    name: &str,
+    color: TagColor,
+    parent_id: Option<i64>,
+) -> Result<Model, DbErr> {
+    let tag = ActiveModel {
+        name: Set(name.to_string()),
+        color: Set(color),
+        parent_id: Set(parent_id),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    // Tags named under it that lost their parent when a tag of this name was deleted
+    let orphans: Vec<i64> = TagEntity::find()
+        .filter(tag::Column::ParentId.is_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|orphan| parent_name(&orphan.name) == Some(name))
+        .map(|orphan| orphan.id)
+        .collect();
+    if !orphans.is_empty() {
+        TagEntity::update_many()
+            .col_expr(tag::Column::ParentId, Expr::value(tag.id))
+            .filter(tag::Column::Id.is_in(orphans))
+            .exec(db)
+            .await?;
+    }
+    Ok(tag)
+}
+
+/// Gives the tags under `old_name` the new name as their prefix
+async fn rename_descendants<C: ConnectionTrait>(
+    db: &C,
+    old_name: &str,
+    new_name: &str,
+) -> Result<(), DbErr> {
+    let prefix = format!("{}/", old_name);
+    let descendants: Vec<Model> = TagEntity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|tag| tag.name.starts_with(&prefix))
+        .collect();
+    for descendant in descendants {
+        let name = format!("{}/{}", new_name, &descendant.name[prefix.len()..]);
+        let mut active: ActiveModel = descendant.into();
+        active.name = Set(name);
+        active.update(db).await?;
+    }
+    Ok(())
+}
+
+fn to_dto(tags: Vec<Model>) -> HashSet<TagDTO> {
+    tags.into_iter()
+        .map(|tag| TagDTO {
+            id: tag.id,
+            name: tag.name,
+            color: tag.color,
+            parent_id: tag.parent_id,
+            category: tag.category,
+        })
+        .collect()
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::dtos::tag_settings_dto::TagSettingsDTO;
+use crate::models::tag_settings::{ActiveModel, Column, Entity, Model};
+use crate::services::connection_db::{db_ref, with_retry};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DbErr, EntityTrait, Set};
+use std::collections::HashMap;
+
+pub async fn find(tag_id: i64) -> Result<Option<TagSettingsDTO>, DbErr> {
+    let model = with_retry(|| Entity::find_by_id(tag_id).one(db_ref())).await?;
+    Ok(model.map(to_dto))
+}
+
+pub async fn find_all() -> Result<HashMap<i64, TagSettingsDTO>, DbErr> {
+    let models = Entity::find().all(db_ref()).await?;
+    Ok(models
+        .into_iter()
+        .map(|model| (model.tag_id, to_dto(model)))
+        .collect())
+}
+
+/// Stores the default view of the tag, removing the row when nothing is set
+pub async fn save(tag_id: i64, settings: TagSettingsDTO) -> Result<(), DbErr> {
+    let db = db_ref();
+
+    if settings.is_empty() {
+        Entity::delete_by_id(tag_id).exec(db).await?;
+        return Ok(());
+    }
+
+    let model = ActiveModel {
+        tag_id: Set(tag_id),
+        sort_order: Set(settings.sort_order),
+        view_density: Set(settings.view_density),
+    };
+
+    Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(Column::TagId)
+                .update_columns([Column::SortOrder, Column::ViewDensity])
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+fn to_dto(model: Model) -> TagSettingsDTO {
+    TagSettingsDTO {
+        sort_order: model.sort_order,
+        view_density: model.view_density,
+    }
+}
@@ -0,0 +1,276 @@
+//! Pictures of stored originals, which thumbnails and previews are made from.
+//!
+//! Each media type has a [`ThumbnailRenderer`]. The registry starts with raster
+//! images, SVG, video (through `ffmpeg`) and PDF (through `pdftoppm`); a new type
+//! only needs a renderer passed to [`register`]. A file counts as importable when a
+//! renderer reads its extension.
+
+use crate::services::image_processor::{self, Frame, Step};
+use image::{DynamicImage, RgbaImage};
+use log::debug;
+use once_cell::sync::Lazy;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, RwLock};
+
+/// Turns a stored original into a picture
+pub trait ThumbnailRenderer: Send + Sync {
+    /// Name shown in the logs
+    fn name(&self) -> &'static str;
+
+    /// Lowercase extensions of the files it reads
+    fn extensions(&self) -> &[&'static str];
+
+    /// Whether the original can be shown as it is. Other originals are always
+    /// previewed through a rendition.
+    fn is_displayable(&self) -> bool {
+        false
+    }
+
+    /// Picture of the file, `size` on its longest side when the format has no
+    /// size of its own. `bytes` is the content of `path`.
+    fn render(&self, path: &Path, bytes: &[u8], size: u32) -> Result<DynamicImage, String>;
+}
+
+/// Registered renderers, the latest first so it can take over an extension
+static RENDERERS: Lazy<RwLock<Vec<Arc<dyn ThumbnailRenderer>>>> = Lazy::new(|| {
+    RwLock::new(vec![
+        Arc::new(RasterRenderer),
+        Arc::new(SvgRenderer),
+        Arc::new(VideoRenderer),
+        Arc::new(PdfRenderer),
+    ])
+});
+
+/// Adds a renderer, used before the ones registered earlier for the same extensions
+pub fn register(renderer: Arc<dyn ThumbnailRenderer>) {
+    if let Ok(mut renderers) = RENDERERS.write() {
+        renderers.insert(0, renderer);
+    }
+}
+
+/// Renderer reading the file, by its extension
+pub fn renderer_for(path: &Path) -> Option<Arc<dyn ThumbnailRenderer>> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    RENDERERS
+        .read()
+        .ok()?
+        .iter()
+        .find(|renderer| renderer.extensions().contains(&extension.as_str()))
+        .cloned()
+}
+
+/// Every extension some renderer reads, for file dialogs
+pub fn supported_extensions() -> Vec<&'static str> {
+    let mut extensions: Vec<&'static str> = RENDERERS
+        .read()
+        .map(|renderers| {
+            renderers
+                .iter()
+                .flat_map(|renderer| renderer.extensions().iter().copied())
+                .collect()
+        })
+        .unwrap_or_default();
+    extensions.sort_unstable();
+    extensions.dedup();
+    extensions
+}
+
+/// Whether the original at `path` can be shown without a rendition
+pub fn is_displayable(path: &Path) -> bool {
+    renderer_for(path).is_some_and(|renderer| renderer.is_displayable())
+}
+
+/// Reads and renders the file at `path`
+pub fn render(path: &Path, size: u32) -> Result<DynamicImage, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    render_bytes(path, &bytes, size)
+}
+
+/// Renders the already read content of the file at `path`
+pub fn render_bytes(path: &Path, bytes: &[u8], size: u32) -> Result<DynamicImage, String> {
+    let renderer =
+        renderer_for(path).ok_or_else(|| format!("No renderer for {}", path.display()))?;
+    debug!(
+        "Rendering {} with the {} renderer",
+        path.display(),
+        renderer.name()
+    );
+    renderer.render(path, bytes, size)
+}
+
+// ===================================
+//         BUILT-IN RENDERERS
+// ===================================
+
+/// Formats the image crate decodes
+pub struct RasterRenderer;
+
+impl ThumbnailRenderer for RasterRenderer {
+    fn name(&self) -> &'static str {
+        "raster"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["png", "jpg", "jpeg", "gif", "bmp", "tiff", "webp"]
+    }
+
+    fn is_displayable(&self) -> bool {
+        true
+    }
+
+    /// Upright, as the EXIF orientation of photos asks
+    fn render(&self, _path: &Path, bytes: &[u8], _size: u32) -> Result<DynamicImage, String> {
+        let frame = image_processor::decode(bytes).map_err(|e| e.to_string())?;
+        image_processor::Orient
+            .apply(frame)
+            .map(Frame::into_image)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Vector drawings, rasterized with resvg
+pub struct SvgRenderer;
+
+/// Fonts for the text of drawings, loaded on first use
+static SVG_OPTIONS: Lazy<resvg::usvg::Options<'static>> = Lazy::new(|| {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    options
+});
+
+impl ThumbnailRenderer for SvgRenderer {
+    fn name(&self) -> &'static str {
+        "svg"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["svg"]
+    }
+
+    fn render(&self, _path: &Path, bytes: &[u8], size: u32) -> Result<DynamicImage, String> {
+        let tree = resvg::usvg::Tree::from_data(bytes, &SVG_OPTIONS).map_err(|e| e.to_string())?;
+        let drawing = tree.size();
+        let scale = size as f32 / drawing.width().max(drawing.height());
+        let width = (drawing.width() * scale).round().max(1.0) as u32;
+        let height = (drawing.height() * scale).round().max(1.0) as u32;
+
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+            .ok_or_else(|| format!("Invalid drawing size {}x{}", width, height))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::from_scale(scale, scale),
+            &mut pixmap.as_mut(),
+        );
+
+        // tiny-skia keeps colors premultiplied by their alpha
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        RgbaImage::from_raw(width, height, pixels)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(|| "Failed to read the rendered drawing".to_string())
+    }
+}
+
+/// Runs an external program and decodes the image it writes to its output
+fn render_with(program: &str, args: &[&str]) -> Result<DynamicImage, String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("{} is not available: {}", program, e))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "{} could not render the file ({})",
+            program, output.status
+        ));
+    }
+    image::load_from_memory(&output.stdout).map_err(|e| e.to_string())
+}
+
+/// A frame of a video, taken with ffmpeg
+pub struct VideoRenderer;
+
+impl ThumbnailRenderer for VideoRenderer {
+    fn name(&self) -> &'static str {
+        "video"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["mp4", "m4v", "mkv", "webm", "mov", "avi"]
+    }
+
+    fn render(&self, path: &Path, _bytes: &[u8], size: u32) -> Result<DynamicImage, String> {
+        let input = path.to_string_lossy();
+        let scale =
+            format!("scale='min({size},iw)':'min({size},ih)':force_original_aspect_ratio=decrease");
+        // A second in skips the black first frame most videos open with, clips
+        // shorter than that get their first frame
+        ["1", "0"]
+            .iter()
+            .find_map(|seek| {
+                render_with(
+                    "ffmpeg",
+                    &[
+                        "-v",
+                        "error",
+                        "-ss",
+                        seek,
+                        "-i",
+                        &input,
+                        "-frames:v",
+                        "1",
+                        "-vf",
+                        &scale,
+                        "-f",
+                        "image2pipe",
+                        "-vcodec",
+                        "png",
+                        "-",
+                    ],
+                )
+                .ok()
+            })
+            .ok_or_else(|| format!("ffmpeg could not read a frame of {}", input))
+    }
+}
+
+/// The first page of a PDF, rasterized with pdftoppm
+pub struct PdfRenderer;
+
+impl ThumbnailRenderer for PdfRenderer {
+    fn name(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["pdf"]
+    }
+
+    fn render(&self, path: &Path, _bytes: &[u8], size: u32) -> Result<DynamicImage, String> {
+        let input = path.to_string_lossy();
+        let size = size.to_string();
+        render_with(
+            "pdftoppm",
+            &[
+                "-png",
+                "-f",
+                "1",
+                "-l",
+                "1",
+                "-singlefile",
+                "-scale-to",
+                &size,
+                &input,
+                "-",
+            ],
+        )
+    }
+}
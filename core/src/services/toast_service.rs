@@ -6,7 +6,12 @@ use tokio::sync::mpsc;
 
 static NEXT_ID: AtomicU32 = AtomicU32::new(1);
 
-static TOAST_CHANNEL: Lazy<(mpsc::UnboundedSender<Toast>, std::sync::Mutex<Option<mpsc::UnboundedReceiver<Toast>>>)> = Lazy::new(|| {
+type ToastChannel = (
+    mpsc::UnboundedSender<Toast>,
+    std::sync::Mutex<Option<mpsc::UnboundedReceiver<Toast>>>,
+);
+
+static TOAST_CHANNEL: Lazy<ToastChannel> = Lazy::new(|| {
     let (tx, rx) = mpsc::unbounded_channel();
     (tx, std::sync::Mutex::new(Some(rx)))
 });
@@ -30,4 +35,10 @@ pub fn push_success<S: Into<String>>(message: S) {
 pub fn push_error<E: Into<String>>(err: E) {
     let toast = Toast::new(ToastKind::Error, err.into(), Duration::from_secs(3));
     push_toast(toast);
-}
\ No newline at end of file
+}
+
+/// Longer lived, for something the user should read before it goes
+pub fn push_warning<S: Into<String>>(message: S) {
+    let toast = Toast::new(ToastKind::Warning, message.into(), Duration::from_secs(8));
+    push_toast(toast);
+}
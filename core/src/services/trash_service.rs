@@ -0,0 +1,197 @@
+//! Entries deleted from the library wait in the trash until they are restored or
+//! purged. A trashed entry keeps its row, marked by `deleted_at`, and its folder
+//! moves from `images/<id>` to `trash/<id>`, so the stored paths are valid again
+//! once it is restored.
+
+use crate::dtos::trashed_image_dto::TrashedImageDTO;
+use crate::models::delete_behavior::DeleteBehavior;
+use crate::models::activity_action::ActivityAction;
+use crate::models::image::{self, Column, Entity};
+use crate::services::change_service::{self, DataChange};
+use crate::services::connection_db::db_ref;
+use crate::services::image_service::{activity_details, to_dto};
+use crate::services::tag_service::get_tags_for_images;
+use crate::services::file_service::DirContents;
+use crate::services::version_service;
+use crate::utils::get_data_dir;
+use crate::services::{activity_service, file_service, image_service, stack_service};
+use log::{error, info};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+
+/// Moves an entry to the trash. It leaves its stack, the rest stays as it was.
+pub async fn trash(image_id: i64) -> Result<(), String> {
+    let db = db_ref();
+    let Some(model) = Entity::find_by_id(image_id)
+        .one(db)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(());
+    };
+    if model.deleted_at.is_some() {
+        return Ok(());
+    }
+
+    stack_service::remove_image(image_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    run_blocking(move || file_service::move_to_trash(image_id)).await?;
+
+    let deleted_at = chrono::Utc::now().naive_utc();
+    if let Err(err) = set_deleted_at(image_id, Some(deleted_at)).await {
+        error!("Failed to mark image {} as trashed: {}", image_id, err);
+        // Put the files back so the entry stays usable
+        let _ = run_blocking(move || file_service::restore_from_trash(image_id)).await;
+        return Err(err.to_string());
+    }
+
+    activity_service::record(
+        ActivityAction::Trash,
+        Some(image_id),
+        activity_details(&model),
+    )
+    .await;
+    change_service::notify(DataChange::Images);
+    info!("Image {} moved to the trash", image_id);
+    Ok(())
+}
+
+/// Files stored for an entry and their size, what deleting it takes away
+pub async fn contents(image_id: i64) -> Result<DirContents, String> {
+    let image_dir = get_data_dir().join("images").join(image_id.to_string());
+    tokio::task::spawn_blocking(move || file_service::dir_contents(&image_dir))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Puts a trashed entry back in the library
+pub async fn restore(image_id: i64) -> Result<(), String> {
+    let Some(model) = find_trashed(image_id).await.map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+
+    run_blocking(move || file_service::restore_from_trash(image_id)).await?;
+    if let Err(err) = set_deleted_at(image_id, None).await {
+        error!("Failed to restore image {}: {}", image_id, err);
+        let _ = run_blocking(move || file_service::move_to_trash(image_id)).await;
+        return Err(err.to_string());
+    }
+
+    activity_service::record(
+        ActivityAction::Restore,
+        Some(image_id),
+        activity_details(&model),
+    )
+    .await;
+    change_service::notify(DataChange::Images);
+    info!("Image {} restored from the trash", image_id);
+    Ok(())
+}
+
+/// Deletes a trashed entry for good: its row, files and versions. The original
+/// files are removed or go to the recycle bin as `behavior` says.
+pub async fn purge(image_id: i64, behavior: DeleteBehavior) -> Result<(), String> {
+    // An entry restored since the id was listed stays in the library
+    if find_trashed(image_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .is_none()
+    {
+        return Ok(());
+    }
+    run_blocking(move || file_service::purge_from_trash(image_id, behavior)).await?;
+    image_service::delete_image(image_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    version_service::delete_versions(image_id).await;
+    Ok(())
+}
+
+/// Purges every trashed entry. Returns how many were purged.
+pub async fn empty(behavior: DeleteBehavior) -> Result<usize, String> {
+    let ids: Vec<i64> = trashed()
+        .all(db_ref())
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|model| model.id)
+        .collect();
+
+    for id in &ids {
+        purge(*id, behavior).await?;
+    }
+    Ok(ids.len())
+}
+
+/// Trashed entries, the last one deleted first
+pub async fn find_all() -> Result<Vec<TrashedImageDTO>, DbErr> {
+    let db = db_ref();
+    let models = trashed()
+        .order_by_desc(Column::DeletedAt)
+        .order_by_desc(Column::Id)
+        .all(db)
+        .await?;
+
+    let trashed: Vec<(String, String)> = models
+        .iter()
+        .map(|model| {
+            let thumbnail = file_service::trashed_path(model.id, &model.thumbnail_path);
+            let deleted_at = model
+                .deleted_at
+                .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            (thumbnail.to_string_lossy().to_string(), deleted_at)
+        })
+        .collect();
+
+    let image_ids: Vec<i64> = models.iter().map(|model| model.id).collect();
+    let tags_map = get_tags_for_images(&image_ids, db).await?;
+    Ok(to_dto(models, tags_map)
+        .into_iter()
+        .zip(trashed)
+        .map(|(image, (thumbnail_path, deleted_at))| TrashedImageDTO {
+            image,
+            thumbnail_path,
+            deleted_at,
+        })
+        .collect())
+}
+
+pub async fn count() -> Result<u64, DbErr> {
+    trashed().count(db_ref()).await
+}
+
+fn trashed() -> sea_orm::Select<Entity> {
+    Entity::find().filter(Column::DeletedAt.is_not_null())
+}
+
+/// Row of an entry in the trash, `None` for one that isn't there
+pub async fn find_trashed(image_id: i64) -> Result<Option<image::Model>, DbErr> {
+    trashed()
+        .filter(Column::Id.eq(image_id))
+        .one(db_ref())
+        .await
+}
+
+async fn set_deleted_at(
+    image_id: i64,
+    deleted_at: Option<chrono::NaiveDateTime>,
+) -> Result<(), DbErr> {
+    Entity::update_many()
+        .col_expr(Column::DeletedAt, Expr::value(deleted_at))
+        .filter(image::Column::Id.eq(image_id))
+        .exec(db_ref())
+        .await?;
+    Ok(())
+}
+
+async fn run_blocking(
+    work: impl FnOnce() -> std::io::Result<()> + Send + 'static,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,351 @@
+//! Looks for newer releases and downloads their installer, and notices when a new
+//! version starts for the first time. The check is off until turned on in the
+//! preferences, nothing is sent anywhere before that.
+
+use crate::config::{Config, get_settings, update_config};
+use crate::dtos::release_dto::ReleaseDTO;
+use crate::services::{atomic_file, database_service, job_service};
+use crate::utils::get_data_dir;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Latest release published on GitHub
+pub const DEFAULT_UPDATE_URL: &str =
+    "https://api.github.com/repos/DarkFoxV/organizer/releases/latest";
+
+/// Installers and archives in the order they are preferred, per platform
+const WINDOWS_ASSETS: [&str; 2] = [".msi", ".exe"];
+const MACOS_ASSETS: [&str; 2] = [".dmg", ".zip"];
+const LINUX_ASSETS: [&str; 3] = [".AppImage", ".deb", ".tar.gz"];
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .user_agent(concat!("Organizer/", env!("CARGO_PKG_VERSION")))
+        // Installers take a while on a slow connection
+        .timeout(Duration::from_secs(600))
+        // What it downloads gets run, redirects to plain http are refused too
+        .https_only(true)
+        .build()
+        .expect("Failed to create HTTP client")
+});
+
+/// Newer release found by the last check, kept for the screens opened after it
+static AVAILABLE: Mutex<Option<ReleaseDTO>> = Mutex::new(None);
+
+/// Release as the GitHub API describes it. `version` and `url` are accepted too, for
+/// endpoints serving a hand-written file, which can state the checksum of the
+/// download in `sha256`.
+#[derive(Debug, Deserialize)]
+struct ReleaseResponse {
+    #[serde(alias = "version")]
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default, alias = "url")]
+    html_url: Option<String>,
+    #[serde(default)]
+    sha256: Option<String>,
+    #[serde(default)]
+    assets: Vec<AssetResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetResponse {
+    name: String,
+    browser_download_url: String,
+    /// `sha256:<hex>`, as GitHub states it for each asset
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+/// Reads the answer of the update endpoint, picking the download for this platform
+pub fn parse_release(content: &[u8]) -> Result<ReleaseDTO, String> {
+    let response: ReleaseResponse = serde_json::from_slice(content).map_err(|e| e.to_string())?;
+    let extensions: &[&str] = if cfg!(target_os = "windows") {
+        &WINDOWS_ASSETS
+    } else if cfg!(target_os = "macos") {
+        &MACOS_ASSETS
+    } else {
+        &LINUX_ASSETS
+    };
+    let asset = extensions.iter().find_map(|extension| {
+        response
+            .assets
+            .iter()
+            .find(|asset| asset.name.ends_with(extension))
+    });
+
+    let digest = asset
+        .and_then(|asset| asset.digest.as_deref())
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .map(str::to_string)
+        .or(response.sha256);
+    let checksum_url = asset.and_then(|asset| {
+        let name = format!("{}.sha256", asset.name);
+        response
+            .assets
+            .iter()
+            .find(|other| other.name == name)
+            .map(|other| other.browser_download_url.clone())
+    });
+
+    Ok(ReleaseDTO {
+        version: response.tag_name.trim_start_matches('v').to_string(),
+        notes: response.body.unwrap_or_default(),
+        page_url: response.html_url,
+        download_url: asset.map(|asset| asset.browser_download_url.clone()),
+        file_name: asset.map(|asset| asset.name.clone()),
+        sha256: digest.map(|digest| digest.trim().to_lowercase()),
+        checksum_url,
+    })
+}
+
+/// Refuses anything but an https URL, what the updater fetches ends up being run
+pub fn require_https(url: &str) -> Result<(), String> {
+    match url::Url::parse(url) {
+        Ok(parsed) if parsed.scheme() == "https" => Ok(()),
+        Ok(_) => Err(format!("Updates are only fetched over https: {}", url)),
+        Err(e) => Err(format!("Invalid update URL {}: {}", url, e)),
+    }
+}
+
+/// Whether the bytes hash to the hex SHA-256 `expected`
+pub fn matches_sha256(bytes: &[u8], expected: &str) -> bool {
+    let actual: String = Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    actual.eq_ignore_ascii_case(expected.trim())
+}
+
+/// Whether `candidate` is a later version than `current`. Versions compare number by
+/// number, a pre-release suffix (`-beta.1`) sorts before the release itself, and
+/// pre-releases compare part by part like semver: `beta.2` after `beta.1`, numbers
+/// before words.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parse(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches('v');
+        let (numbers, pre_release) = match version.split_once('-') {
+            Some((numbers, pre_release)) => (numbers, Some(pre_release)),
+            None => (version, None),
+        };
+        let numbers = numbers
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        (numbers, pre_release)
+    }
+
+    let (candidate, candidate_pre) = parse(candidate);
+    let (current, current_pre) = parse(current);
+    let len = candidate.len().max(current.len());
+    let pad = |mut numbers: Vec<u64>| {
+        numbers.resize(len, 0);
+        numbers
+    };
+    match pad(candidate).cmp(&pad(current)) {
+        Ordering::Equal => match (candidate_pre, current_pre) {
+            (None, Some(_)) => true,
+            (Some(candidate), Some(current)) => pre_release_order(candidate, current).is_gt(),
+            _ => false,
+        },
+        ordering => ordering.is_gt(),
+    }
+}
+
+/// Semver precedence of two pre-release suffixes: numeric parts by value and below
+/// the others, the rest as text, and a suffix running out first comes first
+fn pre_release_order(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+    loop {
+        let ordering = match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+/// Endpoint set in the config, GitHub's when none is
+pub fn update_url(config: &Config) -> String {
+    config
+        .update_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_UPDATE_URL)
+        .to_string()
+}
+
+/// Asks `url` for the latest release. Returns it when it's newer than
+/// `current_version`, and remembers it for [`available`]. Only https endpoints are
+/// asked.
+pub async fn check(url: String, current_version: &str) -> Result<Option<ReleaseDTO>, String> {
+    require_https(&url)?;
+    let response = CLIENT
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    let release = parse_release(&bytes)?;
+
+    let newer = is_newer(&release.version, current_version).then_some(release);
+    match &newer {
+        Some(release) => info!("Organizer {} is available", release.version),
+        None => info!("Organizer {} is up to date", current_version),
+    }
+    *AVAILABLE.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = newer.clone();
+    Ok(newer)
+}
+
+/// Newer release found by the last check of this run
+pub fn available() -> Option<ReleaseDTO> {
+    AVAILABLE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Downloads the installer of the release into the `updates` folder of the library.
+/// It's only kept when it matches the SHA-256 the release publishes, a release
+/// without one can't be downloaded.
+pub async fn download(release: ReleaseDTO) -> Result<PathBuf, String> {
+    let _job = job_service::start();
+    let (Some(url), Some(file_name)) = (release.download_url, release.file_name) else {
+        return Err(format!(
+            "Release {} has nothing to download",
+            release.version
+        ));
+    };
+    // The name comes from the server, keep it from leaving the folder
+    let file_name = Path::new(&file_name)
+        .file_name()
+        .ok_or_else(|| format!("Invalid file name: {}", file_name))?
+        .to_owned();
+
+    require_https(&url)?;
+    let expected = match (release.sha256, release.checksum_url) {
+        (Some(sha256), _) => sha256,
+        (None, Some(checksum_url)) => published_checksum(&checksum_url).await?,
+        (None, None) => {
+            return Err(format!(
+                "Release {} publishes no checksum for its download",
+                release.version
+            ));
+        }
+    };
+
+    let bytes = fetch(&url).await?;
+    if !matches_sha256(&bytes, &expected) {
+        return Err(format!(
+            "The download of {} doesn't match its published checksum",
+            release.version
+        ));
+    }
+
+    let path = get_data_dir().join("updates").join(file_name);
+    let target = path.clone();
+    tokio::task::spawn_blocking(move || {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        atomic_file::write(&target, &bytes)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    info!("Downloaded {}", path.display());
+    Ok(path)
+}
+
+async fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    let response = CLIENT
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    Ok(bytes.to_vec())
+}
+
+/// Digest out of a `.sha256` file, its first word as `sha256sum` writes it
+async fn published_checksum(url: &str) -> Result<String, String> {
+    require_https(url)?;
+    let content = fetch(url).await?;
+    String::from_utf8_lossy(&content)
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+        .ok_or_else(|| format!("Empty checksum file: {}", url))
+}
+
+/// Starts the downloaded installer, which [`download`] checked against its
+/// published checksum. Windows installers run right away, the app has
+/// to quit so they can replace its files. Elsewhere the folder holding the download
+/// is opened. Returns whether the installer was started.
+pub fn launch_installer(path: &Path) -> io::Result<bool> {
+    if !cfg!(target_os = "windows") {
+        let folder = path.parent().unwrap_or(path);
+        crate::services::file_service::open_in_file_explorer(folder)?;
+        return Ok(false);
+    }
+
+    let is_msi = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("msi"));
+    if is_msi {
+        Command::new("msiexec").arg("/i").arg(path).spawn()?;
+    } else {
+        Command::new(path).spawn()?;
+    }
+    Ok(true)
+}
+
+/// Notes the version starting now. When it's newer than the one that ran last, the
+/// database is backed up before its migrations run. Returns the previous version
+/// after an upgrade. Has to run before the database is opened.
+pub fn record_version(current_version: &str) -> Option<String> {
+    let previous = get_settings().config.last_version.clone();
+    if previous.as_deref() == Some(current_version) {
+        return None;
+    }
+
+    let upgraded = previous
+        .as_deref()
+        .filter(|previous| is_newer(current_version, previous));
+    if let Some(previous) = upgraded {
+        info!("Upgraded from {} to {}", previous, current_version);
+        if let Err(e) = database_service::backup_closed_database() {
+            warn!("Failed to back up the database before upgrading: {}", e);
+        }
+    } else if let Some(previous) = &previous {
+        warn!("Started {} after the newer {}", current_version, previous);
+    }
+
+    let version = current_version.to_string();
+    update_config(|config| config.last_version = Some(version));
+    upgraded.map(str::to_string)
+}
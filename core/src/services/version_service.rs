@@ -0,0 +1,186 @@
+use crate::dtos::version_dto::VersionDTO;
+use crate::models::activity_action::ActivityAction;
+use crate::models::image_version::{ActiveModel, Column, Entity, Model};
+use crate::services::connection_db::db_ref;
+use crate::services::file_service::{edit_in_external_program, regenerate_thumbnail};
+use crate::services::{activity_service, atomic_file, image_service, integrity_service};
+use crate::utils::get_data_dir;
+use log::{info, warn};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, Set};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// ===================================
+//         FILES
+// ===================================
+
+fn versions_dir(image_id: i64) -> PathBuf {
+    get_data_dir().join("versions").join(image_id.to_string())
+}
+
+/// Copies the current content of a stored file into the versions directory
+fn snapshot(image_id: i64, path: &Path) -> io::Result<PathBuf> {
+    let dir = versions_dir(image_id);
+    fs::create_dir_all(&dir)?;
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "image".to_string());
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S_%3f");
+    let version_path = dir.join(format!("{}_{}", timestamp, file_name));
+
+    fs::copy(path, &version_path)?;
+    Ok(version_path)
+}
+
+/// Removes the versions kept for an entry, used when the entry is deleted
+pub async fn delete_versions(image_id: i64) {
+    let dir = versions_dir(image_id);
+    if dir.exists()
+        && let Err(e) = fs::remove_dir_all(&dir)
+    {
+        warn!("Failed to delete versions of {}: {}", image_id, e);
+    }
+}
+
+// ===================================
+//         DATABASE
+// ===================================
+
+async fn entry_id(path: &str) -> Result<i64, String> {
+    image_service::find_by_file_path(path)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|model| model.id)
+        .ok_or_else(|| format!("No entry stored for {}", path))
+}
+
+async fn insert_version(image_id: i64, original_path: &str, path: &Path) -> Result<(), String> {
+    let version = ActiveModel {
+        image_id: Set(image_id),
+        original_path: Set(original_path.to_string()),
+        path: Set(path.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+    version.insert(db_ref()).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Refreshes everything derived from a stored file after its content changed
+async fn after_change(image_id: i64, path: &str, details: String) -> Result<(), String> {
+    regenerate_thumbnail(Path::new(path)).map_err(|e| e.to_string())?;
+    integrity_service::rehash_file(path.to_string()).await?;
+    activity_service::record(ActivityAction::Update, Some(image_id), details).await;
+    Ok(())
+}
+
+/// Versions kept for a stored file, newest first
+pub async fn find_for_file(path: String) -> Result<Vec<VersionDTO>, DbErr> {
+    let versions = Entity::find()
+        .filter(Column::OriginalPath.eq(path))
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .all(db_ref())
+        .await?;
+
+    Ok(versions.iter().map(to_dto).collect())
+}
+
+// ===================================
+//         OPERATIONS
+// ===================================
+
+/// Edits a stored file in the external editor, keeping the previous content as a
+/// version when it changes. Returns whether the file changed.
+pub async fn edit_with_versioning(path: String) -> Result<bool, String> {
+    let image_id = entry_id(&path).await?;
+    let version_path = snapshot(image_id, Path::new(&path)).map_err(|e| e.to_string())?;
+
+    let changed = match edit_in_external_program(path.clone()).await {
+        Ok(changed) => changed,
+        Err(e) => {
+            let _ = fs::remove_file(&version_path);
+            return Err(e);
+        }
+    };
+
+    if !changed {
+        let _ = fs::remove_file(&version_path);
+        return Ok(false);
+    }
+
+    insert_version(image_id, &path, &version_path).await?;
+    // The thumbnail was already regenerated by the edit
+    integrity_service::rehash_file(path.clone()).await?;
+    activity_service::record(
+        ActivityAction::Update,
+        Some(image_id),
+        t!("activity.details.edited_externally", path = path).to_string(),
+    )
+    .await;
+    Ok(true)
+}
+
+/// Replaces a stored file with another image, keeping the previous content as a
+/// version. The new image is converted to the stored file's format.
+pub async fn replace_with_versioning(path: String, source: PathBuf) -> Result<(), String> {
+    let image_id = entry_id(&path).await?;
+    let target = PathBuf::from(&path);
+
+    let bytes = fs::read(&source).map_err(|e| e.to_string())?;
+    let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+
+    let version_path = snapshot(image_id, &target).map_err(|e| e.to_string())?;
+    if let Err(e) = atomic_file::save_image(&image, &target) {
+        // Put the previous content back
+        let _ = fs::copy(&version_path, &target);
+        let _ = fs::remove_file(&version_path);
+        return Err(e.to_string());
+    }
+
+    insert_version(image_id, &path, &version_path).await?;
+    info!("Replaced {} with {}", path, source.display());
+    after_change(
+        image_id,
+        &path,
+        t!("activity.details.file_replaced", path = path).to_string(),
+    )
+    .await
+}
+
+/// Restores a version as the current file, keeping the replaced content as a new version
+pub async fn restore(version: VersionDTO) -> Result<(), String> {
+    let target = PathBuf::from(&version.original_path);
+
+    let current = snapshot(version.image_id, &target).map_err(|e| e.to_string())?;
+    fs::copy(&version.path, &target).map_err(|e| e.to_string())?;
+    insert_version(version.image_id, &version.original_path, &current).await?;
+
+    info!(
+        "Restored version {} of {}",
+        version.id, version.original_path
+    );
+    after_change(
+        version.image_id,
+        &version.original_path,
+        t!(
+            "activity.details.version_restored",
+            date = version.created_at,
+            path = version.original_path
+        )
+        .to_string(),
+    )
+    .await
+}
+
+fn to_dto(model: &Model) -> VersionDTO {
+    VersionDTO {
+        id: model.id,
+        image_id: model.image_id,
+        original_path: model.original_path.clone(),
+        path: model.path.clone(),
+        created_at: model.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
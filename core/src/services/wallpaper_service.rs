@@ -0,0 +1,119 @@
+use crate::services::file_service::expand_folder_dto;
+use crate::services::{image_service, job_service};
+use crate::utils::random_index;
+use log::info;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Sets the image as the desktop wallpaper
+pub async fn set_wallpaper(path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let path = fs::canonicalize(&path).map_err(|e| format!("{}: {}", path, e))?;
+        apply_wallpaper(&path).map_err(|e| e.to_string())?;
+        info!("Wallpaper set to {}", path.display());
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Picks a random image with the tag and sets it as wallpaper, returning its path.
+/// Folder entries contribute a random file from inside the folder.
+pub async fn rotate_wallpaper(tag: String) -> Result<Option<String>, String> {
+    let _job = job_service::start();
+    let Some(image) = image_service::random_with_tag(&tag)
+        .await
+        .map_err(|e| e.to_string())?
+    else {
+        return Ok(None);
+    };
+
+    let path = if image.is_folder {
+        let dto = image_service::to_image_dto(&image, &HashMap::new());
+        let files = expand_folder_dto(&dto);
+        match files.get(random_index(files.len())) {
+            Some(file) => file.path.clone(),
+            None => return Ok(None),
+        }
+    } else {
+        image.path
+    };
+
+    set_wallpaper(path.clone()).await?;
+    Ok(Some(path))
+}
+
+fn run(command: &mut Command) -> io::Result<()> {
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{:?} exited with {}",
+            command, status
+        )))
+    }
+}
+
+fn apply_wallpaper(path: &Path) -> io::Result<()> {
+    if cfg!(target_os = "windows") {
+        // SPI_SETDESKWALLPAPER, persisted to the user profile and broadcast to other apps
+        let script = r#"Add-Type -TypeDefinition 'using System.Runtime.InteropServices; public class Wallpaper { [DllImport("user32.dll", CharSet = CharSet.Unicode)] public static extern bool SystemParametersInfo(int action, int param, string value, int flags); }'; if (-not [Wallpaper]::SystemParametersInfo(20, 0, $env:ORGANIZER_WALLPAPER, 3)) { exit 1 }"#;
+        run(Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .env("ORGANIZER_WALLPAPER", path))
+    } else if cfg!(target_os = "macos") {
+        run(Command::new("osascript")
+            .args([
+                "-e",
+                "on run argv",
+                "-e",
+                "tell application \"System Events\" to tell every desktop to set picture to (item 1 of argv)",
+                "-e",
+                "end run",
+            ])
+            .arg(path))
+    } else if cfg!(target_os = "linux") {
+        apply_linux_wallpaper(path)
+    } else {
+        Err(io::Error::other("Unsupported OS"))
+    }
+}
+
+fn apply_linux_wallpaper(path: &Path) -> io::Result<()> {
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .to_lowercase();
+    let uri = format!("file://{}", path.display());
+
+    if desktop.contains("kde") {
+        run(Command::new("plasma-apply-wallpaperimage").arg(path))
+    } else if desktop.contains("cinnamon") {
+        run(Command::new("gsettings").args([
+            "set",
+            "org.cinnamon.desktop.background",
+            "picture-uri",
+            &uri,
+        ]))
+    } else if desktop.contains("mate") {
+        run(Command::new("gsettings")
+            .args(["set", "org.mate.background", "picture-filename"])
+            .arg(path))
+    } else if desktop.contains("gnome") || desktop.contains("unity") {
+        for key in ["picture-uri", "picture-uri-dark"] {
+            run(Command::new("gsettings").args([
+                "set",
+                "org.gnome.desktop.background",
+                key,
+                &uri,
+            ]))?;
+        }
+        Ok(())
+    } else {
+        // Window managers without a desktop: feh is the usual way to draw the root window
+        run(Command::new("feh").arg("--bg-fill").arg(path))
+    }
+}
@@ -0,0 +1,120 @@
+use log::error;
+use once_cell::sync::OnceCell;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// File next to the executable that keeps the library there (portable mode)
+pub const PORTABLE_FLAG: &str = "portable";
+/// Database of the library, a folder holding it holds a library
+pub const DATABASE_FILE: &str = "organizer.db";
+/// Folder of the library inside the per-user data directory of the platform
+const USER_DATA_FOLDER: &str = "Organizer";
+
+/// Where the library (database, stored images and config) is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataLocation {
+    /// Next to the executable, asked for with the portable flag file
+    Portable,
+    /// In the per-user data directory of the platform
+    User,
+    /// Next to the executable because the library was stored there before per-user
+    /// directories were used. Stays there until it's moved or made portable.
+    Legacy,
+}
+
+static DATA_LOCATION: OnceCell<(DataLocation, PathBuf)> = OnceCell::new();
+
+pub fn get_exe_dir() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|path| path.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Folder of the library in the per-user data directory, if the platform has one
+pub fn user_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(USER_DATA_FOLDER))
+}
+
+/// Works out where the library is from the files on disk. Development builds always
+/// keep it next to the executable, as do the platforms without a per-user directory.
+pub fn resolve_data_location() -> (DataLocation, PathBuf) {
+    let exe_dir = get_exe_dir();
+    if cfg!(debug_assertions) || exe_dir.join(PORTABLE_FLAG).exists() {
+        return (DataLocation::Portable, exe_dir);
+    }
+    let Some(user_dir) = user_data_dir() else {
+        return (DataLocation::Portable, exe_dir);
+    };
+    if exe_dir.join(DATABASE_FILE).exists() && !user_dir.join(DATABASE_FILE).exists() {
+        return (DataLocation::Legacy, exe_dir);
+    }
+    match fs::create_dir_all(&user_dir) {
+        Ok(()) => (DataLocation::User, user_dir),
+        Err(e) => {
+            error!("Failed to create {}: {}", user_dir.display(), e);
+            (DataLocation::Portable, exe_dir)
+        }
+    }
+}
+
+/// Where the library is, worked out once per run
+pub fn data_location() -> DataLocation {
+    DATA_LOCATION.get_or_init(resolve_data_location).0
+}
+
+/// Folder holding the database and the stored images
+pub fn get_data_dir() -> PathBuf {
+    DATA_LOCATION.get_or_init(resolve_data_location).1.clone()
+}
+
+/// Returns the base path for config assets depending on the build mode
+pub fn get_assets_path() -> PathBuf {
+    if cfg!(debug_assertions) {
+        // Development mode
+        PathBuf::from("./src/config/")
+    } else {
+        // Release mode: kept with the rest of the library
+        get_data_dir().join("config")
+    }
+}
+
+pub fn capitalize_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Cheap pseudo-random index below `len`, good enough for picking an image at random
+pub fn random_index(len: usize) -> usize {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as usize)
+        .unwrap_or_default();
+    nanos % len.max(1)
+}
+
+/// Plural form of `count` in the current locale, the suffix of its message key.
+/// CLDR cardinal rules of the languages shipped: Portuguese also treats 0 as singular.
+pub fn plural_form(count: u64) -> &'static str {
+    let singular = if rust_i18n::locale().starts_with("pt") {
+        count <= 1
+    } else {
+        count == 1
+    };
+    if singular { "one" } else { "other" }
+}
+
+/// `t!` for messages with a count: translates `<key>.one` or `<key>.other` as the
+/// locale requires, replacing `%{count}` and any other arguments given
+#[macro_export]
+macro_rules! t_count {
+    ($key:expr, $count:expr $(, $name:ident = $value:expr)* $(,)?) => {{
+        let count = $count;
+        let key = format!("{}.{}", $key, $crate::utils::plural_form(count as u64));
+        t!(&key, count = count $(, $name = $value)*)
+    }};
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{ImageFixture, new_tag, run, scope};
+use organizer_core::models::activity_action::ActivityAction;
+use organizer_core::services::{activity_service, image_service};
+
+#[test]
+fn insights_count_added_tags_and_created_entries() {
+    run(async {
+        let scope = scope();
+        let (often, once) = (format!("{scope}-often"), format!("{scope}-once"));
+        let first = ImageFixture::new(&scope).insert().await;
+        let second = ImageFixture::new(&scope).insert().await;
+        for id in [first, second] {
+            activity_service::record(ActivityAction::Create, Some(id), scope.clone()).await;
+        }
+
+        image_service::add_tag_to_images(&[first, second], new_tag(&often))
+            .await
+            .expect("Failed to add the tag");
+        image_service::add_tag_to_images(&[first], new_tag(&once))
+            .await
+            .expect("Failed to add the tag");
+        image_service::remove_tag_from_images(&[second], &often)
+            .await
+            .expect("Failed to remove the tag");
+
+        let insights = activity_service::insights()
+            .await
+            .expect("Failed to compute the insights");
+
+        let added = |name: &str| {
+            insights
+                .top_tags
+                .iter()
+                .find(|(tag, _)| tag == name)
+                .map(|(_, count)| *count)
+        };
+        assert_eq!(added(&often), Some(2), "removing a tag doesn't count");
+        assert_eq!(added(&once), Some(1));
+        assert!(insights.busiest_days[0].1 >= 2);
+        assert!(insights.average_tags > 0.0);
+        assert!(insights.since.is_some());
+    });
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::TempFolder;
+use organizer_core::services::atomic_file;
+use std::fs;
+use std::io;
+
+#[test]
+fn writes_replace_the_file_without_leaving_a_temp_file() {
+    let folder = TempFolder::new("atomic-replace");
+    let path = folder.write("config.json", b"old");
+
+    atomic_file::write(&path, b"new").expect("Failed to write");
+
+    assert_eq!(fs::read(&path).unwrap(), b"new");
+    assert_eq!(fs::read_dir(folder.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn failed_writes_keep_the_previous_file() {
+    let folder = TempFolder::new("atomic-failed");
+    let path = folder.write("meta.json", b"{\"version\": 1}");
+
+    let result = atomic_file::write_with(&path, |temp| {
+        fs::write(temp, b"{\"vers")?;
+        Err(io::Error::other("crashed halfway"))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(fs::read(&path).unwrap(), b"{\"version\": 1}");
+    assert_eq!(fs::read_dir(folder.path()).unwrap().count(), 1);
+}
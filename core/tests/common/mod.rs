@@ -0,0 +1,225 @@
+//! Shared setup of the integration tests: an in-memory database migrated once per
+//! test binary, and builders for the images and tags a test needs.
+//!
+//! Rows are never cleared between tests. Each test works inside its own [`scope`],
+//! naming descriptions, tags and languages after it so searches only see its rows.
+
+#![allow(dead_code)]
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use once_cell::sync::Lazy;
+use organizer_core::dtos::tag_dto::TagDTO;
+use organizer_core::models::image;
+use organizer_core::models::review_status::ReviewStatus;
+use organizer_core::models::tag_color::TagColor;
+use organizer_core::services::connection_db::{db_ref, init_memory_db};
+use organizer_core::services::file_naming::thumbnail_path_for;
+use organizer_core::services::tag_service;
+use sea_orm::{ActiveModelTrait, Set};
+use std::collections::HashSet;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tokio::runtime::Runtime;
+
+/// One runtime for the whole binary, the database pool is bound to it
+static RUNTIME: Lazy<Runtime> = Lazy::new(|| {
+    let runtime = Runtime::new().expect("Failed to start the test runtime");
+    runtime
+        .block_on(init_memory_db())
+        .expect("Failed to prepare the test database");
+    runtime
+});
+
+/// SQLite shares one cache between the in-memory connections and locks whole
+/// tables, so tests take turns instead of failing on each other's writes
+static SERIAL: Mutex<()> = Mutex::new(());
+
+static NEXT_SCOPE: AtomicU32 = AtomicU32::new(0);
+
+/// Runs an async test against the shared database
+pub fn run(test: impl Future<Output = ()>) {
+    let _turn = SERIAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    RUNTIME.block_on(test);
+}
+
+/// Name no other test uses. Fixed width, so one scope is never part of another.
+pub fn scope() -> String {
+    format!("scope{:04}", NEXT_SCOPE.fetch_add(1, Ordering::SeqCst))
+}
+
+/// A day of the fixture timeline, later days sort as newer
+pub fn day(offset: i64) -> NaiveDateTime {
+    let start = NaiveDate::from_ymd_opt(2025, 1, 1)
+        .and_then(|date| date.and_hms_opt(12, 0, 0))
+        .expect("valid date");
+    start + Duration::days(offset)
+}
+
+/// A prepared image row, inserted with [`ImageFixture::insert`]
+pub struct ImageFixture {
+    description: String,
+    tags: Vec<String>,
+    language: Option<String>,
+    created_at: NaiveDateTime,
+    review_status: ReviewStatus,
+    /// Stored file, made up from the description when not set
+    path: Option<PathBuf>,
+    is_folder: bool,
+}
+
+impl ImageFixture {
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            tags: Vec::new(),
+            language: None,
+            created_at: day(0),
+            review_status: ReviewStatus::Kept,
+            path: None,
+            is_folder: false,
+        }
+    }
+
+    pub fn tags(mut self, names: &[&str]) -> Self {
+        self.tags = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn created_at(mut self, created_at: NaiveDateTime) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    pub fn review_status(mut self, status: ReviewStatus) -> Self {
+        self.review_status = status;
+        self
+    }
+
+    /// Inserts a folder entry instead of a single image
+    pub fn folder(mut self) -> Self {
+        self.is_folder = true;
+        self
+    }
+
+    /// Stores the image at `path`, with the thumbnail named after it
+    pub fn stored_at(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Inserts the image and links its tags, creating the ones that don't exist yet
+    pub async fn insert(self) -> i64 {
+        let db = db_ref();
+        let (path, thumbnail_path) = match &self.path {
+            Some(path) => (
+                path.to_string_lossy().to_string(),
+                thumbnail_path_for(path)
+                    .map(|thumb| thumb.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            ),
+            None => (
+                format!("images/{}.png", self.description),
+                format!("images/{}_thumb.png", self.description),
+            ),
+        };
+        let model = image::ActiveModel {
+            path: Set(path),
+            thumbnail_path: Set(thumbnail_path),
+            description: Set(self.description),
+            created_at: Set(self.created_at),
+            is_folder: Set(self.is_folder),
+            is_prepared: Set(true),
+            is_pinned: Set(false),
+            language: Set(self.language),
+            review_status: Set(self.review_status),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .expect("Failed to insert the image fixture");
+
+        let tags = self.tags.iter().map(|name| new_tag(name)).collect();
+        tag_service::update_tags_for_image(db, model.id, tags)
+            .await
+            .expect("Failed to tag the image fixture");
+        model.id
+    }
+}
+
+/// Tag as the screens pass it before it is saved, matched by name
+pub fn new_tag(name: &str) -> TagDTO {
+    TagDTO {
+        id: 0,
+        name: name.to_string(),
+        color: TagColor::default(),
+        parent_id: None,
+        category: None,
+    }
+}
+
+/// Set of not yet saved tags with the given names
+pub fn new_tags(names: &[&str]) -> HashSet<TagDTO> {
+    names.iter().map(|name| new_tag(name)).collect()
+}
+
+/// The stored tag with this name
+pub async fn find_tag(name: &str) -> Option<TagDTO> {
+    tag_service::find_all()
+        .await
+        .expect("Failed to load the tags")
+        .into_iter()
+        .find(|tag| tag.name == name)
+}
+
+/// Names of the tags on an image, sorted
+pub async fn tag_names(image_id: i64) -> Vec<String> {
+    let mut names: Vec<String> = tag_service::get_tags_for_images(&[image_id], db_ref())
+        .await
+        .expect("Failed to load the image tags")
+        .remove(&image_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tag| tag.name)
+        .collect();
+    names.sort();
+    names
+}
+
+/// Empty folder under the system temp directory, removed when dropped
+pub struct TempFolder(PathBuf);
+
+impl TempFolder {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("organizer-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("Failed to create the temp folder");
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Writes a file with the given content and returns its path
+    pub fn write(&self, name: &str, content: &[u8]) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, content).expect("Failed to write the file");
+        path
+    }
+}
+
+impl Drop for TempFolder {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
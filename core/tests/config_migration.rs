@@ -0,0 +1,75 @@
+use organizer_core::config::{CONFIG_VERSION, Config, SEARCH_HISTORY_LEN, parse_config};
+
+#[test]
+fn unversioned_configs_are_migrated_with_their_values() {
+    let (config, report) =
+        parse_config(r#"{"theme": "Light", "language": "es", "items_per_page": 50}"#);
+
+    assert_eq!(report.migrated_from, Some(0));
+    assert!(!report.defaults_applied());
+    assert_eq!(config.version, CONFIG_VERSION);
+    assert_eq!(config.theme, "Light");
+    assert_eq!(config.items_per_page, 50);
+    assert_eq!(
+        config.thumb_compression,
+        Config::default().thumb_compression
+    );
+}
+
+#[test]
+fn unreadable_settings_fall_back_one_by_one() {
+    let content = format!(
+        r#"{{"version": {CONFIG_VERSION}, "theme": "Light", "language": "en", "items_per_page": "many", "grid_columns": 4}}"#
+    );
+    let (config, report) = parse_config(&content);
+
+    assert_eq!(report.migrated_from, None);
+    assert_eq!(report.reset_fields, vec!["items_per_page".to_string()]);
+    assert_eq!(config.items_per_page, Config::default().items_per_page);
+    assert_eq!(config.theme, "Light");
+    assert_eq!(config.grid_columns, Some(4));
+}
+
+#[test]
+fn broken_files_reset_everything() {
+    let (config, report) = parse_config("{ not json");
+
+    assert!(report.reset_all);
+    assert_eq!(config, Config::default());
+}
+
+#[test]
+fn newer_files_are_read_without_migrating() {
+    let content = format!(
+        r#"{{"version": {}, "theme": "Light", "language": "en", "items_per_page": 20, "from_the_future": true}}"#,
+        CONFIG_VERSION + 1
+    );
+    let (config, report) = parse_config(&content);
+
+    assert_eq!(report.newer, Some(CONFIG_VERSION + 1));
+    assert_eq!(report.migrated_from, None);
+    assert!(!report.defaults_applied());
+    assert_eq!(config.items_per_page, 20);
+}
+
+#[test]
+fn search_history_keeps_the_latest_distinct_queries() {
+    let mut config = Config::default();
+    for index in 0..SEARCH_HISTORY_LEN + 5 {
+        config.remember_search(&format!("query {index}"));
+    }
+    config.remember_search("  ");
+    config.remember_search("Query 3");
+
+    let history = config.search_history.unwrap();
+    assert_eq!(history.len(), SEARCH_HISTORY_LEN);
+    assert_eq!(history[0], "Query 3");
+    assert_eq!(history[1], format!("query {}", SEARCH_HISTORY_LEN + 4));
+    assert_eq!(
+        history
+            .iter()
+            .filter(|q| q.to_lowercase() == "query 3")
+            .count(),
+        1
+    );
+}
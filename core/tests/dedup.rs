@@ -0,0 +1,54 @@
+mod common;
+
+use common::{ImageFixture, run, scope};
+use organizer_core::services::dedup_service;
+
+#[test]
+fn near_identical_hashes_are_duplicates_closest_first() {
+    run(async {
+        let scope = scope();
+        let hash: u64 = 0x5a5a_1234_9876_f0f0;
+        let close = ImageFixture::new(format!("{scope} re-encoded"))
+            .insert()
+            .await;
+        let same = ImageFixture::new(format!("{scope} same")).insert().await;
+        let other = ImageFixture::new(format!("{scope} other")).insert().await;
+        dedup_service::store_hash(close, hash ^ 0b111)
+            .await
+            .unwrap();
+        dedup_service::store_hash(same, hash).await.unwrap();
+        dedup_service::store_hash(other, hash ^ 0xffff)
+            .await
+            .unwrap();
+
+        let found: Vec<i64> = dedup_service::find_by_hash(hash)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|image| image.description.starts_with(&scope))
+            .map(|image| image.id)
+            .collect();
+        assert_eq!(found, vec![same, close]);
+    });
+}
+
+#[test]
+fn hashes_with_the_top_bit_set_are_compared_too() {
+    run(async {
+        let scope = scope();
+        let hash: u64 = 0xf00d_0000_0000_beef;
+        let close = ImageFixture::new(format!("{scope} close")).insert().await;
+        dedup_service::store_hash(close, hash ^ (1 << 63) ^ 1)
+            .await
+            .unwrap();
+
+        let found: Vec<i64> = dedup_service::find_by_hash(hash)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|image| image.description.starts_with(&scope))
+            .map(|image| image.id)
+            .collect();
+        assert_eq!(found, vec![close]);
+    });
+}
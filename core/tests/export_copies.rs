@@ -0,0 +1,163 @@
+mod common;
+
+use common::{TempFolder, run};
+use image::{GenericImageView, ImageBuffer, Rgba};
+use organizer_core::dtos::image_dto::ImageDTO;
+use organizer_core::models::export_preset::{ExportFormat, ExportPreset};
+use organizer_core::models::watermark::{Watermark, WatermarkKind, WatermarkPosition};
+use organizer_core::services::export_service::export_copies;
+use std::fs;
+use std::path::Path;
+
+const GRAY: Rgba<u8> = Rgba([128, 128, 128, 255]);
+
+fn stored_image(path: &Path) -> ImageDTO {
+    ImageDTO {
+        id: 1,
+        path: path.to_string_lossy().to_string(),
+        thumbnail_path: String::new(),
+        description: "photo".to_string(),
+        tags: Default::default(),
+        created_at: String::new(),
+        is_folder: false,
+        is_prepared: true,
+        source_url: None,
+        is_pinned: false,
+        stack_id: None,
+        stack_size: 0,
+        language: None,
+        matches: Vec::new(),
+    }
+}
+
+fn gray_png(folder: &TempFolder, name: &str) -> std::path::PathBuf {
+    let path = folder.path().join(name);
+    ImageBuffer::from_pixel(200, 100, GRAY).save(&path).unwrap();
+    path
+}
+
+fn red_logo(folder: &TempFolder) -> Watermark {
+    let logo = folder.path().join("logo.png");
+    ImageBuffer::from_pixel(20, 20, Rgba([255u8, 0, 0, 255]))
+        .save(&logo)
+        .unwrap();
+    Watermark {
+        kind: WatermarkKind::Image,
+        image_path: logo.to_string_lossy().to_string(),
+        position: WatermarkPosition::BottomRight,
+        opacity: 100,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn copies_without_a_watermark_match_the_original() {
+    run(async {
+        let library = TempFolder::new("export-library-plain");
+        let destination = TempFolder::new("export-destination-plain");
+        let original = gray_png(&library, "photo.png");
+
+        let written = export_copies(
+            vec![stored_image(&original)],
+            destination.path().to_path_buf(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(written, 1);
+        assert_eq!(
+            fs::read(destination.path().join("photo.png")).unwrap(),
+            fs::read(&original).unwrap()
+        );
+    });
+}
+
+#[test]
+fn watermarks_go_on_the_copy_only() {
+    run(async {
+        let library = TempFolder::new("export-library-watermark");
+        let destination = TempFolder::new("export-destination-watermark");
+        let original = gray_png(&library, "photo.png");
+        let before = fs::read(&original).unwrap();
+
+        export_copies(
+            vec![stored_image(&original)],
+            destination.path().to_path_buf(),
+            None,
+            Some(red_logo(&library)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read(&original).unwrap(), before);
+
+        let copy = image::open(destination.path().join("photo.png")).unwrap();
+        assert_eq!(copy.dimensions(), (200, 100));
+        // The logo is scaled to a quarter of the height and kept off the edges
+        assert_eq!(copy.get_pixel(185, 85), Rgba([255, 0, 0, 255]));
+        assert_eq!(copy.get_pixel(10, 10), GRAY);
+        assert_eq!(copy.get_pixel(199, 99), GRAY);
+    });
+}
+
+#[test]
+fn copies_never_overwrite_files_in_the_destination() {
+    run(async {
+        let library = TempFolder::new("export-library-names");
+        let destination = TempFolder::new("export-destination-names");
+        let original = gray_png(&library, "photo.png");
+        destination.write("photo.png", b"already there");
+
+        let written = export_copies(
+            vec![stored_image(&original), stored_image(&original)],
+            destination.path().to_path_buf(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(written, 2);
+        assert_eq!(
+            fs::read(destination.path().join("photo.png")).unwrap(),
+            b"already there"
+        );
+        assert!(destination.path().join("photo_2.png").exists());
+        assert!(destination.path().join("photo_3.png").exists());
+    });
+}
+
+#[test]
+fn presets_shrink_and_convert_the_copies() {
+    run(async {
+        let library = TempFolder::new("export-library-preset");
+        let destination = TempFolder::new("export-destination-preset");
+        let large = gray_png(&library, "large.png");
+        let small = library.path().join("small.png");
+        ImageBuffer::from_pixel(30, 20, GRAY).save(&small).unwrap();
+
+        let preset = ExportPreset {
+            name: "Web".to_string(),
+            max_side: 50,
+            format: ExportFormat::Jpeg,
+            quality: 90,
+        };
+        let written = export_copies(
+            vec![stored_image(&large), stored_image(&small)],
+            destination.path().to_path_buf(),
+            Some(preset),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(written, 2);
+        let large = image::open(destination.path().join("large.jpg")).unwrap();
+        assert_eq!(large.dimensions(), (50, 25));
+        let small = image::open(destination.path().join("small.jpg")).unwrap();
+        assert_eq!(small.dimensions(), (30, 20));
+        assert!(!destination.path().join("large.png").exists());
+    });
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use common::TempFolder;
+use organizer_core::dtos::image_dto::ImageDTO;
+use organizer_core::services::file_naming::{
+    DerivedFile, FOLDER_THUMBNAIL, InvalidName, folder_image_index, folder_image_stem,
+    image_file_name, image_stem, is_derived, parse_derived, preview_name, preview_path_for,
+    thumbnail_name, thumbnail_path_for, typed_stem,
+};
+use organizer_core::services::file_service::expand_folder_dto;
+use proptest::prelude::*;
+use std::path::Path;
+
+/// Stems as they show up on disk: dots, spaces, underscores, digits and non-ASCII
+/// letters, anything but path separators
+fn stem() -> impl Strategy<Value = String> {
+    "[a-zA-Z0-9 ._\\-ãéü漢]{1,24}".prop_filter("a name of its own", |stem| {
+        // The two names the module documents as ambiguous
+        !stem.ends_with(".preview") && stem != "folder"
+    })
+}
+
+fn extension() -> impl Strategy<Value = String> {
+    prop::sample::select(vec!["png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff"])
+        .prop_map(str::to_string)
+}
+
+proptest! {
+    #[test]
+    fn image_names_give_back_their_stem(stem in stem(), extension in extension()) {
+        let name = image_file_name(&stem, &extension);
+        prop_assert_eq!(image_stem(&name), stem.as_str());
+    }
+
+    #[test]
+    fn thumbnail_names_give_back_their_stem(stem in stem()) {
+        let name = thumbnail_name(&stem);
+        prop_assert!(is_derived(&name));
+        prop_assert_eq!(parse_derived(&name), Some(DerivedFile::Thumbnail(stem.as_str())));
+    }
+
+    #[test]
+    fn preview_names_give_back_their_stem(stem in stem()) {
+        let name = preview_name(&stem);
+        prop_assert!(is_derived(&name));
+        prop_assert_eq!(parse_derived(&name), Some(DerivedFile::Preview(stem.as_str())));
+    }
+
+    #[test]
+    fn folder_images_give_back_their_index(stem in stem(), index in 0usize..100_000) {
+        prop_assert_eq!(folder_image_index(&folder_image_stem(&stem, index)), Some(index));
+    }
+
+    #[test]
+    fn thumbnails_sit_next_to_their_image(
+        stem in stem(),
+        extension in extension(),
+        index in 0usize..1000,
+    ) {
+        let image_stem = folder_image_stem(&stem, index);
+        let image_path = Path::new("images").join("7").join(image_file_name(&image_stem, &extension));
+
+        let thumbnail = thumbnail_path_for(&image_path).expect("thumbnail path");
+        prop_assert_eq!(thumbnail.parent(), image_path.parent());
+        let name = thumbnail.file_name().and_then(|n| n.to_str()).expect("UTF-8 name");
+        prop_assert_eq!(parse_derived(name), Some(DerivedFile::Thumbnail(image_stem.as_str())));
+
+        let preview = preview_path_for(&image_path).expect("preview path");
+        let name = preview.file_name().and_then(|n| n.to_str()).expect("UTF-8 name");
+        prop_assert_eq!(parse_derived(name), Some(DerivedFile::Preview(image_stem.as_str())));
+    }
+
+    #[test]
+    fn different_images_never_share_a_thumbnail(a in stem(), b in stem()) {
+        prop_assume!(a != b);
+        prop_assert_ne!(thumbnail_name(&a), thumbnail_name(&b));
+        prop_assert_ne!(thumbnail_name(&a), preview_name(&b));
+    }
+}
+
+#[test]
+fn only_the_last_dot_starts_the_extension() {
+    assert_eq!(image_stem("scan.v1.2_3.png"), "scan.v1.2_3");
+    assert_eq!(image_stem("no_extension"), "no_extension");
+    assert_eq!(image_stem(".hidden"), ".hidden");
+}
+
+#[test]
+fn typed_names_become_stems() {
+    assert_eq!(
+        typed_stem("  Beach trip.JPG ", "jpg"),
+        Ok("Beach trip".to_string())
+    );
+    assert_eq!(typed_stem("notes.v2", "png"), Ok("notes.v2".to_string()));
+    assert_eq!(typed_stem(" .. ", "png"), Err(InvalidName::Empty));
+    assert_eq!(typed_stem("a/b", "png"), Err(InvalidName::Forbidden('/')));
+    assert_eq!(typed_stem("con.txt", "png"), Err(InvalidName::Reserved));
+    assert_eq!(typed_stem("thumb_cat", "png"), Err(InvalidName::Reserved));
+    assert_eq!(
+        typed_stem(&"x".repeat(300), "png"),
+        Err(InvalidName::TooLong)
+    );
+}
+
+#[test]
+fn other_files_are_not_derived() {
+    assert_eq!(
+        parse_derived(FOLDER_THUMBNAIL),
+        Some(DerivedFile::FolderThumbnail)
+    );
+    assert_eq!(parse_derived("image_4_0.png"), None);
+    assert_eq!(parse_derived("meta.json"), None);
+    assert_eq!(parse_derived("thumb_image_4_0.jpg"), None);
+}
+
+#[test]
+fn expanded_folders_pair_dotted_names_with_their_thumbnails() {
+    let folder = TempFolder::new("dotted-names");
+    for name in [
+        "scan.v1_0.png",
+        "thumb_scan.v1_0.png",
+        "scan.v1_1.jpg",
+        "thumb_scan.v1_1.png",
+        FOLDER_THUMBNAIL,
+        "meta.json",
+    ] {
+        folder.write(name, b"");
+    }
+
+    let entry = ImageDTO {
+        id: 1,
+        path: folder.path().to_string_lossy().to_string(),
+        thumbnail_path: String::new(),
+        description: "scans".to_string(),
+        tags: Default::default(),
+        created_at: String::new(),
+        is_folder: true,
+        is_prepared: true,
+        source_url: None,
+        is_pinned: false,
+        stack_id: None,
+        stack_size: 0,
+        language: None,
+        matches: Vec::new(),
+    };
+
+    let images = expand_folder_dto(&entry);
+    let pairs: Vec<(String, String)> = images
+        .iter()
+        .map(|image| {
+            let name = |path: &str| {
+                Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            };
+            (name(&image.path), name(&image.thumbnail_path))
+        })
+        .collect();
+
+    assert_eq!(
+        pairs,
+        [
+            (
+                "scan.v1_0.png".to_string(),
+                "thumb_scan.v1_0.png".to_string()
+            ),
+            (
+                "scan.v1_1.jpg".to_string(),
+                "thumb_scan.v1_1.png".to_string()
+            ),
+        ]
+    );
+    for image in &images {
+        assert!(Path::new(&image.thumbnail_path).exists());
+    }
+}
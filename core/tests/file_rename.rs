@@ -0,0 +1,55 @@
+mod common;
+
+use common::{ImageFixture, TempFolder, run, scope};
+use organizer_core::services::image_service;
+
+#[test]
+fn renaming_moves_the_file_and_its_thumbnail() {
+    run(async {
+        let scope = scope();
+        let folder = TempFolder::new(&format!("rename-{scope}"));
+        let original = folder.write("1_cat.png", b"image");
+        folder.write("thumb_1_cat.png", b"thumbnail");
+        let id = ImageFixture::new(format!("{scope} cat"))
+            .stored_at(&original)
+            .insert()
+            .await;
+
+        let renamed = image_service::rename_file(id, "Sleepy cat.png")
+            .await
+            .expect("Rename failed");
+
+        assert_eq!(
+            renamed.path,
+            folder.path().join("Sleepy cat.png").to_string_lossy()
+        );
+        assert!(!original.exists());
+        assert!(folder.path().join("thumb_Sleepy cat.png").exists());
+        let stored = image_service::find_by_id(id).await.unwrap().unwrap();
+        assert_eq!(stored.path, renamed.path);
+        assert_eq!(stored.thumbnail_path, renamed.thumbnail_path);
+    });
+}
+
+#[test]
+fn taken_names_leave_the_files_alone() {
+    run(async {
+        let scope = scope();
+        let folder = TempFolder::new(&format!("rename-{scope}"));
+        let original = folder.write("2_dog.png", b"image");
+        folder.write("thumb_2_dog.png", b"thumbnail");
+        // Only the thumbnail name is taken
+        folder.write("thumb_taken.png", b"other thumbnail");
+        let id = ImageFixture::new(format!("{scope} dog"))
+            .stored_at(&original)
+            .insert()
+            .await;
+
+        assert!(image_service::rename_file(id, "taken").await.is_err());
+        assert!(image_service::rename_file(id, "thumb_x").await.is_err());
+
+        assert!(original.exists());
+        assert!(folder.path().join("thumb_2_dog.png").exists());
+        assert!(!folder.path().join("taken.png").exists());
+    });
+}
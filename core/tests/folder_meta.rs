@@ -0,0 +1,94 @@
+mod common;
+
+use common::TempFolder;
+use organizer_core::services::file_service::{folder_entry_source, folder_stats};
+use std::fs;
+
+fn entry_with_images(name: &str) -> TempFolder {
+    let entry = TempFolder::new(name);
+    for index in 0..3 {
+        entry.write(&format!("image_9_{index}.png"), b"page");
+        entry.write(&format!("thumb_image_9_{index}.png"), b"page");
+    }
+    entry
+}
+
+fn meta(entry: &TempFolder) -> serde_json::Value {
+    let content = fs::read_to_string(entry.path().join("meta.json")).expect("No meta written");
+    serde_json::from_str(&content).expect("Failed to parse the meta")
+}
+
+#[test]
+fn unversioned_meta_is_upgraded_when_read() {
+    let entry = entry_with_images("meta-upgrade");
+    let old = serde_json::json!({
+        "image_count": 3,
+        "next_index": 3,
+        "source_folder": "/pictures/comic",
+    });
+    entry.write("meta.json", old.to_string().as_bytes());
+
+    assert_eq!(
+        folder_entry_source(entry.path()).as_deref(),
+        Some("/pictures/comic")
+    );
+    let meta = meta(&entry);
+    assert_eq!(meta["version"], 1);
+    assert_eq!(meta["source_folder"], "/pictures/comic");
+    assert!(
+        meta["folder_thumb"]
+            .as_str()
+            .unwrap()
+            .ends_with("thumb_folder.png")
+    );
+}
+
+#[test]
+fn corrupt_meta_is_made_again_from_the_images() {
+    let entry = entry_with_images("meta-corrupt");
+    entry.write("meta.json", b"{\"image_count\": 3, \"next_in");
+
+    let stats = folder_stats(entry.path()).expect("Failed to count the entry");
+    assert_eq!(stats.image_count, 3);
+    let meta = meta(&entry);
+    assert_eq!(meta["version"], 1);
+    assert_eq!(meta["next_index"], 3);
+    assert!(
+        meta["cover_thumb"]
+            .as_str()
+            .unwrap()
+            .ends_with("thumb_image_9_0.png")
+    );
+}
+
+#[test]
+fn inconsistent_meta_keeps_its_source() {
+    let entry = entry_with_images("meta-inconsistent");
+    let broken = serde_json::json!({
+        "version": 1,
+        "image_count": 5,
+        "next_index": 2,
+        "source_folder": "/pictures/comic",
+        "source_hashes": ["a", "b"],
+    });
+    entry.write("meta.json", broken.to_string().as_bytes());
+
+    folder_stats(entry.path()).expect("Failed to count the entry");
+    let meta = meta(&entry);
+    assert_eq!(meta["next_index"], 3);
+    assert_eq!(meta["image_count"], 3);
+    assert_eq!(meta["source_folder"], "/pictures/comic");
+    assert_eq!(meta["source_hashes"], serde_json::json!(["a", "b"]));
+}
+
+#[test]
+fn newer_meta_is_left_untouched() {
+    let entry = entry_with_images("meta-newer");
+    let newer = b"{\"version\": 99, \"layout\": \"unknown\"}";
+    entry.write("meta.json", newer);
+
+    let stats = folder_stats(entry.path()).expect("Failed to count the entry");
+    assert_eq!(stats.image_count, 3);
+    assert_eq!(fs::read(entry.path().join("meta.json")).unwrap(), newer);
+    assert_eq!(folder_entry_source(entry.path()), None);
+}
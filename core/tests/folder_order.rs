@@ -0,0 +1,284 @@
+mod common;
+
+use common::{TempFolder, run};
+use image::{ImageBuffer, Rgba};
+use organizer_core::models::delete_behavior::DeleteBehavior;
+use organizer_core::models::enums::image_type::ImageType;
+use organizer_core::services::file_naming::{natural_order, sort_by_name, thumbnail_name};
+use organizer_core::services::file_service::{
+    delete_image, folder_entry_images, folder_stats, move_into_folder_entry,
+    move_out_of_folder_entry, repair_folder_entry,
+};
+use proptest::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+fn sorted(names: &[&str]) -> Vec<String> {
+    let mut paths: Vec<PathBuf> = names
+        .iter()
+        .map(|name| PathBuf::from("in").join(name))
+        .collect();
+    sort_by_name(&mut paths);
+    paths
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+fn numbers_sort_by_value() {
+    assert_eq!(
+        sorted(&["page10.png", "page2.png", "page1.png"]),
+        ["page1.png", "page2.png", "page10.png"]
+    );
+}
+
+#[test]
+fn case_only_breaks_ties() {
+    assert_eq!(
+        sorted(&["b.png", "a.png", "B.png", "A.png"]),
+        ["A.png", "a.png", "B.png", "b.png"]
+    );
+}
+
+proptest! {
+    #[test]
+    fn order_does_not_depend_on_the_listing(
+        names in prop::collection::vec("[a-cA-C0-9 ._]{0,8}", 0..12),
+        seed in any::<u64>(),
+    ) {
+        let mut shuffled = names.clone();
+        // Deterministic shuffle from the seed, the listing order of the file system
+        let len = shuffled.len();
+        for i in 0..len {
+            let j = (seed.rotate_left(i as u32) as usize).wrapping_add(i) % len;
+            shuffled.swap(i, j);
+        }
+
+        let mut expected = names.clone();
+        expected.sort_by(|a, b| natural_order(a, b));
+        shuffled.sort_by(|a, b| natural_order(a, b));
+        prop_assert_eq!(shuffled, expected);
+    }
+
+    #[test]
+    fn only_identical_names_are_equal(a in "[a-cA-C0-9 ._]{0,8}", b in "[a-cA-C0-9 ._]{0,8}") {
+        prop_assert_eq!(natural_order(&a, &b).is_eq(), a == b);
+        prop_assert_eq!(natural_order(&a, &b), natural_order(&b, &a).reverse());
+    }
+}
+
+fn hash(content: &[u8]) -> String {
+    blake3::hash(content).to_hex().to_string()
+}
+
+/// A folder entry as imports stored it while they sorted names as plain text:
+/// page10 got index 1 and page2 index 2
+fn lexically_imported_entry(source: &TempFolder, entry: &TempFolder) {
+    for page in ["1", "2", "10"] {
+        source.write(&format!("page{page}.png"), page.as_bytes());
+    }
+    for (index, page) in ["1", "10", "2"].iter().enumerate() {
+        entry.write(&format!("image_9_{index}.png"), page.as_bytes());
+        entry.write(&format!("thumb_image_9_{index}.png"), page.as_bytes());
+    }
+
+    let meta = serde_json::json!({
+        "image_count": 3,
+        "next_index": 3,
+        "folder_thumb": entry.path().join("thumb_folder.png"),
+        "cover_thumb": entry.path().join("thumb_image_9_0.png"),
+        "source_folder": fs::canonicalize(source.path()).unwrap(),
+        "source_hashes": [hash(b"1"), hash(b"10"), hash(b"2")],
+    });
+    entry.write("meta.json", meta.to_string().as_bytes());
+}
+
+fn content(entry: &TempFolder, name: &str) -> String {
+    fs::read_to_string(entry.path().join(name)).expect("Failed to read the file")
+}
+
+#[test]
+fn repair_renumbers_images_with_their_thumbnails() {
+    let source = TempFolder::new("repair-source");
+    let entry = TempFolder::new("repair-entry");
+    lexically_imported_entry(&source, &entry);
+
+    let repair = repair_folder_entry(entry.path()).expect("Failed to repair the entry");
+    assert!(repair.reordered);
+    assert_eq!(repair.thumbnails, 0);
+    assert_eq!(
+        PathBuf::from(&repair.cover_thumb),
+        entry.path().join("thumb_image_9_0.png")
+    );
+
+    for (index, page) in ["1", "2", "10"].iter().enumerate() {
+        assert_eq!(content(&entry, &format!("image_9_{index}.png")), *page);
+        assert_eq!(
+            content(&entry, &format!("thumb_image_9_{index}.png")),
+            *page
+        );
+    }
+
+    let meta: serde_json::Value =
+        serde_json::from_str(&content(&entry, "meta.json")).expect("Failed to read the meta");
+    assert_eq!(
+        meta["source_hashes"],
+        serde_json::json!([hash(b"1"), hash(b"2"), hash(b"10")])
+    );
+    assert_eq!(meta["next_index"], 3);
+}
+
+#[test]
+fn repair_leaves_ordered_entries_alone() {
+    let source = TempFolder::new("repaired-source");
+    let entry = TempFolder::new("repaired-entry");
+    lexically_imported_entry(&source, &entry);
+
+    repair_folder_entry(entry.path()).expect("Failed to repair the entry");
+    let again = repair_folder_entry(entry.path()).expect("Failed to repair the entry");
+    assert!(!again.reordered);
+    assert_eq!(content(&entry, "image_9_2.png"), "10");
+}
+
+#[test]
+fn repair_keeps_images_whose_source_is_gone_last() {
+    let source = TempFolder::new("removed-source");
+    let entry = TempFolder::new("removed-entry");
+    lexically_imported_entry(&source, &entry);
+    fs::remove_file(source.path().join("page1.png")).unwrap();
+
+    repair_folder_entry(entry.path()).expect("Failed to repair the entry");
+    for (index, page) in ["2", "10", "1"].iter().enumerate() {
+        assert_eq!(content(&entry, &format!("image_9_{index}.png")), *page);
+        assert_eq!(
+            content(&entry, &format!("thumb_image_9_{index}.png")),
+            *page
+        );
+    }
+}
+
+#[test]
+fn grouping_moves_images_with_their_thumbnails() {
+    let library = TempFolder::new("group-library");
+    let entry = library.path().join("7");
+    let mut images = Vec::new();
+    for (id, page) in [(3, "first"), (5, "second")] {
+        let stem = format!("image_{id}");
+        fs::create_dir_all(library.path().join(id.to_string())).unwrap();
+        let dir = format!("{id}/");
+        images.push(library.write(&format!("{dir}{stem}.png"), page.as_bytes()));
+        library.write(&format!("{dir}{}", thumbnail_name(&stem)), page.as_bytes());
+    }
+
+    let moved = move_into_folder_entry(&entry, &images, "image_7").expect("Failed to group");
+    assert_eq!(moved.import.thumbnails.len(), 2);
+    for (index, page) in ["first", "second"].iter().enumerate() {
+        let stem = format!("image_7_{index}");
+        assert_eq!(
+            fs::read_to_string(entry.join(format!("{stem}.png"))).unwrap(),
+            *page
+        );
+        assert_eq!(
+            fs::read_to_string(entry.join(thumbnail_name(&stem))).unwrap(),
+            *page
+        );
+    }
+    let meta: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(entry.join("meta.json")).expect("Failed to read the meta"),
+    )
+    .unwrap();
+    assert_eq!(meta["image_count"], 2);
+    assert_eq!(
+        meta["source_hashes"],
+        serde_json::json!([hash(b"first"), hash(b"second")])
+    );
+
+    moved.remove_sources();
+    assert!(!library.path().join("3").exists());
+    assert!(!library.path().join("5").exists());
+}
+
+#[test]
+fn undoing_a_group_puts_the_images_back() {
+    let library = TempFolder::new("ungroup-library");
+    let entry = library.path().join("7");
+    let image = library.write("image_3.png", b"page");
+    library.write(&thumbnail_name("image_3"), b"page");
+
+    let moved = move_into_folder_entry(&entry, std::slice::from_ref(&image), "image_7")
+        .expect("Failed to group");
+    moved.undo();
+    assert_eq!(fs::read_to_string(&image).unwrap(), "page");
+    assert!(library.path().join(thumbnail_name("image_3")).exists());
+    assert!(!entry.exists());
+}
+
+#[test]
+fn splitting_gives_each_image_its_own_folder() {
+    let library = TempFolder::new("split-library");
+    let entry = library.path().join("7");
+    fs::create_dir_all(&entry).unwrap();
+    for index in 0..2 {
+        ImageBuffer::from_pixel(40, 30, Rgba([index as u8, 0, 0, 255]))
+            .save(entry.join(format!("image_7_{index}.png")))
+            .unwrap();
+    }
+
+    let images: Vec<_> = folder_entry_images(&entry)
+        .unwrap()
+        .into_iter()
+        .zip([8, 9])
+        .map(|(path, id)| {
+            (
+                path,
+                library.path().join(id.to_string()),
+                format!("image_{id}"),
+            )
+        })
+        .collect();
+    let split = move_out_of_folder_entry(&entry, &images).expect("Failed to split");
+    for (id, (path, thumbnail)) in [8, 9].iter().zip(&split.images) {
+        let dir = library.path().join(id.to_string());
+        assert_eq!(PathBuf::from(path), dir.join(format!("image_{id}.png")));
+        assert_eq!(
+            PathBuf::from(thumbnail),
+            dir.join(thumbnail_name(&format!("image_{id}")))
+        );
+        assert!(PathBuf::from(thumbnail).exists());
+    }
+
+    split.remove_source();
+    assert!(!entry.exists());
+}
+
+#[test]
+fn folder_totals_are_kept_until_an_image_goes() {
+    run(async {
+        let source = TempFolder::new("stats-source");
+        let entry = TempFolder::new("stats-entry");
+        lexically_imported_entry(&source, &entry);
+
+        let stats = folder_stats(entry.path()).expect("Failed to count the entry");
+        assert_eq!(stats.image_count, 3);
+        assert_eq!(stats.total_size, 4);
+        assert!(stats.first_date.is_some());
+        assert!(content(&entry, "meta.json").contains("\"total_size\": 4"));
+
+        // Kept in the meta, the files are not read again
+        entry.write("image_9_0.png", b"longer");
+        assert_eq!(folder_stats(entry.path()).unwrap(), stats);
+
+        let removed = entry.path().join("image_9_1.png");
+        delete_image(
+            removed.to_str().unwrap(),
+            ImageType::FromFolder,
+            DeleteBehavior::Permanent,
+        )
+        .await
+            .expect("Failed to delete the image");
+        let stats = folder_stats(entry.path()).unwrap();
+        assert_eq!(stats.image_count, 2);
+        assert_eq!(stats.total_size, 7);
+    });
+}
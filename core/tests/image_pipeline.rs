@@ -0,0 +1,77 @@
+mod common;
+
+use common::TempFolder;
+use image::metadata::Orientation;
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb, Rgba};
+use organizer_core::services::image_processor::{
+    ColorTarget, ConvertColor, Frame, Orient, Pipeline, PngEncoder, Resize, Step,
+};
+
+fn landscape() -> DynamicImage {
+    DynamicImage::ImageRgb8(ImageBuffer::from_pixel(400, 200, Rgb([10, 20, 30])))
+}
+
+#[test]
+fn orient_turns_the_picture_upright() {
+    let image = landscape();
+    let frame = Frame {
+        orientation: Orientation::Rotate90,
+        ..Frame::borrowed(&image)
+    };
+
+    let upright = Orient.apply(frame).unwrap();
+    assert_eq!(upright.image.dimensions(), (200, 400));
+    assert_eq!(upright.orientation, Orientation::NoTransforms);
+}
+
+#[test]
+fn steps_run_in_the_order_they_were_added() {
+    let pipeline = Pipeline::thumbnail(100, 100, 9).with_step(ConvertColor {
+        target: ColorTarget::Luma8,
+    });
+    assert_eq!(
+        pipeline.step_names(),
+        ["orient", "resize", "convert color", "convert color"]
+    );
+
+    let image = landscape();
+    let frame = pipeline.process(Frame::borrowed(&image)).unwrap();
+    assert_eq!(frame.image.dimensions(), (100, 50));
+    assert_eq!(frame.image.color(), image::ColorType::L8);
+}
+
+#[test]
+fn thumbnails_fit_the_box() {
+    let folder = TempFolder::new("pipeline-thumbnail");
+    let output = folder.path().join("thumb.png");
+
+    Pipeline::thumbnail(120, 120, 9)
+        .run(Frame::borrowed(&landscape()), &output)
+        .unwrap();
+
+    let thumbnail = image::open(&output).unwrap();
+    assert_eq!(thumbnail.dimensions(), (120, 60));
+    assert_eq!(thumbnail.get_pixel(60, 30), Rgba([10, 20, 30, 255]));
+}
+
+#[test]
+fn deep_images_are_written_with_eight_bits() {
+    let folder = TempFolder::new("pipeline-16-bit");
+    let output = folder.path().join("deep.png");
+    let image = DynamicImage::ImageRgb16(ImageBuffer::from_pixel(8, 4, Rgb([65535, 0, 32896])));
+
+    Pipeline::new(PngEncoder::new(5))
+        .run(Frame::borrowed(&image), &output)
+        .unwrap();
+
+    let written = image::open(&output).unwrap();
+    assert_eq!(written.dimensions(), (8, 4));
+    assert_eq!(written.get_pixel(0, 0), Rgba([255, 0, 128, 255]));
+}
+
+#[test]
+fn resize_keeps_the_aspect_ratio_of_tall_pictures() {
+    let image = DynamicImage::ImageRgb8(ImageBuffer::from_pixel(30, 300, Rgb([0, 0, 0])));
+    let frame = Resize::fit(50, 50).apply(Frame::borrowed(&image)).unwrap();
+    assert_eq!(frame.image.dimensions(), (5, 50));
+}
@@ -0,0 +1,511 @@
+mod common;
+
+use common::{ImageFixture, day, run, scope};
+use organizer_core::dtos::image_dto::{ImageDTO, ImageUpdateDTO};
+use organizer_core::models::filter::{Filter, SortOrder, TagMatchMode};
+use organizer_core::services::image_service;
+use std::collections::HashSet;
+
+fn filter(query: &str, tags: &[&str]) -> Filter {
+    Filter {
+        query: query.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        ..Filter::new()
+    }
+}
+
+async fn search(filter: Filter) -> Vec<ImageDTO> {
+    image_service::find_all_matching(filter)
+        .await
+        .expect("Search failed")
+}
+
+fn ids(images: &[ImageDTO]) -> HashSet<i64> {
+    images.iter().map(|image| image.id).collect()
+}
+
+fn descriptions(images: &[ImageDTO]) -> Vec<&str> {
+    images
+        .iter()
+        .map(|image| image.description.as_str())
+        .collect()
+}
+
+#[test]
+fn query_matches_part_of_the_description() {
+    run(async {
+        let scope = scope();
+        let cat = ImageFixture::new(format!("{scope} black cat"))
+            .insert()
+            .await;
+        let dog = ImageFixture::new(format!("{scope} brown dog"))
+            .insert()
+            .await;
+
+        let found = search(filter(&format!("{scope} black"), &[])).await;
+        assert_eq!(ids(&found), HashSet::from([cat]));
+
+        let found = search(filter(&scope, &[])).await;
+        assert_eq!(ids(&found), HashSet::from([cat, dog]));
+    });
+}
+
+#[test]
+fn plus_separated_terms_match_any_of_them() {
+    run(async {
+        let scope = scope();
+        let cat = ImageFixture::new(format!("{scope}cat")).insert().await;
+        let dog = ImageFixture::new(format!("{scope}dog")).insert().await;
+        ImageFixture::new(format!("{scope}bird")).insert().await;
+
+        let found = search(filter(&format!("{scope}cat + {scope}dog"), &[])).await;
+        assert_eq!(ids(&found), HashSet::from([cat, dog]));
+    });
+}
+
+#[test]
+fn words_match_by_prefix_and_quotes_keep_phrases() {
+    run(async {
+        let scope = scope();
+        let cafe = ImageFixture::new(format!("{scope} Café with black cats"))
+            .insert()
+            .await;
+        let reversed = ImageFixture::new(format!("{scope} cats, black and white"))
+            .insert()
+            .await;
+
+        let found = search(filter(&format!("{scope} cafe"), &[])).await;
+        assert_eq!(ids(&found), HashSet::from([cafe]), "accents are ignored");
+
+        let found = search(filter(&format!("{scope} bla ca"), &[])).await;
+        assert_eq!(ids(&found), HashSet::from([cafe, reversed]));
+
+        let found = search(filter(&format!("{scope} \"black cats\""), &[])).await;
+        assert_eq!(ids(&found), HashSet::from([cafe]));
+
+        let found = search(filter(&format!("{scope} ats"), &[])).await;
+        assert!(found.is_empty(), "words match from their start");
+
+        let renamed = ImageUpdateDTO {
+            description: Some(format!("{scope} renamed")),
+            is_prepared: true,
+            ..Default::default()
+        };
+        image_service::update_from_dto(reversed, renamed)
+            .await
+            .unwrap();
+        let found = search(filter(&format!("{scope} renamed"), &[])).await;
+        assert_eq!(ids(&found), HashSet::from([reversed]));
+        let found = search(filter(&format!("{scope} white"), &[])).await;
+        assert!(found.is_empty());
+    });
+}
+
+#[test]
+fn tags_must_all_be_present() {
+    run(async {
+        let scope = scope();
+        let (red, round) = (format!("{scope}-red"), format!("{scope}-round"));
+        let both = ImageFixture::new("apple")
+            .tags(&[&red, &round])
+            .insert()
+            .await;
+        let only_red = ImageFixture::new("brick").tags(&[&red]).insert().await;
+        ImageFixture::new("ball").tags(&[&round]).insert().await;
+
+        let found = search(filter("", &[&red])).await;
+        assert_eq!(ids(&found), HashSet::from([both, only_red]));
+
+        let found = search(filter("", &[&red, &round])).await;
+        assert_eq!(ids(&found), HashSet::from([both]));
+        assert_eq!(found[0].tags.len(), 2, "every tag of the image is loaded");
+    });
+}
+
+#[test]
+fn any_tag_matches_images_with_one_of_them() {
+    run(async {
+        let scope = scope();
+        let (red, round) = (format!("{scope}-red"), format!("{scope}-round"));
+        let both = ImageFixture::new("apple")
+            .tags(&[&red, &round])
+            .insert()
+            .await;
+        let only_red = ImageFixture::new("brick").tags(&[&red]).insert().await;
+        let only_round = ImageFixture::new("ball").tags(&[&round]).insert().await;
+
+        let mut any = filter("", &[&red, &round]);
+        any.tag_match = TagMatchMode::Any;
+        let result = image_service::find_all(any, 0, 3).await.unwrap();
+
+        assert_eq!(
+            ids(&result.content),
+            HashSet::from([both, only_red, only_round])
+        );
+        assert_eq!(result.total_pages, 1, "images with both tags count once");
+    });
+}
+
+#[test]
+fn refinements_narrow_the_query() {
+    run(async {
+        let scope = scope();
+        let black_cat = ImageFixture::new(format!("{scope} black cat sleeping"))
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} white cat sleeping"))
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} black dog sleeping"))
+            .insert()
+            .await;
+
+        let mut refined = filter("black", &[]);
+        refined.refinements = vec![scope.clone(), "cat".to_string()];
+        assert_eq!(ids(&search(refined).await), HashSet::from([black_cat]));
+
+        let mut steps_only = filter("", &[]);
+        steps_only.refinements = vec![scope, "sleeping".to_string()];
+        assert_eq!(search(steps_only).await.len(), 3);
+    });
+}
+
+#[test]
+fn fuzzy_query_tolerates_typos_and_ranks_closer_first() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-fuzzy");
+        let cat = ImageFixture::new(format!("{scope} sleeping cat"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+        let bat = ImageFixture::new(format!("{scope} sleeping bat"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} running dog"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+
+        assert!(search(filter("slepeing cat", &[&tag])).await.is_empty());
+
+        let fuzzy = Filter {
+            fuzzy: true,
+            ..filter("slepeing cat", &[&tag])
+        };
+        let found: Vec<i64> = search(fuzzy).await.iter().map(|image| image.id).collect();
+        assert_eq!(found, vec![cat, bat]);
+    });
+}
+
+#[test]
+fn fuzzy_query_finds_misspelled_words() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-misspelled");
+        let cat = ImageFixture::new(format!("{scope} cat"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+        let receipt = ImageFixture::new(format!("{scope} grocery receipt"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+
+        for (query, expected) in [("catt", cat), ("recipt", receipt)] {
+            let fuzzy = Filter {
+                fuzzy: true,
+                ..filter(query, &[&tag])
+            };
+            assert_eq!(
+                ids(&search(fuzzy).await),
+                HashSet::from([expected]),
+                "{query}"
+            );
+        }
+    });
+}
+
+#[test]
+fn matched_parts_of_descriptions_are_marked() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-marked");
+        ImageFixture::new(format!("{scope} Cat on a cat tree"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+
+        let exact = search(filter("cat", &[&tag])).await;
+        let description = exact[0].description.as_str();
+        let marked: Vec<&str> = exact[0]
+            .matches
+            .iter()
+            .map(|range| &description[range.clone()])
+            .collect();
+        assert_eq!(marked, vec!["Cat", "cat"]);
+
+        let fuzzy = Filter {
+            fuzzy: true,
+            ..filter("tre", &[&tag])
+        };
+        let found = search(fuzzy).await;
+        let description = found[0].description.as_str();
+        let marked: Vec<&str> = found[0]
+            .matches
+            .iter()
+            .map(|range| &description[range.clone()])
+            .collect();
+        assert_eq!(marked, vec!["tree"]);
+    });
+}
+
+#[test]
+fn matches_are_marked_whatever_their_accents() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-accents");
+        ImageFixture::new(format!("{scope} Café in São Paulo"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+
+        let found = search(filter("cafe \"sao paulo\"", &[&tag])).await;
+        let description = found[0].description.as_str();
+        let marked: Vec<&str> = found[0]
+            .matches
+            .iter()
+            .map(|range| &description[range.clone()])
+            .collect();
+        assert_eq!(marked, vec!["Café", "São Paulo"]);
+    });
+}
+
+#[test]
+fn query_and_tags_narrow_each_other() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-animal");
+        let cat = ImageFixture::new(format!("{scope} cat"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} dog"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} cat drawing"))
+            .insert()
+            .await;
+
+        let found = search(filter(&format!("{scope} cat"), &[&tag])).await;
+        assert_eq!(ids(&found), HashSet::from([cat]));
+    });
+}
+
+#[test]
+fn language_combines_with_query_and_tags() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-sign");
+        let japanese = ImageFixture::new(format!("{scope} menu"))
+            .language(&format!("{scope}-ja"))
+            .tags(&[&tag])
+            .insert()
+            .await;
+        let english = ImageFixture::new(format!("{scope} menu"))
+            .language(&format!("{scope}-en"))
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} poster"))
+            .language(&format!("{scope}-ja"))
+            .insert()
+            .await;
+
+        let only_language = Filter {
+            language: Some(format!("{scope}-en")),
+            ..Filter::new()
+        };
+        assert_eq!(ids(&search(only_language).await), HashSet::from([english]));
+
+        let with_query = Filter {
+            language: Some(format!("{scope}-ja")),
+            ..filter(&format!("{scope} menu"), &[])
+        };
+        assert_eq!(ids(&search(with_query).await), HashSet::from([japanese]));
+
+        let with_tags = Filter {
+            language: Some(format!("{scope}-en")),
+            ..filter("", &[&tag])
+        };
+        assert!(search(with_tags).await.is_empty());
+    });
+}
+
+#[test]
+fn sort_orders() {
+    run(async {
+        let scope = scope();
+        ImageFixture::new(format!("{scope} b"))
+            .created_at(day(1))
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} C"))
+            .created_at(day(0))
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} a"))
+            .created_at(day(2))
+            .insert()
+            .await;
+
+        let sorted = |sort_order| Filter {
+            sort_order,
+            ..filter(&scope, &[])
+        };
+        let a = format!("{scope} a");
+        let b = format!("{scope} b");
+        let c = format!("{scope} C");
+
+        let found = search(sorted(SortOrder::CreatedDesc)).await;
+        assert_eq!(descriptions(&found), [&a, &b, &c]);
+
+        let found = search(sorted(SortOrder::CreatedAsc)).await;
+        assert_eq!(descriptions(&found), [&c, &b, &a]);
+
+        // Case doesn't count, "C" comes after "b"
+        let found = search(sorted(SortOrder::DescriptionAsc)).await;
+        assert_eq!(descriptions(&found), [&a, &b, &c]);
+    });
+}
+
+#[test]
+fn pages_split_the_results() {
+    run(async {
+        let scope = scope();
+        for offset in 0..7 {
+            ImageFixture::new(format!("{scope} {offset}"))
+                .created_at(day(offset))
+                .insert()
+                .await;
+        }
+
+        let mut seen = HashSet::new();
+        for (page, expected) in [(0, 3), (1, 3), (2, 1), (3, 0)] {
+            let result = image_service::find_all(filter(&scope, &[]), page, 3)
+                .await
+                .expect("Search failed");
+            assert_eq!(result.total_pages, 3);
+            assert_eq!(result.page_number, page);
+            assert_eq!(result.content.len(), expected, "page {page}");
+            seen.extend(ids(&result.content));
+        }
+        assert_eq!(seen.len(), 7, "no image is on two pages");
+
+        let newest = image_service::find_all(filter(&scope, &[]), 0, 3)
+            .await
+            .expect("Search failed");
+        assert_eq!(newest.content[0].description, format!("{scope} 6"));
+    });
+}
+
+#[test]
+fn page_count_rounds_up_and_is_zero_without_results() {
+    run(async {
+        let scope = scope();
+        for offset in 0..6 {
+            ImageFixture::new(format!("{scope} {offset}"))
+                .insert()
+                .await;
+        }
+
+        for (size, pages) in [(1, 6), (3, 2), (4, 2), (6, 1), (10, 1)] {
+            let result = image_service::find_all(filter(&scope, &[]), 0, size)
+                .await
+                .expect("Search failed");
+            assert_eq!(result.total_pages, pages, "page size {size}");
+        }
+
+        let empty = image_service::find_all(filter(&format!("{scope} missing"), &[]), 0, 3)
+            .await
+            .expect("Search failed");
+        assert_eq!(empty.total_pages, 0);
+        assert!(empty.content.is_empty());
+    });
+}
+
+#[test]
+fn pages_count_images_once_with_several_matching_tags() {
+    run(async {
+        let scope = scope();
+        let (red, round) = (format!("{scope}-red"), format!("{scope}-round"));
+        for name in ["apple", "cherry", "tomato"] {
+            ImageFixture::new(name).tags(&[&red, &round]).insert().await;
+        }
+
+        let result = image_service::find_all(filter("", &[&red, &round]), 0, 2)
+            .await
+            .expect("Search failed");
+        assert_eq!(result.total_pages, 2);
+        assert_eq!(result.content.len(), 2);
+    });
+}
+
+#[test]
+fn excluded_tags_leave_out_their_images() {
+    run(async {
+        let scope = scope();
+        let (cat, meme) = (format!("{scope}-cat"), format!("{scope}-meme"));
+        let plain = ImageFixture::new(format!("{scope} plain"))
+            .tags(&[&cat])
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} funny"))
+            .tags(&[&cat, &meme])
+            .insert()
+            .await;
+        let untagged = ImageFixture::new(format!("{scope} untagged")).insert().await;
+
+        let mut with_cat = filter("", &[&cat]);
+        with_cat.excluded_tags = HashSet::from([meme.clone()]);
+        assert_eq!(ids(&search(with_cat).await), HashSet::from([plain]));
+
+        let mut only_excluded = filter(&scope, &[]);
+        only_excluded.excluded_tags = HashSet::from([meme]);
+        assert_eq!(
+            ids(&search(only_excluded).await),
+            HashSet::from([plain, untagged])
+        );
+    });
+}
+
+#[test]
+fn tag_counts_cover_the_matching_images() {
+    run(async {
+        let scope = scope();
+        let (red, round, shiny) = (
+            format!("{scope}-red"),
+            format!("{scope}-round"),
+            format!("{scope}-shiny"),
+        );
+        ImageFixture::new("apple")
+            .tags(&[&red, &round, &shiny])
+            .insert()
+            .await;
+        ImageFixture::new("brick").tags(&[&red, &shiny]).insert().await;
+        ImageFixture::new("cherry").tags(&[&red, &round]).insert().await;
+        ImageFixture::new("ball").tags(&[&round]).insert().await;
+
+        let counts = image_service::count_tags_in_results(filter("", &[&red]), 10)
+            .await
+            .unwrap();
+        let counts: Vec<(&str, u64)> = counts
+            .iter()
+            .map(|(tag, count)| (tag.name.as_str(), *count))
+            .collect();
+
+        // The selected tag is left out, the ball doesn't match
+        assert_eq!(counts, vec![(round.as_str(), 2), (shiny.as_str(), 2)]);
+    });
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{ImageFixture, day, run, scope};
+use organizer_core::models::image;
+use organizer_core::models::review_status::ReviewStatus;
+use organizer_core::services::connection_db::db_ref;
+use organizer_core::services::image_service;
+use sea_orm::EntityTrait;
+
+/// Inbox entries of the test, in the order the inbox lists them
+async fn inbox(scope: &str) -> Vec<i64> {
+    image_service::find_unreviewed(10_000)
+        .await
+        .expect("Failed to load the inbox")
+        .into_iter()
+        .filter(|image| image.description.starts_with(scope))
+        .map(|image| image.id)
+        .collect()
+}
+
+#[test]
+fn new_entries_start_unreviewed() {
+    run(async {
+        let scope = scope();
+        let id = image_service::insert_image(&format!("{scope} imported"))
+            .await
+            .unwrap();
+
+        let model = image::Entity::find_by_id(id)
+            .one(db_ref())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(model.review_status, ReviewStatus::Unreviewed);
+    });
+}
+
+#[test]
+fn inbox_lists_unreviewed_images_oldest_first() {
+    run(async {
+        let scope = scope();
+        let newer = ImageFixture::new(format!("{scope} newer"))
+            .created_at(day(2))
+            .review_status(ReviewStatus::Unreviewed)
+            .insert()
+            .await;
+        let older = ImageFixture::new(format!("{scope} older"))
+            .created_at(day(1))
+            .review_status(ReviewStatus::Unreviewed)
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} kept")).insert().await;
+        ImageFixture::new(format!("{scope} needs work"))
+            .review_status(ReviewStatus::NeedsWork)
+            .insert()
+            .await;
+
+        assert_eq!(inbox(&scope).await, vec![older, newer]);
+    });
+}
+
+#[test]
+fn triaged_images_leave_the_inbox() {
+    run(async {
+        let scope = scope();
+        let kept = ImageFixture::new(format!("{scope} kept"))
+            .review_status(ReviewStatus::Unreviewed)
+            .insert()
+            .await;
+        let later = ImageFixture::new(format!("{scope} later"))
+            .review_status(ReviewStatus::Unreviewed)
+            .insert()
+            .await;
+
+        image_service::set_review_status(kept, ReviewStatus::Kept)
+            .await
+            .unwrap();
+        assert_eq!(inbox(&scope).await, vec![later]);
+
+        image_service::set_review_status(later, ReviewStatus::NeedsWork)
+            .await
+            .unwrap();
+        assert!(inbox(&scope).await.is_empty());
+    });
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{ImageFixture, run, scope};
+use organizer_core::models::image;
+use organizer_core::services::connection_db::db_ref;
+use organizer_core::services::library_location_service;
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
+
+/// Inserts an entry stored under `root`
+async fn stored_in(root: &Path, description: String) -> i64 {
+    let id = ImageFixture::new(description).insert().await;
+    let images = root.join("images").join(id.to_string());
+    image::ActiveModel {
+        id: Set(id),
+        path: Set(images.join("original.png").to_string_lossy().to_string()),
+        thumbnail_path: Set(images.join("thumb.png").to_string_lossy().to_string()),
+        ..Default::default()
+    }
+    .update(db_ref())
+    .await
+    .expect("Failed to point the fixture at its folder");
+    id
+}
+
+async fn path_of(id: i64) -> PathBuf {
+    let model = image::Entity::find_by_id(id)
+        .one(db_ref())
+        .await
+        .unwrap()
+        .expect("entry exists");
+    PathBuf::from(model.path)
+}
+
+#[test]
+fn moved_library_paths_follow_the_new_folder() {
+    run(async {
+        let scope = scope();
+        let old_root = PathBuf::from(format!("{MAIN_SEPARATOR}{scope}{MAIN_SEPARATOR}lib"));
+        let new_root = PathBuf::from(format!("{MAIN_SEPARATOR}{scope}{MAIN_SEPARATOR}moved"));
+        // Shares the start of the old folder's name without being inside it
+        let sibling = PathBuf::from(format!("{MAIN_SEPARATOR}{scope}{MAIN_SEPARATOR}library"));
+        let moved = stored_in(&old_root, format!("{scope} moved")).await;
+        let kept = stored_in(&sibling, format!("{scope} kept")).await;
+
+        let changed = library_location_service::rewrite_paths(&old_root, &new_root)
+            .await
+            .expect("Failed to rewrite the paths");
+
+        assert_eq!(changed, 2, "the original and the thumbnail");
+        assert!(path_of(moved).await.starts_with(new_root.join("images")));
+        assert!(path_of(kept).await.starts_with(&sibling));
+    });
+}
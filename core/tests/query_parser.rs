@@ -0,0 +1,145 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{ImageFixture, day, run, scope};
+use organizer_core::models::filter::{Filter, TagMatchMode};
+use organizer_core::services::image_service;
+use organizer_core::services::query_parser::{ParsedQuery, apply_operators, parse};
+use std::collections::HashSet;
+
+fn date(value: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn operators_are_taken_out_of_the_text() {
+    let parsed = parse(
+        r#"black cat tag:Animals -tag:"old photos" after:2024-01-01 is:folder category:Source"#,
+    );
+
+    assert_eq!(
+        parsed,
+        ParsedQuery {
+            text: "black cat".to_string(),
+            tags: vec!["animals".to_string()],
+            excluded_tags: vec!["old photos".to_string()],
+            before: None,
+            after: Some(date("2024-01-01")),
+            is_folder: Some(true),
+            category: Some("source".to_string()),
+        }
+    );
+}
+
+#[test]
+fn unreadable_operators_stay_in_the_text() {
+    let parsed = parse(r#"before:someday tag: "red car" -tag:x http://example.com"#);
+
+    assert_eq!(
+        parsed.text,
+        r#"before:someday tag: "red car" http://example.com"#
+    );
+    assert_eq!(parsed.excluded_tags, vec!["x".to_string()]);
+    assert_eq!(parsed.before, None);
+}
+
+#[test]
+fn refinements_narrow_the_dates() {
+    let mut filter = Filter {
+        query: "before:2024-06-01 cat".to_string(),
+        refinements: vec!["before:2024-03-01 after:2024-01-01".to_string()],
+        ..Filter::new()
+    };
+    apply_operators(&mut filter);
+
+    assert_eq!(filter.query, "cat");
+    assert_eq!(filter.refinements, vec![String::new()]);
+    assert_eq!(filter.before, Some(date("2024-03-01")));
+    assert_eq!(filter.after, Some(date("2024-01-01")));
+}
+
+#[test]
+fn parsed_filters_select_by_date_and_kind() {
+    run(async {
+        let scope = scope();
+        let early = ImageFixture::new(format!("{scope} early"))
+            .created_at(day(0))
+            .insert()
+            .await;
+        let late = ImageFixture::new(format!("{scope} late"))
+            .created_at(day(10))
+            .insert()
+            .await;
+        let folder = ImageFixture::new(format!("{scope} album"))
+            .created_at(day(10))
+            .folder()
+            .insert()
+            .await;
+
+        let search = |query: String| async move {
+            let mut filter = Filter {
+                query,
+                ..Filter::new()
+            };
+            apply_operators(&mut filter);
+            let found = image_service::find_all_matching(filter).await.unwrap();
+            found.iter().map(|image| image.id).collect::<HashSet<_>>()
+        };
+
+        let cutoff = day(5).date().format("%Y-%m-%d");
+        assert_eq!(
+            search(format!("{scope} before:{cutoff}")).await,
+            HashSet::from([early])
+        );
+        assert_eq!(
+            search(format!("{scope} after:{cutoff}")).await,
+            HashSet::from([late, folder])
+        );
+        assert_eq!(
+            search(format!("{scope} is:folder")).await,
+            HashSet::from([folder])
+        );
+        assert_eq!(
+            search(format!("{scope} -is:folder after:{cutoff}")).await,
+            HashSet::from([late])
+        );
+    });
+}
+
+#[test]
+fn tag_operators_are_required_in_any_mode() {
+    run(async {
+        let scope = scope();
+        let (red, round, metal) = (
+            format!("{scope}-red"),
+            format!("{scope}-round"),
+            format!("{scope}-metal"),
+        );
+        let red_metal = ImageFixture::new(format!("{scope} can"))
+            .tags(&[&red, &metal])
+            .insert()
+            .await;
+        ImageFixture::new(format!("{scope} brick"))
+            .tags(&[&red])
+            .insert()
+            .await;
+        let round_metal = ImageFixture::new(format!("{scope} coin"))
+            .tags(&[&round, &metal])
+            .insert()
+            .await;
+
+        let mut filter = Filter {
+            query: format!("tag:{metal}"),
+            tags: HashSet::from([red, round]),
+            tag_match: TagMatchMode::Any,
+            ..Filter::new()
+        };
+        apply_operators(&mut filter);
+        let found = image_service::find_all_matching(filter).await.unwrap();
+
+        assert_eq!(
+            found.iter().map(|image| image.id).collect::<HashSet<_>>(),
+            HashSet::from([red_metal, round_metal])
+        );
+    });
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::{ImageFixture, find_tag, run, scope};
+use organizer_core::dtos::tag_dto::TagUpdateDTO;
+use organizer_core::models::filter::{Filter, TagMatchMode};
+use organizer_core::models::tag_color::TagColor;
+use organizer_core::services::{image_service, tag_service};
+use std::collections::HashSet;
+
+async fn tagged(query: &str, tags: &[&str], excluded: &[&str], mode: TagMatchMode) -> HashSet<i64> {
+    let filter = Filter {
+        query: query.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        excluded_tags: excluded.iter().map(|tag| tag.to_string()).collect(),
+        tag_match: mode,
+        ..Filter::new()
+    };
+    image_service::find_all_matching(filter)
+        .await
+        .expect("Search failed")
+        .iter()
+        .map(|image| image.id)
+        .collect()
+}
+
+#[test]
+fn nested_names_create_their_parents() {
+    run(async {
+        let scope = scope();
+        tag_service::save(&format!(" {scope}-Animals / Cats/ "), TagColor::Green)
+            .await
+            .unwrap();
+
+        let parent = find_tag(&format!("{scope}-animals")).await.unwrap();
+        let child = find_tag(&format!("{scope}-animals/cats")).await.unwrap();
+        assert_eq!(parent.parent_id, None);
+        assert_eq!(parent.color, TagColor::Green);
+        assert_eq!(child.parent_id, Some(parent.id));
+        assert_eq!(tag_service::leaf_name(&child.name), "cats");
+    });
+}
+
+#[test]
+fn a_parent_tag_matches_the_images_of_its_children() {
+    run(async {
+        let scope = scope();
+        let (animals, cats, dogs, plants) = (
+            format!("{scope}-animals"),
+            format!("{scope}-animals/cats"),
+            format!("{scope}-animals/dogs/puppies"),
+            format!("{scope}-plants"),
+        );
+        let animal = ImageFixture::new(&scope).tags(&[&animals]).insert().await;
+        let cat = ImageFixture::new(&scope).tags(&[&cats]).insert().await;
+        let puppy = ImageFixture::new(&scope)
+            .tags(&[&dogs, &plants])
+            .insert()
+            .await;
+        let plant = ImageFixture::new(&scope).tags(&[&plants]).insert().await;
+
+        let found = tagged("", &[&animals], &[], TagMatchMode::Any).await;
+        assert_eq!(found, HashSet::from([animal, cat, puppy]));
+
+        let found = tagged("", &[&animals, &plants], &[], TagMatchMode::All).await;
+        assert_eq!(found, HashSet::from([puppy]));
+
+        let found = tagged(&scope, &[], &[&animals], TagMatchMode::Any).await;
+        assert_eq!(found, HashSet::from([plant]));
+    });
+}
+
+#[test]
+fn renaming_a_parent_moves_its_children() {
+    run(async {
+        let scope = scope();
+        let image = ImageFixture::new(&scope)
+            .tags(&[&format!("{scope}-animals/cats")])
+            .insert()
+            .await;
+        let parent = find_tag(&format!("{scope}-animals")).await.unwrap();
+
+        let rename = |name: String| TagUpdateDTO {
+            name,
+            color: parent.color.clone(),
+            ..TagUpdateDTO::default()
+        };
+        tag_service::update_from_dto(parent.id, rename(format!("{scope}-pets")))
+            .await
+            .unwrap();
+
+        let child = find_tag(&format!("{scope}-pets/cats")).await.unwrap();
+        assert_eq!(child.parent_id, Some(parent.id));
+        assert!(find_tag(&format!("{scope}-animals/cats")).await.is_none());
+        let pets = format!("{scope}-pets");
+        assert_eq!(
+            tagged("", &[&pets], &[], TagMatchMode::Any).await,
+            HashSet::from([image])
+        );
+
+        let under_itself = format!("{scope}-pets/cats/more");
+        assert!(
+            tag_service::update_from_dto(parent.id, rename(under_itself))
+                .await
+                .is_err()
+        );
+    });
+}
+
+#[test]
+fn random_picks_follow_subtags_and_aliases() {
+    run(async {
+        let scope = scope();
+        let (animals, cats, pets) = (
+            format!("{scope}-animals"),
+            format!("{scope}-animals/cats"),
+            format!("{scope}-pets"),
+        );
+        let cat = ImageFixture::new(&scope).tags(&[&cats]).insert().await;
+        let animals_id = find_tag(&animals).await.unwrap().id;
+        tag_service::add_alias(animals_id, &pets).await.unwrap();
+
+        for name in [&animals, &pets] {
+            let picked = image_service::random_with_tag(name).await.unwrap();
+            assert_eq!(picked.map(|model| model.id), Some(cat));
+        }
+    });
+}
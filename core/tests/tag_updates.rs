@@ -0,0 +1,315 @@
+mod common;
+
+use common::{ImageFixture, find_tag, new_tag, new_tags, run, scope, tag_names};
+use organizer_core::dtos::image_dto::ImageUpdateDTO;
+use organizer_core::dtos::tag_dto::TagUpdateDTO;
+use organizer_core::models::filter::Filter;
+use organizer_core::models::tag_color::TagColor;
+use organizer_core::services::connection_db::db_ref;
+use organizer_core::services::{image_service, tag_service, trash_service};
+use std::collections::HashSet;
+
+#[test]
+fn image_tags_are_replaced_as_a_whole() {
+    run(async {
+        let scope = scope();
+        let (old, kept, new) = (
+            format!("{scope}-old"),
+            format!("{scope}-kept"),
+            format!("{scope}-new"),
+        );
+        let id = ImageFixture::new(&scope)
+            .tags(&[&old, &kept])
+            .insert()
+            .await;
+
+        tag_service::update_tags_for_image(db_ref(), id, new_tags(&[&kept, &new]))
+            .await
+            .expect("Failed to update the tags");
+
+        let mut expected = vec![kept.clone(), new.clone()];
+        expected.sort();
+        assert_eq!(tag_names(id).await, expected);
+        assert!(
+            find_tag(&old).await.is_some(),
+            "a tag left without images is kept"
+        );
+    });
+}
+
+#[test]
+fn existing_tags_are_reused_by_name() {
+    run(async {
+        let scope = scope();
+        let shared = format!("{scope}-shared");
+        let first = ImageFixture::new(&scope).tags(&[&shared]).insert().await;
+        let tag = find_tag(&shared).await.expect("tag created");
+
+        let second = ImageFixture::new(&scope).tags(&[&shared]).insert().await;
+
+        let tags = tag_service::get_tags_for_images(&[first, second], db_ref())
+            .await
+            .expect("Failed to load the tags");
+        assert!(tags[&first].contains(&tag));
+        assert!(tags[&second].contains(&tag));
+        let count = tag_service::find_all()
+            .await
+            .expect("Failed to load the tags")
+            .iter()
+            .filter(|tag| tag.name == shared)
+            .count();
+        assert_eq!(count, 1);
+    });
+}
+
+#[test]
+fn an_empty_set_removes_every_tag() {
+    run(async {
+        let scope = scope();
+        let tag = format!("{scope}-tag");
+        let id = ImageFixture::new(&scope).tags(&[&tag]).insert().await;
+
+        tag_service::update_tags_for_image(db_ref(), id, HashSet::new())
+            .await
+            .expect("Failed to update the tags");
+
+        assert!(tag_names(id).await.is_empty());
+    });
+}
+
+#[test]
+fn image_update_leaves_tags_alone_unless_given_some() {
+    run(async {
+        let scope = scope();
+        let (first, second) = (format!("{scope}-first"), format!("{scope}-second"));
+        let id = ImageFixture::new(&scope).tags(&[&first]).insert().await;
+        let update = |tags| ImageUpdateDTO {
+            tags,
+            is_prepared: true,
+            ..Default::default()
+        };
+
+        image_service::update_from_dto(id, update(None))
+            .await
+            .expect("Failed to update the image");
+        assert_eq!(tag_names(id).await, vec![first.clone()]);
+
+        image_service::update_from_dto(id, update(Some(HashSet::new())))
+            .await
+            .expect("Failed to update the image");
+        assert_eq!(
+            tag_names(id).await,
+            vec![first.clone()],
+            "an empty set isn't a request to clear"
+        );
+
+        image_service::update_from_dto(id, update(Some(new_tags(&[&second]))))
+            .await
+            .expect("Failed to update the image");
+        assert_eq!(tag_names(id).await, vec![second]);
+    });
+}
+
+#[test]
+fn saved_and_renamed_tags_are_lowercase() {
+    run(async {
+        let scope = scope();
+        tag_service::save(&format!("{scope}-Mixed"), TagColor::default())
+            .await
+            .expect("Failed to save the tag");
+        let tag = find_tag(&format!("{scope}-mixed"))
+            .await
+            .expect("tag saved in lowercase");
+
+        let renamed = tag_service::update_from_dto(
+            tag.id,
+            TagUpdateDTO {
+                name: format!("{scope}-Renamed"),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to rename the tag");
+        assert_eq!(renamed.name, format!("{scope}-renamed"));
+        assert!(
+            find_tag(&format!("{scope}-mixed")).await.is_none(),
+            "the cached list follows the rename"
+        );
+    });
+}
+
+#[test]
+fn an_empty_name_keeps_the_tag_name() {
+    run(async {
+        let scope = scope();
+        let name = format!("{scope}-name");
+        tag_service::save(&name, TagColor::default())
+            .await
+            .expect("Failed to save the tag");
+        let tag = find_tag(&name).await.expect("tag saved");
+
+        let updated = tag_service::update_from_dto(tag.id, TagUpdateDTO::default())
+            .await
+            .expect("Failed to update the tag");
+        assert_eq!(updated.name, name);
+    });
+}
+
+#[test]
+fn batch_tagging_only_counts_the_images_it_changed() {
+    run(async {
+        let scope = scope();
+        let (batch, other) = (format!("{scope}-batch"), format!("{scope}-other"));
+        let tagged = ImageFixture::new(&scope).tags(&[&batch]).insert().await;
+        let untagged = ImageFixture::new(&scope).tags(&[&other]).insert().await;
+        let ids = [tagged, untagged];
+
+        let added = image_service::add_tag_to_images(&ids, new_tag(&batch))
+            .await
+            .expect("Failed to tag the images");
+        assert_eq!(added, 1, "the image that already had it is left alone");
+        assert_eq!(tag_names(untagged).await, vec![batch.clone(), other.clone()]);
+
+        let removed = image_service::remove_tag_from_images(&ids, &batch)
+            .await
+            .expect("Failed to untag the images");
+        assert_eq!(removed, 2);
+        assert!(tag_names(tagged).await.is_empty());
+        assert_eq!(tag_names(untagged).await, vec![other]);
+    });
+}
+
+#[test]
+fn aliases_resolve_to_their_tag() {
+    run(async {
+        let scope = scope();
+        let (tag, alias) = (
+            format!("{scope}-screenshot"),
+            format!("{scope}-screen shot"),
+        );
+        tag_service::save(&tag, TagColor::default()).await.unwrap();
+        let id = find_tag(&tag).await.unwrap().id;
+        tag_service::add_alias(id, &alias.to_uppercase())
+            .await
+            .expect("Failed to add the alias");
+        assert!(
+            tag_service::add_alias(id, &tag).await.is_err(),
+            "a tag name is no alias"
+        );
+
+        tag_service::save(&alias, TagColor::default())
+            .await
+            .unwrap();
+        assert!(
+            find_tag(&alias).await.is_none(),
+            "saving an alias creates no tag"
+        );
+
+        let image = ImageFixture::new(&scope).tags(&[&alias]).insert().await;
+        assert_eq!(tag_names(image).await, vec![tag.clone()]);
+
+        let filter = Filter {
+            tags: HashSet::from([alias.clone()]),
+            ..Filter::new()
+        };
+        let found = image_service::find_all_matching(filter).await.unwrap();
+        assert_eq!(
+            found.iter().map(|image| image.id).collect::<Vec<_>>(),
+            vec![image]
+        );
+
+        tag_service::delete(id).await.unwrap();
+        assert!(!tag_service::find_aliases().await.unwrap().contains_key(&id));
+        assert_eq!(tag_service::resolve_alias(&alias).await.unwrap(), alias);
+    });
+}
+
+#[test]
+fn a_tag_saved_with_its_alias_is_added_once() {
+    run(async {
+        let scope = scope();
+        let (tag, alias, other) = (
+            format!("{scope}-screenshot"),
+            format!("{scope}-screen shot"),
+            format!("{scope}-other"),
+        );
+        tag_service::save(&tag, TagColor::default()).await.unwrap();
+        let tag_id = find_tag(&tag).await.unwrap().id;
+        tag_service::add_alias(tag_id, &alias).await.unwrap();
+        let id = ImageFixture::new(&scope).tags(&[&other]).insert().await;
+
+        tag_service::update_tags_for_image(db_ref(), id, new_tags(&[&tag, &alias]))
+            .await
+            .expect("Failed to save a tag with its alias");
+        assert_eq!(tag_names(id).await, vec![tag.clone()]);
+
+        let added = image_service::add_tag_to_images(&[id], new_tag(&alias))
+            .await
+            .expect("Failed to tag the image");
+        assert_eq!(added, 0, "the alias names a tag the image has");
+
+        let removed = image_service::remove_tag_from_images(&[id], &alias)
+            .await
+            .expect("Failed to untag the image");
+        assert_eq!(removed, 1);
+        assert!(tag_names(id).await.is_empty());
+    });
+}
+
+#[test]
+fn counts_leave_out_trashed_images() {
+    run(async {
+        let scope = scope();
+        let (used, unused) = (format!("{scope}-used"), format!("{scope}-unused"));
+        tag_service::save(&unused, TagColor::default())
+            .await
+            .unwrap();
+        ImageFixture::new(&scope).tags(&[&used]).insert().await;
+        let trashed = ImageFixture::new(&scope).tags(&[&used]).insert().await;
+        trash_service::trash(trashed).await.unwrap();
+
+        let count = |name: String| async move {
+            tag_service::find_all_with_counts()
+                .await
+                .expect("Failed to count the tags")
+                .into_iter()
+                .find(|(tag, _)| tag.name == name)
+                .map(|(_, count)| count)
+        };
+        assert_eq!(count(used).await, Some(1));
+        assert_eq!(count(unused).await, Some(0));
+    });
+}
+
+#[test]
+fn categories_are_saved_and_filtered_on() {
+    run(async {
+        let scope = scope();
+        let (character, other) = (format!("{scope}-alice"), format!("{scope}-sunset"));
+        let category = format!("{scope}-character");
+        let tagged = ImageFixture::new(&scope).tags(&[&character]).insert().await;
+        ImageFixture::new(&scope).tags(&[&other]).insert().await;
+
+        let id = find_tag(&character).await.unwrap().id;
+        let dto = TagUpdateDTO {
+            name: character.clone(),
+            category: format!(" {} ", category.to_uppercase()),
+            ..TagUpdateDTO::default()
+        };
+        tag_service::update_from_dto(id, dto).await.unwrap();
+        assert_eq!(
+            find_tag(&character).await.unwrap().category,
+            Some(category.clone())
+        );
+
+        let filter = Filter {
+            category: Some(category),
+            ..Filter::new()
+        };
+        let found = image_service::find_all_matching(filter).await.unwrap();
+        assert_eq!(
+            found.iter().map(|image| image.id).collect::<Vec<_>>(),
+            vec![tagged]
+        );
+    });
+}
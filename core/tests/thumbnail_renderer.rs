@@ -0,0 +1,79 @@
+mod common;
+
+use common::TempFolder;
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use organizer_core::services::file_service::is_image_file;
+use organizer_core::services::thumbnail_renderer::{
+    ThumbnailRenderer, is_displayable, register, render, renderer_for, supported_extensions,
+};
+use std::path::Path;
+use std::sync::Arc;
+
+const DRAWING: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20">
+    <rect width="20" height="20" fill="#ff0000"/>
+    <rect x="20" width="20" height="20" fill="#0000ff" fill-opacity="0.5"/>
+</svg>"##;
+
+#[test]
+fn drawings_are_rendered_to_the_requested_size() {
+    let folder = TempFolder::new("svg-render");
+    let path = folder.write("drawing.svg", DRAWING.as_bytes());
+
+    let image = render(&path, 200).expect("Failed to render the drawing");
+    assert_eq!(image.dimensions(), (200, 100));
+    assert_eq!(image.get_pixel(50, 50), Rgba([255, 0, 0, 255]));
+
+    // Transparency comes out straight, not premultiplied
+    let Rgba([red, _, blue, alpha]) = image.get_pixel(150, 50);
+    assert_eq!(red, 0);
+    assert!(blue >= 254, "blue was {blue}");
+    assert!((127..=128).contains(&alpha), "alpha was {alpha}");
+}
+
+#[test]
+fn only_raster_originals_are_shown_as_they_are() {
+    assert!(is_displayable(Path::new("photo.JPG")));
+    assert!(!is_displayable(Path::new("drawing.svg")));
+    assert!(!is_displayable(Path::new("clip.mp4")));
+    assert!(!is_displayable(Path::new("notes.txt")));
+}
+
+/// Plain text files drawn as a single color, a media type added from outside
+struct TextRenderer;
+
+impl ThumbnailRenderer for TextRenderer {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["txt"]
+    }
+
+    fn render(&self, _path: &Path, bytes: &[u8], size: u32) -> Result<DynamicImage, String> {
+        let shade = bytes.first().copied().unwrap_or_default();
+        Ok(DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+            size,
+            size,
+            Rgba([shade, shade, shade, 255]),
+        )))
+    }
+}
+
+#[test]
+fn registered_renderers_add_media_types() {
+    assert!(!is_image_file(Path::new("notes.txt")));
+    register(Arc::new(TextRenderer));
+
+    assert!(is_image_file(Path::new("notes.TXT")));
+    assert!(supported_extensions().contains(&"txt"));
+    assert_eq!(
+        renderer_for(Path::new("notes.txt")).map(|r| r.name()),
+        Some("text")
+    );
+
+    let folder = TempFolder::new("custom-render");
+    let path = folder.write("notes.txt", b"Z");
+    let image = render(&path, 8).expect("Failed to render the text file");
+    assert_eq!(image.get_pixel(0, 0), Rgba([b'Z', b'Z', b'Z', 255]));
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::{ImageFixture, run, scope};
+use organizer_core::models::delete_behavior::DeleteBehavior;
+use organizer_core::services::file_naming::thumbnail_path_for;
+use organizer_core::services::file_service::DirContents;
+use organizer_core::services::{file_service, image_service, trash_service};
+use organizer_core::utils::get_data_dir;
+use std::fs;
+
+/// Pinned entries of the test, pinned so they are easy to list
+async fn listed(scope: &str) -> Vec<i64> {
+    image_service::find_pinned()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|image| image.description.starts_with(scope))
+        .map(|image| image.id)
+        .collect()
+}
+
+async fn trashed(scope: &str) -> Vec<i64> {
+    trash_service::find_all()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|trashed| trashed.image.description.starts_with(scope))
+        .map(|trashed| trashed.image.id)
+        .collect()
+}
+
+#[test]
+fn trashed_entries_leave_the_library_until_restored() {
+    run(async {
+        let scope = scope();
+        let id = ImageFixture::new(format!("{scope} kept")).insert().await;
+        image_service::set_pinned(id, true).await.unwrap();
+
+        trash_service::trash(id).await.unwrap();
+        assert!(listed(&scope).await.is_empty());
+        assert_eq!(trashed(&scope).await, vec![id]);
+        assert!(
+            image_service::find_by_id(id).await.unwrap().is_none(),
+            "links and the preview don't open trashed entries"
+        );
+        assert!(trash_service::find_trashed(id).await.unwrap().is_some());
+
+        trash_service::restore(id).await.unwrap();
+        assert_eq!(listed(&scope).await, vec![id]);
+        assert!(image_service::find_by_id(id).await.unwrap().is_some());
+        assert!(trashed(&scope).await.is_empty());
+    });
+}
+
+#[test]
+fn purging_leaves_entries_outside_the_trash_alone() {
+    run(async {
+        let scope = scope();
+        let id = ImageFixture::new(format!("{scope} live")).insert().await;
+
+        trash_service::purge(id, DeleteBehavior::Permanent)
+            .await
+            .unwrap();
+        assert!(image_service::find_by_id(id).await.unwrap().is_some());
+    });
+}
+
+#[test]
+fn purging_removes_the_entry_and_its_files() {
+    run(async {
+        let scope = scope();
+        let id = ImageFixture::new(format!("{scope} purged")).insert().await;
+        let image_dir = get_data_dir().join("images").join(id.to_string());
+        let _ = fs::remove_dir_all(file_service::trash_dir(id));
+        fs::create_dir_all(&image_dir).unwrap();
+        let image = image_dir.join("image.png");
+        fs::write(&image, b"png").unwrap();
+
+        trash_service::trash(id).await.unwrap();
+        assert!(!image_dir.exists());
+        let in_trash = file_service::trashed_path(id, &image.to_string_lossy());
+        assert_eq!(fs::read(&in_trash).unwrap(), b"png");
+
+        trash_service::purge(id, DeleteBehavior::Permanent)
+            .await
+            .unwrap();
+        assert!(!file_service::trash_dir(id).exists());
+        assert!(image_service::find_by_id(id).await.unwrap().is_none());
+        assert!(trashed(&scope).await.is_empty());
+    });
+}
+
+#[test]
+fn contents_count_every_stored_file() {
+    run(async {
+        let scope = scope();
+        let id = ImageFixture::new(format!("{scope} album")).insert().await;
+        let image_dir = get_data_dir().join("images").join(id.to_string());
+        fs::create_dir_all(image_dir.join("nested")).unwrap();
+        fs::write(image_dir.join("a.png"), b"12345").unwrap();
+        fs::write(image_dir.join("nested").join("b.png"), b"123").unwrap();
+        fs::write(thumbnail_path_for(&image_dir.join("a.png")).unwrap(), b"12").unwrap();
+
+        let contents = trash_service::contents(id).await.unwrap();
+        assert_eq!(contents, DirContents { files: 3, bytes: 10 });
+
+        fs::remove_dir_all(&image_dir).unwrap();
+        assert!(trash_service::contents(id).await.is_err());
+    });
+}
+
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+#[test]
+fn originals_can_go_to_the_recycle_bin() {
+    run(async {
+        let scope = scope();
+        let id = ImageFixture::new(format!("{scope} recycled")).insert().await;
+        let image_dir = get_data_dir().join("images").join(id.to_string());
+        let _ = fs::remove_dir_all(file_service::trash_dir(id));
+        fs::create_dir_all(&image_dir).unwrap();
+        let image = image_dir.join(format!("{scope}.png"));
+        fs::write(&image, b"png").unwrap();
+        fs::write(thumbnail_path_for(&image).unwrap(), b"thumb").unwrap();
+
+        trash_service::trash(id).await.unwrap();
+        trash_service::purge(id, DeleteBehavior::RecycleBin)
+            .await
+            .unwrap();
+        assert!(!file_service::trash_dir(id).exists());
+
+        // Only the original went to the recycle bin, take it out again
+        let recycled: Vec<_> = trash::os_limited::list()
+            .unwrap()
+            .into_iter()
+            .filter(|item| item.name.to_string_lossy().contains(scope.as_str()))
+            .collect();
+        assert_eq!(recycled.len(), 1);
+        assert_eq!(recycled[0].name, format!("{scope}.png").as_str());
+        trash::os_limited::purge_all(recycled).unwrap();
+    });
+}
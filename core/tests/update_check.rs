@@ -0,0 +1,90 @@
+use organizer_core::services::update_service::{
+    is_newer, matches_sha256, parse_release, require_https,
+};
+
+#[test]
+fn versions_compare_number_by_number() {
+    assert!(is_newer("0.10.0", "0.9.3"));
+    assert!(is_newer("v1.2", "1.1.9"));
+    assert!(is_newer("1.0.0", "1.0.0-beta.2"));
+    assert!(!is_newer("1.0", "1.0.0"));
+    assert!(!is_newer("1.0.0-beta.2", "1.0.0"));
+    assert!(!is_newer("0.9.10", "0.10.0"));
+}
+
+#[test]
+fn pre_releases_compare_part_by_part() {
+    assert!(is_newer("1.0.0-beta.2", "1.0.0-beta.1"));
+    assert!(is_newer("1.0.0-beta.10", "1.0.0-beta.9"));
+    assert!(is_newer("1.0.0-beta", "1.0.0-alpha.3"));
+    assert!(is_newer("1.0.0-alpha.1", "1.0.0-alpha"));
+    assert!(is_newer("1.0.0-rc", "1.0.0-1"));
+    assert!(!is_newer("1.0.0-beta.1", "1.0.0-beta.1"));
+}
+
+#[test]
+fn releases_carry_the_digest_of_their_download() {
+    let content = br#"{
+        "tag_name": "v2.1.0",
+        "assets": [
+            {"name": "organizer-2.1.0.msi", "browser_download_url": "https://example.com/a.msi", "digest": "sha256:ABC123"},
+            {"name": "organizer-2.1.0.dmg", "browser_download_url": "https://example.com/a.dmg", "digest": "sha256:ABC123"},
+            {"name": "organizer-2.1.0.AppImage", "browser_download_url": "https://example.com/a.AppImage", "digest": "sha256:ABC123"}
+        ]
+    }"#;
+
+    let release = parse_release(content).expect("Failed to parse the release");
+
+    assert_eq!(release.sha256.as_deref(), Some("abc123"));
+}
+
+#[test]
+fn downloads_match_their_sha256() {
+    let expected = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    assert!(matches_sha256(b"hello", expected));
+    assert!(matches_sha256(b"hello", &expected.to_uppercase()));
+    assert!(!matches_sha256(b"hello!", expected));
+}
+
+#[test]
+fn updates_are_only_fetched_over_https() {
+    assert!(require_https("https://example.com/latest").is_ok());
+    assert!(require_https("http://example.com/latest").is_err());
+    assert!(require_https("not a url").is_err());
+}
+
+#[test]
+fn releases_pick_the_download_for_this_platform() {
+    let content = br#"{
+        "tag_name": "v2.1.0",
+        "body": "Notes",
+        "html_url": "https://example.com/releases/v2.1.0",
+        "assets": [
+            {"name": "organizer-2.1.0.msi", "browser_download_url": "https://example.com/a.msi"},
+            {"name": "organizer-2.1.0.dmg", "browser_download_url": "https://example.com/a.dmg"},
+            {"name": "organizer-2.1.0.AppImage", "browser_download_url": "https://example.com/a.AppImage"}
+        ]
+    }"#;
+
+    let release = parse_release(content).expect("Failed to parse the release");
+
+    assert_eq!(release.version, "2.1.0");
+    assert_eq!(release.notes, "Notes");
+    let expected = if cfg!(target_os = "windows") {
+        "organizer-2.1.0.msi"
+    } else if cfg!(target_os = "macos") {
+        "organizer-2.1.0.dmg"
+    } else {
+        "organizer-2.1.0.AppImage"
+    };
+    assert_eq!(release.file_name.as_deref(), Some(expected));
+}
+
+#[test]
+fn releases_without_assets_have_nothing_to_download() {
+    let release = parse_release(br#"{"version": "3.0.0"}"#).expect("Failed to parse the release");
+
+    assert_eq!(release.version, "3.0.0");
+    assert!(release.download_url.is_none());
+}
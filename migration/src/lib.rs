@@ -4,6 +4,23 @@ mod m2025701_000003_create_image_tags_table;
 mod m20257013_000003_alter_tags_table;
 mod m20257018_000004_alter_image_table;
 mod m20251014_000005_alter_image_table;
+mod m20261016_000006_add_content_hash;
+mod m20261016_000007_create_activity_table;
+mod m20261016_000008_create_image_versions_table;
+mod m20261016_000009_add_source_url;
+mod m20261016_000010_add_is_pinned;
+mod m20261016_000011_create_tag_settings_table;
+mod m20261016_000012_create_stacks_tables;
+mod m20261016_000013_add_perceptual_hash;
+mod m20261016_000014_add_language;
+mod m20261016_000015_create_quarantine_table;
+mod m20261016_000016_add_review_status;
+mod m20261016_000017_add_deleted_at;
+mod m20261016_000018_create_images_fts;
+mod m20261016_000019_add_tag_parent;
+mod m20261016_000020_create_tag_aliases;
+mod m20261016_000021_add_tag_category;
+mod m20261016_000022_reset_perceptual_hash;
 
 use sea_orm_migration::prelude::*;
 
@@ -19,6 +36,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20257013_000003_alter_tags_table::Migration),
             Box::new(m20257018_000004_alter_image_table::Migration),
             Box::new(m20251014_000005_alter_image_table::Migration),
+            Box::new(m20261016_000006_add_content_hash::Migration),
+            Box::new(m20261016_000007_create_activity_table::Migration),
+            Box::new(m20261016_000008_create_image_versions_table::Migration),
+            Box::new(m20261016_000009_add_source_url::Migration),
+            Box::new(m20261016_000010_add_is_pinned::Migration),
+            Box::new(m20261016_000011_create_tag_settings_table::Migration),
+            Box::new(m20261016_000012_create_stacks_tables::Migration),
+            Box::new(m20261016_000013_add_perceptual_hash::Migration),
+            Box::new(m20261016_000014_add_language::Migration),
+            Box::new(m20261016_000015_create_quarantine_table::Migration),
+            Box::new(m20261016_000016_add_review_status::Migration),
+            Box::new(m20261016_000017_add_deleted_at::Migration),
+            Box::new(m20261016_000018_create_images_fts::Migration),
+            Box::new(m20261016_000019_add_tag_parent::Migration),
+            Box::new(m20261016_000020_create_tag_aliases::Migration),
+            Box::new(m20261016_000021_add_tag_category::Migration),
+            Box::new(m20261016_000022_reset_perceptual_hash::Migration),
         ]
     }
 }
@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Activities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Activities::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Activities::Action).text().not_null())
+                    // No foreign key, entries outlive the images they describe
+                    .col(ColumnDef::new(Activities::ImageId).integer().null())
+                    .col(ColumnDef::new(Activities::Details).text().not_null())
+                    .col(
+                        ColumnDef::new(Activities::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_activities_created_at")
+                    .table(Activities::Table)
+                    .col(Activities::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Activities::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Activities {
+    Table,
+    Id,
+    Action,
+    ImageId,
+    Details,
+    CreatedAt,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImageVersions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImageVersions::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImageVersions::ImageId).integer().not_null())
+                    // File the version belongs to, folders keep versions per image
+                    .col(
+                        ColumnDef::new(ImageVersions::OriginalPath)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImageVersions::Path).text().not_null())
+                    .col(
+                        ColumnDef::new(ImageVersions::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_image_versions_image")
+                            .from(ImageVersions::Table, ImageVersions::ImageId)
+                            .to(Images::Table, Images::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImageVersions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ImageVersions {
+    Table,
+    Id,
+    ImageId,
+    OriginalPath,
+    Path,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Images {
+    Table,
+    Id,
+}
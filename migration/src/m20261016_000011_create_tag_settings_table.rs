@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TagSettings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TagSettings::TagId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // Null keeps the regular search default
+                    .col(ColumnDef::new(TagSettings::SortOrder).text().null())
+                    .col(ColumnDef::new(TagSettings::ViewDensity).text().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_tag_settings_tag")
+                            .from(TagSettings::Table, TagSettings::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TagSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TagSettings {
+    Table,
+    TagId,
+    SortOrder,
+    ViewDensity,
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Id,
+}
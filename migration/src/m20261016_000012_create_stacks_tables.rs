@@ -0,0 +1,112 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Stacks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Stacks::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Stacks::CoverImageId).integer().not_null())
+                    .col(
+                        ColumnDef::new(Stacks::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stacks_cover_image")
+                            .from(Stacks::Table, Stacks::CoverImageId)
+                            .to(Images::Table, Images::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(StackImages::Table)
+                    .if_not_exists()
+                    // An image belongs to one stack at most
+                    .col(
+                        ColumnDef::new(StackImages::ImageId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StackImages::StackId).integer().not_null())
+                    .col(ColumnDef::new(StackImages::Position).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stack_images_image")
+                            .from(StackImages::Table, StackImages::ImageId)
+                            .to(Images::Table, Images::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_stack_images_stack")
+                            .from(StackImages::Table, StackImages::StackId)
+                            .to(Stacks::Table, Stacks::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_stack_images_stack_id")
+                    .table(StackImages::Table)
+                    .col(StackImages::StackId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(StackImages::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Stacks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Stacks {
+    Table,
+    Id,
+    CoverImageId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum StackImages {
+    Table,
+    ImageId,
+    StackId,
+    Position,
+}
+
+#[derive(DeriveIden)]
+enum Images {
+    Table,
+    Id,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Files that failed to decode during an import, kept for review
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuarantinedFiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QuarantinedFiles::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(QuarantinedFiles::Path).text().not_null())
+                    // Folder entry the file was imported into. No foreign key, the
+                    // file stays listed when the entry is deleted
+                    .col(ColumnDef::new(QuarantinedFiles::ImageId).integer().null())
+                    .col(ColumnDef::new(QuarantinedFiles::Reason).text().not_null())
+                    .col(
+                        ColumnDef::new(QuarantinedFiles::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QuarantinedFiles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum QuarantinedFiles {
+    Table,
+    Id,
+    Path,
+    ImageId,
+    Reason,
+    CreatedAt,
+}
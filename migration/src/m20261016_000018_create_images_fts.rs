@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Full-text index of the descriptions, an external content table reading them
+/// from `images` and kept in step by the triggers. Words are matched without case
+/// and accents, the prefix indexes keep short prefix queries fast.
+const UP: &str = "
+CREATE VIRTUAL TABLE images_fts USING fts5(
+    description,
+    content = 'images',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+CREATE TRIGGER images_fts_insert AFTER INSERT ON images BEGIN
+    INSERT INTO images_fts (rowid, description) VALUES (new.id, new.description);
+END;
+
+CREATE TRIGGER images_fts_delete AFTER DELETE ON images BEGIN
+    INSERT INTO images_fts (images_fts, rowid, description)
+    VALUES ('delete', old.id, old.description);
+END;
+
+CREATE TRIGGER images_fts_update AFTER UPDATE OF description ON images BEGIN
+    INSERT INTO images_fts (images_fts, rowid, description)
+    VALUES ('delete', old.id, old.description);
+    INSERT INTO images_fts (rowid, description) VALUES (new.id, new.description);
+END;
+
+INSERT INTO images_fts (images_fts) VALUES ('rebuild');
+";
+
+const DOWN: &str = "
+DROP TRIGGER IF EXISTS images_fts_update;
+DROP TRIGGER IF EXISTS images_fts_delete;
+DROP TRIGGER IF EXISTS images_fts_insert;
+DROP TABLE IF EXISTS images_fts;
+";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(UP).await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.get_connection().execute_unprepared(DOWN).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tags::Table)
+                    // The tag named like this one without its last `/` segment
+                    .add_column(ColumnDef::new(Tags::ParentId).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tags_parent_id")
+                    .table(Tags::Table)
+                    .col(Tags::ParentId)
+                    .to_owned(),
+            )
+            .await?;
+
+        link_existing_paths(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tags_parent_id")
+                    .table(Tags::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tags::Table)
+                    .drop_column(Tags::ParentId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Tags already named like `animals/cats` get their parents, created with the
+/// color of the child when missing
+async fn link_existing_paths(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let backend = manager.get_database_backend();
+
+    let rows = db
+        .query_all(Statement::from_string(
+            backend,
+            "SELECT name FROM tags WHERE name LIKE '%/%' ORDER BY name",
+        ))
+        .await?;
+    for row in rows {
+        let name: String = row.try_get("", "name")?;
+        let mut child = name.as_str();
+        while let Some((parent, _)) = child.rsplit_once('/') {
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "INSERT OR IGNORE INTO tags (name, color) SELECT ?, color FROM tags WHERE name = ?",
+                [parent.into(), name.as_str().into()],
+            ))
+            .await?;
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "UPDATE tags SET parent_id = (SELECT id FROM tags WHERE name = ?) WHERE name = ?",
+                [parent.into(), child.into()],
+            ))
+            .await?;
+            child = parent;
+        }
+    }
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    ParentId,
+}
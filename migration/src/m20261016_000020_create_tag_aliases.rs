@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TagAliases::Table)
+                    .if_not_exists()
+                    // Stored like tag names, lowercase
+                    .col(
+                        ColumnDef::new(TagAliases::Alias)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TagAliases::TagId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_tag_aliases_tag")
+                            .from(TagAliases::Table, TagAliases::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tag_aliases_tag_id")
+                    .table(TagAliases::Table)
+                    .col(TagAliases::TagId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TagAliases::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TagAliases {
+    Table,
+    Alias,
+    TagId,
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Id,
+}
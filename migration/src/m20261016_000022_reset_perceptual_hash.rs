@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Hashes taken from the full image don't compare with those of thumbnails,
+        // they are filled in again from the thumbnails when next looked up
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Images::Table)
+                    .value(Images::PerceptualHash, Option::<i64>::None)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Images {
+    Table,
+    PerceptualHash,
+}
@@ -0,0 +1,74 @@
+use crate::config::get_settings;
+use std::time::{Duration, Instant};
+
+/// Card lifting under the cursor
+pub const CARD_HOVER: Duration = Duration::from_millis(140);
+/// Preview growing into place when opened
+pub const PREVIEW_OPEN: Duration = Duration::from_millis(200);
+
+/// Length of an animation, none at all when the user asked for reduced motion
+pub fn duration(base: Duration) -> Duration {
+    if get_settings().config.reduce_motion.unwrap_or(false) {
+        Duration::ZERO
+    } else {
+        base
+    }
+}
+
+/// A value eased from one point to another over time. Views read it at the time of
+/// the last frame, which the screen keeps while something is running.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    from: f32,
+    to: f32,
+    started: Instant,
+    duration: Duration,
+}
+
+impl Animation {
+    pub fn new(value: f32) -> Self {
+        Animation {
+            from: value,
+            to: value,
+            started: Instant::now(),
+            duration: Duration::ZERO,
+        }
+    }
+
+    /// Starts moving to `to` from wherever the value is at `now`
+    pub fn go_to(&mut self, to: f32, base: Duration, now: Instant) {
+        self.from = self.value(now);
+        self.to = to;
+        self.started = now;
+        self.duration = duration(base);
+    }
+
+    /// Jumps to `from` and animates to `to`
+    pub fn play(&mut self, from: f32, to: f32, base: Duration, now: Instant) {
+        self.from = from;
+        self.go_to(to, base, now);
+    }
+
+    pub fn value(&self, now: Instant) -> f32 {
+        let progress = self.progress(now);
+        // Ease out: quick start, gentle landing
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        self.from + (self.to - self.from) * eased
+    }
+
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn is_running(&self, now: Instant) -> bool {
+        self.progress(now) < 1.0
+    }
+
+    fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.started);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+}
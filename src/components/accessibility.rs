@@ -0,0 +1,74 @@
+use iced::widget::{container, text, tooltip};
+use iced::{Border, Color, Element, Theme};
+use iced_modern_theme::Modern;
+
+/// Suffix of the high contrast theme names, like Modern's own "Dark" check
+const HIGH_CONTRAST: &str = "High Contrast";
+
+/// Modern palette pushed to pure black and white with brighter accents.
+/// The name keeps "Dark" so Modern's widget styles still pick their dark colors.
+pub fn high_contrast_theme(dark: bool) -> Theme {
+    let palette = if dark {
+        iced::theme::Palette {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            primary: Color::from_rgb(0.35, 0.7, 1.0),
+            success: Color::from_rgb(0.3, 0.95, 0.45),
+            danger: Color::from_rgb(1.0, 0.4, 0.4),
+        }
+    } else {
+        iced::theme::Palette {
+            background: Color::WHITE,
+            text: Color::BLACK,
+            primary: Color::from_rgb(0.0, 0.25, 0.75),
+            success: Color::from_rgb(0.0, 0.45, 0.1),
+            danger: Color::from_rgb(0.7, 0.0, 0.0),
+        }
+    };
+    let name = if dark { "Modern Dark" } else { "Modern Light" };
+    Theme::custom(format!("{} {}", name, HIGH_CONTRAST), palette)
+}
+
+pub fn is_high_contrast(theme: &Theme) -> bool {
+    matches!(theme, Theme::Custom(custom) if custom.to_string().ends_with(HIGH_CONTRAST))
+}
+
+/// Outline drawn around whatever has the keyboard focus. The padding is kept without
+/// focus too, so moving the focus doesn't shift the layout.
+pub fn focus_ring<'a, M: 'a>(
+    content: impl Into<Element<'a, M>>,
+    focused: bool,
+    radius: f32,
+) -> Element<'a, M> {
+    container(content)
+        .padding(3)
+        .style(move |theme: &Theme| container::Style {
+            border: Border {
+                color: if !focused {
+                    Color::TRANSPARENT
+                } else if is_high_contrast(theme) {
+                    theme.palette().text
+                } else {
+                    theme.palette().primary
+                },
+                width: if focused { 2.0 } else { 0.0 },
+                radius: (radius + 3.0).into(),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// Names an icon-only control. iced has no accessibility tree to put the name in yet,
+/// so it is shown as a tooltip; every icon-only button goes through here to get one.
+pub fn labeled<'a, M: 'a>(
+    control: impl Into<Element<'a, M>>,
+    label: impl text::IntoFragment<'a>,
+    position: tooltip::Position,
+) -> Element<'a, M> {
+    tooltip(control, text(label), position)
+        .style(Modern::card_container())
+        .padding(8)
+        .gap(4)
+        .into()
+}
@@ -1,3 +1,4 @@
+use iced::widget::text::IntoFragment;
 use iced::widget::{Column, Container, Text};
 use iced::{Alignment, Length};
 use iced_font_awesome::fa_icon;
@@ -5,8 +6,8 @@ use iced_modern_theme::Modern;
 
 pub fn empty_state<'a, M: 'a>(
     icon: &'a str,
-    title: &'a str,
-    subtitle: &'a str,
+    title: impl IntoFragment<'a>,
+    subtitle: impl IntoFragment<'a>,
 ) -> iced::Element<'a, M> {
     let column = Column::new()
         .spacing(20)
@@ -1,5 +1,6 @@
+use crate::components::accessibility::labeled;
 use iced::{Alignment, Length};
-use iced::widget::{button, Container, Row, Space};
+use iced::widget::{button, tooltip, Container, Row, Space};
 use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
 
@@ -13,7 +14,7 @@ where
             .width(Length::Fill)
             .align_y(Alignment::Center)
             .push(Space::with_width(Length::Fill))
-            .push(
+            .push(labeled(
                 button(
                     Container::new(fa_icon_solid("xmark").size(20.0))
                         .width(Length::Fill)
@@ -25,7 +26,9 @@ where
                     .height(Length::Fixed(40.0))
                     .on_press(on_close())
                     .style(Modern::danger_button()),
-            ),
+                t!("accessibility.close"),
+                tooltip::Position::Left,
+            )),
     )
         .padding(iced::Padding {
             top: 10.0,
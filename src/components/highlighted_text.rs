@@ -0,0 +1,48 @@
+use iced::widget::text::Span;
+use iced::widget::{Text, rich_text, span};
+use iced::{Color, Font, border};
+use iced_modern_theme::Modern;
+use std::ops::Range;
+
+/// Marker behind matched text, readable on the light and the dark themes
+const HIGHLIGHT: Color = Color::from_rgba(1.0, 0.76, 0.03, 0.35);
+
+/// Text with the byte ranges marked, the parts a search query matched. Ranges not
+/// falling on character boundaries are left unmarked.
+pub fn highlighted_text<'a, M: Clone + 'static>(
+    text: &'a str,
+    ranges: &[Range<usize>],
+    size: u16,
+) -> iced::Element<'a, M> {
+    if ranges.is_empty() {
+        return Text::new(text).size(size).style(Modern::primary_text()).into();
+    }
+
+    let mut spans: Vec<Span<'a, M, Font>> = Vec::new();
+    let mut position = 0;
+    for range in ranges {
+        let (Some(before), Some(matched)) = (
+            text.get(position..range.start),
+            text.get(range.start..range.end),
+        ) else {
+            continue;
+        };
+        if !before.is_empty() {
+            spans.push(span(before));
+        }
+        spans.push(
+            span(matched)
+                .background(HIGHLIGHT)
+                .border(border::rounded(2)),
+        );
+        position = range.end;
+    }
+    if let Some(rest) = text.get(position..).filter(|rest| !rest.is_empty()) {
+        spans.push(span(rest));
+    }
+
+    rich_text(spans)
+        .size(size)
+        .style(Modern::primary_text())
+        .into()
+}
@@ -1,68 +1,244 @@
+use crate::components::accessibility::{focus_ring, is_high_contrast, labeled};
+use crate::components::tag_chips::tag_chips;
+use crate::components::highlighted_text;
 use crate::dtos::image_dto::ImageDTO;
 use crate::screen::search::Message;
 use iced::alignment::{Horizontal, Vertical};
 use iced::widget::image::Handle;
 use iced::widget::tooltip::Position;
-use iced::widget::{Button, Column, Container, Image, Row, Scrollable, Text, Tooltip};
+use iced::widget::{Button, Column, Container, Image, Row, Scrollable, Text, scrollable, stack};
 use iced::{Background, Border, Color, Length, Shadow, Theme, Vector};
 use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
 use crate::models::enums::image_type::ImageType;
+use crate::models::view_density::CardSize;
+use crate::services::clipboard_service::CopyFormat;
+use crate::services::file_service::FolderStats;
+use crate::services::statistics_service::format_size;
+use crate::services::{file_service, image_cache, thumbnail_renderer};
+use crate::t_count;
+use std::path::Path;
+
+/// Small mark drawn over the corner of a card's thumbnail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardBadge {
+    /// Totals of the images stored in a folder entry
+    Folder(FolderStats),
+    Gif,
+    Video,
+    /// Imported from a link, which the card can open
+    External,
+}
+
+impl CardBadge {
+    /// Badges of an image, read from its path. Folders are counted on disk the
+    /// first time, then their totals are read back from the meta.
+    fn for_image(image: &ImageDTO) -> Vec<CardBadge> {
+        let path = Path::new(&image.path);
+        let mut badges = Vec::new();
+        if image.is_folder {
+            if let Ok(stats) = file_service::folder_stats(path) {
+                badges.push(CardBadge::Folder(stats));
+            }
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"))
+        {
+            badges.push(CardBadge::Gif);
+        } else if thumbnail_renderer::renderer_for(path)
+            .is_some_and(|renderer| renderer.name() == "video")
+        {
+            badges.push(CardBadge::Video);
+        }
+        if image.source_url.is_some() {
+            badges.push(CardBadge::External);
+        }
+        badges
+    }
+
+    fn view<'a>(&self) -> iced::Element<'a, Message> {
+        let (content, label): (iced::Element<'a, Message>, String) = match self {
+            CardBadge::Folder(stats) => (
+                Row::new()
+                    .spacing(4)
+                    .align_y(Vertical::Center)
+                    .push(fa_icon_solid("images").size(10.0).color(Color::WHITE))
+                    .push(
+                        Text::new(format!(
+                            "{} · {}",
+                            stats.image_count,
+                            format_size(stats.total_size)
+                        ))
+                        .size(11)
+                        .color(Color::WHITE),
+                    )
+                    .into(),
+                match folder_date_range(stats) {
+                    Some(range) => format!(
+                        "{}\n{}",
+                        t_count!("message.image.container.badge.pages", stats.image_count),
+                        range
+                    ),
+                    None => t_count!("message.image.container.badge.pages", stats.image_count)
+                        .to_string(),
+                },
+            ),
+            CardBadge::Gif => (
+                Text::new("GIF").size(11).color(Color::WHITE).into(),
+                t!("message.image.container.badge.gif").to_string(),
+            ),
+            CardBadge::Video => (
+                fa_icon_solid("film").size(10.0).color(Color::WHITE).into(),
+                t!("message.image.container.badge.video").to_string(),
+            ),
+            CardBadge::External => (
+                fa_icon_solid("globe").size(10.0).color(Color::WHITE).into(),
+                t!("message.image.container.badge.external").to_string(),
+            ),
+        };
+
+        labeled(
+            Container::new(content)
+                .padding([2, 6])
+                .style(|_theme: &Theme| iced::widget::container::Style {
+                    background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.65))),
+                    border: Border {
+                        radius: 6.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+            label,
+            Position::Top,
+        )
+    }
+}
+
+/// Days the images of a folder entry were stored over, a single day when they all share it
+pub fn folder_date_range(stats: &FolderStats) -> Option<String> {
+    match (&stats.first_date, &stats.last_date) {
+        (Some(first), Some(last)) if first == last => Some(first.clone()),
+        (Some(first), Some(last)) => {
+            Some(t!("folder_stats.date_range", first = first, last = last).to_string())
+        }
+        _ => None,
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ImageContainer {
     pub id: i64,
     pub image_dto: ImageDTO,
     pub handle: Handle,
+    /// Width over height of the thumbnail, unknown until the image is prepared
+    pub aspect_ratio: Option<f32>,
     pub is_from_folder: bool,
+    /// Shows the copy path/link options in place of the action buttons
+    pub show_copy_menu: bool,
+    /// Shows the toggle that picks the image for a new stack
+    pub selecting: bool,
+    pub selected: bool,
+    /// Set while the image is shown inside its opened stack, tells whether it is the cover
+    pub stack_cover: Option<bool>,
+    /// Marks drawn over the thumbnail's corner
+    pub badges: Vec<CardBadge>,
 
     pub tooltip_delete: String,
     pub tooltip_edit: String,
     pub tooltip_view: String,
     pub tooltip_copy: String,
     pub tooltip_open_local: String,
+    pub tooltip_copy_link: String,
+    pub tooltip_open_source: String,
+    pub tooltip_pin: String,
+    pub tooltip_unpin: String,
+    pub tooltip_select: String,
+    pub tooltip_stack_cover: String,
+    pub tooltip_unstack: String,
 }
 
 impl ImageContainer {
+    /// Totals of the folder entry shown by the card, none for single images
+    pub fn folder_stats(&self) -> Option<&FolderStats> {
+        self.badges.iter().find_map(|badge| match badge {
+            CardBadge::Folder(stats) => Some(stats),
+            _ => None,
+        })
+    }
+
     pub fn new(image_data: ImageDTO, is_from_folder: bool) -> Self {
-        let handle = Handle::from_path(image_data.thumbnail_path.clone());
+        let handle = image_cache::get(&image_data.thumbnail_path)
+            .unwrap_or_else(|| Handle::from_path(image_data.thumbnail_path.clone()));
+        // Reads the header only
+        let aspect_ratio = image::image_dimensions(&image_data.thumbnail_path)
+            .ok()
+            .filter(|(width, height)| *width > 0 && *height > 0)
+            .map(|(width, height)| width as f32 / height as f32);
+        let badges = CardBadge::for_image(&image_data);
         Self {
             id: image_data.id,
             image_dto: image_data,
             handle,
+            aspect_ratio,
             is_from_folder,
+            show_copy_menu: false,
+            selecting: false,
+            selected: false,
+            stack_cover: None,
+            badges,
             tooltip_delete: t!("message.image.container.delete").to_string(),
             tooltip_edit: t!("message.image.container.edit").to_string(),
             tooltip_view: t!("message.image.container.open").to_string(),
             tooltip_copy: t!("message.image.container.copy").to_string(),
             tooltip_open_local: t!("message.image.container.open_local").to_string(),
+            tooltip_copy_link: t!("message.image.container.copy_link").to_string(),
+            tooltip_open_source: t!("message.image.container.open_source").to_string(),
+            tooltip_pin: t!("message.image.container.pin").to_string(),
+            tooltip_unpin: t!("message.image.container.unpin").to_string(),
+            tooltip_select: t!("message.image.container.stack_select").to_string(),
+            tooltip_stack_cover: t!("message.image.container.stack_cover").to_string(),
+            tooltip_unstack: t!("message.image.container.unstack").to_string(),
         }
     }
 
-    pub fn view(&'_ self) -> iced::Element<'_, Message> {
+    /// `lift` raises the card while hovered, from 0 at rest to 1
+    pub fn view(
+        &'_ self,
+        size: CardSize,
+        focused: bool,
+        lift: f32,
+    ) -> iced::Element<'_, Message> {
+        let image_height = size.image_height;
         let image_widget = if self.image_dto.is_prepared {
             Container::new(
                 Image::new(&self.handle)
                     .width(Length::Fill)
-                    .height(Length::Fixed(180.0)),
+                    .height(Length::Fixed(image_height)),
             )
             .padding(8)
             .width(Length::Fill)
-            .height(Length::Fixed(180.0))
+            .height(Length::Fixed(image_height))
         } else {
             Container::new(fa_icon_solid("hourglass-half").size(32.0))
                 .padding(8)
                 .width(Length::Fill)
-                .height(Length::Fixed(180.0))
+                .height(Length::Fixed(image_height))
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Center)
         };
+        let image_widget: iced::Element<'_, Message> = if self.badges.is_empty() {
+            image_widget.into()
+        } else {
+            let badges = self
+                .badges
+                .iter()
+                .fold(Row::new().spacing(4), |row, badge| row.push(badge.view()));
+            stack![image_widget, Container::new(badges).padding(12)].into()
+        };
 
         let description = Container::new(Scrollable::new(
             Container::new(
-                Text::new(&self.image_dto.description)
-                    .size(14)
-                    .style(Modern::primary_text()),
+                highlighted_text(&self.image_dto.description, &self.image_dto.matches, 14),
             )
             .padding([8, 12])
             .width(Length::Fill),
@@ -70,14 +246,132 @@ impl ImageContainer {
         .height(Length::Fixed(90.0))
         .width(Length::Fill);
 
-        let created_at = Container::new(
-            Text::new(&self.image_dto.created_at)
-                .size(11)
-                .style(Modern::secondary_text()),
+        let tags = Container::new(
+            Scrollable::new(tag_chips(&self.image_dto.tags, 11, Message::FilterByTag))
+                .direction(scrollable::Direction::Horizontal(
+                    scrollable::Scrollbar::new().width(2).scroller_width(2),
+                ))
+                .width(Length::Fill),
         )
-        .width(Length::Fill)
-        .align_x(Horizontal::Center)
-        .padding([4, 8]);
+        .padding([0, 12])
+        .height(Length::Fixed(28.0))
+        .width(Length::Fill);
+
+        let mut created_at_row = Row::new()
+            .spacing(6)
+            .align_y(Vertical::Center)
+            .push(
+                Text::new(&self.image_dto.created_at)
+                    .size(11)
+                    .style(Modern::secondary_text()),
+            );
+
+        if !self.is_from_folder && self.image_dto.is_prepared {
+            let pin_button = Button::new(fa_icon_solid("thumbtack").size(11.0))
+                .padding([2, 4])
+                .on_press(Message::TogglePin(self.id, !self.image_dto.is_pinned));
+            created_at_row = created_at_row.push(
+                labeled(
+                    if self.image_dto.is_pinned {
+                        pin_button.style(Modern::primary_button())
+                    } else {
+                        pin_button.style(Modern::system_button())
+                    },
+                    if self.image_dto.is_pinned {
+                        self.tooltip_unpin.as_str()
+                    } else {
+                        self.tooltip_pin.as_str()
+                    },
+                    Position::Top,
+                ),
+            );
+        }
+
+        let is_stack = self.stack_cover.is_none() && self.image_dto.stack_size > 1;
+        if is_stack {
+            created_at_row = created_at_row.push(
+                Row::new()
+                    .spacing(3)
+                    .align_y(Vertical::Center)
+                    .push(fa_icon_solid("layer-group").size(11.0))
+                    .push(
+                        Text::new(self.image_dto.stack_size.to_string())
+                            .size(11)
+                            .style(Modern::secondary_text()),
+                    ),
+            );
+        }
+
+        // Files inside a folder entry aren't registered, batch actions can't reach them
+        if self.selecting && !self.is_from_folder {
+            let select_button = Button::new(
+                fa_icon_solid(if self.selected {
+                    "square-check"
+                } else {
+                    "square"
+                })
+                .size(11.0),
+            )
+            .padding([2, 4])
+            .on_press(Message::ToggleSelection(self.id));
+            created_at_row = created_at_row.push(
+                labeled(
+                    if self.selected {
+                        select_button.style(Modern::primary_button())
+                    } else {
+                        select_button.style(Modern::system_button())
+                    },
+                    self.tooltip_select.as_str(),
+                    Position::Top,
+                ),
+            );
+        }
+
+        if let Some(is_cover) = self.stack_cover {
+            let cover_button = Button::new(fa_icon_solid("star").size(11.0))
+                .padding([2, 4])
+                .on_press_maybe((!is_cover).then_some(Message::SetStackCover(self.id)));
+            created_at_row = created_at_row
+                .push(
+                    labeled(
+                        if is_cover {
+                            cover_button.style(Modern::primary_button())
+                        } else {
+                            cover_button.style(Modern::system_button())
+                        },
+                        self.tooltip_stack_cover.as_str(),
+                        Position::Top,
+                    ),
+                )
+                .push(
+                    labeled(
+                        Button::new(fa_icon_solid("object-ungroup").size(11.0))
+                            .style(Modern::system_button())
+                            .padding([2, 4])
+                            .on_press(Message::RemoveFromStack(self.id)),
+                        self.tooltip_unstack.as_str(),
+                        Position::Top,
+                    ),
+                );
+        }
+
+        if let Some(source_url) = &self.image_dto.source_url {
+            created_at_row = created_at_row.push(
+                labeled(
+                    Button::new(fa_icon_solid("arrow-up-right-from-square").size(11.0))
+                        .style(Modern::system_button())
+                        .padding([2, 4])
+                        .on_press(Message::OpenSource(source_url.clone())),
+                    self.tooltip_open_source.as_str(),
+                    Position::Top,
+                ),
+            );
+        }
+
+        let created_at = Container::new(created_at_row)
+            .width(Length::Fill)
+            .align_x(Horizontal::Center)
+            .padding([4, 8]);
 
         let image_type = if self.is_from_folder {
             ImageType::FromFolder
@@ -89,7 +383,7 @@ impl ImageContainer {
 
         let delete_message = Message::DeleteImage(self.image_dto.clone(), image_type);
 
-        let delete_button: iced::Element<Message> = Tooltip::new(
+        let delete_button = labeled(
             Button::new(
                 Container::new(fa_icon_solid("trash").size(16.0))
                     .align_x(Horizontal::Center)
@@ -103,15 +397,11 @@ impl ImageContainer {
             .on_press(delete_message),
             self.tooltip_delete.as_str(),
             Position::Top,
-        )
-        .style(Modern::card_container())
-        .padding(8)
-        .gap(4)
-        .into();
+        );
 
         let copy_button = if !self.image_dto.is_folder {
             Some(
-                Tooltip::new(
+                labeled(
                     Button::new(
                         Container::new(fa_icon_solid("copy").size(16.0))
                             .align_x(Horizontal::Center)
@@ -125,16 +415,13 @@ impl ImageContainer {
                     .on_press(Message::CopyImage(self.image_dto.path.clone())),
                     self.tooltip_copy.as_str(),
                     Position::Top,
-                )
-                .style(Modern::card_container())
-                .padding(8)
-                .gap(4),
+                ),
             )
         } else {
             None
         };
 
-        let view_button = Tooltip::new(
+        let view_button = labeled(
             Button::new(
                 Container::new(fa_icon_solid("eye").size(16.0))
                     .align_x(Horizontal::Center)
@@ -148,14 +435,11 @@ impl ImageContainer {
             .on_press(Message::OpenImage(self.image_dto.clone())),
             self.tooltip_view.as_str(),
             Position::Top,
-        )
-        .style(Modern::card_container())
-        .padding(8)
-        .gap(4);
+        );
 
         let edit_button = if !self.is_from_folder {
             Some(
-                Tooltip::new(
+                labeled(
                     Button::new(
                         Container::new(fa_icon_solid("pen-to-square").size(16.0))
                             .align_x(Horizontal::Center)
@@ -167,18 +451,15 @@ impl ImageContainer {
                     .width(Length::FillPortion(1))
                     .height(Length::Fixed(36.0))
                     .on_press(Message::Update(self.image_dto.clone())),
-                    self.tooltip_edit.as_ref(),
+                    self.tooltip_edit.as_str(),
                     Position::Top,
-                )
-                .style(Modern::card_container())
-                .padding(8)
-                .gap(4),
+                ),
             )
         } else {
             None
         };
 
-        let open_local_button = Tooltip::new(
+        let open_local_button = labeled(
             Button::new(
                 Container::new(fa_icon_solid("folder-open").size(16.0))
                     .align_x(Horizontal::Center)
@@ -192,23 +473,41 @@ impl ImageContainer {
             .on_press(Message::OpenLocalImage(self.id)),
             self.tooltip_open_local.as_str(),
             Position::Top,
-        )
-        .style(Modern::card_container())
-        .padding(8)
-        .gap(4);
+        );
 
-        let mut action_buttons = Row::new()
-            .spacing(6)
-            .push(delete_button)
-            .push(view_button)
-            .push(open_local_button);
+        let copy_link_button = labeled(
+            Button::new(
+                Container::new(fa_icon_solid("link").size(16.0))
+                    .align_x(Horizontal::Center)
+                    .align_y(Vertical::Center)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .style(Modern::secondary_button())
+            .width(Length::FillPortion(1))
+            .height(Length::Fixed(36.0))
+            .on_press(Message::ToggleCopyMenu(self.id)),
+            self.tooltip_copy_link.as_str(),
+            Position::Top,
+        );
 
-        if let Some(edit_btn) = edit_button {
-            action_buttons = action_buttons.push(edit_btn);
-        }
-        if let Some(copy_btn) = copy_button {
-            action_buttons = action_buttons.push(copy_btn);
-        }
+        let action_buttons = if self.show_copy_menu {
+            self.copy_menu()
+        } else {
+            let mut action_buttons = Row::new()
+                .spacing(6)
+                .push(delete_button)
+                .push(view_button)
+                .push(open_local_button);
+
+            if let Some(edit_btn) = edit_button {
+                action_buttons = action_buttons.push(edit_btn);
+            }
+            if let Some(copy_btn) = copy_button {
+                action_buttons = action_buttons.push(copy_btn);
+            }
+            action_buttons.push(copy_link_button)
+        };
 
         // Container dos botões
         let buttons_container = Container::new(action_buttons)
@@ -221,6 +520,7 @@ impl ImageContainer {
                 .spacing(0)
                 .push(image_widget)
                 .push(description)
+                .push(tags)
                 .push(created_at)
                 .push(buttons_container)
         } else {
@@ -228,34 +528,95 @@ impl ImageContainer {
                 .spacing(0)
                 .push(image_widget)
                 .push(description)
+                .push(tags)
                 .push(created_at)
         };
 
         // Card container com sombra e bordas arredondadas
-        Container::new(card_content)
+        let card = Container::new(card_content)
             .padding(5)
-            .width(Length::Fixed(220.0))
-            .height(Length::Fixed(360.0))
+            .width(Length::Fixed(size.width))
+            .height(Length::Fixed(size.height()))
             .style(move |theme: &Theme| iced::widget::container::Style {
                 background: Some(Background::Color(theme.palette().background)),
                 border: Border {
-                    color: if self.image_dto.is_folder {
+                    color: if self.selected {
+                        theme.palette().primary
+                    } else if self.image_dto.is_folder {
                         Color::from_rgb(0.0, 0.5, 1.0) // Azul
-                    }
-                    else {
+                    } else if is_stack {
+                        Color::from_rgb(0.6, 0.2, 0.8)
+                    } else if is_high_contrast(theme) {
+                        Color { a: 0.6, ..theme.palette().text }
+                    } else {
                         Color::from_rgba(0.0, 0.0, 0.0, 0.1)
                     },
-                    width: 1.0,
+                    width: if self.selected { 2.0 } else { 1.0 },
                     radius: 12.0.into(),
                 },
                 shadow: Shadow {
-                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.1),
-                    offset: Vector::new(0.0, 2.0),
-                    blur_radius: 8.0,
+                    color: Color::from_rgba(0.0, 0.0, 0.0, 0.1 + 0.15 * lift),
+                    offset: Vector::new(0.0, 2.0 + 4.0 * lift),
+                    blur_radius: 8.0 + 12.0 * lift,
                 },
                 ..Default::default()
-            })
-            .into()
+            });
+
+        focus_ring(card, focused, 12.0)
+    }
 
+    fn copy_menu(&'_ self) -> Row<'_, Message> {
+        let option = |icon: &'static str, tooltip: String, message: Message| {
+            labeled(
+                Button::new(
+                    Container::new(fa_icon_solid(icon).size(16.0))
+                        .align_x(Horizontal::Center)
+                        .align_y(Vertical::Center)
+                        .width(Length::Fill)
+                        .height(Length::Fill),
+                )
+                .style(Modern::secondary_button())
+                .width(Length::FillPortion(1))
+                .height(Length::Fixed(36.0))
+                .on_press(message),
+                tooltip,
+                Position::Top,
+            )
+        };
+
+        let copy = |format: CopyFormat| {
+            Message::CopyReference(
+                self.image_dto.path.clone(),
+                self.image_dto.description.clone(),
+                format,
+            )
+        };
+
+        let mut row = Row::new()
+            .spacing(6)
+            .push(option(
+                "file",
+                t!("message.image.container.copy_path").to_string(),
+                copy(CopyFormat::Path),
+            ))
+            .push(option(
+                "globe",
+                t!("message.image.container.copy_url").to_string(),
+                copy(CopyFormat::FileUrl),
+            ));
+
+        if !self.image_dto.is_folder {
+            row = row.push(option(
+                "code",
+                t!("message.image.container.copy_markdown").to_string(),
+                copy(CopyFormat::Markdown),
+            ));
+        }
+
+        row.push(option(
+            "xmark",
+            t!("message.image.container.close_copy_menu").to_string(),
+            Message::ToggleCopyMenu(self.id),
+        ))
     }
 }
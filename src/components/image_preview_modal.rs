@@ -1,34 +1,94 @@
+use crate::components::accessibility::labeled;
 use iced::widget::image::{viewer, Handle};
+use iced::widget::tooltip::Position;
 use iced::widget::{button, Column, Container, Row, Space, Text};
 use iced::{Alignment, Background, Border, Color, Length, Shadow, Theme, Vector};
 use iced::alignment::{Horizontal, Vertical};
 use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
 
-pub struct PreviewConfig<M> {
+/// Extra button shown in the preview header
+pub struct PreviewAction<M> {
+    pub icon: &'static str,
+    pub tooltip: String,
+    pub on_press: M,
+}
+
+pub struct PreviewConfig<'a, M> {
     pub handle: Handle,
     pub current_index: usize,
     pub total_images: usize,
     pub on_close: M,
     pub on_previous: Option<M>,
     pub on_next: Option<M>,
+    pub actions: Vec<PreviewAction<M>>,
+    /// Panel shown to the right of the image, e.g. file information
+    pub side_panel: Option<iced::Element<'a, M>>,
+    /// Panel shown below the image, e.g. related images
+    pub bottom_panel: Option<iced::Element<'a, M>>,
+    /// Opening transition, from 0 when just opened to 1 once in place
+    pub appear: f32,
+    /// Set while the image is still decoding, the message stops it
+    pub on_cancel_loading: Option<M>,
 }
 
 pub fn image_preview_modal<'a, M: 'a + Clone>(
-    config: PreviewConfig<M>,
+    config: PreviewConfig<'a, M>,
 ) -> iced::Element<'a, M> {
     let image_counter = format!("{} / {}", config.current_index + 1, config.total_images);
 
-    let header: Row<_> = Row::new()
+    let mut header: Row<_> = Row::new()
         .width(Length::Fill)
+        .spacing(10)
         .align_y(Vertical::Center)
         .push(
             Text::new(image_counter)
                 .size(16)
                 .style(Modern::secondary_text()),
         )
-        .push(Space::with_width(Length::Fill))
-        .push(
+        .push_maybe(config.on_cancel_loading.map(|on_cancel| {
+            Row::new()
+                .spacing(8)
+                .align_y(Vertical::Center)
+                .push(fa_icon_solid("spinner").size(14.0))
+                .push(
+                    Text::new(t!("search.preview.loading"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(labeled(
+                    button(fa_icon_solid("ban").size(14.0))
+                        .padding([4, 8])
+                        .on_press(on_cancel)
+                        .style(Modern::secondary_button()),
+                    t!("search.preview.cancel_loading"),
+                    Position::Bottom,
+                ))
+        }))
+        .push(Space::with_width(Length::Fill));
+
+    for action in config.actions {
+        header = header.push(
+            labeled(
+                button(
+                    Container::new(fa_icon_solid(action.icon).size(18.0))
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .align_x(Alignment::Center)
+                        .align_y(Alignment::Center),
+                )
+                .width(Length::Fixed(40.0))
+                .height(Length::Fixed(40.0))
+                .on_press(action.on_press)
+                .style(Modern::secondary_button()),
+                action.tooltip,
+                Position::Bottom,
+            ),
+        );
+    }
+
+    let header = header
+        .push(labeled(
             button(
                 Container::new(fa_icon_solid("xmark").size(24.0))
                     .width(Length::Fill)
@@ -40,7 +100,9 @@ pub fn image_preview_modal<'a, M: 'a + Clone>(
                 .height(Length::Fixed(40.0))
                 .on_press(config.on_close)
                 .style(Modern::danger_button()),
-        );
+            t!("accessibility.close"),
+            Position::Bottom,
+        ));
 
     let mut prev_button = button(
         Container::new(fa_icon_solid("chevron-left").size(24.0))
@@ -72,12 +134,12 @@ pub fn image_preview_modal<'a, M: 'a + Clone>(
         next_button = next_button.on_press(on_next);
     }
 
-    let body_with_navigation = Row::new()
+    let mut body_with_navigation = Row::new()
         .width(Length::Fill)
         .height(Length::Fill)
         .align_y(Alignment::Center)
         .push(
-            Container::new(prev_button)
+            Container::new(labeled(prev_button, t!("accessibility.previous"), Position::Right))
                 .width(Length::Fixed(70.0))
                 .height(Length::Fill)
                 .align_y(Alignment::Center)
@@ -95,34 +157,58 @@ pub fn image_preview_modal<'a, M: 'a + Clone>(
                 .align_y(Vertical::Center),
         )
         .push(
-            Container::new(next_button)
+            Container::new(labeled(next_button, t!("accessibility.next"), Position::Left))
                 .width(Length::Fixed(70.0))
                 .height(Length::Fill)
                 .align_y(Alignment::Center)
                 .padding([0, 10]),
         );
 
-    let modal_content: Column<_> = Column::new()
+    if let Some(panel) = config.side_panel {
+        body_with_navigation = body_with_navigation.push(
+            Container::new(panel)
+                .width(Length::Fixed(320.0))
+                .height(Length::Fill)
+                .padding(15)
+                .style(Modern::card_container()),
+        );
+    }
+
+    let mut modal_content: Column<_> = Column::new()
         .spacing(15)
         .align_x(Horizontal::Center)
         .push(header)
         .push(body_with_navigation);
 
+    if let Some(panel) = config.bottom_panel {
+        modal_content = modal_content.push(
+            Container::new(panel)
+                .width(Length::Fill)
+                .padding(10)
+                .style(Modern::card_container()),
+        );
+    }
+
+    // Grows from a smaller, fainter box into place while appearing
+    let appear = config.appear.clamp(0.0, 1.0);
     Container::new(modal_content)
-        .padding(30)
+        .padding(30.0 + 40.0 * (1.0 - appear))
         .width(Length::FillPortion(9))
         .height(Length::FillPortion(9))
         .align_x(Horizontal::Center)
         .align_y(Vertical::Center)
-        .style(|theme: &Theme| iced::widget::container::Style {
-            background: Some(Background::Color(theme.palette().background)),
+        .style(move |theme: &Theme| iced::widget::container::Style {
+            background: Some(Background::Color(Color {
+                a: 0.6 + 0.4 * appear,
+                ..theme.palette().background
+            })),
             border: Border {
                 color: Default::default(),
                 width: 0.0,
                 radius: 10.0.into(),
             },
             shadow: Shadow {
-                color: Color::from_rgba(0.0, 0.0, 0.0, 0.3),
+                color: Color::from_rgba(0.0, 0.0, 0.0, 0.3 * appear),
                 offset: Vector::new(0.0, 8.0),
                 blur_radius: 16.0,
             },
@@ -0,0 +1,106 @@
+use crate::models::import_filter::{IMPORT_FORMATS, ImportFilter};
+use iced::widget::{Checkbox, Column, PickList, Row, Text};
+use iced::{Element, Length};
+use iced_modern_theme::Modern;
+
+const MIN_SIDES: [MinSide; 6] = [
+    MinSide(0),
+    MinSide(64),
+    MinSide(128),
+    MinSide(256),
+    MinSide(512),
+    MinSide(1024),
+];
+
+const MAX_SIZES: [MaxSize; 6] = [
+    MaxSize(0),
+    MaxSize(5),
+    MaxSize(10),
+    MaxSize(25),
+    MaxSize(50),
+    MaxSize(100),
+];
+
+/// Shortest side an imported image needs, 0 accepts any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MinSide(u32);
+
+impl std::fmt::Display for MinSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", t!("import_filter.any_resolution")),
+            side => write!(f, "{}", t!("import_filter.min_side", px = side)),
+        }
+    }
+}
+
+/// Largest file an import accepts in MB, 0 accepts any
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MaxSize(u64);
+
+impl std::fmt::Display for MaxSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", t!("import_filter.any_size")),
+            mb => write!(f, "{}", t!("import_filter.max_size", mb = mb)),
+        }
+    }
+}
+
+/// Controls editing an import filter, each change sends the whole edited filter
+pub fn import_filter_editor<'a, M: Clone + 'a>(
+    filter: &ImportFilter,
+    on_change: fn(ImportFilter) -> M,
+) -> Element<'a, M> {
+    let label = |text: String| Text::new(text).size(14).style(Modern::secondary_text());
+
+    let min_side = {
+        let filter = filter.clone();
+        PickList::new(MIN_SIDES, Some(MinSide(filter.min_side)), move |side| {
+            on_change(ImportFilter {
+                min_side: side.0,
+                ..filter.clone()
+            })
+        })
+        .style(Modern::pick_list())
+        .width(Length::Fill)
+    };
+
+    let max_size = {
+        let filter = filter.clone();
+        PickList::new(MAX_SIZES, Some(MaxSize(filter.max_size_mb)), move |size| {
+            on_change(ImportFilter {
+                max_size_mb: size.0,
+                ..filter.clone()
+            })
+        })
+        .style(Modern::pick_list())
+        .width(Length::Fill)
+    };
+
+    let formats = IMPORT_FORMATS
+        .iter()
+        .fold(Row::new().spacing(16), |row, format| {
+            let filter = filter.clone();
+            row.push(
+                Checkbox::new(format.to_uppercase(), filter.allows_format(format))
+                    .on_toggle(move |allowed| {
+                        on_change(filter.clone().with_format(format, allowed))
+                    })
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+            )
+        })
+        .wrap();
+
+    Column::new()
+        .spacing(12)
+        .push(label(t!("import_filter.label.resolution").to_string()))
+        .push(min_side)
+        .push(label(t!("import_filter.label.file_size").to_string()))
+        .push(max_size)
+        .push(label(t!("import_filter.label.formats").to_string()))
+        .push(formats)
+        .into()
+}
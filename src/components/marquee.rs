@@ -0,0 +1,281 @@
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{self, Operation, Tree, tree};
+use iced::advanced::{Clipboard, Renderer as _, Shell, Widget, overlay};
+use iced::event::{self, Event};
+use iced::{
+    Border, Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector, mouse, window,
+};
+
+/// Distance the pointer has to travel with the button held before a drag starts a
+/// rectangle, so a slightly shaky click still reaches the card under it
+const DRAG_THRESHOLD: f32 = 6.0;
+
+/// Draws a selection rectangle over the content while the left button is dragged
+/// across it. On release it reports the ids of the containers in the content the
+/// rectangle touched, the outermost ones only.
+///
+/// A press still reaches the content, the rectangle only starts once the pointer
+/// moves past [`DRAG_THRESHOLD`]. From then on the content sees no pointer events
+/// until the release, which it gets without a cursor so a button pressed at the
+/// start of the drag doesn't fire.
+pub struct Marquee<'a, Message> {
+    content: Element<'a, Message>,
+    on_select: Box<dyn Fn(Vec<widget::Id>) -> Message + 'a>,
+}
+
+/// Content that can be selected from with a dragged rectangle, see [`Marquee`]
+pub fn marquee<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    on_select: impl Fn(Vec<widget::Id>) -> Message + 'a,
+) -> Marquee<'a, Message> {
+    Marquee {
+        content: content.into(),
+        on_select: Box::new(on_select),
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Where the left button went down, while it is held
+    origin: Option<Point>,
+    /// Pointer position while the button is held
+    cursor: Point,
+    /// Whether the pointer moved far enough to draw the rectangle
+    dragging: bool,
+}
+
+impl State {
+    fn area(&self) -> Option<Rectangle> {
+        let origin = self.origin.filter(|_| self.dragging)?;
+        let top_left = Point::new(origin.x.min(self.cursor.x), origin.y.min(self.cursor.y));
+        let size = Size::new(
+            (origin.x - self.cursor.x).abs(),
+            (origin.y - self.cursor.y).abs(),
+        );
+        Some(Rectangle::new(top_left, size))
+    }
+}
+
+/// Collects the ids of the outermost containers touching the area
+struct Touching {
+    area: Rectangle,
+    found: Vec<widget::Id>,
+}
+
+impl Operation for Touching {
+    fn container(
+        &mut self,
+        id: Option<&widget::Id>,
+        bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation),
+    ) {
+        match id {
+            Some(id) if bounds.intersects(&self.area) => self.found.push(id.clone()),
+            Some(_) => {}
+            None => operate_on_children(self),
+        }
+    }
+}
+
+impl<Message> Widget<Message, Theme, Renderer> for Marquee<'_, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                state.origin = cursor.position_over(layout.bounds());
+                state.cursor = state.origin.unwrap_or_default();
+                state.dragging = false;
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if state.origin.is_some() => {
+                if let Some(position) = cursor.position() {
+                    state.cursor = position;
+                }
+                if !state.dragging
+                    && state
+                        .origin
+                        .is_some_and(|origin| origin.distance(state.cursor) > DRAG_THRESHOLD)
+                {
+                    state.dragging = true;
+                }
+                if state.dragging {
+                    shell.request_redraw(window::RedrawRequest::NextFrame);
+                    return event::Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let area = state.area();
+                state.origin = None;
+                state.dragging = false;
+                if let Some(area) = area {
+                    let _ = self.content.as_widget_mut().on_event(
+                        &mut tree.children[0],
+                        event,
+                        layout,
+                        mouse::Cursor::Unavailable,
+                        renderer,
+                        clipboard,
+                        shell,
+                        viewport,
+                    );
+                    let mut touching = Touching {
+                        area,
+                        found: Vec::new(),
+                    };
+                    self.content.as_widget().operate(
+                        &mut tree.children[0],
+                        layout,
+                        renderer,
+                        &mut touching,
+                    );
+                    shell.publish((self.on_select)(touching.found));
+                    return event::Status::Captured;
+                }
+            }
+            _ => {}
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+
+        let Some(area) = tree.state.downcast_ref::<State>().area() else {
+            return;
+        };
+        let color = theme.extended_palette().primary.base.color;
+        renderer.with_layer(*viewport, |renderer| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: area,
+                    border: Border {
+                        color,
+                        width: 1.0,
+                        radius: 2.0.into(),
+                    },
+                    ..renderer::Quad::default()
+                },
+                Color { a: 0.15, ..color },
+            );
+        });
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if tree.state.downcast_ref::<State>().dragging {
+            return mouse::Interaction::Crosshair;
+        }
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer, translation)
+    }
+}
+
+impl<'a, Message: 'a> From<Marquee<'a, Message>> for Element<'a, Message> {
+    fn from(marquee: Marquee<'a, Message>) -> Self {
+        Element::new(marquee)
+    }
+}
@@ -1,17 +1,28 @@
+pub mod accessibility;
 pub mod image_container;
+pub mod import_filter_editor;
+pub mod tag_chips;
 pub mod tag_selector;
 pub mod navbar;
 pub mod toast_view;
 pub mod header;
 pub mod pagination;
 pub mod empty_state;
+pub mod skeleton;
+pub mod status_bar;
 pub mod search_bar;
 pub mod image_preview_modal;
+pub mod quick_look;
+pub mod marquee;
 pub mod scrollable_form;
+pub mod highlighted_text;
 
 pub use scrollable_form::{scrollable_form, ScrollableFormConfig};
 pub use empty_state::empty_state;
 pub use header::header;
+pub use highlighted_text::highlighted_text;
+pub use import_filter_editor::import_filter_editor;
+pub use marquee::marquee;
 pub use image_preview_modal::image_preview_modal;
 pub use pagination::pagination;
 pub use search_bar::search_bar;
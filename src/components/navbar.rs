@@ -1,14 +1,22 @@
-use crate::config::Settings;
+use crate::components::accessibility::labeled;
+use crate::config::{Settings, get_settings, update_config};
+use crate::dtos::library_counts_dto::LibraryCountsDTO;
+use crate::models::saved_search::SavedSearch;
+use crate::services::image_service;
+use crate::services::toast_service::{push_error, push_success};
 use iced::alignment::Horizontal;
-use iced::widget::{Column, button, container, scrollable, text};
-use iced::{Element, Length, Padding, Task};
+use iced::widget::{Column, Row, Space, button, container, mouse_area, text, tooltip};
+use iced::{Alignment, Background, Element, Length, Padding, Task, Theme, mouse};
+use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
-use log::info;
+use log::error;
 use rust_i18n::t;
 
 pub enum Action {
     Run(Task<Message>),
     Navigate(NavButton),
+    /// Runs a pinned search
+    OpenSearch(SavedSearch),
     None,
 }
 
@@ -16,32 +24,128 @@ pub enum Action {
 pub enum NavButton {
     Home,
     Search,
+    Inbox,
     Workspace,
     ManageTags,
+    Timeline,
+    Activity,
+    Quarantine,
+    Trash,
     Preferences,
 }
 
+impl NavButton {
+    /// In the order they are shown, Ctrl+1 to Ctrl+9 and Ctrl+0 select them
+    pub const ALL: [NavButton; 10] = [
+        NavButton::Home,
+        NavButton::Search,
+        NavButton::Inbox,
+        NavButton::Workspace,
+        NavButton::ManageTags,
+        NavButton::Timeline,
+        NavButton::Activity,
+        NavButton::Quarantine,
+        NavButton::Trash,
+        NavButton::Preferences,
+    ];
+
+    fn label(self) -> String {
+        match self {
+            NavButton::Home => t!("navbar.button.home"),
+            NavButton::Search => t!("navbar.button.search"),
+            NavButton::Inbox => t!("navbar.button.inbox"),
+            NavButton::Workspace => t!("navbar.button.workspace"),
+            NavButton::ManageTags => t!("navbar.button.manage_tags"),
+            NavButton::Timeline => t!("navbar.button.timeline"),
+            NavButton::Activity => t!("navbar.button.activity"),
+            NavButton::Quarantine => t!("navbar.button.quarantine"),
+            NavButton::Trash => t!("navbar.button.trash"),
+            NavButton::Preferences => t!("navbar.button.settings"),
+        }
+        .to_string()
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            NavButton::Home => "house",
+            NavButton::Search => "magnifying-glass",
+            NavButton::Inbox => "inbox",
+            NavButton::Workspace => "table-cells-large",
+            NavButton::ManageTags => "tags",
+            NavButton::Timeline => "calendar-days",
+            NavButton::Activity => "clock-rotate-left",
+            NavButton::Quarantine => "file-circle-exclamation",
+            NavButton::Trash => "trash-can",
+            NavButton::Preferences => "gear",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     ButtonSignal(NavButton),
     ButtonPressed(NavButton),
+    CountsLoaded(LibraryCountsDTO),
+    ToggleCollapsed,
+    ResizeStarted,
+    /// Cursor position while dragging the edge, which is the new width
+    Resized(f32),
+    ResizeFinished,
+    PinSearch(SavedSearch),
+    UnpinSearch(usize),
+    OpenPinnedSearch(usize),
+    /// The config was saved, the pinned searches may have changed
+    SettingsChanged,
     NoOps,
 }
 
 pub struct Navbar {
     pub selected: NavButton,
     settings: Settings,
+    /// Library totals shown as badges, None until first loaded
+    counts: Option<LibraryCountsDTO>,
+    /// Icons only, labels move to tooltips
+    collapsed: bool,
+    /// Width when expanded
+    width: f32,
+    /// The edge is being dragged
+    resizing: bool,
 }
 
+const COLLAPSED_WIDTH: f32 = 64.0;
+const MIN_WIDTH: f32 = 180.0;
+const MAX_WIDTH: f32 = 480.0;
+
 impl Navbar {
     pub fn new() -> Self {
-        let settings = Settings::load();
+        let settings = get_settings().clone();
+        let collapsed = settings.config.navbar_collapsed.unwrap_or(false);
+        let width = settings
+            .config
+            .navbar_width
+            .unwrap_or(280.0)
+            .clamp(MIN_WIDTH, MAX_WIDTH);
         Navbar {
             selected: NavButton::Search,
             settings,
+            counts: None,
+            collapsed,
+            width,
+            resizing: false,
         }
     }
 
+    /// Reloads the badge counts, called at startup and whenever data changes
+    pub fn load_counts() -> Task<Message> {
+        Task::perform(image_service::count_library(), |result| match result {
+            Ok(counts) => Message::CountsLoaded(counts),
+            Err(err) => {
+                error!("Failed to count the library: {}", err);
+                Message::NoOps
+            }
+        })
+    }
+
     pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::ButtonSignal(id) => {
@@ -52,92 +156,303 @@ impl Navbar {
                 self.selected = id;
                 Action::Navigate(id)
             }
-            Message::NoOps => {
-                self.settings = Settings::load();
-                info!("navbar update ");
+            Message::CountsLoaded(counts) => {
+                self.counts = Some(counts);
                 Action::None
             }
+            Message::ToggleCollapsed => {
+                self.collapsed = !self.collapsed;
+                let collapsed = self.collapsed;
+                update_config(|config| config.navbar_collapsed = Some(collapsed));
+                Action::None
+            }
+            Message::ResizeStarted => {
+                self.resizing = !self.collapsed;
+                Action::None
+            }
+            Message::Resized(x) => {
+                if self.resizing {
+                    self.width = x.clamp(MIN_WIDTH, MAX_WIDTH);
+                }
+                Action::None
+            }
+            Message::ResizeFinished => {
+                if std::mem::take(&mut self.resizing) {
+                    let width = self.width;
+                    update_config(|config| config.navbar_width = Some(width));
+                }
+                Action::None
+            }
+            Message::PinSearch(search) => {
+                let pinned = self.pinned_searches();
+                if pinned.iter().any(|other| other.same_filters(&search)) {
+                    push_error(t!("message.navbar.pin_exists"));
+                    return Action::None;
+                }
+                push_success(t!("message.navbar.pinned", name = search.name));
+                self.update_pinned(|pinned| pinned.push(search));
+                Action::None
+            }
+            Message::UnpinSearch(index) => {
+                self.update_pinned(|pinned| {
+                    if index < pinned.len() {
+                        pinned.remove(index);
+                    }
+                });
+                Action::None
+            }
+            Message::OpenPinnedSearch(index) => match self.pinned_searches().get(index) {
+                Some(search) => Action::OpenSearch(search.clone()),
+                None => Action::None,
+            },
+            Message::SettingsChanged => {
+                self.settings = get_settings().clone();
+                Action::None
+            }
+            Message::NoOps => Action::None,
         }
     }
 
+    fn pinned_searches(&self) -> &[SavedSearch] {
+        self.settings.config.pinned_searches.as_deref().unwrap_or_default()
+    }
+
+    /// Changes the pinned searches here and in the saved config
+    fn update_pinned(&mut self, change: impl FnOnce(&mut Vec<SavedSearch>)) {
+        let pinned = self.settings.config.pinned_searches.get_or_insert_with(Vec::new);
+        change(pinned);
+        let pinned = pinned.clone();
+        update_config(|config| config.pinned_searches = Some(pinned));
+    }
+
+    /// One button per pinned search, with its unpin button while expanded
+    fn pinned_buttons(&self) -> Column<'_, Message> {
+        let collapsed = self.collapsed;
+        let mut column = Column::new().spacing(5);
+        if !collapsed && !self.pinned_searches().is_empty() {
+            column = column.push(
+                container(
+                    text(t!("navbar.pinned.title"))
+                        .size(12)
+                        .style(Modern::secondary_text()),
+                )
+                .padding(Padding::from([4, 8])),
+            );
+        }
+
+        for (index, search) in self.pinned_searches().iter().enumerate() {
+            let content: Element<'_, Message> = if collapsed {
+                fa_icon_solid("bookmark").size(14.0).into()
+            } else {
+                Row::new()
+                    .align_y(Alignment::Center)
+                    .spacing(8)
+                    .push(fa_icon_solid("bookmark").size(12.0))
+                    .push(
+                        text(&search.name)
+                            .size(14)
+                            .width(Length::Fill)
+                            .wrapping(text::Wrapping::None),
+                    )
+                    .into()
+            };
+            let open = button(container(content).center_x(Length::Fill))
+                .width(Length::Fill)
+                .padding(Padding::from([8, if collapsed { 8 } else { 12 }]))
+                .style(Modern::secondary_button())
+                .on_press(Message::OpenPinnedSearch(index));
+
+            column = column.push(if collapsed {
+                labeled(open, search.name.as_str(), tooltip::Position::Right)
+            } else {
+                Row::new()
+                    .spacing(4)
+                    .align_y(Alignment::Center)
+                    .push(open)
+                    .push(labeled(
+                        button(fa_icon_solid("xmark").size(12.0))
+                            .padding(Padding::from([8, 8]))
+                            .style(Modern::system_button())
+                            .on_press(Message::UnpinSearch(index)),
+                        t!("navbar.pinned.unpin"),
+                        tooltip::Position::Right,
+                    ))
+                    .into()
+            });
+        }
+        column
+    }
+
     pub fn view(&'_ self) -> Element<'_, Message> {
-        fn styled_button(
-            label: String,
-            id: NavButton,
-            selected: NavButton,
-        ) -> iced::widget::Button<'static, Message> {
-            let base = button(text(label).style(Modern::primary_text()).width(Length::Fill).align_x(Horizontal::Center))
+        let collapsed = self.collapsed;
+        let nav_button = |id: NavButton, badges: Vec<Element<'static, Message>>| {
+            let label = id.label();
+            let content: Element<'static, Message> = if collapsed {
+                fa_icon_solid(id.icon()).size(18.0).into()
+            } else {
+                Row::new()
+                    .align_y(Alignment::Center)
+                    .spacing(10)
+                    .push(fa_icon_solid(id.icon()).size(16.0))
+                    .push(
+                        text(label.clone())
+                            .style(Modern::primary_text())
+                            .width(Length::Fill)
+                            .align_x(Horizontal::Center),
+                    )
+                    .extend(badges)
+                    .into()
+            };
+
+            let button = button(container(content).center_x(Length::Fill))
                 .width(Length::Fill)
                 .height(Length::Fixed(48.0))
-                .padding(Padding {
-                    top: 12.0,
-                    right: 16.0,
-                    bottom: 12.0,
-                    left: 16.0,
-                })
+                .padding(Padding::from([12, if collapsed { 8 } else { 16 }]))
                 .on_press(Message::ButtonSignal(id));
+            let button = if id == self.selected {
+                button.style(Modern::primary_button())
+            } else {
+                button.style(Modern::blue_tinted_button())
+            };
 
-            if id == selected {
-                base.style(Modern::primary_button())
+            let element: Element<'static, Message> = if collapsed {
+                labeled(button, label, tooltip::Position::Right)
             } else {
-                base.style(Modern::blue_tinted_button())
-            }
-        }
+                button.into()
+            };
+            element
+        };
+
+        let toggle = button(
+            container(
+                fa_icon_solid(if collapsed { "angles-right" } else { "angles-left" }).size(16.0),
+            )
+            .center_x(Length::Fill),
+        )
+        .width(Length::Fill)
+        .padding(Padding::from([8, 8]))
+        .style(Modern::system_button())
+        .on_press(Message::ToggleCollapsed);
+        let toggle = labeled(
+            toggle,
+            if collapsed {
+                t!("navbar.button.expand")
+            } else {
+                t!("navbar.button.collapse")
+            },
+            tooltip::Position::Right,
+        );
 
         let navbar = Column::new()
-            .push(styled_button(
-                t!("navbar.button.home").to_string(),
-                NavButton::Home,
-                self.selected,
-            ))
-            .push(styled_button(
-                t!("navbar.button.search").to_string(),
-                NavButton::Search,
-                self.selected,
-            ))
-            .push(styled_button(
-                t!("navbar.button.workspace").to_string(),
-                NavButton::Workspace,
-                self.selected,
-            ))
             .spacing(5)
-            .push(styled_button(
-                t!("navbar.button.manage_tags").to_string(),
+            .push(toggle)
+            .push(nav_button(NavButton::Home, Vec::new()))
+            .push(nav_button(NavButton::Search, self.search_badges()))
+            .push(nav_button(
+                NavButton::Inbox,
+                self.count_badge(
+                    |c| c.unreviewed,
+                    Some(t!("navbar.badge.unreviewed").to_string()),
+                ),
+            ))
+            .push(nav_button(NavButton::Workspace, Vec::new()))
+            .push(nav_button(
                 NavButton::ManageTags,
-                self.selected,
+                self.count_badge(|c| c.tags, None),
             ))
-            .spacing(5);
-
-        let empty_middle = scrollable(Column::new().push(text("").size(1)))
-            .width(Length::Fill)
-            .height(Length::Fill);
-
-        let settings_button = Column::new().push(
-            styled_button(
-                t!("navbar.button.settings").to_string(),
-                NavButton::Preferences,
-                self.selected,
-            )
-            .padding(Padding {
-                top: 12.0,
-                right: 16.0,
-                bottom: 12.0,
-                left: 16.0,
-            })
-            .on_press(Message::ButtonSignal(NavButton::Preferences)),
-        );
+            .push(nav_button(NavButton::Timeline, Vec::new()))
+            .push(nav_button(NavButton::Activity, Vec::new()))
+            .push(nav_button(
+                NavButton::Quarantine,
+                self.count_badge(
+                    |c| c.quarantined,
+                    Some(t!("navbar.badge.quarantined").to_string()),
+                ),
+            ))
+            .push(nav_button(
+                NavButton::Trash,
+                self.count_badge(|c| c.trashed, None),
+            ));
 
         let layout = Column::new()
-            .push(navbar.height(Length::Fixed(225.0)))
-            .push(empty_middle.height(Length::Fill))
-            .push(settings_button.height(Length::Fixed(48.0)))
+            .push(navbar)
+            .push(self.pinned_buttons())
+            .push(Space::with_height(Length::Fill))
+            .push(nav_button(NavButton::Preferences, Vec::new()))
             .spacing(10);
 
         container(layout)
-            .width(Length::Fixed(280.0))
+            .width(Length::Fixed(if collapsed {
+                COLLAPSED_WIDTH
+            } else {
+                self.width
+            }))
             .height(Length::Fill)
             .padding(5)
             .style(Modern::card_container())
             .into()
     }
+
+    pub fn counts(&self) -> Option<&LibraryCountsDTO> {
+        self.counts.as_ref()
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.resizing
+    }
+
+    /// Strip between the navbar and the content, dragged to change the width
+    pub fn resize_handle(&self) -> Element<'_, Message> {
+        let handle = container(Space::new(Length::Fixed(6.0), Length::Fill));
+        if self.collapsed {
+            return handle.into();
+        }
+
+        let resizing = self.resizing;
+        mouse_area(handle.style(move |theme: &Theme| container::Style {
+            background: resizing
+                .then(|| Background::Color(theme.extended_palette().primary.weak.color)),
+            ..Default::default()
+        }))
+        .interaction(mouse::Interaction::ResizingHorizontally)
+        .on_press(Message::ResizeStarted)
+        .into()
+    }
+
+    fn search_badges(&self) -> Vec<Element<'static, Message>> {
+        let mut badges = self.count_badge(|counts| counts.images, None);
+        badges.extend(self.count_badge(
+            |counts| counts.untagged,
+            Some(t!("navbar.badge.untagged").to_string()),
+        ));
+        badges
+    }
+
+    /// Small count next to a label, skipped when zero or not loaded yet
+    fn count_badge(
+        &self,
+        count: fn(&LibraryCountsDTO) -> u64,
+        hint: Option<String>,
+    ) -> Vec<Element<'static, Message>> {
+        let Some(value) = self.counts.as_ref().map(count).filter(|value| *value > 0) else {
+            return Vec::new();
+        };
+
+        let label = text(value.to_string()).size(12);
+        let badge: Element<'static, Message> = match hint {
+            // The hinted badge flags something to look at, like untagged images
+            Some(hint) => tooltip(
+                container(label.style(Modern::warning_text())).padding([2, 6]),
+                text(hint),
+                tooltip::Position::Right,
+            )
+            .style(Modern::card_container())
+            .padding(8)
+            .into(),
+            None => container(label.style(Modern::secondary_text()))
+                .padding([2, 6])
+                .into(),
+        };
+        vec![badge]
+    }
 }
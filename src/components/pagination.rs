@@ -38,11 +38,7 @@ pub fn pagination<'a, M: 'a + Clone>(
         );
     }
 
-    let start_page = if current_page > 2 {
-        current_page - 2
-    } else {
-        0
-    };
+    let start_page = current_page.saturating_sub(2);
     let end_page = std::cmp::min(start_page + 5, total_pages);
 
     // First page + ellipsis
@@ -0,0 +1,38 @@
+use iced::widget::image::Handle;
+use iced::widget::{Column, Container, Image, Text};
+use iced::{Alignment, ContentFit, Element, Length};
+use iced_modern_theme::Modern;
+
+/// Side of the box the quick look image is fitted into
+const QUICK_LOOK_SIZE: f32 = 560.0;
+
+/// Mid-size look at a card, centered over the screen. It takes no input, so the
+/// cards under it keep their hover and clicks.
+pub fn quick_look<'a, M: 'a>(handle: Handle, caption: &'a str) -> Element<'a, M> {
+    let card = Container::new(
+        Column::new()
+            .spacing(10)
+            .align_x(Alignment::Center)
+            .push(
+                Image::new(handle)
+                    .content_fit(ContentFit::Contain)
+                    .width(Length::Fixed(QUICK_LOOK_SIZE))
+                    .height(Length::Fixed(QUICK_LOOK_SIZE)),
+            )
+            .push(
+                Text::new(caption)
+                    .size(14)
+                    .style(Modern::secondary_text())
+                    .wrapping(iced::widget::text::Wrapping::None),
+            ),
+    )
+    .padding(16)
+    .style(Modern::floating_container());
+
+    Container::new(card)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
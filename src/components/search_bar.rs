@@ -1,5 +1,7 @@
-use iced::widget::{Button, Container, PickList, Row, Text, TextInput};
-use iced::{Alignment, Length};
+use crate::components::accessibility::labeled;
+use iced::widget::tooltip::Position;
+use iced::widget::{Button, Column, Container, PickList, Row, Text, TextInput, text_input};
+use iced::{Alignment, Length, Padding};
 use iced::alignment::{Horizontal, Vertical};
 use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
@@ -12,6 +14,30 @@ pub struct SearchBarConfig<'a, M, T: Clone + PartialEq> {
     pub on_search: M,
     pub on_register: M,
     pub on_sort_change: Box<dyn Fn(T) -> M + 'a>,
+    /// Further filters shown before the sort order
+    pub filters: Vec<iced::Element<'a, M>>,
+    /// Icon buttons shown after the sort order
+    pub actions: Vec<SearchBarAction<M>>,
+    /// Earlier queries listed under the input, none while the list is closed
+    pub history: Option<SearchBarHistory<'a, M>>,
+}
+
+pub struct SearchBarHistory<'a, M> {
+    pub entries: Vec<String>,
+    /// Entry picked with the arrow keys
+    pub selected: Option<usize>,
+    pub on_select: Box<dyn Fn(String) -> M + 'a>,
+}
+
+pub struct SearchBarAction<M> {
+    pub icon: &'static str,
+    pub tooltip: String,
+    pub on_press: M,
+}
+
+/// Id of the query input, focused with the `/` shortcut
+pub fn input_id() -> text_input::Id {
+    text_input::Id::new("search-query")
 }
 
 pub fn search_bar<'a, M: 'a + Clone, T: 'a + Clone + PartialEq + std::fmt::Display>(
@@ -22,12 +48,18 @@ pub fn search_bar<'a, M: 'a + Clone, T: 'a + Clone + PartialEq + std::fmt::Displ
             .spacing(15)
             .push(
                 Container::new(
-                    TextInput::new(t!("search.input.description").as_ref(), config.query)
-                        .on_input(config.on_query_change)
-                        .on_submit(config.on_search.clone())
-                        .style(Modern::search_input())
-                        .padding([12, 16])
-                        .size(16),
+                    Column::new()
+                        .spacing(6)
+                        .push(
+                            TextInput::new(t!("search.input.description").as_ref(), config.query)
+                                .id(input_id())
+                                .on_input(config.on_query_change)
+                                .on_submit(config.on_search.clone())
+                                .style(Modern::search_input())
+                                .padding([12, 16])
+                                .size(16),
+                        )
+                        .push_maybe(config.history.map(history_list)),
                 )
                     .width(Length::FillPortion(5)),
             )
@@ -65,6 +97,7 @@ pub fn search_bar<'a, M: 'a + Clone, T: 'a + Clone + PartialEq + std::fmt::Displ
                     .width(Length::FillPortion(2))
                     .padding([12, 20]),
             )
+            .extend(config.filters)
             .push(
                 Container::new(
                     PickList::new(
@@ -77,10 +110,51 @@ pub fn search_bar<'a, M: 'a + Clone, T: 'a + Clone + PartialEq + std::fmt::Displ
                         .text_size(16),
                 )
                     .width(Length::FillPortion(1)),
-            ),
+            )
+            .extend(config.actions.into_iter().map(|action| {
+                labeled(
+                    Button::new(
+                        Container::new(fa_icon_solid(action.icon).size(18.0))
+                            .align_x(Horizontal::Center)
+                            .align_y(Vertical::Center),
+                    )
+                        .style(Modern::secondary_button())
+                        .on_press(action.on_press)
+                        .padding([12, 16]),
+                    action.tooltip,
+                    Position::Bottom,
+                )
+            })),
     )
         .width(Length::Fill)
         .padding(20)
         .style(Modern::card_container())
         .into()
-}
\ No newline at end of file
+}
+
+/// Earlier queries, the one picked with the arrow keys highlighted
+fn history_list<'a, M: 'a + Clone>(history: SearchBarHistory<'a, M>) -> iced::Element<'a, M> {
+    let entries = history.entries.into_iter().enumerate().map(|(index, entry)| {
+        let button = Button::new(
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("clock-rotate-left").size(12.0))
+                .push(Text::new(entry.clone()).size(14)),
+        )
+            .padding(Padding::from([6, 12]))
+            .width(Length::Fill)
+            .on_press((history.on_select)(entry));
+        if history.selected == Some(index) {
+            button.style(Modern::blue_tinted_button()).into()
+        } else {
+            button.style(Modern::plain_button()).into()
+        }
+    });
+
+    Container::new(Column::with_children(entries).spacing(2))
+        .padding(4)
+        .width(Length::Fill)
+        .style(Modern::floating_container())
+        .into()
+}
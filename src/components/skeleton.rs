@@ -0,0 +1,52 @@
+use crate::models::view_density::CardSize;
+use iced::widget::{Column, Container, Row, Space};
+use iced::{Background, Border, Element, Length, Theme};
+
+/// Grey stand-ins for the image cards while the first page loads
+pub fn skeleton_cards<'a, M: 'a>(count: usize, size: CardSize) -> Element<'a, M> {
+    let cards = (0..count).map(|_| skeleton_card(size));
+
+    Container::new(Row::with_children(cards).spacing(20).wrap())
+        .width(Length::Fill)
+        .padding(20)
+        .into()
+}
+
+fn skeleton_card<'a, M: 'a>(size: CardSize) -> Element<'a, M> {
+    let width = size.width - 10.0;
+    let content = Column::new()
+        .spacing(12)
+        .push(block(width, size.image_height))
+        .push(block(width * 0.8, 14.0))
+        .push(block(width * 0.5, 14.0));
+
+    Container::new(content)
+        .padding(5)
+        .width(Length::Fixed(size.width))
+        .height(Length::Fixed(size.height()))
+        .style(|theme: &Theme| iced::widget::container::Style {
+            background: Some(Background::Color(theme.palette().background)),
+            border: Border {
+                color: theme.extended_palette().background.strong.color,
+                width: 1.0,
+                radius: 12.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+fn block<'a, M: 'a>(width: f32, height: f32) -> Element<'a, M> {
+    Container::new(Space::new(Length::Fixed(width), Length::Fixed(height)))
+        .style(|theme: &Theme| iced::widget::container::Style {
+            background: Some(Background::Color(
+                theme.extended_palette().background.weak.color,
+            )),
+            border: Border {
+                radius: 8.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .into()
+}
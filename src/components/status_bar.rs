@@ -0,0 +1,133 @@
+use crate::dtos::library_counts_dto::LibraryCountsDTO;
+use crate::services::database_service;
+use crate::utils::get_data_dir;
+use crate::t_count;
+use chrono::{DateTime, Local};
+use iced::widget::{Row, Space, button, container, text, tooltip};
+use iced::{Alignment, Element, Length, Padding, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use rust_i18n::t;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Number of background jobs running, sent by the job subscription
+    JobsChanged(usize),
+    LastBackupLoaded(Option<DateTime<Local>>),
+    /// Version of a newer release, found by the update check
+    UpdateAvailable(String),
+}
+
+pub struct StatusBar {
+    /// Folder holding the database and the stored images, named after it
+    library_name: String,
+    library_path: String,
+    jobs: usize,
+    last_backup: Option<DateTime<Local>>,
+    update: Option<String>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        let library = get_data_dir();
+        StatusBar {
+            library_name: library
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| library.display().to_string()),
+            library_path: library.display().to_string(),
+            jobs: 0,
+            last_backup: None,
+            update: None,
+        }
+    }
+
+    pub fn load_last_backup() -> Task<Message> {
+        Task::perform(
+            async { database_service::last_backup() },
+            Message::LastBackupLoaded,
+        )
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::JobsChanged(jobs) => self.jobs = jobs,
+            Message::LastBackupLoaded(last_backup) => self.last_backup = last_backup,
+            Message::UpdateAvailable(version) => self.update = Some(version),
+        }
+    }
+
+    /// Bottom bar, the image total comes from the counts the navbar already keeps.
+    /// `on_update` is sent when the available update is clicked.
+    pub fn view<'a, T: Clone + 'a>(
+        &'a self,
+        counts: Option<&LibraryCountsDTO>,
+        on_update: T,
+    ) -> Element<'a, T> {
+        let library = tooltip(
+            item("database", self.library_name.as_str()),
+            text(self.library_path.as_str()),
+            tooltip::Position::Top,
+        )
+        .style(Modern::card_container())
+        .padding(8);
+
+        let images = match counts {
+            Some(counts) => t_count!("status_bar.images", counts.images),
+            None => t!("status_bar.counting"),
+        };
+
+        let jobs = if self.jobs == 0 {
+            item("circle-check", t!("status_bar.idle"))
+        } else {
+            item("spinner", t_count!("status_bar.jobs", self.jobs))
+        };
+
+        let last_backup = match &self.last_backup {
+            Some(time) => t!(
+                "status_bar.last_backup",
+                time = time.format("%Y-%m-%d %H:%M").to_string()
+            ),
+            None => t!("status_bar.no_backup"),
+        };
+
+        let update = self.update.as_ref().map(|version| {
+            button(item(
+                "circle-up",
+                t!("status_bar.update_available", version = version),
+            ))
+            .padding(0)
+            .style(Modern::plain_button())
+            .on_press(on_update)
+        });
+
+        container(
+            Row::new()
+                .spacing(20)
+                .align_y(Alignment::Center)
+                .push(library)
+                .push(item("images", images))
+                .push(Space::with_width(Length::Fill))
+                .push_maybe(update)
+                .push(jobs)
+                .push(item("clock-rotate-left", last_backup)),
+        )
+        .padding(Padding::from([4, 16]))
+        .width(Length::Fill)
+        .style(Modern::sheet_container())
+        .into()
+    }
+}
+
+fn item<'a, T: 'a>(icon: &'a str, label: impl text::IntoFragment<'a>) -> Element<'a, T> {
+    Row::new()
+        .spacing(6)
+        .align_y(Alignment::Center)
+        .push(
+            fa_icon_solid(icon)
+                .size(12.0)
+                .style(Modern::secondary_text()),
+        )
+        .push(text(label).size(12).style(Modern::secondary_text()))
+        .into()
+}
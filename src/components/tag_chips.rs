@@ -0,0 +1,44 @@
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::tag_color::TagColor;
+use crate::utils::capitalize_first;
+use iced::widget::{Button, Row, Text};
+use iced::{Element, Padding, Theme};
+use iced_modern_theme::Modern;
+use std::collections::HashSet;
+
+pub type ButtonStyle<'a> =
+    Box<dyn Fn(&Theme, iced::widget::button::Status) -> iced::widget::button::Style + 'a>;
+
+/// Light button style in the color of the tag
+pub fn tinted_style(color: &TagColor) -> ButtonStyle<'static> {
+    match color {
+        TagColor::Red => Box::new(Modern::red_tinted_button()),
+        TagColor::Green => Box::new(Modern::green_tinted_button()),
+        TagColor::Blue => Box::new(Modern::blue_tinted_button()),
+        TagColor::Orange => Box::new(Modern::orange_tinted_button()),
+        TagColor::Purple => Box::new(Modern::purple_tinted_button()),
+        TagColor::Pink => Box::new(Modern::pink_tinted_button()),
+        TagColor::Indigo => Box::new(Modern::indigo_tinted_button()),
+        TagColor::Teal => Box::new(Modern::teal_tinted_button()),
+        TagColor::Gray => Box::new(Modern::plain_button()),
+    }
+}
+
+/// Tags as small colored buttons sorted by name, pressing one emits the message for it
+pub fn tag_chips<'a, M: 'a + Clone>(
+    tags: &HashSet<TagDTO>,
+    size: u16,
+    on_press: impl Fn(TagDTO) -> M,
+) -> Row<'a, M> {
+    let mut tags: Vec<&TagDTO> = tags.iter().collect();
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Row::with_children(tags.into_iter().map(|tag| -> Element<'a, M> {
+        Button::new(Text::new(capitalize_first(&tag.name)).size(size))
+            .style(tinted_style(&tag.color))
+            .padding(Padding::from([2, 8]))
+            .on_press(on_press(tag.clone()))
+            .into()
+    }))
+    .spacing(4)
+}
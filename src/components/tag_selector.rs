@@ -1,18 +1,23 @@
+use crate::components::accessibility::{focus_ring, labeled};
+use crate::components::tag_chips::{ButtonStyle, tinted_style};
 use crate::dtos::tag_dto::TagDTO;
 use crate::models::tag_color::TagColor;
 use crate::services::tag_service;
 use crate::services::toast_service::{push_error, push_success};
 use crate::utils::capitalize_first;
-use iced::widget::{Button, Column, Container, Row, Space, Text, text_input};
-use iced::{Alignment, Element, Length, Padding, Task, Theme};
+use iced::widget::{Button, Column, Container, Row, Space, Text, mouse_area, text_input, tooltip};
+use iced::{Alignment, Element, Length, Padding, Task};
 use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
 use log::info;
 use std::collections::HashSet;
 
+
 #[derive(Debug, Clone)]
 pub enum Message {
     ToggleTag(TagDTO),
+    /// Right click: leaves out the images carrying the tag, or stops doing so
+    ToggleExcluded(TagDTO),
     CreateNewTagPressed,
     NewTagNameChanged(String),
     CreateNewTag(String),
@@ -23,35 +28,61 @@ pub enum Message {
 #[derive(Debug, Clone)]
 pub struct TagSelector {
     pub selected: HashSet<TagDTO>,
+    /// Tags whose images are left out, only used when `excludable`
+    pub excluded: HashSet<TagDTO>,
     pub available: HashSet<TagDTO>,
+    /// A second click on a selected tag excludes it instead of clearing it
+    excludable: bool,
     show_add_tag_button: bool,
     show_new_tag_input: bool,
     new_tag_name: String,
     colorized: bool,
+    /// Tag outlined for keyboard use, an index into `sorted_tags`
+    pub focused: Option<usize>,
 }
 
 impl TagSelector {
     pub fn new(selected: HashSet<TagDTO>, show_add_tag_button: bool, colorized: bool) -> Self {
         Self {
             selected,
+            excluded: HashSet::new(),
             available: HashSet::new(),
+            excludable: false,
             show_add_tag_button,
             show_new_tag_input: false,
             new_tag_name: String::new(),
             colorized,
+            focused: None,
         }
     }
 
+    /// Lets tags be excluded, starting with the given ones
+    pub fn excludable(mut self, excluded: HashSet<TagDTO>) -> Self {
+        self.excludable = true;
+        self.excluded = excluded;
+        self
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            // Off, selected, then excluded when the selector allows it
             Message::ToggleTag(tag) => {
-                if self.selected.contains(&tag) {
-                    self.selected.remove(&tag);
-                } else {
+                if self.selected.remove(&tag) {
+                    if self.excludable {
+                        self.excluded.insert(tag);
+                    }
+                } else if !self.excluded.remove(&tag) {
                     self.selected.insert(tag);
                 }
                 Task::none()
             }
+            Message::ToggleExcluded(tag) => {
+                if self.excludable && !self.excluded.remove(&tag) {
+                    self.selected.remove(&tag);
+                    self.excluded.insert(tag);
+                }
+                Task::none()
+            }
             Message::CreateNewTagPressed => {
                 self.show_new_tag_input = true;
                 Task::none()
@@ -64,7 +95,7 @@ impl TagSelector {
                 self.show_new_tag_input = false;
                 self.new_tag_name.clear();
                 let tag_async = tag.clone();
-                let task = Task::perform(
+                Task::perform(
                     async move {
                         // 1. salva
                         tag_service::save(&tag_async, TagColor::Blue)
@@ -73,9 +104,8 @@ impl TagSelector {
                         // 2. carrega de novo
                         tag_service::find_all().await.map_err(|e| e.to_string())
                     },
-                    |result| Message::TagCreateResult(result),
-                );
-                task
+                    Message::TagCreateResult,
+                )
             }
             Message::CancelNewTag => {
                 self.show_new_tag_input = false;
@@ -99,34 +129,45 @@ impl TagSelector {
         }
     }
 
+    /// Available tags in the order they are shown, grouped by category
+    pub fn sorted_tags(&self) -> Vec<&TagDTO> {
+        let mut tags: Vec<_> = self.available.iter().collect();
+        tags.sort_by(|a, b| {
+            tag_service::category_order(a.category.as_deref(), b.category.as_deref())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        tags
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
+        // One row of buttons per category, with its name above once some tag has one
+        let grouped = self.available.iter().any(|tag| tag.category.is_some());
+        let mut groups = Column::new().spacing(10);
         let mut tag_buttons = Row::new().spacing(8);
+        let mut category = None;
 
-        let mut elements: Vec<_> = self.available.iter().collect();
-        elements.sort_by(|a, b| a.name.cmp(&b.name));
+        for (index, tag) in self.sorted_tags().into_iter().enumerate() {
+            if grouped && (index == 0 || tag.category.as_deref() != category) {
+                if index > 0 {
+                    let finished = std::mem::replace(&mut tag_buttons, Row::new().spacing(8));
+                    groups = groups.push(finished.wrap());
+                }
+                category = tag.category.as_deref();
+                let label = match category {
+                    Some(category) => capitalize_first(category),
+                    None => t!("tag.category.none").to_string(),
+                };
+                groups = groups.push(Text::new(label).size(12).style(Modern::secondary_text()));
+            }
 
-        for tag in elements {
             let selected = self.selected.contains(tag);
+            let excluded = self.excluded.contains(tag);
             let label = capitalize_first(&tag.name);
 
-            let style: Box<
-                dyn for<'a> Fn(
-                        &'a Theme,
-                        iced::widget::button::Status,
-                    ) -> iced::widget::button::Style
-                    + '_,
-            > = if !selected && self.colorized {
-                match tag.color {
-                    TagColor::Red => Box::new(Modern::red_tinted_button()),
-                    TagColor::Green => Box::new(Modern::green_tinted_button()),
-                    TagColor::Blue => Box::new(Modern::blue_tinted_button()),
-                    TagColor::Orange => Box::new(Modern::orange_tinted_button()),
-                    TagColor::Purple => Box::new(Modern::purple_tinted_button()),
-                    TagColor::Pink => Box::new(Modern::pink_tinted_button()),
-                    TagColor::Indigo => Box::new(Modern::indigo_tinted_button()),
-                    TagColor::Teal => Box::new(Modern::teal_tinted_button()),
-                    TagColor::Gray => Box::new(Modern::plain_button()),
-                }
+            let style: ButtonStyle<'_> = if excluded {
+                Box::new(Modern::danger_button())
+            } else if !selected && self.colorized {
+                tinted_style(&tag.color)
             } else if selected && self.colorized {
                 match tag.color {
                     TagColor::Red => Box::new(Modern::danger_button()),
@@ -147,17 +188,25 @@ impl TagSelector {
                 }
             };
 
-            let button_content = Row::new()
-                .spacing(6)
-                .align_y(Alignment::Center)
-                .push(Text::new(label).size(14));
+            let mut button_content = Row::new().spacing(6).align_y(Alignment::Center);
+            if excluded {
+                button_content = button_content.push(fa_icon_solid("ban").size(12.0));
+            }
+            button_content = button_content.push(Text::new(label).size(14));
 
             let button = Button::new(button_content)
                 .style(style)
                 .padding(Padding::from([8, 16]))
                 .on_press(Message::ToggleTag(tag.clone()));
+            let button: Element<'_, Message> = if self.excludable {
+                mouse_area(button)
+                    .on_right_press(Message::ToggleExcluded(tag.clone()))
+                    .into()
+            } else {
+                button.into()
+            };
 
-            tag_buttons = tag_buttons.push(button);
+            tag_buttons = tag_buttons.push(focus_ring(button, self.focused == Some(index), 8.0));
         }
 
         // Add tag section
@@ -176,7 +225,7 @@ impl TagSelector {
                                 .size(14)
                                 .width(Length::FillPortion(7)),
                         )
-                        .push(
+                        .push(labeled(
                             Button::new(
                                 Container::new(fa_icon_solid("check").size(14.0))
                                     .align_x(Alignment::Center)
@@ -186,8 +235,10 @@ impl TagSelector {
                             .on_press(Message::CreateNewTag(self.new_tag_name.clone()))
                             .padding(Padding::from([8, 12]))
                             .width(Length::FillPortion(1)),
-                        )
-                        .push(
+                            t!("accessibility.confirm"),
+                            tooltip::Position::Top,
+                        ))
+                        .push(labeled(
                             Button::new(
                                 Container::new(fa_icon_solid("xmark").size(14.0))
                                     .align_x(Alignment::Center)
@@ -197,7 +248,9 @@ impl TagSelector {
                             .on_press(Message::CancelNewTag)
                             .padding(Padding::from([8, 12]))
                             .width(Length::FillPortion(1)),
-                        ),
+                            t!("accessibility.cancel"),
+                            tooltip::Position::Top,
+                        )),
                 )
                 .padding(Padding::from([5, 0]))
             } else {
@@ -222,9 +275,7 @@ impl TagSelector {
         // Main content
         let main_content = Column::new()
             .spacing(15)
-            .push(Container::new(
-                Column::new().push(Container::new(tag_buttons.wrap())),
-            ))
+            .push(Container::new(groups.push(tag_buttons.wrap())))
             .push(add_tag_section);
 
         Container::new(main_content).into()
@@ -1,6 +1,7 @@
+use crate::components::accessibility::labeled;
 use crate::models::toast::{Toast, ToastKind};
 use iced::alignment::Vertical;
-use iced::widget::{button, Container, Row, Space, Text};
+use iced::widget::{button, tooltip, Container, Row, Space, Text};
 use iced::{alignment, Alignment, Background, Border, Color, Element, Length, Padding, Shadow, Vector};
 use iced_font_awesome::fa_icon_solid;
 
@@ -116,7 +117,7 @@ impl ToastView {
                     .align_y(Alignment::Center)
                     .push(status_icon)
                     .push(message_text)
-                    .push(close_button)
+                    .push(labeled(close_button, t!("accessibility.dismiss"), tooltip::Position::Top))
                     .width(Length::Fill),
             );
 
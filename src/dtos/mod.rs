@@ -1,2 +0,0 @@
-pub mod image_dto;
-pub mod tag_dto;
\ No newline at end of file
@@ -1,62 +1,144 @@
 #[macro_use]
 extern crate rust_i18n;
 
+mod animation;
 mod components;
-mod config;
-mod dtos;
-mod models;
 mod screen;
 mod services;
-mod utils;
+
+use organizer_core::{config, dtos, models, t_count, utils};
 
 use crate::components::navbar::{NavButton, Navbar};
+use crate::components::status_bar::StatusBar;
 use crate::components::toast_view::ToastView;
-use crate::components::{navbar, toast_view};
-use crate::config::get_settings;
+use crate::components::accessibility::labeled;
+use crate::components::{accessibility, navbar, status_bar, toast_view};
+use crate::config::{
+    get_settings, set_current_page, set_excluded_tags, set_refinements, set_scroll_offset,
+    set_search_query, set_selected_tags, set_tag_match,
+};
 use crate::dtos::image_dto::ImageDTO;
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::filter::TagMatchMode;
+use crate::models::maintenance::{MaintenanceTask, TaskRun};
+use crate::models::saved_search::SavedSearch;
 use crate::models::toast::Toast;
 use crate::screen::update::Update;
-use crate::screen::{ManageTags, Preferences, manage_tags, preferences, search};
+use crate::screen::{
+    Activity, Inbox, ManageTags, Preferences, Quarantine, Rules, Timeline, Trash, activity,
+    inbox, manage_tags, preferences, quarantine, rules, search, timeline, trash,
+};
 use crate::screen::{Register, Screen, Search};
 use crate::screen::{register, update};
-use crate::services::{clipboard_service, database_service, logger_service, toast_service};
+use crate::services::change_service::DataChange;
+use crate::services::deep_link_service::DeepLink;
+use crate::services::{
+    change_service, clipboard_service, database_service, deep_link_service, import_service,
+    job_service, library_location_service, logger_service, maintenance_service,
+    tag_service, toast_service, update_service, wallpaper_service,
+};
 use iced::futures::SinkExt;
 use iced::keyboard;
-use iced::widget::{Column, Row, container, stack};
-use iced::{Alignment, Element, Event, Length, Subscription, Task, Theme, time};
-use iced::{event, window};
+use iced::widget::{
+    Column, Row, button, container, focus_next, focus_previous, scrollable, stack, text, tooltip,
+};
+use iced::{Alignment, Element, Event, Length, Padding, Subscription, Task, Theme, time};
+use iced::{event, mouse, window};
+use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
 use image::{DynamicImage, ImageFormat};
-use log::info;
+use log::{error, info, warn};
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
 
 i18n!("locales", fallback = "en");
 
+/// How often the maintenance tasks are checked for being due
+const MAINTENANCE_CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// What Escape can dismiss on a screen, innermost first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dismissal {
+    /// A preview, dialog or popup over the screen
+    Modal,
+    /// An opened folder, stack or related images in place of the results
+    Folder,
+    /// A query, tags or a language narrowing the results
+    Filters,
+}
+
+/// Order Escape goes through. With nothing left to dismiss it leaves for the search.
+const ESCAPE_STACK: [Dismissal; 3] = [Dismissal::Modal, Dismissal::Folder, Dismissal::Filters];
+
 #[derive(Debug, Clone)]
 pub enum Message {
     Navbar(navbar::Message),
+    StatusBar(status_bar::Message),
     Search(search::Message),
     Register(register::Message),
     Update(update::Message),
     Preferences(preferences::Message),
-    SettingsUpdated,
     Toast(toast_view::Message),
     Tick(Instant),
     HandleToast(Toast),
     EscapePressed,
+    /// Any other key, for the keyboard navigation of the current screen
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
     PasteShortcut,
+    RandomShortcut,
     Navigate(NavigationTarget),
     NoOps,
     ManageTags(manage_tags::Message),
+    Activity(activity::Message),
+    Quarantine(quarantine::Message),
+    Trash(trash::Message),
+    Inbox(inbox::Message),
+    Rules(rules::Message),
+    Timeline(timeline::Message),
+    RotateWallpaper,
+    /// Runs the maintenance tasks that are due
+    RunMaintenance,
+    MaintenanceFinished(Vec<(MaintenanceTask, TaskRun)>),
+    /// Opens Search showing only the images with the tag
+    FilterByTag(TagDTO),
+    /// An `organizer://` link or file opened at startup or sent by another launch
+    DeepLink(DeepLink),
+    /// Opens Search with these filters, replacing the current ones
+    OpenSearch {
+        query: String,
+        /// Earlier queries the search is refined by
+        refinements: Vec<String>,
+        tags: HashSet<TagDTO>,
+        excluded: HashSet<TagDTO>,
+        tag_match: TagMatchMode,
+    },
+    /// First frame drawn, starts the deferred startup loading
+    WindowShown,
+    NewTab,
+    SwitchTab(usize),
+    CloseTab(usize),
+    DataChanged(DataChange),
 }
 
 #[derive(Debug, Clone)]
 pub enum NavigationTarget {
+    /// A new search in the active tab, built from the session filters
     Search,
+    /// Back to the active tab as it was left
+    SearchTab,
     Register(Option<DynamicImage>, Option<ImageFormat>),
-    Update(ImageDTO),
+    /// Boxed, the entry would make every other target as large
+    Update(Box<ImageDTO>),
     Preferences,
     ManageTags,
+    Activity,
+    Quarantine,
+    Trash,
+    Inbox,
+    /// Import rules, opened from Preferences
+    Rules,
+    Timeline,
     Workspace,
 }
 
@@ -64,13 +146,25 @@ pub struct Organizer {
     theme: Theme,
     screen: Screen,
     navbar: Navbar,
+    status_bar: StatusBar,
     toasts: Vec<ToastView>,
+    /// Loading held back until the window is on screen, so it opens without waiting on the database
+    startup_task: Option<Task<Message>>,
+    /// Launch time, cleared once the first page of results is shown
+    launched_at: Option<Instant>,
+    /// Open search tabs. The active one is None while it is on screen, the others
+    /// keep their whole state until switched back to
+    search_tabs: Vec<Option<Box<Search>>>,
+    active_tab: usize,
 }
 
 impl Organizer {
-    pub fn new() -> (Self, Task<Message>) {
+    pub fn new(links: Vec<DeepLink>, launched_at: Instant) -> (Self, Task<Message>) {
         let (search, search_task) = Search::new();
-        let task = search_task.map(Message::Search);
+        let mut task = search_task.map(Message::Search);
+        for link in links {
+            task = task.chain(Task::done(Message::DeepLink(link)));
+        }
         let settings = get_settings();
         let theme = Self::get_theme_from_settings(&settings);
 
@@ -79,12 +173,85 @@ impl Organizer {
                 theme,
                 screen: Screen::Search(search),
                 navbar: Navbar::new(),
+                status_bar: StatusBar::new(),
                 toasts: vec![],
+                search_tabs: vec![None],
+                active_tab: 0,
+                startup_task: Some(Task::batch([
+                    task,
+                    Navbar::load_counts().map(Message::Navbar),
+                    StatusBar::load_last_backup().map(Message::StatusBar),
+                    Self::resume_imports(),
+                    Task::done(Message::RunMaintenance),
+                    Self::check_for_update(),
+                ])),
+                launched_at: Some(launched_at),
+            },
+            Task::none(),
+        )
+    }
+
+    /// Looks for a newer release, when the update check is turned on
+    fn check_for_update() -> Task<Message> {
+        let url = {
+            let settings = get_settings();
+            if settings.config.update_check != Some(true) {
+                return Task::none();
+            }
+            update_service::update_url(&settings.config)
+        };
+        Task::perform(
+            update_service::check(url, env!("CARGO_PKG_VERSION")),
+            |result| match result {
+                Ok(Some(release)) => {
+                    toast_service::push_success(t!(
+                        "message.update.available",
+                        version = release.version.as_str()
+                    ));
+                    Message::StatusBar(status_bar::Message::UpdateAvailable(release.version))
+                }
+                Ok(None) => Message::NoOps,
+                Err(err) => {
+                    warn!("Failed to check for updates: {}", err);
+                    Message::NoOps
+                }
             },
-            task,
         )
     }
 
+    /// Finishes the folder imports interrupted when the app last closed
+    fn resume_imports() -> Task<Message> {
+        Task::perform(import_service::resume_pending(), |results| {
+            for result in results {
+                match result {
+                    Ok(import) => {
+                        toast_service::push_success(t_count!(
+                            "message.register.folder.resumed",
+                            import.added
+                        ));
+                        if import.quarantined > 0 {
+                            toast_service::push_error(t_count!(
+                                "message.register.folder.quarantined",
+                                import.quarantined
+                            ));
+                        }
+                        if !import.filtered.is_empty() {
+                            toast_service::push_error(t_count!(
+                                "message.register.folder.filtered",
+                                import.filtered.len()
+                            ));
+                        }
+                    }
+                    Err(err) => toast_service::push_error(t!(
+                        "message.register.folder.resume_error",
+                        err = err
+                    )),
+                }
+            }
+            Message::NoOps
+        })
+    }
+
     pub fn title(&self) -> String {
         t!("app.title").to_string()
     }
@@ -98,6 +265,9 @@ impl Organizer {
     }
 
     fn get_theme_from_settings(settings: &config::Settings) -> Theme {
+        if settings.config.high_contrast.unwrap_or(false) {
+            return accessibility::high_contrast_theme(settings.config.theme != "Light");
+        }
         match settings.config.theme.as_str() {
             "Dark" => Modern::dark_theme(),
             "Light" => Modern::light_theme(),
@@ -105,49 +275,262 @@ impl Organizer {
         }
     }
 
+    /// Shows the screen, keeping the search it replaces in the active tab
+    fn show(&mut self, screen: Screen) {
+        if let Screen::Search(search) = std::mem::replace(&mut self.screen, screen)
+            && let Some(tab) = self.search_tabs.get_mut(self.active_tab)
+        {
+            *tab = Some(Box::new(search));
+        }
+    }
+
+    /// Puts the search of the active tab back on screen, or a new one if it has none
+    fn restore_active_tab(&mut self) -> Task<Message> {
+        self.navbar.selected = NavButton::Search;
+        match self.search_tabs.get_mut(self.active_tab).and_then(Option::take) {
+            Some(mut search) => {
+                search.restore_ui_state();
+                self.screen = Screen::Search(*search);
+                // Other tabs don't hear about changes, catch up on what they missed
+                Task::done(Message::Search(search::Message::DataChanged(
+                    DataChange::Images,
+                )))
+            }
+            None => {
+                let (search, task) = Search::new();
+                self.screen = Screen::Search(search);
+                task.map(Message::Search)
+            }
+        }
+    }
+
+    fn tab_bar(&self) -> Element<'_, Message> {
+        let mut tabs = Row::new().spacing(6).align_y(Alignment::Center);
+        for (index, tab) in self.search_tabs.iter().enumerate() {
+            let title = match (tab, &self.screen) {
+                (Some(search), _) => search.tab_title(),
+                (None, Screen::Search(search)) => search.tab_title(),
+                (None, _) => t!("search.tabs.all").to_string(),
+            };
+
+            let mut content = Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(text(title).size(14).wrapping(text::Wrapping::None));
+            if self.search_tabs.len() > 1 {
+                content = content.push(labeled(
+                    button(fa_icon_solid("xmark").size(12.0))
+                        .padding(2)
+                        .style(Modern::system_button())
+                        .on_press(Message::CloseTab(index)),
+                    t!("search.tabs.close"),
+                    tooltip::Position::Bottom,
+                ));
+            }
+
+            let tab_button = button(content)
+                .padding(Padding::from([6, 12]))
+                .on_press(Message::SwitchTab(index));
+            tabs = tabs.push(if index == self.active_tab {
+                tab_button.style(Modern::primary_button())
+            } else {
+                tab_button.style(Modern::secondary_button())
+            });
+        }
+
+        tabs = tabs.push(labeled(
+            button(fa_icon_solid("plus").size(14.0))
+                .padding(Padding::from([6, 10]))
+                .style(Modern::secondary_button())
+                .on_press(Message::NewTab),
+            t!("search.tabs.new"),
+            tooltip::Position::Bottom,
+        ));
+
+        container(scrollable(tabs).direction(scrollable::Direction::Horizontal(
+            scrollable::Scrollbar::new().width(4).scroller_width(4),
+        )))
+        .padding(Padding::from([8, 20]).bottom(0))
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Opens the saved search, looking its tags and excluded tags up by their
+    /// lowercase names
+    fn open_search_by_tag_names(search: SavedSearch) -> Task<Message> {
+        let SavedSearch {
+            query,
+            refinements,
+            tags,
+            excluded_tags: excluded,
+            tag_match,
+            ..
+        } = search;
+        Task::perform(
+            async move {
+                let known = tag_service::find_all().await.unwrap_or_else(|err| {
+                    error!("Failed to load tags for search: {}", err);
+                    HashSet::new()
+                });
+                let (selected, excluded): (HashSet<TagDTO>, HashSet<TagDTO>) = known
+                    .into_iter()
+                    .filter(|tag| {
+                        let name = tag.name.to_lowercase();
+                        tags.contains(&name) || excluded.contains(&name)
+                    })
+                    .partition(|tag| tags.contains(&tag.name.to_lowercase()));
+                (query, refinements, selected, excluded)
+            },
+            move |(query, refinements, tags, excluded)| Message::OpenSearch {
+                query,
+                refinements,
+                tags,
+                excluded,
+                tag_match,
+            },
+        )
+    }
+
     // Method to navigate to different screens
     fn navigate_to(&mut self, target: NavigationTarget) -> Task<Message> {
         match target {
             NavigationTarget::Search => {
                 let (search, task) = Search::new();
                 self.screen = Screen::Search(search);
+                if let Some(tab) = self.search_tabs.get_mut(self.active_tab) {
+                    *tab = None;
+                }
                 self.navbar.selected = NavButton::Search;
                 task.map(Message::Search)
             }
+            NavigationTarget::SearchTab => {
+                if matches!(self.screen, Screen::Search(_)) {
+                    return Task::none();
+                }
+                self.restore_active_tab()
+            }
             NavigationTarget::Register(image, format) => {
                 let (register, task) = Register::new(image, format);
-                self.screen = Screen::Register(register);
+                self.show(Screen::Register(register));
                 task.map(Message::Register)
             }
             NavigationTarget::Update(dto) => {
-                let (update, task) = Update::new(dto);
-                self.screen = Screen::Update(update);
+                let (update, task) = Update::new(*dto);
+                self.show(Screen::Update(update));
                 task.map(Message::Update)
             }
             NavigationTarget::Preferences => {
                 let (preferences, task) = Preferences::new();
-                self.screen = Screen::Preferences(preferences);
+                self.show(Screen::Preferences(preferences));
                 self.navbar.selected = NavButton::Preferences;
                 task.map(Message::Preferences)
             }
             NavigationTarget::ManageTags => {
                 let (manage_tags, task) = ManageTags::new();
-                self.screen = Screen::ManageTags(manage_tags);
+                self.show(Screen::ManageTags(manage_tags));
                 self.navbar.selected = NavButton::ManageTags;
                 task.map(Message::ManageTags)
             }
+            NavigationTarget::Activity => {
+                let (activity, task) = Activity::new();
+                self.show(Screen::Activity(activity));
+                self.navbar.selected = NavButton::Activity;
+                task.map(Message::Activity)
+            }
+            NavigationTarget::Quarantine => {
+                let (quarantine, task) = Quarantine::new();
+                self.show(Screen::Quarantine(quarantine));
+                self.navbar.selected = NavButton::Quarantine;
+                task.map(Message::Quarantine)
+            }
+            NavigationTarget::Trash => {
+                let (trash, task) = Trash::new();
+                self.show(Screen::Trash(trash));
+                self.navbar.selected = NavButton::Trash;
+                task.map(Message::Trash)
+            }
+            NavigationTarget::Rules => {
+                let (rules, task) = Rules::new();
+                self.show(Screen::Rules(rules));
+                self.navbar.selected = NavButton::Preferences;
+                task.map(Message::Rules)
+            }
+            NavigationTarget::Inbox => {
+                let (inbox, task) = Inbox::new();
+                self.show(Screen::Inbox(inbox));
+                self.navbar.selected = NavButton::Inbox;
+                task.map(Message::Inbox)
+            }
+            NavigationTarget::Timeline => {
+                let (timeline, task) = Timeline::new();
+                self.show(Screen::Timeline(timeline));
+                self.navbar.selected = NavButton::Timeline;
+                task.map(Message::Timeline)
+            }
             NavigationTarget::Workspace => todo!(),
         }
     }
 
     // Method to handle escape key
     fn handle_escape(&mut self) -> Task<Message> {
-        match &mut self.screen {
-            Screen::Search(_) => {
-                let msg = Message::Search(search::Message::ClosePreview);
-                Task::perform(async move { msg }, |m| m)
+        if let Some(message) = ESCAPE_STACK
+            .iter()
+            .find_map(|dismissal| self.dismiss(*dismissal))
+        {
+            return Task::done(message);
+        }
+        match self.screen {
+            Screen::Search(_) => Task::none(),
+            _ => self.navigate_to(NavigationTarget::SearchTab),
+        }
+    }
+
+    /// Message dismissing that part of the current screen, none when it isn't shown
+    fn dismiss(&self, dismissal: Dismissal) -> Option<Message> {
+        match (dismissal, &self.screen) {
+            (Dismissal::Modal, Screen::Search(search)) => {
+                search.modal_close().map(Message::Search)
+            }
+            (Dismissal::Modal, Screen::Timeline(timeline)) if timeline.is_previewing() => {
+                Some(Message::Timeline(timeline::Message::ClosePreview))
+            }
+            (Dismissal::Modal, Screen::Inbox(inbox)) if inbox.is_tagging() => {
+                Some(Message::Inbox(inbox::Message::CancelTagging))
+            }
+            (Dismissal::Folder, Screen::Search(search)) if search.is_in_folder() => {
+                Some(Message::Search(search::Message::CloseFolder))
             }
-            _ => self.navigate_to(NavigationTarget::Search),
+            (Dismissal::Filters, Screen::Search(search)) if search.has_filters() => {
+                Some(Message::Search(search::Message::ClearFilters))
+            }
+            _ => None,
+        }
+    }
+
+    fn handle_key(&mut self, key: keyboard::Key, modifiers: keyboard::Modifiers) -> Task<Message> {
+        use keyboard::key::Named;
+
+        match (&self.screen, key) {
+            (Screen::Search(_), key) => {
+                Task::done(Message::Search(search::Message::KeyPressed(key, modifiers)))
+            }
+            (Screen::Timeline(timeline), keyboard::Key::Named(Named::ArrowLeft))
+                if timeline.is_previewing() =>
+            {
+                Task::done(Message::Timeline(timeline::Message::PreviousImage))
+            }
+            (Screen::Timeline(timeline), keyboard::Key::Named(Named::ArrowRight))
+                if timeline.is_previewing() =>
+            {
+                Task::done(Message::Timeline(timeline::Message::NextImage))
+            }
+            (Screen::Inbox(_), key @ keyboard::Key::Character(_)) if modifiers.is_empty() => {
+                Task::done(Message::Inbox(inbox::Message::KeyPressed(key)))
+            }
+            // Other screens are forms, Tab walks through their inputs
+            (_, keyboard::Key::Named(Named::Tab)) if modifiers.shift() => focus_previous(),
+            (_, keyboard::Key::Named(Named::Tab)) => focus_next(),
+            _ => Task::none(),
         }
     }
 
@@ -171,8 +554,10 @@ impl Organizer {
                 }
                 Screen::Register(register) => {
                     info!("Pasting image to register (format: {:?})", format);
-                    register.update(register::Message::ImagePasted(image, format));
-                    Task::none()
+                    match register.update(register::Message::ImagePasted(image, format)) {
+                        register::Action::Run(task) => task.map(Message::Register),
+                        _ => Task::none(),
+                    }
                 }
                 _ => Task::none(),
             }
@@ -191,30 +576,39 @@ impl Organizer {
                 Task::none()
             }
 
-            Message::SettingsUpdated => {
-                let settings = get_settings();
-                self.theme = Self::get_theme_from_settings(&settings);
-                self.navbar.update(navbar::Message::NoOps);
-                self.navigate_to(NavigationTarget::Preferences)
-            }
-
             Message::EscapePressed => self.handle_escape(),
+            Message::KeyPressed(key, modifiers) => self.handle_key(key, modifiers),
 
             Message::PasteShortcut => self.handle_paste(),
 
+            Message::RandomShortcut => match &self.screen {
+                Screen::Search(_) => Task::done(Message::Search(search::Message::SurpriseMe)),
+                _ => Task::none(),
+            },
+
             Message::Search(message) => {
                 if let Screen::Search(search) = &mut self.screen {
                     let action = search.update(message);
 
+                    if !search.is_loading()
+                        && let Some(launched_at) = self.launched_at.take()
+                    {
+                        info!("Interactive after {:?}", launched_at.elapsed());
+                    }
+
                     match action {
                         search::Action::None => Task::none(),
                         search::Action::Run(task) => task.map(Message::Search),
                         search::Action::NavigateToUpdate(dto) => {
-                            self.navigate_to(NavigationTarget::Update(dto))
+                            self.navigate_to(NavigationTarget::Update(Box::new(dto)))
                         }
                         search::Action::NavigatorToRegister(dynamic_image, format) => {
                             self.navigate_to(NavigationTarget::Register(dynamic_image, format))
                         }
+                        search::Action::FilterByTag(tag) => Task::done(Message::FilterByTag(tag)),
+                        search::Action::PinSearch(saved) => {
+                            Task::done(Message::Navbar(navbar::Message::PinSearch(saved)))
+                        }
                     }
                 } else {
                     Task::none()
@@ -228,7 +622,12 @@ impl Organizer {
                     match action {
                         register::Action::None => Task::none(),
                         register::Action::Run(task) => task.map(Message::Register),
-                        register::Action::GoToSearch => self.navigate_to(NavigationTarget::Search),
+                        register::Action::GoToSearch => self.navigate_to(NavigationTarget::SearchTab),
+                        register::Action::OpenImage(image_id) => self
+                            .navigate_to(NavigationTarget::SearchTab)
+                            .chain(Task::done(Message::Search(search::Message::OpenLinkedImage(
+                                image_id,
+                            )))),
                     }
                 } else {
                     Task::none()
@@ -242,7 +641,8 @@ impl Organizer {
                     match action {
                         update::Action::None => Task::none(),
                         update::Action::Run(task) => task.map(Message::Update),
-                        update::Action::GoToSearch => self.navigate_to(NavigationTarget::Search),
+                        update::Action::GoToSearch => self.navigate_to(NavigationTarget::SearchTab),
+                        update::Action::FilterByTag(tag) => Task::done(Message::FilterByTag(tag)),
                     }
                 } else {
                     Task::none()
@@ -255,15 +655,18 @@ impl Organizer {
 
                     match action {
                         preferences::Action::None => Task::none(),
-                        preferences::Action::UpdateUI() => {
-                            Task::perform(async { Message::SettingsUpdated }, |m| m)
-                        }
+                        preferences::Action::Run(task) => task.map(Message::Preferences),
+                        preferences::Action::OpenRules => self.navigate_to(NavigationTarget::Rules),
                     }
                 } else {
                     Task::none()
                 }
             }
 
+            Message::StatusBar(message) => {
+                self.status_bar.update(message);
+                Task::none()
+            }
             Message::Navbar(navbar_msg) => {
                 info!("Navbar message: {:?}", navbar_msg);
                 let action = self.navbar.update(navbar_msg);
@@ -272,13 +675,21 @@ impl Organizer {
                     navbar::Action::Run(task) => task.map(Message::Navbar),
                     navbar::Action::Navigate(button) => {
                         let target = match button {
-                            NavButton::Home | NavButton::Search => NavigationTarget::Search,
-                            NavButton::Workspace => NavigationTarget::Search,
+                            NavButton::Home | NavButton::Search => NavigationTarget::SearchTab,
+                            NavButton::Workspace => NavigationTarget::SearchTab,
                             NavButton::Preferences => NavigationTarget::Preferences,
                             NavButton::ManageTags => NavigationTarget::ManageTags,
+                            NavButton::Activity => NavigationTarget::Activity,
+                            NavButton::Quarantine => NavigationTarget::Quarantine,
+                            NavButton::Trash => NavigationTarget::Trash,
+                            NavButton::Inbox => NavigationTarget::Inbox,
+                            NavButton::Timeline => NavigationTarget::Timeline,
                         };
                         self.navigate_to(target)
                     }
+                    navbar::Action::OpenSearch(saved) => {
+                        Self::open_search_by_tag_names(saved)
+                    }
                     navbar::Action::None => Task::none(),
                 }
             }
@@ -295,6 +706,189 @@ impl Organizer {
                 Task::none()
             }
 
+            Message::Timeline(message) => {
+                if let Screen::Timeline(timeline) = &mut self.screen {
+                    match timeline.update(message) {
+                        timeline::Action::None => Task::none(),
+                        timeline::Action::Run(task) => task.map(Message::Timeline),
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            Message::FilterByTag(tag) => {
+                info!("Filtering search by tag {}", tag.name);
+                Task::done(Message::OpenSearch {
+                    query: String::new(),
+                    refinements: Vec::new(),
+                    tags: HashSet::from([tag]),
+                    excluded: HashSet::new(),
+                    tag_match: TagMatchMode::All,
+                })
+            }
+            Message::OpenSearch {
+                query,
+                refinements,
+                tags,
+                excluded,
+                tag_match,
+            } => {
+                set_search_query(query);
+                set_refinements(refinements);
+                set_selected_tags(tags);
+                set_excluded_tags(excluded);
+                set_tag_match(tag_match);
+                set_current_page(0);
+                set_scroll_offset(0.0);
+                self.navigate_to(NavigationTarget::Search)
+            }
+            Message::DataChanged(DataChange::Settings) => {
+                self.theme = Self::get_theme_from_settings(&get_settings());
+                self.navbar.update(navbar::Message::SettingsChanged);
+                // Parked tabs take the new settings now and reload once switched back to
+                for tab in self.search_tabs.iter_mut().flatten() {
+                    let _ = tab.update(search::Message::DataChanged(DataChange::Settings));
+                }
+                match &self.screen {
+                    Screen::Search(_) => Task::done(Message::Search(search::Message::DataChanged(
+                        DataChange::Settings,
+                    ))),
+                    _ => Task::none(),
+                }
+            }
+            Message::DataChanged(change) => {
+                let counts = Navbar::load_counts().map(Message::Navbar);
+                let screen = match &self.screen {
+                    Screen::Search(_) => {
+                        Task::done(Message::Search(search::Message::DataChanged(change)))
+                    }
+                    _ => Task::none(),
+                };
+                Task::batch([counts, screen])
+            }
+            Message::NewTab => {
+                set_search_query(String::new());
+                set_refinements(Vec::new());
+                set_selected_tags(HashSet::new());
+                set_excluded_tags(HashSet::new());
+                set_tag_match(TagMatchMode::All);
+                set_current_page(0);
+                set_scroll_offset(0.0);
+                let (search, task) = Search::new();
+                self.show(Screen::Search(search));
+                self.search_tabs.push(None);
+                self.active_tab = self.search_tabs.len() - 1;
+                self.navbar.selected = NavButton::Search;
+                task.map(Message::Search)
+            }
+            Message::SwitchTab(index) => {
+                if index >= self.search_tabs.len() {
+                    return Task::none();
+                }
+                if index == self.active_tab {
+                    return self.navigate_to(NavigationTarget::SearchTab);
+                }
+                // Park the current search in its tab and put the other one in its place
+                if let Screen::Search(current) = &mut self.screen
+                    && let Some(next) = self.search_tabs[index].take()
+                {
+                    let previous = std::mem::replace(current, *next);
+                    self.search_tabs[self.active_tab] = Some(Box::new(previous));
+                    self.active_tab = index;
+                    current.restore_ui_state();
+                    return Task::done(Message::Search(search::Message::DataChanged(
+                        DataChange::Images,
+                    )));
+                }
+                self.active_tab = index;
+                self.restore_active_tab()
+            }
+            Message::CloseTab(index) => {
+                if index >= self.search_tabs.len() || self.search_tabs.len() == 1 {
+                    return Task::none();
+                }
+                if index != self.active_tab {
+                    self.search_tabs.remove(index);
+                    if index < self.active_tab {
+                        self.active_tab -= 1;
+                    }
+                    return Task::none();
+                }
+                self.search_tabs.remove(index);
+                self.active_tab = index.min(self.search_tabs.len() - 1);
+                self.restore_active_tab()
+            }
+            Message::WindowShown => {
+                if let Some(launched_at) = self.launched_at {
+                    info!("Window shown after {:?}", launched_at.elapsed());
+                }
+                self.startup_task.take().unwrap_or_else(Task::none)
+            }
+            Message::DeepLink(link) => {
+                info!("Opening link {:?}", link);
+                let focus = window::get_latest().and_then(window::gain_focus);
+                let task = match link {
+                    DeepLink::Search { query, tags } => {
+                        Self::open_search_by_tag_names(SavedSearch {
+                            query,
+                            tags,
+                            ..Default::default()
+                        })
+                    }
+                    DeepLink::Image(image_id) => self
+                        .navigate_to(NavigationTarget::SearchTab)
+                        .chain(Task::done(Message::Search(search::Message::OpenLinkedImage(
+                            image_id,
+                        )))),
+                    // Register holds one image at a time, so the last file wins
+                    DeepLink::Import(path) => self
+                        .navigate_to(NavigationTarget::Register(None, None))
+                        .chain(Task::done(Message::Register(register::Message::ImageChosen(
+                            path,
+                        )))),
+                    DeepLink::Focus => Task::none(),
+                };
+                Task::batch([focus, task])
+            }
+            Message::RunMaintenance => Task::perform(
+                maintenance_service::run_due(),
+                Message::MaintenanceFinished,
+            ),
+            Message::MaintenanceFinished(runs) => {
+                for (task, run) in &runs {
+                    if let Some(err) = &run.error {
+                        toast_service::push_error(t!(
+                            "message.maintenance.error",
+                            task = task.to_string(),
+                            err = err
+                        ));
+                    } else if *task == MaintenanceTask::VerifyIntegrity && run.count > 0 {
+                        toast_service::push_error(t_count!(
+                            "message.maintenance.integrity_issues",
+                            run.count
+                        ));
+                    }
+                }
+                if runs.iter().any(|(task, _)| *task == MaintenanceTask::Backup) {
+                    StatusBar::load_last_backup().map(Message::StatusBar)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::RotateWallpaper => {
+                let Some(tag) = get_settings().config.wallpaper_tag.clone() else {
+                    return Task::none();
+                };
+                Task::perform(wallpaper_service::rotate_wallpaper(tag), |result| {
+                    match result {
+                        Ok(Some(path)) => info!("Wallpaper rotated to {}", path),
+                        Ok(None) => warn!("No image found for the wallpaper rotation tag"),
+                        Err(err) => error!("Failed to rotate wallpaper: {}", err),
+                    }
+                    Message::NoOps
+                })
+            }
+
             Message::NoOps => Task::none(),
             Message::ManageTags(message) => {
                 if let Screen::ManageTags(manage_tags) = &mut self.screen {
@@ -308,6 +902,56 @@ impl Organizer {
                     Task::none()
                 }
             }
+            Message::Activity(message) => {
+                if let Screen::Activity(activity) = &mut self.screen {
+                    match activity.update(message) {
+                        activity::Action::None => Task::none(),
+                        activity::Action::Run(task) => task.map(Message::Activity),
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Quarantine(message) => {
+                if let Screen::Quarantine(quarantine) = &mut self.screen {
+                    match quarantine.update(message) {
+                        quarantine::Action::None => Task::none(),
+                        quarantine::Action::Run(task) => task.map(Message::Quarantine),
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Trash(message) => {
+                if let Screen::Trash(trash) = &mut self.screen {
+                    match trash.update(message) {
+                        trash::Action::None => Task::none(),
+                        trash::Action::Run(task) => task.map(Message::Trash),
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Inbox(message) => {
+                if let Screen::Inbox(inbox) = &mut self.screen {
+                    match inbox.update(message) {
+                        inbox::Action::None => Task::none(),
+                        inbox::Action::Run(task) => task.map(Message::Inbox),
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Rules(message) => {
+                if let Screen::Rules(rules) = &mut self.screen {
+                    match rules.update(message) {
+                        rules::Action::None => Task::none(),
+                        rules::Action::Close => self.navigate_to(NavigationTarget::Preferences),
+                    }
+                } else {
+                    Task::none()
+                }
+            }
         }
     }
 
@@ -329,11 +973,99 @@ impl Organizer {
             }),
         ));
 
+        subscriptions.push(Subscription::run_with_id(
+            "deep_link",
+            iced::stream::channel(10, |mut output| async move {
+                let mut rx = deep_link_service::listen();
+                while let Some(link) = rx.recv().await {
+                    let _ = output.send(Message::DeepLink(link)).await;
+                }
+                std::future::pending().await
+            }),
+        ));
+
+        subscriptions.push(Subscription::run_with_id(
+            "data_changes",
+            iced::stream::channel(100, |mut output| async move {
+                let mut rx = change_service::subscribe();
+                loop {
+                    match rx.recv().await {
+                        Ok(change) => {
+                            let _ = output.send(Message::DataChanged(change)).await;
+                        }
+                        // Missed changes still mean the data is stale, one refresh covers them
+                        Err(RecvError::Lagged(_)) => {
+                            let _ = output.send(Message::DataChanged(DataChange::Images)).await;
+                            let _ = output.send(Message::DataChanged(DataChange::Tags)).await;
+                        }
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+                std::future::pending().await
+            }),
+        ));
+
+        subscriptions.push(Subscription::run_with_id(
+            "jobs",
+            iced::stream::channel(10, |mut output| async move {
+                let mut rx = job_service::subscribe();
+                loop {
+                    let jobs = *rx.borrow_and_update();
+                    let _ = output
+                        .send(Message::StatusBar(status_bar::Message::JobsChanged(jobs)))
+                        .await;
+                    if rx.changed().await.is_err() {
+                        break;
+                    }
+                }
+                std::future::pending().await
+            }),
+        ));
+
+        if self.navbar.is_resizing() {
+            subscriptions.push(event::listen_with(|event, _, _| match event {
+                Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::Navbar(navbar::Message::Resized(position.x)))
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::Navbar(navbar::Message::ResizeFinished))
+                }
+                _ => None,
+            }));
+        }
+
+        if let Screen::Search(search) = &self.screen
+            && search.is_animating()
+        {
+            subscriptions.push(
+                window::frames().map(|at| Message::Search(search::Message::AnimationFrame(at))),
+            );
+        }
+
+        if self.startup_task.is_some() {
+            subscriptions.push(window::frames().map(|_| Message::WindowShown));
+        }
+
         if !self.toasts.is_empty() {
             subscriptions
                 .push(time::every(Duration::from_secs(1)).map(|_| Message::Tick(Instant::now())));
         }
 
+        let (wallpaper_tag, wallpaper_interval) = {
+            let config = &get_settings().config;
+            (config.wallpaper_tag.is_some(), config.wallpaper_interval.unwrap_or(0))
+        };
+        if wallpaper_tag && wallpaper_interval > 0 {
+            subscriptions.push(
+                time::every(Duration::from_secs(wallpaper_interval * 60))
+                    .map(|_| Message::RotateWallpaper),
+            );
+        }
+
+        subscriptions.push(
+            time::every(MAINTENANCE_CHECK_INTERVAL).map(|_| Message::RunMaintenance),
+        );
+
         subscriptions.push(event::listen().map(|event| match event {
             Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
                 match key {
@@ -343,9 +1075,24 @@ impl Organizer {
                     keyboard::Key::Character(ref c) if c == "v" && modifiers.control() => {
                         Message::PasteShortcut
                     }
-                    _ => Message::NoOps,
+                    // R: open another random image
+                    keyboard::Key::Character(ref c) if c == "r" && modifiers.is_empty() => {
+                        Message::RandomShortcut
+                    }
+                    // CTRL+1..9, CTRL+0: navbar buttons
+                    keyboard::Key::Character(ref c) if modifiers.control() => c
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|digit| NavButton::ALL.get((digit + 9) % 10))
+                        .map_or(Message::NoOps, |button| {
+                            Message::Navbar(navbar::Message::ButtonPressed(*button))
+                        }),
+                    key => Message::KeyPressed(key, modifiers),
                 }
             }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Message::Search(search::Message::ModifiersChanged(modifiers))
+            }
             _ => Message::NoOps,
         }));
 
@@ -356,14 +1103,34 @@ impl Organizer {
         let navbar = self.navbar.view().map(Message::Navbar);
 
         let content = match &self.screen {
-            Screen::Search(search) => search.view().map(Message::Search),
+            Screen::Search(search) => Column::new()
+                .push(self.tab_bar())
+                .push(search.view().map(Message::Search))
+                .into(),
             Screen::Register(register) => register.view().map(Message::Register),
             Screen::Update(update) => update.view().map(Message::Update),
             Screen::Preferences(preferences) => preferences.view().map(Message::Preferences),
             Screen::ManageTags(manage_tags) => manage_tags.view().map(Message::ManageTags),
+            Screen::Activity(activity) => activity.view().map(Message::Activity),
+            Screen::Quarantine(quarantine) => quarantine.view().map(Message::Quarantine),
+            Screen::Trash(trash) => trash.view().map(Message::Trash),
+            Screen::Inbox(inbox) => inbox.view().map(Message::Inbox),
+            Screen::Rules(rules) => rules.view().map(Message::Rules),
+            Screen::Timeline(timeline) => timeline.view().map(Message::Timeline),
         };
 
-        let layout = Row::new().push(navbar).push(content);
+        let layout = Column::new()
+            .push(
+                Row::new()
+                    .push(navbar)
+                    .push(self.navbar.resize_handle().map(Message::Navbar))
+                    .push(content)
+                    .height(Length::Fill),
+            )
+            .push(self.status_bar.view(
+                self.navbar.counts(),
+                Message::Navigate(NavigationTarget::Preferences),
+            ));
 
         let toast_widgets: Vec<_> = self
             .toasts
@@ -383,15 +1150,87 @@ impl Organizer {
 }
 
 fn main() -> iced::Result {
+    let launched_at = Instant::now();
     info!("Starting application");
     logger_service::init().expect("Failed to initialize logger");
+    logger_service::install_panic_hook();
 
     info!("{:?}", _rust_i18n_available_locales());
 
+    // Only one instance may use the database: a second launch hands its
+    // arguments to the window already open and quits, before it touches the library
+    let args = deep_link_service::launch_args();
+    if !deep_link_service::claim_instance() {
+        if !deep_link_service::forward_to_running_instance(&args) {
+            // It may still have the database open, this launch stays out of it
+            error!("Another instance holds the instance lock but did not answer");
+            rust_i18n::set_locale(get_settings().config.language.as_str());
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Error)
+                .set_title(t!("instance.not_responding.title"))
+                .set_description(t!("instance.not_responding.description"))
+                .set_buttons(rfd::MessageButtons::Ok)
+                .show();
+        }
+        return Ok(());
+    }
+
+    // A library move asked for at the last run, before anything reads the library
+    let library_moved = library_location_service::apply_pending_move();
+
     {
         let settings = get_settings();
         rust_i18n::set_locale(settings.config.language.as_str());
     }
+    logger_service::report_last_crash();
+    if let Some(report) = config::take_load_report() {
+        toast_service::push_warning(preferences::defaults_applied_message(&report));
+    }
+    match library_moved {
+        Some(Ok(target)) => toast_service::push_success(t!(
+            "message.library.moved",
+            path = target.display().to_string()
+        )),
+        Some(Err(err)) => {
+            error!("Failed to move the library: {}", err);
+            toast_service::push_error(t!("message.library.move_failed", error = err));
+        }
+        None if utils::data_location() == utils::DataLocation::Legacy
+            && library_location_service::can_move() =>
+        {
+            toast_service::push_warning(t!("message.library.legacy"));
+        }
+        None => {}
+    }
+
+    let links: Vec<DeepLink> = args
+        .iter()
+        .filter_map(|arg| {
+            let parsed = DeepLink::from_arg(arg);
+            if parsed.is_none() {
+                warn!("Ignoring unknown launch argument: {}", arg);
+            }
+            parsed
+        })
+        .collect();
+
+    std::thread::spawn(|| {
+        if let Err(e) = deep_link_service::register_protocol() {
+            warn!("Could not register the organizer:// protocol: {}", e);
+        }
+        if let Err(e) = deep_link_service::register_open_with() {
+            warn!("Could not register Open with Organizer: {}", e);
+        }
+    });
+
+    // Backs up the database before the migrations of a new version run
+    if let Some(previous) = update_service::record_version(env!("CARGO_PKG_VERSION")) {
+        toast_service::push_success(t!(
+            "message.update.upgraded",
+            previous = previous,
+            version = env!("CARGO_PKG_VERSION")
+        ));
+    }
 
     // Create Tokio runtime
     let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
@@ -400,6 +1239,9 @@ fn main() -> iced::Result {
     rt.block_on(async {
         dotenv::dotenv().ok();
         database_service::prepare_database().await.unwrap();
+        if let Err(e) = library_location_service::finish_move().await {
+            error!("Failed to point the stored paths at the moved library: {}", e);
+        }
     });
 
     rt.shutdown_background();
@@ -418,5 +1260,5 @@ fn main() -> iced::Result {
             ),
             ..Default::default()
         })
-        .run_with(Organizer::new)
+        .run_with(move || Organizer::new(links, launched_at))
 }
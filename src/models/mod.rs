@@ -1,8 +0,0 @@
-pub mod filter;
-pub mod image;
-pub mod image_tag;
-pub mod page;
-pub mod tag;
-pub mod toast;
-pub mod tag_color;
-pub mod enums;
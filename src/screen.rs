@@ -3,12 +3,24 @@ pub mod search;
 pub mod update;
 pub mod preferences;
 pub mod manage_tags;
+pub mod activity;
+pub mod quarantine;
+pub mod inbox;
+pub mod rules;
+pub mod timeline;
+pub mod trash;
 
 pub use search::Search;
 pub use register::Register;
 pub use update::Update;
 pub use preferences::Preferences;
 pub use manage_tags::ManageTags;
+pub use activity::Activity;
+pub use quarantine::Quarantine;
+pub use inbox::Inbox;
+pub use rules::Rules;
+pub use timeline::Timeline;
+pub use trash::Trash;
 
 pub enum Screen {
     Search(Search),
@@ -16,4 +28,10 @@ pub enum Screen {
     Update(Update),
     Preferences(Preferences),
     ManageTags(ManageTags),
+    Activity(Activity),
+    Quarantine(Quarantine),
+    Inbox(Inbox),
+    Rules(Rules),
+    Timeline(Timeline),
+    Trash(Trash),
 }
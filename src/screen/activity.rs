@@ -0,0 +1,328 @@
+use crate::dtos::activity_dto::{ActivityDTO, UsageInsightsDTO};
+use crate::models::activity_action::ActivityAction;
+use crate::services::activity_service;
+use crate::services::toast_service::push_error;
+use iced::widget::{
+    Column, Container, Row, Space, column, pick_list, scrollable, text, text_input,
+};
+use iced::{Alignment, Element, Length, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use log::error;
+use std::fmt;
+
+pub enum Action {
+    None,
+    Run(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    FilterChanged(ActionFilter),
+    QueryChanged(String),
+    Loaded(Result<Vec<ActivityDTO>, String>),
+    InsightsLoaded(Result<UsageInsightsDTO, String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionFilter {
+    All,
+    Only(ActivityAction),
+}
+
+impl fmt::Display for ActionFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionFilter::All => write!(f, "{}", t!("activity.filter.all")),
+            ActionFilter::Only(action) => write!(f, "{action}"),
+        }
+    }
+}
+
+pub struct Activity {
+    entries: Vec<ActivityDTO>,
+    filter: ActionFilter,
+    filter_options: Vec<ActionFilter>,
+    query: String,
+    loaded: bool,
+    /// Usage figures worked out from the history, None until loaded
+    insights: Option<UsageInsightsDTO>,
+}
+
+impl Activity {
+    pub fn new() -> (Self, Task<Message>) {
+        let mut filter_options = vec![ActionFilter::All];
+        filter_options.extend(ActivityAction::all().into_iter().map(ActionFilter::Only));
+
+        let activity = Self {
+            entries: Vec::new(),
+            filter: ActionFilter::All,
+            filter_options,
+            query: String::new(),
+            loaded: false,
+            insights: None,
+        };
+        let task = Task::batch([activity.load(), Self::load_insights()]);
+        (activity, task)
+    }
+
+    fn load_insights() -> Task<Message> {
+        Task::perform(
+            async {
+                activity_service::insights()
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::InsightsLoaded,
+        )
+    }
+
+    fn load(&self) -> Task<Message> {
+        let action = match self.filter {
+            ActionFilter::All => None,
+            ActionFilter::Only(action) => Some(action),
+        };
+        let query = self.query.clone();
+
+        Task::perform(
+            async move {
+                activity_service::find(action, &query)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::Loaded,
+        )
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::FilterChanged(filter) => {
+                self.filter = filter;
+                Action::Run(self.load())
+            }
+            Message::QueryChanged(query) => {
+                self.query = query;
+                Action::Run(self.load())
+            }
+            Message::Loaded(result) => {
+                self.loaded = true;
+                match result {
+                    Ok(entries) => self.entries = entries,
+                    Err(err) => {
+                        error!("Failed to load activity: {}", err);
+                        push_error(t!("message.activity.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::InsightsLoaded(result) => {
+                match result {
+                    Ok(insights) => self.insights = Some(insights),
+                    Err(err) => {
+                        error!("Failed to compute usage insights: {}", err);
+                        push_error(t!("message.activity.insights_error"));
+                    }
+                }
+                Action::None
+            }
+        }
+    }
+
+    pub fn view(&'_ self) -> Element<'_, Message> {
+        let header = column![
+            text(t!("activity.title"))
+                .size(32)
+                .style(Modern::primary_text()),
+            Space::new(0, 8),
+            text(t!("activity.subtitle"))
+                .size(16)
+                .style(Modern::secondary_text()),
+        ];
+
+        let filters = Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                text_input(t!("activity.input.query").as_ref(), &self.query)
+                    .on_input(Message::QueryChanged)
+                    .padding(12)
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::FillPortion(3)),
+            )
+            .push(
+                pick_list(
+                    self.filter_options.as_slice(),
+                    Some(self.filter),
+                    Message::FilterChanged,
+                )
+                .padding(12)
+                .style(Modern::pick_list())
+                .width(Length::FillPortion(1)),
+            );
+
+        let mut list = Column::new().spacing(8);
+        if self.loaded && self.entries.is_empty() {
+            list = list.push(
+                text(t!("activity.empty"))
+                    .size(16)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for entry in &self.entries {
+            list = list.push(self.view_entry(entry));
+        }
+
+        let content = Column::new()
+            .spacing(24)
+            .padding(20)
+            .width(Length::Fill)
+            .push(header)
+            .push_maybe(self.insights.as_ref().map(Self::view_insights))
+            .push(filters)
+            .push(
+                Container::new(list)
+                    .padding(20)
+                    .width(Length::Fill)
+                    .style(Modern::card_container()),
+            );
+
+        Container::new(scrollable(content).width(Length::Fill).height(Length::Fill))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Titled list of labels with their count
+    fn view_figures(title: String, rows: &[(String, u64)]) -> Column<'_, Message> {
+        let mut column = Column::new().spacing(6).width(Length::FillPortion(1)).push(
+            text(title)
+                .size(14)
+                .style(Modern::secondary_text()),
+        );
+        if rows.is_empty() {
+            column = column.push(
+                text(t!("activity.insights.none"))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for (label, count) in rows {
+            column = column.push(
+                Row::new()
+                    .spacing(8)
+                    .push(
+                        text(label.as_str())
+                            .size(14)
+                            .style(Modern::primary_text())
+                            .width(Length::Fill),
+                    )
+                    .push(text(count.to_string()).size(14).style(Modern::secondary_text())),
+            );
+        }
+        column
+    }
+
+    /// Most added tags, busiest days and tags per image, side by side
+    fn view_insights(insights: &UsageInsightsDTO) -> Element<'_, Message> {
+        let average = Column::new()
+            .spacing(6)
+            .width(Length::FillPortion(1))
+            .push(
+                text(t!("activity.insights.average_tags"))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            )
+            .push(
+                text(format!("{:.1}", insights.average_tags))
+                    .size(28)
+                    .style(Modern::primary_text()),
+            );
+
+        let note = match &insights.since {
+            Some(date) => t!("activity.insights.note_since", date = date),
+            None => t!("activity.insights.note"),
+        };
+
+        Container::new(
+            Column::new()
+                .spacing(16)
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("chart-simple").size(16.0))
+                        .push(text(t!("activity.insights.title")).size(18)),
+                )
+                .push(
+                    Row::new()
+                        .spacing(24)
+                        .push(Self::view_figures(
+                            t!("activity.insights.top_tags").to_string(),
+                            &insights.top_tags,
+                        ))
+                        .push(Self::view_figures(
+                            t!("activity.insights.busiest_days").to_string(),
+                            &insights.busiest_days,
+                        ))
+                        .push(average),
+                )
+                .push(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(
+                            fa_icon_solid("lock")
+                                .size(12.0)
+                                .style(Modern::secondary_text()),
+                        )
+                        .push(text(note).size(12).style(Modern::secondary_text())),
+                ),
+        )
+        .padding(20)
+        .width(Length::Fill)
+        .style(Modern::card_container())
+        .into()
+    }
+
+    fn view_entry<'a>(&self, entry: &'a ActivityDTO) -> Element<'a, Message> {
+        let image = entry
+            .image_id
+            .map(|id| format!("#{id}"))
+            .unwrap_or_default();
+
+        Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                Container::new(fa_icon_solid(entry.action.icon()).size(14.0))
+                    .width(Length::Fixed(24.0))
+                    .align_x(Alignment::Center),
+            )
+            .push(
+                text(&entry.created_at)
+                    .size(14)
+                    .style(Modern::secondary_text())
+                    .width(Length::Fixed(160.0)),
+            )
+            .push(
+                text(entry.action.to_string())
+                    .size(14)
+                    .style(Modern::primary_text())
+                    .width(Length::Fixed(140.0)),
+            )
+            .push(
+                text(image)
+                    .size(14)
+                    .style(Modern::secondary_text())
+                    .width(Length::Fixed(60.0)),
+            )
+            .push(
+                text(&entry.details)
+                    .size(14)
+                    .style(Modern::primary_text())
+                    .width(Length::Fill),
+            )
+            .into()
+    }
+}
@@ -0,0 +1,372 @@
+use crate::components::accessibility::labeled;
+use crate::components::tag_chips::tag_chips;
+use crate::dtos::image_dto::{ImageDTO, ImageUpdateDTO};
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::review_status::ReviewStatus;
+use crate::models::tag_color::TagColor;
+use crate::services::toast_service::{push_error, push_success};
+use crate::services::{file_service, image_service, thumbnail_renderer, trash_service};
+use crate::t_count;
+use iced::keyboard;
+use iced::widget::image::Handle;
+use iced::widget::{
+    Button, Column, Container, Image, Row, Space, column, text, text_input, tooltip,
+};
+use iced::{Alignment, ContentFit, Element, Length, Padding, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use log::error;
+use std::path::Path;
+
+/// Images loaded into the queue at a time, the next ones come once it is empty
+const BATCH_SIZE: u64 = 100;
+
+pub enum Action {
+    None,
+    Run(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Loaded(Result<Vec<ImageDTO>, String>),
+    KeyPressed(keyboard::Key),
+    Keep,
+    NeedsWork,
+    Delete,
+    StartTagging,
+    CancelTagging,
+    TagInputChanged(String),
+    ApplyTags,
+    /// An image was triaged, or failed to be
+    Triaged(Result<(), String>),
+}
+
+/// Imported images waiting to be looked at, triaged one at a time from the keyboard:
+/// K keeps, N marks for more work, T tags and keeps, D deletes
+pub struct Inbox {
+    /// Current batch, the image on screen first
+    queue: Vec<ImageDTO>,
+    loaded: bool,
+    /// Whether the last load filled a whole batch, so more may be waiting
+    more: bool,
+    /// Preview of the image on screen
+    preview: Option<Handle>,
+    /// Comma separated tags being typed, set while tagging
+    tag_input: Option<String>,
+}
+
+fn tag_input_id() -> text_input::Id {
+    text_input::Id::new("inbox-tags")
+}
+
+impl Inbox {
+    pub fn new() -> (Self, Task<Message>) {
+        let inbox = Self {
+            queue: Vec::new(),
+            loaded: false,
+            more: false,
+            preview: None,
+            tag_input: None,
+        };
+        (inbox, Self::load())
+    }
+
+    fn load() -> Task<Message> {
+        Task::perform(
+            async {
+                image_service::find_unreviewed(BATCH_SIZE)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::Loaded,
+        )
+    }
+
+    pub fn is_tagging(&self) -> bool {
+        self.tag_input.is_some()
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Loaded(result) => {
+                self.loaded = true;
+                match result {
+                    Ok(images) => {
+                        self.more = images.len() as u64 == BATCH_SIZE;
+                        self.queue = images;
+                    }
+                    Err(err) => {
+                        error!("Failed to load the inbox: {}", err);
+                        push_error(t!("message.inbox.error"));
+                    }
+                }
+                self.show_current();
+                Action::None
+            }
+            Message::KeyPressed(key) => {
+                if self.is_tagging() {
+                    return Action::None;
+                }
+                let message = match key.as_ref() {
+                    keyboard::Key::Character("k") => Message::Keep,
+                    keyboard::Key::Character("n") => Message::NeedsWork,
+                    keyboard::Key::Character("t") => Message::StartTagging,
+                    keyboard::Key::Character("d") => Message::Delete,
+                    _ => return Action::None,
+                };
+                self.update(message)
+            }
+            Message::Keep => self.set_status(ReviewStatus::Kept),
+            Message::NeedsWork => self.set_status(ReviewStatus::NeedsWork),
+            Message::Delete => {
+                let Some(image) = self.take_current() else {
+                    return Action::None;
+                };
+                Action::Run(Task::perform(
+                    trash_service::trash(image.id),
+                    Message::Triaged,
+                ))
+            }
+            Message::StartTagging => {
+                if self.queue.is_empty() {
+                    return Action::None;
+                }
+                self.tag_input = Some(String::new());
+                Action::Run(text_input::focus(tag_input_id()))
+            }
+            Message::CancelTagging => {
+                self.tag_input = None;
+                Action::None
+            }
+            Message::TagInputChanged(input) => {
+                if let Some(tag_input) = &mut self.tag_input {
+                    *tag_input = input;
+                }
+                Action::None
+            }
+            Message::ApplyTags => {
+                let input = self.tag_input.take().unwrap_or_default();
+                let Some(image) = self.take_current() else {
+                    return Action::None;
+                };
+                let mut tags = image.tags.clone();
+                tags.extend(
+                    input
+                        .split(',')
+                        .map(|name| name.trim().to_lowercase())
+                        .filter(|name| !name.is_empty())
+                        .map(|name| TagDTO {
+                            id: 0,
+                            name,
+                            color: TagColor::default(),
+                            parent_id: None,
+                            category: None,
+                        }),
+                );
+                Action::Run(Task::perform(
+                    async move {
+                        let dto = ImageUpdateDTO {
+                            tags: Some(tags),
+                            is_folder: image.is_folder,
+                            is_prepared: image.is_prepared,
+                            ..Default::default()
+                        };
+                        image_service::update_from_dto(image.id, dto)
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        image_service::set_review_status(image.id, ReviewStatus::Kept)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::Triaged,
+                ))
+            }
+            Message::Triaged(result) => match result {
+                Ok(()) if self.queue.is_empty() && self.more => {
+                    push_success(t!("message.inbox.next_batch"));
+                    Action::Run(Self::load())
+                }
+                Ok(()) => Action::None,
+                Err(err) => {
+                    error!("Failed to triage image: {}", err);
+                    push_error(t!("message.inbox.error"));
+                    Action::Run(Self::load())
+                }
+            },
+        }
+    }
+
+    fn set_status(&mut self, status: ReviewStatus) -> Action {
+        let Some(image) = self.take_current() else {
+            return Action::None;
+        };
+        Action::Run(Task::perform(
+            async move {
+                image_service::set_review_status(image.id, status)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::Triaged,
+        ))
+    }
+
+    /// Removes the image on screen from the queue and shows the next one
+    fn take_current(&mut self) -> Option<ImageDTO> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let image = self.queue.remove(0);
+        self.show_current();
+        Some(image)
+    }
+
+    fn show_current(&mut self) {
+        self.preview = self.queue.first().map(|image| {
+            let path = Path::new(&image.path);
+            if image.is_folder || !thumbnail_renderer::is_displayable(path) {
+                Handle::from_path(&image.thumbnail_path)
+            } else {
+                Handle::from_path(file_service::preview_source(&image.path))
+            }
+        });
+    }
+
+    pub fn view(&'_ self) -> Element<'_, Message> {
+        let header = column![
+            text(t!("inbox.title"))
+                .size(32)
+                .style(Modern::primary_text()),
+            Space::new(0, 8),
+            text(t!("inbox.subtitle"))
+                .size(16)
+                .style(Modern::secondary_text()),
+        ];
+
+        let body: Element<'_, Message> = match (self.queue.first(), &self.preview) {
+            (Some(image), Some(preview)) => self.view_current(image, preview),
+            _ => text(if self.loaded {
+                t!("inbox.empty")
+            } else {
+                t!("inbox.loading")
+            })
+            .size(16)
+            .style(Modern::secondary_text())
+            .into(),
+        };
+
+        let content = Column::new()
+            .spacing(24)
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .push(header)
+            .push(
+                Container::new(body)
+                    .padding(20)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .style(Modern::card_container()),
+            );
+
+        Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn view_current<'a>(
+        &'a self,
+        image: &'a ImageDTO,
+        preview: &'a Handle,
+    ) -> Element<'a, Message> {
+        let remaining = if self.more {
+            t!("inbox.remaining_more", count = self.queue.len())
+        } else {
+            t_count!("inbox.remaining", self.queue.len())
+        };
+
+        let triage_button = |icon: &'static str, key: &'static str, label: String, message| {
+            labeled(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid(icon).size(14.0))
+                        .push(text(key).size(14).style(Modern::secondary_text())),
+                )
+                .padding(Padding::from([10, 16]))
+                .style(Modern::secondary_button())
+                .on_press(message),
+                label,
+                tooltip::Position::Top,
+            )
+        };
+
+        let mut details = Column::new()
+            .spacing(12)
+            .width(Length::Fixed(320.0))
+            .push(text(remaining).size(14).style(Modern::secondary_text()))
+            .push(
+                text(&image.description)
+                    .size(18)
+                    .style(Modern::primary_text()),
+            )
+            .push(tag_chips(&image.tags, 12, |_| Message::StartTagging))
+            .push(
+                text(&image.created_at)
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            )
+            .push(
+                Row::new()
+                    .spacing(8)
+                    .push(triage_button(
+                        "check",
+                        "K",
+                        t!("inbox.button.keep").to_string(),
+                        Message::Keep,
+                    ))
+                    .push(triage_button(
+                        "wrench",
+                        "N",
+                        t!("inbox.button.needs_work").to_string(),
+                        Message::NeedsWork,
+                    ))
+                    .push(triage_button(
+                        "tag",
+                        "T",
+                        t!("inbox.button.tag").to_string(),
+                        Message::StartTagging,
+                    ))
+                    .push(triage_button(
+                        "trash",
+                        "D",
+                        t!("inbox.button.delete").to_string(),
+                        Message::Delete,
+                    )),
+            );
+
+        if let Some(tag_input) = &self.tag_input {
+            details = details.push(
+                text_input(&t!("inbox.placeholder.tags"), tag_input)
+                    .id(tag_input_id())
+                    .on_input(Message::TagInputChanged)
+                    .on_submit(Message::ApplyTags)
+                    .padding(Padding::new(10.0))
+                    .style(Modern::text_input()),
+            );
+        }
+
+        Row::new()
+            .spacing(24)
+            .height(Length::Fill)
+            .push(
+                Image::new(preview)
+                    .content_fit(ContentFit::Contain)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .push(details)
+            .into()
+    }
+}
@@ -1,7 +1,12 @@
 use crate::dtos::tag_dto::{TagDTO, TagUpdateDTO};
+use crate::dtos::tag_settings_dto::TagSettingsDTO;
+use crate::models::filter::SortOrder;
 use crate::models::tag_color::TagColor;
-use crate::services::tag_service;
+use crate::models::view_density::ViewDensity;
+use crate::services::tag_service::{category_order, leaf_name};
+use crate::services::{tag_service, tag_settings_service};
 use crate::services::toast_service::{push_error, push_success};
+use crate::t_count;
 use crate::utils::capitalize_first;
 use iced::widget::{Column, Container};
 use iced::widget::{
@@ -22,10 +27,27 @@ pub enum Action {
 pub enum Message {
     EditTag(i64),
     NameChanged(i64, String),
+    CategoryChanged(i64, String),
     ColorChanged(i64, TagColor),
+    SortOrderChanged(i64, SortOrder),
+    DensityChanged(i64, ViewDensity),
+    ResetView(i64),
     SubmitTag(i64),
+    /// Asks first, with the number of images carrying the tag, and deletes it on
+    /// the second message
     DeleteTag(i64),
-    TagsLoaded(HashSet<TagDTO>),
+    CancelDelete,
+    /// Shows or hides the tags under a tag
+    ToggleBranch(i64),
+    ReloadTags,
+    /// Tags with the number of images carrying each
+    TagsLoaded(Vec<(TagDTO, u64)>),
+    TagSettingsLoaded(HashMap<i64, TagSettingsDTO>),
+    AliasesLoaded(HashMap<i64, Vec<String>>),
+    AliasInputChanged(i64, String),
+    AddAlias(i64),
+    RemoveAlias(String),
+    AliasChanged(Result<(), String>),
 
     NewTagNameChanged(String),
     NewTagColorChanged(TagColor),
@@ -37,7 +59,19 @@ pub enum Message {
 #[derive(Debug, Default)]
 pub struct ManageTags {
     pub tags: HashSet<TagDTO>,
+    /// Number of images carrying each tag
+    pub usage: HashMap<i64, u64>,
+    /// Tag waiting for its deletion to be confirmed
+    pub confirm_delete: Option<i64>,
     pub editing: HashMap<i64, TagUpdateDTO>,
+    /// Default view of each tag that has one
+    pub tag_settings: HashMap<i64, TagSettingsDTO>,
+    /// Tags whose children are hidden
+    pub collapsed: HashSet<i64>,
+    /// Other names of each tag that has some
+    pub aliases: HashMap<i64, Vec<String>>,
+    /// Alias being typed for each tag under edit
+    pub alias_inputs: HashMap<i64, String>,
     pub new_tag_name: String,
     pub new_tag_color: TagColor,
     pub btn_save: String,
@@ -52,7 +86,13 @@ impl ManageTags {
         (
             Self {
                 tags: HashSet::new(),
+                usage: HashMap::new(),
+                confirm_delete: None,
                 editing: HashMap::new(),
+                tag_settings: HashMap::new(),
+                collapsed: HashSet::new(),
+                aliases: HashMap::new(),
+                alias_inputs: HashMap::new(),
                 new_tag_name: String::new(),
                 new_tag_color: TagColor::Blue,
                 btn_save: t!("manage_tags.button.save").to_string(),
@@ -61,29 +101,54 @@ impl ManageTags {
                 btn_delete: t!("manage_tags.button.delete").to_string(),
                 tag_color_options: TagColor::all(),
             },
-            Task::perform(
-                async move {
-                    let all_tags = tag_service::find_all().await.unwrap_or_default();
-                    all_tags
-                },
-                |all_tags| Message::TagsLoaded(all_tags),
-            ),
+            Task::batch([
+                Self::load_tags(),
+                Task::perform(
+                    async move { tag_settings_service::find_all().await.unwrap_or_default() },
+                    Message::TagSettingsLoaded,
+                ),
+                Self::load_aliases(),
+            ]),
+        )
+    }
+
+    fn load_tags() -> Task<Message> {
+        Task::perform(
+            async move {
+                tag_service::find_all_with_counts()
+                    .await
+                    .unwrap_or_else(|err| {
+                        error!("Failed to load tags: {}", err);
+                        Vec::new()
+                    })
+            },
+            Message::TagsLoaded,
+        )
+    }
+
+    fn load_aliases() -> Task<Message> {
+        Task::perform(
+            async move { tag_service::find_aliases().await.unwrap_or_default() },
+            Message::AliasesLoaded,
         )
     }
 
     pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::EditTag(id) => {
-                if self.editing.remove(&id).is_none() {
-                    if let Some(tag) = self.tags.iter().find(|t| t.id == id) {
-                        self.editing.insert(
-                            id,
-                            TagUpdateDTO {
-                                name: tag.name.clone(),
-                                color: tag.color.clone(),
-                            },
-                        );
-                    }
+                self.confirm_delete = None;
+                if self.editing.remove(&id).is_none()
+                    && let Some(tag) = self.tags.iter().find(|t| t.id == id)
+                {
+                    self.editing.insert(
+                        id,
+                        TagUpdateDTO {
+                            name: tag.name.clone(),
+                            color: tag.color.clone(),
+                            category: tag.category.clone().unwrap_or_default(),
+                            view: self.tag_settings.get(&id).copied().unwrap_or_default(),
+                        },
+                    );
                 }
                 Action::None
             }
@@ -93,14 +158,44 @@ impl ManageTags {
                 }
                 Action::None
             }
+            Message::CategoryChanged(id, category) => {
+                if let Some(edit) = self.editing.get_mut(&id) {
+                    edit.category = category;
+                }
+                Action::None
+            }
             Message::ColorChanged(id, color) => {
                 if let Some(edit) = self.editing.get_mut(&id) {
                     edit.color = color;
                 }
                 Action::None
             }
+            Message::SortOrderChanged(id, sort_order) => {
+                if let Some(edit) = self.editing.get_mut(&id) {
+                    edit.view.sort_order = Some(sort_order);
+                }
+                Action::None
+            }
+            Message::DensityChanged(id, density) => {
+                if let Some(edit) = self.editing.get_mut(&id) {
+                    edit.view.view_density = Some(density);
+                }
+                Action::None
+            }
+            Message::ResetView(id) => {
+                if let Some(edit) = self.editing.get_mut(&id) {
+                    edit.view = TagSettingsDTO::default();
+                }
+                Action::None
+            }
             Message::SubmitTag(id) => {
                 if let Some(edit) = self.editing.remove(&id) {
+                    let view = edit.view;
+                    if view.is_empty() {
+                        self.tag_settings.remove(&id);
+                    } else {
+                        self.tag_settings.insert(id, view);
+                    }
 
                     let old_tag = self.tags.iter().find(|t| t.id == id).cloned();
 
@@ -113,23 +208,30 @@ impl ManageTags {
                             id: old_tag.id,
                             name: edit.name.clone(),
                             color: edit.color.clone(),
+                            parent_id: old_tag.parent_id,
+                            category: tag_service::normalize_category(&edit.category),
                         };
 
                         self.tags.insert(updated_tag);
                     }
 
                     let task = Task::perform(
-                        async move { tag_service::update_from_dto(id, edit).await },
+                        async move {
+                            let tag = tag_service::update_from_dto(id, edit).await?;
+                            tag_settings_service::save(id, view).await?;
+                            Ok::<_, sea_orm::DbErr>(tag)
+                        },
                         move |result| match result {
+                            // A new name can move the tag and the ones under it
                             Ok(tag) => {
                                 debug!("Updated tag: {:#?}", tag);
                                 push_success(t!("message.manage_tags.update.success"));
-                                Message::NoOps
+                                Message::ReloadTags
                             }
                             Err(err) => {
                                 error!("Failed to update tag: {}", err);
                                 push_error(t!("message.manage_tags.update.error"));
-                                Message::NoOps
+                                Message::ReloadTags
                             }
                         },
                     );
@@ -138,7 +240,12 @@ impl ManageTags {
                 Action::None
             }
 
+            Message::DeleteTag(id) if self.confirm_delete != Some(id) => {
+                self.confirm_delete = Some(id);
+                Action::None
+            }
             Message::DeleteTag(id) => {
+                self.confirm_delete = None;
                 self.tags.retain(|t| t.id != id);
 
                 let task = Task::perform(
@@ -146,7 +253,7 @@ impl ManageTags {
                     move |result| match result {
                         Ok(()) => {
                             push_success(t!("message.manage_tags.delete.success"));
-                            Message::NoOps
+                            Message::ReloadTags
                         }
                         Err(err) => {
                             error!("Failed to delete tag: {}", err);
@@ -158,11 +265,76 @@ impl ManageTags {
                 Action::Run(task)
             }
 
+            Message::ToggleBranch(id) => {
+                if !self.collapsed.remove(&id) {
+                    self.collapsed.insert(id);
+                }
+                Action::None
+            }
+
+            Message::ReloadTags => Action::Run(Self::load_tags()),
+
+            Message::CancelDelete => {
+                self.confirm_delete = None;
+                Action::None
+            }
+
             Message::TagsLoaded(tags) => {
-                self.tags = tags;
+                self.usage = tags.iter().map(|(tag, count)| (tag.id, *count)).collect();
+                self.tags = tags.into_iter().map(|(tag, _)| tag).collect();
+                Action::None
+            }
+
+            Message::TagSettingsLoaded(tag_settings) => {
+                self.tag_settings = tag_settings;
                 Action::None
             }
 
+            Message::AliasesLoaded(aliases) => {
+                self.aliases = aliases;
+                Action::None
+            }
+
+            Message::AliasInputChanged(id, alias) => {
+                self.alias_inputs.insert(id, alias);
+                Action::None
+            }
+
+            Message::AddAlias(id) => {
+                let Some(alias) = self
+                    .alias_inputs
+                    .remove(&id)
+                    .filter(|alias| !alias.trim().is_empty())
+                else {
+                    return Action::None;
+                };
+                Action::Run(Task::perform(
+                    async move {
+                        tag_service::add_alias(id, &alias)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::AliasChanged,
+                ))
+            }
+
+            Message::RemoveAlias(alias) => Action::Run(Task::perform(
+                async move {
+                    tag_service::remove_alias(&alias)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                Message::AliasChanged,
+            )),
+
+            Message::AliasChanged(result) => {
+                if let Err(err) = result {
+                    error!("Failed to change a tag alias: {}", err);
+                    push_error(t!("message.manage_tags.alias.error"));
+                }
+                Action::Run(Self::load_aliases())
+            }
+
             // Handlers para adicionar tag
             Message::NewTagNameChanged(name) => {
                 self.new_tag_name = name;
@@ -194,7 +366,7 @@ impl ManageTags {
 
                         tag_service::find_all().await.map_err(|e| e.to_string())
                     },
-                    |result| Message::TagCreateResult(result),
+                    Message::TagCreateResult,
                 );
                 Action::Run(task)
             }
@@ -239,13 +411,15 @@ impl ManageTags {
                 .push(self.view_table_header())
                 .push(Space::new(0, 16));
 
-
-            let mut elements: Vec<_> = self.tags.iter().collect();
-            elements.sort_by(|a, b| a.name.cmp(&b.name));
-            
-            // Add tags rows
-            for (i, tag) in elements.iter().enumerate() {
-                table_column = table_column.push(self.view_tag(tag, i));
+            // Add tags rows, under a heading for each category once some tag has one
+            let grouped = self.tags.iter().any(|tag| tag.category.is_some());
+            let mut category = None;
+            for (i, row) in self.tree_rows().into_iter().enumerate() {
+                if grouped && (i == 0 || row.tag.category.as_deref() != category) {
+                    category = row.tag.category.as_deref();
+                    table_column = table_column.push(view_category_heading(category));
+                }
+                table_column = table_column.push(self.view_tag(row, i));
             }
 
             // Create table container
@@ -269,6 +443,51 @@ impl ManageTags {
             .into()
     }
 
+    /// Tags in tree order, grouped by category, each under its parent sorted by name.
+    /// The tags under a collapsed tag are left out.
+    fn tree_rows(&self) -> Vec<TreeRow<'_>> {
+        let categories: HashMap<i64, Option<&str>> = self
+            .tags
+            .iter()
+            .map(|tag| (tag.id, tag.category.as_deref()))
+            .collect();
+        let mut children: HashMap<Option<i64>, Vec<&TagDTO>> = HashMap::new();
+        for tag in &self.tags {
+            // Without its parent in the list, or in another category, a tag shows at
+            // the top of its group
+            let parent = tag
+                .parent_id
+                .filter(|id| categories.get(id) == Some(&tag.category.as_deref()));
+            children.entry(parent).or_default().push(tag);
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by(|a, b| {
+                category_order(a.category.as_deref(), b.category.as_deref())
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+        }
+
+        let mut rows = Vec::new();
+        let mut pending: Vec<(&TagDTO, usize)> = children
+            .get(&None)
+            .map(|roots| roots.iter().rev().map(|tag| (*tag, 0)).collect())
+            .unwrap_or_default();
+        while let Some((tag, depth)) = pending.pop() {
+            let below = children.get(&Some(tag.id));
+            rows.push(TreeRow {
+                tag,
+                depth,
+                has_children: below.is_some(),
+            });
+            if let Some(below) = below
+                && !self.collapsed.contains(&tag.id)
+            {
+                pending.extend(below.iter().rev().map(|child| (*child, depth + 1)));
+            }
+        }
+        rows
+    }
+
     fn view_header(&'_ self) -> Element<'_, Message> {
         let title = text(t!("manage_tags.title"))
             .size(32)
@@ -321,7 +540,18 @@ impl ManageTags {
             .spacing(16)
             .align_y(Alignment::Center);
 
-        let form_content = column![form_title, Space::new(0, 16), form_controls].spacing(0);
+        let form_hint = text(t!("manage_tags.add_form.hint"))
+            .size(14)
+            .style(Modern::secondary_text());
+
+        let form_content = column![
+            form_title,
+            Space::new(0, 8),
+            form_hint,
+            Space::new(0, 16),
+            form_controls
+        ]
+        .spacing(0);
 
         container(form_content)
             .padding(20)
@@ -370,7 +600,8 @@ impl ManageTags {
         container(header_row).padding([0, 30]).into()
     }
 
-    fn view_tag<'a>(&'a self, tag: &'a TagDTO, index: usize) -> Element<'a, Message> {
+    fn view_tag<'a>(&'a self, row: TreeRow<'a>, index: usize) -> Element<'a, Message> {
+        let tag = row.tag;
         let is_editing = self.editing.contains_key(&tag.id);
         let selected_color = self
             .editing
@@ -393,7 +624,31 @@ impl ManageTags {
             .style(Modern::text_input())
             .into()
         } else {
+            // Children show the last part of their name, indented under the parent
+            let name = if row.depth > 0 {
+                leaf_name(&tag.name)
+            } else {
+                &tag.name
+            };
+            let branch: Element<_> = if row.has_children {
+                let icon = if self.collapsed.contains(&tag_id) {
+                    "chevron-right"
+                } else {
+                    "chevron-down"
+                };
+                button(fa_icon_solid(icon).size(12.0))
+                    .on_press(Message::ToggleBranch(tag_id))
+                    .style(Modern::plain_button())
+                    .padding(4)
+                    .width(Length::Fixed(24.0))
+                    .into()
+            } else {
+                Space::new(24, 0).into()
+            };
             row![
+                Space::new(24.0 * row.depth as f32, 0),
+                branch,
+                Space::new(8, 0),
                 container(text("").size(12).style(|_theme| text::Style {
                     color: Some(self.get_color_from_tag_color(&tag.color)),
                 }))
@@ -410,9 +665,20 @@ impl ManageTags {
                     text_color: None,
                 }),
                 Space::new(12, 0),
-                text(capitalize_first(&tag.name))
-                    .size(16)
-                    .style(Modern::primary_text())
+                column![
+                    text(capitalize_first(name))
+                        .size(16)
+                        .style(Modern::primary_text())
+                ]
+                .push_maybe(self.aliases.get(&tag_id).map(|aliases| {
+                    text(t!("manage_tags.aliases.also", aliases = aliases.join(", ")))
+                        .size(12)
+                        .style(Modern::secondary_text())
+                })),
+                Space::new(12, 0),
+                text(t_count!("manage_tags.usage", self.usage_of(tag_id)))
+                    .size(12)
+                    .style(Modern::secondary_text()),
             ]
             .align_y(Alignment::Center)
             .into()
@@ -458,6 +724,24 @@ impl ManageTags {
                 .style(Modern::danger_button())
                 .padding(8),
             ]
+        } else if self.confirm_delete == Some(tag_id) {
+            row![
+                button(text(&self.btn_cancel).size(14))
+                    .on_press(Message::CancelDelete)
+                    .style(Modern::secondary_button())
+                    .padding(8),
+                button(
+                    row![
+                        fa_icon_solid("eraser").size(14.0),
+                        text(&self.btn_delete).size(14)
+                    ]
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                )
+                .on_press(Message::DeleteTag(tag_id))
+                .style(Modern::danger_button())
+                .padding(8),
+            ]
         } else {
             row![
                 button(
@@ -493,13 +777,32 @@ impl ManageTags {
         )
         .spacing(20)
         .align_y(Alignment::Center);
+        let row_content = column![row_content]
+            .push_maybe((self.confirm_delete == Some(tag_id)).then(|| {
+                let count = self.usage_of(tag_id);
+                let warning = if count == 0 {
+                    t!("manage_tags.delete_warning.unused").to_string()
+                } else {
+                    t_count!("manage_tags.delete_warning", count).to_string()
+                };
+                text(warning).size(14).style(Modern::warning_text())
+            }))
+            .spacing(12);
 
         let styled_container = if is_editing {
-            container(row_content)
-                .style(Modern::floating_container())
-                .padding(16)
-                .width(Length::Fill)
-        } else if index % 2 == 0 {
+            container(
+                column![
+                    row_content,
+                    self.view_tag_category(tag_id),
+                    self.view_tag_defaults(tag_id),
+                    self.view_tag_aliases(tag_id)
+                ]
+                .spacing(16),
+            )
+            .style(Modern::floating_container())
+            .padding(16)
+            .width(Length::Fill)
+        } else if index.is_multiple_of(2) {
             container(row_content)
                 .style(Modern::sheet_container())
                 .padding(16)
@@ -514,6 +817,163 @@ impl ManageTags {
         container(styled_container).padding([10, 20]).into()
     }
 
+    fn usage_of(&self, tag_id: i64) -> u64 {
+        self.usage.get(&tag_id).copied().unwrap_or(0)
+    }
+
+    /// Aliases of a tag under edit, each removable, with an input adding more
+    fn view_tag_aliases(&'_ self, tag_id: i64) -> Element<'_, Message> {
+        let mut chips = row![].spacing(8).align_y(Alignment::Center);
+        for alias in self.aliases.get(&tag_id).into_iter().flatten() {
+            chips = chips.push(
+                button(
+                    row![text(alias).size(14), fa_icon_solid("xmark").size(12.0)]
+                        .spacing(6)
+                        .align_y(Alignment::Center),
+                )
+                .on_press(Message::RemoveAlias(alias.clone()))
+                .style(Modern::secondary_button())
+                .padding([4, 10]),
+            );
+        }
+
+        let input = self
+            .alias_inputs
+            .get(&tag_id)
+            .map(String::as_str)
+            .unwrap_or_default();
+        let alias_input = text_input(t!("manage_tags.aliases.placeholder").as_ref(), input)
+            .on_input(move |alias| Message::AliasInputChanged(tag_id, alias))
+            .on_submit(Message::AddAlias(tag_id))
+            .padding(8)
+            .size(14)
+            .style(Modern::text_input())
+            .width(Length::Fixed(180.0));
+
+        let add_button = button(
+            row![
+                fa_icon_solid("plus").size(14.0),
+                text(t!("manage_tags.aliases.add")).size(14)
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        )
+        .on_press_maybe((!input.trim().is_empty()).then_some(Message::AddAlias(tag_id)))
+        .style(Modern::system_button())
+        .padding(8);
+
+        row![
+            column![
+                text(t!("manage_tags.aliases.title"))
+                    .size(14)
+                    .style(Modern::primary_text()),
+                text(t!("manage_tags.aliases.description"))
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(4)
+            .width(Length::FillPortion(3)),
+            chips,
+            alias_input,
+            add_button,
+        ]
+        .spacing(16)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    /// Category of a tag under edit, typed freely so new ones can be made up
+    fn view_tag_category(&'_ self, tag_id: i64) -> Element<'_, Message> {
+        let category = text_input(
+            t!("manage_tags.category.placeholder").as_ref(),
+            &self.editing[&tag_id].category,
+        )
+        .on_input(move |category| Message::CategoryChanged(tag_id, category))
+        .on_submit(Message::SubmitTag(tag_id))
+        .padding(8)
+        .size(14)
+        .style(Modern::text_input())
+        .width(Length::Fixed(376.0));
+
+        row![
+            column![
+                text(t!("manage_tags.category.title"))
+                    .size(14)
+                    .style(Modern::primary_text()),
+                text(t!("manage_tags.category.description"))
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(4)
+            .width(Length::FillPortion(3)),
+            category,
+        ]
+        .spacing(16)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    fn view_tag_defaults(&'_ self, tag_id: i64) -> Element<'_, Message> {
+        let view = self.editing[&tag_id].view;
+
+        let sort_picker = pick_list(
+            [
+                SortOrder::CreatedDesc,
+                SortOrder::CreatedAsc,
+                SortOrder::DescriptionAsc,
+            ],
+            view.sort_order,
+            move |order| Message::SortOrderChanged(tag_id, order),
+        )
+        .placeholder(t!("manage_tags.view.sort_placeholder"))
+        .style(Modern::pick_list())
+        .width(Length::Fixed(180.0));
+
+        let density_picker = pick_list(
+            [
+                ViewDensity::Compact,
+                ViewDensity::Comfortable,
+                ViewDensity::Large,
+            ],
+            view.view_density,
+            move |density| Message::DensityChanged(tag_id, density),
+        )
+        .placeholder(t!("manage_tags.view.density_placeholder"))
+        .style(Modern::pick_list())
+        .width(Length::Fixed(180.0));
+
+        let reset_button = button(
+            row![
+                fa_icon_solid("rotate-left").size(14.0),
+                text(t!("manage_tags.view.reset")).size(14)
+            ]
+            .spacing(6)
+            .align_y(Alignment::Center),
+        )
+        .on_press_maybe((!view.is_empty()).then_some(Message::ResetView(tag_id)))
+        .style(Modern::system_button())
+        .padding(8);
+
+        row![
+            column![
+                text(t!("manage_tags.view.title"))
+                    .size(14)
+                    .style(Modern::primary_text()),
+                text(t!("manage_tags.view.description"))
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(4)
+            .width(Length::FillPortion(3)),
+            sort_picker,
+            density_picker,
+            reset_button,
+        ]
+        .spacing(16)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
     fn get_color_from_tag_color(&self, tag_color: &TagColor) -> Color {
         match tag_color {
             TagColor::Red => Color::from_rgb(0.9, 0.2, 0.2),
@@ -528,3 +988,22 @@ impl ManageTags {
         }
     }
 }
+
+/// A tag as the tree shows it
+/// Heading above the tags of a category, or of the uncategorized ones
+fn view_category_heading<'a>(category: Option<&str>) -> Element<'a, Message> {
+    let label = match category {
+        Some(category) => capitalize_first(category),
+        None => t!("tag.category.none").to_string(),
+    };
+    container(text(label).size(14).style(Modern::secondary_text()))
+        .padding([12, 20])
+        .into()
+}
+
+#[derive(Clone, Copy)]
+struct TreeRow<'a> {
+    tag: &'a TagDTO,
+    depth: usize,
+    has_children: bool,
+}
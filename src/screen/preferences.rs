@@ -1,43 +1,319 @@
-use crate::config::{get_settings, get_settings_mut};
-use iced::widget::{Column, Container, PickList, Row, Scrollable, Slider, Text, TextInput};
-use iced::{Element, Length, Padding, Task};
+use crate::components::import_filter_editor;
+use crate::config::{Config, LoadReport, get_settings, get_settings_mut};
+use crate::dtos::release_dto::ReleaseDTO;
+use crate::models::delete_behavior::DeleteBehavior;
+use crate::models::export_preset::{ExportFormat, ExportPreset};
+use crate::models::grid_layout::GridLayout;
+use crate::models::import_filter::ImportFilter;
+use crate::models::integrity_report::{IntegrityProblem, IntegrityReport};
+use crate::models::maintenance::{MaintenanceTask, TaskRun, TaskSchedule};
+use crate::models::watermark::{Watermark, WatermarkKind, WatermarkPosition};
+use crate::services::file_service::DEFAULT_FILE_NAME_PATTERN;
+use crate::services::toast_service::{push_error, push_success, push_warning};
+use crate::services::{
+    image_cache, image_service, integrity_service, library_location_service, maintenance_service,
+    update_service, wallpaper_service,
+};
+use crate::t_count;
+use crate::utils::{DataLocation, data_location, get_data_dir};
+use iced::widget::{
+    Button, Checkbox, Column, Container, PickList, Row, Scrollable, Slider, Text, TextInput,
+};
+use iced::{Alignment, Element, Length, Padding, Task};
+use iced_font_awesome::fa_icon_solid;
 use iced_modern_theme::Modern;
 use log::error;
+use rfd::AsyncFileDialog;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
 
 pub enum Action {
     None,
-    UpdateUI(),
+    Run(Task<Message>),
+    OpenRules,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     LanguageChanged(String),
     ThemeChanged(String),
-    ItemsPerPageChanged(u64),
+    HighContrastToggled(bool),
+    ReduceMotionToggled(bool),
+    FuzzySearchToggled(bool),
+    DeleteBehaviorChanged(DeleteBehavior),
+    ItemsPerPageChanged(String),
+    GridColumnsChanged(GridColumns),
+    GridLayoutChanged(GridLayout),
+    QuickLookDelayChanged(QuickLookDelay),
+    ImageCacheChanged(CacheBudget),
     ThumbCompressionChanged(u8),
     ImageCompressionChanged(u8),
+    ClipboardTagChanged(String),
+    ClipboardDescriptionChanged(String),
+    DescriptionFromFilenameToggled(bool),
+    FileNamePatternChanged(String),
+    ImportFilterChanged(ImportFilter),
+    ExternalEditorChanged(String),
+    WatermarkChanged(Watermark),
+    ChooseWatermarkImage,
+    WatermarkImageChosen(PathBuf),
+    AddExportPreset,
+    RemoveExportPreset(usize),
+    ExportPresetChanged(usize, ExportPreset),
+    WallpaperTagChanged(String),
+    WallpaperIntervalChanged(RotationInterval),
+    RotateWallpaperNow,
+    DbRetryAttemptsChanged(u32),
+    DbWalToggled(bool),
+    DbBusyTimeoutChanged(u32),
+    DbRetryBackoffChanged(u32),
+    RenameStoredFiles,
+    OpenRules,
+    StoredFilesRenamed(Result<usize, String>),
+    VerifyLibrary,
+    LibraryVerified(Result<IntegrityReport, String>),
+    RestoreFromBackup,
+    BackupFolderChosen(PathBuf),
+    RestoreFinished(Result<usize, String>),
+    /// Move the library at the next start, next to the executable when true
+    RequestLibraryMove(bool),
+    CancelLibraryMove,
+    KeepLibraryPortable,
+    UpdateCheckToggled(bool),
+    UpdateUrlChanged(String),
+    CheckForUpdates,
+    UpdateChecked(Result<Option<ReleaseDTO>, String>),
+    DownloadUpdate,
+    UpdateDownloaded(Result<PathBuf, String>),
+    Apply,
+    Cancel,
+    /// Asks for confirmation before the config is reset
+    ResetToDefaults,
+    ConfirmReset,
+    CancelReset,
+    ExportSettings,
+    ImportSettings,
+    ExportPathChosen(PathBuf),
+    ImportPathChosen(PathBuf),
+    MaintenanceToggled(MaintenanceTask, bool),
+    MaintenanceIntervalChanged(MaintenanceTask, MaintenanceInterval),
+    RunMaintenanceTask(MaintenanceTask),
+    MaintenanceTaskFinished(MaintenanceTask, TaskRun),
     NoOps,
 }
 
 pub struct Preferences {
     available_languages: Vec<String>,
-    pub theme: String,
-    pub items_per_page: u64,
-    pub thumb_compression: u8,
-    pub image_compression: u8,
-    selected_language: String,
+    /// Config as currently saved on disk. Boxed, two configs make the screen the
+    /// largest by far.
+    saved: Box<Config>,
+    /// Config being edited, only written on Apply
+    draft: Config,
+    /// Raw text of the items per page input, validated before applying
+    items_per_page_input: String,
+    /// Whether stored files are currently being renamed to the pattern
+    renaming: bool,
+    /// Whether the library is being verified or restored
+    verifying: bool,
+    /// Result of the last library verification
+    integrity_report: Option<IntegrityReport>,
+    /// Where the library is kept this run
+    library_location: DataLocation,
+    /// Folder the library moves to at the next start
+    library_move: Option<PathBuf>,
+    /// Newer release found by the last update check, boxed like `saved`
+    release: Option<Box<ReleaseDTO>>,
+    /// Whether an update is being checked for or downloaded
+    updating: bool,
+    /// Last run of each maintenance task
+    maintenance_runs: BTreeMap<MaintenanceTask, TaskRun>,
+    /// Maintenance tasks started from this screen and still running
+    running_tasks: HashSet<MaintenanceTask>,
+    /// Reset to defaults was pressed and waits for confirmation
+    confirm_reset: bool,
 }
 
 const THEMES: [&str; 3] = ["Light", "Dark", "System"];
+const ROTATION_INTERVALS: [RotationInterval; 6] = [
+    RotationInterval(0),
+    RotationInterval(15),
+    RotationInterval(30),
+    RotationInterval(60),
+    RotationInterval(180),
+    RotationInterval(1440),
+];
+
+/// Minutes between wallpaper changes, 0 turns the rotation off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationInterval(u64);
+
+impl std::fmt::Display for RotationInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", t!("preferences.wallpaper.off")),
+            minutes if minutes % 60 == 0 => write!(
+                f,
+                "{}",
+                t_count!("preferences.wallpaper.every_hours", minutes / 60)
+            ),
+            minutes => write!(
+                f,
+                "{}",
+                t_count!("preferences.wallpaper.every_minutes", minutes)
+            ),
+        }
+    }
+}
+
+const GRID_COLUMNS: [GridColumns; 7] = [
+    GridColumns(0),
+    GridColumns(2),
+    GridColumns(3),
+    GridColumns(4),
+    GridColumns(5),
+    GridColumns(6),
+    GridColumns(8),
+];
+
+/// Cards per row of the search grid, 0 wraps them at their own size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridColumns(u32);
+
+impl std::fmt::Display for GridColumns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", t!("preferences.grid.auto")),
+            columns => write!(f, "{}", t_count!("preferences.grid.columns", columns)),
+        }
+    }
+}
+
+const QUICK_LOOK_DELAYS: [QuickLookDelay; 5] = [
+    QuickLookDelay(0),
+    QuickLookDelay(300),
+    QuickLookDelay(500),
+    QuickLookDelay(800),
+    QuickLookDelay(1200),
+];
+
+/// Milliseconds a card is hovered before its quick look opens, 0 leaves it to the
+/// space bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickLookDelay(u64);
+
+impl std::fmt::Display for QuickLookDelay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "{}", t!("preferences.quick_look.space_only")),
+            millis => write!(
+                f,
+                "{}",
+                t!("preferences.quick_look.after", millis = millis)
+            ),
+        }
+    }
+}
+
+const CACHE_BUDGETS: [CacheBudget; 6] = [
+    CacheBudget(64),
+    CacheBudget(128),
+    CacheBudget(256),
+    CacheBudget(512),
+    CacheBudget(1024),
+    CacheBudget(2048),
+];
+
+/// Megabytes of decoded images kept in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheBudget(u64);
+
+impl std::fmt::Display for CacheBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", t!("preferences.image_cache.megabytes", size = self.0))
+    }
+}
+
+const MAINTENANCE_INTERVALS: [MaintenanceInterval; 5] = [
+    MaintenanceInterval(6),
+    MaintenanceInterval(24),
+    MaintenanceInterval(24 * 3),
+    MaintenanceInterval(24 * 7),
+    MaintenanceInterval(24 * 30),
+];
+
+/// Hours between two runs of a maintenance task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceInterval(u32);
+
+impl std::fmt::Display for MaintenanceInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            hours if hours % 24 == 0 => write!(
+                f,
+                "{}",
+                t_count!("preferences.maintenance.every_days", hours / 24)
+            ),
+            hours => write!(
+                f,
+                "{}",
+                t_count!("preferences.maintenance.every_hours", hours)
+            ),
+        }
+    }
+}
+
+const MAX_ITEMS_PER_PAGE: u64 = 100;
+
+/// Copies the settings edited in the draft to `config`. Those left as they were
+/// when the screen opened and those other screens own, like the pinned searches
+/// or the search history, keep the value `config` has.
+fn write_edits(draft: &Config, saved: &Config, config: &mut Config) {
+    macro_rules! edited {
+        ($($field:ident),* $(,)?) => {
+            $(
+                if draft.$field != saved.$field {
+                    config.$field = draft.$field.clone();
+                }
+            )*
+        };
+    }
+    edited!(
+        theme,
+        language,
+        items_per_page,
+        thumb_compression,
+        image_compression,
+        clipboard_tag,
+        clipboard_description,
+        description_from_filename,
+        file_name_pattern,
+        external_editor,
+        wallpaper_tag,
+        wallpaper_interval,
+        db_retry_attempts,
+        db_retry_backoff_ms,
+        db_wal,
+        db_busy_timeout_ms,
+        high_contrast,
+        reduce_motion,
+        grid_columns,
+        grid_layout,
+        image_cache_mb,
+        import_filter,
+        maintenance,
+        watermark,
+        export_presets,
+        quick_look_delay_ms,
+        fuzzy_search,
+        delete_behavior,
+        update_check,
+        update_url,
+    );
+}
 
 impl Preferences {
     pub fn new() -> (Self, Task<Message>) {
-        let settings = get_settings();
-        let selected_language = settings.config.language.clone();
-        let theme = settings.config.theme.clone();
-        let items_per_page = settings.config.items_per_page;
-        let thumb_compression = settings.config.thumb_compression.unwrap_or(9);
-        let image_compression = settings.config.image_compression.unwrap_or(5);
+        let saved = Box::new(get_settings().config.clone());
         let available_languages = rust_i18n::available_locales!()
             .iter()
             .map(|l| l.to_string())
@@ -45,64 +321,570 @@ impl Preferences {
         (
             Self {
                 available_languages,
-                selected_language,
-                theme,
-                items_per_page,
-                thumb_compression,
-                image_compression,
+                items_per_page_input: saved.items_per_page.to_string(),
+                draft: (*saved).clone(),
+                saved,
+                renaming: false,
+                verifying: false,
+                integrity_report: None,
+                library_location: data_location(),
+                library_move: library_location_service::pending_move(),
+                release: update_service::available().map(Box::new),
+                updating: false,
+                maintenance_runs: maintenance_service::last_runs(),
+                running_tasks: HashSet::new(),
+                confirm_reset: false,
             },
             Task::none(),
         )
     }
 
+    /// Reloads the screen state from the global settings, discarding the draft
+    fn sync_from_settings(&mut self) {
+        *self.saved = get_settings().config.clone();
+        self.draft = (*self.saved).clone();
+        self.items_per_page_input = self.saved.items_per_page.to_string();
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.draft != *self.saved
+            || self.items_per_page_input != self.saved.items_per_page.to_string()
+    }
+
+    /// Returns the first validation error of the draft, if any
+    fn validation_error(&self) -> Option<String> {
+        if let Some(pattern) = &self.draft.file_name_pattern
+            && !pattern.contains("{id}")
+        {
+            return Some(t!("preferences.validation.file_name_pattern").to_string());
+        }
+
+        if let Some(presets) = &self.draft.export_presets
+            && presets.iter().any(|preset| preset.name.trim().is_empty())
+        {
+            return Some(t!("preferences.validation.export_preset_name").to_string());
+        }
+
+        match self.items_per_page_input.trim().parse::<u64>() {
+            Ok(value) if (1..=MAX_ITEMS_PER_PAGE).contains(&value) => None,
+            _ => Some(
+                t!(
+                    "preferences.validation.items_per_page",
+                    max = MAX_ITEMS_PER_PAGE
+                )
+                .to_string(),
+            ),
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Action {
         match message {
             Message::LanguageChanged(language) => {
-                let mut settings = get_settings_mut();
-                settings.config.language = language;
-                if let Err(err) = settings.save() {
-                    eprintln!("Failed to save settings: {}", err);
-                }
-                rust_i18n::set_locale(&settings.config.language);
-                self.selected_language = settings.config.language.clone();
-                Action::UpdateUI()
+                self.draft.language = language;
+                Action::None
             }
             Message::ThemeChanged(theme) => {
-                let mut settings = get_settings_mut();
-                settings.config.theme = theme;
-                if let Err(err) = settings.save() {
-                    error!("Failed to save settings: {}", err);
-                }
-                self.theme = settings.config.theme.clone();
-                Action::UpdateUI()
-            }
-            Message::ItemsPerPageChanged(items_per_page) => {
-                self.items_per_page = items_per_page.clamp(1, 100);
-                let mut settings = get_settings_mut();
-                settings.config.items_per_page = self.items_per_page;
-                if let Err(err) = settings.save() {
-                    error!("Failed to save settings: {}", err);
+                self.draft.theme = theme;
+                Action::None
+            }
+            Message::HighContrastToggled(enabled) => {
+                self.draft.high_contrast = Some(enabled);
+                Action::None
+            }
+            Message::ReduceMotionToggled(enabled) => {
+                self.draft.reduce_motion = Some(enabled);
+                Action::None
+            }
+            Message::FuzzySearchToggled(enabled) => {
+                self.draft.fuzzy_search = Some(enabled);
+                Action::None
+            }
+            Message::ItemsPerPageChanged(input) => {
+                if input.chars().all(|c| c.is_ascii_digit()) {
+                    if let Ok(value) = input.parse::<u64>() {
+                        self.draft.items_per_page = value;
+                    }
+                    self.items_per_page_input = input;
                 }
                 Action::None
             }
+            Message::GridColumnsChanged(GridColumns(columns)) => {
+                self.draft.grid_columns = Some(columns);
+                Action::None
+            }
+            Message::GridLayoutChanged(layout) => {
+                self.draft.grid_layout = Some(layout);
+                Action::None
+            }
+            Message::DeleteBehaviorChanged(behavior) => {
+                self.draft.delete_behavior = Some(behavior);
+                Action::None
+            }
+            Message::QuickLookDelayChanged(QuickLookDelay(millis)) => {
+                self.draft.quick_look_delay_ms = Some(millis);
+                Action::None
+            }
+            Message::ImageCacheChanged(CacheBudget(megabytes)) => {
+                self.draft.image_cache_mb = Some(megabytes);
+                Action::None
+            }
             Message::ThumbCompressionChanged(compression) => {
-                self.thumb_compression = compression.clamp(0, 9);
-                let mut settings = get_settings_mut();
-                settings.config.thumb_compression = Some(self.thumb_compression);
-                if let Err(err) = settings.save() {
-                    error!("Failed to save settings: {}", err);
-                }
+                self.draft.thumb_compression = Some(compression.clamp(0, 9));
                 Action::None
             }
             Message::ImageCompressionChanged(compression) => {
-                self.image_compression = compression.clamp(0, 9);
-                let mut settings = get_settings_mut();
-                settings.config.image_compression = Some(self.image_compression);
-                if let Err(err) = settings.save() {
-                    error!("Failed to save settings: {}", err);
+                self.draft.image_compression = Some(compression.clamp(0, 9));
+                Action::None
+            }
+            Message::ClipboardTagChanged(tag) => {
+                self.draft.clipboard_tag = Some(tag).filter(|t| !t.is_empty());
+                Action::None
+            }
+            Message::ClipboardDescriptionChanged(template) => {
+                self.draft.clipboard_description = Some(template).filter(|t| !t.is_empty());
+                Action::None
+            }
+            Message::DescriptionFromFilenameToggled(enabled) => {
+                self.draft.description_from_filename = Some(enabled);
+                Action::None
+            }
+            Message::FileNamePatternChanged(pattern) => {
+                self.draft.file_name_pattern = Some(pattern).filter(|p| !p.is_empty());
+                Action::None
+            }
+            Message::ImportFilterChanged(filter) => {
+                self.draft.import_filter = Some(filter);
+                Action::None
+            }
+            Message::ExternalEditorChanged(command) => {
+                self.draft.external_editor = Some(command).filter(|c| !c.is_empty());
+                Action::None
+            }
+            Message::WatermarkChanged(watermark) => {
+                self.draft.watermark = Some(watermark);
+                Action::None
+            }
+            Message::ChooseWatermarkImage => Action::Run(Task::perform(
+                async {
+                    AsyncFileDialog::new()
+                        .add_filter("PNG", &["png"])
+                        .pick_file()
+                        .await
+                },
+                |file| match file {
+                    Some(file) => Message::WatermarkImageChosen(file.path().to_path_buf()),
+                    None => Message::NoOps,
+                },
+            )),
+            Message::AddExportPreset => {
+                let presets = self.draft.export_presets.get_or_insert_default();
+                presets.push(ExportPreset {
+                    name: t!("preferences.export_presets.new", number = presets.len() + 1)
+                        .to_string(),
+                    ..Default::default()
+                });
+                Action::None
+            }
+            Message::RemoveExportPreset(index) => {
+                if let Some(presets) = self.draft.export_presets.as_mut()
+                    && index < presets.len()
+                {
+                    presets.remove(index);
+                }
+                Action::None
+            }
+            Message::ExportPresetChanged(index, preset) => {
+                if let Some(slot) = self
+                    .draft
+                    .export_presets
+                    .as_mut()
+                    .and_then(|presets| presets.get_mut(index))
+                {
+                    *slot = preset;
+                }
+                Action::None
+            }
+            Message::WatermarkImageChosen(path) => {
+                let watermark = self.draft.watermark.get_or_insert_default();
+                watermark.kind = WatermarkKind::Image;
+                watermark.image_path = path.to_string_lossy().to_string();
+                Action::None
+            }
+            Message::WallpaperTagChanged(tag) => {
+                self.draft.wallpaper_tag = Some(tag).filter(|t| !t.is_empty());
+                Action::None
+            }
+            Message::WallpaperIntervalChanged(interval) => {
+                self.draft.wallpaper_interval = Some(interval.0);
+                Action::None
+            }
+            Message::DbRetryAttemptsChanged(attempts) => {
+                self.draft.db_retry_attempts = Some(attempts.clamp(1, 10));
+                Action::None
+            }
+            Message::DbWalToggled(enabled) => {
+                self.draft.db_wal = Some(enabled);
+                Action::None
+            }
+            Message::DbBusyTimeoutChanged(timeout) => {
+                self.draft.db_busy_timeout_ms = Some(timeout as u64);
+                Action::None
+            }
+            Message::DbRetryBackoffChanged(backoff) => {
+                self.draft.db_retry_backoff_ms = Some(backoff as u64);
+                Action::None
+            }
+            Message::RotateWallpaperNow => {
+                let Some(tag) = self.draft.wallpaper_tag.clone() else {
+                    return Action::None;
+                };
+                Action::Run(Task::perform(
+                    wallpaper_service::rotate_wallpaper(tag),
+                    |result| {
+                        match result {
+                            Ok(Some(_)) => push_success(t!("message.wallpaper.success")),
+                            Ok(None) => push_error(t!("message.wallpaper.no_image")),
+                            Err(err) => {
+                                error!("Failed to rotate wallpaper: {}", err);
+                                push_error(t!("message.wallpaper.error"));
+                            }
+                        }
+                        Message::NoOps
+                    },
+                ))
+            }
+            Message::OpenRules => Action::OpenRules,
+            Message::RenameStoredFiles => {
+                self.renaming = true;
+                Action::Run(Task::perform(
+                    image_service::rename_stored_files(),
+                    Message::StoredFilesRenamed,
+                ))
+            }
+            Message::StoredFilesRenamed(result) => {
+                self.renaming = false;
+                match result {
+                    Ok(count) => {
+                        push_success(t_count!("message.preferences.rename.success", count))
+                    }
+                    Err(err) => {
+                        error!("Failed to rename stored files: {}", err);
+                        push_error(t!("message.preferences.rename.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::RequestLibraryMove(portable) => {
+                match library_location_service::request_move(portable) {
+                    Ok(target) => self.library_move = Some(target),
+                    Err(err) => {
+                        error!("Failed to request the library move: {}", err);
+                        push_error(t!("message.preferences.library.move_error"));
+                    }
+                }
+                Action::None
+            }
+            Message::CancelLibraryMove => {
+                match library_location_service::cancel_move() {
+                    Ok(()) => self.library_move = None,
+                    Err(err) => {
+                        error!("Failed to cancel the library move: {}", err);
+                        push_error(t!("message.preferences.library.move_error"));
+                    }
                 }
                 Action::None
             }
+            Message::KeepLibraryPortable => {
+                match library_location_service::keep_portable() {
+                    Ok(()) => {
+                        self.library_location = DataLocation::Portable;
+                        push_success(t!("message.preferences.library.kept_portable"));
+                    }
+                    Err(err) => {
+                        error!("Failed to make the library portable: {}", err);
+                        push_error(t!("message.preferences.library.move_error"));
+                    }
+                }
+                Action::None
+            }
+            Message::UpdateCheckToggled(enabled) => {
+                self.draft.update_check = Some(enabled);
+                Action::None
+            }
+            Message::UpdateUrlChanged(url) => {
+                self.draft.update_url = Some(url);
+                Action::None
+            }
+            Message::CheckForUpdates => {
+                self.updating = true;
+                Action::Run(Task::perform(
+                    update_service::check(
+                        update_service::update_url(&self.draft),
+                        env!("CARGO_PKG_VERSION"),
+                    ),
+                    Message::UpdateChecked,
+                ))
+            }
+            Message::UpdateChecked(result) => {
+                self.updating = false;
+                match result {
+                    Ok(Some(release)) => self.release = Some(Box::new(release)),
+                    Ok(None) => {
+                        self.release = None;
+                        push_success(t!("message.preferences.update.up_to_date"));
+                    }
+                    Err(err) => {
+                        error!("Failed to check for updates: {}", err);
+                        push_error(t!("message.preferences.update.check_error"));
+                    }
+                }
+                Action::None
+            }
+            Message::DownloadUpdate => {
+                let Some(release) = self.release.as_deref() else {
+                    return Action::None;
+                };
+                self.updating = true;
+                Action::Run(Task::perform(
+                    update_service::download(release.clone()),
+                    Message::UpdateDownloaded,
+                ))
+            }
+            Message::UpdateDownloaded(result) => {
+                self.updating = false;
+                let path = match result {
+                    Ok(path) => path,
+                    Err(err) => {
+                        error!("Failed to download the update: {}", err);
+                        push_error(t!("message.preferences.update.download_error"));
+                        return Action::None;
+                    }
+                };
+                match update_service::launch_installer(&path) {
+                    // The installer replaces the running executable
+                    Ok(true) => Action::Run(iced::exit()),
+                    Ok(false) => {
+                        push_success(t!(
+                            "message.preferences.update.downloaded",
+                            path = path.display().to_string()
+                        ));
+                        Action::None
+                    }
+                    Err(err) => {
+                        error!("Failed to start the installer: {}", err);
+                        push_error(t!("message.preferences.update.download_error"));
+                        Action::None
+                    }
+                }
+            }
+            Message::VerifyLibrary => {
+                self.verifying = true;
+                Action::Run(Task::perform(
+                    integrity_service::verify_library(),
+                    Message::LibraryVerified,
+                ))
+            }
+            Message::LibraryVerified(result) => {
+                self.verifying = false;
+                match result {
+                    Ok(report) => {
+                        if report.issues.is_empty() {
+                            push_success(t!("message.preferences.verify.success"));
+                        } else {
+                            push_error(t_count!(
+                                "message.preferences.verify.issues",
+                                report.issues.len()
+                            ));
+                        }
+                        self.integrity_report = Some(report);
+                    }
+                    Err(err) => {
+                        error!("Failed to verify library: {}", err);
+                        push_error(t!("message.preferences.verify.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::RestoreFromBackup => Action::Run(Task::perform(
+                async {
+                    AsyncFileDialog::new()
+                        .set_title(t!("preferences.button.restore_backup"))
+                        .pick_folder()
+                        .await
+                },
+                |folder| match folder {
+                    Some(folder) => Message::BackupFolderChosen(folder.path().to_path_buf()),
+                    None => Message::NoOps,
+                },
+            )),
+            Message::BackupFolderChosen(path) => {
+                let issues = self
+                    .integrity_report
+                    .as_ref()
+                    .map(|r| r.issues.clone())
+                    .unwrap_or_default();
+                self.verifying = true;
+                Action::Run(Task::perform(
+                    integrity_service::restore_from_backup(path, issues),
+                    Message::RestoreFinished,
+                ))
+            }
+            Message::RestoreFinished(result) => {
+                match result {
+                    Ok(count) => {
+                        push_success(t_count!("message.preferences.restore.success", count))
+                    }
+                    Err(err) => {
+                        error!("Failed to restore from backup: {}", err);
+                        push_error(t!("message.preferences.restore.error"));
+                    }
+                }
+                // Verify again so the list reflects what is still broken
+                Action::Run(Task::done(Message::VerifyLibrary))
+            }
+            Message::Apply => {
+                if let Some(err) = self.validation_error() {
+                    push_error(err);
+                    return Action::None;
+                }
+                {
+                    let mut settings = get_settings_mut();
+                    // Only what changed here, the rest may have changed elsewhere since
+                    let previous = settings.config.clone();
+                    write_edits(&self.draft, &self.saved, &mut settings.config);
+                    if let Err(err) = settings.save() {
+                        error!("Failed to save settings: {}", err);
+                        settings.config = previous;
+                        push_error(t!("message.preferences.apply.error"));
+                        return Action::None;
+                    }
+                    rust_i18n::set_locale(&settings.config.language);
+                }
+                image_cache::trim();
+                self.sync_from_settings();
+                push_success(t!("message.preferences.apply.success"));
+                Action::None
+            }
+            Message::Cancel => {
+                self.sync_from_settings();
+                Action::None
+            }
+            Message::ResetToDefaults => {
+                self.confirm_reset = true;
+                Action::None
+            }
+            Message::CancelReset => {
+                self.confirm_reset = false;
+                Action::None
+            }
+            Message::ConfirmReset => {
+                self.confirm_reset = false;
+                {
+                    let mut settings = get_settings_mut();
+                    if let Err(err) = settings.reset() {
+                        error!("Failed to reset settings: {}", err);
+                        push_error(t!("message.preferences.reset.error"));
+                        return Action::None;
+                    }
+                    rust_i18n::set_locale(&settings.config.language);
+                }
+                image_cache::trim();
+                self.sync_from_settings();
+                push_success(t!("message.preferences.reset.success"));
+                Action::None
+            }
+            Message::ExportSettings => Action::Run(Task::perform(
+                async {
+                    AsyncFileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .set_file_name("config.json")
+                        .save_file()
+                        .await
+                },
+                |maybe| match maybe {
+                    Some(file) => Message::ExportPathChosen(file.path().to_path_buf()),
+                    None => Message::NoOps,
+                },
+            )),
+            Message::ImportSettings => Action::Run(Task::perform(
+                async {
+                    AsyncFileDialog::new()
+                        .add_filter("JSON", &["json"])
+                        .pick_file()
+                        .await
+                },
+                |maybe| match maybe {
+                    Some(file) => Message::ImportPathChosen(file.path().to_path_buf()),
+                    None => Message::NoOps,
+                },
+            )),
+            Message::ExportPathChosen(path) => {
+                match get_settings().export_to(&path) {
+                    Ok(()) => push_success(t!("message.preferences.export.success")),
+                    Err(err) => {
+                        error!("Failed to export settings: {}", err);
+                        push_error(t!("message.preferences.export.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::ImportPathChosen(path) => {
+                {
+                    let mut settings = get_settings_mut();
+                    match settings.import_from(&path) {
+                        Ok(report) if report.defaults_applied() => {
+                            push_warning(defaults_applied_message(&report));
+                        }
+                        Ok(_) => push_success(t!("message.preferences.import.success")),
+                        Err(err) => {
+                            error!("Failed to import settings: {}", err);
+                            push_error(t!("message.preferences.import.error"));
+                            return Action::None;
+                        }
+                    }
+                    rust_i18n::set_locale(&settings.config.language);
+                }
+                self.sync_from_settings();
+                Action::None
+            }
+            Message::MaintenanceToggled(task, enabled) => {
+                let schedule = self.draft.maintenance_schedule(task);
+                self.draft
+                    .maintenance
+                    .get_or_insert_default()
+                    .insert(task, TaskSchedule { enabled, ..schedule });
+                Action::None
+            }
+            Message::MaintenanceIntervalChanged(task, MaintenanceInterval(hours)) => {
+                let schedule = self.draft.maintenance_schedule(task);
+                self.draft.maintenance.get_or_insert_default().insert(
+                    task,
+                    TaskSchedule {
+                        interval_hours: hours,
+                        ..schedule
+                    },
+                );
+                Action::None
+            }
+            Message::RunMaintenanceTask(task) => {
+                self.running_tasks.insert(task);
+                Action::Run(Task::perform(maintenance_service::run(task), move |run| {
+                    Message::MaintenanceTaskFinished(task, run)
+                }))
+            }
+            Message::MaintenanceTaskFinished(task, run) => {
+                self.running_tasks.remove(&task);
+                match &run.error {
+                    Some(err) => push_error(t!(
+                        "message.maintenance.error",
+                        task = task.to_string(),
+                        err = err
+                    )),
+                    None => push_success(t!("message.maintenance.success", task = task.to_string())),
+                }
+                self.maintenance_runs.insert(task, run);
+                Action::None
+            }
             Message::NoOps => Action::None,
         }
     }
@@ -115,7 +897,7 @@ impl Preferences {
             t!("preferences.label.language").to_string(),
             PickList::new(
                 language_options,
-                Some(self.selected_language.clone()),
+                Some(self.draft.language.clone()),
                 Message::LanguageChanged,
             )
             .placeholder(t!("preferences.select.language"))
@@ -126,29 +908,205 @@ impl Preferences {
         // Theme Section
         let theme_section = self.create_section(
             t!("preferences.label.theme").to_string(),
-            PickList::new(THEMES, Some(self.theme.as_str()), |theme| {
-                Message::ThemeChanged(theme.to_string())
-            })
-            .placeholder(t!("preferences.select.theme"))
-            .style(Modern::pick_list())
-            .width(Length::Fill),
+            Column::new()
+                .spacing(12)
+                .push(
+                    PickList::new(THEMES, Some(self.draft.theme.as_str()), |theme| {
+                        Message::ThemeChanged(theme.to_string())
+                    })
+                    .placeholder(t!("preferences.select.theme"))
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Checkbox::new(
+                        t!("preferences.label.high_contrast"),
+                        self.draft.high_contrast.unwrap_or(false),
+                    )
+                    .on_toggle(Message::HighContrastToggled)
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+                )
+                .push(
+                    Checkbox::new(
+                        t!("preferences.label.reduce_motion"),
+                        self.draft.reduce_motion.unwrap_or(false),
+                    )
+                    .on_toggle(Message::ReduceMotionToggled)
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+                ),
         );
 
         // Items per Page Section
         let items_section = self.create_section(
             t!("preferences.label.items_per_page").to_string(),
-            number_input(self.items_per_page, 100, Message::ItemsPerPageChanged)
+            TextInput::new("", &self.items_per_page_input)
+                .on_input(Message::ItemsPerPageChanged)
+                .on_submit(Message::Apply)
+                .padding(Padding::new(12.0))
+                .size(16)
                 .style(Modern::text_input())
                 .width(Length::Fill),
         );
 
+        // Search Section
+        let search_section = self.create_section(
+            t!("preferences.label.search").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.search.fuzzy_hint"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    Checkbox::new(
+                        t!("preferences.label.fuzzy_search"),
+                        self.draft.fuzzy_search.unwrap_or(false),
+                    )
+                    .on_toggle(Message::FuzzySearchToggled)
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+                ),
+        );
+
+        // Grid Section
+        let grid_section = self.create_section(
+            t!("preferences.label.grid").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.label.grid_layout"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        GridLayout::ALL,
+                        Some(self.draft.grid_layout.unwrap_or_default()),
+                        Message::GridLayoutChanged,
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Text::new(t!("preferences.label.grid_columns"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        GRID_COLUMNS,
+                        Some(GridColumns(self.draft.grid_columns.unwrap_or(0))),
+                        Message::GridColumnsChanged,
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Text::new(t!("preferences.label.quick_look"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        QUICK_LOOK_DELAYS,
+                        Some(QuickLookDelay(self.draft.quick_look_delay_ms.unwrap_or(0))),
+                        Message::QuickLookDelayChanged,
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                ),
+        );
+
+        // Image Cache Section
+        let image_cache_section = self.create_section(
+            t!("preferences.label.image_cache").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.image_cache.hint"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        CACHE_BUDGETS,
+                        Some(CacheBudget(self.draft.image_cache_mb.unwrap_or(256))),
+                        Message::ImageCacheChanged,
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                ),
+        );
+
         // Thumb Compression Section
         let thumb_compression_section = self.create_compression_section(
             t!("preferences.label.thumb_compression").to_string(),
-            self.thumb_compression,
+            self.draft.thumb_compression.unwrap_or(9),
             Message::ThumbCompressionChanged,
         );
 
+        // Clipboard Capture Section
+        let clipboard_section = self.create_section(
+            t!("preferences.label.clipboard").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.label.clipboard_tag"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    TextInput::new(
+                        &t!("preferences.placeholder.clipboard_tag"),
+                        self.draft.clipboard_tag.as_deref().unwrap_or_default(),
+                    )
+                    .on_input(Message::ClipboardTagChanged)
+                    .padding(Padding::new(12.0))
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Text::new(t!("preferences.label.clipboard_description"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    TextInput::new(
+                        &t!("preferences.placeholder.clipboard_description"),
+                        self.draft
+                            .clipboard_description
+                            .as_deref()
+                            .unwrap_or_default(),
+                    )
+                    .on_input(Message::ClipboardDescriptionChanged)
+                    .padding(Padding::new(12.0))
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::Fill),
+                ),
+        );
+
+        // External Editor Section
+        let editor_section = self.create_section(
+            t!("preferences.label.external_editor").to_string(),
+            TextInput::new(
+                &t!("preferences.placeholder.external_editor"),
+                self.draft.external_editor.as_deref().unwrap_or_default(),
+            )
+            .on_input(Message::ExternalEditorChanged)
+            .padding(Padding::new(12.0))
+            .size(16)
+            .style(Modern::text_input())
+            .width(Length::Fill),
+        );
+
         let scrollable = Scrollable::new(
             Column::new()
                 .padding(20)
@@ -163,13 +1121,30 @@ impl Preferences {
                         .size(16)
                         .style(Modern::secondary_text()),
                 )
+                .push(self.create_apply_bar())
                 .push(
                     Column::new()
                         .spacing(25)
                         .push(language_section)
                         .push(theme_section)
                         .push(items_section)
+                        .push(search_section)
+                        .push(grid_section)
                         .push(thumb_compression_section)
+                        .push(image_cache_section)
+                        .push(self.create_import_section())
+                        .push(clipboard_section)
+                        .push(editor_section)
+                        .push(self.create_delete_section())
+                        .push(self.create_export_presets_section())
+                        .push(self.create_watermark_section())
+                        .push(self.create_wallpaper_section())
+                        .push(self.create_database_section())
+                        .push(self.create_library_location_section())
+                        .push(self.create_updates_section())
+                        .push(self.create_integrity_section())
+                        .push(self.create_maintenance_section())
+                        .push(self.create_backup_section()),
                 ),
         );
 
@@ -196,6 +1171,843 @@ impl Preferences {
         .into()
     }
 
+    fn create_apply_bar<'a>(&self) -> Element<'a, Message> {
+        let dirty = self.is_dirty();
+        let error = self.validation_error();
+
+        let status: Element<'a, Message> = if let Some(err) = error.clone() {
+            Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("circle-exclamation").size(16.0))
+                .push(Text::new(err).size(16).style(Modern::error_text()))
+                .into()
+        } else if dirty {
+            Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("pen").size(16.0))
+                .push(
+                    Text::new(t!("preferences.status.unsaved"))
+                        .size(16)
+                        .style(Modern::warning_text()),
+                )
+                .into()
+        } else {
+            Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("check").size(16.0))
+                .push(
+                    Text::new(t!("preferences.status.saved"))
+                        .size(16)
+                        .style(Modern::secondary_text()),
+                )
+                .into()
+        };
+
+        let mut apply_button = Button::new(
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("floppy-disk").size(16.0))
+                .push(Text::new(t!("preferences.button.apply")).size(16)),
+        )
+        .padding(Padding::from([12, 20]));
+
+        let mut cancel_button = Button::new(
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("xmark").size(16.0))
+                .push(Text::new(t!("preferences.button.cancel")).size(16)),
+        )
+        .padding(Padding::from([12, 20]));
+
+        if dirty && error.is_none() {
+            apply_button = apply_button
+                .style(Modern::success_button())
+                .on_press(Message::Apply);
+        } else {
+            apply_button = apply_button.style(Modern::secondary_button());
+        }
+
+        if dirty {
+            cancel_button = cancel_button
+                .style(Modern::danger_button())
+                .on_press(Message::Cancel);
+        } else {
+            cancel_button = cancel_button.style(Modern::secondary_button());
+        }
+
+        Container::new(
+            Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(Container::new(status).width(Length::Fill))
+                .push(cancel_button)
+                .push(apply_button),
+        )
+        .padding(20)
+        .style(Modern::floating_container())
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn create_delete_section(&self) -> Element<'_, Message> {
+        self.create_section(
+            t!("preferences.label.delete").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.delete.hint"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        DeleteBehavior::ALL,
+                        Some(self.draft.delete_behavior.unwrap_or_default()),
+                        Message::DeleteBehaviorChanged,
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                ),
+        )
+    }
+
+    fn create_import_section(&self) -> Element<'_, Message> {
+        let rename_button = Button::new(
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("i-cursor").size(16.0))
+                .push(Text::new(t!("preferences.button.rename_files")).size(16)),
+        )
+        .padding(Padding::from([12, 20]))
+        .style(Modern::secondary_button())
+        .on_press_maybe((!self.renaming).then_some(Message::RenameStoredFiles));
+
+        let rules_button = Button::new(
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("wand-magic-sparkles").size(16.0))
+                .push(Text::new(t!("preferences.button.import_rules")).size(16)),
+        )
+        .padding(Padding::from([12, 20]))
+        .style(Modern::secondary_button())
+        .on_press(Message::OpenRules);
+
+        self.create_section(
+            t!("preferences.label.import").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Checkbox::new(
+                        t!("preferences.label.description_from_filename"),
+                        self.draft.description_from_filename.unwrap_or(false),
+                    )
+                    .on_toggle(Message::DescriptionFromFilenameToggled)
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+                )
+                .push(
+                    Text::new(t!("preferences.label.file_name_pattern"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    TextInput::new(
+                        DEFAULT_FILE_NAME_PATTERN,
+                        self.draft.file_name_pattern.as_deref().unwrap_or_default(),
+                    )
+                    .on_input(Message::FileNamePatternChanged)
+                    .padding(Padding::new(12.0))
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::Fill),
+                )
+                .push(rename_button)
+                .push(
+                    Text::new(t!("preferences.label.import_filter"))
+                        .size(16)
+                        .style(Modern::primary_text()),
+                )
+                .push(import_filter_editor(
+                    self.draft.import_filter.as_ref().unwrap_or(&ImportFilter::default()),
+                    Message::ImportFilterChanged,
+                ))
+                .push(rules_button),
+        )
+    }
+
+    fn create_export_presets_section(&self) -> Element<'_, Message> {
+        let mut list = Column::new().spacing(12).push(
+            Text::new(t!("preferences.export_presets.hint"))
+                .size(14)
+                .style(Modern::secondary_text()),
+        );
+
+        for (index, preset) in self.draft.export_presets.iter().flatten().enumerate() {
+            let changed = move |change: &dyn Fn(&mut ExportPreset)| {
+                let mut preset = preset.clone();
+                change(&mut preset);
+                Message::ExportPresetChanged(index, preset)
+            };
+            let max_side = if preset.max_side == 0 {
+                String::new()
+            } else {
+                preset.max_side.to_string()
+            };
+
+            let mut row = Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(
+                    TextInput::new(&t!("preferences.placeholder.export_preset_name"), &preset.name)
+                        .on_input(move |name| changed(&|preset| preset.name = name.clone()))
+                        .padding(Padding::new(10.0))
+                        .size(14)
+                        .style(Modern::text_input())
+                        .width(Length::Fill),
+                )
+                .push(
+                    TextInput::new(&t!("preferences.placeholder.export_preset_side"), &max_side)
+                        .on_input(move |input| {
+                            let digits: String =
+                                input.chars().filter(|c| c.is_ascii_digit()).collect();
+                            let side = digits.parse().unwrap_or(0);
+                            changed(&|preset| preset.max_side = side)
+                        })
+                        .padding(Padding::new(10.0))
+                        .size(14)
+                        .style(Modern::text_input())
+                        .width(Length::Fixed(140.0)),
+                )
+                .push(
+                    PickList::new(ExportFormat::ALL, Some(preset.format), move |format| {
+                        changed(&|preset| preset.format = format)
+                    })
+                    .style(Modern::pick_list())
+                    .width(Length::Fixed(140.0)),
+                );
+            if preset.format == ExportFormat::Jpeg {
+                row = row.push(
+                    Column::new()
+                        .spacing(4)
+                        .width(Length::Fixed(140.0))
+                        .push(
+                            Text::new(t!(
+                                "preferences.label.export_preset_quality",
+                                quality = preset.quality
+                            ))
+                            .size(12)
+                            .style(Modern::secondary_text()),
+                        )
+                        .push(Slider::new(1..=100u8, preset.quality, move |quality| {
+                            changed(&|preset| preset.quality = quality)
+                        })),
+                );
+            }
+            row = row.push(
+                Button::new(fa_icon_solid("trash").size(14.0))
+                    .padding(Padding::from([8, 12]))
+                    .style(Modern::danger_button())
+                    .on_press(Message::RemoveExportPreset(index)),
+            );
+            list = list.push(row);
+        }
+
+        list = list.push(
+            Button::new(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid("plus").size(16.0))
+                    .push(Text::new(t!("preferences.button.add_export_preset")).size(16)),
+            )
+            .padding(Padding::from([12, 20]))
+            .style(Modern::secondary_button())
+            .on_press(Message::AddExportPreset),
+        );
+
+        self.create_section(t!("preferences.label.export_presets").to_string(), list)
+    }
+
+    fn create_watermark_section(&self) -> Element<'_, Message> {
+        let watermark = self.draft.watermark.clone().unwrap_or_default();
+        let changed = |change: fn(&mut Watermark, String)| {
+            let watermark = watermark.clone();
+            move |value: String| {
+                let mut watermark = watermark.clone();
+                change(&mut watermark, value);
+                Message::WatermarkChanged(watermark)
+            }
+        };
+
+        let source: Element<'_, Message> = match watermark.kind {
+            WatermarkKind::Text => TextInput::new(
+                &t!("preferences.placeholder.watermark_text"),
+                &watermark.text,
+            )
+            .on_input(changed(|watermark, text| watermark.text = text))
+            .padding(Padding::new(12.0))
+            .size(16)
+            .style(Modern::text_input())
+            .width(Length::Fill)
+            .into(),
+            WatermarkKind::Image => Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(
+                    TextInput::new(
+                        &t!("preferences.placeholder.watermark_image"),
+                        &watermark.image_path,
+                    )
+                    .on_input(changed(|watermark, path| watermark.image_path = path))
+                    .padding(Padding::new(12.0))
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Button::new(
+                        Row::new()
+                            .spacing(8)
+                            .align_y(Alignment::Center)
+                            .push(fa_icon_solid("folder-open").size(16.0))
+                            .push(Text::new(t!("preferences.button.choose_watermark")).size(16)),
+                    )
+                    .padding(Padding::from([12, 20]))
+                    .style(Modern::secondary_button())
+                    .on_press(Message::ChooseWatermarkImage),
+                )
+                .into(),
+        };
+
+        let with_kind = watermark.clone();
+        let with_position = watermark.clone();
+        let with_opacity = watermark.clone();
+
+        self.create_section(
+            t!("preferences.label.watermark").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.watermark.hint"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(WatermarkKind::ALL, Some(watermark.kind), move |kind| {
+                        Message::WatermarkChanged(Watermark {
+                            kind,
+                            ..with_kind.clone()
+                        })
+                    })
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                )
+                .push(source)
+                .push(
+                    Text::new(t!("preferences.label.watermark_position"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        WatermarkPosition::ALL,
+                        Some(watermark.position),
+                        move |position| {
+                            Message::WatermarkChanged(Watermark {
+                                position,
+                                ..with_position.clone()
+                            })
+                        },
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Text::new(t!(
+                        "preferences.label.watermark_opacity",
+                        opacity = watermark.opacity
+                    ))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+                )
+                .push(
+                    Slider::new(0..=100u8, watermark.opacity, move |opacity| {
+                        Message::WatermarkChanged(Watermark {
+                            opacity,
+                            ..with_opacity.clone()
+                        })
+                    })
+                    .width(Length::Fill),
+                ),
+        )
+    }
+
+    fn create_wallpaper_section(&self) -> Element<'_, Message> {
+        let interval = RotationInterval(self.draft.wallpaper_interval.unwrap_or(0));
+
+        self.create_section(
+            t!("preferences.label.wallpaper").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Text::new(t!("preferences.label.wallpaper_tag"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    TextInput::new(
+                        &t!("preferences.placeholder.wallpaper_tag"),
+                        self.draft.wallpaper_tag.as_deref().unwrap_or_default(),
+                    )
+                    .on_input(Message::WallpaperTagChanged)
+                    .padding(Padding::new(12.0))
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Text::new(t!("preferences.label.wallpaper_interval"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    PickList::new(
+                        ROTATION_INTERVALS,
+                        Some(interval),
+                        Message::WallpaperIntervalChanged,
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fill),
+                )
+                .push(
+                    Button::new(
+                        Row::new()
+                            .spacing(8)
+                            .align_y(Alignment::Center)
+                            .push(fa_icon_solid("shuffle").size(16.0))
+                            .push(Text::new(t!("preferences.button.rotate_wallpaper")).size(16)),
+                    )
+                    .padding(Padding::from([12, 20]))
+                    .style(Modern::secondary_button())
+                    .on_press_maybe(
+                        self.draft
+                            .wallpaper_tag
+                            .is_some()
+                            .then_some(Message::RotateWallpaperNow),
+                    ),
+                ),
+        )
+    }
+
+    fn create_database_section(&self) -> Element<'_, Message> {
+        let attempts = self.draft.db_retry_attempts.unwrap_or(3);
+        let backoff = self.draft.db_retry_backoff_ms.unwrap_or(100).min(2000) as u32;
+        let busy_timeout = self.draft.db_busy_timeout_ms.unwrap_or(5000).min(30_000) as u32;
+
+        let labeled_slider = |label: String, slider: Slider<'static, u32, Message>| {
+            Column::new()
+                .spacing(8)
+                .push(Text::new(label).size(14).style(Modern::secondary_text()))
+                .push(slider.width(Length::Fill))
+        };
+
+        self.create_section(
+            t!("preferences.label.database").to_string(),
+            Column::new()
+                .spacing(12)
+                .push(
+                    Checkbox::new(
+                        t!("preferences.label.db_wal"),
+                        self.draft.db_wal.unwrap_or(true),
+                    )
+                    .on_toggle(Message::DbWalToggled)
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+                )
+                .push(labeled_slider(
+                    t!("preferences.label.db_busy_timeout", ms = busy_timeout).to_string(),
+                    Slider::new(1000..=30_000, busy_timeout, Message::DbBusyTimeoutChanged)
+                        .step(1000u32),
+                ))
+                .push(
+                    Text::new(t!("preferences.hint.db_restart"))
+                        .size(12)
+                        .style(Modern::secondary_text()),
+                )
+                .push(labeled_slider(
+                    t!("preferences.label.db_retry_attempts", count = attempts).to_string(),
+                    Slider::new(1..=10, attempts, Message::DbRetryAttemptsChanged),
+                ))
+                .push(labeled_slider(
+                    t!("preferences.label.db_retry_backoff", ms = backoff).to_string(),
+                    Slider::new(50..=2000, backoff, Message::DbRetryBackoffChanged).step(50u32),
+                )),
+        )
+    }
+
+    /// Where the library is kept, and the assistant that moves it between the
+    /// folder of the executable and the per-user data directory
+    fn create_updates_section(&self) -> Element<'_, Message> {
+        let button = |icon: &'static str, label: String| {
+            Button::new(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid(icon).size(16.0))
+                    .push(Text::new(label).size(16)),
+            )
+            .padding(Padding::from([12, 20]))
+        };
+
+        let mut content = Column::new()
+            .spacing(12)
+            .push(
+                Text::new(t!(
+                    "preferences.updates.current",
+                    version = env!("CARGO_PKG_VERSION")
+                ))
+                .size(16),
+            )
+            .push(
+                Checkbox::new(
+                    t!("preferences.updates.check"),
+                    self.draft.update_check.unwrap_or(false),
+                )
+                .on_toggle(Message::UpdateCheckToggled)
+                .size(18)
+                .text_size(16)
+                .style(Modern::checkbox()),
+            )
+            .push(
+                Text::new(t!("preferences.updates.url"))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            )
+            .push(
+                TextInput::new(
+                    update_service::DEFAULT_UPDATE_URL,
+                    self.draft.update_url.as_deref().unwrap_or_default(),
+                )
+                .on_input(Message::UpdateUrlChanged)
+                .padding(Padding::new(12.0))
+                .size(16)
+                .style(Modern::text_input())
+                .width(Length::Fill),
+            );
+
+        let mut buttons = Row::new().spacing(10).push(
+            button(
+                "arrows-rotate",
+                t!("preferences.updates.check_now").to_string(),
+            )
+            .style(Modern::secondary_button())
+            .on_press_maybe((!self.updating).then_some(Message::CheckForUpdates)),
+        );
+        if let Some(release) = &self.release {
+            content = content.push(
+                Text::new(t!(
+                    "preferences.updates.available",
+                    version = release.version.as_str()
+                ))
+                .size(14)
+                .style(Modern::warning_text()),
+            );
+            if release.download_url.is_some() {
+                buttons = buttons.push(
+                    button("download", t!("preferences.updates.download").to_string())
+                        .style(Modern::primary_button())
+                        .on_press_maybe((!self.updating).then_some(Message::DownloadUpdate)),
+                );
+            }
+        }
+
+        self.create_section(
+            t!("preferences.label.updates").to_string(),
+            content.push(buttons),
+        )
+    }
+
+    fn create_library_location_section(&self) -> Element<'_, Message> {
+        let button = |icon: &'static str, label: String| {
+            Button::new(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid(icon).size(16.0))
+                    .push(Text::new(label).size(16)),
+            )
+            .padding(Padding::from([12, 20]))
+        };
+        let hint = |text: String| Text::new(text).size(14).style(Modern::secondary_text());
+
+        let location = match self.library_location {
+            DataLocation::Portable => t!("preferences.library.portable"),
+            DataLocation::User => t!("preferences.library.user"),
+            DataLocation::Legacy => t!("preferences.library.legacy"),
+        };
+        let mut content = Column::new()
+            .spacing(12)
+            .push(Text::new(location).size(16))
+            .push(hint(get_data_dir().display().to_string()));
+
+        if let Some(target) = &self.library_move {
+            return self.create_section(
+                t!("preferences.label.library_location").to_string(),
+                content
+                    .push(
+                        Text::new(t!(
+                            "preferences.library.pending",
+                            path = target.display().to_string()
+                        ))
+                        .size(14)
+                        .style(Modern::warning_text()),
+                    )
+                    .push(
+                        button("xmark", t!("preferences.library.cancel").to_string())
+                            .style(Modern::secondary_button())
+                            .on_press(Message::CancelLibraryMove),
+                    ),
+            );
+        }
+
+        if !library_location_service::can_move() {
+            content = content.push(hint(t!("preferences.library.fixed").to_string()));
+        } else {
+            let to_user = button("user", t!("preferences.library.move_to_user").to_string())
+                .style(Modern::primary_button())
+                .on_press(Message::RequestLibraryMove(false));
+            let buttons = match self.library_location {
+                DataLocation::Legacy => Row::new().spacing(10).push(to_user).push(
+                    button(
+                        "thumbtack",
+                        t!("preferences.library.keep_portable").to_string(),
+                    )
+                    .style(Modern::secondary_button())
+                    .on_press(Message::KeepLibraryPortable),
+                ),
+                DataLocation::Portable => Row::new().spacing(10).push(to_user),
+                DataLocation::User => Row::new().spacing(10).push(
+                    button(
+                        "box-archive",
+                        t!("preferences.library.move_to_portable").to_string(),
+                    )
+                    .style(Modern::secondary_button())
+                    .on_press(Message::RequestLibraryMove(true)),
+                ),
+            };
+            content = content
+                .push(hint(t!("preferences.library.hint").to_string()))
+                .push(buttons);
+        }
+
+        self.create_section(
+            t!("preferences.label.library_location").to_string(),
+            content,
+        )
+    }
+
+    fn create_integrity_section(&self) -> Element<'_, Message> {
+        let button = |icon: &'static str, label: String| {
+            Button::new(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid(icon).size(16.0))
+                    .push(Text::new(label).size(16)),
+            )
+            .padding(Padding::from([12, 20]))
+        };
+
+        let mut buttons = Row::new().spacing(10).push(
+            button(
+                "shield-halved",
+                t!("preferences.button.verify_library").to_string(),
+            )
+            .style(Modern::primary_button())
+            .on_press_maybe((!self.verifying).then_some(Message::VerifyLibrary)),
+        );
+
+        let mut content = Column::new().spacing(12);
+
+        if let Some(report) = &self.integrity_report {
+            content = content.push(
+                Text::new(format!(
+                    "{}, {}, {}",
+                    t_count!("preferences.label.verify_checked", report.checked),
+                    t_count!("preferences.label.verify_hashed", report.baselined),
+                    t_count!("preferences.label.verify_issues", report.issues.len())
+                ))
+                .size(14)
+                .style(Modern::secondary_text()),
+            );
+
+            for issue in &report.issues {
+                let problem = match issue.problem {
+                    IntegrityProblem::Modified => t!("preferences.label.integrity_modified"),
+                    IntegrityProblem::Missing => t!("preferences.label.integrity_missing"),
+                };
+                content = content.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("triangle-exclamation").size(14.0))
+                        .push(Text::new(problem).size(14).style(Modern::warning_text()))
+                        .push(
+                            Text::new(&issue.path)
+                                .size(14)
+                                .style(Modern::secondary_text()),
+                        ),
+                );
+            }
+
+            if !report.issues.is_empty() {
+                buttons = buttons.push(
+                    button(
+                        "clock-rotate-left",
+                        t!("preferences.button.restore_backup").to_string(),
+                    )
+                    .style(Modern::secondary_button())
+                    .on_press_maybe((!self.verifying).then_some(Message::RestoreFromBackup)),
+                );
+            }
+        }
+
+        self.create_section(
+            t!("preferences.label.integrity").to_string(),
+            content.push(buttons),
+        )
+    }
+
+    fn create_maintenance_section(&self) -> Element<'_, Message> {
+        let mut content = Column::new().spacing(16);
+
+        for task in MaintenanceTask::ALL {
+            let schedule = self.draft.maintenance_schedule(task);
+            let running = self.running_tasks.contains(&task);
+
+            let controls = Row::new()
+                .spacing(12)
+                .align_y(Alignment::Center)
+                .push(
+                    Checkbox::new(task.to_string(), schedule.enabled)
+                        .on_toggle(move |enabled| Message::MaintenanceToggled(task, enabled))
+                        .size(18)
+                        .text_size(16)
+                        .style(Modern::checkbox())
+                        .width(Length::Fill),
+                )
+                .push(
+                    PickList::new(
+                        MAINTENANCE_INTERVALS,
+                        Some(MaintenanceInterval(schedule.interval_hours)),
+                        move |interval| Message::MaintenanceIntervalChanged(task, interval),
+                    )
+                    .style(Modern::pick_list())
+                    .width(Length::Fixed(200.0)),
+                )
+                .push(
+                    Button::new(
+                        Row::new()
+                            .spacing(8)
+                            .align_y(Alignment::Center)
+                            .push(
+                                fa_icon_solid(if running { "hourglass-half" } else { "play" })
+                                    .size(14.0),
+                            )
+                            .push(Text::new(t!("preferences.button.run_now")).size(14)),
+                    )
+                    .padding(Padding::from([8, 14]))
+                    .style(Modern::secondary_button())
+                    .on_press_maybe((!running).then_some(Message::RunMaintenanceTask(task))),
+                );
+
+            content = content.push(
+                Column::new().spacing(4).push(controls).push(
+                    Text::new(last_run_status(task, self.maintenance_runs.get(&task)))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                ),
+            );
+        }
+
+        self.create_section(t!("preferences.label.maintenance").to_string(), content)
+    }
+
+    fn create_backup_section<'a>(&self) -> Element<'a, Message> {
+        let action_button = |icon: &'a str, label: String, message: Message| {
+            Button::new(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid(icon).size(16.0))
+                    .push(Text::new(label).size(16)),
+            )
+            .padding(Padding::from([12, 20]))
+            .on_press(message)
+        };
+
+        if self.confirm_reset {
+            let confirm = Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(Text::new(t!("preferences.label.confirm_reset")).size(16))
+                .push(
+                    Button::new(Text::new(t!("preferences.button.cancel")).size(16))
+                        .padding(Padding::from([12, 20]))
+                        .style(Modern::secondary_button())
+                        .on_press(Message::CancelReset),
+                )
+                .push(
+                    action_button(
+                        "rotate-left",
+                        t!("preferences.button.confirm_reset").to_string(),
+                        Message::ConfirmReset,
+                    )
+                    .style(Modern::danger_button()),
+                );
+            let title = t!("preferences.label.settings_file").to_string();
+            return self.create_section(title, confirm);
+        }
+
+        let buttons = Row::new()
+            .spacing(10)
+            .push(
+                action_button(
+                    "file-import",
+                    t!("preferences.button.import").to_string(),
+                    Message::ImportSettings,
+                )
+                .style(Modern::primary_button()),
+            )
+            .push(
+                action_button(
+                    "file-export",
+                    t!("preferences.button.export").to_string(),
+                    Message::ExportSettings,
+                )
+                .style(Modern::primary_button()),
+            )
+            .push(
+                action_button(
+                    "rotate-left",
+                    t!("preferences.button.reset").to_string(),
+                    Message::ResetToDefaults,
+                )
+                .style(Modern::danger_button()),
+            );
+
+        self.create_section(t!("preferences.label.settings_file").to_string(), buttons)
+    }
+
     fn create_compression_section<'a>(
         &self,
         title: String,
@@ -247,25 +2059,55 @@ impl Preferences {
     }
 }
 
-fn number_input<'a>(
-    value: u64,
-    max: u64,
-    on_change: impl Fn(u64) -> Message + 'a,
-) -> TextInput<'a, Message> {
-    TextInput::new("", &value.to_string())
-        .on_input(move |s| {
-            if let Ok(num) = s.parse::<u64>() {
-                if num <= max {
-                    on_change(num)
-                } else {
-                    on_change(max)
-                }
-            } else if s.is_empty() {
-                on_change(1)
-            } else {
-                on_change(value)
-            }
+/// Tells which settings of a loaded config went back to their default
+pub fn defaults_applied_message(report: &LoadReport) -> String {
+    let message = if report.reset_all {
+        t!("message.config.reset_all").to_string()
+    } else {
+        t!(
+            "message.config.reset_fields",
+            fields = report.reset_fields.join(", ")
+        )
+        .to_string()
+    };
+    match &report.backup {
+        Some(backup) => format!(
+            "{} {}",
+            message,
+            t!("message.config.backup", path = backup.display())
+        ),
+        None => message,
+    }
+}
+
+/// When a maintenance task last ran and what it did
+fn last_run_status(task: MaintenanceTask, run: Option<&TaskRun>) -> String {
+    let Some(run) = run else {
+        return t!("preferences.maintenance.never_run").to_string();
+    };
+    let time = chrono::DateTime::from_timestamp(run.finished_at, 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
         })
-        .padding(Padding::new(12.0))
-        .size(16)
+        .unwrap_or_default();
+
+    let result = match (&run.error, task) {
+        (Some(err), _) => t!("maintenance.result.failed", err = err).to_string(),
+        (None, MaintenanceTask::PruneThumbnails) => {
+            t_count!("maintenance.result.pruned", run.count).to_string()
+        }
+        (None, MaintenanceTask::Backup) => t!("maintenance.result.backup").to_string(),
+        (None, MaintenanceTask::VerifyIntegrity) => {
+            t_count!("maintenance.result.issues", run.count).to_string()
+        }
+        (None, MaintenanceTask::CleanOrphans) => {
+            t_count!("maintenance.result.orphans", run.count).to_string()
+        }
+        (None, MaintenanceTask::RepairFolders) => {
+            t_count!("maintenance.result.repaired", run.count).to_string()
+        }
+    };
+    t!("preferences.maintenance.last_run", time = time, result = result).to_string()
 }
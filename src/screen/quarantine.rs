@@ -0,0 +1,202 @@
+use crate::components::accessibility::labeled;
+use crate::dtos::quarantine_dto::QuarantinedFileDTO;
+use crate::services::toast_service::{push_error, push_success};
+use crate::services::{import_service, quarantine_service};
+use iced::widget::{Button, Column, Container, Row, Space, column, scrollable, text, tooltip};
+use iced::{Alignment, Element, Length, Padding, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use log::error;
+use std::collections::HashSet;
+
+pub enum Action {
+    None,
+    Run(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Loaded(Result<Vec<QuarantinedFileDTO>, String>),
+    Retry(i64),
+    Retried(i64, Result<(), String>),
+    Discard(i64),
+    Discarded(Result<(), String>),
+}
+
+/// Files that failed to decode during an import, to retry or discard
+pub struct Quarantine {
+    files: Vec<QuarantinedFileDTO>,
+    loaded: bool,
+    /// Files being imported again
+    retrying: HashSet<i64>,
+}
+
+impl Quarantine {
+    pub fn new() -> (Self, Task<Message>) {
+        let quarantine = Self {
+            files: Vec::new(),
+            loaded: false,
+            retrying: HashSet::new(),
+        };
+        (quarantine, Self::load())
+    }
+
+    fn load() -> Task<Message> {
+        Task::perform(
+            async {
+                quarantine_service::find_all()
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::Loaded,
+        )
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Loaded(result) => {
+                self.loaded = true;
+                match result {
+                    Ok(files) => self.files = files,
+                    Err(err) => {
+                        error!("Failed to load quarantined files: {}", err);
+                        push_error(t!("message.quarantine.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::Retry(id) => {
+                let Some(file) = self.files.iter().find(|file| file.id == id).cloned() else {
+                    return Action::None;
+                };
+                self.retrying.insert(id);
+                Action::Run(Task::perform(
+                    import_service::retry_quarantined(file),
+                    move |result| Message::Retried(id, result),
+                ))
+            }
+            Message::Retried(id, result) => {
+                self.retrying.remove(&id);
+                match result {
+                    Ok(()) => push_success(t!("message.quarantine.retried")),
+                    Err(err) => {
+                        error!("Failed to import quarantined file {}: {}", id, err);
+                        push_error(t!("message.quarantine.retry_error", err = err));
+                    }
+                }
+                Action::Run(Self::load())
+            }
+            Message::Discard(id) => Action::Run(Task::perform(
+                async move {
+                    quarantine_service::discard(id)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                Message::Discarded,
+            )),
+            Message::Discarded(result) => {
+                if let Err(err) = result {
+                    error!("Failed to discard quarantined file: {}", err);
+                    push_error(t!("message.quarantine.error"));
+                }
+                Action::Run(Self::load())
+            }
+        }
+    }
+
+    pub fn view(&'_ self) -> Element<'_, Message> {
+        let header = column![
+            text(t!("quarantine.title"))
+                .size(32)
+                .style(Modern::primary_text()),
+            Space::new(0, 8),
+            text(t!("quarantine.subtitle"))
+                .size(16)
+                .style(Modern::secondary_text()),
+        ];
+
+        let mut list = Column::new().spacing(8);
+        if self.loaded && self.files.is_empty() {
+            list = list.push(
+                text(t!("quarantine.empty"))
+                    .size(16)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for file in &self.files {
+            list = list.push(self.view_file(file));
+        }
+
+        let content = Column::new()
+            .spacing(24)
+            .padding(20)
+            .width(Length::Fill)
+            .push(header)
+            .push(
+                Container::new(list)
+                    .padding(20)
+                    .width(Length::Fill)
+                    .style(Modern::card_container()),
+            );
+
+        Container::new(scrollable(content).width(Length::Fill).height(Length::Fill))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn view_file<'a>(&self, file: &'a QuarantinedFileDTO) -> Element<'a, Message> {
+        let entry = file.image_id.map(|id| format!("#{id}")).unwrap_or_default();
+        let retrying = self.retrying.contains(&file.id);
+
+        let retry = labeled(
+            Button::new(
+                fa_icon_solid(if retrying {
+                    "hourglass-half"
+                } else {
+                    "rotate-right"
+                })
+                .size(14.0),
+            )
+            .padding(Padding::from([6, 10]))
+            .style(Modern::secondary_button())
+            .on_press_maybe((!retrying).then_some(Message::Retry(file.id))),
+            t!("quarantine.button.retry"),
+            tooltip::Position::Top,
+        );
+        let discard = labeled(
+            Button::new(fa_icon_solid("trash").size(14.0))
+                .padding(Padding::from([6, 10]))
+                .style(Modern::danger_button())
+                .on_press_maybe((!retrying).then_some(Message::Discard(file.id))),
+            t!("quarantine.button.discard"),
+            tooltip::Position::Top,
+        );
+
+        Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                text(&file.created_at)
+                    .size(14)
+                    .style(Modern::secondary_text())
+                    .width(Length::Fixed(160.0)),
+            )
+            .push(
+                text(entry)
+                    .size(14)
+                    .style(Modern::secondary_text())
+                    .width(Length::Fixed(60.0)),
+            )
+            .push(
+                Column::new()
+                    .spacing(4)
+                    .width(Length::Fill)
+                    .push(text(&file.path).size(14).style(Modern::primary_text()))
+                    .push(text(&file.reason).size(12).style(Modern::secondary_text())),
+            )
+            .push(retry)
+            .push(discard)
+            .into()
+    }
+}
@@ -1,16 +1,16 @@
 use crate::components::{scrollable_form, tag_selector, ScrollableFormConfig};
 use crate::components::tag_selector::TagSelector;
-use crate::dtos::image_dto::ImageUpdateDTO;
+use crate::dtos::image_dto::ImageDTO;
 use crate::dtos::tag_dto::TagDTO;
-use crate::services::file_service::{
-    save_image_file_with_thumbnail, save_images_from_folder_with_thumbnails,
-};
-use crate::services::image_processor::{dynamic_image_to_rgba};
+use crate::services::import_service::{self, FolderImportResult};
+use crate::services::image_handle::dynamic_image_to_rgba;
+use crate::services::clipboard_service::get_clipboard_source_url;
+use crate::services::download_service::{download_image, import_urls, is_http_url};
 use crate::services::toast_service::{push_error, push_success};
-use crate::services::{image_service, tag_service};
+use crate::services::{dedup_service, image_service, job_service, tag_service};
 use iced::widget::image::Handle;
 use iced::widget::{
-    Button, Column, Container, Image, Row, Text, text_input,
+    Button, Column, Container, Image, Row, Text, text_editor, text_input,
 };
 use iced::{Alignment, Color, Element, Length, Padding, Task};
 use iced_font_awesome::{fa_icon, fa_icon_solid};
@@ -21,6 +21,11 @@ use rfd::AsyncFileDialog;
 use std::collections::HashSet;
 use std::path::{Path};
 use crate::components::header::header;
+use crate::components::import_filter_editor;
+use crate::models::import_filter::{ImportFilter, SkipReason};
+use crate::config::get_settings;
+use crate::models::tag_color::TagColor;
+use crate::t_count;
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -28,11 +33,25 @@ pub enum Message {
     OpenFolderPicker,
     ImageChosen(String),
     DescriptionChanged(String),
-    TagSelectorMessage(tag_selector::Message),
+    TagSelector(tag_selector::Message),
     TagsLoaded(HashSet<TagDTO>),
+    ClipboardTagCreated(Result<HashSet<TagDTO>, String>),
     Submit,
     NavigateToSearch,
+    ImportFilterChanged(ImportFilter),
+    FolderImported(Result<FolderImportResult, String>),
     ImagePasted(DynamicImage, ImageFormat),
+    UrlInputChanged(String),
+    LoadFromUrl,
+    UrlDownloaded(String, Result<(DynamicImage, ImageFormat), String>),
+    SourceUrlChanged(String),
+    ToggleUrlList,
+    UrlListEdited(text_editor::Action),
+    ImportUrlList,
+    UrlListImported(Vec<(String, Result<i64, String>)>),
+    /// Stored images that look like the chosen one, for the check with that number
+    DuplicatesFound(u64, Result<Vec<ImageDTO>, String>),
+    OpenDuplicate(i64),
     NoOps,
 }
 
@@ -40,6 +59,8 @@ pub enum Action {
     None,
     Run(Task<Message>),
     GoToSearch,
+    /// Shows a stored image in the search screen
+    OpenImage(i64),
 }
 
 pub struct Register {
@@ -52,25 +73,67 @@ pub struct Register {
     tag_selector: TagSelector,
     tags_loaded: bool,
     submitted: bool,
+    /// Tag to select once the available tags are loaded (clipboard captures)
+    pending_tag: Option<String>,
+    /// Description filled in from the chosen file name, replaced on the next pick
+    auto_description: Option<String>,
+    /// Address the image came from, stored with the entry
+    source_url: String,
+    url_input: String,
+    downloading: bool,
+    /// Batch import mode: one URL per line, all registered with the selected tags
+    show_url_list: bool,
+    url_list: text_editor::Content,
+    /// URLs that failed in the last batch import, with the reason
+    url_list_failures: Vec<(String, String)>,
+    /// Filter for the folder import, starts from the configured one
+    import_filter: ImportFilter,
+    /// Files the filter left out of the last folder import, with the reason
+    filtered_files: Vec<(String, SkipReason)>,
+    /// Stored images that look like the chosen one, closest first
+    duplicates: Vec<ImageDTO>,
+    /// Number of the last duplicate check, so a late result for an earlier image is dropped
+    duplicate_check: u64,
 }
 
 impl Register {
     pub fn new(dynamic_image: Option<DynamicImage>, format: Option<ImageFormat>) -> (Self, Task<Message>) {
         let tag_selector = TagSelector::new(HashSet::new(), true, true);
-        let image_handle = dynamic_image.as_ref().map(|img| dynamic_image_to_rgba(img));
+        let image_handle = dynamic_image.as_ref().map(dynamic_image_to_rgba);
+        let from_clipboard = dynamic_image.is_some();
+        let mut register = Self {
+            dynamic_image,
+            image_handle,
+            is_folder: false,
+            path: None,
+            original_format: format,
+            description: String::new(),
+            tag_selector,
+            tags_loaded: false,
+            submitted: false,
+            pending_tag: None,
+            auto_description: None,
+            source_url: String::new(),
+            url_input: String::new(),
+            downloading: false,
+            show_url_list: false,
+            url_list: text_editor::Content::new(),
+            url_list_failures: Vec::new(),
+            import_filter: get_settings().config.import_filter.clone().unwrap_or_default(),
+            filtered_files: Vec::new(),
+            duplicates: Vec::new(),
+            duplicate_check: 0,
+        };
+
+        let mut check = Task::none();
+        if from_clipboard {
+            let _ = register.apply_clipboard_defaults();
+            check = register.check_duplicates();
+        }
+
         (
-            Self {
-                dynamic_image,
-                image_handle,
-                is_folder: false,
-                path: None,
-                original_format: format,
-                description: String::new(),
-                tag_selector,
-                tags_loaded: false,
-                submitted: false,
-            },
-            Task::perform(async { tag_service::find_all().await }, |tags| match tags {
+            register,
+            Task::batch([check, Task::perform(async { tag_service::find_all().await }, |tags| match tags {
                 Ok(tags) => {
                     info!("Loaded {} tags", tags.len());
                     Message::TagsLoaded(tags)
@@ -80,11 +143,296 @@ impl Register {
                     push_error("Erro ao carregar tags");
                     Message::TagsLoaded(HashSet::new())
                 }
-            }),
+            })]),
+        )
+    }
+
+    /// Looks for stored images that look like the chosen one
+    fn check_duplicates(&mut self) -> Task<Message> {
+        self.duplicates.clear();
+        self.duplicate_check += 1;
+        let Some(image) = self.dynamic_image.clone() else {
+            return Task::none();
+        };
+        let check = self.duplicate_check;
+        Task::perform(
+            async move {
+                dedup_service::find_duplicates(image)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            move |result| Message::DuplicatesFound(check, result),
+        )
+    }
+
+    /// Warning listing the stored images that look like the chosen one
+    fn duplicates_section(&'_ self) -> Element<'_, Message> {
+        let list = self.duplicates.iter().fold(
+            Column::new()
+                .spacing(8)
+                .push(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("triangle-exclamation").size(16.0))
+                        .push(
+                            Text::new(t_count!("register.duplicates.title", self.duplicates.len()))
+                                .size(16)
+                                .style(Modern::error_text()),
+                        ),
+                )
+                .push(
+                    Text::new(t!("register.duplicates.hint"))
+                        .size(13)
+                        .style(Modern::secondary_text()),
+                ),
+            |column, image| {
+                column.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(
+                            Image::new(Handle::from_path(&image.thumbnail_path))
+                                .width(48.0)
+                                .height(48.0),
+                        )
+                        .push(
+                            Text::new(format!("#{} — {}", image.id, image.description))
+                                .size(14)
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            Button::new(Text::new(t!("register.duplicates.open")))
+                                .style(Modern::secondary_button())
+                                .padding(Padding::from([6, 12]))
+                                .on_press(Message::OpenDuplicate(image.id)),
+                        ),
+                )
+            },
+        );
+
+        Container::new(list)
+            .padding(15)
+            .width(Length::Fill)
+            .style(Modern::floating_container())
+            .into()
+    }
+
+    /// Pre-fills the description and tag configured for clipboard captures
+    fn apply_clipboard_defaults(&mut self) -> Task<Message> {
+        let (tag, template) = {
+            let settings = get_settings();
+            (
+                settings.config.clipboard_tag.clone(),
+                settings.config.clipboard_description.clone(),
+            )
+        };
+
+        self.source_url = get_clipboard_source_url().unwrap_or_default();
+
+        if let Some(template) = template.filter(|t| !t.trim().is_empty())
+            && self.description.trim().is_empty()
+        {
+            self.description = render_clipboard_description(&template);
+        }
+
+        self.pending_tag = tag
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty());
+
+        if self.tags_loaded {
+            self.select_pending_tag()
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Selects the pending tag, creating it first if it doesn't exist yet
+    fn select_pending_tag(&mut self) -> Task<Message> {
+        let Some(name) = self.pending_tag.clone() else {
+            return Task::none();
+        };
+
+        if let Some(tag) = self.tag_selector.available.iter().find(|t| t.name == name) {
+            self.tag_selector.selected.insert(tag.clone());
+            self.pending_tag = None;
+            return Task::none();
+        }
+
+        Task::perform(
+            async move {
+                tag_service::save(&name, TagColor::default())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                tag_service::find_all().await.map_err(|e| e.to_string())
+            },
+            Message::ClipboardTagCreated,
         )
     }
 
+    /// Fills the description from the file or folder name when enabled in preferences,
+    /// unless the user already typed one
+    fn apply_filename_description(&mut self, path: &Path) {
+        if !get_settings().config.description_from_filename.unwrap_or(false) {
+            return;
+        }
+
+        let user_edited = !self.description.trim().is_empty()
+            && self.auto_description.as_deref() != Some(self.description.as_str());
+        if user_edited {
+            return;
+        }
+
+        if let Some(description) = description_from_path(path) {
+            self.description = description.clone();
+            self.auto_description = Some(description);
+        }
+    }
+
+    /// Valid addresses pasted in the URL list, without duplicates
+    fn list_urls(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.url_list
+            .text()
+            .lines()
+            .map(str::trim)
+            .filter(|line| is_http_url(line))
+            .filter(|line| seen.insert(line.to_string()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn url_list_section(&'_ self) -> Element<'_, Message> {
+        let url_count = self.list_urls().len();
+
+        let mut column = Column::new()
+            .spacing(15)
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid("list").size(20.0))
+                    .push(
+                        Text::new(t!("register.section.url_list"))
+                            .size(20)
+                            .font(iced::Font::MONOSPACE)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        Button::new(Text::new(t!("register.button.single_image")))
+                            .style(Modern::secondary_button())
+                            .padding(Padding::from([8, 16]))
+                            .on_press(Message::ToggleUrlList),
+                    ),
+            )
+            .push(
+                Text::new(t!("register.tooltip.url_list"))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            )
+            .push(
+                text_editor(&self.url_list)
+                    .placeholder(t!("register.placeholder.url_list"))
+                    .height(Length::Fixed(220.0))
+                    .padding(12)
+                    .on_action(Message::UrlListEdited),
+            )
+            .push(
+                Text::new(t_count!("register.status.url_count", url_count))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            );
+
+        if !self.url_list_failures.is_empty() {
+            let failures = self.url_list_failures.iter().fold(
+                Column::new().spacing(6).push(
+                    Text::new(t!("register.status.url_failures"))
+                        .size(16)
+                        .style(Modern::error_text()),
+                ),
+                |column, (url, err)| {
+                    column.push(
+                        Text::new(format!("{} — {}", url, err))
+                            .size(13)
+                            .style(Modern::secondary_text()),
+                    )
+                },
+            );
+            column = column.push(
+                Container::new(failures)
+                    .padding(15)
+                    .width(Length::Fill)
+                    .style(Modern::floating_container()),
+            );
+        }
+
+        Container::new(column)
+            .padding(30)
+            .style(Modern::card_container())
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// Filter overrides for the folder import and the files it left out last time
+    fn import_filter_section(&'_ self) -> Element<'_, Message> {
+        let mut column = Column::new()
+            .spacing(15)
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid("filter").size(16.0))
+                    .push(Text::new(t!("register.section.import_filter")).size(16)),
+            )
+            .push(import_filter_editor(
+                &self.import_filter,
+                Message::ImportFilterChanged,
+            ));
+
+        if !self.filtered_files.is_empty() {
+            let summary = SkipReason::ALL
+                .iter()
+                .filter_map(|reason| {
+                    let count = self
+                        .filtered_files
+                        .iter()
+                        .filter(|(_, skipped)| skipped == reason)
+                        .count();
+                    (count > 0).then(|| format!("{}: {}", reason, count))
+                })
+                .collect::<Vec<_>>()
+                .join(" · ");
+
+            let report = self.filtered_files.iter().fold(
+                Column::new()
+                    .spacing(6)
+                    .push(
+                        Text::new(t!("register.status.filtered_files"))
+                            .size(16)
+                            .style(Modern::error_text()),
+                    )
+                    .push(Text::new(summary).size(14).style(Modern::secondary_text())),
+                |column, (name, reason)| {
+                    column.push(
+                        Text::new(format!("{} — {}", name, reason))
+                            .size(13)
+                            .style(Modern::secondary_text()),
+                    )
+                },
+            );
+            column = column.push(
+                Container::new(report)
+                    .padding(15)
+                    .width(Length::Fill)
+                    .style(Modern::floating_container()),
+            );
+        }
+
+        column.into()
+    }
+
     fn reset_image_state(&mut self) {
+        self.duplicates.clear();
         self.dynamic_image = None;
         self.image_handle = None;
         self.original_format = None;
@@ -94,7 +442,9 @@ impl Register {
 
     fn set_folder_state(&mut self, path: String) {
         self.is_folder = true;
+        self.filtered_files.clear();
         self.path = Some(path);
+        self.duplicates.clear();
         self.dynamic_image = None;
         self.image_handle = None;
         self.original_format = None;
@@ -111,6 +461,7 @@ impl Register {
                 // Verifica se é um diretório
                 if path_buf.is_dir() {
                     info!("Chosen path is a directory, treating as folder");
+                    self.apply_filename_description(path_buf);
                     self.set_folder_state(path);
                     return Action::None;
                 }
@@ -139,7 +490,11 @@ impl Register {
                                         self.dynamic_image = Some(dynamic_image);
                                         self.original_format = Some(format);
                                         self.is_folder = false;
-                                        self.path = None;
+                                        // Kept for the import rules matching on the file
+                                        self.path = Some(path.clone());
+                                        self.source_url.clear();
+                                        self.apply_filename_description(path_buf);
+                                        return Action::Run(self.check_duplicates());
                                     }
                                     Err(e) => {
                                         error!("Failed to decode image: {}", e);
@@ -174,128 +529,72 @@ impl Register {
                 info!("Loaded {} tags", tags.len());
                 self.tag_selector.available = tags;
                 self.tags_loaded = true;
+                Action::Run(self.select_pending_tag())
+            }
+            Message::ClipboardTagCreated(result) => {
+                match result {
+                    Ok(tags) => {
+                        self.tag_selector.available = tags;
+                        if let Some(name) = self.pending_tag.take()
+                            && let Some(tag) =
+                                self.tag_selector.available.iter().find(|t| t.name == name)
+                        {
+                            self.tag_selector.selected.insert(tag.clone());
+                        }
+                    }
+                    Err(err) => {
+                        error!("Failed to create clipboard tag: {}", err);
+                        self.pending_tag = None;
+                        push_error(t!("message.tag.error"));
+                    }
+                }
                 Action::None
             }
-            Message::TagSelectorMessage(msg) => {
+            Message::TagSelector(msg) => {
                 let task: Task<tag_selector::Message> = self.tag_selector.update(msg);
-                let task: Task<Message> = task.map(Message::TagSelectorMessage);
+                let task: Task<Message> = task.map(Message::TagSelector);
                 Action::Run(task)
             }
             Message::Submit => {
                 self.submitted = true;
-                let original_format = self.original_format.clone().unwrap_or(ImageFormat::Png);
+                let original_format = self.original_format.unwrap_or(ImageFormat::Png);
                 let description = self.description.clone();
                 let tags = self.tag_selector.selected.clone();
+                let source_url = Some(self.source_url.trim().to_string()).filter(|u| !u.is_empty());
 
                 if self.is_folder {
                     // Processar pasta
                     let folder_path = self.path.clone().unwrap();
                     let task = Task::perform(
-                        async move {
-                            let folder_path = Path::new(&folder_path);
-
-                            // Inserir entrada principal no banco
-                            let image_id = image_service::insert_image(&description)
-                                .await
-                                .map_err(|err| {
-                                    error!("Erro ao inserir imagem no banco: {}", err);
-                                    format!("Falha ao inserir imagem: {}", err)
-                                })?;
-
-                            // Processar todas as imagens da pasta
-                            let saved_paths =
-                                save_images_from_folder_with_thumbnails(image_id, folder_path)
-                                    .map_err(|err| {
-                                        error!(
-                                            "Erro ao processar imagens da pasta {}: {}",
-                                            folder_path.display(),
-                                            err
-                                        );
-                                        format!("Falha ao processar imagens da pasta: {}", err)
-                                    })?;
-
-                            if saved_paths.is_empty() {
-                                return Err("Nenhuma imagem válida encontrada na pasta".to_string());
-                            }
-
-                            // Usar o caminho da pasta como path principal e o primeiro thumbnail
-                            let (image_dir, main_thumb_path) = &saved_paths[0];
-
-                            let mut dto = ImageUpdateDTO::default();
-                            dto.path = Some(image_dir.to_string());
-                            dto.thumbnail_path = Some(main_thumb_path.clone());
-                            dto.tags = Some(tags);
-                            dto.is_folder = true;
-                            dto.is_prepared = true;
-
-                            image_service::update_from_dto(image_id, dto)
-                                .await
-                                .map_err(|err| {
-                                    error!("Erro ao atualizar imagem {}: {}", image_id, err);
-                                    format!("Falha ao atualizar imagem: {}", err)
-                                })?;
-
-                            info!(
-                                "Processadas {} imagens da pasta para ID {}",
-                                saved_paths.len(),
-                                image_id
-                            );
-                            Ok(saved_paths.len())
-                        },
-                        |result: Result<usize, String>| match result {
-                            Ok(count) => {
-                                push_success(t!("message.register.folder.success", count = count));
-                                Message::NavigateToSearch
-                            }
-                            Err(err) => {
-                                error!("Erro no processo de submit da pasta: {}", err);
-                                push_error(t!("message.register.folder.success", err = err));
-                                Message::NoOps
-                            }
-                        },
+                        import_service::import_folder(
+                            folder_path,
+                            description,
+                            tags,
+                            source_url,
+                            self.import_filter.clone(),
+                        ),
+                        Message::FolderImported,
                     );
 
                     Action::Run(task)
                 } else {
                     // Processar imagem única
                     let dynamic_image = self.dynamic_image.clone().unwrap();
+                    let origin = self.path.clone().or_else(|| source_url.clone());
                     let task = Task::perform(
                         async move {
-                            let image_id = image_service::insert_image(&description)
-                                .await
-                                .map_err(|err| {
-                                    error!("Erro ao inserir imagem no banco: {}", err);
-                                    format!("Falha ao inserir imagem: {}", err)
-                                })?;
-
-                            let (new_path, thumb_path) = save_image_file_with_thumbnail(
-                                image_id,
+                            let _job = job_service::start();
+                            image_service::register_image(
                                 dynamic_image,
-                                original_format
-
+                                original_format,
+                                description,
+                                tags,
+                                source_url,
+                                origin,
                             )
-                            .map_err(|err| {
-                                error!("Erro ao salvar arquivo de imagem {}: {}", image_id, err);
-                                format!("Falha ao salvar arquivo: {}", err)
-                            })?;
-
-                            let mut dto = ImageUpdateDTO::default();
-                            dto.path = Some(new_path);
-                            dto.thumbnail_path = Some(thumb_path);
-                            dto.tags = Some(tags);
-                            dto.is_prepared = true;
-
-                            image_service::update_from_dto(image_id, dto)
-                                .await
-                                .map_err(|err| {
-                                    error!("Erro ao atualizar imagem {}: {}", image_id, err);
-                                    format!("Falha ao atualizar imagem: {}", err)
-                                })?;
-
-                            info!("Image {} successfully registered", image_id);
-                            Ok(())
+                            .await
                         },
-                        |result: Result<(), String>| match result {
+                        |result: Result<i64, String>| match result {
                             Ok(_) => {
                                 push_success(t!("message.register.success"));
                                 Message::NavigateToSearch
@@ -311,6 +610,110 @@ impl Register {
                     Action::Run(task)
                 }
             }
+            Message::ToggleUrlList => {
+                self.show_url_list = !self.show_url_list;
+                Action::None
+            }
+            Message::UrlListEdited(action) => {
+                self.url_list.perform(action);
+                Action::None
+            }
+            Message::ImportUrlList => {
+                let shared_description = self.description.trim().to_string();
+                let entries: Vec<(String, String)> = self
+                    .list_urls()
+                    .into_iter()
+                    .map(|url| {
+                        let description = if shared_description.is_empty() {
+                            description_from_url(&url)
+                        } else {
+                            shared_description.clone()
+                        };
+                        (url, description)
+                    })
+                    .collect();
+                if entries.is_empty() {
+                    return Action::None;
+                }
+
+                self.submitted = true;
+                self.url_list_failures.clear();
+                let tags = self.tag_selector.selected.clone();
+                Action::Run(Task::perform(
+                    import_urls(entries, tags),
+                    Message::UrlListImported,
+                ))
+            }
+            Message::UrlListImported(results) => {
+                self.submitted = false;
+                let imported = results.iter().filter(|(_, r)| r.is_ok()).count();
+                self.url_list_failures = results
+                    .into_iter()
+                    .filter_map(|(url, result)| result.err().map(|err| (url, err)))
+                    .collect();
+
+                if self.url_list_failures.is_empty() {
+                    push_success(t_count!("message.register.url_list.success", imported));
+                    return Action::GoToSearch;
+                }
+
+                // Keep only the failed URLs so they can be retried
+                let failed: Vec<&str> = self
+                    .url_list_failures
+                    .iter()
+                    .map(|(url, _)| url.as_str())
+                    .collect();
+                self.url_list = text_editor::Content::with_text(&failed.join("\n"));
+                push_error(t_count!(
+                    "message.register.url_list.partial",
+                    imported,
+                    failed = self.url_list_failures.len()
+                ));
+                Action::None
+            }
+            Message::ImportFilterChanged(filter) => {
+                self.import_filter = filter;
+                Action::None
+            }
+            Message::FolderImported(result) => {
+                self.submitted = false;
+                let import = match result {
+                    Ok(import) => import,
+                    Err(err) => {
+                        error!("Erro no processo de submit da pasta: {}", err);
+                        push_error(t!("message.register.folder.success", err = err));
+                        return Action::None;
+                    }
+                };
+
+                if import.skipped > 0 {
+                    push_success(t_count!(
+                        "message.register.folder.skipped",
+                        import.skipped,
+                        added = import.added
+                    ));
+                } else {
+                    push_success(t_count!("message.register.folder.success", import.added));
+                }
+
+                if import.quarantined > 0 {
+                    push_error(t_count!(
+                        "message.register.folder.quarantined",
+                        import.quarantined
+                    ));
+                }
+
+                self.filtered_files = import.filtered;
+                if self.filtered_files.is_empty() {
+                    return Action::GoToSearch;
+                }
+                // Stay to show what was left out, the filter can be loosened and the folder imported again
+                push_error(t_count!(
+                    "message.register.folder.filtered",
+                    self.filtered_files.len()
+                ));
+                Action::None
+            }
             Message::NavigateToSearch => Action::GoToSearch,
             Message::ImagePasted(dynamic_image,format) => {
                 info!("Image pasted from clipboard");
@@ -319,6 +722,63 @@ impl Register {
                 self.is_folder = false;
                 self.path = None;
                 self.original_format = Option::from(format);
+                Action::Run(Task::batch([
+                    self.apply_clipboard_defaults(),
+                    self.check_duplicates(),
+                ]))
+            }
+            Message::UrlInputChanged(url) => {
+                self.url_input = url;
+                Action::None
+            }
+            Message::LoadFromUrl => {
+                let url = self.url_input.trim().to_string();
+                if !is_http_url(&url) {
+                    push_error(t!("message.register.url.invalid"));
+                    return Action::None;
+                }
+                self.downloading = true;
+                Action::Run(Task::perform(
+                    async move {
+                        let result = download_image(&url).await;
+                        (url, result)
+                    },
+                    |(url, result)| Message::UrlDownloaded(url, result),
+                ))
+            }
+            Message::UrlDownloaded(url, result) => {
+                self.downloading = false;
+                match result {
+                    Ok((dynamic_image, format)) => {
+                        self.image_handle = Some(dynamic_image_to_rgba(&dynamic_image));
+                        self.dynamic_image = Some(dynamic_image);
+                        self.original_format = Some(format);
+                        self.is_folder = false;
+                        self.path = None;
+                        self.source_url = url;
+                        self.url_input.clear();
+                        return Action::Run(self.check_duplicates());
+                    }
+                    Err(err) => {
+                        error!("Failed to download {}: {}", url, err);
+                        push_error(t!("message.register.url.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::DuplicatesFound(check, result) => {
+                if check != self.duplicate_check {
+                    return Action::None;
+                }
+                match result {
+                    Ok(duplicates) => self.duplicates = duplicates,
+                    Err(err) => error!("Failed to look for duplicates: {}", err),
+                }
+                Action::None
+            }
+            Message::OpenDuplicate(image_id) => Action::OpenImage(image_id),
+            Message::SourceUrlChanged(url) => {
+                self.source_url = url;
                 Action::None
             }
             Message::NoOps => {
@@ -396,57 +856,117 @@ impl Register {
                 .into()
         };
 
-        let upload_section = Container::new(
-            Column::new()
-                .spacing(20)
-                .push(
-                    Text::new(t!("register.section.image"))
-                        .size(20)
-                        .font(iced::Font::MONOSPACE),
-                )
-                .push(preview)
-                .push(
-                    Row::new()
-                        .spacing(10)
-                        .push(
-                            Button::new(
-                                Row::new()
-                                    .spacing(8)
-                                    .align_y(Alignment::Center)
-                                    .push(fa_icon_solid("folder-plus").size(16.0))
-                                    .push(Text::new(t!("register.button.select_image"))),
-                            )
-                                .style(Modern::primary_button())
-                                .padding(Padding::from([12, 20]))
-                                .on_press(Message::OpenImagePicker),
+        let mut upload_column = Column::new()
+            .spacing(20)
+            .push(
+                Text::new(t!("register.section.image"))
+                    .size(20)
+                    .font(iced::Font::MONOSPACE),
+            )
+            .push(preview);
+
+        if !self.duplicates.is_empty() {
+            upload_column = upload_column.push(self.duplicates_section());
+        }
+
+        upload_column = upload_column
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Button::new(
+                            Row::new()
+                                .spacing(8)
+                                .align_y(Alignment::Center)
+                                .push(fa_icon_solid("folder-plus").size(16.0))
+                                .push(Text::new(t!("register.button.select_image"))),
                         )
-                        .push(
-                            Button::new(
-                                Row::new()
-                                    .spacing(8)
-                                    .align_y(Alignment::Center)
-                                    .push(fa_icon_solid("folder-plus").size(16.0))
-                                    .push(Text::new(t!("register.button.select_folder"))),
-                            )
-                                .style(Modern::primary_button())
-                                .padding(Padding::from([12, 20]))
-                                .on_press(Message::OpenFolderPicker),
-                        ),
-                ),
-        )
+                            .style(Modern::primary_button())
+                            .padding(Padding::from([12, 20]))
+                            .on_press(Message::OpenImagePicker),
+                    )
+                    .push(
+                        Button::new(
+                            Row::new()
+                                .spacing(8)
+                                .align_y(Alignment::Center)
+                                .push(fa_icon_solid("folder-plus").size(16.0))
+                                .push(Text::new(t!("register.button.select_folder"))),
+                        )
+                            .style(Modern::primary_button())
+                            .padding(Padding::from([12, 20]))
+                            .on_press(Message::OpenFolderPicker),
+                    ),
+            )
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(
+                        text_input(t!("register.placeholder.url").as_ref(), &self.url_input)
+                            .style(Modern::text_input())
+                            .padding(Padding::from([12, 16]))
+                            .size(16)
+                            .on_input(Message::UrlInputChanged)
+                            .on_submit(Message::LoadFromUrl),
+                    )
+                    .push(
+                        Button::new(
+                            Row::new()
+                                .spacing(8)
+                                .align_y(Alignment::Center)
+                                .push(fa_icon_solid("download").size(16.0))
+                                .push(Text::new(t!("register.button.load_url"))),
+                        )
+                            .style(Modern::primary_button())
+                            .padding(Padding::from([12, 20]))
+                            .on_press_maybe((!self.downloading).then_some(Message::LoadFromUrl)),
+                    )
+                    .push(
+                        Button::new(
+                            Row::new()
+                                .spacing(8)
+                                .align_y(Alignment::Center)
+                                .push(fa_icon_solid("list").size(16.0))
+                                .push(Text::new(t!("register.button.url_list"))),
+                        )
+                            .style(Modern::secondary_button())
+                            .padding(Padding::from([12, 20]))
+                            .on_press(Message::ToggleUrlList),
+                    ),
+            );
+
+        if self.is_folder {
+            upload_column = upload_column.push(self.import_filter_section());
+        }
+
+        let upload_section = Container::new(upload_column)
             .padding(30)
             .style(Modern::card_container())
             .width(Length::Fill);
 
         // Description section
-        let description_section = Container::new(
-            Column::new()
-                .spacing(15)
-                .push(
-                    Text::new(t!("register.section.description"))
-                        .size(20)
-                        .font(iced::Font::MONOSPACE),
+        let mut description_column = Column::new()
+            .spacing(15)
+            .push(
+                Text::new(t!("register.section.description"))
+                    .size(20)
+                    .font(iced::Font::MONOSPACE),
+            );
+
+        if self.show_url_list {
+            description_column = description_column.push(
+                text_input(
+                    t!("register.placeholder.url_list_description").as_ref(),
+                    &self.description,
                 )
+                    .style(Modern::text_input())
+                    .padding(Padding::from([12, 16]))
+                    .size(16)
+                    .on_input(Message::DescriptionChanged),
+            );
+        } else {
+            description_column = description_column
                 .push(
                     text_input(
                         t!("register.placeholder.description").as_ref(),
@@ -456,8 +976,20 @@ impl Register {
                         .padding(Padding::from([12, 16]))
                         .size(16)
                         .on_input(Message::DescriptionChanged),
-                ),
-        )
+                )
+                .push(
+                    text_input(
+                        t!("register.placeholder.source_url").as_ref(),
+                        &self.source_url,
+                    )
+                        .style(Modern::text_input())
+                        .padding(Padding::from([12, 16]))
+                        .size(16)
+                        .on_input(Message::SourceUrlChanged),
+                );
+        }
+
+        let description_section = Container::new(description_column)
             .padding(30)
             .style(Modern::card_container())
             .width(Length::Fill);
@@ -478,7 +1010,7 @@ impl Register {
                         ),
                 )
                 .push(if self.tags_loaded {
-                    self.tag_selector.view().map(Message::TagSelectorMessage)
+                    self.tag_selector.view().map(Message::TagSelector)
                 } else {
                     Container::new(
                         Row::new().spacing(10).align_y(Alignment::Center).push(
@@ -497,9 +1029,18 @@ impl Register {
             .width(Length::Fill);
 
         // Fields validation
-        let ready = !self.description.trim().is_empty()
-            && !self.tag_selector.selected.is_empty()
-            && (self.dynamic_image.is_some() || self.is_folder);
+        let ready = if self.show_url_list {
+            !self.tag_selector.selected.is_empty() && !self.list_urls().is_empty()
+        } else {
+            !self.description.trim().is_empty()
+                && !self.tag_selector.selected.is_empty()
+                && (self.dynamic_image.is_some() || self.is_folder)
+        };
+        let submit_message = if self.show_url_list {
+            Message::ImportUrlList
+        } else {
+            Message::Submit
+        };
 
         let submit_section = Container::new(
             Column::new()
@@ -552,7 +1093,7 @@ impl Register {
                     if ready && !self.submitted {
                         button = button
                             .style(Modern::success_button())
-                            .on_press(Message::Submit);
+                            .on_press(submit_message);
                     } else if self.submitted {
                         button = button.style(Modern::plain_button());
                     } else {
@@ -569,7 +1110,11 @@ impl Register {
         // Main content
         let main_content = scrollable_form(ScrollableFormConfig {
             header,
-            content_section: upload_section.into(),
+            content_section: if self.show_url_list {
+                self.url_list_section()
+            } else {
+                upload_section.into()
+            },
             description_section: description_section.into(),
             tags_section: tags_section.into(),
             bottom_section: submit_section.into(),
@@ -608,3 +1153,45 @@ fn pick_path(folder: bool) -> Task<Message> {
         },
     )
 }
+
+/// Replaces the `{date}` and `{time}` placeholders of a clipboard description template
+fn render_clipboard_description(template: &str) -> String {
+    let now = chrono::Local::now();
+    template
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M:%S").to_string())
+}
+
+/// Description for a batch-imported URL: the cleaned-up file name, or the address itself
+fn description_from_url(url: &str) -> String {
+    let without_query = url.split(['?', '#']).next().unwrap_or(url);
+    let path = without_query
+        .split_once("://")
+        .and_then(|(_, rest)| rest.split_once('/'))
+        .map(|(_, path)| path)
+        .unwrap_or_default();
+
+    path.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .and_then(|segment| description_from_path(Path::new(segment)))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Builds a readable description from a file or folder name, dropping the extension
+/// and turning underscores and dashes into spaces
+fn description_from_path(path: &Path) -> Option<String> {
+    let name = if path.is_dir() {
+        path.file_name()?
+    } else {
+        path.file_stem()?
+    };
+
+    let cleaned = name
+        .to_string_lossy()
+        .replace(['_', '-'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (!cleaned.is_empty()).then_some(cleaned)
+}
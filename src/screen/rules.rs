@@ -0,0 +1,372 @@
+use crate::components::accessibility::labeled;
+use crate::components::header::header;
+use crate::components::tag_chips::tag_chips;
+use crate::config::{get_settings, update_config};
+use crate::dtos::tag_dto::TagDTO;
+use crate::models::import_rule::{ConditionKind, ImportRule, RuleCondition};
+use crate::services::tag_service;
+use crate::services::toast_service::{push_error, push_success};
+use crate::utils::capitalize_first;
+use iced::widget::{
+    Button, Checkbox, Column, Container, PickList, Row, Space, Text, TextInput, column, scrollable,
+    tooltip,
+};
+use iced::{Alignment, Element, Length, Padding, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use log::error;
+use std::collections::HashSet;
+
+pub enum Action {
+    None,
+    Close,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    TagsLoaded(Result<HashSet<TagDTO>, String>),
+    AddRule,
+    RemoveRule(usize),
+    NameChanged(usize, String),
+    EnabledToggled(usize, bool),
+    AddCondition(usize),
+    RemoveCondition(usize, usize),
+    ConditionKindChanged(usize, usize, ConditionKind),
+    ConditionValueChanged(usize, usize, String),
+    AddTag(usize, String),
+    RemoveTag(usize, i64),
+    LanguageChanged(usize, String),
+    PinToggled(usize, bool),
+    Save,
+    Close,
+}
+
+/// Import rules: conditions on imported files and the tags and settings they give
+pub struct Rules {
+    /// Rules as saved in the config
+    saved: Vec<ImportRule>,
+    /// Rules being edited, only written on Save
+    rules: Vec<ImportRule>,
+    tags: Vec<TagDTO>,
+}
+
+impl Rules {
+    pub fn new() -> (Self, Task<Message>) {
+        let saved = get_settings()
+            .config
+            .import_rules
+            .clone()
+            .unwrap_or_default();
+        let rules = Self {
+            rules: saved.clone(),
+            saved,
+            tags: Vec::new(),
+        };
+        let task = Task::perform(
+            async { tag_service::find_all().await.map_err(|e| e.to_string()) },
+            Message::TagsLoaded,
+        );
+        (rules, task)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::TagsLoaded(result) => match result {
+                Ok(tags) => {
+                    self.tags = tags.into_iter().collect();
+                    self.tags.sort_by(|a, b| a.name.cmp(&b.name));
+                }
+                Err(err) => {
+                    error!("Failed to load tags for the rules: {}", err);
+                    push_error(t!("message.rules.error"));
+                }
+            },
+            Message::AddRule => self.rules.push(ImportRule {
+                name: t!("rules.new_rule", number = self.rules.len() + 1).to_string(),
+                enabled: true,
+                conditions: vec![RuleCondition::default()],
+                ..Default::default()
+            }),
+            Message::RemoveRule(index) => {
+                if index < self.rules.len() {
+                    self.rules.remove(index);
+                }
+            }
+            Message::NameChanged(index, name) => self.edit(index, |rule| rule.name = name),
+            Message::EnabledToggled(index, enabled) => {
+                self.edit(index, |rule| rule.enabled = enabled)
+            }
+            Message::AddCondition(index) => {
+                self.edit(index, |rule| rule.conditions.push(RuleCondition::default()))
+            }
+            Message::RemoveCondition(index, condition) => self.edit(index, |rule| {
+                if condition < rule.conditions.len() {
+                    rule.conditions.remove(condition);
+                }
+            }),
+            Message::ConditionKindChanged(index, condition, kind) => self.edit(index, |rule| {
+                if let Some(condition) = rule.conditions.get_mut(condition) {
+                    condition.kind = kind;
+                }
+            }),
+            Message::ConditionValueChanged(index, condition, value) => self.edit(index, |rule| {
+                if let Some(condition) = rule.conditions.get_mut(condition) {
+                    condition.value = value;
+                }
+            }),
+            Message::AddTag(index, name) => {
+                if let Some(tag) = self
+                    .tags
+                    .iter()
+                    .find(|tag| capitalize_first(&tag.name) == name)
+                {
+                    let id = tag.id;
+                    self.edit(index, |rule| {
+                        if !rule.tag_ids.contains(&id) {
+                            rule.tag_ids.push(id);
+                        }
+                    });
+                }
+            }
+            Message::RemoveTag(index, id) => {
+                self.edit(index, |rule| rule.tag_ids.retain(|tag_id| *tag_id != id))
+            }
+            Message::LanguageChanged(index, language) => self.edit(index, |rule| {
+                rule.language = Some(language).filter(|l| !l.is_empty())
+            }),
+            Message::PinToggled(index, pin) => self.edit(index, |rule| rule.pin = pin),
+            Message::Save => {
+                let rules = self.rules.clone();
+                update_config(|config| config.import_rules = Some(rules));
+                self.saved = self.rules.clone();
+                push_success(t!("message.rules.saved"));
+            }
+            Message::Close => return Action::Close,
+        }
+        Action::None
+    }
+
+    fn edit(&mut self, index: usize, change: impl FnOnce(&mut ImportRule)) {
+        if let Some(rule) = self.rules.get_mut(index) {
+            change(rule);
+        }
+    }
+
+    pub fn view(&'_ self) -> Element<'_, Message> {
+        let title = column![
+            Text::new(t!("rules.title"))
+                .size(32)
+                .style(Modern::primary_text()),
+            Space::new(0, 8),
+            Text::new(t!("rules.subtitle"))
+                .size(16)
+                .style(Modern::secondary_text()),
+        ];
+
+        let dirty = self.rules != self.saved;
+        let toolbar = Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("plus").size(16.0))
+                        .push(Text::new(t!("rules.button.add_rule")).size(16)),
+                )
+                .padding(Padding::from([12, 20]))
+                .style(Modern::secondary_button())
+                .on_press(Message::AddRule),
+            )
+            .push(Space::with_width(Length::Fill))
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("floppy-disk").size(16.0))
+                        .push(Text::new(t!("rules.button.save")).size(16)),
+                )
+                .padding(Padding::from([12, 20]))
+                .style(Modern::success_button())
+                .on_press_maybe(dirty.then_some(Message::Save)),
+            );
+
+        let mut list = Column::new().spacing(20);
+        if self.rules.is_empty() {
+            list = list.push(
+                Text::new(t!("rules.empty"))
+                    .size(16)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for (index, rule) in self.rules.iter().enumerate() {
+            list = list.push(self.view_rule(index, rule));
+        }
+
+        let content = Column::new()
+            .spacing(24)
+            .padding(20)
+            .width(Length::Fill)
+            .push(title)
+            .push(toolbar)
+            .push(list);
+
+        Column::new()
+            .push(header(|| Message::Close))
+            .push(scrollable(content).width(Length::Fill).height(Length::Fill))
+            .into()
+    }
+
+    fn view_rule<'a>(&'a self, index: usize, rule: &'a ImportRule) -> Element<'a, Message> {
+        let top = Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                TextInput::new(&t!("rules.placeholder.name"), &rule.name)
+                    .on_input(move |name| Message::NameChanged(index, name))
+                    .padding(Padding::new(10.0))
+                    .size(16)
+                    .style(Modern::text_input())
+                    .width(Length::Fill),
+            )
+            .push(
+                Checkbox::new(t!("rules.label.enabled"), rule.enabled)
+                    .on_toggle(move |enabled| Message::EnabledToggled(index, enabled))
+                    .size(18)
+                    .text_size(16)
+                    .style(Modern::checkbox()),
+            )
+            .push(labeled(
+                Button::new(fa_icon_solid("trash").size(14.0))
+                    .padding(Padding::from([8, 12]))
+                    .style(Modern::danger_button())
+                    .on_press(Message::RemoveRule(index)),
+                t!("rules.button.remove_rule"),
+                tooltip::Position::Left,
+            ));
+
+        let mut conditions = Column::new().spacing(8).push(
+            Text::new(t!("rules.label.conditions"))
+                .size(14)
+                .style(Modern::secondary_text()),
+        );
+        for (position, condition) in rule.conditions.iter().enumerate() {
+            conditions = conditions.push(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(
+                        PickList::new(ConditionKind::ALL, Some(condition.kind), move |kind| {
+                            Message::ConditionKindChanged(index, position, kind)
+                        })
+                        .style(Modern::pick_list())
+                        .width(Length::Fixed(220.0)),
+                    )
+                    .push(
+                        TextInput::new(&t!("rules.placeholder.value"), &condition.value)
+                            .on_input(move |value| {
+                                Message::ConditionValueChanged(index, position, value)
+                            })
+                            .padding(Padding::new(10.0))
+                            .size(14)
+                            .style(Modern::text_input())
+                            .width(Length::Fill),
+                    )
+                    .push(labeled(
+                        Button::new(fa_icon_solid("xmark").size(14.0))
+                            .padding(Padding::from([8, 12]))
+                            .style(Modern::plain_button())
+                            .on_press(Message::RemoveCondition(index, position)),
+                        t!("rules.button.remove_condition"),
+                        tooltip::Position::Left,
+                    )),
+            );
+        }
+        conditions = conditions.push(
+            Button::new(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid("plus").size(12.0))
+                    .push(Text::new(t!("rules.button.add_condition")).size(14)),
+            )
+            .padding(Padding::from([6, 12]))
+            .style(Modern::plain_button())
+            .on_press(Message::AddCondition(index)),
+        );
+
+        let rule_tags: HashSet<TagDTO> = self
+            .tags
+            .iter()
+            .filter(|tag| rule.tag_ids.contains(&tag.id))
+            .cloned()
+            .collect();
+        let other_tags: Vec<String> = self
+            .tags
+            .iter()
+            .filter(|tag| !rule.tag_ids.contains(&tag.id))
+            .map(|tag| capitalize_first(&tag.name))
+            .collect();
+
+        let actions = Column::new()
+            .spacing(8)
+            .push(
+                Text::new(t!("rules.label.actions"))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            )
+            .push(
+                Row::new()
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                    .push(
+                        PickList::new(other_tags, None::<String>, move |name| {
+                            Message::AddTag(index, name)
+                        })
+                        .placeholder(t!("rules.placeholder.add_tag"))
+                        .style(Modern::pick_list())
+                        .width(Length::Fixed(220.0)),
+                    )
+                    .push(tag_chips(&rule_tags, 14, move |tag| {
+                        Message::RemoveTag(index, tag.id)
+                    })),
+            )
+            .push(
+                Row::new()
+                    .spacing(12)
+                    .align_y(Alignment::Center)
+                    .push(
+                        TextInput::new(
+                            &t!("rules.placeholder.language"),
+                            rule.language.as_deref().unwrap_or_default(),
+                        )
+                        .on_input(move |language| Message::LanguageChanged(index, language))
+                        .padding(Padding::new(10.0))
+                        .size(14)
+                        .style(Modern::text_input())
+                        .width(Length::Fixed(220.0)),
+                    )
+                    .push(
+                        Checkbox::new(t!("rules.label.pin"), rule.pin)
+                            .on_toggle(move |pin| Message::PinToggled(index, pin))
+                            .size(18)
+                            .text_size(14)
+                            .style(Modern::checkbox()),
+                    ),
+            );
+
+        Container::new(
+            Column::new()
+                .spacing(16)
+                .push(top)
+                .push(conditions)
+                .push(actions),
+        )
+        .padding(20)
+        .width(Length::Fill)
+        .style(Modern::card_container())
+        .into()
+    }
+}
@@ -1,79 +1,360 @@
-use crate::components::image_container::ImageContainer;
-use crate::components::{empty_state, header, image_preview_modal, pagination, search_bar, tag_selector};
+use crate::components::accessibility::labeled;
+use crate::components::image_container::{self, ImageContainer};
+use crate::components::{empty_state, header, highlighted_text, image_preview_modal, marquee, pagination, quick_look, search_bar, skeleton, tag_selector};
+use crate::components::tag_chips::{self, tag_chips};
 use crate::components::tag_selector::TagSelector;
+use crate::animation::{self, Animation};
 use crate::config::{
-    get_current_page, get_scroll_offset, get_search_query, get_selected_tags, get_settings,
-    set_current_page, set_scroll_offset, set_search_query, set_selected_tags,
+    get_current_page, get_excluded_tags, get_refinements, get_scroll_offset, get_search_query,
+    get_selected_tags, get_settings, get_tag_match, set_current_page, set_excluded_tags,
+    set_refinements, set_scroll_offset, set_search_query, set_selected_tags, set_tag_match,
+    update_config,
 };
 use crate::dtos::image_dto::ImageDTO;
+use crate::dtos::stack_dto::StackDTO;
 use crate::dtos::tag_dto::TagDTO;
-use crate::models::filter::{Filter, SortOrder};
-use crate::services::clipboard_service::copy_image_to_clipboard;
+use crate::dtos::tag_settings_dto::TagSettingsDTO;
+use crate::models::export_preset::ExportPreset;
+use crate::models::filter::{Filter, LanguageFilter, SortOrder, TagMatchMode};
+use crate::models::grid_layout::GridLayout;
+use crate::models::saved_search::SavedSearch;
+use crate::models::tag_color::TagColor;
+use crate::models::view_density::{CardSize, ViewDensity};
+use crate::services::clipboard_service::{
+    CopyFormat, clear_copy_history, copy_file_reference, copy_files_to_clipboard, copy_history,
+    copy_image_to_clipboard,
+};
+use crate::services::change_service::DataChange;
 use crate::services::toast_service::{push_error, push_success};
-use crate::services::{file_service, image_service, tag_service};
+use crate::dtos::version_dto::VersionDTO;
+use crate::services::export_service::{ContactSheetOptions, SheetFormat};
+use crate::services::file_service::DirContents;
+use crate::services::{
+    export_service, file_naming, file_service, image_cache, image_service, query_parser, similarity_service, stack_service, statistics_service, tag_service, tag_settings_service,
+    trash_service, version_service, wallpaper_service,
+};
 use iced::alignment::{Horizontal};
 use iced::widget::image::{Handle};
 use iced::widget::{
-    Column, Container, Row, Scrollable, Space,
-    scrollable,
+    Button, Checkbox, Column, Container, PickList, Row, Scrollable, Space, Text, TextInput,
+    container, mouse_area, responsive, scrollable, stack, text_input, tooltip,
 };
-use iced::{Element, Length, Task};
+use iced::{Alignment, Element, Length, Padding, Task, keyboard};
+use iced_font_awesome::fa_icon_solid;
+use rfd::AsyncFileDialog;
 use iced_modern_theme::Modern;
 use image::{DynamicImage, ImageFormat};
-use log::{error, info};
+use log::{error, info, warn};
+use std::borrow::Cow;
 use std::collections::HashSet;
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use crate::models::enums::image_type::ImageType;
+use crate::utils::{capitalize_first, random_index};
+use crate::t_count;
 
 pub enum Action {
     None,
     Run(Task<Message>),
     NavigateToUpdate(ImageDTO),
     NavigatorToRegister(Option<DynamicImage>, Option<ImageFormat>),
+    FilterByTag(TagDTO),
+    /// Pins the query and tags to the navbar
+    PinSearch(SavedSearch),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    TagSelectorMessage(tag_selector::Message),
+    TagSelector(tag_selector::Message),
     QueryChanged(String),
+    /// Searches for the query and keeps it in the history
+    SubmitQuery,
+    /// Searches for a query picked from the history
+    HistorySelected(String),
+    /// Turns refining on, keeping the query as the first step, or off, dropping the steps
+    ToggleRefine,
+    /// Keeps the query as a step the next ones search within
+    AddRefinement,
+    /// Drops the last step, back in the query to edit
+    RefineBack,
     DelayedQuery(String, u64),
     SearchButtonPressed,
     RequestImages,
     PushContainer(Vec<ImageDTO>, u64, u64, bool),
     OpenImage(ImageDTO),
     OpenLocalImage(i64),
+    OpenSource(String),
+    /// Deletes the card. A folder entry is deleted on the second message, once its
+    /// contents were shown.
     DeleteImage(ImageDTO, ImageType),
+    DeleteContentsCounted(i64, Result<DirContents, String>),
+    CancelDelete,
     CopyImage(String),
+    ToggleCopyMenu(i64),
+    CopyReference(String, String, CopyFormat),
+    EditExternally(String),
+    SetWallpaper(String),
+    PinnedLoaded(Vec<ImageDTO>),
+    TogglePin(i64, bool),
+    SimilarLoaded(i64, Vec<ImageDTO>),
+    ShowRelated(ImageDTO),
+    FilterByTag(TagDTO),
+    OpenSimilar(usize),
+    SimilarBack,
+    /// Previews a registered image opened from an `organizer://image` link
+    OpenLinkedImage(i64),
+    LinkedImageLoaded(Result<Option<ImageDTO>, String>),
+    /// Something changed the data shown, possibly outside this screen
+    DataChanged(DataChange),
+    Refreshed(Vec<ImageDTO>, u64, u64),
+    /// Loading the results failed, the grid shows an error with a retry button
+    SearchFailed(String),
+    PinChanged(Result<(), String>),
+    OpenPinned(usize),
+    PreviousPinned,
+    NextPinned,
+    SurpriseMe,
+    SurpriseFound(Result<Option<ImageDTO>, String>),
+    PreviousSurprise,
+    OpenFileLocation(String),
+    ToggleSurpriseFilter,
+    ToggleExportPanel,
+    ToggleFacets,
+    /// Tags of the current results with how many of them carry each
+    FacetsLoaded(Vec<(TagDTO, u64)>),
+    /// Narrows the results to the images carrying the tag
+    FacetSelected(TagDTO),
+    ToggleCopyHistory,
+    PinSearch,
+    ClearCopyHistory,
+    ToggleSelecting,
+    ToggleSelection(i64),
+    /// Cards touched by a rectangle dragged over the grid, added to the selection
+    /// with ctrl or shift held and replacing it otherwise
+    MarqueeSelected(Vec<iced::advanced::widget::Id>),
+    /// Held modifier keys, a ctrl or shift click on a card selects it instead of opening it
+    ModifiersChanged(keyboard::Modifiers),
+    CopySelectedFiles,
+    BatchTagChanged(String),
+    AddTagToSelection,
+    RemoveTagFromSelection,
+    SelectionTagged(Result<usize, String>),
+    TrashSelection,
+    SelectionTrashed(Result<usize, String>),
+    CreateStack,
+    GroupIntoFolder,
+    GroupedIntoFolder(Result<i64, String>),
+    StackLoaded(Option<StackDTO>),
+    SetStackCover(i64),
+    RemoveFromStack(i64),
+    StackChanged(Result<(), String>),
+    ExportColumnsChanged(u32),
+    ExportRowsChanged(u32),
+    ExportLabelsToggled(bool),
+    ExportFormatChanged(SheetFormat),
+    ExportContactSheet,
+    ContactSheetExported(Option<Result<usize, String>>),
+    ExportWatermarkToggled(bool),
+    ExportPresetChanged(PresetChoice),
+    ExportCopies,
+    CopiesExported(Option<Result<usize, String>>),
+    ExternalEditFinished(String, Result<bool, String>),
+    ThumbnailsRefreshed(Vec<String>),
+    /// Thumbnails decoded in the background, by path
+    ThumbnailsDecoded(Vec<(String, Handle)>),
+    ToggleInfoPanel,
+    VersionsLoaded(String, Result<Vec<VersionDTO>, String>),
+    ViewVersion(String),
+    RestoreVersion(VersionDTO),
+    ReplaceOriginal(String),
+    /// Opens the name field for the previewed image's file
+    StartRename(i64, String),
+    RenameChanged(String),
+    ConfirmRename,
+    CancelRename,
+    FileRenamed(Result<ImageDTO, String>),
+    ReplacementChosen(String, PathBuf),
+    VersionChanged(String, Result<(), String>),
     TagsLoaded(HashSet<TagDTO>),
+    TagSettingsLoaded(Option<TagSettingsDTO>, bool),
     GoToPage(u64),
     Update(ImageDTO),
     ClosePreview,
     CloseFolder,
+    CloseHistory,
+    CloseQuickLook,
+    /// Drops the query, its steps, the tags and the language
+    ClearFilters,
     NavigateToRegister,
     SortOrderChanged(SortOrder),
+    LanguagesLoaded(Vec<String>),
+    LanguageChanged(LanguageFilter),
+    TagMatchChanged(TagMatchMode),
+    FuzzyToggled(bool),
     ImagePasted(DynamicImage, ImageFormat),
+    /// Rendition of a large image ready, or none needed
+    /// Decoded image, with the rendition it was decoded from
+    PreviewLoaded(String, Result<(Option<String>, Handle), String>),
+    CancelPreviewLoad,
+    ToggleFullResolution,
     PreviousImage,
     NextImage,
     ScrollChanged(scrollable::Viewport),
+    /// Key not taken by a text input or a global shortcut
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
+    /// The cursor entered or left a card
+    CardHovered(i64, bool),
+    /// The card was hovered long enough for its quick look
+    QuickLookDue(i64),
+    QuickLookLoaded(i64, Result<Handle, String>),
+    AnimationFrame(Instant),
     NoOps,
 }
 
 pub struct Search {
     query: String,
+    /// Earlier queries the results are narrowed by, oldest first
+    refinements: Vec<String>,
+    /// Whether a submitted query becomes a step instead of replacing the search
+    refining: bool,
+    /// Lists the earlier queries containing the typed text under the input
+    history_open: bool,
+    /// History entry picked with the arrow keys, its query is in the input
+    history_cursor: Option<usize>,
+    /// Text typed before picking from the history, the list is narrowed by it
+    history_typed: String,
     images: Vec<ImageContainer>,
-    tag_selector: TagSelector,
+    /// Boxed, its selected, excluded and available tag sets would make the screen
+    /// the largest by far
+    tag_selector: Box<TagSelector>,
+    /// Whether the images need every selected tag or any of them
+    tag_match: TagMatchMode,
     page_size: u64,
     current_page: u64,
     total_pages: u64,
     show_preview: bool,
     preview_handle: Handle,
+    /// Original of the image in the preview
+    preview_path: String,
+    /// Mid-size rendition the preview shows in place of a large original
+    preview_rendition: Option<String>,
+    /// Shows the original at full resolution instead of the rendition
+    full_resolution: bool,
+    /// Decode of the previewed image still running, dropping it aborts the load
+    preview_loading: Option<iced::task::Handle>,
     current_preview_index: usize,
     selected_sort_order: SortOrder,
+    /// Only images in this language are shown
+    language: Option<String>,
+    /// Languages set on images, offered by the filter
+    languages: Vec<String>,
+    view_density: ViewDensity,
+    /// Tag selected alone, whose default view is applied
+    primary_tag: Option<i64>,
+    /// Default view of the primary tag currently applied
+    tag_settings: TagSettingsDTO,
     current_search_id: u64,
     folder_opened: bool,
+    /// Id and description of the image whose related images are shown in place of the results
+    related_to: Option<(i64, String)>,
+    /// Stack whose images are shown in place of the results
+    opened_stack: Option<i64>,
+    /// Picking entries for a batch action or a stack, in the order they were picked.
+    /// A shift click selects the cards from the last one picked to the clicked one.
+    selecting: bool,
+    selection: Vec<i64>,
+    /// Tag typed in the selection bar, added to or removed from the selection
+    batch_tag: String,
+    modifiers: keyboard::Modifiers,
     scroll_id: scrollable::Id,
     scroll_offset: f32,
+    /// Shows the information and versions panel next to the preview
+    show_info: bool,
+    /// Image whose file is being renamed, with the name typed so far
+    renaming: Option<(i64, String)>,
+    /// Versions of the previewed image
+    versions: Vec<VersionDTO>,
+    /// Images like the previewed one, shown below the preview
+    similar: Vec<ImageDTO>,
+    /// Images opened from the similar strip or a link, the last one is previewed
+    similar_trail: Vec<ImageDTO>,
+    /// Pinned images shown in the strip above the results
+    pinned: Vec<ImageDTO>,
+    /// Index of the pinned image open in the preview
+    pinned_preview: Option<usize>,
+    /// Random images opened with "Surprise me", in the order they were picked
+    surprises: Vec<ImageDTO>,
+    surprise_index: usize,
+    /// Pick random images only among those matching the current query and tags
+    surprise_filtered: bool,
+    /// Shows the contact sheet export options above the results
+    show_export: bool,
+    /// Shows the images recently copied to the clipboard above the results
+    show_copy_history: bool,
+    /// Tag counts within the results, shown beside them. None while the panel is closed
+    facets: Option<Vec<(TagDTO, u64)>>,
+    export_options: ContactSheetOptions,
+    exporting: bool,
+    /// Whether exported copies get the watermark set in the preferences
+    export_watermark: bool,
+    /// Size and format of exported copies, `None` copies the originals as they are
+    export_preset: Option<ExportPreset>,
+    /// First page not loaded yet, the grid shows placeholders
+    loading: bool,
+    /// Images changed while a preview was open, the grid reloads once it closes
+    stale: bool,
+    /// Last load of the results failed
+    load_failed: bool,
+    /// Keyboard focus, counting the tags of the selector first and then the cards
+    focus: Option<usize>,
+    /// Time of the last animation frame, what the view draws the transitions at
+    frame: Instant,
+    preview_appear: Animation,
+    /// Cards lifted under the cursor, or settling back down
+    card_lift: Vec<(i64, Animation)>,
+    /// Card under the cursor
+    hovered_card: Option<i64>,
+    /// Card shown in the quick look, with its thumbnail until the rendition loads.
+    /// Boxed to keep the screen small while it is closed.
+    quick_look: Option<Box<(i64, Handle)>>,
+    /// Folder entry waiting for its deletion to be confirmed, boxed like `quick_look`
+    pending_delete: Option<Box<PendingDelete>>,
+}
+
+const SHEET_SIZES: [u32; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+/// Entry of the export preset list, `None` for the originals as they are
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetChoice(Option<ExportPreset>);
+
+impl std::fmt::Display for PresetChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(preset) => write!(f, "{}", preset),
+            None => write!(f, "{}", t!("search.export.no_preset")),
+        }
+    }
+}
+
+/// Folder entry about to be deleted, with what it holds once counted
+struct PendingDelete {
+    image: ImageDTO,
+    contents: Option<Result<DirContents, String>>,
+}
+
+const SIMILAR_COUNT: usize = 10;
+/// Tags listed in the facet panel, the most common in the results
+const FACET_COUNT: u64 = 30;
+/// Earlier queries listed under the input at most
+const HISTORY_SHOWN: usize = 8;
+const GRID_SPACING: f32 = 20.0;
+const GRID_PADDING: f32 = 20.0;
+/// Room left for the vertical scrollbar when fitting cards to columns
+const SCROLLBAR_WIDTH: f32 = 10.0;
+
+/// Id of the container around a card in the grid
+fn card_id(id: i64) -> container::Id {
+    container::Id::new(format!("card-{id}"))
 }
 
 impl Search {
@@ -81,27 +362,89 @@ impl Search {
         let settings = get_settings();
         let page_size = settings.config.items_per_page;
         let query = get_search_query();
+        let refinements = get_refinements();
         let page = get_current_page();
         let selected_tags = get_selected_tags();
+        let excluded_tags = get_excluded_tags();
+        let tag_match = get_tag_match();
         let scroll_offset = get_scroll_offset();
+        let primary_tag = Self::single_tag(&selected_tags);
         let component = Self {
             query: query.clone(),
+            refining: !refinements.is_empty(),
+            refinements: refinements.clone(),
+            history_open: false,
+            history_cursor: None,
+            history_typed: String::new(),
             images: Vec::with_capacity(page_size as usize),
-            tag_selector: TagSelector::new(selected_tags.clone(), false, true),
+            tag_selector: Box::new(
+                TagSelector::new(selected_tags.clone(), false, true)
+                    .excludable(excluded_tags.clone()),
+            ),
+            tag_match,
             page_size,
             current_page: page,
             total_pages: 0,
             show_preview: false,
             preview_handle: Handle::from_path("".to_string()),
+            preview_path: String::new(),
+            preview_rendition: None,
+            full_resolution: false,
+            preview_loading: None,
             current_preview_index: 0,
             selected_sort_order: SortOrder::CreatedDesc,
+            language: None,
+            languages: Vec::new(),
+            view_density: ViewDensity::default(),
+            primary_tag,
+            tag_settings: TagSettingsDTO::default(),
             current_search_id: 0,
             folder_opened: false,
+            related_to: None,
+            opened_stack: None,
+            selecting: false,
+            selection: Vec::new(),
+            batch_tag: String::new(),
+            modifiers: keyboard::Modifiers::default(),
             scroll_id: scrollable::Id::unique(),
             scroll_offset,
+            show_info: false,
+            renaming: None,
+            versions: Vec::new(),
+            similar: Vec::new(),
+            similar_trail: Vec::new(),
+            pinned: Vec::new(),
+            pinned_preview: None,
+            surprises: Vec::new(),
+            surprise_index: 0,
+            surprise_filtered: true,
+            show_export: false,
+            show_copy_history: false,
+            facets: None,
+            export_options: ContactSheetOptions {
+                columns: 5,
+                rows: 6,
+                labels: true,
+                format: SheetFormat::Png,
+            },
+            exporting: false,
+            export_watermark: false,
+            export_preset: None,
+            loading: true,
+            stale: false,
+            load_failed: false,
+            focus: None,
+            frame: Instant::now(),
+            preview_appear: Animation::new(1.0),
+            card_lift: Vec::new(),
+            hovered_card: None,
+            quick_look: None,
+            pending_delete: None,
         };
 
         let task = Task::batch([
+            Self::load_pinned(),
+            Self::load_languages(),
             Task::perform(
                 async { tag_service::find_all().await },
                 |result| match result {
@@ -112,128 +455,2723 @@ impl Search {
                     }
                 },
             ),
-            Task::perform(
-                async move {
-                    let mut filter = Filter::new();
-                    filter.query = query;
-                    filter.tags = selected_tags.iter().map(|tag| tag.name.clone()).collect();
+            Task::future(async move {
+                let settings = match primary_tag {
+                    Some(tag_id) => tag_settings_service::find(tag_id)
+                        .await
+                        .unwrap_or_else(|err| {
+                            error!("Failed to load tag settings: {}", err);
+                            None
+                        }),
+                    None => None,
+                };
 
-                    match image_service::find_all(filter, page, page_size).await {
-                        Ok(page) => (page.content, page.page_number, page.total_pages),
-                        Err(_) => (vec![], 0, 0),
-                    }
-                },
-                |(images, current_page, total_pages)| {
-                    Message::PushContainer(images, current_page, total_pages, false)
-                },
-            ),
+                let mut filter = Filter::new();
+                filter.query = query;
+                filter.refinements = refinements;
+                filter.tags = selected_tags.iter().map(|tag| tag.name.clone()).collect();
+                filter.excluded_tags = excluded_tags.iter().map(|tag| tag.name.clone()).collect();
+                filter.tag_match = tag_match;
+                if let Some(sort_order) = settings.and_then(|s| s.sort_order) {
+                    filter.sort_order = sort_order;
+                }
+
+                let page = image_service::find_all(filter, page, page_size).await;
+                (page, settings)
+            })
+            .then(|(page, settings)| {
+                let results = match page {
+                    Ok(page) => Message::PushContainer(
+                        page.content,
+                        page.page_number,
+                        page.total_pages,
+                        false,
+                    ),
+                    Err(err) => Message::SearchFailed(err.to_string()),
+                };
+                Task::batch([
+                    Task::done(Message::TagSettingsLoaded(settings, false)),
+                    Task::done(results),
+                ])
+            }),
         ]);
 
         (component, task)
     }
 
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    /// Message closing the topmost dialog, popup or preview, none when none is open
+    pub fn modal_close(&self) -> Option<Message> {
+        if self.pending_delete.is_some() {
+            Some(Message::CancelDelete)
+        } else if self.renaming.is_some() {
+            Some(Message::CancelRename)
+        } else if self.history_open {
+            Some(Message::CloseHistory)
+        } else if self.quick_look.is_some() {
+            Some(Message::CloseQuickLook)
+        } else if self.show_preview
+            || self.pinned_preview.is_some()
+            || !self.surprises.is_empty()
+            || !self.similar_trail.is_empty()
+        {
+            Some(Message::ClosePreview)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a folder, stack or related images are shown in place of the results
+    pub fn is_in_folder(&self) -> bool {
+        self.folder_opened || self.opened_stack.is_some() || self.related_to.is_some()
+    }
+
+    /// Whether the query, tags or language narrow the results
+    pub fn has_filters(&self) -> bool {
+        !self.query.is_empty()
+            || !self.refinements.is_empty()
+            || !self.tag_selector.selected.is_empty()
+            || !self.tag_selector.excluded.is_empty()
+            || self.language.is_some()
+    }
+
+    /// Short name of the search for its tab
+    pub fn tab_title(&self) -> String {
+        if let Some((_, description)) = &self.related_to {
+            return t!("search.tabs.related", description = description).to_string();
+        }
+        self.filters_title()
+            .unwrap_or_else(|| t!("search.tabs.all").to_string())
+    }
+
+    /// Query, after the refinement steps, followed by the `#tags` and the excluded
+    /// `-#tags`, None when the search has none of them. Tags matched by any rather than all are joined with `|`
+    fn filters_title(&self) -> Option<String> {
+        let mut parts: Vec<String> = Vec::new();
+        let steps: Vec<&str> = self
+            .refinements
+            .iter()
+            .map(String::as_str)
+            .chain([self.query.trim()])
+            .filter(|step| !step.is_empty())
+            .collect();
+        if !steps.is_empty() {
+            parts.push(steps.join(" › "));
+        }
+        let mut tags: Vec<&str> = self
+            .tag_selector
+            .selected
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect();
+        tags.sort_unstable();
+        let tags: Vec<String> = tags.into_iter().map(|tag| format!("#{}", tag)).collect();
+        match self.tag_match {
+            TagMatchMode::Any if !tags.is_empty() => parts.push(tags.join(" | ")),
+            _ => parts.extend(tags),
+        }
+        let mut excluded: Vec<&str> = self
+            .tag_selector
+            .excluded
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect();
+        excluded.sort_unstable();
+        parts.extend(excluded.into_iter().map(|tag| format!("-#{}", tag)));
+
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    /// Points the session state back at this search when its tab becomes active again,
+    /// so screens that rebuild the search from it pick up this tab's filters. Keys
+    /// released while it was parked never reached it, so it starts with none held
+    pub fn restore_ui_state(&mut self) {
+        self.modifiers = keyboard::Modifiers::default();
+        set_search_query(self.query.clone());
+        set_refinements(self.refinements.clone());
+        set_selected_tags(self.tag_selector.selected.clone());
+        set_excluded_tags(self.tag_selector.excluded.clone());
+        set_tag_match(self.tag_match);
+        set_current_page(self.current_page);
+        set_scroll_offset(self.scroll_offset);
+    }
+
     // Helpers
 
-    fn change_preview(&mut self, delta: isize) {
-        if self.show_preview && !self.images.is_empty() {
-            let len = self.images.len() as isize;
-            // calcula o índice circular
-            self.current_preview_index =
-                ((self.current_preview_index as isize + delta + len) % len) as usize;
+    /// Keyboard control: Tab and the arrows move the focus over the tags and cards,
+    /// Enter or Space toggles the tag, Enter opens the card and Space shows or hides
+    /// the quick look of the hovered or focused card, E edits, P pins, `/` jumps to
+    /// the query and Page Up/Down turn pages. Up and Down pick from the search
+    /// history while it's listed, Escape closes it. While a preview is open the arrows step
+    /// through it.
+    fn handle_key(&mut self, key: keyboard::Key, modifiers: keyboard::Modifiers) -> Action {
+        use keyboard::key::Named;
 
-            let current_image = &self.images[self.current_preview_index];
-            let path = if current_image.image_dto.is_folder {
-                &current_image.image_dto.thumbnail_path
-            } else {
-                &current_image.image_dto.path
+        let (previous, next) = if self.pinned_preview.is_some() {
+            (Some(Message::PreviousPinned), Some(Message::NextPinned))
+        } else if self.surprises.get(self.surprise_index).is_some() {
+            (Some(Message::PreviousSurprise), Some(Message::SurpriseMe))
+        } else if !self.similar_trail.is_empty() {
+            (Some(Message::SimilarBack), None)
+        } else if self.show_preview {
+            (Some(Message::PreviousImage), Some(Message::NextImage))
+        } else {
+            (None, None)
+        };
+        if previous.is_some() || next.is_some() {
+            let message = match key {
+                keyboard::Key::Named(Named::ArrowLeft) => previous,
+                keyboard::Key::Named(Named::ArrowRight) => next,
+                keyboard::Key::Character(ref c) if c == "i" && self.show_preview => {
+                    Some(Message::ToggleInfoPanel)
+                }
+                _ => None,
             };
-            self.preview_handle = Handle::from_path(path.clone());
+            return message.map_or(Action::None, |message| self.update(message));
+        }
+
+        let message = match key {
+            keyboard::Key::Named(Named::Tab) => {
+                self.move_focus(if modifiers.shift() { -1 } else { 1 });
+                None
+            }
+            keyboard::Key::Named(Named::ArrowRight) => {
+                self.move_focus(1);
+                None
+            }
+            keyboard::Key::Named(Named::ArrowLeft) => {
+                self.move_focus(-1);
+                None
+            }
+            keyboard::Key::Named(Named::Space) if self.focused_tag().is_none() => {
+                if self.quick_look.take().is_some() {
+                    return Action::None;
+                }
+                return match self.hovered_card.or(self.focused_card()) {
+                    Some(id) => self.open_quick_look(id),
+                    None => Action::None,
+                };
+            }
+            keyboard::Key::Named(Named::Enter | Named::Space) => {
+                if let Some(tag) = self.focused_tag() {
+                    Some(Message::TagSelector(tag_selector::Message::ToggleTag(tag)))
+                } else if let Some(card) = self.focused_container() {
+                    Some(if self.selecting {
+                        Message::ToggleSelection(card.id)
+                    } else {
+                        Message::OpenImage(card.image_dto.clone())
+                    })
+                } else {
+                    None
+                }
+            }
+            keyboard::Key::Named(Named::ArrowDown) if self.history_open => {
+                self.move_history_cursor(1);
+                None
+            }
+            keyboard::Key::Named(Named::ArrowUp) if self.history_open => {
+                self.move_history_cursor(-1);
+                None
+            }
+            keyboard::Key::Named(Named::Escape) if self.history_open => {
+                self.close_history();
+                None
+            }
+            keyboard::Key::Character(ref c) if c == "/" => {
+                self.set_focus(None);
+                self.history_open = true;
+                self.history_typed = self.query.clone();
+                return Action::Run(text_input::focus(search_bar::input_id()));
+            }
+            keyboard::Key::Character(ref c) if c == "e" => self
+                .focused_container()
+                .filter(|card| card.image_dto.is_prepared)
+                .map(|card| Message::Update(card.image_dto.clone())),
+            keyboard::Key::Character(ref c) if c == "p" => self
+                .focused_container()
+                .map(|card| Message::TogglePin(card.id, !card.image_dto.is_pinned)),
+            keyboard::Key::Named(Named::PageDown) if self.current_page + 1 < self.total_pages => {
+                Some(Message::GoToPage(self.current_page + 1))
+            }
+            keyboard::Key::Named(Named::PageUp) if self.current_page > 0 => {
+                Some(Message::GoToPage(self.current_page - 1))
+            }
+            _ => None,
+        };
+        message.map_or(Action::None, |message| self.update(message))
+    }
+
+    /// Earlier queries containing the typed text, latest first
+    fn history_entries(&self) -> Vec<String> {
+        let typed = self.history_typed.trim().to_lowercase();
+        get_settings()
+            .config
+            .search_history
+            .iter()
+            .flatten()
+            .filter(|query| {
+                let query = query.to_lowercase();
+                query.contains(&typed) && query != typed
+            })
+            .take(HISTORY_SHOWN)
+            .cloned()
+            .collect()
+    }
+
+    /// Picks the next or previous history entry, putting its query in the input.
+    /// Stepping past either end goes back to the typed text
+    fn move_history_cursor(&mut self, step: isize) {
+        let entries = self.history_entries();
+        if entries.is_empty() {
+            return;
         }
+        let next = match self.history_cursor {
+            Some(index) => index as isize + step,
+            None if step < 0 => entries.len() as isize - 1,
+            None => 0,
+        };
+        if (0..entries.len() as isize).contains(&next) {
+            self.history_cursor = Some(next as usize);
+            self.query = entries[next as usize].clone();
+        } else {
+            self.history_cursor = None;
+            self.query = self.history_typed.clone();
+        }
+        set_search_query(self.query.clone());
     }
 
-    fn change_scroll(&mut self) -> Task<Message> {
+    fn close_history(&mut self) {
+        self.history_open = false;
+        self.history_cursor = None;
+    }
 
-        let scroll_offset = self.scroll_offset;
-        let scroll_id = self.scroll_id.clone();
-        let task = Task::done(()).then(move |_| {
-            scrollable::scroll_to(
-                scroll_id.clone(),
-                scrollable::AbsoluteOffset {
-                    x: 0.0,
-                    y: scroll_offset,
+    /// Moves the keyboard focus by `step`, wrapping around the tags and cards
+    fn move_focus(&mut self, step: isize) {
+        let total = self.tag_selector.available.len() + self.images.len();
+        if total == 0 {
+            self.set_focus(None);
+            return;
+        }
+        let next = match self.focus {
+            Some(index) => (index.min(total - 1) as isize + step).rem_euclid(total as isize),
+            None if step < 0 => total as isize - 1,
+            None => 0,
+        };
+        self.set_focus(Some(next as usize));
+    }
+
+    fn set_focus(&mut self, focus: Option<usize>) {
+        self.focus = focus;
+        self.tag_selector.focused = focus.filter(|index| *index < self.tag_selector.available.len());
+    }
+
+    fn focused_tag(&self) -> Option<TagDTO> {
+        let index = self.focus?;
+        self.tag_selector.sorted_tags().get(index).map(|tag| (*tag).clone())
+    }
+
+    fn focused_container(&self) -> Option<&ImageContainer> {
+        let index = self.focus?.checked_sub(self.tag_selector.available.len())?;
+        self.images.get(index)
+    }
+
+    fn focused_card(&self) -> Option<i64> {
+        self.focused_container().map(|card| card.id)
+    }
+
+    /// Shows the card's thumbnail in the quick look and loads its mid-size rendition
+    fn open_quick_look(&mut self, id: i64) -> Action {
+        let Some(card) = self.images.iter().find(|img| img.id == id) else {
+            return Action::None;
+        };
+        self.quick_look = Some(Box::new((id, Handle::from_path(&card.image_dto.thumbnail_path))));
+        if card.image_dto.is_folder {
+            return Action::None;
+        }
+
+        let path = card.image_dto.path.clone();
+        Action::Run(Task::perform(
+            async move {
+                let rendition = file_service::prepare_preview(path.clone())
+                    .await
+                    .unwrap_or_else(|err| {
+                        warn!("Failed to prepare the quick look of {}: {}", path, err);
+                        None
+                    });
+                image_cache::load(rendition.unwrap_or(path)).await
+            },
+            move |result| Message::QuickLookLoaded(id, result),
+        ))
+    }
+
+    fn preview_actions(&self) -> Vec<image_preview_modal::PreviewAction<Message>> {
+        let Some(img) = self.images.get(self.current_preview_index) else {
+            return Vec::new();
+        };
+
+        let file_actions = (!img.image_dto.is_folder).then(|| {
+            [
+                image_preview_modal::PreviewAction {
+                    icon: "paintbrush",
+                    tooltip: t!("message.image.container.edit_external").to_string(),
+                    on_press: Message::EditExternally(img.image_dto.path.clone()),
                 },
-            )
+                image_preview_modal::PreviewAction {
+                    icon: "desktop",
+                    tooltip: t!("message.image.container.set_wallpaper").to_string(),
+                    on_press: Message::SetWallpaper(img.image_dto.path.clone()),
+                },
+            ]
         });
+        let info_action = image_preview_modal::PreviewAction {
+            icon: "circle-info",
+            tooltip: t!("search.preview.info").to_string(),
+            on_press: Message::ToggleInfoPanel,
+        };
 
-        task
+        [
+            ("file", "message.image.container.copy_path", CopyFormat::Path),
+            ("globe", "message.image.container.copy_url", CopyFormat::FileUrl),
+            ("code", "message.image.container.copy_markdown", CopyFormat::Markdown),
+        ]
+        .into_iter()
+        .map(|(icon, tooltip, format)| image_preview_modal::PreviewAction {
+            icon,
+            tooltip: t!(tooltip).to_string(),
+            on_press: Message::CopyReference(
+                img.image_dto.path.clone(),
+                img.image_dto.description.clone(),
+                format,
+            ),
+        })
+        .chain(file_actions.into_iter().flatten())
+        .chain(std::iter::once(info_action))
+        .chain(self.resolution_action())
+        .collect()
     }
 
-    pub fn update(&mut self, message: Message) -> Action {
-        match message {
-            Message::QueryChanged(query) => {
-                self.query = query.clone();
-                set_search_query(query.clone());
-                self.current_search_id += 1;
-                let search_id = self.current_search_id;
+    /// Switches between the rendition and the full resolution original of a large image
+    fn resolution_action(&self) -> Option<image_preview_modal::PreviewAction<Message>> {
+        self.preview_rendition.as_ref()?;
+        Some(if self.full_resolution {
+            image_preview_modal::PreviewAction {
+                icon: "compress",
+                tooltip: t!("search.preview.fit_resolution").to_string(),
+                on_press: Message::ToggleFullResolution,
+            }
+        } else {
+            image_preview_modal::PreviewAction {
+                icon: "magnifying-glass-plus",
+                tooltip: t!("search.preview.full_resolution").to_string(),
+                on_press: Message::ToggleFullResolution,
+            }
+        })
+    }
 
-                let task = Task::perform(
-                    {
-                        let query = query;
-                        async move {
-                            tokio::time::sleep(Duration::from_millis(300)).await;
-                            (query, search_id)
-                        }
-                    },
-                    |(query, search_id)| Message::DelayedQuery(query, search_id),
-                );
-                Action::Run(task)
+    /// The tag filtering alone, which is the one whose default view applies
+    fn single_tag(tags: &HashSet<TagDTO>) -> Option<i64> {
+        match tags.len() {
+            1 => tags.iter().next().map(|tag| tag.id),
+            _ => None,
+        }
+    }
+
+    /// Applies the default view of the primary tag. Settings the tag leaves unset fall back
+    /// to the regular defaults only when the previous tag had overridden them.
+    fn apply_tag_settings(&mut self, settings: TagSettingsDTO) {
+        match settings.sort_order {
+            Some(sort_order) => self.selected_sort_order = sort_order,
+            None if self.tag_settings.sort_order.is_some() => {
+                self.selected_sort_order = SortOrder::CreatedDesc
             }
+            None => {}
+        }
+        self.view_density = settings.view_density.unwrap_or_default();
+        self.tag_settings = settings;
+    }
 
-            Message::DelayedQuery(query, search_id) => {
-                if self.query == query && self.current_search_id == search_id {
-                    let task = Task::perform(
-                        async {
-                            tokio::time::sleep(Duration::from_millis(300)).await;
-                        },
-                        |_| Message::SearchButtonPressed,
-                    );
-                    Action::Run(task)
-                } else {
-                    Action::None
+    fn load_pinned() -> Task<Message> {
+        Task::perform(
+            async {
+                image_service::find_pinned().await.unwrap_or_else(|err| {
+                    error!("Failed to load pinned images: {}", err);
+                    Vec::new()
+                })
+            },
+            Message::PinnedLoaded,
+        )
+    }
+
+    fn show_pinned(&mut self, index: usize) -> Task<Message> {
+        let Some(path) = self.pinned.get(index).map(|image| image.path.clone()) else {
+            return Task::none();
+        };
+        self.pinned_preview = Some(index);
+        self.show_in_preview(&path)
+    }
+
+    /// Points the preview at an image. Its thumbnail shows at once while the image is
+    /// decoded in the background, from its mid-size rendition when it is large.
+    fn show_in_preview(&mut self, path: &str) -> Task<Message> {
+        let thumbnail = file_naming::thumbnail_path_for(Path::new(path))
+            .filter(|thumbnail| thumbnail.exists());
+        self.preview_handle = match thumbnail {
+            Some(thumbnail) => Handle::from_path(thumbnail),
+            None => Handle::from_path(""),
+        };
+        self.preview_path = path.to_string();
+        self.preview_rendition = None;
+        self.full_resolution = false;
+        self.load_preview()
+    }
+
+    /// Decodes the previewed image off the UI thread, the original when at full
+    /// resolution and otherwise its rendition, generated first when missing.
+    /// A load still running for the previous image is aborted.
+    fn load_preview(&mut self) -> Task<Message> {
+        let path = self.preview_path.clone();
+        let full_resolution = self.full_resolution;
+        let (task, handle) = Task::perform(
+            {
+                let path = path.clone();
+                async move {
+                    let rendition = if full_resolution {
+                        None
+                    } else {
+                        file_service::prepare_preview(path.clone())
+                            .await
+                            .unwrap_or_else(|err| {
+                                warn!("Failed to prepare the preview of {}: {}", path, err);
+                                None
+                            })
+                    };
+                    let source = rendition.clone().unwrap_or(path);
+                    let decoded = image_cache::load(source).await?;
+                    Ok((rendition, decoded))
                 }
+            },
+            move |result| Message::PreviewLoaded(path.clone(), result),
+        )
+        .abortable();
+        self.preview_loading = Some(handle.abort_on_drop());
+        task
+    }
+
+    fn pinned_strip(&self) -> Element<'_, Message> {
+        let items = self.pinned.iter().enumerate().map(|(index, image)| {
+            Column::new()
+                .spacing(4)
+                .width(Length::Fixed(110.0))
+                .align_x(Alignment::Center)
+                .push(
+                    Button::new(
+                        iced::widget::Image::new(Handle::from_path(&image.thumbnail_path))
+                            .content_fit(iced::ContentFit::Cover)
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                    .padding(0)
+                    .style(Modern::plain_button())
+                    .width(Length::Fixed(110.0))
+                    .height(Length::Fixed(80.0))
+                    .on_press(Message::OpenPinned(index)),
+                )
+                .push(
+                    Text::new(&image.description)
+                        .size(11)
+                        .style(Modern::secondary_text())
+                        .wrapping(iced::widget::text::Wrapping::None),
+                )
+                .into()
+        });
+
+        Container::new(
+            Row::new()
+                .spacing(12)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("thumbtack").size(16.0))
+                .push(
+                    Scrollable::new(
+                        Row::with_children(items)
+                            .spacing(12)
+                            .padding(Padding::ZERO.bottom(12)),
+                    )
+                    .direction(scrollable::Direction::Horizontal(
+                            scrollable::Scrollbar::default(),
+                        ))
+                        .width(Length::Fill),
+                ),
+        )
+            .width(Length::Fill)
+            .padding(10)
+            .style(Modern::card_container())
+            .into()
+    }
+
+    /// Images recently copied to the clipboard, a click copies one again
+    fn copy_history_panel(&self) -> Element<'_, Message> {
+        let history = copy_history();
+        let content: Element<'_, Message> = if history.is_empty() {
+            Text::new(t!("search.copy_history.empty"))
+                .size(14)
+                .style(Modern::secondary_text())
+                .into()
+        } else {
+            let items = history.into_iter().map(|path| {
+                let thumbnail = file_naming::thumbnail_path_for(Path::new(&path))
+                    .filter(|thumbnail| thumbnail.exists())
+                    .unwrap_or_else(|| PathBuf::from(&path));
+                let name = Path::new(&path)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                Column::new()
+                    .spacing(4)
+                    .width(Length::Fixed(110.0))
+                    .align_x(Alignment::Center)
+                    .push(
+                        Button::new(
+                            iced::widget::Image::new(Handle::from_path(thumbnail))
+                                .content_fit(iced::ContentFit::Cover)
+                                .width(Length::Fill)
+                                .height(Length::Fill),
+                        )
+                        .padding(0)
+                        .style(Modern::plain_button())
+                        .width(Length::Fixed(110.0))
+                        .height(Length::Fixed(80.0))
+                        .on_press(Message::CopyImage(path)),
+                    )
+                    .push(
+                        Text::new(name)
+                            .size(11)
+                            .style(Modern::secondary_text())
+                            .wrapping(iced::widget::text::Wrapping::None),
+                    )
+                    .into()
+            });
+            Scrollable::new(
+                Row::with_children(items)
+                    .spacing(12)
+                    .padding(Padding::ZERO.bottom(12)),
+            )
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::default(),
+            ))
+            .width(Length::Fill)
+            .into()
+        };
+
+        Container::new(
+            Row::new()
+                .spacing(12)
+                .align_y(Alignment::Center)
+                .push(labeled(
+                    fa_icon_solid("clock-rotate-left").size(16.0),
+                    t!("search.copy_history.title"),
+                    tooltip::Position::Bottom,
+                ))
+                .push(Container::new(content).width(Length::Fill))
+                .push(
+                    Button::new(Text::new(t!("search.copy_history.clear")).size(14))
+                        .padding(Padding::from([8, 16]))
+                        .style(Modern::plain_button())
+                        .on_press(Message::ClearCopyHistory),
+                ),
+        )
+            .width(Length::Fill)
+            .padding(10)
+            .style(Modern::card_container())
+            .into()
+    }
+
+    /// Filter for the current query, tags and sort order
+    fn current_filter(&self) -> Filter {
+        let mut filter = Filter::new();
+        filter.query = self.query.clone();
+        filter.refinements = self.refinements.clone();
+        filter.tags = self
+            .tag_selector
+            .selected
+            .iter()
+            .map(|t| t.name.clone())
+            .collect();
+        filter.excluded_tags = self
+            .tag_selector
+            .excluded
+            .iter()
+            .map(|t| t.name.clone())
+            .collect();
+        filter.tag_match = self.tag_match;
+        filter.sort_order = self.selected_sort_order;
+        filter.language = self.language.clone();
+        filter.fuzzy = get_settings().config.fuzzy_search.unwrap_or(false);
+        query_parser::apply_operators(&mut filter);
+        filter
+    }
+
+    /// Steps of the refined search, each searching within the results of the ones
+    /// before it. Shown while refining
+    fn refine_breadcrumbs(&self) -> Option<Element<'_, Message>> {
+        if !self.refining {
+            return None;
+        }
+
+        let crumb = |label: String, current: bool| {
+            let crumb = Text::new(label).size(14);
+            if current {
+                crumb.style(Modern::primary_text())
+            } else {
+                crumb.style(Modern::secondary_text())
             }
+        };
+        let separator = || {
+            fa_icon_solid("chevron-right")
+                .size(10.0)
+                .style(Modern::secondary_text())
+        };
 
-            Message::ScrollChanged(viewport) => {
-                self.scroll_offset = viewport.absolute_offset().y;
-                set_scroll_offset(self.scroll_offset);
+        let mut trail = Row::new()
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .push(fa_icon_solid("magnifying-glass-plus").size(14.0))
+            .push(crumb(
+                t!("search.refine.all").to_string(),
+                self.refinements.is_empty(),
+            ));
+        for (index, step) in self.refinements.iter().enumerate() {
+            trail = trail
+                .push(separator())
+                .push(crumb(step.clone(), index + 1 == self.refinements.len()));
+        }
+        if self.refinements.is_empty() {
+            trail = trail.push(separator()).push(
+                Text::new(t!("search.refine.hint"))
+                    .size(13)
+                    .style(Modern::secondary_text()),
+            );
+        }
+
+        let button = |icon: &'static str, label: String| {
+            Button::new(
+                Row::new()
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid(icon).size(12.0))
+                    .push(Text::new(label).size(13)),
+            )
+            .padding(Padding::from([6, 12]))
+        };
+
+        Some(
+            Container::new(
+                Row::new()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(trail.width(Length::Fill))
+                    .push(
+                        button("arrow-left", t!("search.refine.back").to_string())
+                            .style(Modern::secondary_button())
+                            .on_press_maybe(
+                                (!self.refinements.is_empty()).then_some(Message::RefineBack),
+                            ),
+                    )
+                    .push(
+                        button("xmark", t!("search.refine.stop").to_string())
+                            .style(Modern::secondary_button())
+                            .on_press(Message::ToggleRefine),
+                    ),
+            )
+            .width(Length::Fill)
+            .padding(Padding::from([10, 16]))
+            .style(Modern::card_container())
+            .into(),
+        )
+    }
+
+    /// Tags of the results with their counts, pressing one narrows the results to it.
+    /// Hidden inside folders, stacks and related searches, which the counts don't cover
+    fn facet_panel(&self) -> Option<Element<'_, Message>> {
+        let facets = self.facets.as_ref()?;
+        if self.folder_opened || self.opened_stack.is_some() || self.related_to.is_some() {
+            return None;
+        }
+
+        let mut list = Column::new().spacing(6);
+        if facets.is_empty() {
+            list = list.push(
+                Text::new(t!("search.facets.empty"))
+                    .size(13)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for (tag, count) in facets {
+            list = list.push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(
+                            Text::new(capitalize_first(&tag.name))
+                                .size(13)
+                                .width(Length::Fill),
+                        )
+                        .push(Text::new(count.to_string()).size(12)),
+                )
+                .style(tag_chips::tinted_style(&tag.color))
+                .padding(Padding::from([4, 10]))
+                .width(Length::Fill)
+                .on_press(Message::FacetSelected(tag.clone())),
+            );
+        }
+
+        Some(
+            Container::new(
+                Column::new()
+                    .spacing(12)
+                    .push(
+                        Row::new()
+                            .spacing(8)
+                            .align_y(Alignment::Center)
+                            .push(fa_icon_solid("filter").size(14.0))
+                            .push(Text::new(t!("search.facets.title")).size(16)),
+                    )
+                    .push(scrollable(list).height(Length::Fill)),
+            )
+            .style(Modern::card_container())
+            .width(Length::Fixed(220.0))
+            .height(Length::Fill)
+            .padding(16)
+            .into(),
+        )
+    }
+
+    /// Pick list of how the selected tags combine, shown once there are two of them
+    /// or while they are matched by any
+    fn tag_match_filter(&self) -> Option<Element<'_, Message>> {
+        if self.tag_selector.selected.len() < 2 && self.tag_match == TagMatchMode::All {
+            return None;
+        }
+        Some(
+            Container::new(
+                PickList::new(
+                    TagMatchMode::ALL,
+                    Some(self.tag_match),
+                    Message::TagMatchChanged,
+                )
+                .style(Modern::pick_list())
+                .padding([12, 16])
+                .text_size(16),
+            )
+            .width(Length::FillPortion(1))
+            .into(),
+        )
+    }
+
+    /// Checkbox letting descriptions match the query with a few typos
+    fn fuzzy_filter(&self) -> Element<'_, Message> {
+        let fuzzy = get_settings().config.fuzzy_search.unwrap_or(false);
+        Container::new(
+            Checkbox::new(t!("search.fuzzy"), fuzzy)
+                .on_toggle(Message::FuzzyToggled)
+                .style(Modern::checkbox())
+                .text_size(16),
+        )
+        .center_y(Length::Shrink)
+        .padding([12, 0])
+        .into()
+    }
+
+    /// Pick list of the languages set on images, hidden while none is
+    fn language_filter(&self) -> Option<Element<'_, Message>> {
+        if self.languages.is_empty() {
+            return None;
+        }
+        let options: Vec<LanguageFilter> = std::iter::once(LanguageFilter::Any)
+            .chain(self.languages.iter().cloned().map(LanguageFilter::Only))
+            .collect();
+        let selected = match &self.language {
+            Some(language) => LanguageFilter::Only(language.clone()),
+            None => LanguageFilter::Any,
+        };
+
+        Some(
+            Container::new(
+                PickList::new(options, Some(selected), Message::LanguageChanged)
+                    .style(Modern::pick_list())
+                    .padding([12, 16])
+                    .text_size(16),
+            )
+            .width(Length::FillPortion(1))
+            .into(),
+        )
+    }
+
+    /// Cards wrapped to the width, or laid out in the number of columns set in the
+    /// preferences with the cards scaled to fill each row. The masonry layout deals
+    /// the cards to columns instead, each as tall as its image needs.
+    fn image_grid(&self) -> Element<'_, Message> {
+        let (columns, layout) = {
+            let config = &get_settings().config;
+            (
+                config.grid_columns.unwrap_or(0),
+                config.grid_layout.unwrap_or_default(),
+            )
+        };
+        if columns == 0 && layout == GridLayout::Grid {
+            let size = CardSize::from(self.view_density);
+            let cards = self.images.iter().map(|image| self.card(image, size));
+            return self.grid_scrollable(Row::with_children(cards).spacing(GRID_SPACING).wrap().into());
+        }
+
+        responsive(move |bounds| {
+            let width = bounds.width - 2.0 * GRID_PADDING - SCROLLBAR_WIDTH;
+            let columns = if columns == 0 {
+                // As many cards of the density as fit, stretched to fill the row
+                ((width + GRID_SPACING) / (self.view_density.card_width() + GRID_SPACING))
+                    .floor()
+                    .max(1.0) as u32
+            } else {
+                columns
+            };
+            let size = CardSize::fitted(self.view_density, columns, width, GRID_SPACING);
+            let grid = match layout {
+                GridLayout::Grid => self.card_rows(size, columns),
+                GridLayout::Masonry => self.masonry(size, columns),
+            };
+            self.grid_scrollable(grid)
+        })
+        .into()
+    }
+
+    fn card_rows(&self, size: CardSize, columns: u32) -> Element<'_, Message> {
+        let rows = self.images.chunks(columns as usize).map(|row| {
+            Row::with_children(row.iter().map(|image| self.card(image, size)))
+                .spacing(GRID_SPACING)
+                .into()
+        });
+        Column::with_children(rows).spacing(GRID_SPACING).into()
+    }
+
+    /// Each card goes to the shortest column so far, sized to its image's aspect ratio
+    fn masonry(&self, size: CardSize, columns: u32) -> Element<'_, Message> {
+        let mut stacks: Vec<(f32, Vec<Element<'_, Message>>)> =
+            (0..columns.max(1)).map(|_| (0.0, Vec::new())).collect();
+        for image in &self.images {
+            let card_size = match image.aspect_ratio {
+                Some(aspect_ratio) => size.with_aspect_ratio(aspect_ratio),
+                None => size,
+            };
+            if let Some((height, cards)) = stacks.iter_mut().min_by(|a, b| a.0.total_cmp(&b.0)) {
+                *height += card_size.height() + GRID_SPACING;
+                cards.push(self.card(image, card_size));
+            }
+        }
+
+        Row::with_children(stacks.into_iter().map(|(_, cards)| {
+            Column::with_children(cards).spacing(GRID_SPACING).into()
+        }))
+        .spacing(GRID_SPACING)
+        .into()
+    }
+
+    /// Scrolled grid. Outside a folder a rectangle dragged over it picks the cards
+    /// it touches.
+    fn grid_scrollable<'a>(&'a self, grid: Element<'a, Message>) -> Element<'a, Message> {
+        let content: Element<'a, Message> = Container::new(grid)
+            .width(Length::Fill)
+            .align_x(Horizontal::Center)
+            .padding(GRID_PADDING)
+            .into();
+        let content = if self.folder_opened {
+            content
+        } else {
+            marquee(content, Message::MarqueeSelected).into()
+        };
+        Scrollable::new(content)
+            .id(self.scroll_id.clone())
+            .on_scroll(Message::ScrollChanged)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn card<'a>(&'a self, image: &'a ImageContainer, size: CardSize) -> Element<'a, Message> {
+        let card = image.view(
+            size,
+            self.focused_card() == Some(image.id),
+            self.card_lift(image.id),
+        );
+        let area = mouse_area(card)
+            .on_enter(Message::CardHovered(image.id, true))
+            .on_exit(Message::CardHovered(image.id, false));
+        // Ctrl or shift click anywhere on the card picks it for a batch action
+        let area = if !self.folder_opened && (self.modifiers.command() || self.modifiers.shift()) {
+            area.on_press(Message::OpenImage(image.image_dto.clone()))
+        } else {
+            area
+        };
+        // The id lets a dragged rectangle find the card
+        container(area).id(card_id(image.id)).into()
+    }
+
+    fn load_languages() -> Task<Message> {
+        Task::perform(
+            async { image_service::find_languages().await },
+            |result| match result {
+                Ok(languages) => Message::LanguagesLoaded(languages),
+                Err(err) => {
+                    error!("Failed to load languages: {}", err);
+                    Message::NoOps
+                }
+            },
+        )
+    }
+
+    fn export_panel(&self) -> Element<'_, Message> {
+        let labeled = |label: String, widget: Element<'static, Message>| {
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(Text::new(label).size(14).style(Modern::secondary_text()))
+                .push(widget)
+        };
+
+        let options = self.export_options;
+        let sheet = Row::new()
+            .spacing(20)
+            .align_y(Alignment::Center)
+            .push(labeled(
+                t!("search.export.columns").to_string(),
+                PickList::new(SHEET_SIZES, Some(options.columns), Message::ExportColumnsChanged)
+                    .style(Modern::pick_list())
+                    .into(),
+            ))
+            .push(labeled(
+                t!("search.export.rows").to_string(),
+                PickList::new(SHEET_SIZES, Some(options.rows), Message::ExportRowsChanged)
+                    .style(Modern::pick_list())
+                    .into(),
+            ))
+            .push(labeled(
+                t!("search.export.format").to_string(),
+                PickList::new(SheetFormat::ALL, Some(options.format), Message::ExportFormatChanged)
+                    .style(Modern::pick_list())
+                    .into(),
+            ))
+            .push(
+                Checkbox::new(t!("search.export.labels"), options.labels)
+                    .on_toggle(Message::ExportLabelsToggled)
+                    .style(Modern::checkbox()),
+            )
+            .push(Space::with_width(Length::Fill))
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid(if self.exporting { "hourglass-half" } else { "file-export" }).size(16.0))
+                        .push(Text::new(t!("search.export.button")).size(16)),
+                )
+                    .style(Modern::primary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press_maybe((!self.exporting && !self.images.is_empty()).then_some(Message::ExportContactSheet)),
+            );
+
+        let (watermark_set, presets) = {
+            let settings = get_settings();
+            let watermark_set = settings
+                .config
+                .watermark
+                .as_ref()
+                .is_some_and(|watermark| watermark.is_set());
+            let presets: Vec<PresetChoice> = std::iter::once(PresetChoice(None))
+                .chain(
+                    settings
+                        .config
+                        .export_presets
+                        .iter()
+                        .flatten()
+                        .cloned()
+                        .map(|preset| PresetChoice(Some(preset))),
+                )
+                .collect();
+            (watermark_set, presets)
+        };
+        let copies = Row::new()
+            .spacing(20)
+            .align_y(Alignment::Center)
+            .push(labeled(
+                t!("search.export.preset").to_string(),
+                PickList::new(presets, Some(PresetChoice(self.export_preset.clone())), Message::ExportPresetChanged)
+                    .style(Modern::pick_list())
+                    .into(),
+            ))
+            .push(
+                Checkbox::new(t!("search.export.watermark"), self.export_watermark && watermark_set)
+                    .on_toggle_maybe(watermark_set.then_some(Message::ExportWatermarkToggled))
+                    .style(Modern::checkbox()),
+            )
+            .push_maybe((!watermark_set).then(|| {
+                Text::new(t!("search.export.watermark_hint"))
+                    .size(14)
+                    .style(Modern::secondary_text())
+            }))
+            .push(Space::with_width(Length::Fill))
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid(if self.exporting { "hourglass-half" } else { "copy" }).size(16.0))
+                        .push(Text::new(t!("search.export.copies")).size(16)),
+                )
+                    .style(Modern::secondary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press_maybe((!self.exporting && !self.images.is_empty()).then_some(Message::ExportCopies)),
+            );
+
+        Container::new(Column::new().spacing(15).push(sheet).push(copies))
+            .width(Length::Fill)
+            .padding(15)
+            .style(Modern::card_container())
+            .into()
+    }
+
+    /// Enters or leaves selection mode, starting with nothing selected
+    fn set_selecting(&mut self, selecting: bool) {
+        self.selecting = selecting;
+        self.selection.clear();
+        for img in &mut self.images {
+            img.selecting = selecting;
+            img.selected = false;
+        }
+    }
+
+    fn toggle_selected(&mut self, id: i64) {
+        let selected = if let Some(index) = self.selection.iter().position(|&s| s == id) {
+            self.selection.remove(index);
+            false
+        } else {
+            self.selection.push(id);
+            true
+        };
+        for img in self.images.iter_mut().filter(|img| img.id == id) {
+            img.selected = selected;
+        }
+    }
+
+    /// Selects the cards from the last one picked to `id`, in the order they are shown,
+    /// so `id` ends up picked last
+    fn select_range(&mut self, id: i64) {
+        let position = |id| self.images.iter().position(|img| img.id == id);
+        let anchor = self.selection.last().copied().and_then(position);
+        let (Some(anchor), Some(end)) = (anchor, position(id)) else {
+            self.toggle_selected(id);
+            return;
+        };
+
+        let range: Vec<usize> = if anchor <= end {
+            (anchor..=end).collect()
+        } else {
+            (end..=anchor).rev().collect()
+        };
+        for index in range {
+            let img = &mut self.images[index];
+            if !img.selected {
+                img.selected = true;
+                self.selection.push(img.id);
+            }
+        }
+        if let Some(picked) = self.selection.iter().position(|&s| s == id) {
+            self.selection.remove(picked);
+            self.selection.push(id);
+        }
+    }
+
+    /// Whether every selected entry is a standalone image, which stacks and folders need
+    fn selection_is_standalone(&self) -> bool {
+        self.selection.iter().all(|id| {
+            self.images
+                .iter()
+                .find(|img| img.id == *id)
+                .is_some_and(|img| !img.image_dto.is_folder && img.image_dto.is_prepared)
+        })
+    }
+
+    /// Selected entries that are still on screen, in the order they were picked
+    fn selected_dtos(&self) -> Vec<ImageDTO> {
+        self.selection
+            .iter()
+            .filter_map(|id| self.images.iter().find(|img| img.id == *id))
+            .map(|img| img.image_dto.clone())
+            .collect()
+    }
+
+    /// Confirmation of a folder entry's deletion, with how many files it holds
+    fn delete_bar<'a>(&self, pending: &'a PendingDelete) -> Element<'a, Message> {
+        let contents = match &pending.contents {
+            None => t!("search.delete_folder.counting").to_string(),
+            Some(Ok(contents)) => t_count!(
+                "search.delete_folder.contents",
+                contents.files,
+                size = statistics_service::format_size(contents.bytes)
+            )
+            .to_string(),
+            Some(Err(_)) => t!("search.delete_folder.unknown").to_string(),
+        };
+
+        let bar = Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(fa_icon_solid("triangle-exclamation").size(16.0))
+            .push(
+                Column::new()
+                    .spacing(4)
+                    .width(Length::Fill)
+                    .push(
+                        Text::new(t!(
+                            "search.delete_folder.title",
+                            name = pending.image.description
+                        ))
+                        .size(16)
+                        .style(Modern::primary_text()),
+                    )
+                    .push(Text::new(contents).size(14).style(Modern::secondary_text())),
+            )
+            .push(
+                Button::new(Text::new(t!("search.delete_folder.cancel")).size(16))
+                    .style(Modern::secondary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press(Message::CancelDelete),
+            )
+            .push(
+                Button::new(Text::new(t!("search.delete_folder.confirm")).size(16))
+                    .style(Modern::danger_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press_maybe(pending.contents.is_some().then(|| {
+                        Message::DeleteImage(pending.image.clone(), ImageType::Folder)
+                    })),
+            );
+
+        Container::new(bar)
+            .padding(15)
+            .width(Length::Fill)
+            .style(Modern::card_container())
+            .into()
+    }
+
+    fn selection_bar(&self) -> Element<'_, Message> {
+        let has_selection = !self.selection.is_empty();
+        let can_stack = self.selection.len() > 1 && self.selection_is_standalone();
+        let has_tag = has_selection && !self.batch_tag.trim().is_empty();
+
+        let batch = Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(fa_icon_solid("tag").size(16.0))
+            .push(
+                text_input(&t!("search.selection.tag_placeholder"), &self.batch_tag)
+                    .on_input(Message::BatchTagChanged)
+                    .on_submit_maybe(has_tag.then_some(Message::AddTagToSelection))
+                    .padding(10)
+                    .width(Length::Fixed(220.0))
+                    .style(Modern::text_input()),
+            )
+            .push(
+                Button::new(Text::new(t!("search.selection.add_tag")).size(16))
+                    .style(Modern::secondary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press_maybe(has_tag.then_some(Message::AddTagToSelection)),
+            )
+            .push(
+                Button::new(Text::new(t!("search.selection.remove_tag")).size(16))
+                    .style(Modern::secondary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press_maybe(has_tag.then_some(Message::RemoveTagFromSelection)),
+            )
+            .push(Space::with_width(Length::Fill))
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("file-export").size(16.0))
+                        .push(Text::new(t!("search.selection.export")).size(16)),
+                )
+                .style(Modern::secondary_button())
+                .padding(Padding::from([10, 20]))
+                .on_press_maybe((has_selection && !self.exporting).then_some(Message::ExportCopies)),
+            )
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("trash").size(16.0))
+                        .push(Text::new(t!("search.selection.delete")).size(16)),
+                )
+                .style(Modern::danger_button())
+                .padding(Padding::from([10, 20]))
+                .on_press_maybe(has_selection.then_some(Message::TrashSelection)),
+            );
+
+        let actions = Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(fa_icon_solid("layer-group").size(16.0))
+            .push(
+                Text::new(t_count!("search.stack.selected", self.selection.len()))
+                    .size(14)
+                    .style(Modern::secondary_text()),
+            )
+            .push(Space::with_width(Length::Fill))
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("copy").size(16.0))
+                        .push(
+                            Text::new(t_count!(
+                                "search.stack.copy_files",
+                                self.selection.len()
+                            ))
+                            .size(16),
+                        ),
+                )
+                .style(Modern::secondary_button())
+                .padding(Padding::from([10, 20]))
+                .on_press_maybe(has_selection.then_some(Message::CopySelectedFiles)),
+            )
+            .push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("folder-plus").size(16.0))
+                        .push(Text::new(t!("search.stack.group_folder")).size(16)),
+                )
+                .style(Modern::secondary_button())
+                .padding(Padding::from([10, 20]))
+                .on_press_maybe(can_stack.then_some(Message::GroupIntoFolder)),
+            )
+            .push(
+                Button::new(Text::new(t!("search.stack.cancel")).size(16))
+                    .style(Modern::secondary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press(Message::ToggleSelecting),
+            )
+            .push(
+                Button::new(Text::new(t!("search.stack.create")).size(16))
+                    .style(Modern::primary_button())
+                    .padding(Padding::from([10, 20]))
+                    .on_press_maybe(can_stack.then_some(Message::CreateStack)),
+            );
+
+        Container::new(Column::new().spacing(12).push(actions).push(batch))
+            .width(Length::Fill)
+            .padding(15)
+            .style(Modern::card_container())
+            .into()
+    }
+
+    /// Loads a page of results for the current query, tags and sort order
+    fn load_page(&self, page: u64) -> Task<Message> {
+        let filter = self.current_filter();
+        let page_size = self.page_size;
+        Task::perform(
+            async move { image_service::find_all(filter, page, page_size).await },
+            |result| match result {
+                Ok(page) => {
+                    Message::PushContainer(page.content, page.page_number, page.total_pages, false)
+                }
+                Err(err) => Message::SearchFailed(err.to_string()),
+            },
+        )
+    }
+
+    /// Tag counts of the current results, while the facet panel is open
+    fn load_facets(&self) -> Task<Message> {
+        if self.facets.is_none() {
+            return Task::none();
+        }
+        let filter = self.current_filter();
+        Task::perform(
+            async move { image_service::count_tags_in_results(filter, FACET_COUNT).await },
+            |result| match result {
+                Ok(facets) => Message::FacetsLoaded(facets),
+                Err(err) => {
+                    error!("Failed to count the tags of the results: {}", err);
+                    Message::NoOps
+                }
+            },
+        )
+    }
+
+    fn load_related(&self, image_id: i64, page: u64) -> Task<Message> {
+        let page_size = self.page_size;
+        Task::perform(
+            async move { image_service::find_related(image_id, page, page_size).await },
+            |result| match result {
+                Ok(page) => {
+                    Message::PushContainer(page.content, page.page_number, page.total_pages, false)
+                }
+                Err(err) => Message::SearchFailed(err.to_string()),
+            },
+        )
+    }
+
+    /// Reloads what the grid shows without clearing it first or moving the scroll
+    fn refresh(&mut self) -> Task<Message> {
+        self.stale = false;
+        if let Some(stack_id) = self.opened_stack {
+            return Self::load_stack(stack_id);
+        }
+        if let Some((image_id, _)) = self.related_to {
+            let page_size = self.page_size;
+            let page = self.current_page;
+            return Task::perform(
+                async move { image_service::find_related(image_id, page, page_size).await },
+                |result| match result {
+                    Ok(page) => Message::Refreshed(page.content, page.page_number, page.total_pages),
+                    Err(err) => {
+                        error!("Failed to refresh related images: {}", err);
+                        Message::NoOps
+                    }
+                },
+            );
+        }
+
+        let filter = self.current_filter();
+        let page_size = self.page_size;
+        let page = self.current_page;
+        Task::perform(
+            async move { image_service::find_all(filter, page, page_size).await },
+            |result| match result {
+                Ok(page) => Message::Refreshed(page.content, page.page_number, page.total_pages),
+                Err(err) => {
+                    error!("Failed to refresh search results: {}", err);
+                    Message::NoOps
+                }
+            },
+        )
+    }
+
+    fn refresh_if_stale(&mut self) -> Task<Message> {
+        if self.stale && !self.show_preview && !self.folder_opened {
+            self.refresh()
+        } else {
+            Task::none()
+        }
+    }
+
+    fn load_stack(stack_id: i64) -> Task<Message> {
+        Task::perform(
+            async move {
+                stack_service::find_stack(stack_id)
+                    .await
+                    .unwrap_or_else(|err| {
+                        error!("Failed to load stack {}: {}", stack_id, err);
+                        None
+                    })
+            },
+            Message::StackLoaded,
+        )
+    }
+
+    /// Path of the previewed image, if it is a file
+    fn previewed_path(&self) -> Option<&str> {
+        self.images
+            .get(self.current_preview_index)
+            .filter(|img| !img.image_dto.is_folder)
+            .map(|img| img.image_dto.path.as_str())
+    }
+
+    fn load_versions(&mut self) -> Task<Message> {
+        self.versions.clear();
+        let Some(path) = self.previewed_path().map(str::to_string) else {
+            return Task::none();
+        };
+        if !self.show_preview || !self.show_info {
+            return Task::none();
+        }
+
+        Task::perform(version_service::find_for_file(path.clone()), move |result| {
+            Message::VersionsLoaded(path.clone(), result.map_err(|e| e.to_string()))
+        })
+    }
+
+    fn info_panel(&self) -> Option<Element<'_, Message>> {
+        if !self.show_info {
+            return None;
+        }
+        let img = self.images.get(self.current_preview_index)?;
+        if img.image_dto.is_folder {
+            return Some(self.folder_info_panel(img));
+        }
+        let path = img.image_dto.path.clone();
+
+        let related_button = (!img.is_from_folder && !img.image_dto.tags.is_empty()).then(|| {
+            Button::new(
+                Row::new()
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .push(fa_icon_solid("tags").size(14.0))
+                    .push(Text::new(t!("search.related.button")).size(14)),
+            )
+            .padding(Padding::from([6, 10]))
+            .style(Modern::secondary_button())
+            .on_press(Message::ShowRelated(img.image_dto.clone()))
+        });
+
+        let small_button = |icon: &'static str, label: Cow<'static, str>, message: Message| {
+            labeled(
+                Button::new(fa_icon_solid(icon).size(14.0))
+                    .padding(Padding::from([6, 10]))
+                    .style(Modern::secondary_button())
+                    .on_press(message),
+                label,
+                tooltip::Position::Top,
+            )
+        };
+
+        let mut versions = Column::new().spacing(8);
+        if self.versions.is_empty() {
+            versions = versions.push(
+                Text::new(t!("search.preview.no_versions"))
+                    .size(13)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for version in &self.versions {
+            versions = versions.push(
+                Row::new()
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .push(
+                        Text::new(&version.created_at)
+                            .size(13)
+                            .style(Modern::primary_text())
+                            .width(Length::Fill),
+                    )
+                    .push(small_button(
+                        "eye",
+                        t!("search.preview.view_version"),
+                        Message::ViewVersion(version.path.clone()),
+                    ))
+                    .push(small_button(
+                        "rotate-left",
+                        t!("search.preview.restore_version"),
+                        Message::RestoreVersion(version.clone()),
+                    )),
+            );
+        }
+
+        let panel = Column::new()
+            .spacing(12)
+            .push(
+                Text::new(t!("search.preview.info"))
+                    .size(18)
+                    .style(Modern::primary_text()),
+            )
+            .push(highlighted_text(
+                &img.image_dto.description,
+                &img.image_dto.matches,
+                14,
+            ))
+            .push(tag_chips(&img.image_dto.tags, 12, Message::FilterByTag).wrap())
+            .push(
+                Text::new(&img.image_dto.created_at)
+                    .size(13)
+                    .style(Modern::secondary_text()),
+            )
+            .push(
+                Text::new(&img.image_dto.path)
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            )
+            .push_maybe(self.rename_field(img))
+            .push_maybe(related_button)
+            .push(
+                Row::new()
+                    .spacing(6)
+                    .push(
+                        Button::new(Text::new(t!("search.preview.current")).size(14))
+                            .padding(Padding::from([6, 10]))
+                            .style(Modern::secondary_button())
+                            .on_press(Message::ViewVersion(path.clone())),
+                    )
+                    .push(
+                        Button::new(Text::new(t!("search.preview.replace_file")).size(14))
+                            .padding(Padding::from([6, 10]))
+                            .style(Modern::primary_button())
+                            .on_press(Message::ReplaceOriginal(path)),
+                    ),
+            )
+            .push(
+                Text::new(t!("search.preview.versions"))
+                    .size(16)
+                    .style(Modern::primary_text()),
+            )
+            .push(Scrollable::new(versions).height(Length::Fill));
+
+        Some(panel.into())
+    }
+
+    /// Name field of the previewed image's file while renaming it, a button opening it
+    /// otherwise. Images inside a folder entry keep the folder's naming.
+    fn rename_field<'a>(&'a self, img: &'a ImageContainer) -> Option<Element<'a, Message>> {
+        if img.is_from_folder {
+            return None;
+        }
+        let Some((_, name)) = self.renaming.as_ref().filter(|(id, _)| *id == img.id) else {
+            let stem = Path::new(&img.image_dto.path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return Some(
+                Button::new(
+                    Row::new()
+                        .spacing(6)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("i-cursor").size(14.0))
+                        .push(Text::new(t!("search.preview.rename_file")).size(14)),
+                )
+                .padding(Padding::from([6, 10]))
+                .style(Modern::secondary_button())
+                .on_press(Message::StartRename(img.id, stem))
+                .into(),
+            );
+        };
+
+        Some(
+            Row::new()
+                .spacing(6)
+                .align_y(Alignment::Center)
+                .push(
+                    TextInput::new(t!("search.preview.rename_file").as_ref(), name)
+                        .on_input(Message::RenameChanged)
+                        .on_submit(Message::ConfirmRename)
+                        .style(Modern::text_input())
+                        .padding(Padding::from([6, 10]))
+                        .size(14)
+                        .width(Length::Fill),
+                )
+                .push(
+                    Button::new(Text::new(t!("search.preview.rename_save")).size(14))
+                        .padding(Padding::from([6, 10]))
+                        .style(Modern::primary_button())
+                        .on_press(Message::ConfirmRename),
+                )
+                .push(
+                    Button::new(Text::new(t!("search.preview.rename_cancel")).size(14))
+                        .padding(Padding::from([6, 10]))
+                        .style(Modern::secondary_button())
+                        .on_press(Message::CancelRename),
+                )
+                .into(),
+        )
+    }
+
+    /// Info of a previewed folder entry: its totals in place of the versions of a file
+    fn folder_info_panel<'a>(&self, img: &'a ImageContainer) -> Element<'a, Message> {
+        let stat_row = |icon: &'static str, value: String| {
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid(icon).size(13.0))
+                .push(Text::new(value).size(13).style(Modern::primary_text()))
+        };
+
+        let mut panel = Column::new()
+            .spacing(12)
+            .push(
+                Text::new(t!("search.preview.info"))
+                    .size(18)
+                    .style(Modern::primary_text()),
+            )
+            .push(
+                Text::new(&img.image_dto.description)
+                    .size(14)
+                    .style(Modern::primary_text()),
+            )
+            .push(tag_chips(&img.image_dto.tags, 12, Message::FilterByTag).wrap())
+            .push(
+                Text::new(&img.image_dto.created_at)
+                    .size(13)
+                    .style(Modern::secondary_text()),
+            );
+
+        if let Some(stats) = img.folder_stats() {
+            panel = panel
+                .push(stat_row(
+                    "images",
+                    t_count!("message.image.container.badge.pages", stats.image_count).to_string(),
+                ))
+                .push(stat_row(
+                    "hard-drive",
+                    statistics_service::format_size(stats.total_size),
+                ))
+                .push_maybe(
+                    image_container::folder_date_range(stats)
+                        .map(|range| stat_row("calendar", range)),
+                );
+        }
+
+        panel
+            .push(
+                Text::new(&img.image_dto.path)
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            )
+            .into()
+    }
+
+    /// Checks the given images for originals modified outside the app
+    fn refresh_stale_thumbnails<'a>(images: impl Iterator<Item = &'a ImageDTO>) -> Task<Message> {
+        let paths: Vec<String> = images
+            .filter(|img| img.is_prepared && !img.is_folder)
+            .map(|img| img.path.clone())
+            .collect();
+
+        if paths.is_empty() {
+            return Task::none();
+        }
+
+        Task::perform(
+            file_service::refresh_stale_thumbnails(paths),
+            Message::ThumbnailsRefreshed,
+        )
+    }
+
+    /// Decodes the thumbnails of the cards off the UI thread, through the shared cache
+    fn decode_thumbnails(&self) -> Task<Message> {
+        let paths: Vec<String> = self
+            .images
+            .iter()
+            .filter(|img| img.image_dto.is_prepared)
+            .map(|img| img.image_dto.thumbnail_path.clone())
+            .filter(|path| image_cache::get(path).is_none())
+            .collect();
+        if paths.is_empty() {
+            return Task::none();
+        }
+        Task::perform(image_cache::load_all(paths), Message::ThumbnailsDecoded)
+    }
+
+    /// Reloads the thumbnail and preview of an image whose file changed on disk.
+    /// Handles are built from bytes because path handles are cached by path.
+    fn refresh_image(&mut self, path: &str) {
+        image_cache::invalidate(path);
+        if let Some(preview_path) = file_naming::preview_path_for(Path::new(path)) {
+            image_cache::invalidate(&preview_path.to_string_lossy());
+        }
+        for img in self.images.iter_mut().filter(|img| img.image_dto.path == path) {
+            image_cache::invalidate(&img.image_dto.thumbnail_path);
+            if let Ok(bytes) = std::fs::read(&img.image_dto.thumbnail_path) {
+                img.handle = Handle::from_bytes(bytes);
+            }
+        }
+
+        if self.show_preview
+            && self
+                .images
+                .get(self.current_preview_index)
+                .is_some_and(|img| img.image_dto.path == path)
+            && let Ok(bytes) = std::fs::read(path)
+        {
+            self.preview_handle = Handle::from_bytes(bytes);
+        }
+    }
+
+    fn change_preview(&mut self, delta: isize) -> Task<Message> {
+        if !self.show_preview || self.images.is_empty() {
+            return Task::none();
+        }
+        let len = self.images.len() as isize;
+        // calcula o índice circular
+        self.current_preview_index =
+            ((self.current_preview_index as isize + delta + len) % len) as usize;
+
+        let current_image = &self.images[self.current_preview_index];
+        let path = if current_image.image_dto.is_folder {
+            current_image.image_dto.thumbnail_path.clone()
+        } else {
+            current_image.image_dto.path.clone()
+        };
+        self.show_in_preview(&path)
+    }
+
+    /// Registered image currently shown in the preview
+    fn previewed_image_id(&self) -> Option<i64> {
+        if let Some(image) = self.similar_trail.last() {
+            return Some(image.id);
+        }
+        self.images
+            .get(self.current_preview_index)
+            .filter(|img| self.show_preview && !img.is_from_folder)
+            .map(|img| img.id)
+    }
+
+    /// Previews an image on top of the current one, going back returns to it
+    fn open_in_trail(&mut self, image: ImageDTO) -> Task<Message> {
+        let shown = self.show_in_preview(if image.is_folder {
+            &image.thumbnail_path
+        } else {
+            &image.path
+        });
+        self.similar_trail.push(image);
+        Task::batch([shown, self.load_similar()])
+    }
+
+    fn load_similar(&mut self) -> Task<Message> {
+        self.similar.clear();
+        let Some(image_id) = self.previewed_image_id() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                similarity_service::find_similar(image_id, SIMILAR_COUNT)
+                    .await
+                    .unwrap_or_else(|err| {
+                        error!("Failed to find images like {}: {}", image_id, err);
+                        Vec::new()
+                    })
+            },
+            move |images| Message::SimilarLoaded(image_id, images),
+        )
+    }
+
+    fn similar_strip(&self) -> Option<Element<'_, Message>> {
+        if self.similar.is_empty() {
+            return None;
+        }
+
+        let items = self.similar.iter().enumerate().map(|(index, image)| {
+            labeled(
+                Button::new(
+                    iced::widget::Image::new(Handle::from_path(&image.thumbnail_path))
+                        .content_fit(iced::ContentFit::Cover)
+                        .width(Length::Fill)
+                        .height(Length::Fill),
+                )
+                .padding(0)
+                .style(Modern::plain_button())
+                .width(Length::Fixed(96.0))
+                .height(Length::Fixed(72.0))
+                .on_press(Message::OpenSimilar(index)),
+                image.description.as_str(),
+                tooltip::Position::Top,
+            )
+        });
+
+        Some(
+            Column::new()
+                .spacing(8)
+                .push(
+                    Text::new(t!("search.similar.title"))
+                        .size(14)
+                        .style(Modern::secondary_text()),
+                )
+                .push(
+                    Scrollable::new(
+                        Row::with_children(items)
+                            .spacing(10)
+                            .padding(Padding::ZERO.bottom(12)),
+                    )
+                    .direction(scrollable::Direction::Horizontal(
+                        scrollable::Scrollbar::default(),
+                    ))
+                    .width(Length::Fill),
+                )
+                .into(),
+        )
+    }
+
+    fn change_scroll(&mut self) -> Task<Message> {
+
+        let scroll_offset = self.scroll_offset;
+        let scroll_id = self.scroll_id.clone();
+
+        Task::done(()).then(move |_| {
+            scrollable::scroll_to(
+                scroll_id.clone(),
+                scrollable::AbsoluteOffset {
+                    x: 0.0,
+                    y: scroll_offset,
+                },
+            )
+        })
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        let preview_was_open = self.preview_open();
+        let action = self.apply(message);
+        if !preview_was_open && self.preview_open() {
+            let now = Instant::now();
+            self.frame = now;
+            self.preview_appear.play(0.0, 1.0, animation::PREVIEW_OPEN, now);
+        }
+        action
+    }
+
+    /// Whether a preview covers the results
+    fn preview_open(&self) -> bool {
+        self.pinned_preview.is_some()
+            || self.surprises.get(self.surprise_index).is_some()
+            || !self.similar_trail.is_empty()
+            || self.show_preview
+    }
+
+    /// Some transition is still playing, frames are needed until it ends
+    pub fn is_animating(&self) -> bool {
+        self.preview_appear.is_running(self.frame)
+            || self
+                .card_lift
+                .iter()
+                .any(|(_, lift)| lift.is_running(self.frame))
+    }
+
+    fn card_lift(&self, id: i64) -> f32 {
+        self.card_lift
+            .iter()
+            .find(|(card, _)| *card == id)
+            .map_or(0.0, |(_, lift)| lift.value(self.frame))
+    }
+
+    fn apply(&mut self, message: Message) -> Action {
+        match message {
+            Message::ToggleRefine => {
+                self.refining = !self.refining;
+                if self.refining {
+                    return self.update(Message::AddRefinement);
+                }
+                if self.refinements.is_empty() {
+                    return Action::None;
+                }
+                self.refinements.clear();
+                set_refinements(Vec::new());
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+            Message::SubmitQuery => {
+                self.close_history();
+                let query = self.query.clone();
+                update_config(|config| config.remember_search(&query));
+                self.update(Message::SearchButtonPressed)
+            }
+            Message::HistorySelected(query) => {
+                self.query = query;
+                set_search_query(self.query.clone());
+                self.update(if self.refining {
+                    Message::AddRefinement
+                } else {
+                    Message::SubmitQuery
+                })
+            }
+            Message::AddRefinement => {
+                let step = self.query.trim().to_string();
+                if step.is_empty() {
+                    return Action::None;
+                }
+                self.close_history();
+                update_config(|config| config.remember_search(&step));
+                self.refinements.push(step);
+                self.query.clear();
+                set_search_query(String::new());
+                set_refinements(self.refinements.clone());
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+            Message::RefineBack => {
+                let Some(step) = self.refinements.pop() else {
+                    return Action::None;
+                };
+                self.query = step;
+                set_search_query(self.query.clone());
+                set_refinements(self.refinements.clone());
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+            Message::QueryChanged(query) => {
+                self.history_open = true;
+                self.history_cursor = None;
+                self.history_typed = query.clone();
+                self.query = query.clone();
+                set_search_query(query.clone());
+                self.current_search_id += 1;
+                let search_id = self.current_search_id;
+
+                let task = Task::perform(
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(300)).await;
+                        (query, search_id)
+                    },
+                    |(query, search_id)| Message::DelayedQuery(query, search_id),
+                );
+                Action::Run(task)
+            }
+
+            Message::DelayedQuery(query, search_id) => {
+                if self.query == query && self.current_search_id == search_id {
+                    let task = Task::perform(
+                        async {
+                            tokio::time::sleep(Duration::from_millis(300)).await;
+                        },
+                        |_| Message::SearchButtonPressed,
+                    );
+                    Action::Run(task)
+                } else {
+                    Action::None
+                }
+            }
+
+            Message::ScrollChanged(viewport) => {
+                self.scroll_offset = viewport.absolute_offset().y;
+                set_scroll_offset(self.scroll_offset);
+                Action::None
+            }
+
+            Message::Update(image_dto) => {
+                info!("Update image_dto: {}", image_dto.id);
+                info!("Update image_dto: {:?}", image_dto.tags);
+                Action::NavigateToUpdate(image_dto)
+            }
+
+            Message::OpenLocalImage(id) => {
+                let Some(img) = self.images.iter().find(|img| img.id == id) else {
+                    return Action::None;
+                };
+
+                let path = Path::new(&img.image_dto.path);
+                let path_buf = if img.image_dto.is_folder {
+                    path.to_path_buf()
+                } else {
+                    path.parent().unwrap_or(path).to_path_buf()
+                };
+
+                let task = Task::perform(
+                    async move {
+                        let _ = file_service::open_in_file_explorer(&path_buf);
+                    },
+                    |_| Message::NoOps,
+                );
+                Action::Run(task)
+            }
+
+            Message::SetWallpaper(path) => Action::Run(Task::perform(
+                wallpaper_service::set_wallpaper(path),
+                |result| {
+                    match result {
+                        Ok(()) => push_success(t!("message.wallpaper.success")),
+                        Err(err) => {
+                            error!("Failed to set wallpaper: {}", err);
+                            push_error(t!("message.wallpaper.error"));
+                        }
+                    }
+                    Message::NoOps
+                },
+            )),
+
+            Message::PinnedLoaded(pinned) => {
+                self.pinned = pinned;
+                Action::None
+            }
+            Message::TogglePin(id, pinned) => {
+                for img in self.images.iter_mut().filter(|img| img.id == id) {
+                    img.image_dto.is_pinned = pinned;
+                }
+                Action::Run(Task::perform(
+                    async move {
+                        image_service::set_pinned(id, pinned)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::PinChanged,
+                ))
+            }
+            Message::PinChanged(result) => {
+                if let Err(err) = result {
+                    error!("Failed to update pin: {}", err);
+                    push_error(t!("message.pin.error"));
+                }
+                Action::Run(Self::load_pinned())
+            }
+            Message::OpenPinned(index) => Action::Run(self.show_pinned(index)),
+            Message::PreviousPinned => match self.pinned_preview {
+                Some(index) => {
+                    let len = self.pinned.len();
+                    Action::Run(self.show_pinned((index + len - 1) % len))
+                }
+                None => Action::None,
+            },
+            Message::NextPinned => match self.pinned_preview {
+                Some(index) => Action::Run(self.show_pinned((index + 1) % self.pinned.len())),
+                None => Action::None,
+            },
+            Message::FilterByTag(tag) => Action::FilterByTag(tag),
+            Message::ShowRelated(image) => {
+                let image_id = image.id;
+                self.related_to = Some((image.id, image.description));
+                self.show_preview = false;
+                self.versions.clear();
+                self.similar.clear();
+                self.similar_trail.clear();
+                self.preview_handle = Handle::from_path("".to_string());
+                self.preview_loading = None;
+                self.current_preview_index = 0;
+                self.folder_opened = false;
+                self.opened_stack = None;
+                self.images.clear();
+                self.scroll_offset = 0.0;
+                set_scroll_offset(0.0);
+                Action::Run(self.load_related(image_id, 0))
+            }
+            Message::DataChanged(DataChange::Tags) => Action::Run(Task::perform(
+                async { tag_service::find_all().await.unwrap_or_default() },
+                Message::TagsLoaded,
+            )),
+            Message::DataChanged(DataChange::Settings) => {
+                let page_size = get_settings().config.items_per_page;
+                if page_size == self.page_size {
+                    return Action::None;
+                }
+                // Pages no longer start where they did, go back to the first one
+                self.page_size = page_size;
+                self.current_page = 0;
+                self.stale = true;
+                Action::Run(self.refresh_if_stale())
+            }
+            Message::DataChanged(DataChange::Images) => {
+                let pinned = if self.pinned_preview.is_none() {
+                    Self::load_pinned()
+                } else {
+                    Task::none()
+                };
+                // Cards under an open preview or folder are indexed, reload them later
+                if self.show_preview
+                    || self.folder_opened
+                    || self.pinned_preview.is_some()
+                    || !self.surprises.is_empty()
+                    || !self.similar_trail.is_empty()
+                {
+                    self.stale = true;
+                    return Action::Run(Task::batch([pinned, Self::load_languages()]));
+                }
+                Action::Run(Task::batch([
+                    pinned,
+                    Self::load_languages(),
+                    self.refresh(),
+                    self.load_facets(),
+                ]))
+            }
+            Message::SearchFailed(err) => {
+                error!("Failed to load search results: {}", err);
+                push_error(t!("search.error.toast"));
+                self.loading = false;
+                self.load_failed = true;
+                self.images.clear();
+                Action::None
+            }
+            Message::Refreshed(images, current_page, total_pages) => {
+                // Changes emptied the last page, step back to the new last one
+                if images.is_empty() && current_page > 0 && total_pages > 0 {
+                    return Action::Run(Task::done(Message::GoToPage(total_pages - 1)));
+                }
+                self.images.clear();
+                Action::Run(Task::done(Message::PushContainer(
+                    images,
+                    current_page,
+                    total_pages,
+                    false,
+                )))
+            }
+            Message::SimilarLoaded(image_id, images) => {
+                // Ignore results for an image that is no longer previewed
+                if self.previewed_image_id() == Some(image_id) {
+                    self.similar = images;
+                }
+                Action::None
+            }
+            Message::OpenSimilar(index) => {
+                let Some(image) = self.similar.get(index).cloned() else {
+                    return Action::None;
+                };
+                Action::Run(self.open_in_trail(image))
+            }
+            Message::OpenLinkedImage(image_id) => Action::Run(Task::perform(
+                async move {
+                    image_service::find_by_id(image_id)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                Message::LinkedImageLoaded,
+            )),
+            Message::LinkedImageLoaded(result) => match result {
+                Ok(Some(image)) => Action::Run(self.open_in_trail(image)),
+                Ok(None) => {
+                    push_error(t!("message.deep_link.not_found"));
+                    Action::None
+                }
+                Err(err) => {
+                    error!("Failed to open linked image: {}", err);
+                    push_error(t!("message.deep_link.not_found"));
+                    Action::None
+                }
+            },
+            Message::SimilarBack => {
+                self.similar_trail.pop();
+                let shown = match self.similar_trail.last() {
+                    Some(image) => {
+                        let path = if image.is_folder {
+                            image.thumbnail_path.clone()
+                        } else {
+                            image.path.clone()
+                        };
+                        self.show_in_preview(&path)
+                    }
+                    None => self.change_preview(0),
+                };
+                Action::Run(Task::batch([shown, self.load_similar()]))
+            }
+            Message::SurpriseMe => {
+                let filter = if self.surprise_filtered {
+                    self.current_filter()
+                } else {
+                    Filter::new()
+                };
+                Action::Run(Task::perform(
+                    async move {
+                        image_service::random_matching(filter)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::SurpriseFound,
+                ))
+            }
+            Message::SurpriseFound(result) => {
+                match result {
+                    Ok(Some(image)) => {
+                        // Folders show one of their images
+                        let image = if image.is_folder {
+                            let files = file_service::expand_folder_dto(&image);
+                            match files.get(random_index(files.len())) {
+                                Some(file) => file.clone(),
+                                None => return Action::None,
+                            }
+                        } else {
+                            image
+                        };
+                        let shown = self.show_in_preview(&image.path);
+                        self.surprises.push(image);
+                        self.surprise_index = self.surprises.len() - 1;
+                        return Action::Run(shown);
+                    }
+                    Ok(None) => push_error(t!("message.surprise.empty")),
+                    Err(err) => {
+                        error!("Failed to pick a random image: {}", err);
+                        push_error(t!("message.surprise.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::PreviousSurprise => {
+                self.surprise_index = self.surprise_index.saturating_sub(1);
+                match self.surprises.get(self.surprise_index) {
+                    Some(image) => {
+                        let path = image.path.clone();
+                        Action::Run(self.show_in_preview(&path))
+                    }
+                    None => Action::None,
+                }
+            }
+            Message::PreviewLoaded(path, result) => {
+                // Ignore a load for an image that is no longer previewed
+                if path != self.preview_path {
+                    return Action::None;
+                }
+                self.preview_loading = None;
+                match result {
+                    Ok((rendition, handle)) => {
+                        self.preview_handle = handle;
+                        if !self.full_resolution {
+                            self.preview_rendition = rendition;
+                        }
+                    }
+                    Err(err) => {
+                        error!("Failed to decode {}: {}", path, err);
+                        push_error(t!("search.preview.load_error"));
+                    }
+                }
+                Action::None
+            }
+            Message::CancelPreviewLoad => {
+                // The thumbnail stays in place of the image
+                self.preview_loading = None;
+                Action::None
+            }
+            Message::ToggleFullResolution => {
+                if self.preview_rendition.is_none() {
+                    return Action::None;
+                }
+                self.full_resolution = !self.full_resolution;
+                Action::Run(self.load_preview())
+            }
+            Message::OpenFileLocation(path) => {
+                if let Some(parent) = Path::new(&path).parent()
+                    && let Err(err) = file_service::open_in_file_explorer(parent)
+                {
+                    error!("Failed to open {}: {}", parent.display(), err);
+                }
+                Action::None
+            }
+            Message::ToggleSurpriseFilter => {
+                self.surprise_filtered = !self.surprise_filtered;
+                push_success(if self.surprise_filtered {
+                    t!("message.surprise.filtered")
+                } else {
+                    t!("message.surprise.everything")
+                });
+                Action::None
+            }
+            Message::ToggleSelecting => {
+                let selecting = !self.selecting;
+                self.set_selecting(selecting);
+                Action::None
+            }
+            Message::ToggleSelection(id) => {
+                if self.modifiers.shift() {
+                    self.select_range(id);
+                } else {
+                    self.toggle_selected(id);
+                }
+                Action::None
+            }
+            Message::MarqueeSelected(ids) => {
+                let additive = self.modifiers.command() || self.modifiers.shift();
+                if ids.is_empty() && (!self.selecting || additive) {
+                    return Action::None;
+                }
+                if !self.selecting || !additive {
+                    self.set_selecting(true);
+                }
+                for img in &mut self.images {
+                    if !img.selected && ids.contains(&card_id(img.id).into()) {
+                        img.selected = true;
+                        self.selection.push(img.id);
+                    }
+                }
+                Action::None
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Action::None
+            }
+            Message::BatchTagChanged(tag) => {
+                self.batch_tag = tag;
+                Action::None
+            }
+            Message::AddTagToSelection => {
+                let name = self.batch_tag.trim().to_lowercase();
+                if name.is_empty() || self.selection.is_empty() {
+                    return Action::None;
+                }
+                let image_ids = self.selection.clone();
+                let tag = TagDTO {
+                    id: 0,
+                    name,
+                    color: TagColor::default(),
+                    parent_id: None,
+                    category: None,
+                };
+                Action::Run(Task::perform(
+                    async move {
+                        image_service::add_tag_to_images(&image_ids, tag)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::SelectionTagged,
+                ))
+            }
+            Message::RemoveTagFromSelection => {
+                let name = self.batch_tag.trim().to_lowercase();
+                if name.is_empty() || self.selection.is_empty() {
+                    return Action::None;
+                }
+                let image_ids = self.selection.clone();
+                Action::Run(Task::perform(
+                    async move {
+                        image_service::remove_tag_from_images(&image_ids, &name)
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::SelectionTagged,
+                ))
+            }
+            Message::SelectionTagged(result) => {
+                match result {
+                    Ok(count) => {
+                        self.batch_tag.clear();
+                        push_success(t_count!("message.selection.tagged", count));
+                    }
+                    Err(err) => {
+                        error!("Failed to tag the selection: {}", err);
+                        push_error(t!("message.selection.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::TrashSelection => {
+                if self.selection.is_empty() {
+                    return Action::None;
+                }
+                let image_ids = std::mem::take(&mut self.selection);
+                self.set_selecting(false);
+                Action::Run(Task::perform(
+                    async move {
+                        for id in &image_ids {
+                            trash_service::trash(*id).await?;
+                        }
+                        Ok(image_ids.len())
+                    },
+                    Message::SelectionTrashed,
+                ))
+            }
+            Message::SelectionTrashed(result) => {
+                match result {
+                    Ok(count) => push_success(t_count!("message.selection.trashed", count)),
+                    Err(err) => {
+                        error!("Failed to move the selection to the trash: {}", err);
+                        push_error(t!("message.selection.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::CopySelectedFiles => {
+                // Folder entries give every image they hold
+                let paths: Vec<String> = self
+                    .selection
+                    .iter()
+                    .filter_map(|id| self.images.iter().find(|img| img.id == *id))
+                    .flat_map(|img| {
+                        if img.image_dto.is_folder {
+                            file_service::expand_folder_dto(&img.image_dto)
+                                .into_iter()
+                                .map(|image| image.path)
+                                .collect()
+                        } else {
+                            vec![img.image_dto.path.clone()]
+                        }
+                    })
+                    .collect();
+                match copy_files_to_clipboard(&paths) {
+                    Ok(()) => push_success(t_count!("message.copy.files_success", paths.len())),
+                    Err(e) => {
+                        error!("Error copying files to clipboard: {}", e);
+                        push_error(t!("message.copy.files_error"));
+                    }
+                }
+                Action::None
+            }
+            Message::CreateStack => {
+                if self.selection.len() < 2 {
+                    return Action::None;
+                }
+                let image_ids = std::mem::take(&mut self.selection);
+                self.selecting = false;
+                for img in &mut self.images {
+                    img.selecting = false;
+                    img.selected = false;
+                }
+                Action::Run(Task::perform(
+                    async move {
+                        stack_service::create(image_ids)
+                            .await
+                            .map(|_| ())
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::StackChanged,
+                ))
+            }
+            Message::GroupIntoFolder => {
+                if self.selection.len() < 2 {
+                    return Action::None;
+                }
+                let image_ids = std::mem::take(&mut self.selection);
+                self.selecting = false;
+                for img in &mut self.images {
+                    img.selecting = false;
+                    img.selected = false;
+                }
+                Action::Run(Task::perform(
+                    image_service::group_into_folder(image_ids),
+                    Message::GroupedIntoFolder,
+                ))
+            }
+            Message::GroupedIntoFolder(result) => {
+                match result {
+                    Ok(_) => push_success(t!("message.group.success")),
+                    Err(err) => {
+                        error!("Failed to group images into a folder: {}", err);
+                        push_error(t!("message.group.error", reason = err));
+                    }
+                }
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+            Message::StackLoaded(stack) => {
+                let Some(stack) = stack else {
+                    // The stack was dissolved, back to the results
+                    self.opened_stack = None;
+                    return Action::Run(Task::done(Message::SearchButtonPressed));
+                };
+                self.images.clear();
+                for image in stack.images {
+                    let is_cover = image.id == stack.cover_image_id;
+                    let mut container = ImageContainer::new(image, false);
+                    container.stack_cover = Some(is_cover);
+                    self.images.push(container);
+                }
+                self.current_page = 0;
+                self.total_pages = 0;
+                Action::None
+            }
+            Message::SetStackCover(image_id) => {
+                let Some(stack_id) = self.opened_stack else {
+                    return Action::None;
+                };
+                for img in &mut self.images {
+                    img.stack_cover = Some(img.id == image_id);
+                }
+                Action::Run(Task::perform(
+                    async move { stack_service::set_cover(stack_id, image_id).await },
+                    |result| {
+                        if let Err(err) = result {
+                            error!("Failed to change the stack cover: {}", err);
+                            push_error(t!("message.stack.error"));
+                        }
+                        Message::NoOps
+                    },
+                ))
+            }
+            Message::RemoveFromStack(image_id) => Action::Run(Task::perform(
+                async move {
+                    stack_service::remove_image(image_id)
+                        .await
+                        .map_err(|e| e.to_string())
+                },
+                Message::StackChanged,
+            )),
+            Message::StackChanged(result) => {
+                if let Err(err) = result {
+                    error!("Failed to update stack: {}", err);
+                    push_error(t!("message.stack.error"));
+                }
+                match self.opened_stack {
+                    Some(stack_id) => Action::Run(Self::load_stack(stack_id)),
+                    None => Action::Run(Task::done(Message::SearchButtonPressed)),
+                }
+            }
+            Message::ToggleCopyHistory => {
+                self.show_copy_history = !self.show_copy_history;
+                Action::None
+            }
+            Message::PinSearch => {
+                let Some(name) = self.filters_title() else {
+                    push_error(t!("message.navbar.pin_empty"));
+                    return Action::None;
+                };
+                let mut tags: Vec<String> = self
+                    .tag_selector
+                    .selected
+                    .iter()
+                    .map(|tag| tag.name.to_lowercase())
+                    .collect();
+                tags.sort_unstable();
+                let mut excluded_tags: Vec<String> = self
+                    .tag_selector
+                    .excluded
+                    .iter()
+                    .map(|tag| tag.name.to_lowercase())
+                    .collect();
+                excluded_tags.sort_unstable();
+                Action::PinSearch(SavedSearch {
+                    name,
+                    query: self.query.trim().to_string(),
+                    refinements: self.refinements.clone(),
+                    tags,
+                    excluded_tags,
+                    tag_match: self.tag_match,
+                })
+            }
+            Message::ClearCopyHistory => {
+                clear_copy_history();
+                Action::None
+            }
+            Message::ToggleFacets => {
+                if self.facets.take().is_some() {
+                    return Action::None;
+                }
+                self.facets = Some(Vec::new());
+                Action::Run(self.load_facets())
+            }
+            Message::FacetsLoaded(facets) => {
+                if self.facets.is_some() {
+                    self.facets = Some(facets);
+                }
+                Action::None
+            }
+            Message::FacetSelected(tag) => {
+                self.tag_selector.excluded.remove(&tag);
+                self.tag_selector.selected.insert(tag);
+                set_selected_tags(self.tag_selector.selected.clone());
+                set_excluded_tags(self.tag_selector.excluded.clone());
+                self.current_page = 0;
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+            Message::ToggleExportPanel => {
+                self.show_export = !self.show_export;
+                Action::None
+            }
+            Message::ExportColumnsChanged(columns) => {
+                self.export_options.columns = columns;
+                Action::None
+            }
+            Message::ExportRowsChanged(rows) => {
+                self.export_options.rows = rows;
+                Action::None
+            }
+            Message::ExportLabelsToggled(labels) => {
+                self.export_options.labels = labels;
+                Action::None
+            }
+            Message::ExportFormatChanged(format) => {
+                self.export_options.format = format;
+                Action::None
+            }
+            Message::ExportContactSheet => {
+                let options = self.export_options;
+                // An opened folder or stack exports its images, otherwise every page of the results
+                let folder_images = (self.folder_opened || self.opened_stack.is_some())
+                    .then(|| self.images.iter().map(|img| img.image_dto.clone()).collect::<Vec<_>>());
+                let filter = self.current_filter();
+                self.exporting = true;
+
+                Action::Run(Task::perform(
+                    async move {
+                        let extension = options.format.extension();
+                        let destination = AsyncFileDialog::new()
+                            .add_filter(extension.to_uppercase(), &[extension])
+                            .set_file_name(format!("contact_sheet.{}", extension))
+                            .save_file()
+                            .await?;
+
+                        let images = match folder_images {
+                            Some(images) => images,
+                            None => match image_service::find_all_matching(filter).await {
+                                Ok(images) => images,
+                                Err(err) => return Some(Err(err.to_string())),
+                            },
+                        };
+
+                        Some(
+                            export_service::export_contact_sheet(
+                                images,
+                                options,
+                                destination.path().to_path_buf(),
+                            )
+                            .await,
+                        )
+                    },
+                    Message::ContactSheetExported,
+                ))
+            }
+            Message::ExportWatermarkToggled(watermark) => {
+                self.export_watermark = watermark;
+                Action::None
+            }
+            Message::ExportPresetChanged(PresetChoice(preset)) => {
+                self.export_preset = preset;
+                Action::None
+            }
+            Message::ExportCopies => {
+                let folder_images = if self.selecting && !self.selection.is_empty() {
+                    Some(self.selected_dtos())
+                } else {
+                    (self.folder_opened || self.opened_stack.is_some())
+                        .then(|| self.images.iter().map(|img| img.image_dto.clone()).collect::<Vec<_>>())
+                };
+                let filter = self.current_filter();
+                let preset = self.export_preset.clone();
+                let watermark = self
+                    .export_watermark
+                    .then(|| get_settings().config.watermark.clone())
+                    .flatten();
+                self.exporting = true;
+
+                Action::Run(Task::perform(
+                    async move {
+                        let destination = AsyncFileDialog::new()
+                            .set_title(t!("search.export.copies"))
+                            .pick_folder()
+                            .await?;
+
+                        let images = match folder_images {
+                            Some(images) => images,
+                            None => match image_service::find_all_matching(filter).await {
+                                Ok(images) => images,
+                                Err(err) => return Some(Err(err.to_string())),
+                            },
+                        };
+
+                        Some(
+                            export_service::export_copies(
+                                images,
+                                destination.path().to_path_buf(),
+                                preset,
+                                watermark,
+                            )
+                            .await,
+                        )
+                    },
+                    Message::CopiesExported,
+                ))
+            }
+            Message::CopiesExported(result) => {
+                self.exporting = false;
+                match result {
+                    Some(Ok(files)) => {
+                        push_success(t_count!("message.export.copies_success", files));
+                    }
+                    Some(Err(err)) => {
+                        error!("Failed to export copies: {}", err);
+                        push_error(t!("message.export.copies_error"));
+                    }
+                    None => {}
+                }
+                Action::None
+            }
+            Message::ContactSheetExported(result) => {
+                self.exporting = false;
+                match result {
+                    Some(Ok(sheets)) => {
+                        push_success(t_count!("message.export.success", sheets));
+                    }
+                    Some(Err(err)) => {
+                        error!("Failed to export contact sheet: {}", err);
+                        push_error(t!("message.export.error"));
+                    }
+                    None => {}
+                }
                 Action::None
             }
 
-            Message::Update(image_dto) => {
-                info!("Update image_dto: {}", image_dto.id);
-                info!("Update image_dto: {:?}", image_dto.tags);
-                Action::NavigateToUpdate(image_dto)
-            }
-
-            Message::OpenLocalImage(id) => {
-                let img = self.images.iter().find(|img| img.id == id).unwrap();
-
-                let path_buf = if !img.image_dto.is_folder {
-                    Path::new(&img.image_dto.path)
-                        .parent()
-                        .expect("Image path should have a parent")
-                        .to_path_buf()
-                } else {
-                    Path::new(&img.image_dto.path).to_path_buf()
-                };
-
-                let task = Task::perform(
-                    async move {
-                        let _ = file_service::open_in_file_explorer(&path_buf);
-                    },
-                    |_| Message::NoOps,
-                );
-                Action::Run(task)
+            Message::OpenSource(url) => {
+                if let Err(e) = file_service::open_url(&url) {
+                    error!("Failed to open source URL {}: {}", url, e);
+                    push_error(t!("message.image.open_source_error"));
+                }
+                Action::None
             }
 
             Message::CopyImage(src) => {
@@ -256,30 +3194,128 @@ impl Search {
                 Action::Run(task)
             }
 
+            Message::ToggleCopyMenu(id) => {
+                for img in &mut self.images {
+                    img.show_copy_menu = img.id == id && !img.show_copy_menu;
+                }
+                Action::None
+            }
+
+            Message::CopyReference(path, description, format) => {
+                for img in &mut self.images {
+                    img.show_copy_menu = false;
+                }
+                match copy_file_reference(&path, format, &description) {
+                    Ok(_) => push_success(t!("message.copy.link_success")),
+                    Err(e) => {
+                        error!("Error copying file reference to clipboard: {}", e);
+                        push_error(t!("message.copy.link_error"));
+                    }
+                }
+                Action::None
+            }
+
+            Message::EditExternally(path) => Action::Run(Task::perform(
+                version_service::edit_with_versioning(path.clone()),
+                move |result| Message::ExternalEditFinished(path.clone(), result),
+            )),
+
+            Message::ExternalEditFinished(path, result) => {
+                match result {
+                    Ok(true) => {
+                        self.refresh_image(&path);
+                        push_success(t!("message.edit_external.success"));
+                        return Action::Run(self.load_versions());
+                    }
+                    Ok(false) => info!("External editor closed without changes: {}", path),
+                    Err(e) => {
+                        error!("Error editing image externally: {}", e);
+                        push_error(t!("message.edit_external.error"));
+                    }
+                }
+                Action::None
+            }
+
             Message::DeleteImage(dto, image_type) => {
+                // A folder can hold hundreds of files, they are counted and shown first
+                let confirmed = self
+                    .pending_delete
+                    .as_ref()
+                    .is_some_and(|pending| pending.image.id == dto.id);
+                if confirmed {
+                    self.pending_delete = None;
+                } else if image_type == ImageType::Folder {
+                    let id = dto.id;
+                    self.pending_delete = Some(Box::new(PendingDelete {
+                        image: dto,
+                        contents: None,
+                    }));
+                    return Action::Run(Task::perform(trash_service::contents(id), move |result| {
+                        Message::DeleteContentsCounted(id, result)
+                    }));
+                }
+
                 self.images.retain(|img| img.id != dto.id);
+                // The stack gets a new cover or goes away, either way the cards change
+                let in_stack = dto.stack_id.is_some();
+                let behavior = get_settings().config.delete_behavior.unwrap_or_default();
                 let task = Task::perform(
                     async move {
-                        // Deletar do banco de dados
-                        if let Err(e) = image_service::delete_image(dto.id).await {
-                            error!("Failed to delete image from database: {}", e);
+                        // An image of an opened folder has no entry of its own, only its file goes
+                        if image_type == ImageType::FromFolder {
+                            return file_service::delete_image(&dto.path, image_type, behavior)
+                                .await
+                                .map(|_| false)
+                                .map_err(|e| e.to_string());
                         }
-                        
-                        if let Err(e) = file_service::delete_image(&dto.path, image_type).await {
-                            error!("Failed to delete image files: {}", e);
-                        }
-                        
+                        trash_service::trash(dto.id).await.map(|_| true)
                     },
-                    |_| {
-                        push_success(t!("message.delete.success"));
-                        Message::NoOps
+                    move |result| {
+                        match result {
+                            Ok(true) => push_success(t!("message.delete.trashed")),
+                            Ok(false) => push_success(t!("message.delete.success")),
+                            Err(err) => {
+                                error!("Failed to delete image: {}", err);
+                                push_error(t!("message.delete.error"));
+                            }
+                        }
+                        if in_stack {
+                            Message::StackChanged(Ok(()))
+                        } else {
+                            Message::NoOps
+                        }
                     },
                 );
                 Action::Run(task)
             }
 
+            Message::DeleteContentsCounted(id, result) => {
+                if let Err(err) = &result {
+                    error!("Failed to count the files of folder {}: {}", id, err);
+                }
+                if let Some(pending) = self
+                    .pending_delete
+                    .as_mut()
+                    .filter(|pending| pending.image.id == id)
+                {
+                    pending.contents = Some(result);
+                }
+                Action::None
+            }
+
+            Message::CancelDelete => {
+                self.pending_delete = None;
+                Action::None
+            }
+
             Message::PushContainer(images, current_page, total_pages, is_from_folder) => {
+                self.loading = false;
+                self.load_failed = false;
+                if !is_from_folder {
+                    self.stale = false;
+                }
                 self.images.reserve(images.len());
+                let refresh_task = Self::refresh_stale_thumbnails(images.iter());
 
                 info!("Pushing {} images", images.len());
                 for img in images {
@@ -288,28 +3324,78 @@ impl Search {
                         "Tags: {:?}",
                         img.tags.iter().map(|t| &t.name).collect::<Vec<_>>()
                     );
-                    self.images
-                        .push(ImageContainer::new(img.clone(), is_from_folder));
+                    let mut container = ImageContainer::new(img.clone(), is_from_folder);
+                    container.selecting = self.selecting;
+                    container.selected = self.selection.contains(&container.id);
+                    self.images.push(container);
                 }
 
                 set_current_page(current_page);
                 self.current_page = current_page;
                 self.total_pages = total_pages;
 
-                Action::Run(self.change_scroll())
+                Action::Run(Task::batch([
+                    self.change_scroll(),
+                    refresh_task,
+                    self.decode_thumbnails(),
+                ]))
+            }
+
+            Message::ThumbnailsDecoded(decoded) => {
+                for (path, handle) in decoded {
+                    for img in self
+                        .images
+                        .iter_mut()
+                        .filter(|img| img.image_dto.thumbnail_path == path)
+                    {
+                        img.handle = handle.clone();
+                    }
+                }
+                Action::None
+            }
+
+            Message::ThumbnailsRefreshed(paths) => {
+                if !paths.is_empty() {
+                    info!("Refreshed {} outdated thumbnails", paths.len());
+                }
+                for path in paths {
+                    self.refresh_image(&path);
+                }
+                Action::None
             }
 
+            Message::OpenImage(image_dto)
+                if !self.folder_opened
+                    && (self.modifiers.command() || self.modifiers.shift()) =>
+            {
+                if !self.selecting {
+                    self.set_selecting(true);
+                }
+                if self.modifiers.shift() {
+                    self.select_range(image_dto.id);
+                } else {
+                    self.toggle_selected(image_dto.id);
+                }
+                Action::None
+            }
             Message::OpenImage(image_dto) => {
-                if image_dto.is_folder {
+                self.quick_look = None;
+                if self.opened_stack.is_none()
+                    && image_dto.stack_size > 1
+                    && let Some(stack_id) = image_dto.stack_id
+                {
+                    info!("Opening stack {}", stack_id);
+                    self.images.clear();
+                    self.opened_stack = Some(stack_id);
+                    self.show_preview = false;
+                    Action::Run(Self::load_stack(stack_id))
+                } else if image_dto.is_folder {
                     info!("Opening folder {}", image_dto.path);
                     self.images.clear();
                     self.folder_opened = true;
                     self.show_preview = false;
                     let task = Task::perform(
-                        async move {
-                            let sub_images = file_service::expand_folder_dto(&image_dto);
-                            sub_images
-                        },
+                        async move { file_service::expand_folder_dto(&image_dto) },
                         |sub_images| Message::PushContainer(sub_images, 0, 0, true),
                     );
                     Action::Run(task)
@@ -322,55 +3408,235 @@ impl Search {
                     {
                         self.current_preview_index = index;
                         self.show_preview = true;
-
-                        if image_dto.is_folder {
-                            self.preview_handle =
-                                Handle::from_path(image_dto.thumbnail_path.clone());
-                        } else {
-                            self.preview_handle = Handle::from_path(image_dto.path.clone());
-                        }
                     }
-                    Action::None
+                    let shown = self.show_in_preview(if image_dto.is_folder {
+                        &image_dto.thumbnail_path
+                    } else {
+                        &image_dto.path
+                    });
+                    Action::Run(Task::batch([
+                        shown,
+                        Self::refresh_stale_thumbnails(std::iter::once(&image_dto)),
+                        self.load_versions(),
+                        self.load_similar(),
+                    ]))
                 }
             }
 
             Message::PreviousImage => {
-                self.change_preview(-1);
-                Action::None
+                let shown = self.change_preview(-1);
+                Action::Run(Task::batch([shown, self.load_versions(), self.load_similar()]))
             }
 
             Message::NextImage => {
-                self.change_preview(1);
+                let shown = self.change_preview(1);
+                Action::Run(Task::batch([shown, self.load_versions(), self.load_similar()]))
+            }
+
+            Message::ToggleInfoPanel => {
+                self.show_info = !self.show_info;
+                Action::Run(self.load_versions())
+            }
+
+            Message::VersionsLoaded(path, result) => {
+                // Ignore results for an image that is no longer previewed
+                if self.previewed_path() != Some(path.as_str()) {
+                    return Action::None;
+                }
+                match result {
+                    Ok(versions) => self.versions = versions,
+                    Err(e) => {
+                        error!("Failed to load versions of {}: {}", path, e);
+                        self.versions.clear();
+                    }
+                }
+                Action::None
+            }
+
+            Message::ViewVersion(path) => {
+                if let Ok(bytes) = std::fs::read(&path) {
+                    self.preview_handle = Handle::from_bytes(bytes);
+                }
+                Action::None
+            }
+
+            Message::RestoreVersion(version) => {
+                let path = version.original_path.clone();
+                Action::Run(Task::perform(version_service::restore(version), move |result| {
+                    Message::VersionChanged(path.clone(), result)
+                }))
+            }
+
+            Message::ReplaceOriginal(path) => Action::Run(Task::perform(
+                async {
+                    AsyncFileDialog::new()
+                        .set_title(t!("search.preview.replace_file"))
+                        .add_filter("Images", &["png", "jpg", "jpeg", "gif", "bmp", "tiff", "webp"])
+                        .pick_file()
+                        .await
+                },
+                move |file| match file {
+                    Some(file) => Message::ReplacementChosen(path.clone(), file.path().to_path_buf()),
+                    None => Message::NoOps,
+                },
+            )),
+
+            Message::StartRename(id, name) => {
+                self.renaming = Some((id, name));
+                Action::None
+            }
+            Message::RenameChanged(name) => {
+                if let Some((_, typed)) = &mut self.renaming {
+                    *typed = name;
+                }
                 Action::None
             }
+            Message::CancelRename => {
+                self.renaming = None;
+                Action::None
+            }
+            Message::ConfirmRename => {
+                let Some((id, name)) = self.renaming.clone() else {
+                    return Action::None;
+                };
+                Action::Run(Task::perform(
+                    async move { image_service::rename_file(id, &name).await },
+                    Message::FileRenamed,
+                ))
+            }
+            Message::FileRenamed(result) => {
+                match result {
+                    Ok(dto) => {
+                        self.renaming = None;
+                        if let Some(img) = self.images.iter_mut().find(|img| img.id == dto.id) {
+                            img.image_dto.path = dto.path.clone();
+                            img.image_dto.thumbnail_path = dto.thumbnail_path.clone();
+                        }
+                        let name = Path::new(&dto.path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        push_success(t!("message.rename.done", name = name));
+                    }
+                    // The field stays open to correct the name
+                    Err(e) => {
+                        error!("Failed to rename the file: {}", e);
+                        push_error(t!("message.rename.error", error = e));
+                    }
+                }
+                Action::Run(self.load_versions())
+            }
+
+            Message::ReplacementChosen(path, source) => Action::Run(Task::perform(
+                version_service::replace_with_versioning(path.clone(), source),
+                move |result| Message::VersionChanged(path.clone(), result),
+            )),
+
+            Message::VersionChanged(path, result) => {
+                match result {
+                    Ok(()) => {
+                        self.refresh_image(&path);
+                        push_success(t!("message.version.success"));
+                    }
+                    Err(e) => {
+                        error!("Failed to change version of {}: {}", path, e);
+                        push_error(t!("message.version.error"));
+                    }
+                }
+                Action::Run(self.load_versions())
+            }
 
             Message::ClosePreview => {
+                // Leaving the similar images goes back to the image they started from
+                if !self.similar_trail.is_empty() {
+                    self.similar_trail.clear();
+                    let shown = self.change_preview(0);
+                    return Action::Run(Task::batch([shown, self.load_similar()]));
+                }
+                if self.pinned_preview.take().is_some() {
+                    self.preview_handle = Handle::from_path("".to_string());
+                    self.preview_loading = None;
+                self.preview_loading = None;
+                    return Action::Run(self.refresh_if_stale());
+                }
+                if !self.surprises.is_empty() {
+                    self.surprises.clear();
+                    self.surprise_index = 0;
+                    self.preview_handle = Handle::from_path("".to_string());
+                    self.preview_loading = None;
+                self.preview_loading = None;
+                    return Action::Run(self.refresh_if_stale());
+                }
                 self.show_preview = false;
+                self.versions.clear();
+                self.similar.clear();
                 self.preview_handle = Handle::from_path("".to_string());
+                self.preview_loading = None;
                 self.current_preview_index = 0;
 
+                if self.stale {
+                    return Action::Run(self.refresh_if_stale());
+                }
                 Action::Run(self.change_scroll())
             }
 
             Message::CloseFolder => {
                 self.images.clear();
                 self.folder_opened = false;
+                self.opened_stack = None;
                 let task = Task::perform(async {}, |_| Message::SearchButtonPressed);
                 Action::Run(task)
             }
 
+            Message::CloseHistory => {
+                self.close_history();
+                Action::None
+            }
+
+            Message::CloseQuickLook => {
+                self.quick_look = None;
+                Action::None
+            }
+
+            Message::ClearFilters => {
+                self.query.clear();
+                self.refinements.clear();
+                self.refining = false;
+                self.language = None;
+                self.tag_selector.selected.clear();
+                self.tag_selector.excluded.clear();
+                set_search_query(String::new());
+                set_refinements(Vec::new());
+                set_selected_tags(HashSet::new());
+                set_excluded_tags(HashSet::new());
+                if self.primary_tag.take().is_some() {
+                    self.apply_tag_settings(TagSettingsDTO::default());
+                }
+                self.update(Message::SearchButtonPressed)
+            }
+
             Message::TagsLoaded(tags) => {
                 self.tag_selector.available = tags;
                 Action::None
             }
 
-            Message::TagSelectorMessage(msg) => {
+            Message::TagSettingsLoaded(settings, search) => {
+                self.apply_tag_settings(settings.unwrap_or_default());
+                if search {
+                    Action::Run(Task::done(Message::SearchButtonPressed))
+                } else {
+                    Action::None
+                }
+            }
+
+            Message::TagSelector(msg) => {
                 // Update the tag selector state with the incoming message
                 let _ = self.tag_selector.update(msg);
 
                 // Get the currently selected tags and save them globally
                 let selected_tags = self.tag_selector.selected.clone();
                 set_selected_tags(selected_tags.clone());
+                set_excluded_tags(self.tag_selector.excluded.clone());
 
                 // Debug log to verify tags are being saved globally
                 info!(
@@ -378,76 +3644,112 @@ impl Search {
                     selected_tags.iter().map(|t| &t.name).collect::<Vec<_>>()
                 );
 
+                let primary_tag = Self::single_tag(&selected_tags);
+                if primary_tag != self.primary_tag {
+                    self.primary_tag = primary_tag;
+                    return Action::Run(Task::perform(
+                        async move {
+                            match primary_tag {
+                                Some(tag_id) => {
+                                    tag_settings_service::find(tag_id).await.unwrap_or_else(
+                                        |err| {
+                                            error!("Failed to load tag settings: {}", err);
+                                            None
+                                        },
+                                    )
+                                }
+                                None => None,
+                            }
+                        },
+                        |settings| Message::TagSettingsLoaded(settings, true),
+                    ));
+                }
+
                 // Trigger a search task asynchronously
                 let task = Task::perform(async move {}, |_| Message::SearchButtonPressed);
                 Action::Run(task)
             }
 
-            Message::GoToPage(page_index) => {
-                let page_size = self.page_size;
-                self.images.clear();
-                let query = self.query.clone();
-                let selected_tags = self.tag_selector.selected.clone();
-                self.scroll_offset = 0.0;
-                set_scroll_offset(0.0);
-                let task = Task::perform(
-                    async move {
-                        let mut filter = Filter::new();
-
-                        if !query.is_empty() {
-                            filter.query = query;
-                        }
-
-                        if !selected_tags.is_empty() {
-                            filter.tags = selected_tags.iter().map(|t| t.name.clone()).collect();
-                        }
+            Message::KeyPressed(key, modifiers) => self.handle_key(key, modifiers),
 
-                        let page = image_service::find_all(filter, page_index, page_size)
-                            .await
-                            .unwrap();
-                        (page.content, page.page_number, page.total_pages)
-                    },
-                    |(images, current_page, total_pages)| {
-                        Message::PushContainer(images, current_page, total_pages, false)
-                    },
-                );
+            Message::CardHovered(id, hovered) => {
+                let now = Instant::now();
+                self.frame = now;
+                let target = if hovered { 1.0 } else { 0.0 };
+                match self.card_lift.iter_mut().find(|(card, _)| *card == id) {
+                    Some((_, lift)) => lift.go_to(target, animation::CARD_HOVER, now),
+                    None => {
+                        let mut lift = Animation::new(0.0);
+                        lift.go_to(target, animation::CARD_HOVER, now);
+                        self.card_lift.push((id, lift));
+                    }
+                }
 
-                Action::Run(task)
+                if !hovered {
+                    if self.hovered_card == Some(id) {
+                        self.hovered_card = None;
+                    }
+                    if self.quick_look.as_ref().is_some_and(|shown| shown.0 == id) {
+                        self.quick_look = None;
+                    }
+                    return Action::None;
+                }
+                self.hovered_card = Some(id);
+                let delay = get_settings().config.quick_look_delay_ms.unwrap_or(0);
+                if delay == 0 {
+                    return Action::None;
+                }
+                Action::Run(Task::perform(
+                    tokio::time::sleep(Duration::from_millis(delay)),
+                    move |_| Message::QuickLookDue(id),
+                ))
             }
 
-            Message::SearchButtonPressed => {
-                self.images.clear();
-                let page_size = self.page_size;
-                let query = self.query.clone();
-                let selected_tags = self.tag_selector.selected.clone();
-                let selected_sort_order = self.selected_sort_order.clone();
-
-                info!("Query: {} Tags: {:?}", query, selected_tags);
-
-                let task = Task::perform(
-                    async move {
-                        let mut filter = Filter::new();
-
-                        if !query.is_empty() {
-                            filter.query = query.clone();
-                        }
+            Message::QuickLookDue(id) => {
+                if self.hovered_card != Some(id) || self.quick_look.is_some() || self.show_preview {
+                    return Action::None;
+                }
+                self.open_quick_look(id)
+            }
 
-                        if !selected_tags.is_empty() {
-                            filter.tags = selected_tags.iter().map(|t| t.name.clone()).collect();
+            Message::QuickLookLoaded(id, result) => {
+                match result {
+                    Ok(handle) => {
+                        if let Some(shown) = self.quick_look.as_mut()
+                            && shown.0 == id
+                        {
+                            shown.1 = handle;
                         }
+                    }
+                    Err(err) => warn!("Failed to load the quick look of card {}: {}", id, err),
+                }
+                Action::None
+            }
 
-                        filter.sort_order = selected_sort_order;
-
-                        let page = image_service::find_all(filter, 0, page_size).await.unwrap();
+            Message::AnimationFrame(now) => {
+                self.frame = now;
+                // Cards back on the ground need no more frames
+                self.card_lift
+                    .retain(|(_, lift)| lift.target() > 0.0 || lift.is_running(now));
+                Action::None
+            }
 
-                        (page.content, page.page_number, page.total_pages)
-                    },
-                    |(images, current_page, total_pages)| {
-                        Message::PushContainer(images, current_page, total_pages, false)
-                    },
-                );
+            Message::GoToPage(page_index) => {
+                self.images.clear();
+                self.scroll_offset = 0.0;
+                set_scroll_offset(0.0);
+                if let Some((image_id, _)) = self.related_to {
+                    return Action::Run(self.load_related(image_id, page_index));
+                }
+                Action::Run(self.load_page(page_index))
+            }
 
-                Action::Run(task)
+            Message::SearchButtonPressed => {
+                self.images.clear();
+                self.opened_stack = None;
+                self.related_to = None;
+                info!("Query: {} Tags: {:?}", self.query, self.tag_selector.selected);
+                Action::Run(Task::batch([self.load_page(0), self.load_facets()]))
             }
 
             Message::SortOrderChanged(order) => {
@@ -456,6 +3758,39 @@ impl Search {
                 Action::Run(task)
             }
 
+            Message::LanguagesLoaded(languages) => {
+                // The language filtered on is no longer set on any image
+                if self
+                    .language
+                    .as_ref()
+                    .is_some_and(|language| !languages.contains(language))
+                {
+                    self.language = None;
+                }
+                self.languages = languages;
+                Action::None
+            }
+
+            Message::LanguageChanged(language) => {
+                self.language = match language {
+                    LanguageFilter::Any => None,
+                    LanguageFilter::Only(language) => Some(language),
+                };
+                let task = Task::perform(async move {}, |_| Message::SearchButtonPressed);
+                Action::Run(task)
+            }
+
+            Message::TagMatchChanged(mode) => {
+                self.tag_match = mode;
+                set_tag_match(mode);
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+
+            Message::FuzzyToggled(fuzzy) => {
+                update_config(|config| config.fuzzy_search = Some(fuzzy));
+                Action::Run(Task::done(Message::SearchButtonPressed))
+            }
+
             Message::NavigateToRegister => Action::NavigatorToRegister(None, None),
             Message::ImagePasted(dynamic_image, format) => {
                 info!("Image pasted in search");
@@ -467,7 +3802,21 @@ impl Search {
 
     pub fn view(&'_ self) -> Element<'_, Message> {
         // Close folder header
-        let close_folder: Element<Message> = if self.folder_opened {
+        let close_folder: Element<Message> = if let Some((_, description)) = &self.related_to {
+            Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .padding(Padding::ZERO.left(22.5))
+                .push(fa_icon_solid("tags").size(16.0))
+                .push(
+                    Text::new(t!("search.related.title", description = description))
+                        .size(14)
+                        .style(Modern::secondary_text())
+                        .width(Length::Fill),
+                )
+                .push(header::header(|| Message::CloseFolder))
+                .into()
+        } else if self.folder_opened || self.opened_stack.is_some() {
             header::header(|| Message::CloseFolder)
         } else {
             Container::new(Space::new(Length::Shrink, Length::Shrink))
@@ -479,7 +3828,7 @@ impl Search {
         let tags_view = Container::new(
             self.tag_selector
                 .view()
-                .map(Message::TagSelectorMessage),
+                .map(Message::TagSelector),
         )
             .width(Length::Fill)
             .padding(10)
@@ -487,24 +3836,116 @@ impl Search {
 
         let search_bar = search_bar::search_bar(search_bar::SearchBarConfig {
             query: &self.query,
-            sort_order: self.selected_sort_order.clone(),
-            sort_options: &[SortOrder::CreatedAsc, SortOrder::CreatedDesc],
+            sort_order: self.selected_sort_order,
+            sort_options: &[
+                SortOrder::CreatedAsc,
+                SortOrder::CreatedDesc,
+                SortOrder::DescriptionAsc,
+            ],
             on_query_change: Box::new(Message::QueryChanged),
-            on_search: Message::SearchButtonPressed,
+            on_search: if self.refining {
+                Message::AddRefinement
+            } else {
+                Message::SubmitQuery
+            },
             on_register: Message::NavigateToRegister,
             on_sort_change: Box::new(Message::SortOrderChanged),
+            filters: std::iter::once(self.fuzzy_filter())
+                .chain(self.tag_match_filter())
+                .chain(self.language_filter())
+                .collect(),
+            history: Some(self.history_entries())
+                .filter(|entries| self.history_open && !entries.is_empty())
+                .map(|entries| search_bar::SearchBarHistory {
+                    entries,
+                    selected: self.history_cursor,
+                    on_select: Box::new(Message::HistorySelected),
+                }),
+            actions: vec![
+                search_bar::SearchBarAction {
+                    icon: "shuffle",
+                    tooltip: t!("search.surprise.button").to_string(),
+                    on_press: Message::SurpriseMe,
+                },
+                search_bar::SearchBarAction {
+                    icon: "layer-group",
+                    tooltip: t!("search.stack.button").to_string(),
+                    on_press: Message::ToggleSelecting,
+                },
+                search_bar::SearchBarAction {
+                    icon: "magnifying-glass-plus",
+                    tooltip: if self.refining {
+                        t!("search.refine.stop").to_string()
+                    } else {
+                        t!("search.refine.start").to_string()
+                    },
+                    on_press: Message::ToggleRefine,
+                },
+                search_bar::SearchBarAction {
+                    icon: "filter",
+                    tooltip: t!("search.facets.title").to_string(),
+                    on_press: Message::ToggleFacets,
+                },
+                search_bar::SearchBarAction {
+                    icon: "table-cells",
+                    tooltip: t!("search.export.title").to_string(),
+                    on_press: Message::ToggleExportPanel,
+                },
+                search_bar::SearchBarAction {
+                    icon: "clock-rotate-left",
+                    tooltip: t!("search.copy_history.title").to_string(),
+                    on_press: Message::ToggleCopyHistory,
+                },
+                search_bar::SearchBarAction {
+                    icon: "bookmark",
+                    tooltip: t!("search.pin_search").to_string(),
+                    on_press: Message::PinSearch,
+                },
+            ],
         });
 
         // Header
-        let header = Column::new().spacing(20).push(search_bar).push(tags_view);
-
-        // Image grid
-        let mut images_row = Row::new().spacing(20);
-        for image in &self.images {
-            images_row = images_row.push(image.view());
+        let mut header = Column::new()
+            .spacing(20)
+            .push(search_bar)
+            .push_maybe(self.refine_breadcrumbs())
+            .push(tags_view);
+        if self.show_export {
+            header = header.push(self.export_panel());
+        }
+        if self.show_copy_history {
+            header = header.push(self.copy_history_panel());
+        }
+        if self.selecting {
+            header = header.push(self.selection_bar());
+        }
+        if let Some(pending) = &self.pending_delete {
+            header = header.push(self.delete_bar(pending));
         }
 
-        let images_grid = if self.images.is_empty() {
+        let images_grid = if self.loading {
+            skeleton::skeleton_cards(
+                self.page_size.min(12) as usize,
+                CardSize::from(self.view_density),
+            )
+        } else if self.load_failed {
+            Column::new()
+                .spacing(10)
+                .align_x(Alignment::Center)
+                .push(empty_state::empty_state(
+                    "triangle-exclamation",
+                    t!("search.error.title"),
+                    t!("search.error.subtitle"),
+                ))
+                .push(
+                    Button::new(Text::new(t!("search.error.retry")).size(16))
+                        .style(Modern::primary_button())
+                        .padding(Padding::from([10, 20]))
+                        .on_press(Message::GoToPage(self.current_page)),
+                )
+                .width(Length::Fill)
+                .into()
+        } else if self.images.is_empty() {
             empty_state::empty_state(
                 "image",
                 "No images found",
@@ -516,18 +3957,7 @@ impl Search {
                     .width(Length::Fill)
                     .height(Length::Fill)
                     .push(close_folder)
-                    .push(
-                        Scrollable::new(
-                            Container::new(images_row.wrap())
-                                .width(Length::Fill)
-                                .align_x(Horizontal::Center)
-                                .padding(20),
-                        )
-                            .id(self.scroll_id.clone())
-                            .on_scroll(Message::ScrollChanged)
-                            .width(Length::Fill)
-                            .height(Length::Fill),
-                    ),
+                    .push(self.image_grid()),
             )
                 .width(Length::Fill)
                 .height(Length::Fill)
@@ -539,6 +3969,11 @@ impl Search {
             .width(Length::Fill)
             .height(Length::Fill)
             .padding(20);
+        let images_container = Row::new()
+            .spacing(20)
+            .height(Length::Fill)
+            .push(images_container)
+            .push_maybe(self.facet_panel());
 
         let pagination_view = pagination::pagination(
             self.current_page,
@@ -546,9 +3981,11 @@ impl Search {
             Message::GoToPage,
         );
 
-        let content = Column::new()
-            .spacing(30)
-            .push(header)
+        let mut content = Column::new().spacing(30).push(header);
+        if !self.pinned.is_empty() && !self.folder_opened && self.opened_stack.is_none() {
+            content = content.push(self.pinned_strip());
+        }
+        let content = content
             .push(images_container)
             .push(pagination_view);
 
@@ -557,6 +3994,117 @@ impl Search {
             .height(Length::Fill)
             .padding(20);
 
+        // Pinned image preview
+        if let Some(index) = self.pinned_preview
+            && let Some(image) = self.pinned.get(index)
+        {
+            let total_images = self.pinned.len();
+            let mut actions = Vec::new();
+            if !image.is_folder {
+                actions.push(image_preview_modal::PreviewAction {
+                    icon: "copy",
+                    tooltip: t!("message.image.container.copy").to_string(),
+                    on_press: Message::CopyImage(image.path.clone()),
+                });
+            }
+            actions.push(image_preview_modal::PreviewAction {
+                icon: "thumbtack",
+                tooltip: t!("message.image.container.unpin").to_string(),
+                on_press: Message::TogglePin(image.id, false),
+            });
+            actions.extend(self.resolution_action());
+
+            return image_preview_modal::image_preview_modal(image_preview_modal::PreviewConfig {
+                handle: self.preview_handle.clone(),
+                current_index: index,
+                total_images,
+                on_close: Message::ClosePreview,
+                on_previous: (total_images > 1).then_some(Message::PreviousPinned),
+                on_next: (total_images > 1).then_some(Message::NextPinned),
+                actions,
+                side_panel: None,
+                bottom_panel: None,
+                appear: self.preview_appear.value(self.frame),
+                on_cancel_loading: self
+                    .preview_loading
+                    .is_some()
+                    .then_some(Message::CancelPreviewLoad),
+            });
+        }
+
+        // Random discovery
+        if let Some(image) = self.surprises.get(self.surprise_index) {
+            let mut actions = vec![
+                image_preview_modal::PreviewAction {
+                    icon: if self.surprise_filtered { "filter" } else { "filter-circle-xmark" },
+                    tooltip: if self.surprise_filtered {
+                        t!("search.surprise.filtered").to_string()
+                    } else {
+                        t!("search.surprise.everything").to_string()
+                    },
+                    on_press: Message::ToggleSurpriseFilter,
+                },
+                image_preview_modal::PreviewAction {
+                    icon: "folder-open",
+                    tooltip: t!("message.image.container.open_local").to_string(),
+                    on_press: Message::OpenFileLocation(image.path.clone()),
+                },
+            ];
+            actions.extend(self.resolution_action());
+
+            return image_preview_modal::image_preview_modal(image_preview_modal::PreviewConfig {
+                handle: self.preview_handle.clone(),
+                current_index: self.surprise_index,
+                total_images: self.surprises.len(),
+                on_close: Message::ClosePreview,
+                on_previous: (self.surprise_index > 0).then_some(Message::PreviousSurprise),
+                on_next: Some(Message::SurpriseMe),
+                actions,
+                side_panel: None,
+                bottom_panel: None,
+                appear: self.preview_appear.value(self.frame),
+                on_cancel_loading: self
+                    .preview_loading
+                    .is_some()
+                    .then_some(Message::CancelPreviewLoad),
+            });
+        }
+
+        // Image reached through "More like this" or a link
+        if let Some(image) = self.similar_trail.last() {
+            let mut actions = Vec::new();
+            if !image.is_folder {
+                actions.push(image_preview_modal::PreviewAction {
+                    icon: "copy",
+                    tooltip: t!("message.image.container.copy").to_string(),
+                    on_press: Message::CopyImage(image.path.clone()),
+                });
+            }
+            actions.push(image_preview_modal::PreviewAction {
+                icon: "folder-open",
+                tooltip: t!("message.image.container.open_local").to_string(),
+                on_press: Message::OpenFileLocation(image.path.clone()),
+            });
+            actions.extend(self.resolution_action());
+
+            return image_preview_modal::image_preview_modal(image_preview_modal::PreviewConfig {
+                handle: self.preview_handle.clone(),
+                current_index: self.similar_trail.len() - 1,
+                total_images: self.similar_trail.len(),
+                on_close: Message::ClosePreview,
+                on_previous: Some(Message::SimilarBack),
+                on_next: None,
+                actions,
+                side_panel: None,
+                bottom_panel: self.similar_strip(),
+                appear: self.preview_appear.value(self.frame),
+                on_cancel_loading: self
+                    .preview_loading
+                    .is_some()
+                    .then_some(Message::CancelPreviewLoad),
+            });
+        }
+
         // Image preview
         if self.show_preview {
             let preview_config = image_preview_modal::PreviewConfig {
@@ -574,8 +4122,24 @@ impl Search {
                 } else {
                     None
                 },
+                actions: self.preview_actions(),
+                side_panel: self.info_panel(),
+                bottom_panel: self.similar_strip(),
+                appear: self.preview_appear.value(self.frame),
+                on_cancel_loading: self
+                    .preview_loading
+                    .is_some()
+                    .then_some(Message::CancelPreviewLoad),
             };
             image_preview_modal::image_preview_modal(preview_config)
+        } else if let Some((id, handle)) = self.quick_look.as_deref()
+            && let Some(card) = self.images.iter().find(|img| img.id == *id)
+        {
+            stack![
+                layout,
+                quick_look::quick_look(handle.clone(), &card.image_dto.description)
+            ]
+            .into()
         } else {
             layout.into()
         }
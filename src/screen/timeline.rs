@@ -0,0 +1,565 @@
+use crate::components::image_preview_modal;
+use crate::dtos::image_dto::ImageDTO;
+use crate::dtos::timeline_dto::MonthGroupDTO;
+use crate::services::toast_service::{push_error, push_success};
+use crate::services::{file_service, image_service, statistics_service};
+use crate::t_count;
+use iced::widget::image::Handle;
+use iced::widget::{Button, Column, Container, Image, Row, Space, column, scrollable, text};
+use iced::{Alignment, ContentFit, Element, Length, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use log::error;
+use rfd::AsyncFileDialog;
+use std::path::{Path, PathBuf};
+
+/// Every row has the same number of cells so the height of a group only depends
+/// on how many images it has loaded, which keeps jump offsets exact
+const COLUMNS: usize = 6;
+const THUMB_HEIGHT: f32 = 150.0;
+const SPACING: f32 = 12.0;
+const HEADER_HEIGHT: f32 = 48.0;
+const MORE_HEIGHT: f32 = 44.0;
+const GROUP_SPACING: f32 = 24.0;
+const CONTENT_PADDING: f32 = 20.0;
+/// Images fetched per request for a month
+const PAGE_SIZE: u64 = 30;
+
+pub enum Action {
+    None,
+    Run(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    GroupsLoaded(Result<Vec<MonthGroupDTO>, String>),
+    LoadMore(usize),
+    PageLoaded(usize, Result<Vec<ImageDTO>, String>),
+    Scrolled(scrollable::Viewport),
+    JumpTo(usize),
+    OpenImage(usize, usize),
+    PreviousImage,
+    NextImage,
+    ClosePreview,
+    ExportReport,
+    ReportPathChosen(PathBuf),
+    ReportExported(Result<(), String>),
+    NoOps,
+}
+
+struct MonthGroup {
+    info: MonthGroupDTO,
+    images: Vec<ImageDTO>,
+    loading: bool,
+}
+
+impl MonthGroup {
+    fn has_more(&self) -> bool {
+        (self.images.len() as u64) < self.info.count
+    }
+
+    fn height(&self) -> f32 {
+        let rows = self.images.len().div_ceil(COLUMNS) as f32;
+        let mut height = HEADER_HEIGHT;
+        if rows > 0.0 {
+            height += SPACING + rows * THUMB_HEIGHT + (rows - 1.0) * SPACING;
+        }
+        if self.has_more() {
+            height += SPACING + MORE_HEIGHT;
+        }
+        height
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "{} {}",
+            t!(format!("timeline.month.{}", self.info.month)),
+            self.info.year
+        )
+    }
+}
+
+pub struct Timeline {
+    groups: Vec<MonthGroup>,
+    loaded: bool,
+    scroll_id: scrollable::Id,
+    scroll_offset: f32,
+    viewport_height: f32,
+    /// Group and index of the image open in the preview
+    preview: Option<(usize, usize)>,
+    preview_handle: Handle,
+    /// Whether the library report is being written
+    exporting: bool,
+}
+
+impl Timeline {
+    pub fn new() -> (Self, Task<Message>) {
+        let timeline = Self {
+            groups: Vec::new(),
+            loaded: false,
+            scroll_id: scrollable::Id::unique(),
+            scroll_offset: 0.0,
+            viewport_height: 800.0,
+            preview: None,
+            preview_handle: Handle::from_path(""),
+            exporting: false,
+        };
+
+        let task = Task::perform(
+            async {
+                image_service::count_by_month()
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            Message::GroupsLoaded,
+        );
+
+        (timeline, task)
+    }
+
+    /// Offset of the top of each group inside the scrollable
+    fn group_offsets(&self) -> Vec<f32> {
+        let mut offset = CONTENT_PADDING;
+        self.groups
+            .iter()
+            .map(|group| {
+                let start = offset;
+                offset += group.height() + GROUP_SPACING;
+                start
+            })
+            .collect()
+    }
+
+    /// Group whose header is currently at (or above) the top of the viewport
+    fn current_group(&self) -> Option<usize> {
+        if self.groups.is_empty() {
+            return None;
+        }
+        let offsets = self.group_offsets();
+        Some(
+            offsets
+                .iter()
+                .rposition(|start| *start <= self.scroll_offset + 1.0)
+                .unwrap_or(0),
+        )
+    }
+
+    fn load_page(&mut self, index: usize) -> Task<Message> {
+        let Some(group) = self.groups.get_mut(index) else {
+            return Task::none();
+        };
+        if group.loading || !group.has_more() {
+            return Task::none();
+        }
+        group.loading = true;
+
+        let month = group.info.key();
+        let page = group.images.len() as u64 / PAGE_SIZE;
+        Task::perform(
+            async move {
+                image_service::find_by_month(&month, page, PAGE_SIZE)
+                    .await
+                    .map_err(|e| e.to_string())
+            },
+            move |result| Message::PageLoaded(index, result),
+        )
+    }
+
+    /// Fetches the first page of every group visible in the viewport that has none yet
+    fn load_visible(&mut self) -> Task<Message> {
+        let top = self.scroll_offset;
+        let bottom = top + self.viewport_height;
+        let visible: Vec<usize> = self
+            .group_offsets()
+            .into_iter()
+            .enumerate()
+            .filter(|(index, start)| {
+                let end = start + self.groups[*index].height();
+                end >= top && *start <= bottom && self.groups[*index].images.is_empty()
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        Task::batch(visible.into_iter().map(|index| self.load_page(index)))
+    }
+
+    pub fn is_previewing(&self) -> bool {
+        self.preview.is_some()
+    }
+
+    fn previewed(&self) -> Option<&ImageDTO> {
+        let (group, index) = self.preview?;
+        self.groups.get(group)?.images.get(index)
+    }
+
+    fn show_preview(&mut self, group: usize, index: usize) {
+        self.preview = Some((group, index));
+        if let Some(image) = self.previewed() {
+            self.preview_handle = Handle::from_path(file_service::preview_source(&image.path));
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::GroupsLoaded(result) => {
+                self.loaded = true;
+                match result {
+                    Ok(groups) => {
+                        self.groups = groups
+                            .into_iter()
+                            .map(|info| MonthGroup {
+                                info,
+                                images: Vec::new(),
+                                loading: false,
+                            })
+                            .collect();
+                    }
+                    Err(err) => {
+                        error!("Failed to load timeline: {}", err);
+                        push_error(t!("message.timeline.error"));
+                    }
+                }
+                Action::Run(self.load_visible())
+            }
+            Message::LoadMore(index) => Action::Run(self.load_page(index)),
+            Message::PageLoaded(index, result) => {
+                if let Some(group) = self.groups.get_mut(index) {
+                    group.loading = false;
+                    match result {
+                        Ok(images) => {
+                            // An empty page means the count changed since it was taken
+                            if images.is_empty() {
+                                group.info.count = group.images.len() as u64;
+                            }
+                            group.images.extend(images);
+                        }
+                        Err(err) => {
+                            error!("Failed to load images for {}: {}", group.info.key(), err);
+                            push_error(t!("message.timeline.error"));
+                        }
+                    }
+                }
+                Action::Run(self.load_visible())
+            }
+            Message::Scrolled(viewport) => {
+                self.scroll_offset = viewport.absolute_offset().y;
+                self.viewport_height = viewport.bounds().height;
+                Action::Run(self.load_visible())
+            }
+            Message::JumpTo(index) => {
+                let Some(offset) = self.group_offsets().get(index).copied() else {
+                    return Action::None;
+                };
+                // Headers above the target keep their height, so the offset stays valid
+                // while the group itself loads
+                self.scroll_offset = offset - CONTENT_PADDING;
+                Action::Run(Task::batch([
+                    scrollable::scroll_to(
+                        self.scroll_id.clone(),
+                        scrollable::AbsoluteOffset {
+                            x: 0.0,
+                            y: self.scroll_offset,
+                        },
+                    ),
+                    self.load_page(index),
+                ]))
+            }
+            Message::OpenImage(group, index) => {
+                let Some(image) = self.groups.get(group).and_then(|g| g.images.get(index)) else {
+                    return Action::None;
+                };
+                if image.is_folder {
+                    if let Err(err) = file_service::open_in_file_explorer(Path::new(&image.path)) {
+                        error!("Failed to open folder {}: {}", image.path, err);
+                    }
+                } else {
+                    self.show_preview(group, index);
+                }
+                Action::None
+            }
+            Message::PreviousImage => {
+                if let Some((group, index)) = self.preview {
+                    let len = self.groups[group].images.len();
+                    self.show_preview(group, (index + len - 1) % len);
+                }
+                Action::None
+            }
+            Message::NextImage => {
+                if let Some((group, index)) = self.preview {
+                    let len = self.groups[group].images.len();
+                    self.show_preview(group, (index + 1) % len);
+                }
+                Action::None
+            }
+            Message::ClosePreview => {
+                self.preview = None;
+                Action::None
+            }
+            Message::ExportReport => Action::Run(Task::perform(
+                async {
+                    AsyncFileDialog::new()
+                        .add_filter("HTML", &["html"])
+                        .set_file_name("library_report.html")
+                        .save_file()
+                        .await
+                },
+                |file| match file {
+                    Some(file) => Message::ReportPathChosen(file.path().to_path_buf()),
+                    None => Message::NoOps,
+                },
+            )),
+            Message::ReportPathChosen(path) => {
+                self.exporting = true;
+                Action::Run(Task::perform(
+                    statistics_service::export_report(path),
+                    Message::ReportExported,
+                ))
+            }
+            Message::ReportExported(result) => {
+                self.exporting = false;
+                match result {
+                    Ok(()) => push_success(t!("message.report.success")),
+                    Err(err) => {
+                        error!("Failed to export the library report: {}", err);
+                        push_error(t!("message.report.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::NoOps => Action::None,
+        }
+    }
+
+    pub fn view(&'_ self) -> Element<'_, Message> {
+        if let Some((group, index)) = self.preview {
+            let total_images = self.groups[group].images.len();
+            return image_preview_modal::image_preview_modal(image_preview_modal::PreviewConfig {
+                handle: self.preview_handle.clone(),
+                current_index: index,
+                total_images,
+                on_close: Message::ClosePreview,
+                on_previous: (total_images > 1).then_some(Message::PreviousImage),
+                on_next: (total_images > 1).then_some(Message::NextImage),
+                actions: Vec::new(),
+                side_panel: None,
+                bottom_panel: None,
+                appear: 1.0,
+                on_cancel_loading: None,
+            });
+        }
+
+        let title = column![
+            text(t!("timeline.title"))
+                .size(32)
+                .style(Modern::primary_text()),
+            Space::new(0, 8),
+            text(t!("timeline.subtitle"))
+                .size(16)
+                .style(Modern::secondary_text()),
+        ];
+        let export = Button::new(
+            Row::new()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(
+                    fa_icon_solid(if self.exporting {
+                        "hourglass-half"
+                    } else {
+                        "chart-column"
+                    })
+                    .size(16.0),
+                )
+                .push(text(t!("timeline.button.export_report")).size(16)),
+        )
+        .padding([12, 20])
+        .style(Modern::secondary_button())
+        .on_press_maybe((!self.exporting).then_some(Message::ExportReport));
+        let header = Row::new()
+            .align_y(Alignment::Center)
+            .push(title)
+            .push(Space::with_width(Length::Fill))
+            .push(export);
+
+        let body: Element<Message> = if self.loaded && self.groups.is_empty() {
+            Container::new(
+                text(t!("timeline.empty"))
+                    .size(16)
+                    .style(Modern::secondary_text()),
+            )
+            .padding(20)
+            .into()
+        } else {
+            Row::new()
+                .spacing(20)
+                .height(Length::Fill)
+                .push(self.date_navigation())
+                .push(
+                    Column::new()
+                        .spacing(10)
+                        .width(Length::Fill)
+                        .push(self.sticky_header())
+                        .push(self.groups_view()),
+                )
+                .into()
+        };
+
+        Container::new(
+            Column::new()
+                .spacing(24)
+                .padding(20)
+                .push(header)
+                .push(body),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Label of the month at the top of the viewport, shown above the scrolled content
+    fn sticky_header(&self) -> Element<'_, Message> {
+        let label = self
+            .current_group()
+            .map(|index| {
+                let group = &self.groups[index];
+                format!(
+                    "{} · {}",
+                    group.title(),
+                    t_count!("timeline.count", group.info.count)
+                )
+            })
+            .unwrap_or_default();
+
+        Container::new(
+            Row::new()
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .push(fa_icon_solid("calendar").size(18.0))
+                .push(text(label).size(18).style(Modern::primary_text())),
+        )
+        .padding([10, 20])
+        .width(Length::Fill)
+        .style(Modern::card_container())
+        .into()
+    }
+
+    /// Years with their months, each month jumping to its group
+    fn date_navigation(&self) -> Element<'_, Message> {
+        let current = self.current_group();
+        let mut list = Column::new().spacing(6);
+        let mut year = None;
+
+        for (index, group) in self.groups.iter().enumerate() {
+            if year != Some(group.info.year) {
+                year = Some(group.info.year);
+                list = list.push(
+                    text(group.info.year.to_string())
+                        .size(18)
+                        .style(Modern::primary_text()),
+                );
+            }
+
+            let label = format!(
+                "{} ({})",
+                t!(format!("timeline.month.{}", group.info.month)),
+                group.info.count
+            );
+            let button = Button::new(text(label).size(14))
+                .width(Length::Fill)
+                .on_press(Message::JumpTo(index));
+            list = list.push(if current == Some(index) {
+                button.style(Modern::primary_button())
+            } else {
+                button.style(Modern::secondary_button())
+            });
+        }
+
+        Container::new(scrollable(list.padding([0, 12])).height(Length::Fill))
+            .width(Length::Fixed(190.0))
+            .height(Length::Fill)
+            .padding([12, 0])
+            .style(Modern::card_container())
+            .into()
+    }
+
+    fn groups_view(&self) -> Element<'_, Message> {
+        let mut content = Column::new()
+            .spacing(GROUP_SPACING)
+            .padding(CONTENT_PADDING);
+
+        for (index, group) in self.groups.iter().enumerate() {
+            content = content.push(self.group_view(index, group));
+        }
+
+        scrollable(content)
+            .id(self.scroll_id.clone())
+            .on_scroll(Message::Scrolled)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn group_view<'a>(&'a self, group_index: usize, group: &'a MonthGroup) -> Element<'a, Message> {
+        let mut column = Column::new().spacing(SPACING).push(
+            Container::new(
+                Row::new()
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+                    .push(text(group.title()).size(22).style(Modern::primary_text()))
+                    .push(
+                        text(t_count!("timeline.count", group.info.count))
+                            .size(14)
+                            .style(Modern::secondary_text()),
+                    ),
+            )
+            .height(Length::Fixed(HEADER_HEIGHT))
+            .align_y(Alignment::Center),
+        );
+
+        for (row_index, chunk) in group.images.chunks(COLUMNS).enumerate() {
+            let mut row = Row::new()
+                .spacing(SPACING)
+                .height(Length::Fixed(THUMB_HEIGHT));
+            for (column_index, image) in chunk.iter().enumerate() {
+                let index = row_index * COLUMNS + column_index;
+                row = row.push(
+                    Button::new(
+                        Image::new(Handle::from_path(&image.thumbnail_path))
+                            .content_fit(ContentFit::Cover)
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                    .padding(0)
+                    .style(Modern::plain_button())
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .on_press(Message::OpenImage(group_index, index)),
+                );
+            }
+            // Pad the last row so cells keep the same width
+            for _ in chunk.len()..COLUMNS {
+                row = row.push(Space::with_width(Length::Fill));
+            }
+            column = column.push(row);
+        }
+
+        if group.has_more() {
+            let remaining = group.info.count - group.images.len() as u64;
+            let label = if group.loading {
+                t!("timeline.loading").to_string()
+            } else {
+                t_count!("timeline.load_more", remaining).to_string()
+            };
+            column = column.push(
+                Container::new(
+                    Button::new(text(label).size(14))
+                        .style(Modern::secondary_button())
+                        .on_press_maybe((!group.loading).then_some(Message::LoadMore(group_index))),
+                )
+                .height(Length::Fixed(MORE_HEIGHT))
+                .align_y(Alignment::Center),
+            );
+        }
+
+        column.into()
+    }
+}
@@ -0,0 +1,339 @@
+use crate::components::accessibility::labeled;
+use crate::config::get_settings;
+use crate::dtos::trashed_image_dto::TrashedImageDTO;
+use crate::models::delete_behavior::DeleteBehavior;
+use crate::services::toast_service::{push_error, push_success};
+use crate::services::trash_service;
+use crate::t_count;
+use iced::widget::image::Handle;
+use iced::widget::{
+    Button, Column, Container, Image, Row, Space, column, scrollable, text, tooltip,
+};
+use iced::{Alignment, ContentFit, Element, Length, Padding, Task};
+use iced_font_awesome::fa_icon_solid;
+use iced_modern_theme::Modern;
+use log::error;
+use std::collections::HashSet;
+
+pub enum Action {
+    None,
+    Run(Task<Message>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Loaded(Result<Vec<TrashedImageDTO>, String>),
+    Restore(i64),
+    Restored(i64, Result<(), String>),
+    /// Purges with the configured behavior, asking first when files are removed for good
+    Purge(i64, DeleteBehavior),
+    CancelPurge,
+    Purged(i64, Result<(), String>),
+    /// Asks for confirmation before anything is purged
+    Empty,
+    ConfirmEmpty(DeleteBehavior),
+    CancelEmpty,
+    Emptied(DeleteBehavior, Result<usize, String>),
+}
+
+/// Entries deleted from the library, to restore or purge for good
+pub struct Trash {
+    images: Vec<TrashedImageDTO>,
+    loaded: bool,
+    /// Entries being restored or purged
+    busy: HashSet<i64>,
+    /// Entry waiting for confirmation before its files are removed for good
+    confirm_purge: Option<i64>,
+    confirm_empty: bool,
+    emptying: bool,
+}
+
+impl Trash {
+    pub fn new() -> (Self, Task<Message>) {
+        let trash = Self {
+            images: Vec::new(),
+            loaded: false,
+            busy: HashSet::new(),
+            confirm_purge: None,
+            confirm_empty: false,
+            emptying: false,
+        };
+        (trash, Self::load())
+    }
+
+    fn load() -> Task<Message> {
+        Task::perform(
+            async { trash_service::find_all().await.map_err(|e| e.to_string()) },
+            Message::Loaded,
+        )
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Loaded(result) => {
+                self.loaded = true;
+                match result {
+                    Ok(images) => self.images = images,
+                    Err(err) => {
+                        error!("Failed to load the trash: {}", err);
+                        push_error(t!("message.trash.error"));
+                    }
+                }
+                Action::None
+            }
+            Message::Restore(id) => {
+                self.busy.insert(id);
+                Action::Run(Task::perform(trash_service::restore(id), move |result| {
+                    Message::Restored(id, result)
+                }))
+            }
+            Message::Restored(id, result) => {
+                self.busy.remove(&id);
+                match result {
+                    Ok(()) => push_success(t!("message.trash.restored")),
+                    Err(err) => {
+                        error!("Failed to restore image {}: {}", id, err);
+                        push_error(t!("message.trash.restore_error", err = err));
+                    }
+                }
+                Action::Run(Self::load())
+            }
+            Message::Purge(id, DeleteBehavior::Permanent) if self.confirm_purge != Some(id) => {
+                self.confirm_purge = Some(id);
+                Action::None
+            }
+            Message::Purge(id, behavior) => {
+                self.confirm_purge = None;
+                self.busy.insert(id);
+                Action::Run(Task::perform(
+                    trash_service::purge(id, behavior),
+                    move |result| Message::Purged(id, result),
+                ))
+            }
+            Message::CancelPurge => {
+                self.confirm_purge = None;
+                Action::None
+            }
+            Message::Purged(id, result) => {
+                self.busy.remove(&id);
+                if let Err(err) = result {
+                    error!("Failed to purge image {}: {}", id, err);
+                    push_error(t!("message.trash.error"));
+                }
+                Action::Run(Self::load())
+            }
+            Message::Empty => {
+                self.confirm_empty = true;
+                Action::None
+            }
+            Message::ConfirmEmpty(behavior) => {
+                self.confirm_empty = false;
+                self.emptying = true;
+                Action::Run(Task::perform(trash_service::empty(behavior), move |result| {
+                    Message::Emptied(behavior, result)
+                }))
+            }
+            Message::CancelEmpty => {
+                self.confirm_empty = false;
+                Action::None
+            }
+            Message::Emptied(behavior, result) => {
+                self.emptying = false;
+                match result {
+                    Ok(count) if behavior == DeleteBehavior::RecycleBin => {
+                        push_success(t_count!("message.trash.recycled", count))
+                    }
+                    Ok(count) => push_success(t_count!("message.trash.emptied", count)),
+                    Err(err) => {
+                        error!("Failed to empty the trash: {}", err);
+                        push_error(t!("message.trash.error"));
+                    }
+                }
+                Action::Run(Self::load())
+            }
+        }
+    }
+
+    pub fn view(&'_ self) -> Element<'_, Message> {
+        let mut actions = Row::new().spacing(8).align_y(Alignment::Center);
+        if self.confirm_empty {
+            actions = actions.push(
+                Button::new(text(t!("trash.button.cancel")))
+                    .padding(Padding::from([8, 16]))
+                    .style(Modern::secondary_button())
+                    .on_press(Message::CancelEmpty),
+            );
+            // Removing the files for good stays on offer as the second choice
+            if delete_behavior() == DeleteBehavior::RecycleBin {
+                actions = actions.push(
+                    Button::new(text(t_count!(
+                        "trash.button.confirm_recycle",
+                        self.images.len()
+                    )))
+                    .padding(Padding::from([8, 16]))
+                    .style(Modern::primary_button())
+                    .on_press(Message::ConfirmEmpty(DeleteBehavior::RecycleBin)),
+                );
+            }
+            actions = actions.push(
+                Button::new(text(t_count!(
+                    "trash.button.confirm_empty",
+                    self.images.len()
+                )))
+                .padding(Padding::from([8, 16]))
+                .style(Modern::danger_button())
+                .on_press(Message::ConfirmEmpty(DeleteBehavior::Permanent)),
+            );
+        } else {
+            actions = actions.push(
+                Button::new(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("trash").size(14.0))
+                        .push(text(t!("trash.button.empty"))),
+                )
+                .padding(Padding::from([8, 16]))
+                .style(Modern::danger_button())
+                .on_press_maybe(
+                    (!self.images.is_empty() && !self.emptying).then_some(Message::Empty),
+                ),
+            );
+        }
+
+        let header = Row::new()
+            .align_y(Alignment::Center)
+            .push(
+                column![
+                    text(t!("trash.title"))
+                        .size(32)
+                        .style(Modern::primary_text()),
+                    Space::new(0, 8),
+                    text(t!("trash.subtitle"))
+                        .size(16)
+                        .style(Modern::secondary_text()),
+                ]
+                .width(Length::Fill),
+            )
+            .push(actions);
+
+        let mut list = Column::new().spacing(8);
+        if self.loaded && self.images.is_empty() {
+            list = list.push(
+                text(t!("trash.empty"))
+                    .size(16)
+                    .style(Modern::secondary_text()),
+            );
+        }
+        for trashed in &self.images {
+            list = list.push(self.view_image(trashed));
+        }
+
+        let content = Column::new()
+            .spacing(24)
+            .padding(20)
+            .width(Length::Fill)
+            .push(header)
+            .push(
+                Container::new(list)
+                    .padding(20)
+                    .width(Length::Fill)
+                    .style(Modern::card_container()),
+            );
+
+        Container::new(scrollable(content).width(Length::Fill).height(Length::Fill))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn view_image<'a>(&self, trashed: &'a TrashedImageDTO) -> Element<'a, Message> {
+        let image = &trashed.image;
+        let busy = self.busy.contains(&image.id) || self.emptying;
+        let behavior = delete_behavior();
+
+        let restore = labeled(
+            Button::new(fa_icon_solid("trash-arrow-up").size(14.0))
+                .padding(Padding::from([6, 10]))
+                .style(Modern::secondary_button())
+                .on_press_maybe((!busy).then_some(Message::Restore(image.id))),
+            t!("trash.button.restore"),
+            tooltip::Position::Top,
+        );
+        let purge: Element<'a, Message> = if self.confirm_purge == Some(image.id) {
+            Row::new()
+                .spacing(8)
+                .push(
+                    Button::new(text(t!("trash.button.cancel")).size(14))
+                        .padding(Padding::from([6, 10]))
+                        .style(Modern::secondary_button())
+                        .on_press(Message::CancelPurge),
+                )
+                .push(
+                    Button::new(text(t!("trash.button.purge")).size(14))
+                        .padding(Padding::from([6, 10]))
+                        .style(Modern::danger_button())
+                        .on_press_maybe(
+                            (!busy).then_some(Message::Purge(image.id, DeleteBehavior::Permanent)),
+                        ),
+                )
+                .into()
+        } else {
+            let label = match behavior {
+                DeleteBehavior::Permanent => t!("trash.button.purge"),
+                DeleteBehavior::RecycleBin => t!("trash.button.recycle"),
+            };
+            labeled(
+                Button::new(fa_icon_solid("xmark").size(14.0))
+                    .padding(Padding::from([6, 10]))
+                    .style(Modern::danger_button())
+                    .on_press_maybe((!busy).then_some(Message::Purge(image.id, behavior))),
+                label,
+                tooltip::Position::Top,
+            )
+        };
+
+        let kind = if image.is_folder {
+            t!("trash.kind.folder")
+        } else {
+            t!("trash.kind.image")
+        };
+
+        Row::new()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                Image::new(Handle::from_path(&trashed.thumbnail_path))
+                    .content_fit(ContentFit::Cover)
+                    .width(Length::Fixed(64.0))
+                    .height(Length::Fixed(64.0)),
+            )
+            .push(
+                Column::new()
+                    .spacing(4)
+                    .width(Length::Fill)
+                    .push(
+                        text(&image.description)
+                            .size(14)
+                            .style(Modern::primary_text()),
+                    )
+                    .push(
+                        text(format!("#{} · {}", image.id, kind))
+                            .size(12)
+                            .style(Modern::secondary_text()),
+                    ),
+            )
+            .push(
+                text(t!("trash.deleted_at", time = trashed.deleted_at))
+                    .size(12)
+                    .style(Modern::secondary_text()),
+            )
+            .push(restore)
+            .push(purge)
+            .into()
+    }
+}
+
+fn delete_behavior() -> DeleteBehavior {
+    get_settings().config.delete_behavior.unwrap_or_default()
+}
@@ -1,4 +1,5 @@
 use crate::components::{scrollable_form, tag_selector, ScrollableFormConfig};
+use crate::components::tag_chips::tag_chips;
 use crate::components::tag_selector::{Message as TagSelectorMessage, TagSelector};
 use crate::dtos::image_dto::{ImageDTO, ImageUpdateDTO};
 use crate::dtos::tag_dto::TagDTO;
@@ -19,18 +20,24 @@ pub enum Action {
     None,
     Run(Task<Message>),
     GoToSearch,
+    FilterByTag(TagDTO),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    TagSelectorMessage(TagSelectorMessage),
+    TagSelector(TagSelectorMessage),
     TagsLoaded(HashSet<TagDTO>),
     DescriptionChanged(String),
+    LanguageChanged(String),
     Submit {
         description: String,
+        language: String,
         tags: HashSet<TagDTO>,
     },
     NavigateToSearch,
+    FilterByTag(TagDTO),
+    SplitFolder,
+    FolderSplit(Result<usize, String>),
     NoOps,
 }
 
@@ -39,6 +46,7 @@ pub struct Update {
     image_dto: ImageDTO,
     description: String,
     original_description: String,
+    language: String,
     tags_loaded: bool,
     submitted: bool,
 }
@@ -47,6 +55,7 @@ impl Update {
     pub fn new(image_dto: ImageDTO) -> (Self, Task<Message>) {
         let description = image_dto.description.clone();
         let original_description = image_dto.description.clone();
+        let language = image_dto.language.clone().unwrap_or_default();
 
         let tag_selector = TagSelector::new(HashSet::new(), true, true);
         let update = Update {
@@ -54,17 +63,15 @@ impl Update {
             image_dto,
             description,
             original_description,
+            language,
             tags_loaded: false,
             submitted: false,
         };
 
         // Carrega todas as tags disponíveis
         let task = Task::perform(
-            async move {
-                let all_tags = tag_service::find_all().await.unwrap_or_default();
-                all_tags
-            },
-            |all_tags| Message::TagsLoaded(all_tags),
+            async move { tag_service::find_all().await.unwrap_or_default() },
+            Message::TagsLoaded,
         );
 
         (update, task)
@@ -81,9 +88,9 @@ impl Update {
                 Action::None
             }
 
-            Message::TagSelectorMessage(msg) => {
+            Message::TagSelector(msg) => {
                 let task: Task<tag_selector::Message> = self.tag_selector.update(msg);
-                let task: Task<Message> = task.map(Message::TagSelectorMessage);
+                let task: Task<Message> = task.map(Message::TagSelector);
                 Action::Run(task)
             }
 
@@ -92,7 +99,16 @@ impl Update {
                 Action::None
             }
 
-            Message::Submit { description, tags } => {
+            Message::LanguageChanged(language) => {
+                self.language = language;
+                Action::None
+            }
+
+            Message::Submit {
+                description,
+                language,
+                tags,
+            } => {
                 if self.submitted {
                     return Action::None;
                 }
@@ -109,6 +125,7 @@ impl Update {
                         if !tags.is_empty() {
                             update_dto.tags = Some(tags);
                         }
+                        update_dto.language = Some(language);
                         update_dto.is_prepared = true;
 
                         image_service::update_from_dto(image_id, update_dto).await
@@ -129,7 +146,33 @@ impl Update {
                 self.submitted = true;
                 Action::Run(task)
             }
+            Message::SplitFolder => {
+                if self.submitted || !self.image_dto.is_folder {
+                    return Action::None;
+                }
+                self.submitted = true;
+                let folder_id = self.image_dto.id;
+                Action::Run(Task::perform(
+                    async move {
+                        image_service::split_folder_entry(folder_id)
+                            .await
+                            .map(|ids| ids.len())
+                    },
+                    Message::FolderSplit,
+                ))
+            }
+            Message::FolderSplit(result) => {
+                match result {
+                    Ok(count) => push_success(t!("message.split.success", count = count)),
+                    Err(err) => {
+                        error!("Error splitting folder entry: {}", err);
+                        push_error(t!("message.split.error", reason = err));
+                    }
+                }
+                Action::GoToSearch
+            }
             Message::NavigateToSearch => Action::GoToSearch,
+            Message::FilterByTag(tag) => Action::FilterByTag(tag),
 
             _ => Action::None,
         }
@@ -185,6 +228,19 @@ impl Update {
                         .padding(Padding::from([12, 16]))
                         .size(16)
                         .on_input(Message::DescriptionChanged),
+                )
+                .push(
+                    Row::new()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("language").size(16.0))
+                        .push(
+                            text_input(t!("update.placeholder.language").as_ref(), &self.language)
+                                .style(Modern::text_input())
+                                .padding(Padding::from([12, 16]))
+                                .size(16)
+                                .on_input(Message::LanguageChanged),
+                        ),
                 ),
         )
         .padding(30)
@@ -206,8 +262,19 @@ impl Update {
                                 .font(iced::Font::MONOSPACE),
                         ),
                 )
+                .push_maybe((!self.image_dto.tags.is_empty()).then(|| {
+                    Row::new()
+                        .spacing(10)
+                        .align_y(Alignment::Center)
+                        .push(
+                            Text::new(t!("update.section.tags_search"))
+                                .size(14)
+                                .color(Color::from_rgb(0.6, 0.6, 0.6)),
+                        )
+                        .push(tag_chips(&self.image_dto.tags, 13, Message::FilterByTag).wrap())
+                }))
                 .push(if self.tags_loaded {
-                    self.tag_selector.view().map(Message::TagSelectorMessage)
+                    self.tag_selector.view().map(Message::TagSelector)
                 } else {
                     Container::new(
                         Row::new()
@@ -231,8 +298,10 @@ impl Update {
 
         // Field validation
         let description_changed = self.description != self.original_description;
+        let language_changed = self.language.trim()
+            != self.image_dto.language.as_deref().unwrap_or_default();
         let tags_changed = self.tag_selector.selected_tags() != self.image_dto.tags;
-        let has_changes = description_changed || tags_changed;
+        let has_changes = description_changed || language_changed || tags_changed;
 
         let description_valid = !self.description.trim().is_empty();
         let tags_valid = !self.tag_selector.selected.is_empty();
@@ -258,6 +327,20 @@ impl Update {
                 );
             }
 
+            if language_changed {
+                changes_list = changes_list.push(
+                    Row::new()
+                        .spacing(8)
+                        .align_y(Alignment::Center)
+                        .push(fa_icon_solid("circle-dot").size(12.0))
+                        .push(
+                            Text::new(t!("update.changes.language"))
+                                .size(14)
+                                .color(Color::from_rgb(0.2, 0.6, 0.8)),
+                        ),
+                );
+            }
+
             if tags_changed {
                 changes_list = changes_list.push(
                     Row::new()
@@ -361,6 +444,7 @@ impl Update {
                             .style(Modern::success_button())
                             .on_press(Message::Submit {
                                 description: self.description.clone(),
+                                language: self.language.clone(),
                                 tags: self.tag_selector.selected_tags(),
                             });
                     } else if self.submitted {
@@ -370,7 +454,19 @@ impl Update {
                     }
 
                     button
-                }),
+                })
+                .push_maybe(self.image_dto.is_folder.then(|| {
+                    Button::new(
+                        Row::new()
+                            .spacing(12)
+                            .align_y(Alignment::Center)
+                            .push(fa_icon_solid("object-ungroup").size(18.0))
+                            .push(Text::new(t!("update.button.split_folder")).size(16)),
+                    )
+                    .padding(Padding::from([15, 30]))
+                    .style(Modern::secondary_button())
+                    .on_press_maybe((!self.submitted).then_some(Message::SplitFolder))
+                })),
         )
         .padding(30)
         .style(Modern::floating_container())
@@ -1,11 +1,20 @@
 use arboard::{Clipboard, ImageData};
 use image::DynamicImage;
 use log::info;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
+use crate::services::download_service;
 use crate::services::file_service::detect_image_format;
 
 static CLIPBOARD: OnceLock<Mutex<Clipboard>> = OnceLock::new();
 
+/// Images kept in the copy history
+const HISTORY_SIZE: usize = 10;
+
+/// Paths of the images last copied from the app, the latest first
+static HISTORY: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
 pub fn get_clipboard() -> &'static Mutex<Clipboard> {
     CLIPBOARD.get_or_init(|| Mutex::new(Clipboard::new().expect("Failed to create Clipboard")))
 }
@@ -24,9 +33,158 @@ pub fn copy_image_to_clipboard(path: &str) -> Result<(), Box<dyn std::error::Err
     let mut clipboard = clipboard.lock().unwrap();
     clipboard.set_image(img_data)?;
 
+    remember_copy(path);
     Ok(())
 }
 
+/// Moves the image to the front of the history, dropping the oldest past its size
+fn remember_copy(path: &str) {
+    if let Ok(mut history) = HISTORY.lock() {
+        history.retain(|copied| copied != path);
+        history.push_front(path.to_string());
+        history.truncate(HISTORY_SIZE);
+    }
+}
+
+/// Images recently copied to the clipboard that are still on disk, the latest first
+pub fn copy_history() -> Vec<String> {
+    HISTORY
+        .lock()
+        .map(|history| {
+            history
+                .iter()
+                .filter(|path| Path::new(path).exists())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn clear_copy_history() {
+    if let Ok(mut history) = HISTORY.lock() {
+        history.clear();
+    }
+}
+
+/// How a stored file is written to the clipboard as text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// Absolute path on disk
+    Path,
+    /// `file://` URL
+    FileUrl,
+    /// Markdown image link pointing at the file URL
+    Markdown,
+}
+
+fn absolute_path(path: &str) -> String {
+    let absolute = std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string());
+
+    // Windows canonical paths carry the verbatim prefix, which other apps don't understand
+    absolute
+        .strip_prefix(r"\\?\")
+        .map(str::to_string)
+        .unwrap_or(absolute)
+}
+
+fn file_url(absolute_path: &str) -> String {
+    let normalized = absolute_path.replace('\\', "/");
+    let encoded: String = normalized
+        .chars()
+        .map(|c| match c {
+            ' ' => "%20".to_string(),
+            '#' => "%23".to_string(),
+            '%' => "%25".to_string(),
+            '(' => "%28".to_string(),
+            ')' => "%29".to_string(),
+            '?' => "%3F".to_string(),
+            _ => c.to_string(),
+        })
+        .collect();
+
+    if encoded.starts_with('/') {
+        format!("file://{}", encoded)
+    } else {
+        format!("file:///{}", encoded)
+    }
+}
+
+/// Copies a reference to a stored file as text: its absolute path, a file URL
+/// or a markdown image link using `alt` as the alternative text
+pub fn copy_file_reference(
+    path: &str,
+    format: CopyFormat,
+    alt: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let absolute = absolute_path(path);
+    let text = match format {
+        CopyFormat::Path => absolute,
+        CopyFormat::FileUrl => file_url(&absolute),
+        CopyFormat::Markdown => {
+            let alt = alt.replace(['[', ']'], "").replace('\n', " ");
+            format!("![{}]({})", alt.trim(), file_url(&absolute))
+        }
+    };
+
+    let clipboard = get_clipboard();
+    let mut clipboard = clipboard.lock().unwrap();
+    clipboard.set_text(text)?;
+
+    Ok(())
+}
+
+/// Puts the files on the clipboard as a file list, so a file manager or mail client
+/// pastes the files themselves: `CF_HDROP` on Windows, file URLs on macOS and
+/// `text/uri-list` on Linux
+pub fn copy_files_to_clipboard(paths: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let files: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| PathBuf::from(absolute_path(path)))
+        .collect();
+
+    let clipboard = get_clipboard();
+    let mut clipboard = clipboard.lock().unwrap();
+    clipboard.set().file_list(&files)?;
+
+    Ok(())
+}
+
+/// Extracts the page or image address from HTML placed on the clipboard by browsers
+fn source_url_from_html(html: &str) -> Option<String> {
+    // Windows CF_HTML carries the page address in its header
+    let header_url = html
+        .lines()
+        .find_map(|line| line.strip_prefix("SourceURL:"))
+        .map(|url| url.trim().to_string());
+
+    let img_src = || {
+        let lower = html.to_ascii_lowercase();
+        let img = lower.find("<img")?;
+        let src = img + lower[img..].find("src=")? + 4;
+        let quote = html[src..].chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let start = src + 1;
+        let end = start + html[start..].find(quote)?;
+        Some(html[start..end].replace("&amp;", "&"))
+    };
+
+    header_url
+        .or_else(img_src)
+        .filter(|url| download_service::is_http_url(url))
+}
+
+/// Address the clipboard image was copied from, when a browser provided one
+pub fn get_clipboard_source_url() -> Option<String> {
+    let clipboard = get_clipboard();
+    let mut clipboard = clipboard.lock().ok()?;
+    let html = clipboard.get().html().ok()?;
+    source_url_from_html(&html)
+}
+
 fn get_direct_image(clipboard: &mut Clipboard) -> Option<(DynamicImage, image::ImageFormat)> {
     match clipboard.get_image() {
         Ok(image_data) => {
@@ -0,0 +1,125 @@
+use crate::config::get_settings;
+use crate::services::image_handle::decode_to_handle;
+use iced::widget::image::Handle;
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Decoded images shared by the grid thumbnails and the preview, keyed by file path.
+/// The least recently used ones are dropped once the configured budget is exceeded.
+static CACHE: Lazy<Mutex<ImageCache>> = Lazy::new(|| Mutex::new(ImageCache::default()));
+
+const MEGABYTE: usize = 1024 * 1024;
+
+#[derive(Default)]
+struct ImageCache {
+    entries: HashMap<String, Entry>,
+    /// Bytes held by all entries
+    used: usize,
+    /// Bumped on every access, orders the entries by last use
+    clock: u64,
+}
+
+struct Entry {
+    handle: Handle,
+    size: usize,
+    last_used: u64,
+}
+
+impl ImageCache {
+    fn get(&mut self, path: &str) -> Option<Handle> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(path)?;
+        entry.last_used = self.clock;
+        Some(entry.handle.clone())
+    }
+
+    fn insert(&mut self, path: String, handle: Handle, size: usize, budget: usize) {
+        self.remove(&path);
+        if size > budget {
+            return;
+        }
+        self.trim(budget - size);
+        self.clock += 1;
+        self.used += size;
+        self.entries.insert(
+            path,
+            Entry {
+                handle,
+                size,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn remove(&mut self, path: &str) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.used -= entry.size;
+        }
+    }
+
+    /// Evicts the least recently used entries until at most `limit` bytes are held
+    fn trim(&mut self, limit: usize) {
+        while self.used > limit {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            debug!("Evicting {} from the image cache", oldest);
+            self.remove(&oldest);
+        }
+    }
+}
+
+fn budget() -> usize {
+    get_settings().config.image_cache_mb.unwrap_or(256) as usize * MEGABYTE
+}
+
+fn cache() -> std::sync::MutexGuard<'static, ImageCache> {
+    CACHE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Cached image of the file, if it was decoded and not evicted since
+pub fn get(path: &str) -> Option<Handle> {
+    cache().get(path)
+}
+
+/// Forgets the decoded image of a file that changed on disk
+pub fn invalidate(path: &str) {
+    cache().remove(path);
+}
+
+/// Drops entries until the cache fits the budget again, after the budget was lowered
+pub fn trim() {
+    cache().trim(budget());
+}
+
+/// Decoded image of the file, from the cache or decoded off the UI thread and cached
+pub async fn load(path: String) -> Result<Handle, String> {
+    if let Some(handle) = get(&path) {
+        return Ok(handle);
+    }
+
+    let handle = decode_to_handle(path.clone()).await?;
+    if let Handle::Rgba { pixels, .. } = &handle {
+        cache().insert(path, handle.clone(), pixels.len(), budget());
+    }
+    Ok(handle)
+}
+
+/// Loads several files, skipping those that fail to decode
+pub async fn load_all(paths: Vec<String>) -> Vec<(String, Handle)> {
+    let mut loaded = Vec::with_capacity(paths.len());
+    for path in paths {
+        match load(path.clone()).await {
+            Ok(handle) => loaded.push((path, handle)),
+            Err(err) => warn!("Failed to decode {}: {}", path, err),
+        }
+    }
+    loaded
+}
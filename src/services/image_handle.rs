@@ -0,0 +1,21 @@
+use iced::advanced::image::Handle;
+use image::DynamicImage;
+
+/// Decodes an image file into a handle off the UI thread, so the renderer does not
+/// stall on large files
+pub async fn decode_to_handle(path: String) -> Result<Handle, String> {
+    tokio::task::spawn_blocking(move || {
+        let image = image::open(&path).map_err(|e| format!("{}: {}", path, e))?;
+        Ok(dynamic_image_to_rgba(&image))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Converts a DynamicImage to an Iced Handle
+pub fn dynamic_image_to_rgba(dynamic_image: &DynamicImage) -> Handle {
+    let rgba_image = dynamic_image.to_rgba8();
+    let (width, height) = rgba_image.dimensions();
+    let pixels = rgba_image.into_raw();
+    Handle::from_rgba(width, height, pixels)
+}
\ No newline at end of file
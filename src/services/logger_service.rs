@@ -1,8 +1,15 @@
+use organizer_core::utils::{get_exe_dir, user_data_dir};
+use std::fs;
+use std::path::PathBuf;
 use tracing_subscriber::{fmt, EnvFilter};
 
+/// Left by a panic off the main thread, shown at the next start
+const CRASH_MARKER: &str = "crash.txt";
+
 pub fn init() -> Result<(), Box<dyn std::error::Error>> {
     let filter = EnvFilter::from_default_env()
         .add_directive("Organizer=debug".parse()?)
+        .add_directive("organizer_core=debug".parse()?)
         .add_directive("iced=error".parse()?)
         .add_directive("wgpu_core=error".parse()?)
         .add_directive("wgpu_hal=error".parse()?);
@@ -11,3 +18,60 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Logs panics and tells the user the app crashed instead of just vanishing. A
+/// dialog can only be opened safely from the main thread, a panic anywhere else
+/// leaves a marker that [`report_last_crash`] shows at the next start.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown error".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        log::error!("Panic at {}: {}", location, message);
+
+        if std::thread::current().name() == Some("main") {
+            show_crash_dialog(t!("crash.description", message = message).to_string());
+        } else if let Err(e) = fs::write(crash_marker(), &message) {
+            log::error!("Failed to leave the crash marker: {}", e);
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// Tells the user about a panic of the last run that happened off the main thread.
+/// Runs on the main thread, once the language is set.
+pub fn report_last_crash() {
+    let marker = crash_marker();
+    let Ok(message) = fs::read_to_string(&marker) else {
+        return;
+    };
+    if let Err(e) = fs::remove_file(&marker) {
+        log::warn!("Failed to remove {}: {}", marker.display(), e);
+    }
+    show_crash_dialog(t!("crash.last_run", message = message).to_string());
+}
+
+fn crash_marker() -> PathBuf {
+    user_data_dir()
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(get_exe_dir)
+        .join(CRASH_MARKER)
+}
+
+fn show_crash_dialog(description: String) {
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title(t!("crash.title"))
+        .set_description(description)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
@@ -1,9 +1,7 @@
-pub mod image_service;
-pub mod file_service;
+//! Services of the desktop app, next to the ones `organizer_core` provides
+pub use organizer_core::services::*;
+
 pub mod clipboard_service;
-pub mod connection_db;
-pub mod tag_service;
-pub mod database_service;
+pub mod image_cache;
+pub mod image_handle;
 pub mod logger_service;
-pub mod toast_service;
-pub mod image_processor;